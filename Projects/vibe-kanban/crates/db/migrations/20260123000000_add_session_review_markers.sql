@@ -0,0 +1,12 @@
+-- Last commit a reviewer looked at, per session and repo, so the diff view
+-- can highlight what changed since then.
+CREATE TABLE session_review_markers (
+    session_id  BLOB NOT NULL,
+    repo_id     BLOB NOT NULL,
+    commit_oid  TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (session_id, repo_id),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
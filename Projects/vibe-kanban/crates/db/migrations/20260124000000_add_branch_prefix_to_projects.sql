@@ -0,0 +1,4 @@
+-- Per-project task branch prefix; NULL falls back to the global git_branch_prefix setting
+ALTER TABLE projects ADD COLUMN git_branch_prefix TEXT;
+-- Existing projects keep accepting any branch name; new projects enforce the prefix
+ALTER TABLE projects ADD COLUMN enforce_branch_prefix INTEGER NOT NULL DEFAULT 0;
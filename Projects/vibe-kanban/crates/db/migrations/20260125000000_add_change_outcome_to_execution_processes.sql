@@ -0,0 +1,2 @@
+-- What a finished coding agent run did to the worktrees; NULL until classified
+ALTER TABLE execution_processes ADD COLUMN change_outcome TEXT;
@@ -0,0 +1,2 @@
+-- Regex matched against dev server output to detect when it is ready; NULL uses the built-in URL detection
+ALTER TABLE projects ADD COLUMN dev_server_ready_pattern TEXT;
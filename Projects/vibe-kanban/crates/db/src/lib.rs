@@ -1,18 +1,101 @@
-use std::{str::FromStr, sync::Arc};
+use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
 
+use serde::Serialize;
 use sqlx::{
     Error, Pool, Sqlite, SqlitePool,
-    migrate::MigrateError,
-    sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions},
+    migrate::{MigrateError, Migrator},
+    sqlite::{
+        SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions,
+        SqliteSynchronous,
+    },
 };
+use thiserror::Error as ThisError;
+use ts_rs::TS;
 use utils::assets::asset_dir;
 
 pub mod models;
 
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Where the database stands relative to the migrations embedded in this build
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct MigrationStatus {
+    /// Highest successfully applied version
+    pub current_version: Option<i64>,
+    /// Highest version embedded in this build
+    pub latest_version: Option<i64>,
+    pub applied_versions: Vec<i64>,
+    /// Embedded migrations not yet applied
+    pub pending_versions: Vec<i64>,
+    /// Migrations recorded as started but not successfully finished
+    pub dirty_versions: Vec<i64>,
+    /// Applied migrations whose stored checksum differs from the embedded file
+    pub checksum_mismatches: Vec<i64>,
+    /// Applied migrations this build doesn't know about, i.e. the database was
+    /// migrated by a newer version
+    pub unknown_versions: Vec<i64>,
+}
+
+impl MigrationStatus {
+    /// True when nothing is pending, dirty, mismatched or unknown
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending_versions.is_empty()
+            && self.dirty_versions.is_empty()
+            && self.checksum_mismatches.is_empty()
+            && self.unknown_versions.is_empty()
+    }
+}
+
+/// The database was migrated by a newer build than the one running
+#[derive(Debug, ThisError)]
+#[error(
+    "database was created by a newer version of Vibe Kanban (migrations {versions:?} are unknown to this build); upgrade the app instead of downgrading"
+)]
+pub struct DatabaseTooNew {
+    pub versions: Vec<i64>,
+}
+
+/// `(version, success, checksum)` rows of `_sqlx_migrations`, oldest first.
+/// Empty if no migration has ever run.
+async fn applied_migrations(pool: &Pool<Sqlite>) -> Result<Vec<(i64, bool, Vec<u8>)>, Error> {
+    let table_exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(pool)
+    .await?;
+    if !table_exists {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query_as("SELECT version, success, checksum FROM _sqlx_migrations ORDER BY version")
+        .fetch_all(pool)
+        .await
+}
+
+/// Refuse to touch a database that has migrations this binary doesn't ship.
+/// Running older code against it would silently misread or corrupt data.
+async fn ensure_not_downgraded(pool: &Pool<Sqlite>) -> Result<(), Error> {
+    let unknown: Vec<i64> = applied_migrations(pool)
+        .await?
+        .into_iter()
+        .map(|(version, _, _)| version)
+        .filter(|version| MIGRATOR.iter().all(|m| m.version != *version))
+        .collect();
+
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(Error::Migrate(Box::new(MigrateError::Source(Box::new(
+        DatabaseTooNew { versions: unknown },
+    )))))
+}
+
 async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
     use std::collections::HashSet;
 
-    let migrator = sqlx::migrate!("./migrations");
+    ensure_not_downgraded(pool).await?;
+
+    let migrator = &MIGRATOR;
     let mut processed_versions: HashSet<i64> = HashSet::new();
 
     loop {
@@ -67,6 +150,128 @@ async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
     }
 }
 
+/// Database file size around a [`DBService::compact`] run
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CompactionReport {
+    pub size_before_bytes: i64,
+    pub size_after_bytes: i64,
+}
+
+/// Result of `PRAGMA wal_checkpoint(TRUNCATE)`. Frame counts are -1 when the
+/// database is not in WAL mode.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WalCheckpoint {
+    /// A reader or writer blocked the checkpoint from completing
+    pub busy: bool,
+    pub wal_frames: i64,
+    pub checkpointed_frames: i64,
+}
+
+/// Per-connection SQLite settings. The defaults favour integrity; each can be
+/// overridden with a `VK_SQLITE_*` environment variable.
+#[derive(Debug, Clone)]
+pub struct SqlitePragmas {
+    pub journal_mode: SqliteJournalMode,
+    pub synchronous: SqliteSynchronous,
+    /// `None` keeps sqlx's default, which enforces foreign keys
+    pub foreign_keys: Option<bool>,
+    /// `None` keeps sqlx's default of 5 seconds
+    pub busy_timeout: Option<Duration>,
+}
+
+impl Default for SqlitePragmas {
+    fn default() -> Self {
+        Self {
+            journal_mode: SqliteJournalMode::Delete,
+            synchronous: SqliteSynchronous::Full,
+            foreign_keys: None,
+            busy_timeout: None,
+        }
+    }
+}
+
+impl SqlitePragmas {
+    /// Defaults overridden by `VK_SQLITE_JOURNAL_MODE`, `VK_SQLITE_SYNCHRONOUS`,
+    /// `VK_SQLITE_FOREIGN_KEYS` and `VK_SQLITE_BUSY_TIMEOUT_MS`. Invalid values
+    /// are ignored with a warning.
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let mut pragmas = Self::default();
+
+        if let Some(mode) = env_pragma(&var, "VK_SQLITE_JOURNAL_MODE", SqliteJournalMode::from_str)
+        {
+            pragmas.journal_mode = mode;
+            // WAL is durable enough with NORMAL and much faster
+            if matches!(mode, SqliteJournalMode::Wal) {
+                pragmas.synchronous = SqliteSynchronous::Normal;
+            }
+        }
+        if let Some(synchronous) =
+            env_pragma(&var, "VK_SQLITE_SYNCHRONOUS", SqliteSynchronous::from_str)
+        {
+            pragmas.synchronous = synchronous;
+        }
+        if let Some(foreign_keys) = env_pragma(&var, "VK_SQLITE_FOREIGN_KEYS", |v| match v {
+            "1" | "on" | "true" => Ok(true),
+            "0" | "off" | "false" => Ok(false),
+            _ => Err(()),
+        }) {
+            if !foreign_keys {
+                tracing::warn!("SQLite foreign key enforcement disabled by VK_SQLITE_FOREIGN_KEYS");
+            }
+            pragmas.foreign_keys = Some(foreign_keys);
+        }
+        if let Some(ms) = env_pragma(&var, "VK_SQLITE_BUSY_TIMEOUT_MS", u64::from_str) {
+            pragmas.busy_timeout = Some(Duration::from_millis(ms));
+        }
+
+        pragmas
+    }
+
+    fn apply(&self, options: SqliteConnectOptions) -> SqliteConnectOptions {
+        let mut options = options
+            .journal_mode(self.journal_mode)
+            .synchronous(self.synchronous);
+        if let Some(foreign_keys) = self.foreign_keys {
+            options = options.foreign_keys(foreign_keys);
+        }
+        if let Some(busy_timeout) = self.busy_timeout {
+            options = options.busy_timeout(busy_timeout);
+        }
+        options
+    }
+}
+
+fn env_pragma<T, E>(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Option<T> {
+    let value = var(name)?;
+    let value = value.trim().to_ascii_lowercase();
+    match parse(&value) {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            tracing::warn!("Ignoring invalid {}={}", name, value);
+            None
+        }
+    }
+}
+
+/// Options for the app database; the pragmas are applied to every connection
+/// the pool opens.
+fn connect_options(pragmas: &SqlitePragmas) -> Result<SqliteConnectOptions, Error> {
+    let database_url = format!(
+        "sqlite://{}",
+        asset_dir().join("db.sqlite").to_string_lossy()
+    );
+    let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(true);
+    Ok(pragmas.apply(options))
+}
+
 #[derive(Clone)]
 pub struct DBService {
     pub pool: Pool<Sqlite>,
@@ -74,18 +279,111 @@ pub struct DBService {
 
 impl DBService {
     pub async fn new() -> Result<DBService, Error> {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .journal_mode(SqliteJournalMode::Delete);
+        let options = connect_options(&SqlitePragmas::from_env())?;
         let pool = SqlitePool::connect_with(options).await?;
         run_migrations(&pool).await?;
         Ok(DBService { pool })
     }
 
+    /// Run a trivial query to confirm the database is reachable.
+    pub async fn ping(&self) -> Result<(), Error> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Size of the database in bytes, excluding any WAL file.
+    pub async fn size_bytes(&self) -> Result<i64, Error> {
+        sqlx::query_scalar(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Rebuild the database file with `VACUUM` to release free pages. Holds a
+    /// write lock for the duration, so it is meant to be run while idle.
+    pub async fn compact(&self) -> Result<CompactionReport, Error> {
+        let size_before_bytes = self.size_bytes().await?;
+
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("VACUUM").execute(&mut *conn).await?;
+        drop(conn);
+
+        // VACUUM in WAL mode writes the new file through the WAL
+        self.wal_checkpoint().await?;
+
+        let size_after_bytes = self.size_bytes().await?;
+        tracing::info!(
+            "Compacted database from {} to {} bytes",
+            size_before_bytes,
+            size_after_bytes
+        );
+        Ok(CompactionReport {
+            size_before_bytes,
+            size_after_bytes,
+        })
+    }
+
+    /// Copy the WAL back into the database file and truncate it.
+    pub async fn wal_checkpoint(&self) -> Result<WalCheckpoint, Error> {
+        let (busy, wal_frames, checkpointed_frames): (i64, i64, i64) =
+            sqlx::query_as("PRAGMA wal_checkpoint(TRUNCATE)")
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(WalCheckpoint {
+            busy: busy != 0,
+            wal_frames,
+            checkpointed_frames,
+        })
+    }
+
+    /// Compare `_sqlx_migrations` against the migrations embedded in this build.
+    pub async fn migration_status(&self) -> Result<MigrationStatus, Error> {
+        let applied = applied_migrations(&self.pool).await?;
+
+        let available: HashMap<i64, &[u8]> = MIGRATOR
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .map(|m| (m.version, &*m.checksum))
+            .collect();
+
+        let mut status = MigrationStatus {
+            current_version: None,
+            latest_version: available.keys().max().copied(),
+            applied_versions: Vec::new(),
+            pending_versions: Vec::new(),
+            dirty_versions: Vec::new(),
+            checksum_mismatches: Vec::new(),
+            unknown_versions: Vec::new(),
+        };
+
+        for (version, success, checksum) in &applied {
+            if !success {
+                status.dirty_versions.push(*version);
+                continue;
+            }
+            status.applied_versions.push(*version);
+            match available.get(version) {
+                Some(expected) if *expected != checksum.as_slice() => {
+                    status.checksum_mismatches.push(*version)
+                }
+                Some(_) => {}
+                None => status.unknown_versions.push(*version),
+            }
+        }
+        status.current_version = status.applied_versions.last().copied();
+
+        let mut pending: Vec<i64> = available
+            .keys()
+            .filter(|v| !applied.iter().any(|(applied, _, _)| applied == *v))
+            .copied()
+            .collect();
+        pending.sort_unstable();
+        status.pending_versions = pending;
+
+        Ok(status)
+    }
+
     pub async fn new_with_after_connect<F>(after_connect: F) -> Result<DBService, Error>
     where
         F: for<'a> Fn(
@@ -110,13 +408,7 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .journal_mode(SqliteJournalMode::Delete);
+        let options = connect_options(&SqlitePragmas::from_env())?;
 
         let pool = if let Some(hook) = after_connect {
             SqlitePoolOptions::new()
@@ -137,3 +429,120 @@ impl DBService {
         Ok(pool)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_pool() -> SqlitePool {
+        // One connection, so every query sees the same in-memory database
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn migration_status_compares_applied_and_embedded_migrations() {
+        let db = DBService {
+            pool: test_pool().await,
+        };
+
+        let fresh = db.migration_status().await.unwrap();
+        assert_eq!(fresh.current_version, None);
+        assert!(fresh.applied_versions.is_empty());
+        assert_eq!(fresh.pending_versions.last().copied(), fresh.latest_version);
+        assert!(!fresh.is_up_to_date());
+
+        run_migrations(&db.pool).await.unwrap();
+        let migrated = db.migration_status().await.unwrap();
+        assert!(migrated.is_up_to_date());
+        assert_eq!(migrated.current_version, migrated.latest_version);
+
+        let latest = migrated.latest_version.unwrap();
+        sqlx::query("UPDATE _sqlx_migrations SET checksum = X'00' WHERE version = ?")
+            .bind(latest)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        let first = migrated.applied_versions[0];
+        sqlx::query("UPDATE _sqlx_migrations SET success = 0 WHERE version = ?")
+            .bind(first)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        let tampered = db.migration_status().await.unwrap();
+        assert_eq!(tampered.checksum_mismatches, [latest]);
+        assert_eq!(tampered.dirty_versions, [first]);
+        assert!(!tampered.applied_versions.contains(&first));
+        assert!(!tampered.is_up_to_date());
+    }
+
+    #[tokio::test]
+    async fn migrating_a_newer_database_fails_with_database_too_new() {
+        let pool = test_pool().await;
+        run_migrations(&pool).await.unwrap();
+
+        let future_version = i64::MAX;
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+             VALUES (?, 'from a newer build', 1, X'00', 0)",
+        )
+        .bind(future_version)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let Err(Error::Migrate(err)) = run_migrations(&pool).await else {
+            panic!("migrating a newer database should fail");
+        };
+        let source = match *err {
+            MigrateError::Source(source) => source,
+            other => panic!("expected a migration source error, got {other:?}"),
+        };
+        let too_new = source.downcast_ref::<DatabaseTooNew>().unwrap();
+        assert_eq!(too_new.versions, [future_version]);
+    }
+
+    #[test]
+    fn pragmas_are_parsed_from_env_vars() {
+        let from = |vars: &[(&str, &str)]| {
+            let vars: HashMap<String, String> = vars
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+            SqlitePragmas::from_vars(|name| vars.get(name).cloned())
+        };
+
+        let defaults = from(&[]);
+        assert!(matches!(defaults.journal_mode, SqliteJournalMode::Delete));
+        assert!(matches!(defaults.synchronous, SqliteSynchronous::Full));
+        assert_eq!(defaults.foreign_keys, None);
+        assert_eq!(defaults.busy_timeout, None);
+
+        let wal = from(&[("VK_SQLITE_JOURNAL_MODE", " WAL ")]);
+        assert!(matches!(wal.journal_mode, SqliteJournalMode::Wal));
+        assert!(matches!(wal.synchronous, SqliteSynchronous::Normal));
+
+        let overridden = from(&[
+            ("VK_SQLITE_JOURNAL_MODE", "wal"),
+            ("VK_SQLITE_SYNCHRONOUS", "extra"),
+            ("VK_SQLITE_FOREIGN_KEYS", "Off"),
+            ("VK_SQLITE_BUSY_TIMEOUT_MS", "250"),
+        ]);
+        assert!(matches!(overridden.synchronous, SqliteSynchronous::Extra));
+        assert_eq!(overridden.foreign_keys, Some(false));
+        assert_eq!(overridden.busy_timeout, Some(Duration::from_millis(250)));
+
+        let invalid = from(&[
+            ("VK_SQLITE_JOURNAL_MODE", "sideways"),
+            ("VK_SQLITE_FOREIGN_KEYS", "maybe"),
+            ("VK_SQLITE_BUSY_TIMEOUT_MS", "-1"),
+        ]);
+        assert!(matches!(invalid.journal_mode, SqliteJournalMode::Delete));
+        assert_eq!(invalid.foreign_keys, None);
+        assert_eq!(invalid.busy_timeout, None);
+    }
+}
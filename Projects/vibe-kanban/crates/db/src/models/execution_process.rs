@@ -43,6 +43,8 @@ pub enum ExecutionProcessError {
 #[serde(rename_all = "lowercase")]
 #[ts(use_ts_enum)]
 pub enum ExecutionProcessStatus {
+    /// Waiting for a free coding agent slot; nothing has been spawned yet
+    Queued,
     Running,
     Completed,
     Failed,
@@ -59,6 +61,20 @@ pub enum ExecutionProcessRunReason {
     DevServer,
 }
 
+/// What a finished coding agent run did to the workspace, judged from git
+/// state alone. Ordered so the most significant outcome across repos wins.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, TS)]
+#[sqlx(type_name = "execution_change_outcome", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionChangeOutcome {
+    /// HEAD and the worktree are exactly as they were before the run
+    NoChanges,
+    /// Only untracked files appeared; nothing tracked was modified or committed
+    OnlyUntracked,
+    /// Tracked files were modified or new commits were made
+    ChangesMade,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct ExecutionProcess {
     pub id: Uuid,
@@ -76,6 +92,8 @@ pub struct ExecutionProcess {
     pub completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Set once a coding agent run finishes; `None` for other run reasons
+    pub change_outcome: Option<ExecutionChangeOutcome>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -113,6 +131,15 @@ pub struct LatestProcessInfo {
     pub completed_at: Option<DateTime<Utc>>,
 }
 
+/// A coding agent run of one of a project's workspaces, for reporting
+#[derive(Debug, Clone)]
+pub struct CodingAgentRunSummary {
+    pub workspace_id: Uuid,
+    pub status: ExecutionProcessStatus,
+    pub change_outcome: Option<ExecutionChangeOutcome>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ExecutorActionField {
@@ -147,7 +174,8 @@ impl ExecutionProcess {
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
-                    ep.updated_at as "updated_at!: DateTime<Utc>"
+                    ep.updated_at as "updated_at!: DateTime<Utc>",
+                    ep.change_outcome as "change_outcome: ExecutionChangeOutcome"
                FROM execution_processes ep WHERE ep.id = ?"#,
             id
         )
@@ -221,7 +249,8 @@ impl ExecutionProcess {
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
-                    ep.updated_at as "updated_at!: DateTime<Utc>"
+                    ep.updated_at as "updated_at!: DateTime<Utc>",
+                    ep.change_outcome as "change_outcome: ExecutionChangeOutcome"
                FROM execution_processes ep WHERE ep.rowid = ?"#,
             rowid
         )
@@ -248,7 +277,8 @@ impl ExecutionProcess {
                       ep.started_at      as "started_at!: DateTime<Utc>",
                       ep.completed_at    as "completed_at?: DateTime<Utc>",
                       ep.created_at      as "created_at!: DateTime<Utc>",
-                      ep.updated_at      as "updated_at!: DateTime<Utc>"
+                      ep.updated_at      as "updated_at!: DateTime<Utc>",
+                      ep.change_outcome  as "change_outcome: ExecutionChangeOutcome"
                FROM execution_processes ep
                WHERE ep.session_id = ?
                  AND (? OR ep.dropped = FALSE)
@@ -260,7 +290,7 @@ impl ExecutionProcess {
         .await
     }
 
-    /// Find running execution processes
+    /// Find execution processes that are running or waiting for a slot
     pub async fn find_running(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             ExecutionProcess,
@@ -275,8 +305,9 @@ impl ExecutionProcess {
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
-                    ep.updated_at as "updated_at!: DateTime<Utc>"
-               FROM execution_processes ep WHERE ep.status = 'running' ORDER BY ep.created_at ASC"#,
+                    ep.updated_at as "updated_at!: DateTime<Utc>",
+                    ep.change_outcome as "change_outcome: ExecutionChangeOutcome"
+               FROM execution_processes ep WHERE ep.status IN ('running', 'queued') ORDER BY ep.created_at ASC"#,
         )
         .fetch_all(pool)
         .await
@@ -291,7 +322,7 @@ impl ExecutionProcess {
             ExecutionProcess,
             r#"SELECT ep.id as "id!: Uuid", ep.session_id as "session_id!: Uuid", ep.run_reason as "run_reason!: ExecutionProcessRunReason", ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                       ep.status as "status!: ExecutionProcessStatus", ep.exit_code,
-                      ep.dropped as "dropped!: bool", ep.started_at as "started_at!: DateTime<Utc>", ep.completed_at as "completed_at?: DateTime<Utc>", ep.created_at as "created_at!: DateTime<Utc>", ep.updated_at as "updated_at!: DateTime<Utc>"
+                      ep.dropped as "dropped!: bool", ep.started_at as "started_at!: DateTime<Utc>", ep.completed_at as "completed_at?: DateTime<Utc>", ep.created_at as "created_at!: DateTime<Utc>", ep.updated_at as "updated_at!: DateTime<Utc>", ep.change_outcome as "change_outcome: ExecutionChangeOutcome"
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
                JOIN workspaces w ON s.workspace_id = w.id
@@ -314,7 +345,7 @@ impl ExecutionProcess {
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
                WHERE s.workspace_id = $1
-                 AND ep.status = 'running'
+                 AND ep.status IN ('running', 'queued')
                  AND ep.run_reason != 'devserver'"#,
             workspace_id
         )
@@ -342,7 +373,8 @@ impl ExecutionProcess {
             ep.started_at as "started_at!: DateTime<Utc>",
             ep.completed_at as "completed_at?: DateTime<Utc>",
             ep.created_at as "created_at!: DateTime<Utc>",
-            ep.updated_at as "updated_at!: DateTime<Utc>"
+            ep.updated_at as "updated_at!: DateTime<Utc>",
+            ep.change_outcome as "change_outcome: ExecutionChangeOutcome"
         FROM execution_processes ep
         JOIN sessions s ON ep.session_id = s.id
         WHERE s.workspace_id = ?
@@ -404,7 +436,8 @@ impl ExecutionProcess {
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
-                    ep.updated_at as "updated_at!: DateTime<Utc>"
+                    ep.updated_at as "updated_at!: DateTime<Utc>",
+                    ep.change_outcome as "change_outcome: ExecutionChangeOutcome"
                FROM execution_processes ep
                WHERE ep.session_id = ? AND ep.run_reason = ? AND ep.dropped = FALSE
                ORDER BY ep.created_at DESC LIMIT 1"#,
@@ -434,7 +467,8 @@ impl ExecutionProcess {
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
-                    ep.updated_at as "updated_at!: DateTime<Utc>"
+                    ep.updated_at as "updated_at!: DateTime<Utc>",
+                    ep.change_outcome as "change_outcome: ExecutionChangeOutcome"
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
                WHERE s.workspace_id = ? AND ep.run_reason = ? AND ep.dropped = FALSE
@@ -457,6 +491,7 @@ impl ExecutionProcess {
         pool: &SqlitePool,
         data: &CreateExecutionProcess,
         process_id: Uuid,
+        status: ExecutionProcessStatus,
         repo_states: &[CreateExecutionProcessRepoState],
     ) -> Result<Self, sqlx::Error> {
         let now = Utc::now();
@@ -471,7 +506,7 @@ impl ExecutionProcess {
             data.session_id,
             data.run_reason,
             executor_action_json,
-            ExecutionProcessStatus::Running,
+            status,
             None::<i64>,
             now,
             None::<DateTime<Utc>>,
@@ -500,6 +535,23 @@ impl ExecutionProcess {
         false
     }
 
+    /// Move a queued process to running once it got a slot. Returns `false` if
+    /// it is no longer queued, e.g. because it was stopped in the meantime.
+    pub async fn mark_started(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE execution_processes
+               SET status = $1, started_at = $2
+               WHERE id = $3 AND status = $4"#,
+            ExecutionProcessStatus::Running,
+            Utc::now(),
+            id,
+            ExecutionProcessStatus::Queued
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Update execution process status and completion info
     pub async fn update_completion(
         pool: &SqlitePool,
@@ -528,6 +580,21 @@ impl ExecutionProcess {
         Ok(())
     }
 
+    pub async fn update_change_outcome(
+        pool: &SqlitePool,
+        id: Uuid,
+        outcome: ExecutionChangeOutcome,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE execution_processes SET change_outcome = $1 WHERE id = $2"#,
+            outcome,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub fn executor_action(&self) -> Result<&ExecutorAction, anyhow::Error> {
         match &self.executor_action.0 {
             ExecutorActionField::ExecutorAction(action) => Ok(action),
@@ -661,7 +728,8 @@ impl ExecutionProcess {
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
-                    ep.updated_at as "updated_at!: DateTime<Utc>"
+                    ep.updated_at as "updated_at!: DateTime<Utc>",
+                    ep.change_outcome as "change_outcome: ExecutionChangeOutcome"
                FROM execution_processes ep
                WHERE ep.session_id = ? AND ep.run_reason = ? AND ep.dropped = FALSE
                ORDER BY ep.created_at DESC LIMIT 1"#,
@@ -739,6 +807,32 @@ impl ExecutionProcess {
         Ok(result)
     }
 
+    /// Non-dropped coding agent runs of every workspace of a project's tasks,
+    /// oldest first
+    pub async fn find_coding_agent_runs_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<CodingAgentRunSummary>, sqlx::Error> {
+        sqlx::query_as!(
+            CodingAgentRunSummary,
+            r#"SELECT s.workspace_id AS "workspace_id!: Uuid",
+                      ep.status AS "status!: ExecutionProcessStatus",
+                      ep.change_outcome AS "change_outcome: ExecutionChangeOutcome",
+                      ep.created_at AS "created_at!: DateTime<Utc>"
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               JOIN workspaces w ON s.workspace_id = w.id
+               JOIN tasks t ON w.task_id = t.id
+               WHERE t.project_id = $1
+                 AND ep.run_reason = 'codingagent'
+                 AND ep.dropped = FALSE
+               ORDER BY ep.created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Find all workspaces with running dev servers, filtered by archived status.
     /// Returns a set of workspace IDs that have at least one running dev server.
     pub async fn find_workspaces_with_running_dev_servers(
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool};
@@ -133,6 +135,38 @@ impl ExecutionProcessRepoState {
         Ok(())
     }
 
+    /// HEAD of each repo when the first execution of each workspace of a
+    /// project's tasks started, i.e. where the workspace branch began. Keyed
+    /// by `(workspace_id, repo_id)`.
+    pub async fn find_start_commits_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<HashMap<(Uuid, Uuid), String>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT s.workspace_id AS "workspace_id!: Uuid",
+                      eprs.repo_id AS "repo_id!: Uuid",
+                      eprs.before_head_commit AS "before_head_commit!: String"
+               FROM execution_process_repo_states eprs
+               JOIN execution_processes ep ON eprs.execution_process_id = ep.id
+               JOIN sessions s ON ep.session_id = s.id
+               JOIN workspaces w ON s.workspace_id = w.id
+               JOIN tasks t ON w.task_id = t.id
+               WHERE t.project_id = $1 AND eprs.before_head_commit IS NOT NULL
+               ORDER BY ep.created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut commits = HashMap::new();
+        for row in rows {
+            commits
+                .entry((row.workspace_id, row.repo_id))
+                .or_insert(row.before_head_commit);
+        }
+        Ok(commits)
+    }
+
     pub async fn find_by_execution_process_id(
         pool: &SqlitePool,
         execution_process_id: Uuid,
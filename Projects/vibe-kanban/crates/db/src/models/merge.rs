@@ -77,6 +77,20 @@ struct MergeRow {
 }
 
 impl Merge {
+    pub fn workspace_id(&self) -> Uuid {
+        match self {
+            Merge::Direct(direct) => direct.workspace_id,
+            Merge::Pr(pr) => pr.workspace_id,
+        }
+    }
+
+    pub fn repo_id(&self) -> Uuid {
+        match self {
+            Merge::Direct(direct) => direct.repo_id,
+            Merge::Pr(pr) => pr.repo_id,
+        }
+    }
+
     pub fn merge_commit(&self) -> Option<String> {
         match self {
             Merge::Direct(direct) => Some(direct.merge_commit.clone()),
@@ -258,6 +272,39 @@ impl Merge {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
+    /// Find all merges of every workspace of a project's tasks, newest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            MergeRow,
+            r#"SELECT
+                m.id as "id!: Uuid",
+                m.workspace_id as "workspace_id!: Uuid",
+                m.repo_id as "repo_id!: Uuid",
+                m.merge_type as "merge_type!: MergeType",
+                m.merge_commit,
+                m.pr_number,
+                m.pr_url,
+                m.pr_status as "pr_status?: MergeStatus",
+                m.pr_merged_at as "pr_merged_at?: DateTime<Utc>",
+                m.pr_merge_commit_sha,
+                m.target_branch_name as "target_branch_name!: String",
+                m.created_at as "created_at!: DateTime<Utc>"
+            FROM merges m
+            JOIN workspaces w ON m.workspace_id = w.id
+            JOIN tasks t ON w.task_id = t.id
+            WHERE t.project_id = $1
+            ORDER BY m.created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
     /// Find all merges for a workspace and specific repo
     pub async fn find_by_workspace_and_repo_id(
         pool: &SqlitePool,
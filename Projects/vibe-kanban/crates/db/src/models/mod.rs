@@ -9,6 +9,7 @@ pub mod project_repo;
 pub mod repo;
 pub mod scratch;
 pub mod session;
+pub mod session_review_marker;
 pub mod tag;
 pub mod task;
 pub mod workspace;
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_with::rust::double_option;
 use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
@@ -22,6 +23,14 @@ pub struct Project {
     pub id: Uuid,
     pub name: String,
     pub default_agent_working_dir: Option<String>,
+    /// Prefix for this project's task branches; `None` uses the global setting
+    pub git_branch_prefix: Option<String>,
+    /// Reject task branch names that don't carry the prefix
+    pub enforce_branch_prefix: bool,
+    /// Regex matched against dev server output to tell when it is ready. The
+    /// first capture group, or else the whole match, is the URL or port to open.
+    /// `None` looks for a localhost URL.
+    pub dev_server_ready_pattern: Option<String>,
     pub remote_project_id: Option<Uuid>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
@@ -38,6 +47,25 @@ pub struct CreateProject {
 #[derive(Debug, Deserialize, TS)]
 pub struct UpdateProject {
     pub name: Option<String>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub git_branch_prefix: Option<Option<String>>,
+
+    #[ts(optional)]
+    pub enforce_branch_prefix: Option<bool>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub dev_server_ready_pattern: Option<Option<String>>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -70,6 +98,9 @@ impl Project {
             r#"SELECT id as "id!: Uuid",
                       name,
                       default_agent_working_dir,
+                      git_branch_prefix,
+                      enforce_branch_prefix as "enforce_branch_prefix!: bool",
+                      dev_server_ready_pattern,
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -87,6 +118,9 @@ impl Project {
             r#"
             SELECT p.id as "id!: Uuid", p.name,
                    p.default_agent_working_dir,
+                   p.git_branch_prefix,
+                   p.enforce_branch_prefix as "enforce_branch_prefix!: bool",
+                   p.dev_server_ready_pattern,
                    p.remote_project_id as "remote_project_id: Uuid",
                    p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
@@ -110,6 +144,9 @@ impl Project {
             r#"SELECT id as "id!: Uuid",
                       name,
                       default_agent_working_dir,
+                      git_branch_prefix,
+                      enforce_branch_prefix as "enforce_branch_prefix!: bool",
+                      dev_server_ready_pattern,
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -127,6 +164,9 @@ impl Project {
             r#"SELECT id as "id!: Uuid",
                       name,
                       default_agent_working_dir,
+                      git_branch_prefix,
+                      enforce_branch_prefix as "enforce_branch_prefix!: bool",
+                      dev_server_ready_pattern,
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -147,6 +187,9 @@ impl Project {
             r#"SELECT id as "id!: Uuid",
                       name,
                       default_agent_working_dir,
+                      git_branch_prefix,
+                      enforce_branch_prefix as "enforce_branch_prefix!: bool",
+                      dev_server_ready_pattern,
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -168,13 +211,17 @@ impl Project {
             Project,
             r#"INSERT INTO projects (
                     id,
-                    name
+                    name,
+                    enforce_branch_prefix
                 ) VALUES (
-                    $1, $2
+                    $1, $2, 1
                 )
                 RETURNING id as "id!: Uuid",
                           name,
                           default_agent_working_dir,
+                          git_branch_prefix,
+                          enforce_branch_prefix as "enforce_branch_prefix!: bool",
+                          dev_server_ready_pattern,
                           remote_project_id as "remote_project_id: Uuid",
                           created_at as "created_at!: DateTime<Utc>",
                           updated_at as "updated_at!: DateTime<Utc>""#,
@@ -195,20 +242,40 @@ impl Project {
             .ok_or(sqlx::Error::RowNotFound)?;
 
         let name = payload.name.clone().unwrap_or(existing.name);
+        let git_branch_prefix = match &payload.git_branch_prefix {
+            None => existing.git_branch_prefix,
+            Some(v) => v.clone(),
+        };
+        let enforce_branch_prefix = payload
+            .enforce_branch_prefix
+            .unwrap_or(existing.enforce_branch_prefix);
+        let dev_server_ready_pattern = match &payload.dev_server_ready_pattern {
+            None => existing.dev_server_ready_pattern,
+            Some(v) => v.clone(),
+        };
 
         sqlx::query_as!(
             Project,
             r#"UPDATE projects
-               SET name = $2
+               SET name = $2,
+                   git_branch_prefix = $3,
+                   enforce_branch_prefix = $4,
+                   dev_server_ready_pattern = $5
                WHERE id = $1
                RETURNING id as "id!: Uuid",
                          name,
                          default_agent_working_dir,
+                         git_branch_prefix,
+                         enforce_branch_prefix as "enforce_branch_prefix!: bool",
+                         dev_server_ready_pattern,
                          remote_project_id as "remote_project_id: Uuid",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
+            git_branch_prefix,
+            enforce_branch_prefix,
+            dev_server_ready_pattern,
         )
         .fetch_one(pool)
         .await
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool};
@@ -77,6 +79,30 @@ impl Session {
         .await
     }
 
+    /// Number of sessions of each workspace of a project's tasks. Workspaces
+    /// without sessions are left out.
+    pub async fn count_by_workspace_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<HashMap<Uuid, usize>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT s.workspace_id AS "workspace_id!: Uuid", COUNT(*) AS "count!: i64"
+               FROM sessions s
+               JOIN workspaces w ON s.workspace_id = w.id
+               JOIN tasks t ON w.task_id = t.id
+               WHERE t.project_id = $1
+               GROUP BY s.workspace_id"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.workspace_id, row.count as usize))
+            .collect())
+    }
+
     /// Find the most recently used session for a workspace.
     /// "Most recently used" is defined as the most recent non-dev server execution process.
     /// Sessions with no executions fall back to created_at for ordering.
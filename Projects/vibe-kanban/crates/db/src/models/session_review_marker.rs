@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// The last commit a reviewer has seen for one repo of a session.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct SessionReviewMarker {
+    pub session_id: Uuid,
+    pub repo_id: Uuid,
+    pub commit_oid: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SessionReviewMarker {
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            SessionReviewMarker,
+            r#"SELECT session_id AS "session_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      commit_oid,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM session_review_markers
+               WHERE session_id = $1"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record `commit_oid` as the last reviewed commit, replacing any earlier marker.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        repo_id: Uuid,
+        commit_oid: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            SessionReviewMarker,
+            r#"INSERT INTO session_review_markers (session_id, repo_id, commit_oid)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(session_id, repo_id) DO UPDATE SET
+                   commit_oid = excluded.commit_oid,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING session_id AS "session_id!: Uuid",
+                         repo_id AS "repo_id!: Uuid",
+                         commit_oid,
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            session_id,
+            repo_id,
+            commit_oid
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM session_review_markers WHERE session_id = $1 AND repo_id = $2",
+            session_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -159,6 +159,41 @@ impl Workspace {
         Ok(workspaces)
     }
 
+    /// Workspaces of a project's tasks created in `[from, to)`; either bound may
+    /// be open. Oldest first.
+    pub async fn find_by_project_created_between(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Workspace,
+            r#"SELECT w.id AS "id!: Uuid",
+                      w.task_id AS "task_id!: Uuid",
+                      w.container_ref,
+                      w.branch,
+                      w.agent_working_dir,
+                      w.setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                      w.created_at AS "created_at!: DateTime<Utc>",
+                      w.updated_at AS "updated_at!: DateTime<Utc>",
+                      w.archived AS "archived!: bool",
+                      w.pinned AS "pinned!: bool",
+                      w.name
+               FROM workspaces w
+               JOIN tasks t ON w.task_id = t.id
+               WHERE t.project_id = $1
+                 AND ($2 IS NULL OR datetime(w.created_at) >= datetime($2))
+                 AND ($3 IS NULL OR datetime(w.created_at) < datetime($3))
+               ORDER BY w.created_at ASC"#,
+            project_id,
+            from,
+            to
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Load workspace with full validation - ensures workspace belongs to task and task belongs to project
     pub async fn load_context(
         pool: &SqlitePool,
@@ -561,7 +596,7 @@ impl Workspace {
                     FROM sessions s
                     JOIN execution_processes ep ON ep.session_id = s.id
                     WHERE s.workspace_id = w.id
-                      AND ep.status = 'running'
+                      AND ep.status IN ('running', 'queued')
                       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
                     LIMIT 1
                 ) THEN 1 ELSE 0 END AS "is_running!: i64",
@@ -662,7 +697,7 @@ impl Workspace {
                     FROM sessions s
                     JOIN execution_processes ep ON ep.session_id = s.id
                     WHERE s.workspace_id = w.id
-                      AND ep.status = 'running'
+                      AND ep.status IN ('running', 'queued')
                       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
                     LIMIT 1
                 ) THEN 1 ELSE 0 END AS "is_running!: i64",
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -34,6 +34,16 @@ pub struct RepoWithTargetBranch {
     pub target_branch: String,
 }
 
+/// A repo of a workspace and the branch it targets, without the repo's
+/// configuration
+#[derive(Debug, Clone)]
+pub struct WorkspaceRepoTarget {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub repo_path: PathBuf,
+    pub target_branch: String,
+}
+
 /// Repo info with copy_files configuration.
 #[derive(Debug, Clone)]
 pub struct RepoWithCopyFiles {
@@ -182,6 +192,44 @@ impl WorkspaceRepo {
             .collect())
     }
 
+    /// Repos and target branches of every workspace of a project's tasks, keyed
+    /// by workspace id. Each workspace's repos are ordered by display name.
+    pub async fn find_targets_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<HashMap<Uuid, Vec<WorkspaceRepoTarget>>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT wr.workspace_id AS "workspace_id!: Uuid",
+                      r.id AS "repo_id!: Uuid",
+                      r.name,
+                      r.path,
+                      wr.target_branch
+               FROM workspace_repos wr
+               JOIN repos r ON r.id = wr.repo_id
+               JOIN workspaces w ON wr.workspace_id = w.id
+               JOIN tasks t ON w.task_id = t.id
+               WHERE t.project_id = $1
+               ORDER BY r.display_name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut targets: HashMap<Uuid, Vec<WorkspaceRepoTarget>> = HashMap::new();
+        for row in rows {
+            targets
+                .entry(row.workspace_id)
+                .or_default()
+                .push(WorkspaceRepoTarget {
+                    repo_id: row.repo_id,
+                    repo_name: row.name,
+                    repo_path: PathBuf::from(row.path),
+                    target_branch: row.target_branch,
+                });
+        }
+        Ok(targets)
+    }
+
     pub async fn find_by_workspace_and_repo_id(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -27,7 +27,7 @@ use services::services::{
     filesystem::{FilesystemError, FilesystemService},
     filesystem_watcher::FilesystemWatcherError,
     image::{ImageError, ImageService},
-    pr_monitor::PrMonitorService,
+    pr_monitor::{PrMonitorHandle, PrMonitorService},
     project::ProjectService,
     queued_message::QueuedMessageService,
     repo::RepoService,
@@ -42,6 +42,21 @@ use utils::sentry as sentry_utils;
 #[error("Remote client not configured")]
 pub struct RemoteClientNotConfigured;
 
+/// Why remote features can't be used right now
+#[derive(Debug, Clone, Copy, Error)]
+pub enum RemoteUnavailable {
+    #[error("Remote client not configured")]
+    NotConfigured,
+    #[error("Remote server is unreachable")]
+    Offline,
+}
+
+impl From<RemoteClientNotConfigured> for RemoteUnavailable {
+    fn from(_: RemoteClientNotConfigured) -> Self {
+        Self::NotConfigured
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum DeploymentError {
     #[error(transparent)]
@@ -110,6 +125,9 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn auth_context(&self) -> &AuthContext;
 
+    /// Pause, resume and retune the PR monitor at runtime
+    fn pr_monitor(&self) -> &PrMonitorHandle;
+
     async fn update_sentry_scope(&self) -> Result<(), DeploymentError> {
         let user_id = self.user_id();
         let config = self.config().read().await;
@@ -129,7 +147,13 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 user_id: self.user_id().to_string(),
                 analytics_service: analytics_service.clone(),
             });
-        PrMonitorService::spawn(db, analytics).await
+        PrMonitorService::spawn(
+            db,
+            self.config().clone(),
+            analytics,
+            self.pr_monitor().clone(),
+        )
+        .await
     }
 
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
@@ -172,7 +196,12 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
                     match self
                         .project()
-                        .create_project(&self.db().pool, self.repo(), create_data.clone())
+                        .create_project(
+                            &self.db().pool,
+                            self.git(),
+                            self.repo(),
+                            create_data.clone(),
+                        )
                         .await
                     {
                         Ok(project) => {
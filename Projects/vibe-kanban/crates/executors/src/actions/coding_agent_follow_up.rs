@@ -10,7 +10,9 @@ use crate::{
     actions::Executable,
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
-    executors::{BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    executors::{
+        BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor, TurnRef,
+    },
     profile::ExecutorProfileId,
 };
 
@@ -26,6 +28,9 @@ pub struct CodingAgentFollowUpRequest {
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    /// Resume from this earlier turn instead of the latest one
+    #[serde(default)]
+    pub resume_from: Option<TurnRef>,
 }
 
 impl CodingAgentFollowUpRequest {
@@ -44,6 +49,32 @@ impl CodingAgentFollowUpRequest {
     pub fn base_executor(&self) -> BaseCodingAgent {
         self.executor_profile_id.executor
     }
+
+    async fn spawn_with(
+        &self,
+        agent: &(impl StandardCodingAgentExecutor + Sync),
+        effective_dir: &Path,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        match &self.resume_from {
+            Some(turn) => {
+                agent
+                    .spawn_follow_up_from_turn(
+                        effective_dir,
+                        &self.prompt,
+                        &self.session_id,
+                        turn,
+                        env,
+                    )
+                    .await
+            }
+            None => {
+                agent
+                    .spawn_follow_up(effective_dir, &self.prompt, &self.session_id, env)
+                    .await
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -61,9 +92,7 @@ impl Executable for CodingAgentFollowUpRequest {
         {
             tracing::info!("QA mode: using mock executor for follow-up instead of real agent");
             let executor = crate::executors::qa_mock::QaMockExecutor;
-            return executor
-                .spawn_follow_up(&effective_dir, &self.prompt, &self.session_id, env)
-                .await;
+            return self.spawn_with(&executor, &effective_dir, env).await;
         }
 
         #[cfg(not(feature = "qa-mode"))]
@@ -77,9 +106,57 @@ impl Executable for CodingAgentFollowUpRequest {
 
             agent.use_approvals(approvals.clone());
 
-            agent
-                .spawn_follow_up(&effective_dir, &self.prompt, &self.session_id, env)
-                .await
+            self.spawn_with(&agent, &effective_dir, env).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executors::scripted::ScriptedExecutor;
+
+    fn request(resume_from: Option<TurnRef>) -> CodingAgentFollowUpRequest {
+        CodingAgentFollowUpRequest {
+            prompt: "continue".to_string(),
+            session_id: "abc".to_string(),
+            executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+            working_dir: None,
+            resume_from,
         }
     }
+
+    #[tokio::test]
+    async fn resume_from_goes_through_the_turn_aware_spawn() {
+        // The scripted executor only implements the latest-turn follow-up
+        let executor = ScriptedExecutor::new(Vec::new());
+        let env = ExecutionEnv::new(Default::default(), false);
+        let dir = Path::new(".");
+
+        let turn = TurnRef {
+            message_id: "msg-1".to_string(),
+        };
+        let result = request(Some(turn)).spawn_with(&executor, dir, &env).await;
+        assert!(matches!(
+            result,
+            Err(ExecutorError::FollowUpNotSupported(_))
+        ));
+
+        let mut spawned = request(None)
+            .spawn_with(&executor, dir, &env)
+            .await
+            .unwrap();
+        let _ = spawned.child.kill().await;
+    }
+
+    #[test]
+    fn stored_requests_without_resume_from_still_deserialize() {
+        let req: CodingAgentFollowUpRequest = serde_json::from_value(serde_json::json!({
+            "prompt": "continue",
+            "session_id": "abc",
+            "executor_profile_id": { "executor": "CLAUDE_CODE" },
+        }))
+        .unwrap();
+        assert_eq!(req.resume_from, None);
+    }
 }
@@ -13,7 +13,8 @@ use crate::{
     },
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
-    executors::{BaseCodingAgent, ExecutorError, SpawnedChild},
+    executors::{BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    profile::ExecutorConfigs,
 };
 pub mod coding_agent_follow_up;
 pub mod coding_agent_initial;
@@ -69,6 +70,20 @@ impl ExecutorAction {
             ExecutorActionType::ScriptRequest(_) => None,
         }
     }
+
+    /// Whether the agent this action starts opted into spawn retries, see
+    /// [`StandardCodingAgentExecutor::retries_spawn`]. Scripts never retry.
+    pub fn retries_spawn(&self) -> bool {
+        let profile_id = match self.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => &request.executor_profile_id,
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => &request.executor_profile_id,
+            ExecutorActionType::ReviewRequest(request) => &request.executor_profile_id,
+            ExecutorActionType::ScriptRequest(_) => return false,
+        };
+        ExecutorConfigs::get_cached()
+            .get_coding_agent(profile_id)
+            .is_some_and(|agent| agent.retries_spawn())
+    }
 }
 
 #[async_trait]
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
 use git::GitService;
 use tokio::process::Command;
@@ -11,13 +11,16 @@ pub struct RepoContext {
     pub workspace_root: PathBuf,
     /// Names of repositories in the workspace (subdirectory names)
     pub repo_names: Vec<String>,
+    /// The deployment's git service, so status checks honour its settings
+    pub git: GitService,
 }
 
 impl RepoContext {
-    pub fn new(workspace_root: PathBuf, repo_names: Vec<String>) -> Self {
+    pub fn new(workspace_root: PathBuf, repo_names: Vec<String>, git: GitService) -> Self {
         Self {
             workspace_root,
             repo_names,
+            git,
         }
     }
 
@@ -37,8 +40,8 @@ impl RepoContext {
             return String::new();
         }
 
+        let git = self.git.clone();
         tokio::task::spawn_blocking(move || {
-            let git = GitService::new();
             let mut all_status = String::new();
 
             for repo_path in &repo_paths {
@@ -74,12 +77,27 @@ impl RepoContext {
     }
 }
 
+/// Variables that configure the Vibe Kanban server rather than the project.
+/// Processes started in a workspace don't inherit them, so e.g. the server's
+/// log filter doesn't change the output of the project's own tools. `HOST` and
+/// the ports are kept: the MCP task server finds the backend through them.
+pub const SERVER_ENV_VARS: &[&str] = &[
+    "RUST_LOG",
+    "VK_ALLOWED_ORIGINS",
+    "VK_PORT_FALLBACK",
+    "VK_NO_BROWSER",
+    "DISABLE_WORKTREE_CLEANUP",
+    "FORCE_WORKTREE_CLEANUP",
+];
+
 /// Environment variables to inject into executor processes
 #[derive(Debug, Clone)]
 pub struct ExecutionEnv {
     pub vars: HashMap<String, String>,
     pub repo_context: RepoContext,
     pub commit_reminder: bool,
+    /// Fail the process once it has written no stdout or stderr for this long
+    pub idle_timeout: Option<Duration>,
 }
 
 impl ExecutionEnv {
@@ -88,6 +106,7 @@ impl ExecutionEnv {
             vars: HashMap::new(),
             repo_context,
             commit_reminder,
+            idle_timeout: None,
         }
     }
 
@@ -117,8 +136,15 @@ impl ExecutionEnv {
         }
     }
 
-    /// Apply all environment variables to a Command
+    /// Apply all environment variables to a Command, and drop the
+    /// [`SERVER_ENV_VARS`] it would inherit but doesn't set itself
     pub fn apply_to_command(&self, command: &mut Command) {
+        for var in SERVER_ENV_VARS {
+            let set_explicitly = command.as_std().get_envs().any(|(key, _)| key == *var);
+            if !set_explicitly {
+                command.env_remove(var);
+            }
+        }
         for (key, value) in &self.vars {
             command.env(key, value);
         }
@@ -153,4 +179,22 @@ mod tests {
         assert_eq!(merged.vars.get("FOO").unwrap(), "profile"); // overrides
         assert_eq!(merged.vars.get("BAR").unwrap(), "profile");
     }
+
+    #[test]
+    fn server_vars_are_not_inherited_unless_set() {
+        let mut env = ExecutionEnv::new(RepoContext::default(), false);
+        env.insert("VK_PROJECT_NAME", "runtime");
+        env.insert("VK_NO_BROWSER", "1");
+
+        let mut command = Command::new("true");
+        command.env("RUST_LOG", "error");
+        env.apply_to_command(&mut command);
+
+        let envs: HashMap<_, _> = command.as_std().get_envs().collect();
+        let get = |key: &str| envs.get(std::ffi::OsStr::new(key)).copied();
+        assert_eq!(get("RUST_LOG"), Some(Some("error".as_ref())));
+        assert_eq!(get("VK_NO_BROWSER"), Some(Some("1".as_ref())));
+        assert_eq!(get("VK_PROJECT_NAME"), Some(Some("runtime".as_ref())));
+        assert_eq!(get("VK_ALLOWED_ORIGINS"), Some(None));
+    }
 }
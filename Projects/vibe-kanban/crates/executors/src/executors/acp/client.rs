@@ -9,7 +9,7 @@ use workspace_utils::approvals::ApprovalStatus;
 
 use crate::{
     approvals::{ExecutorApprovalError, ExecutorApprovalService},
-    executors::acp::{AcpEvent, ApprovalResponse},
+    executors::acp::{AcpEvent, ApprovalResponse, token_usage_from_meta},
 };
 
 /// ACP client that handles agent-client protocol communication
@@ -183,6 +183,12 @@ impl acp::Client for AcpClient {
     }
 
     async fn session_notification(&self, args: acp::SessionNotification) -> Result<(), acp::Error> {
+        // Some agents attach token usage to the notification or to its update
+        let usage = serde_json::to_value(&args).ok().and_then(|value| {
+            token_usage_from_meta(&value)
+                .or_else(|| value.get("update").and_then(token_usage_from_meta))
+        });
+
         // Convert to typed events
         let event = match args.update {
             acp::SessionUpdate::AgentMessageChunk(chunk) => Some(AcpEvent::Message(chunk.content)),
@@ -196,6 +202,9 @@ impl acp::Client for AcpClient {
         if let Some(event) = event {
             self.send_event(event);
         }
+        if let Some(total_tokens) = usage {
+            self.send_event(AcpEvent::TokenUsage(total_tokens));
+        }
 
         Ok(())
     }
@@ -23,7 +23,11 @@ use crate::{
     approvals::ExecutorApprovalService,
     command::{CmdOverrides, CommandParts},
     env::ExecutionEnv,
-    executors::{ExecutorError, ExecutorExitResult, SpawnedChild, acp::AcpEvent},
+    executors::{
+        ExecutorError, ExecutorExitResult, SpawnedChild,
+        acp::{AcpEvent, token_usage_from_meta},
+        utils::UnmanagedApiKey,
+    },
 };
 
 /// Reusable harness for ACP-based conns (Gemini, Qwen, etc.)
@@ -31,6 +35,8 @@ pub struct AcpAgentHarness {
     session_namespace: String,
     model: Option<String>,
     mode: Option<String>,
+    /// The agent's API key vars, and whether to strip them before spawn
+    api_key: Option<(UnmanagedApiKey, bool)>,
 }
 
 impl Default for AcpAgentHarness {
@@ -47,6 +53,7 @@ impl AcpAgentHarness {
             session_namespace: "gemini_sessions".to_string(),
             model: None,
             mode: None,
+            api_key: None,
         }
     }
 
@@ -56,6 +63,7 @@ impl AcpAgentHarness {
             session_namespace: namespace.into(),
             model: None,
             mode: None,
+            api_key: None,
         }
     }
 
@@ -69,6 +77,13 @@ impl AcpAgentHarness {
         self
     }
 
+    /// Remove `key` from the agent's environment when `disable` is set,
+    /// otherwise warn in the logs when it is present
+    pub fn with_api_key(mut self, key: UnmanagedApiKey, disable: bool) -> Self {
+        self.api_key = Some((key, disable));
+        self
+    }
+
     pub async fn spawn_with_command(
         &self,
         current_dir: &Path,
@@ -90,9 +105,11 @@ impl AcpAgentHarness {
             .env("NODE_NO_WARNINGS", "1")
             .args(&args);
 
-        env.clone()
-            .with_profile(cmd_overrides)
-            .apply_to_command(&mut command);
+        let env = env.clone().with_profile(cmd_overrides);
+        env.apply_to_command(&mut command);
+        let api_key_warning = self
+            .api_key
+            .and_then(|(key, disable)| key.apply(&mut command, &env, disable));
 
         let mut child = command.group_spawn()?;
 
@@ -109,6 +126,7 @@ impl AcpAgentHarness {
             self.model.clone(),
             self.mode.clone(),
             approvals,
+            api_key_warning,
             cancel.clone(),
         )
         .await?;
@@ -143,9 +161,11 @@ impl AcpAgentHarness {
             .env("NODE_NO_WARNINGS", "1")
             .args(&args);
 
-        env.clone()
-            .with_profile(cmd_overrides)
-            .apply_to_command(&mut command);
+        let env = env.clone().with_profile(cmd_overrides);
+        env.apply_to_command(&mut command);
+        let api_key_warning = self
+            .api_key
+            .and_then(|(key, disable)| key.apply(&mut command, &env, disable));
 
         let mut child = command.group_spawn()?;
 
@@ -162,6 +182,7 @@ impl AcpAgentHarness {
             self.model.clone(),
             self.mode.clone(),
             approvals,
+            api_key_warning,
             cancel.clone(),
         )
         .await?;
@@ -184,6 +205,7 @@ impl AcpAgentHarness {
         model: Option<String>,
         mode: Option<String>,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
+        api_key_warning: Option<String>,
         cancel: CancellationToken,
     ) -> Result<(), ExecutorError> {
         // Take child's stdio for ACP wiring
@@ -215,6 +237,10 @@ impl AcpAgentHarness {
             }
         });
 
+        if let Some(warning) = api_key_warning {
+            let _ = log_tx.send(AcpEvent::Error(warning).to_string());
+        }
+
         // ACP client STDIO
         let (mut to_acp_writer, acp_incoming_reader) = tokio::io::duplex(64 * 1024);
         let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
@@ -465,6 +491,13 @@ impl AcpAgentHarness {
 
                             match prompt_result {
                                 Ok(resp) => {
+                                    if let Some(total_tokens) = serde_json::to_value(&resp)
+                                        .ok()
+                                        .and_then(|value| token_usage_from_meta(&value))
+                                    {
+                                        let _ = log_tx
+                                            .send(AcpEvent::TokenUsage(total_tokens).to_string());
+                                    }
                                     // Emit done with stop_reason
                                     let stop_reason = serde_json::to_string(&resp.stop_reason)
                                         .unwrap_or_default();
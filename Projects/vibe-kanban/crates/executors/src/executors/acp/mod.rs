@@ -28,6 +28,8 @@ pub enum AcpEvent {
     ApprovalResponse(ApprovalResponse),
     Error(String),
     Done(String),
+    /// Tokens in the agent's context, from usage the agent attached to a message
+    TokenUsage(u32),
     Other(agent_client_protocol::SessionNotification),
 }
 
@@ -50,3 +52,66 @@ pub struct ApprovalResponse {
     pub tool_call_id: String,
     pub status: ApprovalStatus,
 }
+
+/// Context tokens from the `_meta` of an ACP message, for agents that report
+/// usage there. Input and output tokens both count, as the reply becomes part
+/// of the context for the next turn.
+///
+/// ```text
+/// Gemini CLI: {"_meta": {"quota": {"token_count": {"input_tokens": 1200, "output_tokens": 80}}}}
+/// Qwen Code:  {"_meta": {"usage": {"promptTokens": 1200, "completionTokens": 80}}}
+/// ```
+pub fn token_usage_from_meta(message: &serde_json::Value) -> Option<u32> {
+    let meta = message.get("_meta")?;
+    let tokens = |value: &serde_json::Value, keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| value.get(key).and_then(serde_json::Value::as_u64))
+    };
+
+    let (input, output) = if let Some(count) = meta.pointer("/quota/token_count") {
+        (
+            tokens(count, &["input_tokens"]),
+            tokens(count, &["output_tokens"]),
+        )
+    } else {
+        let usage = meta.get("usage")?;
+        (
+            tokens(usage, &["promptTokens", "inputTokens", "input_tokens"]),
+            tokens(
+                usage,
+                &["completionTokens", "outputTokens", "output_tokens"],
+            ),
+        )
+    };
+    let total = input?.saturating_add(output.unwrap_or(0));
+    Some(u32::try_from(total).unwrap_or(u32::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn reads_gemini_and_qwen_usage() {
+        let gemini = json!({
+            "stopReason": "end_turn",
+            "_meta": {"quota": {"token_count": {"input_tokens": 1200, "output_tokens": 80}}}
+        });
+        assert_eq!(token_usage_from_meta(&gemini), Some(1280));
+
+        let qwen = json!({"_meta": {"usage": {"promptTokens": 900, "completionTokens": 40}}});
+        assert_eq!(token_usage_from_meta(&qwen), Some(940));
+    }
+
+    #[test]
+    fn ignores_messages_without_input_tokens() {
+        assert_eq!(
+            token_usage_from_meta(&json!({"stopReason": "end_turn"})),
+            None
+        );
+        let output_only = json!({"_meta": {"usage": {"completionTokens": 40}}});
+        assert_eq!(token_usage_from_meta(&output_only), None);
+    }
+}
@@ -16,13 +16,15 @@ use crate::{
     approvals::ToolCallMetadata,
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
-        TodoItem, ToolResult, ToolResultValueType, ToolStatus as LogToolStatus,
+        TodoItem, TokenUsageInfo, ToolResult, ToolResultValueType, ToolStatus as LogToolStatus,
         stderr_processor::normalize_stderr_logs,
-        utils::{ConversationPatch, EntryIndexProvider},
+        utils::{ContextBudgetGuard, ConversationPatch, EntryIndexProvider},
     },
 };
 
-pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
+/// `model_context_window` is the size reported alongside token usage; ACP
+/// agents report the tokens used but not the window.
+pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path, model_context_window: u32) {
     // stderr normalization
     let entry_index = EntryIndexProvider::start_from(&msg_store);
     normalize_stderr_logs(msg_store.clone(), entry_index.clone());
@@ -36,6 +38,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
         let mut stored_session_id = false;
         let mut streaming: StreamingState = StreamingState::default();
         let mut tool_states: ToolStates = HashMap::new();
+        let mut context_budget = ContextBudgetGuard::for_store(&msg_store);
 
         let mut stdout_lines = msg_store.stdout_lines_stream();
         while let Some(Ok(line)) = stdout_lines.next().await {
@@ -64,6 +67,28 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         streaming.assistant_text = None;
                         streaming.thinking_text = None;
                     }
+                    AcpEvent::TokenUsage(total_tokens) => {
+                        let usage = TokenUsageInfo {
+                            total_tokens,
+                            model_context_window,
+                        };
+                        let warning = context_budget.check(&usage);
+                        let idx = entry_index.next();
+                        let entry = NormalizedEntry {
+                            timestamp: None,
+                            entry_type: NormalizedEntryType::TokenUsageInfo(usage),
+                            content: format!(
+                                "Tokens used: {total_tokens} / Context window: {model_context_window}"
+                            ),
+                            metadata: None,
+                        };
+                        msg_store.push_patch(ConversationPatch::add_normalized_entry(idx, entry));
+                        if let Some(warning) = warning {
+                            let idx = entry_index.next();
+                            msg_store
+                                .push_patch(ConversationPatch::add_normalized_entry(idx, warning));
+                        }
+                    }
                     AcpEvent::Message(content) => {
                         streaming.thinking_text = None;
                         if let agent_client_protocol::ContentBlock::Text(text) = content {
@@ -381,7 +406,12 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                         .and_then(|v| serde_json::from_value::<SearchArgs>(v.clone()).ok())
                         .map(|a| a.query)
                         .unwrap_or_else(|| tc.title.clone());
-                    ActionType::Search { query }
+                    // Gemini reports its web search with the same kind as file searches
+                    if tc.id.0.starts_with("google_web_search") {
+                        ActionType::WebSearch { query }
+                    } else {
+                        ActionType::Search { query }
+                    }
                 }
                 agent_client_protocol::ToolKind::Fetch => {
                     let mut url = tc
@@ -172,6 +172,10 @@ impl StandardCodingAgentExecutor for Amp {
     }
 
     // MCP configuration methods
+    fn retries_spawn(&self) -> bool {
+        true
+    }
+
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".config").join("amp").join("settings.json"))
     }
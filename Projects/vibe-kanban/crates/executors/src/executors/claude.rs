@@ -34,14 +34,14 @@ use crate::{
     env::ExecutionEnv,
     executors::{
         AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
-        codex::client::LogWriter, utils::reorder_slash_commands,
+        TurnRef, check_known_model, codex::client::LogWriter, utils::reorder_slash_commands,
     },
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
         TodoItem, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
-            EntryIndexProvider,
+            ContextBudgetGuard, EntryIndexProvider,
             patch::{self, ConversationPatch},
         },
     },
@@ -58,6 +58,21 @@ fn base_command(claude_code_router: bool) -> &'static str {
 
 use derivative::Derivative;
 
+/// Model aliases accepted by `--model`, plus current full model ids, suggested
+/// in the config UI
+const CLAUDE_MODELS: &[&str] = &[
+    "default",
+    "opus",
+    "sonnet",
+    "haiku",
+    "opusplan",
+    "sonnet[1m]",
+    "claude-opus-4-5",
+    "claude-sonnet-4-5",
+    "claude-haiku-4-5",
+    "claude-opus-4-1",
+];
+
 #[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
 #[derivative(Debug, PartialEq)]
 pub struct ClaudeCode {
@@ -215,6 +230,26 @@ impl StandardCodingAgentExecutor for ClaudeCode {
             .await
     }
 
+    async fn spawn_follow_up_from_turn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        resume_from: &TurnRef,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command_builder = self.build_command_builder().await?;
+        let command_parts = command_builder.build_follow_up(&[
+            "--fork-session".to_string(),
+            "--resume".to_string(),
+            session_id.to_string(),
+            "--resume-session-at".to_string(),
+            resume_from.message_id.clone(),
+        ])?;
+        self.spawn_internal(current_dir, prompt, command_parts, env)
+            .await
+    }
+
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &Path) {
         let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
 
@@ -231,6 +266,36 @@ impl StandardCodingAgentExecutor for ClaudeCode {
     }
 
     // MCP configuration methods
+    fn supported_models(&self) -> Vec<String> {
+        CLAUDE_MODELS
+            .iter()
+            .map(|model| model.to_string())
+            .collect()
+    }
+
+    fn validate_model(&self, model: &str) -> Result<(), ExecutorError> {
+        // The router takes `provider,model` pairs, and dated model ids are
+        // published with every release. Bedrock and Vertex ids (`us.anthropic.…`,
+        // ARNs, `…@date`) are provider-qualified and passed through as-is.
+        if self.claude_code_router.unwrap_or(false)
+            || model.starts_with("claude-")
+            || model.contains(['.', ':', '/', '@'])
+        {
+            return Ok(());
+        }
+        // `sonnet[1m]` selects the long-context variant of an alias
+        let supported = self.supported_models();
+        let alias = model.strip_suffix("[1m]").unwrap_or(model);
+        if supported.iter().any(|known| known == alias) {
+            return Ok(());
+        }
+        check_known_model(model, supported)
+    }
+
+    fn retries_spawn(&self) -> bool {
+        true
+    }
+
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".claude.json"))
     }
@@ -289,6 +354,9 @@ impl ClaudeCode {
         command_parts: CommandParts,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
+        if let Some(model) = &self.model {
+            self.validate_model(model)?;
+        }
         let (program_path, args) = command_parts.into_resolved().await?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
@@ -399,15 +467,16 @@ pub struct ClaudeLogProcessor {
     main_model_name: Option<String>,
     main_model_context_window: u32,
     context_tokens_used: u32,
+    context_budget: ContextBudgetGuard,
 }
 
 impl ClaudeLogProcessor {
     #[cfg(test)]
     fn new() -> Self {
-        Self::new_with_strategy(HistoryStrategy::Default)
+        Self::new_with_strategy(HistoryStrategy::Default, ContextBudgetGuard::default())
     }
 
-    fn new_with_strategy(strategy: HistoryStrategy) -> Self {
+    fn new_with_strategy(strategy: HistoryStrategy, context_budget: ContextBudgetGuard) -> Self {
         Self {
             model_name: None,
             main_model_name: None,
@@ -418,6 +487,7 @@ impl ClaudeLogProcessor {
             last_assistant_message: None,
             main_model_context_window: DEFAULT_CLAUDE_CONTEXT_WINDOW,
             context_tokens_used: 0,
+            context_budget,
         }
     }
 
@@ -434,7 +504,8 @@ impl ClaudeLogProcessor {
             let mut buffer = String::new();
             let worktree_path = current_dir_clone.to_string_lossy().to_string();
             let mut session_id_extracted = false;
-            let mut processor = Self::new_with_strategy(strategy);
+            let mut processor =
+                Self::new_with_strategy(strategy, ContextBudgetGuard::for_store(&msg_store));
 
             while let Some(Ok(msg)) = stream.next().await {
                 let chunk = match msg {
@@ -729,7 +800,9 @@ impl ClaudeLogProcessor {
                 query: pattern.clone(),
             },
             ClaudeToolData::WebFetch { url, .. } => ActionType::WebFetch { url: url.clone() },
-            ClaudeToolData::WebSearch { query, .. } => ActionType::WebFetch { url: query.clone() },
+            ClaudeToolData::WebSearch { query, .. } => ActionType::WebSearch {
+                query: query.clone(),
+            },
             ClaudeToolData::Task {
                 description,
                 prompt,
@@ -829,6 +902,7 @@ impl ClaudeLogProcessor {
                 api_key_source,
                 model,
                 status,
+                compact_metadata,
                 ..
             } => {
                 // emit billing warning if required
@@ -854,7 +928,12 @@ impl ClaudeLogProcessor {
                             patches.push(add_system_message(status.clone(), entry_index_provider));
                         }
                     }
-                    Some("compact_boundary") => {}
+                    Some("compact_boundary") => {
+                        patches.push(add_system_message(
+                            compact_boundary_message(compact_metadata.as_ref()),
+                            entry_index_provider,
+                        ));
+                    }
                     Some(subtype) => {
                         let entry = NormalizedEntry {
                             timestamp: None,
@@ -1254,7 +1333,7 @@ impl ClaudeLogProcessor {
                         let total_tokens = input_tokens + output_tokens;
                         self.context_tokens_used = total_tokens as u32;
 
-                        patches.push(self.add_token_usage_entry(entry_index_provider));
+                        patches.extend(self.add_token_usage_entries(entry_index_provider));
                     }
                 }
                 ClaudeStreamEvent::MessageStop => {
@@ -1279,7 +1358,7 @@ impl ClaudeLogProcessor {
                         .and_then(|usage| usage.context_window)
                 }) {
                     self.main_model_context_window = context_window;
-                    patches.push(self.add_token_usage_entry(entry_index_provider));
+                    patches.extend(self.add_token_usage_entries(entry_index_provider));
                 }
 
                 if matches!(self.strategy, HistoryStrategy::AmpResume) && is_error.unwrap_or(false)
@@ -1381,6 +1460,7 @@ impl ClaudeLogProcessor {
             ActionType::CommandRun { command, .. } => command.to_string(),
             ActionType::Search { query } => query.to_string(),
             ActionType::WebFetch { url } => url.to_string(),
+            ActionType::WebSearch { query } => query.to_string(),
             ActionType::TaskCreate { description } => {
                 if description.is_empty() {
                     "Task".to_string()
@@ -1462,27 +1542,56 @@ impl ClaudeLogProcessor {
         }
     }
 
-    fn add_token_usage_entry(
+    fn add_token_usage_entries(
         &mut self,
         entry_index_provider: &EntryIndexProvider,
-    ) -> json_patch::Patch {
+    ) -> Vec<json_patch::Patch> {
+        let usage = crate::logs::TokenUsageInfo {
+            total_tokens: self.context_tokens_used,
+            model_context_window: self.main_model_context_window,
+        };
+        let warning = self.context_budget.check(&usage);
         let entry = NormalizedEntry {
             timestamp: None,
-            entry_type: NormalizedEntryType::TokenUsageInfo(crate::logs::TokenUsageInfo {
-                total_tokens: self.context_tokens_used,
-                model_context_window: self.main_model_context_window,
-            }),
+            entry_type: NormalizedEntryType::TokenUsageInfo(usage),
             content: format!(
                 "Tokens used: {} / Context window: {}",
                 self.context_tokens_used, self.main_model_context_window
             ),
             metadata: None,
         };
-        let idx = entry_index_provider.next();
-        ConversationPatch::add_normalized_entry(idx, entry)
+        let mut patches = vec![ConversationPatch::add_normalized_entry(
+            entry_index_provider.next(),
+            entry,
+        )];
+        if let Some(warning) = warning {
+            patches.push(ConversationPatch::add_normalized_entry(
+                entry_index_provider.next(),
+                warning,
+            ));
+        }
+        patches
     }
 }
 
+/// Human-readable note for a context compaction, with token counts when Claude reports them
+fn compact_boundary_message(metadata: Option<&ClaudeCompactMetadata>) -> String {
+    let Some(metadata) = metadata else {
+        return "Context compacted".to_string();
+    };
+    let mut message = match metadata.trigger.as_deref() {
+        Some("auto") => "Context compacted automatically".to_string(),
+        Some("manual") => "Context compacted manually".to_string(),
+        _ => "Context compacted".to_string(),
+    };
+    match (metadata.pre_tokens, metadata.post_tokens) {
+        (Some(pre), Some(post)) => message.push_str(&format!(" ({pre} → {post} tokens)")),
+        (Some(pre), None) => message.push_str(&format!(" ({pre} tokens before compaction)")),
+        _ => {}
+    }
+    message
+}
+
 fn add_system_message(
     content: String,
     entry_index_provider: &EntryIndexProvider,
@@ -1673,6 +1782,8 @@ pub enum ClaudeJson {
         slash_commands: Vec<String>,
         #[serde(default)]
         plugins: Vec<ClaudePlugin>,
+        #[serde(default)]
+        compact_metadata: Option<ClaudeCompactMetadata>,
     },
     Assistant {
         message: ClaudeMessage,
@@ -1753,6 +1864,15 @@ pub struct ClaudePlugin {
     pub path: PathBuf,
 }
 
+/// Details attached to a `compact_boundary` system event
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClaudeCompactMetadata {
+    /// `auto` when the context window filled up, `manual` for `/compact`
+    pub trigger: Option<String>,
+    pub pre_tokens: Option<u64>,
+    pub post_tokens: Option<u64>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct ClaudeMessage {
     pub id: Option<String>,
@@ -2222,6 +2342,27 @@ mod tests {
         assert_eq!(result, "**/*.ts");
     }
 
+    #[test]
+    fn test_web_search_tool_is_web_search_action() {
+        let search_data = ClaudeToolData::WebSearch {
+            query: "rust async traits".to_string(),
+            num_results: None,
+        };
+
+        let action_type =
+            ClaudeLogProcessor::extract_action_type(&search_data, "/tmp/test-worktree");
+        assert!(matches!(
+            &action_type,
+            ActionType::WebSearch { query } if query == "rust async traits"
+        ));
+        let result = ClaudeLogProcessor::generate_concise_content(
+            &search_data,
+            &action_type,
+            "/tmp/test-worktree",
+        );
+        assert_eq!(result, "rust async traits");
+    }
+
     #[test]
     fn test_glob_tool_pattern_only() {
         // Test Glob with pattern only
@@ -2587,6 +2728,36 @@ mod tests {
         assert_eq!(entries_no_key.len(), 0); // No warning when field is missing
     }
 
+    #[test]
+    fn test_compact_boundary_system_message() {
+        let compact = r#"{"type":"system","subtype":"compact_boundary","session_id":"test123","compact_metadata":{"trigger":"auto","pre_tokens":152340}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(compact).unwrap();
+        let entries = normalize(&parsed, "");
+
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(
+            entries[0].entry_type,
+            NormalizedEntryType::SystemMessage
+        ));
+        assert_eq!(
+            entries[0].content,
+            "Context compacted automatically (152340 tokens before compaction)"
+        );
+
+        let manual = r#"{"type":"system","subtype":"compact_boundary","session_id":"test123","compact_metadata":{"trigger":"manual","pre_tokens":9000,"post_tokens":1200}}"#;
+        let parsed_manual: ClaudeJson = serde_json::from_str(manual).unwrap();
+        let entries_manual = normalize(&parsed_manual, "");
+        assert_eq!(
+            entries_manual[0].content,
+            "Context compacted manually (9000 → 1200 tokens)"
+        );
+
+        let bare = r#"{"type":"system","subtype":"compact_boundary","session_id":"test123"}"#;
+        let parsed_bare: ClaudeJson = serde_json::from_str(bare).unwrap();
+        let entries_bare = normalize(&parsed_bare, "");
+        assert_eq!(entries_bare[0].content, "Context compacted");
+    }
+
     #[test]
     fn test_mixed_content_with_thinking_ignores_tool_result() {
         let complex_assistant_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"I need to read the file first"},{"type":"text","text":"I'll help you with that"},{"type":"tool_result","tool_use_id":"tool_789","content":"Success","is_error":false}]}}"#;
@@ -19,31 +19,13 @@ use crate::{
     env::{ExecutionEnv, RepoContext},
     executors::{
         BaseCodingAgent, ExecutorError, SlashCommandDescription,
-        utils::{SlashCommandCache, SlashCommandCacheKey},
+        utils::{SlashCommandCache, SlashCommandCacheKey, frontmatter_description},
     },
 };
 
 const SLASH_COMMANDS_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(120);
 
 impl ClaudeCode {
-    fn extract_description(content: &str) -> Option<String> {
-        if !content.starts_with("---") {
-            return None;
-        }
-
-        // Find end of frontmatter
-        let end = content[3..].find("---")?;
-        let frontmatter = &content[3..3 + end];
-
-        for line in frontmatter.lines() {
-            let line = line.trim();
-            if let Some(rest) = line.strip_prefix("description:") {
-                return Some(rest.trim().to_string());
-            }
-        }
-        None
-    }
-
     fn discover_custom_command_descriptions(
         current_dir: &Path,
         plugins: &[ClaudePlugin],
@@ -64,7 +46,7 @@ impl ClaudeCode {
                         && path.extension().is_some_and(|ext| ext == "md")
                         && let Some(name) = path.file_stem().and_then(|s| s.to_str())
                         && let Ok(content) = std::fs::read_to_string(path)
-                        && let Some(desc) = Self::extract_description(&content)
+                        && let Some(desc) = frontmatter_description(&content)
                     {
                         let key = if let Some(p) = prefix {
                             format!("{}:{}", p, name)
@@ -93,7 +75,7 @@ impl ClaudeCode {
                             .and_then(|p| p.file_name())
                             .and_then(|s| s.to_str())
                             && let Ok(content) = std::fs::read_to_string(path)
-                            && let Some(desc) = Self::extract_description(&content)
+                            && let Some(desc) = frontmatter_description(&content)
                         {
                             let key = if let Some(p) = prefix {
                                 format!("{}:{}", p, parent)
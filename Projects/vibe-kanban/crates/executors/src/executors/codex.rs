@@ -31,6 +31,7 @@ use codex_protocol::{
 };
 use command_group::AsyncCommandGroup;
 use derivative::Derivative;
+use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -51,12 +52,30 @@ use crate::{
     env::ExecutionEnv,
     executors::{
         AppendPrompt, AvailabilityInfo, ExecutorError, ExecutorExitResult, SlashCommandDescription,
-        SpawnedChild, StandardCodingAgentExecutor,
+        SpawnedChild, StandardCodingAgentExecutor, check_known_model,
+        utils::{UnmanagedApiKey, discover_markdown_commands},
     },
     logs::utils::patch,
     stdout_dup::create_stdout_pipe_writer,
 };
 
+/// Models suggested for ChatGPT sign-in and the OpenAI API
+const CODEX_MODELS: &[&str] = &[
+    "gpt-5.2-codex",
+    "gpt-5.2",
+    "gpt-5.1-codex-max",
+    "gpt-5.1-codex",
+    "gpt-5.1-codex-mini",
+    "gpt-5.1",
+    "gpt-5-codex",
+    "gpt-5",
+];
+
+const OPENAI_API_KEY: UnmanagedApiKey = UnmanagedApiKey {
+    vars: &["OPENAI_API_KEY"],
+    warning: "Codex + {var} detected. Usage will be billed via OpenAI API pay-as-you-go instead of your ChatGPT subscription. If this is unintended, please select the `disable_api_key` checkbox in the coding-agent-configurations settings page.",
+};
+
 /// Sandbox policy modes for Codex
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, AsRefStr)]
 #[serde(rename_all = "kebab-case")]
@@ -154,6 +173,8 @@ pub struct Codex {
     pub compact_prompt: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub developer_instructions: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disable_api_key: Option<bool>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
 
@@ -195,9 +216,28 @@ impl StandardCodingAgentExecutor for Codex {
                 description: Some("list configured MCP tools".to_string()),
             },
         ];
-        Ok(Box::pin(futures::stream::once(async move {
-            patch::slash_commands(commands, false, None)
-        })))
+        let initial = patch::slash_commands(commands.clone(), true, None);
+
+        // Custom prompts in `$CODEX_HOME/prompts` are invoked as `/prompts:<name>`
+        let discovery_stream = futures::stream::once(async move {
+            let prompts = tokio::task::spawn_blocking(|| {
+                codex_home()
+                    .map(|home| discover_markdown_commands(&home.join("prompts"), Some("prompts")))
+                    .unwrap_or_default()
+            })
+            .await;
+            match prompts {
+                Ok(prompts) => patch::slash_commands([commands, prompts].concat(), false, None),
+                Err(e) => {
+                    tracing::warn!("Failed to discover Codex custom prompts: {}", e);
+                    patch::slash_commands(commands, false, Some(e.to_string()))
+                }
+            }
+        });
+
+        Ok(Box::pin(
+            futures::stream::once(async move { initial }).chain(discovery_stream),
+        ))
     }
 
     async fn spawn(
@@ -225,6 +265,28 @@ impl StandardCodingAgentExecutor for Codex {
         normalize_logs(msg_store, worktree_path);
     }
 
+    fn supported_models(&self) -> Vec<String> {
+        CODEX_MODELS.iter().map(|model| model.to_string()).collect()
+    }
+
+    fn validate_model(&self, model: &str) -> Result<(), ExecutorError> {
+        // Local and third-party providers serve their own models, and a profile
+        // may pick one. New GPT and o-series models ship faster than this list.
+        if self.oss.unwrap_or(false)
+            || self.model_provider.is_some()
+            || self.profile.is_some()
+            || model.starts_with("gpt-")
+            || (model.starts_with('o') && model[1..].starts_with(|c: char| c.is_ascii_digit()))
+        {
+            return Ok(());
+        }
+        check_known_model(model, self.supported_models())
+    }
+
+    fn retries_spawn(&self) -> bool {
+        true
+    }
+
     fn default_mcp_config_path(&self) -> Option<PathBuf> {
         codex_home().map(|home| home.join("config.toml"))
     }
@@ -449,6 +511,9 @@ impl Codex {
         F: FnOnce(Arc<AppServerClient>, ExitSignalSender) -> Fut + Send + 'static,
         Fut: std::future::Future<Output = Result<(), ExecutorError>> + Send + 'static,
     {
+        if let Some(model) = &self.model {
+            self.validate_model(model)?;
+        }
         let (program_path, args) = command_parts.into_resolved().await?;
 
         let mut process = Command::new(program_path);
@@ -464,9 +529,16 @@ impl Codex {
             .env("RUST_LOG", "error")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut process);
+        let profile_env = env.clone().with_profile(&self.cmd);
+        profile_env.apply_to_command(&mut process);
+        let api_key_warning = OPENAI_API_KEY
+            .apply(
+                &mut process,
+                &profile_env,
+                self.disable_api_key.unwrap_or(false),
+            )
+            // Local models don't bill the key
+            .filter(|_| !self.oss.unwrap_or(false));
 
         let mut child = process.group_spawn()?;
 
@@ -512,6 +584,13 @@ impl Codex {
             );
             client.connect(rpc_peer);
 
+            if let Some(warning) = api_key_warning {
+                log_writer
+                    .log_raw(&Error::unmanaged_api_key(warning).raw())
+                    .await
+                    .ok();
+            }
+
             let result = async {
                 client.initialize().await?;
                 task(client, exit_signal_tx.clone()).await
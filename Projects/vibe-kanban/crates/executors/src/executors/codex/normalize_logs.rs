@@ -39,7 +39,7 @@ use crate::{
         ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
-            ConversationPatch, EntryIndexProvider,
+            ContextBudgetGuard, ConversationPatch, EntryIndexProvider,
             patch::{add_normalized_entry, replace_normalized_entry, upsert_normalized_entry},
         },
     },
@@ -156,8 +156,8 @@ impl ToNormalizedEntry for WebSearchState {
             timestamp: None,
             entry_type: NormalizedEntryType::ToolUse {
                 tool_name: "web_search".to_string(),
-                action_type: ActionType::WebFetch {
-                    url: self.query.clone().unwrap_or_else(|| "...".to_string()),
+                action_type: ActionType::WebSearch {
+                    query: self.query.clone().unwrap_or_else(|| "...".to_string()),
                 },
                 status: self.status.clone(),
             },
@@ -215,6 +215,7 @@ struct LogState {
     mcp_tools: HashMap<String, McpToolState>,
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
+    context_budget: ContextBudgetGuard,
 }
 
 enum StreamingTextKind {
@@ -223,7 +224,7 @@ enum StreamingTextKind {
 }
 
 impl LogState {
-    fn new(entry_index: EntryIndexProvider) -> Self {
+    fn new(entry_index: EntryIndexProvider, context_budget: ContextBudgetGuard) -> Self {
         Self {
             entry_index,
             assistant: None,
@@ -232,6 +233,7 @@ impl LogState {
             mcp_tools: HashMap::new(),
             patches: HashMap::new(),
             web_searches: HashMap::new(),
+            context_budget,
         }
     }
 
@@ -361,7 +363,10 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
 
     let worktree_path_str = worktree_path.to_string_lossy().to_string();
     tokio::spawn(async move {
-        let mut state = LogState::new(entry_index.clone());
+        let mut state = LogState::new(
+            entry_index.clone(),
+            ContextBudgetGuard::for_store(&msg_store),
+        );
         let mut stdout_lines = msg_store.stdout_lines_stream();
 
         while let Some(Ok(line)) = stdout_lines.next().await {
@@ -999,20 +1004,18 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        let usage = crate::logs::TokenUsageInfo {
+                            total_tokens: info.last_token_usage.total_tokens as u32,
+                            model_context_window: info.model_context_window.unwrap_or_default()
+                                as u32,
+                        };
+                        let warning = state.context_budget.check(&usage);
                         add_normalized_entry(
                             &msg_store,
                             &entry_index,
                             NormalizedEntry {
                                 timestamp: None,
-                                entry_type: NormalizedEntryType::TokenUsageInfo(
-                                    crate::logs::TokenUsageInfo {
-                                        total_tokens: info.last_token_usage.total_tokens as u32,
-                                        model_context_window: info
-                                            .model_context_window
-                                            .unwrap_or_default()
-                                            as u32,
-                                    },
-                                ),
+                                entry_type: NormalizedEntryType::TokenUsageInfo(usage),
                                 content: format!(
                                     "Tokens used: {} / Context window: {}",
                                     info.last_token_usage.total_tokens,
@@ -1021,6 +1024,9 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                 metadata: None,
                             },
                         );
+                        if let Some(warning) = warning {
+                            add_normalized_entry(&msg_store, &entry_index, warning);
+                        }
                     }
                 }
                 EventMsg::ContextCompacted(..) => {
@@ -1155,6 +1161,7 @@ static SESSION_ID: LazyLock<Regex> = LazyLock::new(|| {
 pub enum Error {
     LaunchError { error: String },
     AuthRequired { error: String },
+    UnmanagedApiKey { error: String },
 }
 
 impl Error {
@@ -1164,6 +1171,9 @@ impl Error {
     pub fn auth_required(error: String) -> Self {
         Self::AuthRequired { error }
     }
+    pub fn unmanaged_api_key(error: String) -> Self {
+        Self::UnmanagedApiKey { error }
+    }
 
     pub fn raw(&self) -> String {
         serde_json::to_string(self).unwrap_or_default()
@@ -1181,6 +1191,14 @@ impl ToNormalizedEntry for Error {
                 content: error.clone(),
                 metadata: None,
             },
+            Error::UnmanagedApiKey { error } => NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::ErrorMessage {
+                    error_type: NormalizedEntryError::Other,
+                },
+                content: error.clone(),
+                metadata: None,
+            },
             Error::AuthRequired { error } => NormalizedEntry {
                 timestamp: None,
                 entry_type: NormalizedEntryType::ErrorMessage {
@@ -213,6 +213,10 @@ impl StandardCodingAgentExecutor for Copilot {
     }
 
     // MCP configuration methods
+    fn retries_spawn(&self) -> bool {
+        true
+    }
+
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".copilot").join("mcp-config.json"))
     }
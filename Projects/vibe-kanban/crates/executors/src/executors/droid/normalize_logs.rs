@@ -381,17 +381,17 @@ pub fn normalize_logs(
                             }
 
                             DroidToolData::WebSearch { query, .. } => {
-                                let tool_state = WebFetchState {
+                                let tool_state = WebSearchState {
                                     index: None,
-                                    url: query.clone(),
+                                    query: query.clone(),
                                     status: ToolStatus::Created,
                                 };
-                                state.web_fetches.insert(id.clone(), tool_state);
-                                state.pending_fifo.push_back(PendingToolCall::Fetch {
+                                state.web_searches.insert(id.clone(), tool_state);
+                                state.pending_fifo.push_back(PendingToolCall::WebSearch {
                                     tool_call_id: id.clone(),
                                 });
 
-                                let tool_state = state.web_fetches.get_mut(&id).unwrap();
+                                let tool_state = state.web_searches.get_mut(&id).unwrap();
                                 let index = add_normalized_entry(
                                     &msg_store,
                                     &entry_index_provider,
@@ -612,6 +612,21 @@ pub fn normalize_logs(
                                     );
                                 }
                             }
+                            PendingToolCall::WebSearch { tool_call_id } => {
+                                if let Some(mut state) = state.web_searches.remove(&tool_call_id) {
+                                    state.status = if is_error {
+                                        ToolStatus::Failed
+                                    } else {
+                                        ToolStatus::Success
+                                    };
+                                    let entry = state.to_normalized_entry();
+                                    replace_normalized_entry(
+                                        &msg_store,
+                                        state.index.unwrap(),
+                                        entry,
+                                    );
+                                }
+                            }
                             PendingToolCall::Generic { tool_call_id } => {
                                 if let Some(mut state) = state.generic_tools.remove(&tool_call_id) {
                                     state.status = if is_error {
@@ -1176,6 +1191,30 @@ impl ToNormalizedEntry for WebFetchState {
     }
 }
 
+#[derive(Debug, Clone)]
+struct WebSearchState {
+    index: Option<usize>,
+    query: String,
+    status: ToolStatus,
+}
+
+impl ToNormalizedEntry for WebSearchState {
+    fn to_normalized_entry(&self) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "web_search".to_string(),
+                action_type: ActionType::WebSearch {
+                    query: self.query.clone(),
+                },
+                status: self.status.clone(),
+            },
+            content: self.query.clone(),
+            metadata: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct GenericToolState {
     index: Option<usize>,
@@ -1220,6 +1259,7 @@ enum PendingToolCall {
     Todo { tool_call_id: ToolCallId },
     Search { tool_call_id: ToolCallId },
     Fetch { tool_call_id: ToolCallId },
+    WebSearch { tool_call_id: ToolCallId },
     Generic { tool_call_id: ToolCallId },
 }
 
@@ -1233,6 +1273,7 @@ struct ToolCallStates {
     todo_updates: HashMap<String, TodoManagementState>,
     searches: HashMap<String, SearchState>,
     web_fetches: HashMap<String, WebFetchState>,
+    web_searches: HashMap<String, WebSearchState>,
     generic_tools: HashMap<String, GenericToolState>,
     pending_fifo: VecDeque<PendingToolCall>,
     model_reported: bool,
@@ -1248,6 +1289,7 @@ impl ToolCallStates {
             todo_updates: HashMap::new(),
             searches: HashMap::new(),
             web_fetches: HashMap::new(),
+            web_searches: HashMap::new(),
             generic_tools: HashMap::new(),
             pending_fifo: VecDeque::new(),
             model_reported: false,
@@ -14,9 +14,31 @@ use crate::{
     env::ExecutionEnv,
     executors::{
         AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+        check_known_model, utils::UnmanagedApiKey,
     },
 };
 
+/// Context window used when none is configured; current Gemini models have 1M tokens
+const DEFAULT_GEMINI_CONTEXT_WINDOW: u32 = 1_048_576;
+
+/// CLI aliases and current model ids, suggested in the config UI
+const GEMINI_MODELS: &[&str] = &[
+    "auto",
+    "pro",
+    "flash",
+    "flash-lite",
+    "gemini-3-pro-preview",
+    "gemini-3-flash-preview",
+    "gemini-2.5-pro",
+    "gemini-2.5-flash",
+    "gemini-2.5-flash-lite",
+];
+
+const GEMINI_API_KEY: UnmanagedApiKey = UnmanagedApiKey {
+    vars: &["GEMINI_API_KEY", "GOOGLE_API_KEY"],
+    warning: "Gemini CLI + {var} detected. Usage will be billed to your Gemini API key instead of your Google account login. If this is unintended, please select the `disable_api_key` checkbox in the coding-agent-configurations settings page.",
+};
+
 #[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
 #[derivative(Debug, PartialEq)]
 pub struct Gemini {
@@ -26,6 +48,11 @@ pub struct Gemini {
     pub model: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub yolo: Option<bool>,
+    /// Context window of the model in tokens, for the context usage bar
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disable_api_key: Option<bool>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
     #[serde(skip)]
@@ -51,6 +78,10 @@ impl Gemini {
 
         apply_overrides(builder, &self.cmd)
     }
+
+    fn harness(&self) -> AcpAgentHarness {
+        AcpAgentHarness::new().with_api_key(GEMINI_API_KEY, self.disable_api_key.unwrap_or(false))
+    }
 }
 
 #[async_trait]
@@ -65,7 +96,10 @@ impl StandardCodingAgentExecutor for Gemini {
         prompt: &str,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let harness = AcpAgentHarness::new();
+        if let Some(model) = &self.model {
+            self.validate_model(model)?;
+        }
+        let harness = self.harness();
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
         let gemini_command = self.build_command_builder()?.build_initial()?;
         let approvals = if self.yolo.unwrap_or(false) {
@@ -92,7 +126,10 @@ impl StandardCodingAgentExecutor for Gemini {
         session_id: &str,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let harness = AcpAgentHarness::new();
+        if let Some(model) = &self.model {
+            self.validate_model(model)?;
+        }
+        let harness = self.harness();
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
         let gemini_command = self.build_command_builder()?.build_follow_up(&[])?;
         let approvals = if self.yolo.unwrap_or(false) {
@@ -114,7 +151,30 @@ impl StandardCodingAgentExecutor for Gemini {
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
-        super::acp::normalize_logs(msg_store, worktree_path);
+        super::acp::normalize_logs(
+            msg_store,
+            worktree_path,
+            self.context_window.unwrap_or(DEFAULT_GEMINI_CONTEXT_WINDOW),
+        );
+    }
+
+    fn supported_models(&self) -> Vec<String> {
+        GEMINI_MODELS
+            .iter()
+            .map(|model| model.to_string())
+            .collect()
+    }
+
+    fn validate_model(&self, model: &str) -> Result<(), ExecutorError> {
+        // Preview and dated variants come and go; any Gemini model id is passed through
+        if model.starts_with("gemini-") {
+            return Ok(());
+        }
+        check_known_model(model, self.supported_models())
+    }
+
+    fn retries_spawn(&self) -> bool {
+        true
     }
 
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
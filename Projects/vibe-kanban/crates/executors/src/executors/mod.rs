@@ -1,16 +1,18 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, future::Future, path::Path, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use command_group::AsyncGroupChild;
 use enum_dispatch::enum_dispatch;
 use futures::stream::BoxStream;
 use futures_io::Error as FuturesIoError;
+use rand::Rng;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sqlx::Type;
 use strum_macros::{Display, EnumDiscriminants, EnumString, VariantNames};
 use thiserror::Error;
 use ts_rs::TS;
+use uuid::Uuid;
 use workspace_utils::msg_store::MsgStore;
 
 #[cfg(feature = "qa-mode")]
@@ -40,6 +42,8 @@ pub mod opencode;
 #[cfg(feature = "qa-mode")]
 pub mod qa_mock;
 pub mod qwen;
+#[cfg(feature = "scripted")]
+pub mod scripted;
 pub mod utils;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
@@ -50,11 +54,20 @@ pub struct SlashCommandDescription {
     pub description: Option<String>,
 }
 
+/// Points at an earlier turn of an agent session to resume from instead of the latest.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+pub struct TurnRef {
+    /// Agent-native id of the last message to keep, e.g. a Claude assistant message uuid
+    pub message_id: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[ts(use_ts_enum)]
 pub enum BaseAgentCapability {
     SessionFork,
+    /// Agent can resume a session from a specific earlier turn (see [`TurnRef`])
+    ResumeFromTurn,
     /// Agent requires a setup script before it can run (e.g., login, installation)
     SetupHelper,
     /// Agent reports context/token usage information
@@ -87,6 +100,102 @@ pub enum ExecutorError {
     SetupHelperNotSupported,
     #[error("Auth required: {0}")]
     AuthRequired(String),
+    #[error("Unknown model `{model}`. Supported models: {}", .supported.join(", "))]
+    UnknownModel {
+        model: String,
+        supported: Vec<String>,
+    },
+    #[error("Timeout: process took more than {} seconds to start", .0.as_secs())]
+    SpawnTimeout(Duration),
+}
+
+/// Errors npm reports when two `npx` runs install the same package into its
+/// cache at once
+const NPX_INSTALL_RACE_CODES: &[&str] = &["ENOTEMPTY", "EEXIST", "ETXTBSY"];
+
+impl ExecutorError {
+    /// Whether spawning again may succeed. On an agent's first `npx` run the
+    /// package is still being installed, possibly by a concurrent run: the
+    /// executable can be missing or busy being written (`ETXTBSY`), and npm
+    /// fails moving it into its cache. Forking can also fail for a moment
+    /// under load (`EAGAIN`). Anything else, like an auth problem, needs user
+    /// action, so retrying it only delays the failure.
+    pub fn is_transient(&self) -> bool {
+        use std::io::ErrorKind;
+
+        let momentary = |e: &std::io::Error| {
+            matches!(
+                e.kind(),
+                ErrorKind::WouldBlock | ErrorKind::ResourceBusy | ErrorKind::Interrupted
+            ) || {
+                let message = e.to_string();
+                NPX_INSTALL_RACE_CODES
+                    .iter()
+                    .any(|code| message.contains(code))
+            }
+        };
+        match self {
+            ExecutorError::SpawnTimeout(_) | ExecutorError::ExecutableNotFound { .. } => true,
+            ExecutorError::SpawnError(e) => {
+                matches!(
+                    e.kind(),
+                    ErrorKind::NotFound | ErrorKind::ExecutableFileBusy
+                ) || momentary(e)
+            }
+            ExecutorError::Io(e) => momentary(e),
+            _ => false,
+        }
+    }
+}
+
+/// How [`spawn_with_retry`] retries a failed spawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpawnRetryPolicy {
+    /// Retries after the first attempt; 0 disables retrying
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each one after it
+    pub base_delay: Duration,
+}
+
+impl SpawnRetryPolicy {
+    /// Delay before retry number `retry` (from 1): exponential backoff scaled
+    /// by a random factor in `[0.5, 1.0]`, so executors started together do
+    /// not retry in lockstep.
+    fn delay(&self, retry: u32) -> Duration {
+        let backoff = self.base_delay.saturating_mul(1 << (retry - 1).min(16));
+        backoff.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+/// Run `spawn` until it succeeds, fails with an error `is_transient` rejects,
+/// or `policy.max_retries` retries are used up. `on_retry` is called before
+/// each wait with the error, the delay and the retry number (from 1).
+///
+/// Errors are classified by the caller so wrappers around [`ExecutorError`]
+/// can be retried too; for a bare `ExecutorError` pass
+/// [`ExecutorError::is_transient`].
+pub async fn spawn_with_retry<T, E, F, Fut>(
+    policy: SpawnRetryPolicy,
+    mut spawn: F,
+    is_transient: impl Fn(&E) -> bool,
+    mut on_retry: impl FnMut(&E, Duration, u32),
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut retries = 0;
+    loop {
+        match spawn().await {
+            Err(e) if retries < policy.max_retries && is_transient(&e) => {
+                retries += 1;
+                let delay = policy.delay(retries);
+                on_retry(&e, delay, retries);
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
 }
 
 #[enum_dispatch]
@@ -176,6 +285,7 @@ impl CodingAgent {
         match self {
             Self::ClaudeCode(_) => vec![
                 BaseAgentCapability::SessionFork,
+                BaseAgentCapability::ResumeFromTurn,
                 BaseAgentCapability::ContextUsage,
             ],
             Self::Opencode(_) => vec![
@@ -187,9 +297,11 @@ impl CodingAgent {
                 BaseAgentCapability::SetupHelper,
                 BaseAgentCapability::ContextUsage,
             ],
-            Self::Amp(_) | Self::Gemini(_) | Self::QwenCode(_) | Self::Droid(_) => {
-                vec![BaseAgentCapability::SessionFork]
-            }
+            Self::Gemini(_) | Self::QwenCode(_) => vec![
+                BaseAgentCapability::SessionFork,
+                BaseAgentCapability::ContextUsage,
+            ],
+            Self::Amp(_) | Self::Droid(_) => vec![BaseAgentCapability::SessionFork],
             Self::CursorAgent(_) => vec![BaseAgentCapability::SetupHelper],
             Self::Copilot(_) => vec![],
             #[cfg(feature = "qa-mode")]
@@ -245,6 +357,21 @@ pub trait StandardCodingAgentExecutor {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError>;
 
+    /// Resume `session_id` from an earlier turn rather than the latest one.
+    /// Executors without [`BaseAgentCapability::ResumeFromTurn`] reject this.
+    async fn spawn_follow_up_from_turn(
+        &self,
+        _current_dir: &Path,
+        _prompt: &str,
+        _session_id: &str,
+        _resume_from: &TurnRef,
+        _env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        Err(ExecutorError::FollowUpNotSupported(
+            "this executor cannot resume from a specific turn".to_string(),
+        ))
+    }
+
     async fn spawn_review(
         &self,
         current_dir: &Path,
@@ -260,6 +387,25 @@ pub trait StandardCodingAgentExecutor {
 
     fn normalize_logs(&self, _raw_logs_event_store: Arc<MsgStore>, _worktree_path: &Path);
 
+    /// Model names the agent is known to accept, offered by the config UI.
+    /// Empty when the agent takes any model name.
+    fn supported_models(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Reject a model the agent won't accept before spawning it, rather than
+    /// letting the CLI fail after launch
+    fn validate_model(&self, model: &str) -> Result<(), ExecutorError> {
+        check_known_model(model, self.supported_models())
+    }
+
+    /// Whether a spawn that fails transiently is worth retrying. Agents run
+    /// through `npx` opt in: their first run installs the package, which can
+    /// race with another run doing the same.
+    fn retries_spawn(&self) -> bool {
+        false
+    }
+
     // MCP configuration methods
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf>;
 
@@ -281,6 +427,18 @@ pub trait StandardCodingAgentExecutor {
     }
 }
 
+/// Accept `model` if it is in `supported`, or if there is no known list
+pub fn check_known_model(model: &str, supported: Vec<String>) -> Result<(), ExecutorError> {
+    if supported.is_empty() || supported.iter().any(|known| known == model) {
+        Ok(())
+    } else {
+        Err(ExecutorError::UnknownModel {
+            model: model.to_string(),
+            supported,
+        })
+    }
+}
+
 /// Result communicated through the exit signal
 #[derive(Debug, Clone, Copy)]
 pub enum ExecutorExitResult {
@@ -341,27 +499,75 @@ impl AppendPrompt {
     }
 }
 
+/// Narrows what a review prompt asks the agent to look at
+#[derive(Debug, Clone, Default)]
+pub struct ReviewPromptOptions {
+    /// Paths to review per repo, keyed by repo id. Repos without an entry
+    /// are reviewed in full.
+    pub repo_paths: HashMap<Uuid, Vec<String>>,
+    /// Most files to review across all repos
+    pub max_files: Option<usize>,
+}
+
 pub fn build_review_prompt(
     context: Option<&[RepoReviewContext]>,
     additional_prompt: Option<&str>,
+) -> String {
+    build_review_prompt_with_options(context, additional_prompt, &ReviewPromptOptions::default())
+}
+
+pub fn build_review_prompt_with_options(
+    context: Option<&[RepoReviewContext]>,
+    additional_prompt: Option<&str>,
+    options: &ReviewPromptOptions,
 ) -> String {
     let mut prompt = String::from("Please review the code changes.\n\n");
 
     if let Some(repos) = context {
         for repo in repos {
+            let paths = options
+                .repo_paths
+                .get(&repo.repo_id)
+                .filter(|paths| !paths.is_empty());
             prompt.push_str(&format!("Repository: {}\n", repo.repo_name));
-            prompt.push_str(&format!(
-                "Review all changes from base commit {} to HEAD.\n",
-                repo.base_commit
-            ));
-            prompt.push_str(&format!(
-                "Use `git diff {}..HEAD` to see the changes.\n",
-                repo.base_commit
-            ));
+            match paths {
+                Some(paths) => {
+                    prompt.push_str(&format!(
+                        "Review the changes from base commit {} to HEAD in these paths: {}\n",
+                        repo.base_commit,
+                        paths.join(", ")
+                    ));
+                    let quoted: Vec<_> = paths
+                        .iter()
+                        .map(|path| shlex::try_quote(path).unwrap_or(path.into()))
+                        .collect();
+                    prompt.push_str(&format!(
+                        "Use `git diff {}..HEAD -- {}` to see the changes.\n",
+                        repo.base_commit,
+                        quoted.join(" ")
+                    ));
+                }
+                None => {
+                    prompt.push_str(&format!(
+                        "Review all changes from base commit {} to HEAD.\n",
+                        repo.base_commit
+                    ));
+                    prompt.push_str(&format!(
+                        "Use `git diff {}..HEAD` to see the changes.\n",
+                        repo.base_commit
+                    ));
+                }
+            }
             prompt.push('\n');
         }
     }
 
+    if let Some(max_files) = options.max_files {
+        prompt.push_str(&format!(
+            "Review at most {max_files} files in total, prioritizing the most significant changes, and list any changed files you skipped.\n\n"
+        ));
+    }
+
     if let Some(additional) = additional_prompt {
         prompt.push_str(additional);
     }
@@ -400,4 +606,183 @@ mod tests {
         assert!(result.is_ok(), "CURSOR should deserialize via serde");
         assert_eq!(result.unwrap(), BaseCodingAgent::CursorAgent);
     }
+
+    #[test]
+    fn test_review_prompt_scopes_diff_to_repo_paths() {
+        let repos = [
+            RepoReviewContext {
+                repo_id: Uuid::new_v4(),
+                repo_name: "api".to_string(),
+                base_commit: "abc123".to_string(),
+            },
+            RepoReviewContext {
+                repo_id: Uuid::new_v4(),
+                repo_name: "web".to_string(),
+                base_commit: "def456".to_string(),
+            },
+        ];
+        let options = ReviewPromptOptions {
+            repo_paths: HashMap::from([(
+                repos[0].repo_id,
+                vec!["src/routes".to_string(), "docs/api guide.md".to_string()],
+            )]),
+            max_files: Some(20),
+        };
+
+        let prompt = build_review_prompt_with_options(Some(&repos), None, &options);
+        assert!(prompt.contains("`git diff abc123..HEAD -- src/routes 'docs/api guide.md'`"));
+        assert!(prompt.contains("`git diff def456..HEAD`"));
+        assert!(prompt.contains("at most 20 files"));
+
+        assert_eq!(
+            build_review_prompt(Some(&repos), Some("Focus on security.")),
+            build_review_prompt_with_options(
+                Some(&repos),
+                Some("Focus on security."),
+                &ReviewPromptOptions::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_unknown_models_are_rejected() {
+        let claude: ClaudeCode = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(claude.validate_model("opus").is_ok());
+        assert!(claude.validate_model("claude-sonnet-4-5-20250929").is_ok());
+        assert!(matches!(
+            claude.validate_model("opsu"),
+            Err(ExecutorError::UnknownModel { model, supported })
+                if model == "opsu" && supported.contains(&"opus".to_string())
+        ));
+
+        assert!(check_known_model("anything", Vec::new()).is_ok());
+    }
+
+    #[test]
+    fn test_models_that_cannot_be_listed_pass_validation() {
+        let claude: ClaudeCode = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(claude.validate_model("sonnet[1m]").is_ok());
+        assert!(
+            claude
+                .validate_model("us.anthropic.claude-sonnet-4-5-20250929-v1:0")
+                .is_ok()
+        );
+        let router: ClaudeCode =
+            serde_json::from_value(serde_json::json!({ "claude_code_router": true })).unwrap();
+        assert!(router.validate_model("openrouter,some/model").is_ok());
+
+        let codex: Codex = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(codex.validate_model("gpt-6").is_ok());
+        assert!(codex.validate_model("o5-mini").is_ok());
+        assert!(codex.validate_model("gtp-5").is_err());
+        let oss: Codex = serde_json::from_value(serde_json::json!({ "oss": true })).unwrap();
+        assert!(oss.validate_model("llama3").is_ok());
+
+        let gemini: Gemini = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(gemini.validate_model("flash").is_ok());
+        assert!(gemini.validate_model("gemini-9-ultra").is_ok());
+        assert!(gemini.validate_model("flsh").is_err());
+    }
+
+    #[test]
+    fn test_only_transient_spawn_errors_are_retryable() {
+        let io = |kind| std::io::Error::new(kind, "spawn failed");
+        assert!(ExecutorError::SpawnError(io(std::io::ErrorKind::NotFound)).is_transient());
+        assert!(
+            ExecutorError::SpawnError(io(std::io::ErrorKind::ExecutableFileBusy)).is_transient()
+        );
+        assert!(ExecutorError::SpawnError(io(std::io::ErrorKind::WouldBlock)).is_transient());
+        assert!(
+            ExecutorError::ExecutableNotFound {
+                program: "npx".to_string()
+            }
+            .is_transient()
+        );
+        assert!(
+            ExecutorError::Io(std::io::Error::other(
+                "npm ERR! code ENOTEMPTY\nnpm ERR! syscall rename"
+            ))
+            .is_transient()
+        );
+        assert!(ExecutorError::SpawnTimeout(Duration::from_secs(30)).is_transient());
+
+        assert!(
+            !ExecutorError::SpawnError(io(std::io::ErrorKind::PermissionDenied)).is_transient()
+        );
+        assert!(!ExecutorError::Io(io(std::io::ErrorKind::NotFound)).is_transient());
+        assert!(!ExecutorError::Io(io(std::io::ErrorKind::Other)).is_transient());
+        assert!(!ExecutorError::AuthRequired("login".to_string()).is_transient());
+        assert!(!ExecutorError::UnknownExecutorType("X".to_string()).is_transient());
+    }
+
+    #[test]
+    fn test_only_npx_agents_opt_into_spawn_retries() {
+        let claude: ClaudeCode = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(claude.retries_spawn());
+        let cursor: CursorAgent = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(!cursor.retries_spawn());
+    }
+
+    #[tokio::test]
+    async fn test_spawn_with_retry_stops_at_non_transient_errors() {
+        let policy = SpawnRetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::ZERO,
+        };
+        let busy = || {
+            ExecutorError::SpawnError(std::io::Error::from(std::io::ErrorKind::ExecutableFileBusy))
+        };
+
+        // Transient failures are retried until the spawn succeeds
+        let mut attempts = 0;
+        let mut retries = Vec::new();
+        let result = spawn_with_retry(
+            policy,
+            || {
+                attempts += 1;
+                let result = if attempts < 3 {
+                    Err(busy())
+                } else {
+                    Ok(attempts)
+                };
+                async move { result }
+            },
+            ExecutorError::is_transient,
+            |_, _, retry| retries.push(retry),
+        )
+        .await;
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(retries, vec![1, 2]);
+
+        // Non-transient errors fail on the first attempt
+        let mut attempts = 0;
+        let result: Result<(), _> = spawn_with_retry(
+            policy,
+            || {
+                attempts += 1;
+                async { Err(ExecutorError::AuthRequired("login".to_string())) }
+            },
+            ExecutorError::is_transient,
+            |_, _, _| {},
+        )
+        .await;
+        assert!(matches!(result, Err(ExecutorError::AuthRequired(_))));
+        assert_eq!(attempts, 1);
+
+        // Retries are bounded
+        let mut attempts = 0;
+        let result: Result<(), _> = spawn_with_retry(
+            policy,
+            || {
+                attempts += 1;
+                let e = busy();
+                async move { Err(e) }
+            },
+            ExecutorError::is_transient,
+            |_, _, _| {},
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 4);
+    }
 }
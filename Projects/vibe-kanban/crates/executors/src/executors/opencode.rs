@@ -30,7 +30,7 @@ mod slash_commands;
 mod types;
 
 use sdk::{LogWriter, RunConfig, generate_server_password, run_session, run_slash_command};
-use slash_commands::{OpencodeSlashCommand, hardcoded_slash_commands};
+use slash_commands::{OpencodeSlashCommand, local_slash_commands};
 
 #[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
 #[derivative(Debug, PartialEq)]
@@ -304,7 +304,7 @@ impl StandardCodingAgentExecutor for Opencode {
         &self,
         current_dir: &Path,
     ) -> Result<futures::stream::BoxStream<'static, json_patch::Patch>, ExecutorError> {
-        let defaults = hardcoded_slash_commands();
+        let defaults = local_slash_commands(current_dir);
         let this = self.clone();
         let current_dir = current_dir.to_path_buf();
 
@@ -353,6 +353,10 @@ impl StandardCodingAgentExecutor for Opencode {
         normalize_logs::normalize_logs(msg_store, worktree_path);
     }
 
+    fn retries_spawn(&self) -> bool {
+        true
+    }
+
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         // Try multiple config file names (.json and .jsonc) in XDG/platform config dirs
         #[cfg(unix)]
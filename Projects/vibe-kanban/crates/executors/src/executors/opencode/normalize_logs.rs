@@ -17,7 +17,7 @@ use crate::{
         ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
-            EntryIndexProvider,
+            ContextBudgetGuard, EntryIndexProvider,
             patch::{add_normalized_entry, replace_normalized_entry, upsert_normalized_entry},
         },
     },
@@ -72,15 +72,17 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     total_tokens,
                     model_context_window,
                 } => {
+                    let usage = TokenUsageInfo {
+                        total_tokens,
+                        model_context_window,
+                    };
+                    let warning = state.context_budget.check(&usage);
                     add_normalized_entry(
                         &msg_store,
                         &entry_index,
                         NormalizedEntry {
                             timestamp: None,
-                            entry_type: NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
-                                total_tokens,
-                                model_context_window,
-                            }),
+                            entry_type: NormalizedEntryType::TokenUsageInfo(usage),
                             content: format!(
                                 "Tokens used: {} / Context window: {}",
                                 total_tokens, model_context_window
@@ -88,6 +90,9 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             metadata: None,
                         },
                     );
+                    if let Some(warning) = warning {
+                        add_normalized_entry(&msg_store, &entry_index, warning);
+                    }
                 }
                 OpencodeExecutorEvent::SlashCommandResult { message } => {
                     let idx = entry_index.next();
@@ -177,10 +182,12 @@ struct LogState {
     todo_update_entry: Option<usize>,
     todo_update_fingerprint: Option<String>,
     retry_status_fingerprint: Option<String>,
+    context_budget: ContextBudgetGuard,
 }
 
 impl LogState {
     fn new(entry_index: EntryIndexProvider, msg_store: Arc<MsgStore>) -> Self {
+        let context_budget = ContextBudgetGuard::for_store(&msg_store);
         Self {
             entry_index,
             msg_store,
@@ -193,6 +200,7 @@ impl LogState {
             todo_update_entry: None,
             todo_update_fingerprint: None,
             retry_status_fingerprint: None,
+            context_budget,
         }
     }
 
@@ -640,6 +648,9 @@ enum ToolData {
     WebFetch {
         url: Option<String>,
     },
+    WebSearch {
+        query: Option<String>,
+    },
     Search {
         query: Option<String>,
     },
@@ -844,6 +855,13 @@ impl ToolCallState {
                     *url = Some(u);
                 }
             }
+            ToolData::WebSearch { query } => {
+                if let Some(q) =
+                    input.and_then(|v| v.get("query").and_then(Value::as_str).map(str::to_string))
+                {
+                    *query = Some(q);
+                }
+            }
             ToolData::Search { query } => {
                 if let Some(inp) = input {
                     *query = inp
@@ -943,7 +961,8 @@ impl ToolCallState {
                 unified_diff: None,
             },
             "webfetch" => ToolData::WebFetch { url: None },
-            "websearch" | "codesearch" | "grep" | "glob" => ToolData::Search { query: None },
+            "websearch" => ToolData::WebSearch { query: None },
+            "codesearch" | "grep" | "glob" => ToolData::Search { query: None },
             "todoread" | "todowrite" => ToolData::Todo {
                 operation: if self.tool_name == "todoread" {
                     TodoOperation::Read
@@ -1031,6 +1050,9 @@ impl ToolCallState {
             ToolData::WebFetch { url } => ActionType::WebFetch {
                 url: url.clone().unwrap_or_default(),
             },
+            ToolData::WebSearch { query } => ActionType::WebSearch {
+                query: query.clone().unwrap_or_default(),
+            },
             ToolData::Search { query } => ActionType::Search {
                 query: query.clone().unwrap_or_default(),
             },
@@ -1075,6 +1097,7 @@ impl ToolCallState {
             ActionType::FileEdit { path, .. } => path.clone(),
             ActionType::Search { query } => query.clone(),
             ActionType::WebFetch { url } => url.clone(),
+            ActionType::WebSearch { query } => query.clone(),
             ActionType::TodoManagement { .. } => "TODO list updated".to_string(),
             _ => String::new(),
         }
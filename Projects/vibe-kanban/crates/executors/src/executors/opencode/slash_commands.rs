@@ -30,8 +30,8 @@ use crate::{
         BaseCodingAgent, ExecutorError, SlashCommandDescription,
         opencode::Opencode,
         utils::{
-            SlashCommandCache, SlashCommandCacheKey, SlashCommandCall, parse_slash_command,
-            reorder_slash_commands,
+            SlashCommandCache, SlashCommandCacheKey, SlashCommandCall, discover_markdown_commands,
+            parse_slash_command, reorder_slash_commands,
         },
     },
 };
@@ -128,6 +128,39 @@ impl<'a> From<SlashCommandCall<'a>> for OpencodeSlashCommand {
     }
 }
 
+/// Global `command` directory in the OpenCode config dir.
+fn global_command_dir() -> Option<std::path::PathBuf> {
+    #[cfg(unix)]
+    {
+        xdg::BaseDirectories::with_prefix("opencode").get_config_file("command")
+    }
+    #[cfg(not(unix))]
+    {
+        dirs::config_dir().map(|config| config.join("opencode").join("command"))
+    }
+}
+
+/// Hardcoded commands plus custom commands found in the project's
+/// `.opencode/command` and the global OpenCode `command` directory, without
+/// starting a server. Project commands shadow global ones of the same name.
+pub fn local_slash_commands(current_dir: &Path) -> Vec<SlashCommandDescription> {
+    let defaults = hardcoded_slash_commands();
+    let mut seen: HashSet<String> = defaults.iter().map(|cmd| cmd.name.clone()).collect();
+
+    let project = discover_markdown_commands(&current_dir.join(".opencode").join("command"), None);
+    let global = global_command_dir()
+        .map(|dir| discover_markdown_commands(&dir, None))
+        .unwrap_or_default();
+
+    let commands = project
+        .into_iter()
+        .chain(global)
+        .filter(|cmd| seen.insert(cmd.name.clone()))
+        .chain(defaults)
+        .collect();
+    reorder_slash_commands(commands)
+}
+
 /// Build the list of hardcoded slash commands for discovery.
 pub fn hardcoded_slash_commands() -> Vec<SlashCommandDescription> {
     vec![
@@ -195,6 +195,7 @@ fn generate_mock_logs(prompt: &str) -> Vec<String> {
             status: None,
             slash_commands: vec![],
             plugins: vec![],
+            compact_metadata: None,
         },
         // 2. Assistant thinking
         ClaudeJson::Assistant {
@@ -17,6 +17,9 @@ use crate::{
     },
 };
 
+/// Context window used when none is configured, that of Qwen3-Coder-Plus
+const DEFAULT_QWEN_CONTEXT_WINDOW: u32 = 1_048_576;
+
 #[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
 #[derivative(Debug, PartialEq)]
 pub struct QwenCode {
@@ -24,6 +27,9 @@ pub struct QwenCode {
     pub append_prompt: AppendPrompt,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub yolo: Option<bool>,
+    /// Context window of the model in tokens, for the context usage bar
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u32>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
     #[serde(skip)]
@@ -105,10 +111,18 @@ impl StandardCodingAgentExecutor for QwenCode {
     }
 
     fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
-        crate::executors::acp::normalize_logs(msg_store, worktree_path);
+        crate::executors::acp::normalize_logs(
+            msg_store,
+            worktree_path,
+            self.context_window.unwrap_or(DEFAULT_QWEN_CONTEXT_WINDOW),
+        );
     }
 
     // MCP configuration methods
+    fn retries_spawn(&self) -> bool {
+        true
+    }
+
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".qwen").join("settings.json"))
     }
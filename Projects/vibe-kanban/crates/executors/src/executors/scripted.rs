@@ -0,0 +1,194 @@
+//! Deterministic executor for tests
+//!
+//! Replays a fixed sequence of [`NormalizedEntry`] values instead of running an
+//! agent, so downstream crates can exercise spawning, log normalization and
+//! exit signaling without installing a coding agent or enabling `qa-mode`.
+
+use std::{path::Path, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use tokio::io::AsyncWriteExt;
+use tokio_util::sync::CancellationToken;
+use workspace_utils::msg_store::MsgStore;
+
+use crate::{
+    env::ExecutionEnv,
+    executors::{ExecutorError, ExecutorExitResult, SpawnedChild, StandardCodingAgentExecutor},
+    logs::{
+        NormalizedEntry,
+        utils::{EntryIndexProvider, patch::ConversationPatch},
+    },
+    stdout_dup::spawn_local_output_process,
+};
+
+/// Writes each entry as a JSON line on stdout, then signals `exit_result`.
+/// Cancelling stops the replay and signals [`ExecutorExitResult::Failure`].
+#[derive(Debug, Clone)]
+pub struct ScriptedExecutor {
+    entries: Vec<NormalizedEntry>,
+    exit_result: ExecutorExitResult,
+    step_delay: Duration,
+}
+
+impl ScriptedExecutor {
+    pub fn new(entries: Vec<NormalizedEntry>) -> Self {
+        Self {
+            entries,
+            exit_result: ExecutorExitResult::Success,
+            step_delay: Duration::ZERO,
+        }
+    }
+
+    /// Result signalled once every entry has been written
+    pub fn with_exit_result(mut self, exit_result: ExecutorExitResult) -> Self {
+        self.exit_result = exit_result;
+        self
+    }
+
+    /// Pause after each entry, e.g. to cancel part way through the script
+    pub fn with_step_delay(mut self, step_delay: Duration) -> Self {
+        self.step_delay = step_delay;
+        self
+    }
+
+    fn replay(&self) -> Result<SpawnedChild, ExecutorError> {
+        let (mut spawned, mut writer) = spawn_local_output_process()?;
+        let (exit_signal_tx, exit_signal_rx) = tokio::sync::oneshot::channel();
+        let cancel = CancellationToken::new();
+
+        let entries = self.entries.clone();
+        let exit_result = self.exit_result;
+        let step_delay = self.step_delay;
+        let cancel_for_task = cancel.clone();
+        tokio::spawn(async move {
+            for entry in entries {
+                if cancel_for_task.is_cancelled() {
+                    let _ = exit_signal_tx.send(ExecutorExitResult::Failure);
+                    return;
+                }
+                let mut line = serde_json::to_string(&entry).unwrap_or_default();
+                line.push('\n');
+                if let Err(err) = writer.write_all(line.as_bytes()).await {
+                    tracing::error!("Failed to write scripted entry: {err}");
+                    let _ = exit_signal_tx.send(ExecutorExitResult::Failure);
+                    return;
+                }
+                tokio::select! {
+                    _ = cancel_for_task.cancelled() => {
+                        let _ = exit_signal_tx.send(ExecutorExitResult::Failure);
+                        return;
+                    }
+                    _ = tokio::time::sleep(step_delay) => {}
+                }
+            }
+            let _ = writer.flush().await;
+            let _ = exit_signal_tx.send(exit_result);
+        });
+
+        spawned.exit_signal = Some(exit_signal_rx);
+        spawned.cancel = Some(cancel);
+        Ok(spawned)
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for ScriptedExecutor {
+    async fn spawn(
+        &self,
+        _current_dir: &Path,
+        _prompt: &str,
+        _env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.replay()
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        _current_dir: &Path,
+        _prompt: &str,
+        _session_id: &str,
+        _env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        self.replay()
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, _worktree_path: &Path) {
+        let entry_index = EntryIndexProvider::start_from(&msg_store);
+        tokio::spawn(async move {
+            let mut stdout_lines = msg_store.stdout_lines_stream();
+            while let Some(Ok(line)) = stdout_lines.next().await {
+                match serde_json::from_str::<NormalizedEntry>(&line) {
+                    Ok(entry) => msg_store.push_patch(ConversationPatch::add_normalized_entry(
+                        entry_index.next(),
+                        entry,
+                    )),
+                    Err(err) => tracing::warn!("Skipping scripted output line: {err}"),
+                }
+            }
+        });
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+    use crate::logs::NormalizedEntryType;
+
+    fn message(content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn replays_entries_then_signals_exit() {
+        let executor = ScriptedExecutor::new(vec![message("first"), message("second")])
+            .with_exit_result(ExecutorExitResult::Failure);
+        let env = ExecutionEnv::new(Default::default(), false);
+        let mut spawned = executor.spawn(Path::new("."), "", &env).await.unwrap();
+
+        let mut stdout = spawned.child.inner().stdout.take().unwrap();
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).await.unwrap();
+        let contents: Vec<_> = output
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<NormalizedEntry>(line)
+                    .unwrap()
+                    .content
+            })
+            .collect();
+        assert_eq!(contents, ["first", "second"]);
+
+        let result = spawned.exit_signal.take().unwrap().await.unwrap();
+        assert!(matches!(result, ExecutorExitResult::Failure));
+        let _ = spawned.child.kill().await;
+    }
+
+    #[tokio::test]
+    async fn cancelling_stops_the_replay() {
+        let executor = ScriptedExecutor::new(vec![message("first"), message("never sent")])
+            .with_step_delay(Duration::from_secs(60));
+        let env = ExecutionEnv::new(Default::default(), false);
+        let mut spawned = executor.spawn(Path::new("."), "", &env).await.unwrap();
+
+        spawned.cancel.take().unwrap().cancel();
+        let result =
+            tokio::time::timeout(Duration::from_secs(5), spawned.exit_signal.take().unwrap())
+                .await
+                .unwrap()
+                .unwrap();
+        assert!(matches!(result, ExecutorExitResult::Failure));
+        let _ = spawned.child.kill().await;
+    }
+}
@@ -1,14 +1,15 @@
 use std::{
     num::NonZeroUsize,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex, OnceLock},
     time::{Duration, Instant},
 };
 
 use lru::LruCache;
+use tokio::process::Command;
 
 use super::SlashCommandDescription;
-use crate::executors::BaseCodingAgent;
+use crate::{env::ExecutionEnv, executors::BaseCodingAgent};
 
 /// Parsed slash command with name and arguments.
 
@@ -41,6 +42,58 @@ where
     Some(T::from(SlashCommandCall { name, arguments }))
 }
 
+/// `description` from the YAML frontmatter of a markdown command file.
+pub fn frontmatter_description(content: &str) -> Option<String> {
+    if !content.starts_with("---") {
+        return None;
+    }
+
+    // Find end of frontmatter
+    let end = content[3..].find("---")?;
+    let frontmatter = &content[3..3 + end];
+
+    for line in frontmatter.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("description:") {
+            return Some(rest.trim().to_string());
+        }
+    }
+    None
+}
+
+/// Custom commands defined as `<name>.md` files directly in `dir`, named
+/// `prefix:name` when a prefix is given, sorted by name. A missing directory
+/// has no commands.
+pub fn discover_markdown_commands(
+    dir: &Path,
+    prefix: Option<&str>,
+) -> Vec<SlashCommandDescription> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut commands: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?;
+            let description = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| frontmatter_description(&content));
+            Some(SlashCommandDescription {
+                name: match prefix {
+                    Some(prefix) => format!("{prefix}:{name}"),
+                    None => name.to_string(),
+                },
+                description,
+            })
+        })
+        .collect();
+    commands.sort_by(|a, b| a.name.cmp(&b.name));
+    commands
+}
+
 pub const SLASH_COMMANDS_CACHE_CAPACITY: usize = 32;
 const TTL: Duration = Duration::from_secs(60 * 5);
 
@@ -130,3 +183,78 @@ impl SlashCommandCache {
         );
     }
 }
+
+/// Env vars holding an agent's pay-as-you-go API key. When set, the agent
+/// bills the key instead of the user's subscription login.
+#[derive(Debug, Clone, Copy)]
+pub struct UnmanagedApiKey {
+    pub vars: &'static [&'static str],
+    /// Shown in the logs when the agent is spawned with one of `vars`, with
+    /// `{var}` replaced by the variable found
+    pub warning: &'static str,
+}
+
+impl UnmanagedApiKey {
+    /// Remove the key from `command` when `disable` is set. Otherwise returns
+    /// the warning if `env` or the inherited environment sets one of the vars.
+    pub fn apply(
+        &self,
+        command: &mut Command,
+        env: &ExecutionEnv,
+        disable: bool,
+    ) -> Option<String> {
+        if disable {
+            for var in self.vars {
+                command.env_remove(var);
+            }
+            tracing::info!("{} removed from environment", self.vars.join(", "));
+            return None;
+        }
+
+        let var = self.vars.iter().find(|var| match env.get(var) {
+            Some(value) => !value.is_empty(),
+            None => std::env::var_os(var).is_some_and(|value| !value.is_empty()),
+        })?;
+        tracing::warn!(
+            "{var} env variable detected, the agent's subscription login is not being used"
+        );
+        Some(self.warning.replace("{var}", var))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_description_from_frontmatter() {
+        let content = "---\nargument-hint: [file]\ndescription: Explain a file \n---\nBody";
+        assert_eq!(
+            frontmatter_description(content).as_deref(),
+            Some("Explain a file")
+        );
+        assert_eq!(
+            frontmatter_description("description: not frontmatter"),
+            None
+        );
+        assert_eq!(frontmatter_description("---\nname: x\n---\n"), None);
+    }
+
+    #[test]
+    fn unmanaged_api_key_warns_unless_disabled() {
+        let key = UnmanagedApiKey {
+            vars: &["VK_TEST_UNSET_API_KEY", "VK_TEST_API_KEY"],
+            warning: "{var} detected",
+        };
+        let mut env = ExecutionEnv::new(Default::default(), false);
+        let mut command = Command::new("true");
+        assert_eq!(key.apply(&mut command, &env, false), None);
+
+        env.insert("VK_TEST_API_KEY", "sk-test");
+        assert_eq!(
+            key.apply(&mut command, &env, false).as_deref(),
+            Some("VK_TEST_API_KEY detected")
+        );
+        assert_eq!(key.apply(&mut command, &env, true), None);
+    }
+}
@@ -199,6 +199,9 @@ pub enum ActionType {
     WebFetch {
         url: String,
     },
+    WebSearch {
+        query: String,
+    },
     /// Generic tool with optional arguments and result for rich rendering
     Tool {
         tool_name: String,
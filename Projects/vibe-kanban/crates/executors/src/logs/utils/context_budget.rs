@@ -0,0 +1,114 @@
+//! Warns when an agent's context window is close to full, before the agent
+//! starts silently truncating or compacting history.
+
+use workspace_utils::msg_store::MsgStore;
+
+use crate::logs::{NormalizedEntry, NormalizedEntryType, TokenUsageInfo};
+
+pub const DEFAULT_CONTEXT_WARNING_THRESHOLD_PERCENT: u8 = 85;
+
+/// Per-execution state so the warning fires once each time usage crosses the
+/// threshold, and again only after usage has dropped back below it (e.g. after
+/// the agent compacted its context).
+/// The default guard never warns.
+#[derive(Debug, Default)]
+pub struct ContextBudgetGuard {
+    threshold_percent: Option<u8>,
+    warned: bool,
+}
+
+impl ContextBudgetGuard {
+    /// Guard for the execution whose logs `msg_store` holds, using the
+    /// threshold the deployment set on that store
+    pub fn for_store(msg_store: &MsgStore) -> Self {
+        Self {
+            threshold_percent: msg_store
+                .context_warning_threshold()
+                .map(|percent| percent.clamp(1, 100)),
+            warned: false,
+        }
+    }
+
+    pub fn check(&mut self, usage: &TokenUsageInfo) -> Option<NormalizedEntry> {
+        let threshold_percent = self.threshold_percent?;
+        self.check_with_threshold(usage, threshold_percent)
+    }
+
+    fn check_with_threshold(
+        &mut self,
+        usage: &TokenUsageInfo,
+        threshold_percent: u8,
+    ) -> Option<NormalizedEntry> {
+        if usage.model_context_window == 0 {
+            return None;
+        }
+        let percent_used =
+            u64::from(usage.total_tokens) * 100 / u64::from(usage.model_context_window);
+        if percent_used < u64::from(threshold_percent) {
+            self.warned = false;
+            return None;
+        }
+        if self.warned {
+            return None;
+        }
+        self.warned = true;
+
+        Some(NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content: format!(
+                "Context window is {percent_used}% full ({} / {} tokens). Consider compacting the conversation or starting a new session.",
+                usage.total_tokens, usage.model_context_window
+            ),
+            metadata: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(total_tokens: u32) -> TokenUsageInfo {
+        TokenUsageInfo {
+            total_tokens,
+            model_context_window: 200_000,
+        }
+    }
+
+    #[test]
+    fn warns_once_per_threshold_crossing() {
+        let mut guard = ContextBudgetGuard::default();
+        assert!(guard.check_with_threshold(&usage(100_000), 85).is_none());
+        assert!(guard.check_with_threshold(&usage(172_000), 85).is_some());
+        assert!(guard.check_with_threshold(&usage(180_000), 85).is_none());
+        // Usage drops after compaction, then climbs back over the threshold
+        assert!(guard.check_with_threshold(&usage(40_000), 85).is_none());
+        assert!(guard.check_with_threshold(&usage(190_000), 85).is_some());
+    }
+
+    #[test]
+    fn ignores_unknown_context_window() {
+        let mut guard = ContextBudgetGuard::default();
+        let usage = TokenUsageInfo {
+            total_tokens: 1_000,
+            model_context_window: 0,
+        };
+        assert!(guard.check_with_threshold(&usage, 85).is_none());
+    }
+
+    #[test]
+    fn takes_the_threshold_from_the_store() {
+        let disabled = MsgStore::new();
+        assert!(
+            ContextBudgetGuard::for_store(&disabled)
+                .check(&usage(199_000))
+                .is_none()
+        );
+
+        let store = MsgStore::new().with_context_warning(Some(50));
+        let mut guard = ContextBudgetGuard::for_store(&store);
+        assert!(guard.check(&usage(90_000)).is_none());
+        assert!(guard.check(&usage(110_000)).is_some());
+    }
+}
@@ -0,0 +1,128 @@
+//! Detects when a dev server is up by scanning its output for the address it
+//! listens on, so the UI can link to the running app instead of guessing.
+
+use std::sync::Arc;
+
+use futures::{StreamExt, stream::select};
+use regex::Regex;
+use tokio::task::JoinHandle;
+use workspace_utils::msg_store::MsgStore;
+
+/// Local URLs as printed by Vite, Next.js, webpack-dev-server, Rails, Django
+/// and most other frameworks once they are listening.
+pub const DEFAULT_READY_PATTERN: &str =
+    r"https?://(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1?\])(?::\d+)?[^\s'\x22<>]*";
+
+/// Prefix of the line announcing the URL. The frontend prefers a line starting
+/// with it over any URL it guesses from earlier output.
+pub const READY_LINE_PREFIX: &str = "Dev server is ready at ";
+
+pub struct DevServerReadyDetector {
+    pattern: Regex,
+}
+
+impl DevServerReadyDetector {
+    /// `pattern` replaces the built-in localhost URL detection.
+    pub fn new(pattern: Option<&str>) -> Result<Self, regex::Error> {
+        Ok(Self {
+            pattern: Regex::new(pattern.unwrap_or(DEFAULT_READY_PATTERN))?,
+        })
+    }
+
+    /// The URL a ready line points at. The pattern's first capture group, or
+    /// else its whole match, may be a URL or a bare port.
+    pub fn detect(&self, line: &str) -> Option<String> {
+        let line = strip_ansi_escapes::strip_str(line);
+        let captures = self.pattern.captures(&line)?;
+        let matched = captures.get(1).or_else(|| captures.get(0))?.as_str();
+        let matched = matched.trim().trim_end_matches(['.', ',', ';', ')']);
+
+        let port = matched.strip_prefix(':').unwrap_or(matched);
+        if !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()) {
+            return Some(format!("http://localhost:{port}"));
+        }
+        if matched.is_empty() {
+            return None;
+        }
+        // Wildcard binds aren't browsable addresses
+        Some(matched.replacen("://0.0.0.0", "://localhost", 1).replacen(
+            "://[::]",
+            "://localhost",
+            1,
+        ))
+    }
+
+    /// Watch the dev server's output and announce the URL on its stdout once
+    /// it's ready, where the preview reads it. Stops at the first match or when
+    /// the process exits; abort the handle to stop it sooner.
+    pub fn spawn(self, msg_store: Arc<MsgStore>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut lines = select(
+                msg_store.stdout_lines_stream(),
+                msg_store.stderr_lines_stream(),
+            );
+            while let Some(Ok(line)) = lines.next().await {
+                if let Some(url) = self.detect(&line) {
+                    msg_store.push_stdout(format!("{READY_LINE_PREFIX}{url}\n"));
+                    break;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use workspace_utils::log_msg::LogMsg;
+
+    use super::*;
+
+    #[test]
+    fn detects_common_framework_output() {
+        let detector = DevServerReadyDetector::new(None).unwrap();
+        assert_eq!(
+            detector.detect("  \x1b[32m➜\x1b[39m  Local:   \x1b[36mhttp://localhost:5173/\x1b[39m"),
+            Some("http://localhost:5173/".to_string())
+        );
+        assert_eq!(
+            detector.detect("Listening on http://0.0.0.0:3000."),
+            Some("http://localhost:3000".to_string())
+        );
+        assert_eq!(detector.detect("compiling 42 modules"), None);
+    }
+
+    #[test]
+    fn custom_pattern_capture_can_be_a_port() {
+        let detector = DevServerReadyDetector::new(Some(r"server started on port (\d+)")).unwrap();
+        assert_eq!(
+            detector.detect("INFO server started on port 8080"),
+            Some("http://localhost:8080".to_string())
+        );
+        assert!(DevServerReadyDetector::new(Some("(unclosed")).is_err());
+    }
+
+    #[tokio::test]
+    async fn announces_the_url_on_stdout_once() {
+        let store = Arc::new(MsgStore::new());
+        let handle = DevServerReadyDetector::new(None)
+            .unwrap()
+            .spawn(store.clone());
+        store.push_stdout("compiling\n");
+        store.push_stderr("Listening on http://0.0.0.0:3000\n");
+        tokio::time::timeout(std::time::Duration::from_secs(5), handle)
+            .await
+            .unwrap()
+            .unwrap();
+        store.push_stdout("Also on http://localhost:3001\n");
+
+        let ready: Vec<_> = store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stdout(s) if s.starts_with(READY_LINE_PREFIX) => Some(s),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ready, ["Dev server is ready at http://localhost:3000\n"]);
+    }
+}
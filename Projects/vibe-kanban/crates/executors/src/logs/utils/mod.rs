@@ -1,7 +1,10 @@
 //! Utility modules for executor framework
 
+pub mod context_budget;
+pub mod dev_server_ready;
 pub mod entry_index;
 pub mod patch;
 
+pub use context_budget::ContextBudgetGuard;
 pub use entry_index::EntryIndexProvider;
 pub use patch::ConversationPatch;
@@ -44,7 +44,7 @@ pub fn escape_json_pointer_segment(s: &str) -> String {
 pub struct ConversationPatch;
 
 impl ConversationPatch {
-    /// Create an ADD patch for a new conversation entry at the given index
+    /// Create an ADD patch for a new conversation entry at the given index.
     pub fn add_normalized_entry(entry_index: usize, entry: NormalizedEntry) -> Patch {
         let patch_entry = PatchEntry {
             op: PatchOperation::Add,
@@ -108,7 +108,7 @@ impl ConversationPatch {
         .unwrap()
     }
 
-    /// Create a REPLACE patch for updating an existing conversation entry at the given index
+    /// Create a REPLACE patch for updating an existing conversation entry at the given index.
     pub fn replace(entry_index: usize, entry: NormalizedEntry) -> Patch {
         let patch_entry = PatchEntry {
             op: PatchOperation::Replace,
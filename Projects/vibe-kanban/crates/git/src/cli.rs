@@ -16,16 +16,21 @@
 //! `git` CLI, while keeping libgit2 for read‑only graph queries and credentialed
 //! network operations when useful.
 use std::{
+    collections::HashMap,
     ffi::{OsStr, OsString},
-    io::Write as _,
-    path::Path,
-    process::{Command, Stdio},
+    io::{Read, Write as _},
+    path::{Path, PathBuf},
+    process::{Child, Command, Output, Stdio},
+    sync::Mutex,
+    time::Duration,
 };
 
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
 use utils::{path::ALWAYS_SKIP_DIRS, shell::resolve_executable_path_blocking};
 
 use super::Commit;
+use crate::progress::{FetchProgress, FetchProgressSink, apply_progress_line};
 
 #[derive(Debug, Error)]
 pub enum GitCliError {
@@ -39,8 +44,15 @@ pub enum GitCliError {
     PushRejected(String),
     #[error("rebase in progress in this worktree")]
     RebaseInProgress,
+    #[error("git command was cancelled")]
+    Cancelled,
 }
 
+/// How often a running cancellable command checks its token
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How long [`GitCli::remote_head_branch`] waits on an unreachable remote.
+const REMOTE_HEAD_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Clone, Default)]
 pub struct GitCli;
 
@@ -65,11 +77,32 @@ pub struct StatusDiffEntry {
     pub old_path: Option<String>,
 }
 
+/// Totals from `git diff --numstat`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffNumstat {
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Line counts for one file from `git diff --numstat` (zero for binary files)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileNumstat {
+    pub path: String,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
 /// Parsed worktree entry from `git worktree list --porcelain`
 #[derive(Debug, Clone)]
 pub struct WorktreeEntry {
     pub path: String,
+    pub head: String,
     pub branch: Option<String>,
+    /// `git worktree lock` was used; git refuses to remove it without `--force --force`
+    pub locked: bool,
+    /// The worktree directory is gone and `git worktree prune` would drop it
+    pub prunable: bool,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -77,6 +110,24 @@ pub struct StatusDiffOptions {
     pub path_filter: Option<Vec<String>>, // pathspecs to limit diff
 }
 
+/// Which half of the worktree's changes to diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexDiff {
+    /// HEAD vs index (`git diff --cached`)
+    Staged,
+    /// Index vs working tree (`git diff`)
+    Unstaged,
+}
+
+impl IndexDiff {
+    fn args(self) -> Vec<OsString> {
+        match self {
+            IndexDiff::Staged => vec!["diff".into(), "--cached".into()],
+            IndexDiff::Unstaged => vec!["diff".into()],
+        }
+    }
+}
+
 impl GitCli {
     pub fn new() -> Self {
         Self {}
@@ -107,6 +158,31 @@ impl GitCli {
         Ok(())
     }
 
+    /// Run `git switch` in an existing worktree, creating the branch from HEAD
+    /// with `create`. `discard_changes` throws away local modifications that
+    /// would otherwise block the switch.
+    pub fn switch_branch(
+        &self,
+        worktree_path: &Path,
+        branch: &str,
+        create: bool,
+        discard_changes: bool,
+    ) -> Result<(), GitCliError> {
+        let mut args: Vec<OsString> = vec!["switch".into()];
+        if discard_changes {
+            args.push("--discard-changes".into());
+        }
+        if create {
+            args.push("-c".into());
+        }
+        args.push(OsString::from(branch));
+        self.git(worktree_path, args)?;
+
+        // Non-fatal if it fails or not configured
+        let _ = self.git(worktree_path, ["sparse-checkout", "reapply"]);
+        Ok(())
+    }
+
     /// Run `git -C <repo> worktree remove <path>`
     pub fn worktree_remove(
         &self,
@@ -148,12 +224,65 @@ impl GitCli {
         Ok(())
     }
 
+    /// Run `git -C <repo> worktree repair <path>` to relink a worktree and its admin entry
+    pub fn worktree_repair(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+    ) -> Result<(), GitCliError> {
+        self.ensure_available()?;
+        let args: Vec<OsString> = vec![
+            "worktree".into(),
+            "repair".into(),
+            worktree_path.as_os_str().into(),
+        ];
+        self.git(repo_path, args)?;
+        Ok(())
+    }
+
     /// Prune stale worktree metadata
     pub fn worktree_prune(&self, repo_path: &Path) -> Result<(), GitCliError> {
         self.git(repo_path, ["worktree", "prune"])?;
         Ok(())
     }
 
+    /// Tracked files at the repository root and one directory below it.
+    pub fn ls_files_shallow(&self, repo_path: &Path) -> Result<Vec<String>, GitCliError> {
+        let out = self.git(
+            repo_path,
+            ["ls-files", "-z", "--", ":(glob)*", ":(glob)*/*"],
+        )?;
+        Ok(out
+            .split('\0')
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Directory git runs hooks from for this worktree. Linked worktrees share
+    /// the main repository's hooks, and `core.hooksPath` is honoured.
+    pub fn hooks_dir(&self, worktree_path: &Path) -> Result<PathBuf, GitCliError> {
+        let out = self.git(worktree_path, ["rev-parse", "--git-path", "hooks"])?;
+        // Relative results are relative to the directory git ran in
+        Ok(worktree_path.join(out.trim()))
+    }
+
+    /// Absolute path of this worktree's own git directory; for a linked
+    /// worktree that is `.git/worktrees/<name>` in the main repository.
+    pub fn worktree_git_dir(&self, worktree_path: &Path) -> Result<PathBuf, GitCliError> {
+        let out = self.git(worktree_path, ["rev-parse", "--absolute-git-dir"])?;
+        Ok(PathBuf::from(out.trim()))
+    }
+
+    /// Run `git submodule update --init --recursive` in the worktree.
+    pub fn submodule_update_init(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(
+            worktree_path,
+            ["submodule", "update", "--init", "--recursive"],
+        )?;
+        Ok(())
+    }
+
     /// Return true if there are any changes in the working tree (staged or unstaged).
     pub fn has_changes(&self, worktree_path: &Path) -> Result<bool, GitCliError> {
         let out = self.git(
@@ -171,6 +300,158 @@ impl GitCli {
         base_commit: &Commit,
         opts: StatusDiffOptions,
     ) -> Result<Vec<StatusDiffEntry>, GitCliError> {
+        let (_tmp_dir, envs) = self.stage_into_temp_index(worktree_path)?;
+        // git diff --cached
+        let mut args: Vec<OsString> = vec![
+            "-c".into(),
+            "core.quotepath=false".into(),
+            "diff".into(),
+            "--cached".into(),
+            "-M".into(),
+            "--name-status".into(),
+            OsString::from(base_commit.to_string()),
+        ];
+        args = Self::apply_pathspec_filter(args, opts.path_filter.as_ref());
+        let out = self.git_with_env(worktree_path, args, &envs)?;
+        Ok(Self::parse_name_status(&out))
+    }
+
+    /// Name-status of the staged or unstaged changes in the worktree. Unlike
+    /// [`Self::diff_status`], untracked files are not included.
+    pub fn diff_index_status(
+        &self,
+        worktree_path: &Path,
+        which: IndexDiff,
+        opts: StatusDiffOptions,
+    ) -> Result<Vec<StatusDiffEntry>, GitCliError> {
+        let mut args: Vec<OsString> = vec!["-c".into(), "core.quotepath=false".into()];
+        args.extend(which.args());
+        args.extend(["-M".into(), "--name-status".into()]);
+        args = Self::apply_pathspec_filter(args, opts.path_filter.as_ref());
+        let out = self.git(worktree_path, args)?;
+        Ok(Self::parse_name_status(&out))
+    }
+
+    /// Per-file line counts of the staged or unstaged changes in the worktree
+    pub fn diff_index_numstat_files(
+        &self,
+        worktree_path: &Path,
+        which: IndexDiff,
+        opts: StatusDiffOptions,
+    ) -> Result<Vec<FileNumstat>, GitCliError> {
+        let mut args = which.args();
+        args.extend(["-M".into(), "--numstat".into(), "-z".into()]);
+        args = Self::apply_pathspec_filter(args, opts.path_filter.as_ref());
+        let out = self.git(worktree_path, args)?;
+        Ok(Self::parse_numstat_files(&out))
+    }
+
+    /// Line counts vs a base commit without loading file contents (always includes
+    /// untracked). Binary files count as changed files with no lines.
+    pub fn diff_numstat(
+        &self,
+        worktree_path: &Path,
+        base_commit: &Commit,
+    ) -> Result<DiffNumstat, GitCliError> {
+        let (_tmp_dir, envs) = self.stage_into_temp_index(worktree_path)?;
+        let args: Vec<OsString> = vec![
+            "diff".into(),
+            "--cached".into(),
+            "-M".into(),
+            "--numstat".into(),
+            OsString::from(base_commit.to_string()),
+        ];
+        let out = self.git_with_env(worktree_path, args, &envs)?;
+        Ok(Self::parse_numstat(&out))
+    }
+
+    /// Line counts for the committed changes on `branch` since `base_commit`.
+    /// Unlike [`Self::diff_numstat`], no worktree is needed.
+    pub fn diff_numstat_branch(
+        &self,
+        repo_path: &Path,
+        base_commit: &Commit,
+        branch: &str,
+    ) -> Result<DiffNumstat, GitCliError> {
+        let range = format!("{base_commit}..{branch}");
+        let out = self.git(repo_path, ["diff", "-M", "--numstat", range.as_str()])?;
+        Ok(Self::parse_numstat(&out))
+    }
+
+    /// Per-file variant of [`Self::diff_numstat`]. Renamed files are reported under
+    /// their new path.
+    pub fn diff_numstat_files(
+        &self,
+        worktree_path: &Path,
+        base_commit: &Commit,
+        opts: StatusDiffOptions,
+    ) -> Result<Vec<FileNumstat>, GitCliError> {
+        let (_tmp_dir, envs) = self.stage_into_temp_index(worktree_path)?;
+        let mut args: Vec<OsString> = vec![
+            "diff".into(),
+            "--cached".into(),
+            "-M".into(),
+            "--numstat".into(),
+            "-z".into(),
+            OsString::from(base_commit.to_string()),
+        ];
+        args = Self::apply_pathspec_filter(args, opts.path_filter.as_ref());
+        let out = self.git_with_env(worktree_path, args, &envs)?;
+        Ok(Self::parse_numstat_files(&out))
+    }
+
+    /// Parse `--numstat -z` output. Renames have an empty path field followed by
+    /// the old and new paths as separate NUL-terminated fields.
+    fn parse_numstat_files(out: &str) -> Vec<FileNumstat> {
+        let mut files = Vec::new();
+        let mut fields = out.split('\0');
+        while let Some(record) = fields.next() {
+            let record = record.trim_start_matches('\n');
+            if record.is_empty() {
+                continue;
+            }
+            let mut parts = record.splitn(3, '\t');
+            let additions = parts.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+            let deletions = parts.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+            let path = match parts.next() {
+                Some(path) if !path.is_empty() => path.to_string(),
+                _ => {
+                    let _old = fields.next();
+                    match fields.next() {
+                        Some(new) => new.to_string(),
+                        None => break,
+                    }
+                }
+            };
+            files.push(FileNumstat {
+                path,
+                additions,
+                deletions,
+            });
+        }
+        files
+    }
+
+    fn parse_numstat(out: &str) -> DiffNumstat {
+        let mut stat = DiffNumstat::default();
+        for line in out.lines().filter(|l| !l.trim().is_empty()) {
+            let mut parts = line.splitn(3, '\t');
+            let added = parts.next().and_then(|n| n.parse::<usize>().ok());
+            let deleted = parts.next().and_then(|n| n.parse::<usize>().ok());
+            stat.files_changed += 1;
+            stat.additions += added.unwrap_or(0);
+            stat.deletions += deleted.unwrap_or(0);
+        }
+        stat
+    }
+
+    /// Build a temporary index from HEAD plus every changed and untracked file, so
+    /// `git diff --cached` sees the full working tree. Keep the returned dir alive
+    /// while using the env vars.
+    fn stage_into_temp_index(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<(tempfile::TempDir, Vec<(OsString, OsString)>), GitCliError> {
         // Create a temp index file
         let tmp_dir = tempfile::TempDir::new()
             .map_err(|e| GitCliError::CommandFailed(format!("temp dir create failed: {e}")))?;
@@ -212,19 +493,7 @@ impl GitCli {
             ];
             self.git_with_stdin(worktree_path, args, Some(&envs), &input)?;
         }
-        // git diff --cached
-        let mut args: Vec<OsString> = vec![
-            "-c".into(),
-            "core.quotepath=false".into(),
-            "diff".into(),
-            "--cached".into(),
-            "-M".into(),
-            "--name-status".into(),
-            OsString::from(base_commit.to_string()),
-        ];
-        args = Self::apply_pathspec_filter(args, opts.path_filter.as_ref());
-        let out = self.git_with_env(worktree_path, args, &envs)?;
-        Ok(Self::parse_name_status(&out))
+        Ok((tmp_dir, envs))
     }
 
     /// Return `git status --porcelain` parsed into a structured summary
@@ -238,7 +507,7 @@ impl GitCli {
             "-z",
             "--untracked-files=normal",
         ]);
-        let out = self.git_impl(worktree_path, args, None, None)?;
+        let out = self.git_impl(worktree_path, args, None, None, None, None)?;
         let mut entries = Vec::new();
         let mut uncommitted_tracked = 0usize;
         let mut untracked = 0usize;
@@ -296,24 +565,53 @@ impl GitCli {
         Ok(())
     }
 
+    /// Apply a patch to the index only (`git apply --cached`), leaving the working
+    /// tree untouched. With `check`, the patch is validated but nothing is applied.
+    pub fn apply_to_index(
+        &self,
+        worktree_path: &Path,
+        patch: &str,
+        reverse: bool,
+        check: bool,
+    ) -> Result<(), GitCliError> {
+        let mut args = vec!["apply", "--cached", "--whitespace=nowarn"];
+        if reverse {
+            args.push("--reverse");
+        }
+        if check {
+            args.push("--check");
+        }
+        args.push("-");
+        self.git_with_stdin(worktree_path, args, None, patch.as_bytes())?;
+        Ok(())
+    }
+
     pub fn list_worktrees(&self, repo_path: &Path) -> Result<Vec<WorktreeEntry>, GitCliError> {
         let out = self.git(repo_path, ["worktree", "list", "--porcelain"])?;
         let mut entries = Vec::new();
         let mut current_path: Option<String> = None;
         let mut current_head: Option<String> = None;
         let mut current_branch: Option<String> = None;
+        let mut locked = false;
+        let mut prunable = false;
 
         for line in out.lines() {
             let line = line.trim();
 
             if line.is_empty() {
                 // End of current worktree entry, save it if we have required data
-                if let (Some(path), Some(_head)) = (current_path.take(), current_head.take()) {
+                if let (Some(path), Some(head)) = (current_path.take(), current_head.take()) {
                     entries.push(WorktreeEntry {
                         path,
+                        head,
                         branch: current_branch.take(),
+                        locked,
+                        prunable,
                     });
                 }
+                current_branch = None;
+                locked = false;
+                prunable = false;
             } else if let Some(path) = line.strip_prefix("worktree ") {
                 current_path = Some(path.to_string());
             } else if let Some(head) = line.strip_prefix("HEAD ") {
@@ -323,14 +621,21 @@ impl GitCli {
                 current_branch = branch_ref
                     .strip_prefix("refs/heads/")
                     .map(|name| name.to_string());
+            } else if line == "locked" || line.starts_with("locked ") {
+                locked = true;
+            } else if line == "prunable" || line.starts_with("prunable ") {
+                prunable = true;
             }
         }
 
         // Handle the last entry if no trailing empty line
-        if let (Some(path), Some(_head)) = (current_path, current_head) {
+        if let (Some(path), Some(head)) = (current_path, current_head) {
             entries.push(WorktreeEntry {
                 path,
+                head,
                 branch: current_branch,
+                locked,
+                prunable,
             });
         }
 
@@ -338,23 +643,81 @@ impl GitCli {
     }
 
     /// Commit staged changes with the given message.
-    pub fn commit(&self, worktree_path: &Path, message: &str) -> Result<(), GitCliError> {
-        self.git(worktree_path, ["commit", "-m", message])?;
+    /// Commit the index. With `sign`, the commit is signed (`-S`) using the key
+    /// and `gpg.format` from the git config.
+    /// `hooks_path` overrides `core.hooksPath` for this commit only.
+    pub fn commit(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+        sign: bool,
+        hooks_path: Option<&Path>,
+    ) -> Result<(), GitCliError> {
+        let mut args = hooks_path_args(hooks_path);
+        args.push("commit".into());
+        if sign {
+            args.push("-S".into());
+        }
+        args.extend(["-m".into(), message.into()]);
+        self.git(worktree_path, args)?;
         Ok(())
     }
     /// Fetch a branch to the given remote using native git authentication.
+    /// With `cancel`, the fetch is killed when the token is cancelled and
+    /// [`GitCliError::Cancelled`] is returned.
     pub fn fetch_with_refspec(
         &self,
         repo_path: &Path,
         remote_url: &str,
         refspec: &str,
+        cancel: Option<&CancellationToken>,
+        progress: Option<FetchProgressSink<'_>>,
     ) -> Result<(), GitCliError> {
         let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
 
+        let mut args = vec![OsString::from("fetch")];
+        if progress.is_some() {
+            // git only reports progress to a terminal unless asked
+            args.push(OsString::from("--progress"));
+        }
+        args.extend([OsString::from(remote_url), OsString::from(refspec)]);
+
+        let state = Mutex::new(FetchProgress::default());
+        let on_stderr = |line: &str| {
+            let Some(progress) = progress else {
+                return;
+            };
+            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+            if apply_progress_line(&mut state, line) {
+                progress(*state);
+            }
+        };
+        let on_stderr: Option<&(dyn Fn(&str) + Sync)> = progress.map(|_| &on_stderr as _);
+
+        match self.git_impl(repo_path, args, Some(&envs), None, cancel, on_stderr) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Fetch `depth` more commits of history from `remote` into a shallow
+    /// repository, or all of it (`--unshallow`) when `depth` is `None`.
+    pub fn fetch_deepen(
+        &self,
+        repo_path: &Path,
+        remote: &str,
+        depth: Option<u32>,
+    ) -> Result<(), GitCliError> {
+        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        let depth_arg = match depth {
+            Some(depth) => format!("--deepen={depth}"),
+            None => "--unshallow".to_string(),
+        };
         let args = [
             OsString::from("fetch"),
-            OsString::from(remote_url),
-            OsString::from(refspec),
+            OsString::from(depth_arg),
+            OsString::from(remote),
         ];
 
         match self.git_with_env(repo_path, args, &envs) {
@@ -365,12 +728,14 @@ impl GitCli {
     }
 
     /// Push a branch to the given remote using native git authentication.
+    /// `hooks_path` overrides `core.hooksPath` for this push only.
     pub fn push(
         &self,
         repo_path: &Path,
         remote_url: &str,
         branch: &str,
         force: bool,
+        hooks_path: Option<&Path>,
     ) -> Result<(), GitCliError> {
         let refspec = if force {
             format!("+refs/heads/{branch}:refs/heads/{branch}")
@@ -379,10 +744,65 @@ impl GitCli {
         };
         let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
 
-        let args = [
+        let mut args = hooks_path_args(hooks_path);
+        args.extend([
             OsString::from("push"),
             OsString::from(remote_url),
             OsString::from(refspec),
+        ]);
+
+        match self.git_with_env(repo_path, args, &envs) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Force-push a branch only if the remote branch is still at `expected_sha`,
+    /// or still missing when `None`, so commits others pushed in the meantime are
+    /// not overwritten. A failed lease is reported as [`GitCliError::PushRejected`].
+    /// `hooks_path` overrides `core.hooksPath` for this push only.
+    pub fn push_with_lease(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        branch: &str,
+        expected_sha: Option<&str>,
+        hooks_path: Option<&Path>,
+    ) -> Result<(), GitCliError> {
+        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+
+        let mut args = hooks_path_args(hooks_path);
+        args.extend([
+            OsString::from("push"),
+            OsString::from(format!(
+                "--force-with-lease=refs/heads/{branch}:{}",
+                expected_sha.unwrap_or_default()
+            )),
+            OsString::from(remote_url),
+            OsString::from(format!("refs/heads/{branch}:refs/heads/{branch}")),
+        ]);
+
+        match self.git_with_env(repo_path, args, &envs) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Delete a branch on the given remote using native git authentication.
+    pub fn delete_remote_branch(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        branch: &str,
+    ) -> Result<(), GitCliError> {
+        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+
+        let args = [
+            OsString::from("push"),
+            OsString::from(remote_url),
+            OsString::from(format!(":refs/heads/{branch}")),
         ];
 
         match self.git_with_env(repo_path, args, &envs) {
@@ -415,6 +835,33 @@ impl GitCli {
         }
     }
 
+    /// The commit a branch points at on the remote, via `git ls-remote`; `None`
+    /// when the branch doesn't exist there
+    pub fn remote_branch_sha(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Option<String>, GitCliError> {
+        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+
+        let args = [
+            OsString::from("ls-remote"),
+            OsString::from("--heads"),
+            OsString::from(remote_url),
+            OsString::from(format!("refs/heads/{branch_name}")),
+        ];
+
+        match self.git_with_env(repo_path, args, &envs) {
+            Ok(output) => Ok(output
+                .lines()
+                .find_map(|line| line.split_whitespace().next())
+                .map(str::to_string)),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
     pub fn get_remote_url(
         &self,
         repo_path: &Path,
@@ -424,6 +871,54 @@ impl GitCli {
         Ok(output.trim().to_string())
     }
 
+    /// The branch a remote reports as its default (what its `HEAD` points at), via
+    /// `git ls-remote --symref`. This asks the remote, so it needs network access;
+    /// it never prompts for credentials and gives up after [`REMOTE_HEAD_TIMEOUT`]
+    /// with [`GitCliError::Cancelled`].
+    pub fn remote_head_branch(
+        &self,
+        repo_path: &Path,
+        remote: &str,
+    ) -> Result<Option<String>, GitCliError> {
+        let mut envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        // Keep ssh from asking for a passphrase or host key confirmation
+        if std::env::var_os("GIT_SSH_COMMAND").is_none() && std::env::var_os("GIT_SSH").is_none() {
+            envs.push((
+                OsString::from("GIT_SSH_COMMAND"),
+                OsString::from("ssh -o BatchMode=yes"),
+            ));
+        }
+
+        let cancel = CancellationToken::new();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        std::thread::spawn({
+            let cancel = cancel.clone();
+            move || {
+                if done_rx.recv_timeout(REMOTE_HEAD_TIMEOUT)
+                    == Err(std::sync::mpsc::RecvTimeoutError::Timeout)
+                {
+                    cancel.cancel();
+                }
+            }
+        });
+        let out = self.git_impl(
+            repo_path,
+            ["ls-remote", "--symref", remote, "HEAD"],
+            Some(&envs),
+            None,
+            Some(&cancel),
+            None,
+        );
+        drop(done_tx);
+        let output = String::from_utf8_lossy(&out?).to_string();
+        // ref: refs/heads/main	HEAD
+        Ok(output.lines().find_map(|line| {
+            line.strip_prefix("ref: refs/heads/")?
+                .strip_suffix("\tHEAD")
+                .map(str::to_string)
+        }))
+    }
+
     /// List all remotes with their URLs using `git remote -v`.
     /// Returns a Vec of (name, url) tuples, deduplicated (fetch/push show the same URL).
     pub fn list_remotes(&self, repo_path: &Path) -> Result<Vec<(String, String)>, GitCliError> {
@@ -516,6 +1011,30 @@ impl GitCli {
         Ok(out.trim().to_string())
     }
 
+    /// Run `git cherry -v <upstream> <head>` and return `(in_upstream, sha, subject)`
+    /// for each commit of `head` missing from `upstream`, oldest first.
+    pub fn cherry(
+        &self,
+        repo_path: &Path,
+        upstream: &str,
+        head: &str,
+    ) -> Result<Vec<(bool, String, String)>, GitCliError> {
+        let out = self.git(repo_path, ["cherry", "-v", upstream, head])?;
+        Ok(out
+            .lines()
+            .filter_map(|line| {
+                let (mark, rest) = line.split_once(' ')?;
+                let in_upstream = match mark {
+                    "-" => true,
+                    "+" => false,
+                    _ => return None,
+                };
+                let (sha, subject) = rest.split_once(' ').unwrap_or((rest, ""));
+                Some((in_upstream, sha.to_string(), subject.to_string()))
+            })
+            .collect())
+    }
+
     /// Perform `git rebase --onto <new_base> <old_base>` on <task_branch> in `worktree_path`.
     pub fn rebase_onto(
         &self,
@@ -600,6 +1119,115 @@ impl GitCli {
         self.git(worktree_path, ["rebase", "--quit"]).map(|_| ())
     }
 
+    /// Stash local changes, untracked files included, with `message`
+    pub fn stash_push(&self, worktree_path: &Path, message: &str) -> Result<(), GitCliError> {
+        self.git(
+            worktree_path,
+            ["stash", "push", "--include-untracked", "-m", message],
+        )
+        .map(|_| ())
+    }
+
+    /// Tree of the tracked files as they are in the worktree, without touching
+    /// the stash list. `None` when nothing tracked differs from HEAD.
+    pub fn stash_create_tree(&self, worktree_path: &Path) -> Result<Option<String>, GitCliError> {
+        let sha = self.git(worktree_path, ["stash", "create"])?;
+        let sha = sha.trim();
+        if sha.is_empty() {
+            return Ok(None);
+        }
+        let tree = self.git(worktree_path, ["rev-parse", &format!("{sha}^{{tree}}")])?;
+        Ok(Some(tree.trim().to_string()))
+    }
+
+    /// Untracked, non-ignored files, including those inside untracked
+    /// directories, with the blob id of each one's current content
+    pub fn untracked_blobs(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<HashMap<Vec<u8>, String>, GitCliError> {
+        let out = self.git_impl(
+            worktree_path,
+            Self::apply_default_excludes(["ls-files", "--others", "--exclude-standard", "-z"]),
+            None,
+            None,
+            None,
+            None,
+        )?;
+        let paths: Vec<Vec<u8>> = out
+            .split(|b| *b == 0)
+            .filter(|p| !p.is_empty())
+            .map(<[u8]>::to_vec)
+            .collect();
+        if paths.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut stdin = paths.join(&b'\n');
+        stdin.push(b'\n');
+        let ids = self.git_impl(
+            worktree_path,
+            ["hash-object", "--stdin-paths"],
+            None,
+            Some(&stdin),
+            None,
+            None,
+        )?;
+        let ids = String::from_utf8_lossy(&ids);
+        Ok(paths
+            .into_iter()
+            .zip(ids.lines().map(str::to_string))
+            .collect())
+    }
+
+    /// Apply the stash commit `sha`, wherever it is in the stash list
+    pub fn stash_apply_commit(&self, worktree_path: &Path, sha: &str) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["stash", "apply", sha]).map(|_| ())
+    }
+
+    /// Apply `stash@{index}` to the worktree, keeping it in the stash list
+    pub fn stash_apply(&self, worktree_path: &Path, index: usize) -> Result<(), GitCliError> {
+        self.git(
+            worktree_path,
+            ["stash", "apply", &format!("stash@{{{index}}}")],
+        )
+        .map(|_| ())
+    }
+
+    /// Delete `stash@{index}`; later stashes move down by one
+    pub fn stash_drop(&self, worktree_path: &Path, index: usize) -> Result<(), GitCliError> {
+        self.git(
+            worktree_path,
+            ["stash", "drop", &format!("stash@{{{index}}}")],
+        )
+        .map(|_| ())
+    }
+
+    /// Run `git verify-commit --raw` (or `verify-tag`) and return what it
+    /// reports, whether or not the signature verified. The report goes to
+    /// stderr even on success, so the exit status is not checked here.
+    pub fn verify_signature(
+        &self,
+        repo_path: &Path,
+        object: &str,
+        is_tag: bool,
+    ) -> Result<String, GitCliError> {
+        let subcommand = if is_tag {
+            "verify-tag"
+        } else {
+            "verify-commit"
+        };
+        // ssh-keygen messages are matched as text, so keep them untranslated
+        let envs = [(OsString::from("LC_ALL"), OsString::from("C"))];
+        let out = self
+            .command(repo_path, Some(&envs))?
+            .args([subcommand, "--raw", object])
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| GitCliError::CommandFailed(e.to_string()))?;
+        Ok(String::from_utf8_lossy(&out.stderr).into_owned())
+    }
+
     /// Return true if there are staged changes (index differs from HEAD)
     pub fn has_staged_changes(&self, repo_path: &Path) -> Result<bool, GitCliError> {
         // `git diff --cached --quiet` returns exit code 1 if there are differences
@@ -658,6 +1286,13 @@ impl GitCli {
         self.git(worktree_path, ["merge", "--abort"]).map(|_| ())
     }
 
+    /// Apply a single commit on top of HEAD. On conflict the cherry-pick is
+    /// left in progress.
+    pub fn cherry_pick(&self, worktree_path: &Path, commit_sha: &str) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["cherry-pick", commit_sha])
+            .map(|_| ())
+    }
+
     pub fn abort_cherry_pick(&self, worktree_path: &Path) -> Result<(), GitCliError> {
         if !self.is_cherry_pick_in_progress(worktree_path)? {
             return Ok(());
@@ -701,6 +1336,7 @@ impl GitCli {
             || lower.contains("failed to push some refs")
             || lower.contains("fetch first")
             || lower.contains("updates were rejected because the tip")
+            || lower.contains("stale info")
         {
             GitCliError::PushRejected(msg)
         } else {
@@ -722,6 +1358,24 @@ impl GitCli {
         }
     }
 
+    /// `git -C <repo_path>` with `envs` set, ready for arguments
+    fn command(
+        &self,
+        repo_path: &Path,
+        envs: Option<&[(OsString, OsString)]>,
+    ) -> Result<Command, GitCliError> {
+        self.ensure_available()?;
+        let git = resolve_executable_path_blocking("git").ok_or(GitCliError::NotAvailable)?;
+        let mut cmd = Command::new(&git);
+        cmd.arg("-C").arg(repo_path);
+        if let Some(envs) = envs {
+            for (k, v) in envs {
+                cmd.env(k, v);
+            }
+        }
+        Ok(cmd)
+    }
+
     /// Run `git -C <repo_path> <args...>` and return stdout bytes on success.
     /// Prefer adding specific helpers (e.g. `get_worktree_status`, `diff_status`)
     /// instead of calling this directly, so all parsing and command choices are
@@ -741,25 +1395,15 @@ impl GitCli {
         args: I,
         envs: Option<&[(OsString, OsString)]>,
         stdin: Option<&[u8]>,
+        cancel: Option<&CancellationToken>,
+        on_stderr: Option<&(dyn Fn(&str) + Sync)>,
     ) -> Result<Vec<u8>, GitCliError>
     where
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
     {
-        self.ensure_available()?;
-        let git = resolve_executable_path_blocking("git").ok_or(GitCliError::NotAvailable)?;
-        let mut cmd = Command::new(&git);
-        cmd.arg("-C").arg(repo_path);
-
-        if let Some(envs) = envs {
-            for (k, v) in envs {
-                cmd.env(k, v);
-            }
-        }
-
-        for a in args {
-            cmd.arg(a);
-        }
+        let mut cmd = self.command(repo_path, envs)?;
+        cmd.args(args);
 
         if stdin.is_some() {
             cmd.stdin(Stdio::piped());
@@ -789,9 +1433,13 @@ impl GitCli {
             None
         };
 
-        let out = child
-            .wait_with_output()
-            .map_err(|e| GitCliError::CommandFailed(e.to_string()))?;
+        let out = if cancel.is_some() || on_stderr.is_some() {
+            Self::wait_watched(child, cancel, on_stderr)?
+        } else {
+            child
+                .wait_with_output()
+                .map_err(|e| GitCliError::CommandFailed(e.to_string()))?
+        };
 
         if !out.status.success() {
             let stderr = String::from_utf8_lossy(&out.stderr).trim().to_string();
@@ -812,12 +1460,86 @@ impl GitCli {
         Ok(out.stdout)
     }
 
+    /// Like `wait_with_output`, but kills the child as soon as `cancel` fires and
+    /// passes each stderr line to `on_stderr` as it arrives. Progress output
+    /// redraws lines with `\r`, so that ends a line too.
+    fn wait_watched(
+        mut child: Child,
+        cancel: Option<&CancellationToken>,
+        on_stderr: Option<&(dyn Fn(&str) + Sync)>,
+    ) -> Result<Output, GitCliError> {
+        let stdout_pipe = child.stdout.take();
+        let stderr_pipe = child.stderr.take();
+
+        std::thread::scope(|scope| {
+            // Drain the pipes while waiting so a chatty child can't block on a full pipe
+            let stdout = scope.spawn(move || {
+                let mut buf = Vec::new();
+                if let Some(mut pipe) = stdout_pipe {
+                    let _ = pipe.read_to_end(&mut buf);
+                }
+                buf
+            });
+            let stderr = scope.spawn(move || {
+                let mut buf = Vec::new();
+                let Some(mut pipe) = stderr_pipe else {
+                    return buf;
+                };
+                let mut chunk = [0u8; 4096];
+                let mut line_start = 0;
+                loop {
+                    let read = match pipe.read(&mut chunk) {
+                        Ok(0) => break,
+                        Ok(read) => read,
+                        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                        Err(_) => break,
+                    };
+                    buf.extend_from_slice(&chunk[..read]);
+                    let Some(on_stderr) = on_stderr else {
+                        continue;
+                    };
+                    while let Some(len) = buf[line_start..]
+                        .iter()
+                        .position(|b| matches!(b, b'\r' | b'\n'))
+                    {
+                        // `\r\n` would otherwise report an empty line
+                        if len > 0 {
+                            on_stderr(&String::from_utf8_lossy(&buf[line_start..line_start + len]));
+                        }
+                        line_start += len + 1;
+                    }
+                }
+                buf
+            });
+
+            let status = loop {
+                if cancel.is_some_and(CancellationToken::is_cancelled) {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(GitCliError::Cancelled);
+                }
+                match child
+                    .try_wait()
+                    .map_err(|e| GitCliError::CommandFailed(e.to_string()))?
+                {
+                    Some(status) => break status,
+                    None => std::thread::sleep(CANCEL_POLL_INTERVAL),
+                }
+            };
+            Ok(Output {
+                status,
+                stdout: stdout.join().unwrap_or_default(),
+                stderr: stderr.join().unwrap_or_default(),
+            })
+        })
+    }
+
     pub fn git<I, S>(&self, repo_path: &Path, args: I) -> Result<String, GitCliError>
     where
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
     {
-        let out = self.git_impl(repo_path, args, None, None)?;
+        let out = self.git_impl(repo_path, args, None, None, None, None)?;
         Ok(String::from_utf8_lossy(&out).to_string())
     }
 
@@ -831,7 +1553,7 @@ impl GitCli {
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
     {
-        let out = self.git_impl(repo_path, args, Some(envs), None)?;
+        let out = self.git_impl(repo_path, args, Some(envs), None, None, None)?;
         Ok(String::from_utf8_lossy(&out).to_string())
     }
 
@@ -846,7 +1568,7 @@ impl GitCli {
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
     {
-        let out = self.git_impl(repo_path, args, envs, Some(stdin))?;
+        let out = self.git_impl(repo_path, args, envs, Some(stdin), None, None)?;
         Ok(String::from_utf8_lossy(&out).to_string())
     }
 
@@ -918,3 +1640,48 @@ pub struct WorktreeStatus {
     pub untracked: usize,
     pub entries: Vec<StatusEntry>,
 }
+
+/// Leading `-c core.hooksPath=<dir>` arguments, so a single invocation runs a
+/// worktree's own hooks without touching the repository's config.
+fn hooks_path_args(hooks_path: Option<&Path>) -> Vec<OsString> {
+    let Some(dir) = hooks_path else {
+        return Vec::new();
+    };
+    let mut value = OsString::from("core.hooksPath=");
+    value.push(dir);
+    vec![OsString::from("-c"), value]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wait_watched_splits_stderr_on_carriage_returns() {
+        let child = Command::new("sh")
+            .args([
+                "-c",
+                "printf 'Receiving objects:  50%% (1/2)\\rReceiving objects: 100%% (2/2), done.\\r\\nResolving deltas: 100%% (1/1), done.\\n' >&2; echo out",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let lines = Mutex::new(Vec::new());
+        let on_stderr = |line: &str| lines.lock().unwrap().push(line.to_string());
+        let out = GitCli::wait_watched(child, None, Some(&on_stderr)).unwrap();
+
+        assert_eq!(
+            lines.into_inner().unwrap(),
+            [
+                "Receiving objects:  50% (1/2)",
+                "Receiving objects: 100% (2/2), done.",
+                "Resolving deltas: 100% (1/1), done.",
+            ]
+        );
+        assert_eq!(out.stdout, b"out\n");
+        // The raw stderr is still kept for error reporting
+        assert!(String::from_utf8_lossy(&out.stderr).starts_with("Receiving objects:  50%"));
+    }
+}
@@ -0,0 +1,149 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+
+/// Subject pattern for <https://www.conventionalcommits.org>, offered as a preset
+pub const CONVENTIONAL_COMMIT_PATTERN: &str =
+    r"^(build|chore|ci|docs|feat|fix|perf|refactor|revert|style|test)(\([^()]+\))?!?: \S";
+
+/// House style for commit messages. The default enforces nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct CommitMessageRules {
+    /// Maximum length of the subject line, in characters
+    #[serde(default)]
+    pub max_subject_length: Option<u32>,
+    /// Require a blank line between the subject and the body
+    #[serde(default)]
+    pub require_blank_line_before_body: bool,
+    /// Regex the subject line must match, e.g. [`CONVENTIONAL_COMMIT_PATTERN`]
+    #[serde(default)]
+    pub subject_pattern: Option<String>,
+}
+
+impl CommitMessageRules {
+    pub fn is_enforced(&self) -> bool {
+        self.max_subject_length.is_some()
+            || self.require_blank_line_before_body
+            || self.subject_pattern.is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum CommitMessageIssue {
+    #[error("subject line is empty")]
+    EmptySubject,
+    #[error("subject line is {length} characters, at most {max} allowed")]
+    SubjectTooLong { length: u32, max: u32 },
+    #[error("subject line must be followed by a blank line")]
+    MissingBlankLineBeforeBody,
+    #[error("subject line does not match the required format `{pattern}`")]
+    SubjectPatternMismatch { pattern: String },
+    #[error("subject pattern `{pattern}` is not a valid regex: {error}")]
+    InvalidSubjectPattern { pattern: String, error: String },
+}
+
+/// Check `message` against `rules`, returning every issue found.
+pub fn validate_commit_message(
+    message: &str,
+    rules: &CommitMessageRules,
+) -> Result<(), Vec<CommitMessageIssue>> {
+    let mut issues = Vec::new();
+    let mut lines = message.trim_end().lines();
+    let subject = lines.next().unwrap_or("").trim_end();
+
+    if subject.trim().is_empty() {
+        issues.push(CommitMessageIssue::EmptySubject);
+    }
+
+    if let Some(max) = rules.max_subject_length {
+        let length = subject.chars().count() as u32;
+        if length > max {
+            issues.push(CommitMessageIssue::SubjectTooLong { length, max });
+        }
+    }
+
+    if rules.require_blank_line_before_body
+        && lines.next().is_some_and(|line| !line.trim().is_empty())
+    {
+        issues.push(CommitMessageIssue::MissingBlankLineBeforeBody);
+    }
+
+    if let Some(pattern) = &rules.subject_pattern {
+        match Regex::new(pattern) {
+            Ok(re) if !re.is_match(subject) => {
+                issues.push(CommitMessageIssue::SubjectPatternMismatch {
+                    pattern: pattern.clone(),
+                });
+            }
+            Ok(_) => {}
+            Err(e) => issues.push(CommitMessageIssue::InvalidSubjectPattern {
+                pattern: pattern.clone(),
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_rules_accept_any_non_empty_message() {
+        let rules = CommitMessageRules::default();
+        assert!(!rules.is_enforced());
+        assert!(validate_commit_message("wip", &rules).is_ok());
+        assert!(validate_commit_message(&"x".repeat(500), &rules).is_ok());
+        assert_eq!(
+            validate_commit_message("  \n", &rules),
+            Err(vec![CommitMessageIssue::EmptySubject])
+        );
+    }
+
+    #[test]
+    fn reports_every_broken_rule() {
+        let rules = CommitMessageRules {
+            max_subject_length: Some(20),
+            require_blank_line_before_body: true,
+            subject_pattern: Some(CONVENTIONAL_COMMIT_PATTERN.to_string()),
+        };
+        assert!(validate_commit_message("fix: typo\n\nDetails\n", &rules).is_ok());
+        assert!(validate_commit_message("feat(ui)!: x", &rules).is_ok());
+
+        let issues = validate_commit_message("Fixed the login page bug\nmore", &rules).unwrap_err();
+        assert_eq!(
+            issues,
+            vec![
+                CommitMessageIssue::SubjectTooLong {
+                    length: 24,
+                    max: 20
+                },
+                CommitMessageIssue::MissingBlankLineBeforeBody,
+                CommitMessageIssue::SubjectPatternMismatch {
+                    pattern: CONVENTIONAL_COMMIT_PATTERN.to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_pattern_is_an_issue() {
+        let rules = CommitMessageRules {
+            subject_pattern: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            validate_commit_message("fix: x", &rules).unwrap_err()[..],
+            [CommitMessageIssue::InvalidSubjectPattern { .. }]
+        ));
+    }
+}
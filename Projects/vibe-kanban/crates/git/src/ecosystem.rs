@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::path::ALWAYS_SKIP_DIRS;
+
+/// Build directories that never hold a project's own manifests
+const SKIP_DIRS: &[&str] = &["target", "dist", "build", "vendor", "venv", ".venv"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum EcosystemKind {
+    Rust,
+    Node,
+    Go,
+    Python,
+    Ruby,
+    Java,
+    DotNet,
+    Php,
+    Elixir,
+    Swift,
+}
+
+/// Ordered so that sorting descending puts the most certain matches first
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum EcosystemConfidence {
+    /// Only found in a subdirectory, e.g. one package of a monorepo
+    Low,
+    /// A secondary marker such as `requirements.txt` at the root
+    Medium,
+    /// The ecosystem's primary manifest is at the repository root
+    High,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct Ecosystem {
+    pub kind: EcosystemKind,
+    pub confidence: EcosystemConfidence,
+    /// Repository-relative paths of the files that matched
+    pub markers: Vec<String>,
+}
+
+/// `(file name, ecosystem, is primary manifest)`. Names starting with `*` match
+/// by extension.
+const MARKERS: &[(&str, EcosystemKind, bool)] = &[
+    ("Cargo.toml", EcosystemKind::Rust, true),
+    ("package.json", EcosystemKind::Node, true),
+    ("go.mod", EcosystemKind::Go, true),
+    ("pyproject.toml", EcosystemKind::Python, true),
+    ("setup.py", EcosystemKind::Python, false),
+    ("requirements.txt", EcosystemKind::Python, false),
+    ("Pipfile", EcosystemKind::Python, false),
+    ("Gemfile", EcosystemKind::Ruby, true),
+    ("pom.xml", EcosystemKind::Java, true),
+    ("build.gradle", EcosystemKind::Java, true),
+    ("build.gradle.kts", EcosystemKind::Java, true),
+    ("*.sln", EcosystemKind::DotNet, true),
+    ("*.csproj", EcosystemKind::DotNet, false),
+    ("composer.json", EcosystemKind::Php, true),
+    ("mix.exs", EcosystemKind::Elixir, true),
+    ("Package.swift", EcosystemKind::Swift, true),
+];
+
+fn marker_for(file_name: &str) -> Option<(EcosystemKind, bool)> {
+    MARKERS.iter().find_map(|(marker, kind, primary)| {
+        let matches = match marker.strip_prefix('*') {
+            Some(ext) => file_name.ends_with(ext),
+            None => file_name == *marker,
+        };
+        matches.then_some((*kind, *primary))
+    })
+}
+
+/// Detect ecosystems from repository-relative file paths. Only the root and
+/// its immediate subdirectories are considered.
+pub fn detect_ecosystems<'a>(paths: impl IntoIterator<Item = &'a str>) -> Vec<Ecosystem> {
+    let mut found: Vec<Ecosystem> = Vec::new();
+
+    for path in paths {
+        let mut parts = path.split('/');
+        let (dir, file_name) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(file), None, _) => (None, file),
+            (Some(dir), Some(file), None) => (Some(dir), file),
+            _ => continue,
+        };
+        if let Some(dir) = dir
+            && (ALWAYS_SKIP_DIRS.contains(&dir) || SKIP_DIRS.contains(&dir))
+        {
+            continue;
+        }
+        let Some((kind, primary)) = marker_for(file_name) else {
+            continue;
+        };
+
+        let confidence = match (dir, primary) {
+            (None, true) => EcosystemConfidence::High,
+            (None, false) => EcosystemConfidence::Medium,
+            (Some(_), _) => EcosystemConfidence::Low,
+        };
+        match found.iter_mut().find(|e| e.kind == kind) {
+            Some(existing) => {
+                existing.confidence = existing.confidence.max(confidence);
+                existing.markers.push(path.to_string());
+            }
+            None => found.push(Ecosystem {
+                kind,
+                confidence,
+                markers: vec![path.to_string()],
+            }),
+        }
+    }
+
+    found.sort_by(|a, b| {
+        b.confidence
+            .cmp(&a.confidence)
+            .then_with(|| b.markers.len().cmp(&a.markers.len()))
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_manifest_outranks_nested_ones() {
+        let detected = detect_ecosystems([
+            "Cargo.toml",
+            "README.md",
+            "frontend/package.json",
+            "scripts/requirements.txt",
+            "src/main.rs",
+        ]);
+        assert_eq!(detected[0].kind, EcosystemKind::Rust);
+        assert_eq!(detected[0].confidence, EcosystemConfidence::High);
+        let node = detected
+            .iter()
+            .find(|e| e.kind == EcosystemKind::Node)
+            .unwrap();
+        assert_eq!(node.confidence, EcosystemConfidence::Low);
+        assert_eq!(node.markers, vec!["frontend/package.json"]);
+    }
+
+    #[test]
+    fn skips_vendored_and_deep_paths() {
+        let detected = detect_ecosystems([
+            "node_modules/left-pad/package.json",
+            "target/package/Cargo.toml",
+            "a/b/go.mod",
+        ]);
+        assert!(detected.is_empty());
+    }
+
+    #[test]
+    fn secondary_markers_have_medium_confidence() {
+        let detected = detect_ecosystems(["requirements.txt", "App.sln"]);
+        assert_eq!(detected[0].kind, EcosystemKind::DotNet);
+        assert_eq!(detected[1].kind, EcosystemKind::Python);
+        assert_eq!(detected[1].confidence, EcosystemConfidence::Medium);
+    }
+}
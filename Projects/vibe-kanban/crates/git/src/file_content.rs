@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Max inline diff size for UI (in bytes). Files larger than this have their
+/// contents omitted from the diff stream to avoid UI crashes.
+pub const DEFAULT_MAX_INLINE_DIFF_BYTES: usize = 2 * 1024 * 1024; // ~2MB
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum BinaryDetection {
+    /// Any null byte marks the file as binary
+    #[default]
+    NullByte,
+    /// Only files that cannot be decoded as text are treated as binary, so
+    /// text containing null bytes is still shown
+    Undecodable,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum FallbackEncoding {
+    /// ISO-8859-1; every byte maps to the code point of the same value
+    Latin1,
+}
+
+/// How file contents are turned into text for diffs
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(default)]
+pub struct FileContentOptions {
+    /// Files larger than this, in bytes, have their contents omitted
+    pub max_inline_bytes: usize,
+    pub binary_detection: BinaryDetection,
+    /// Encoding to decode with when contents are not valid UTF-8. Such files
+    /// are treated as binary when unset.
+    pub fallback_encoding: Option<FallbackEncoding>,
+}
+
+impl FileContentOptions {
+    /// Whether `bytes` should be treated as binary before decoding is attempted
+    pub fn looks_binary(&self, bytes: &[u8]) -> bool {
+        match self.binary_detection {
+            BinaryDetection::NullByte => bytes.contains(&0),
+            BinaryDetection::Undecodable => false,
+        }
+    }
+
+    /// Decode as UTF-8, then with the fallback encoding if one is set
+    pub fn decode(&self, bytes: Vec<u8>) -> Option<String> {
+        match String::from_utf8(bytes) {
+            Ok(content) => Some(content),
+            Err(e) => match self.fallback_encoding {
+                Some(FallbackEncoding::Latin1) => {
+                    Some(e.into_bytes().into_iter().map(char::from).collect())
+                }
+                None => None,
+            },
+        }
+    }
+}
+
+impl Default for FileContentOptions {
+    fn default() -> Self {
+        Self {
+            max_inline_bytes: DEFAULT_MAX_INLINE_DIFF_BYTES,
+            binary_detection: BinaryDetection::NullByte,
+            fallback_encoding: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_reject_null_bytes_and_invalid_utf8() {
+        let options = FileContentOptions::default();
+        assert!(options.looks_binary(b"a\0b"));
+        assert!(!options.looks_binary(b"plain text"));
+        assert_eq!(options.decode(vec![0x63, 0x61, 0x66, 0xe9]), None);
+        assert_eq!(
+            options.decode(b"caf\xc3\xa9".to_vec()).as_deref(),
+            Some("café")
+        );
+    }
+
+    #[test]
+    fn latin1_fallback_decodes_invalid_utf8() {
+        let options = FileContentOptions {
+            binary_detection: BinaryDetection::Undecodable,
+            fallback_encoding: Some(FallbackEncoding::Latin1),
+            ..Default::default()
+        };
+        assert!(!options.looks_binary(b"a\0b"));
+        assert_eq!(
+            options.decode(vec![0x63, 0x61, 0x66, 0xe9]).as_deref(),
+            Some("café")
+        );
+    }
+}
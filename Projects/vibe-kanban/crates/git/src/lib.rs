@@ -1,22 +1,53 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+};
 
 use chrono::{DateTime, Utc};
+use futures::{Stream, stream};
 use git2::{
     BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, Reference, Remote,
     Repository, Sort,
 };
+use lru::LruCache;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
 use ts_rs::TS;
-use utils::diff::{Diff, DiffChangeKind, FileDiffDetails, compute_line_change_counts};
+use utils::diff::{
+    Diff, DiffChangeKind, DiffResult, DiffSummary, FileChangeSize, FileDiffDetails,
+    SubmoduleUpdate, compute_line_change_counts,
+};
 
 mod cli;
+mod commit_message;
+mod ecosystem;
+mod file_content;
+mod progress;
+mod signature;
 mod validation;
 
-use cli::{ChangeType, StatusDiffEntry, StatusDiffOptions};
-pub use cli::{GitCli, GitCliError, StatusEntry, WorktreeStatus};
+use cli::{ChangeType, IndexDiff, StatusDiffEntry, StatusDiffOptions};
+pub use cli::{
+    DiffNumstat, FileNumstat, GitCli, GitCliError, StatusEntry, WorktreeEntry, WorktreeStatus,
+};
+pub use commit_message::{
+    CONVENTIONAL_COMMIT_PATTERN, CommitMessageIssue, CommitMessageRules, validate_commit_message,
+};
+pub use ecosystem::{Ecosystem, EcosystemConfidence, EcosystemKind, detect_ecosystems};
+pub use file_content::{
+    BinaryDetection, DEFAULT_MAX_INLINE_DIFF_BYTES, FallbackEncoding, FileContentOptions,
+};
+pub use progress::{FetchProgress, FetchProgressSink};
+pub use signature::SignatureStatus;
+use signature::parse_verify_output;
 pub use utils::path::ALWAYS_SKIP_DIRS;
-pub use validation::is_valid_branch_prefix;
+pub use validation::{branch_has_prefix, is_valid_branch_prefix, is_valid_remote_name};
 
 /// Statistics for a single file based on git history
 #[derive(Clone, Debug)]
@@ -52,14 +83,105 @@ pub enum GitServiceError {
     WorktreeDirty(String, String),
     #[error("Rebase in progress; resolve or abort it before retrying")]
     RebaseInProgress,
+    #[error(
+        "Shallow clone is missing the history needed for this: {0}. Run `git fetch --unshallow` in the repository"
+    )]
+    ShallowRepository(String),
+    #[error("Commit message rejected: {}", format_commit_message_issues(.0))]
+    InvalidCommitMessage(Vec<CommitMessageIssue>),
+    #[error("Patch does not apply to the index: {message}")]
+    PatchRejected { hunk: String, message: String },
+    #[error("A different {0} hook is already installed; use force to replace it")]
+    HookExists(HookName),
+    #[error("No stash at index {0}")]
+    StashNotFound(usize),
+    #[error(
+        "Restoring stashed changes from {stash} caused merge conflicts. The stash was kept; resolve the conflicts, then drop it"
+    )]
+    StashPopConflict {
+        stash: StashRef,
+        conflicted_files: Vec<String>,
+    },
+    #[error("Branch {branch} is already checked out in worktree {worktree_path}")]
+    BranchCheckedOutElsewhere {
+        branch: String,
+        worktree_path: String,
+    },
+    #[error("Branch {0} is not fully merged; use force to delete it anyway")]
+    BranchNotMerged(String),
+    #[error("Failed to sign commit: {0}")]
+    CommitSigningFailed(String),
+    #[error("Git operation was cancelled")]
+    Cancelled,
+}
+
+fn format_commit_message_issues(issues: &[CommitMessageIssue]) -> String {
+    issues
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Whether `git commit -S` stderr says the signing step failed, e.g.
+/// `error: gpg failed to sign the data` or, for SSH, a missing `user.signingkey`
+fn is_signing_failure(stderr: &str) -> bool {
+    let stderr = stderr.to_ascii_lowercase();
+    ["failed to sign", "signingkey", "defaultkeycommand"]
+        .iter()
+        .any(|needle| stderr.contains(needle))
 }
 /// Service for managing Git operations in task execution workflows
 #[derive(Clone)]
-pub struct GitService {}
+pub struct GitService {
+    /// Author of commits made in repositories with no user.name/user.email
+    identity: Option<CommitIdentity>,
+    /// Merge bases found by [`GitService::get_base_commit`], shared by clones
+    merge_base_cache: Arc<Mutex<LruCache<MergeBaseKey, git2::Oid>>>,
+    /// Held while resolving and using a `stash@{n}` index, one per repository
+    /// keyed by its common git dir. Every worktree of a repository shares one
+    /// stash list, so another task's stash would shift it.
+    stash_locks: Arc<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>>,
+    /// Whether [`GitService::commit`] signs; shared by clones so a config
+    /// change reaches every holder
+    sign_commits: Arc<AtomicBool>,
+    /// Size limit, binary detection and decoding for file contents in diffs;
+    /// shared by clones like `sign_commits`
+    file_content: Arc<RwLock<FileContentOptions>>,
+    /// House style [`GitService::commit`] holds messages to; `None` when no
+    /// rule is enforced
+    commit_message_rules: Arc<RwLock<Option<CommitMessageRules>>>,
+    /// Remote names to use as the default remote, in order of preference
+    preferred_remotes: Arc<RwLock<Vec<String>>>,
+    /// Whether a rebase in a shallow clone may fetch the history it is missing
+    deepen_shallow_clones: Arc<AtomicBool>,
+    /// Whether [`GitService::add_worktree`] checks out submodules
+    init_submodules: Arc<AtomicBool>,
+}
+
+/// Repository (common git dir, so worktrees share entries) and the two branch
+/// tips. Keying on the tips means a moved branch simply misses the cache.
+type MergeBaseKey = (PathBuf, git2::Oid, git2::Oid);
 
-// Max inline diff size for UI (in bytes). Files larger than this will have
-// their contents omitted from the diff stream to avoid UI crashes.
-const MAX_INLINE_DIFF_BYTES: usize = 2 * 1024 * 1024; // ~2MB
+const MERGE_BASE_CACHE_CAPACITY: usize = 256;
+
+/// Most threads [`GitService::collect_recent_file_stats`] diffs commits on
+const FILE_STATS_MAX_THREADS: usize = 8;
+
+/// Name and email recorded on commits
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct CommitIdentity {
+    pub name: String,
+    pub email: String,
+}
+
+const DEFAULT_IDENTITY_NAME: &str = "Vibe Kanban";
+const DEFAULT_IDENTITY_EMAIL: &str = "noreply@vibekanban.com";
+
+/// How much extra history to fetch, in turn, when a rebase in a shallow clone
+/// needs commits beyond the shallow boundary. The whole history is fetched if
+/// these are not enough.
+const SHALLOW_DEEPEN_STEPS: [u32; 3] = [100, 1_000, 10_000];
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -71,6 +193,60 @@ pub enum ConflictOp {
     Revert,
 }
 
+/// An unmerged file with the contents of its index stages. `base` is stage 1,
+/// `ours` stage 2 and `theirs` stage 3; a side is `None` when the file does not
+/// exist there (e.g. deleted on one side) or its content is omitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct FileConflict {
+    pub path: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    /// True when a side is binary or larger than the inline size limit
+    pub content_omitted: bool,
+}
+
+/// Last change to a line of a file, as found by [`GitService::blame_file`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct BlameLine {
+    /// 1-based line number in the blamed version of the file
+    pub line: usize,
+    pub commit: String,
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    pub authored_at: DateTime<Utc>,
+}
+
+/// Client-side hooks that can be installed with [`GitService::install_hooks`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+#[ts(rename_all = "kebab-case")]
+pub enum HookName {
+    PreCommit,
+    PrepareCommitMsg,
+    CommitMsg,
+    PostCommit,
+    PrePush,
+}
+
+impl HookName {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            HookName::PreCommit => "pre-commit",
+            HookName::PrepareCommitMsg => "prepare-commit-msg",
+            HookName::CommitMsg => "commit-msg",
+            HookName::PostCommit => "post-commit",
+            HookName::PrePush => "pre-push",
+        }
+    }
+}
+
+impl std::fmt::Display for HookName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.file_name())
+    }
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct GitBranch {
     pub name: String,
@@ -80,16 +256,126 @@ pub struct GitBranch {
     pub last_commit_date: DateTime<Utc>,
 }
 
+/// A commit of a branch that is not in its upstream by ancestry, as reported by
+/// `git cherry`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct CherryStatusEntry {
+    pub sha: String,
+    pub subject: String,
+    /// An equivalent change (same patch id) is already upstream, e.g. because it
+    /// was cherry-picked; rebasing would drop it
+    pub in_upstream: bool,
+}
+
+/// An entry of `git stash list`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct StashEntry {
+    /// Position in the stash list, as in `stash@{index}`; 0 is the newest
+    pub index: usize,
+    pub sha: String,
+    pub message: String,
+    /// Branch checked out when the stash was made; `None` for a detached HEAD
+    pub branch: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A stash made by [`GitService::stash_worktree`]. Identified by its commit,
+/// which unlike `stash@{n}` stays valid as other stashes come and go.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct StashRef {
+    pub sha: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for StashRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "stash {}", &self.sha[..self.sha.len().min(12)])
+    }
+}
+
+/// Directory inside a worktree's git dir that [`GitService::install_hooks`] writes to
+const WORKTREE_HOOKS_DIR: &str = "vk-hooks";
+
+/// Hook that runs `original` in place, so `$0` and paths relative to it are
+/// unchanged
+fn chain_hook_script(original: &Path) -> String {
+    let quoted = original.to_string_lossy().replace('\'', r"'\''");
+    format!("#!/bin/sh\nexec '{quoted}' \"$@\"\n")
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(meta) = std::fs::metadata(path) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        meta.is_file() && meta.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        meta.is_file()
+    }
+}
+
+/// Write an executable hook script
+fn write_hook(path: &Path, script: &str) -> std::io::Result<()> {
+    std::fs::write(path, script)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+/// Message of stashes made before rebasing with `auto_stash`
+const AUTO_STASH_MESSAGE: &str = "vibe-kanban: auto-stash before rebase";
+
+/// Branch named in a default stash message, e.g. `WIP on main: abc123 subject`
+/// or `On main: message`
+fn stash_branch(message: &str) -> Option<String> {
+    let rest = message
+        .strip_prefix("WIP on ")
+        .or_else(|| message.strip_prefix("On "))?;
+    let (branch, _) = rest.split_once(':')?;
+    (branch != "(no branch)").then(|| branch.to_string())
+}
+
+/// A submodule entry from `.gitmodules`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct SubmoduleInfo {
+    pub name: String,
+    /// Path relative to the repository root
+    pub path: String,
+    pub url: Option<String>,
+    pub branch: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, TS)]
 pub struct GitRemote {
     pub name: String,
     pub url: String,
 }
 
+/// Git state of a worktree at one point in time, for telling whether anything
+/// changed between two snapshots
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorktreeSnapshot {
+    pub head: Option<String>,
+    /// Tree of the uncommitted tracked changes; `None` when there are none
+    pub tracked_tree: Option<String>,
+    /// Untracked file paths with the blob id of their content
+    pub untracked: HashMap<Vec<u8>, String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct HeadInfo {
+    /// Checked-out branch, or `"HEAD"` when detached
     pub branch: String,
     pub oid: String,
+    /// HEAD points directly at a commit rather than at a branch
+    pub is_detached: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -110,6 +396,26 @@ impl std::fmt::Display for Commit {
     }
 }
 
+/// Past either limit, [`GitService::get_diffs_or_summary`] returns a summary
+/// instead of per-file diffs.
+#[derive(Debug, Clone, Copy)]
+pub struct DiffSizeLimits {
+    pub max_files: usize,
+    pub max_changed_lines: usize,
+    /// How many of the largest files to list in the summary
+    pub top_n: usize,
+}
+
+impl Default for DiffSizeLimits {
+    fn default() -> Self {
+        Self {
+            max_files: 300,
+            max_changed_lines: 50_000,
+            top_n: 20,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct WorktreeResetOptions {
     pub perform_reset: bool,
@@ -140,7 +446,26 @@ pub struct WorktreeResetOutcome {
     pub applied: bool,
 }
 
+/// How [`GitService::push_branch`] treats a remote branch that is not an
+/// ancestor of the local one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushMode {
+    /// Reject the push
+    Normal,
+    /// Overwrite the remote branch
+    Force,
+    /// Overwrite the remote branch only if it still points at `expected_sha`,
+    /// the commit the user saw there (e.g. when a normal push was rejected), so
+    /// nothing pushed since is lost. The safe choice after a rebase.
+    ForceWithLease { expected_sha: String },
+}
+
+/// Starts the line naming a file left out of [`GitService::get_commit_patch`]
+/// for being too large
+pub const PATCH_TRUNCATED_MARKER: &str = "# truncated:";
+
 /// Target for diff generation
+#[derive(Clone, Copy)]
 pub enum DiffTarget<'p> {
     /// Work-in-progress branch checked out in this worktree
     Worktree {
@@ -152,24 +477,257 @@ pub enum DiffTarget<'p> {
         repo_path: &'p Path,
         branch_name: &'p str,
         base_branch: &'p str,
+        /// Diff from the merge base of the two branches (`base...branch`, as in a
+        /// PR) instead of from the base branch tip (`base..branch`), so changes
+        /// made on the base since the branch diverged are left out
+        merge_base_relative: bool,
     },
     /// Specific commit vs base branch
     Commit {
         repo_path: &'p Path,
         commit_sha: &'p str,
     },
+    /// Changes staged in this worktree, vs HEAD (`git diff --cached`)
+    StagedOnly { worktree_path: &'p Path },
+    /// Changes in this worktree not yet staged, vs the index (`git diff`).
+    /// Untracked files are not included.
+    UnstagedOnly { worktree_path: &'p Path },
 }
 
+/// [`DiffTarget`] with its borrows cloned, so it can move to another thread
+enum OwnedDiffTarget {
+    Worktree {
+        worktree_path: PathBuf,
+        base_commit: Commit,
+    },
+    Branch {
+        repo_path: PathBuf,
+        branch_name: String,
+        base_branch: String,
+        merge_base_relative: bool,
+    },
+    Commit {
+        repo_path: PathBuf,
+        commit_sha: String,
+    },
+    StagedOnly {
+        worktree_path: PathBuf,
+    },
+    UnstagedOnly {
+        worktree_path: PathBuf,
+    },
+}
+
+impl From<DiffTarget<'_>> for OwnedDiffTarget {
+    fn from(target: DiffTarget<'_>) -> Self {
+        match target {
+            DiffTarget::Worktree {
+                worktree_path,
+                base_commit,
+            } => Self::Worktree {
+                worktree_path: worktree_path.to_path_buf(),
+                base_commit: base_commit.clone(),
+            },
+            DiffTarget::Branch {
+                repo_path,
+                branch_name,
+                base_branch,
+                merge_base_relative,
+            } => Self::Branch {
+                repo_path: repo_path.to_path_buf(),
+                branch_name: branch_name.to_string(),
+                base_branch: base_branch.to_string(),
+                merge_base_relative,
+            },
+            DiffTarget::Commit {
+                repo_path,
+                commit_sha,
+            } => Self::Commit {
+                repo_path: repo_path.to_path_buf(),
+                commit_sha: commit_sha.to_string(),
+            },
+            DiffTarget::StagedOnly { worktree_path } => Self::StagedOnly {
+                worktree_path: worktree_path.to_path_buf(),
+            },
+            DiffTarget::UnstagedOnly { worktree_path } => Self::UnstagedOnly {
+                worktree_path: worktree_path.to_path_buf(),
+            },
+        }
+    }
+}
+
+impl OwnedDiffTarget {
+    fn as_target(&self) -> DiffTarget<'_> {
+        match self {
+            Self::Worktree {
+                worktree_path,
+                base_commit,
+            } => DiffTarget::Worktree {
+                worktree_path,
+                base_commit,
+            },
+            Self::Branch {
+                repo_path,
+                branch_name,
+                base_branch,
+                merge_base_relative,
+            } => DiffTarget::Branch {
+                repo_path,
+                branch_name,
+                base_branch,
+                merge_base_relative: *merge_base_relative,
+            },
+            Self::Commit {
+                repo_path,
+                commit_sha,
+            } => DiffTarget::Commit {
+                repo_path,
+                commit_sha,
+            },
+            Self::StagedOnly { worktree_path } => DiffTarget::StagedOnly { worktree_path },
+            Self::UnstagedOnly { worktree_path } => DiffTarget::UnstagedOnly { worktree_path },
+        }
+    }
+}
+
+/// Where [`GitService::status_entry_to_diff`] reads one side of a change from
+enum DiffSide<'a> {
+    /// A commit's tree; `None` before the first commit
+    Tree(Option<&'a git2::Tree<'a>>),
+    Index(&'a git2::Index),
+    /// Files on disk
+    Workdir,
+}
+
+impl DiffSide<'_> {
+    /// Object recorded for `path` on a tree or index side, and whether it is a
+    /// submodule commit
+    fn object(&self, path: &str) -> Option<(git2::Oid, bool)> {
+        match self {
+            DiffSide::Tree(tree) => {
+                let entry = tree.as_ref()?.get_path(Path::new(path)).ok()?;
+                Some((entry.id(), entry.kind() == Some(git2::ObjectType::Commit)))
+            }
+            DiffSide::Index(index) => {
+                let entry = index.get_path(Path::new(path), 0)?;
+                Some((entry.id, entry.mode == 0o160000))
+            }
+            DiffSide::Workdir => None,
+        }
+    }
+}
+
+/// Diffs [`GitService::stream_diffs`] computes ahead of the consumer
+const DIFF_STREAM_BUFFER: usize = 16;
+
 impl Default for GitService {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl std::fmt::Debug for GitService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitService").finish_non_exhaustive()
+    }
+}
+
 impl GitService {
     /// Create a new GitService for the given repository path
     pub fn new() -> Self {
-        Self {}
+        Self {
+            identity: None,
+            merge_base_cache: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(MERGE_BASE_CACHE_CAPACITY).unwrap(),
+            ))),
+            stash_locks: Arc::new(Mutex::new(HashMap::new())),
+            sign_commits: Arc::new(AtomicBool::new(false)),
+            file_content: Arc::new(RwLock::new(FileContentOptions::default())),
+            commit_message_rules: Arc::new(RwLock::new(None)),
+            preferred_remotes: Arc::new(RwLock::new(Vec::new())),
+            deepen_shallow_clones: Arc::new(AtomicBool::new(true)),
+            init_submodules: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// A GitService that commits as `name <email>` in repositories with no
+    /// identity configured, instead of the Vibe Kanban default
+    pub fn with_identity(name: impl Into<String>, email: impl Into<String>) -> Self {
+        Self {
+            identity: Some(CommitIdentity {
+                name: name.into(),
+                email: email.into(),
+            }),
+            ..Self::new()
+        }
+    }
+
+    /// Set from user config. When enabled, commits made by [`Self::commit`] are
+    /// signed with `git commit -S`, using the key and format (`gpg.format`) from
+    /// the user's git config.
+    pub fn set_commit_signing(&self, enabled: bool) {
+        self.sign_commits.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Set from user config. Controls the size limit, binary detection and
+    /// decoding used when loading file contents for diffs.
+    pub fn set_file_content(&self, options: FileContentOptions) {
+        *self.file_content.write().unwrap_or_else(|e| e.into_inner()) = options;
+    }
+
+    /// Set from user config. [`Self::commit`] rejects messages that break these
+    /// rules; the default rules enforce nothing.
+    pub fn set_commit_message_rules(&self, rules: CommitMessageRules) {
+        *self
+            .commit_message_rules
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = rules.is_enforced().then_some(rules);
+    }
+
+    /// Set from user config. When enabled, new worktrees get their submodules
+    /// initialized.
+    pub fn set_init_submodules(&self, enabled: bool) {
+        self.init_submodules.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Set from user config. Remote names to use as the default remote, in order
+    /// of preference, when the repository does not set `remote.pushDefault`.
+    pub fn set_preferred_remotes(&self, names: Vec<String>) {
+        *self
+            .preferred_remotes
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = names;
+    }
+
+    /// Set from user config. When disabled, rebasing in a shallow clone that lacks
+    /// the needed history fails with [`GitServiceError::ShallowRepository`] instead
+    /// of fetching more history.
+    pub fn set_shallow_deepening(&self, enabled: bool) {
+        self.deepen_shallow_clones.store(enabled, Ordering::Relaxed);
+    }
+
+    fn file_content(&self) -> FileContentOptions {
+        self.file_content
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Forget all cached merge bases. Entries are keyed by branch tips and go
+    /// stale on their own; this is for tests and callers that rewrote history.
+    pub fn clear_merge_base_cache(&self) {
+        self.merge_base_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Name and email to commit with when the repository has none
+    fn fallback_identity(&self) -> (&str, &str) {
+        match &self.identity {
+            Some(identity) => (&identity.name, &identity.email),
+            None => (DEFAULT_IDENTITY_NAME, DEFAULT_IDENTITY_EMAIL),
+        }
     }
 
     pub fn is_branch_name_valid(&self, name: &str) -> bool {
@@ -182,7 +740,8 @@ impl GitService {
     }
 
     /// Ensure local (repo-scoped) identity exists for CLI commits.
-    /// Sets user.name/email only if missing in the repo config.
+    /// Sets user.name/email only if missing in the repo config. Signing settings
+    /// (`user.signingkey`, `gpg.format`, `commit.gpgsign`) are never touched.
     fn ensure_cli_commit_identity(&self, repo_path: &Path) -> Result<(), GitServiceError> {
         let repo = self.open_repo(repo_path)?;
         let cfg = repo.config()?;
@@ -190,8 +749,9 @@ impl GitService {
         let has_email = cfg.get_string("user.email").is_ok();
         if !(has_name && has_email) {
             let mut cfg = repo.config()?;
-            cfg.set_str("user.name", "Vibe Kanban")?;
-            cfg.set_str("user.email", "noreply@vibekanban.com")?;
+            let (name, email) = self.fallback_identity();
+            cfg.set_str("user.name", name)?;
+            cfg.set_str("user.email", email)?;
         }
         Ok(())
     }
@@ -203,11 +763,19 @@ impl GitService {
     ) -> Result<git2::Signature<'a>, GitServiceError> {
         match repo.signature() {
             Ok(sig) => Ok(sig),
-            Err(_) => git2::Signature::now("Vibe Kanban", "noreply@vibekanban.com")
-                .map_err(GitServiceError::from),
+            Err(_) => {
+                let (name, email) = self.fallback_identity();
+                git2::Signature::now(name, email).map_err(GitServiceError::from)
+            }
         }
     }
 
+    /// The remote used when a branch has no remote of its own. The first of these
+    /// that names an existing remote wins:
+    /// 1. `remote.pushDefault` from the git config
+    /// 2. the preferred remotes from user config, in order
+    /// 3. `origin`
+    /// 4. the first remote listed by `git remote`
     fn default_remote(
         &self,
         repo: &Repository,
@@ -215,21 +783,55 @@ impl GitService {
     ) -> Result<GitRemote, GitServiceError> {
         let mut remotes = GitCli::new().list_remotes(repo_path)?;
 
-        // Check for pushDefault config
+        let push_default = repo
+            .config()
+            .ok()
+            .and_then(|config| config.get_string("remote.pushDefault").ok());
+        let preferred = self
+            .preferred_remotes
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let idx = push_default
+            .into_iter()
+            .chain(preferred)
+            .chain(std::iter::once("origin".to_string()))
+            .find_map(|wanted| remotes.iter().position(|(name, _)| *name == wanted));
+
+        match idx {
+            Some(idx) => {
+                let (name, url) = remotes.swap_remove(idx);
+                Ok(GitRemote { name, url })
+            }
+            None => remotes
+                .into_iter()
+                .next()
+                .map(|(name, url)| GitRemote { name, url })
+                .ok_or_else(|| {
+                    GitServiceError::InvalidRepository("No remotes configured".to_string())
+                }),
+        }
+    }
+
+    /// Where a branch is pushed: its `branch.<name>.pushRemote`, falling back to
+    /// [`Self::default_remote`].
+    fn push_remote_for_branch(
+        &self,
+        repo: &Repository,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<GitRemote, GitServiceError> {
         if let Ok(config) = repo.config()
-            && let Ok(default_name) = config.get_string("remote.pushDefault")
-            && let Some(idx) = remotes.iter().position(|(name, _)| name == &default_name)
+            && let Ok(name) = config.get_string(&format!("branch.{branch_name}.pushRemote"))
+            && let Ok(remote) = repo.find_remote(&name)
+            && let Some(url) = remote.pushurl().or(remote.url())
         {
-            let (name, url) = remotes.swap_remove(idx);
-            return Ok(GitRemote { name, url });
+            return Ok(GitRemote {
+                url: url.to_string(),
+                name,
+            });
         }
-
-        // Fall back to first remote
-        remotes
-            .into_iter()
-            .next()
-            .map(|(name, url)| GitRemote { name, url })
-            .ok_or_else(|| GitServiceError::InvalidRepository("No remotes configured".to_string()))
+        self.default_remote(repo, repo_path)
     }
 
     /// Initialize a new git repository with a main branch and initial commit
@@ -256,15 +858,20 @@ impl GitService {
         Ok(())
     }
 
-    /// Ensure an existing repository has a main branch (for empty repos)
+    /// Ensure an existing repository has a branch (for empty repos). The initial
+    /// commit goes on the branch found by [`GitService::detect_default_branch`],
+    /// or `main` if there is none.
     pub fn ensure_main_branch_exists(&self, repo_path: &Path) -> Result<(), GitServiceError> {
         let repo = self.open_repo(repo_path)?;
 
         match repo.branches(None) {
             Ok(branches) => {
                 if branches.count() == 0 {
-                    // No branches exist - create initial commit on main branch
-                    self.create_initial_commit(&repo)?;
+                    // No branches exist - create initial commit on the default branch
+                    let branch = self
+                        .detect_default_branch(repo_path)
+                        .unwrap_or_else(|_| "main".to_string());
+                    self.create_initial_commit_on_branch(&repo, &branch)?;
                 }
             }
             Err(e) => {
@@ -277,6 +884,14 @@ impl GitService {
     }
 
     pub fn create_initial_commit(&self, repo: &Repository) -> Result<(), GitServiceError> {
+        self.create_initial_commit_on_branch(repo, "main")
+    }
+
+    fn create_initial_commit_on_branch(
+        &self,
+        repo: &Repository,
+        branch: &str,
+    ) -> Result<(), GitServiceError> {
         let signature = self.signature_with_fallback(repo)?;
 
         let tree_id = {
@@ -285,9 +900,9 @@ impl GitService {
         };
         let tree = repo.find_tree(tree_id)?;
 
-        // Create initial commit on main branch
+        let refname = format!("refs/heads/{branch}");
         let _commit_id = repo.commit(
-            Some("refs/heads/main"),
+            Some(&refname),
             &signature,
             &signature,
             "Initial commit",
@@ -295,13 +910,66 @@ impl GitService {
             &[],
         )?;
 
-        // Set HEAD to point to main branch
-        repo.set_head("refs/heads/main")?;
+        // Set HEAD to point to the new branch
+        repo.set_head(&refname)?;
 
         Ok(())
     }
 
+    /// The branch work should be based on when none is configured. The first of
+    /// these that is known wins:
+    /// 1. the branch `refs/remotes/<remote>/HEAD` points at, for the default remote
+    /// 2. the default branch the remote itself reports (needs network access)
+    /// 3. a local `main`, then a local `master`
+    pub fn detect_default_branch(&self, repo_path: &Path) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+
+        if let Ok(remote) = self.default_remote(&repo, repo_path) {
+            let prefix = format!("refs/remotes/{}/", remote.name);
+            let tracked = repo
+                .find_reference(&format!("{prefix}HEAD"))
+                .ok()
+                .and_then(|head| {
+                    head.symbolic_target()
+                        .and_then(|target| target.strip_prefix(&prefix))
+                        .map(str::to_string)
+                });
+            if let Some(branch) = tracked {
+                return Ok(branch);
+            }
+
+            match GitCli::new().remote_head_branch(repo_path, &remote.name) {
+                Ok(Some(branch)) => return Ok(branch),
+                Ok(None) => {}
+                Err(e) => tracing::debug!(
+                    "Could not ask remote {} for its default branch: {e}",
+                    remote.name
+                ),
+            }
+        }
+
+        ["main", "master"]
+            .into_iter()
+            .find(|name| repo.find_branch(name, BranchType::Local).is_ok())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                GitServiceError::BranchNotFound(
+                    "no default branch: the remote has no HEAD and there is no main or master"
+                        .to_string(),
+                )
+            })
+    }
+
     pub fn commit(&self, path: &Path, message: &str) -> Result<bool, GitServiceError> {
+        if let Some(rules) = self
+            .commit_message_rules
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            validate_commit_message(message, rules)
+                .map_err(GitServiceError::InvalidCommitMessage)?;
+        }
         // Use Git CLI to respect sparse-checkout semantics for staging and commit
         let git = GitCli::new();
         let has_changes = git
@@ -316,17 +984,180 @@ impl GitService {
             .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
         // Only ensure identity once we know we're about to commit
         self.ensure_cli_commit_identity(path)?;
-        git.commit(path, message)
-            .map_err(|e| GitServiceError::InvalidRepository(format!("git commit failed: {e}")))?;
+        let sign = self.sign_commits.load(Ordering::Relaxed);
+        if sign {
+            self.check_signing_key(path)?;
+        }
+        let hooks_path = Repository::open(path)
+            .ok()
+            .and_then(|repo| Self::worktree_hooks_path(&repo));
+        git.commit(path, message, sign, hooks_path.as_deref())
+            .map_err(|e| match e {
+                GitCliError::CommandFailed(msg) if sign && is_signing_failure(&msg) => {
+                    GitServiceError::CommitSigningFailed(msg)
+                }
+                e => GitServiceError::InvalidRepository(format!("git commit failed: {e}")),
+            })?;
         Ok(true)
     }
 
+    /// Fail early when signing is on but git has no key to sign with. GPG can
+    /// fall back to the key matching the committer email; SSH signing cannot
+    /// unless `gpg.ssh.defaultKeyCommand` supplies one.
+    fn check_signing_key(&self, repo_path: &Path) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let cfg = repo.config()?;
+        let is_ssh = cfg
+            .get_string("gpg.format")
+            .is_ok_and(|format| format.eq_ignore_ascii_case("ssh"));
+        let has_key = cfg
+            .get_string("user.signingkey")
+            .is_ok_and(|key| !key.trim().is_empty())
+            || cfg.get_string("gpg.ssh.defaultKeyCommand").is_ok();
+        if is_ssh && !has_key {
+            return Err(GitServiceError::CommitSigningFailed(
+                "no signing key configured; set user.signingkey to the SSH key to sign with"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Stage a single hunk, like `git add -p`. `patch` is a unified diff of the
+    /// worktree against the index: the file header (`diff --git`, `---`, `+++`)
+    /// followed by the hunk(s) to stage.
+    pub fn stage_hunk(&self, worktree_path: &Path, patch: &str) -> Result<(), GitServiceError> {
+        Self::apply_hunk_to_index(worktree_path, patch, false)
+    }
+
+    /// Unstage a single hunk, like `git reset -p`. `patch` is a unified diff of
+    /// the index against `HEAD` (as from `git diff --cached`), containing the
+    /// hunk(s) to take back out of the index. The worktree is left untouched.
+    pub fn unstage_hunk(&self, worktree_path: &Path, patch: &str) -> Result<(), GitServiceError> {
+        Self::apply_hunk_to_index(worktree_path, patch, true)
+    }
+
+    /// Check the patch against the index first so a stale or malformed hunk is
+    /// reported as [`GitServiceError::PatchRejected`] and never half-applied.
+    fn apply_hunk_to_index(
+        worktree_path: &Path,
+        patch: &str,
+        reverse: bool,
+    ) -> Result<(), GitServiceError> {
+        let mut patch = patch.to_string();
+        if !patch.ends_with('\n') {
+            patch.push('\n');
+        }
+        let git = GitCli::new();
+        match git.apply_to_index(worktree_path, &patch, reverse, true) {
+            Ok(()) => {}
+            Err(GitCliError::CommandFailed(message)) => {
+                return Err(GitServiceError::PatchRejected {
+                    hunk: patch,
+                    message,
+                });
+            }
+            Err(e) => return Err(e.into()),
+        }
+        git.apply_to_index(worktree_path, &patch, reverse, false)?;
+        Ok(())
+    }
+
+    /// Changed file and line counts of a worktree vs a base commit. Much cheaper than
+    /// [`Self::get_diffs`] because file contents are never loaded.
+    pub fn get_diff_stats(
+        &self,
+        worktree_path: &Path,
+        base_commit: &Commit,
+    ) -> Result<DiffNumstat, GitServiceError> {
+        let git = GitCli::new();
+        git.diff_numstat(worktree_path, base_commit)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git diff failed: {e}")))
+    }
+
+    /// Diff stats for what `branch_name` has committed since it forked from
+    /// `base_branch_name`. Works without a worktree, e.g. for archived workspaces.
+    /// Once the branch is merged its merge base is its own tip, so the stats are
+    /// taken from `start_commit` (where the branch began), when known, instead.
+    pub fn get_branch_diff_stats(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        base_branch_name: &str,
+        start_commit: Option<&str>,
+    ) -> Result<DiffNumstat, GitServiceError> {
+        let mut base_commit = self.get_base_commit(repo_path, branch_name, base_branch_name)?;
+        let repo = Repository::open(repo_path)?;
+        let branch_oid = Self::find_branch(&repo, branch_name)?
+            .get()
+            .peel_to_commit()?
+            .id();
+        if base_commit.as_oid() == branch_oid
+            && let Some(start_oid) = start_commit.and_then(|sha| git2::Oid::from_str(sha).ok())
+            && repo
+                .graph_descendant_of(branch_oid, start_oid)
+                .unwrap_or(false)
+        {
+            base_commit = Commit::new(start_oid);
+        }
+        GitCli::new()
+            .diff_numstat_branch(repo_path, &base_commit, branch_name)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git diff failed: {e}")))
+    }
+
     /// Get diffs between branches or worktree changes
     pub fn get_diffs(
         &self,
         target: DiffTarget,
         path_filter: Option<&[&str]>,
     ) -> Result<Vec<Diff>, GitServiceError> {
+        let mut diffs = Vec::new();
+        self.visit_diffs(target, path_filter, &mut |diff| {
+            diffs.push(diff);
+            true
+        })?;
+        Ok(diffs)
+    }
+
+    /// Like [`Self::get_diffs`], but yields each file's diff as soon as it is
+    /// computed instead of waiting for the whole set. Contents are still omitted
+    /// per file past the configured inline size. The work runs on tokio's
+    /// blocking pool, so this must be called within a runtime, and stops early
+    /// once the stream is dropped.
+    pub fn stream_diffs(
+        &self,
+        target: DiffTarget,
+        path_filter: Option<&[&str]>,
+    ) -> impl Stream<Item = Result<Diff, GitServiceError>> + Send + use<> {
+        let target = OwnedDiffTarget::from(target);
+        let path_filter: Option<Vec<String>> =
+            path_filter.map(|fs| fs.iter().map(|s| s.to_string()).collect());
+        let (tx, mut rx) = tokio::sync::mpsc::channel(DIFF_STREAM_BUFFER);
+        let service = self.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let filter: Option<Vec<&str>> = path_filter
+                .as_ref()
+                .map(|fs| fs.iter().map(String::as_str).collect());
+            let result = service.visit_diffs(target.as_target(), filter.as_deref(), &mut |diff| {
+                tx.blocking_send(Ok(diff)).is_ok()
+            });
+            if let Err(e) = result {
+                let _ = tx.blocking_send(Err(e));
+            }
+        });
+
+        stream::poll_fn(move |cx| rx.poll_recv(cx))
+    }
+
+    /// Compute diffs for `target`, handing each one to `emit` as it is built.
+    /// Stops early without error once `emit` returns false.
+    fn visit_diffs(
+        &self,
+        target: DiffTarget,
+        path_filter: Option<&[&str]>,
+        emit: &mut dyn FnMut(Diff) -> bool,
+    ) -> Result<(), GitServiceError> {
         match target {
             DiffTarget::Worktree {
                 worktree_path,
@@ -352,94 +1183,481 @@ impl GitService {
                     .map_err(|e| {
                         GitServiceError::InvalidRepository(format!("git diff failed: {e}"))
                     })?;
-                Ok(entries
-                    .into_iter()
-                    .map(|e| Self::status_entry_to_diff(&repo, &base_tree, e))
-                    .collect())
+                let old = DiffSide::Tree(Some(&base_tree));
+                for entry in entries {
+                    if !emit(self.status_entry_to_diff(&repo, &old, &DiffSide::Workdir, entry)) {
+                        break;
+                    }
+                }
+                Ok(())
             }
             DiffTarget::Branch {
                 repo_path,
                 branch_name,
                 base_branch,
+                merge_base_relative,
             } => {
                 let repo = self.open_repo(repo_path)?;
-                let base_tree = Self::find_branch(&repo, base_branch)?
-                    .get()
-                    .peel_to_commit()?
-                    .tree()?;
-                let branch_tree = Self::find_branch(&repo, branch_name)?
-                    .get()
-                    .peel_to_commit()?
-                    .tree()?;
-
-                let mut diff_opts = DiffOptions::new();
-                diff_opts.include_typechange(true);
+                let diff = self.branch_diff(
+                    &repo,
+                    branch_name,
+                    base_branch,
+                    merge_base_relative,
+                    path_filter,
+                )?;
+                self.visit_file_diffs(diff, &repo, emit)
+            }
+            DiffTarget::Commit {
+                repo_path,
+                commit_sha,
+            } => {
+                let repo = self.open_repo(repo_path)?;
+                let diff = Self::commit_diff(&repo, commit_sha, path_filter)?;
+                self.visit_file_diffs(diff, &repo, emit)
+            }
+            DiffTarget::StagedOnly { worktree_path }
+            | DiffTarget::UnstagedOnly { worktree_path } => {
+                let repo = Repository::open(worktree_path)?;
+                let index = repo.index()?;
+                let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+                let (which, old, new) = match target {
+                    DiffTarget::StagedOnly { .. } => (
+                        IndexDiff::Staged,
+                        DiffSide::Tree(head_tree.as_ref()),
+                        DiffSide::Index(&index),
+                    ),
+                    _ => (
+                        IndexDiff::Unstaged,
+                        DiffSide::Index(&index),
+                        DiffSide::Workdir,
+                    ),
+                };
 
-                // Add path filtering if specified
-                if let Some(paths) = path_filter {
-                    for path in paths {
-                        diff_opts.pathspec(*path);
+                let cli_opts = StatusDiffOptions {
+                    path_filter: path_filter.map(|fs| fs.iter().map(|s| s.to_string()).collect()),
+                };
+                let entries = GitCli::new()
+                    .diff_index_status(worktree_path, which, cli_opts)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git diff failed: {e}"))
+                    })?;
+                for entry in entries {
+                    if !emit(self.status_entry_to_diff(&repo, &old, &new, entry)) {
+                        break;
                     }
                 }
+                Ok(())
+            }
+        }
+    }
 
-                let mut diff = repo.diff_tree_to_tree(
-                    Some(&base_tree),
-                    Some(&branch_tree),
-                    Some(&mut diff_opts),
-                )?;
+    /// Diff for [`DiffTarget::Branch`], with renames detected
+    fn branch_diff<'r>(
+        &self,
+        repo: &'r Repository,
+        branch_name: &str,
+        base_branch: &str,
+        merge_base_relative: bool,
+        path_filter: Option<&[&str]>,
+    ) -> Result<git2::Diff<'r>, GitServiceError> {
+        let (base_tree_id, branch_tree_id) =
+            self.branch_diff_tree_ids(repo, branch_name, base_branch, merge_base_relative)?;
+        let base_tree = repo.find_tree(base_tree_id)?;
+        let branch_tree = repo.find_tree(branch_tree_id)?;
+
+        let mut diff_opts = DiffOptions::new();
+        diff_opts.include_typechange(true);
+
+        // Add path filtering if specified
+        if let Some(paths) = path_filter {
+            for path in paths {
+                diff_opts.pathspec(*path);
+            }
+        }
 
-                // Enable rename detection
-                let mut find_opts = DiffFindOptions::new();
-                diff.find_similar(Some(&mut find_opts))?;
+        let mut diff =
+            repo.diff_tree_to_tree(Some(&base_tree), Some(&branch_tree), Some(&mut diff_opts))?;
+
+        // Enable rename detection
+        let mut find_opts = DiffFindOptions::new();
+        diff.find_similar(Some(&mut find_opts))?;
+        Ok(diff)
+    }
 
-                self.convert_diff_to_file_diffs(diff, &repo)
+    /// Trees to diff for [`DiffTarget::Branch`]: the base branch tip, or its
+    /// merge base with the branch when `merge_base_relative`, against the branch tip
+    fn branch_diff_tree_ids(
+        &self,
+        repo: &Repository,
+        branch_name: &str,
+        base_branch: &str,
+        merge_base_relative: bool,
+    ) -> Result<(git2::Oid, git2::Oid), GitServiceError> {
+        let base_commit = Self::find_branch(repo, base_branch)?
+            .get()
+            .peel_to_commit()?;
+        let branch_commit = Self::find_branch(repo, branch_name)?
+            .get()
+            .peel_to_commit()?;
+
+        let base_tree_id = if merge_base_relative {
+            let merge_base = self.merge_base_checked(repo, base_commit.id(), branch_commit.id())?;
+            repo.find_commit(merge_base)?.tree_id()
+        } else {
+            base_commit.tree_id()
+        };
+        Ok((base_tree_id, branch_commit.tree_id()))
+    }
+
+    /// First parent of `commit`, the baseline a squash merge is diffed against.
+    /// In a shallow clone the parent may have been cut off rather than missing.
+    fn baseline_parent<'r>(
+        repo: &'r Repository,
+        commit: &git2::Commit<'r>,
+    ) -> Result<git2::Commit<'r>, GitServiceError> {
+        commit.parent(0).map_err(|_| {
+            if Self::is_shallow_boundary(repo, commit.id()) {
+                GitServiceError::ShallowRepository(format!(
+                    "the parent of {} has not been fetched",
+                    commit.id()
+                ))
+            } else {
+                GitServiceError::InvalidRepository(
+                    "Commit has no parent; cannot diff a squash merge without a baseline".into(),
+                )
+            }
+        })
+    }
+
+    /// Diff of a commit against its first parent, with renames detected
+    fn commit_diff<'r>(
+        repo: &'r Repository,
+        commit_sha: &str,
+        path_filter: Option<&[&str]>,
+    ) -> Result<git2::Diff<'r>, GitServiceError> {
+        // Diff options
+        let mut diff_opts = git2::DiffOptions::new();
+        diff_opts.include_typechange(true);
+
+        // Optional path filtering
+        if let Some(paths) = path_filter {
+            for path in paths {
+                diff_opts.pathspec(*path);
+            }
+        }
+        Self::commit_diff_with_options(repo, commit_sha, &mut diff_opts)
+    }
+
+    fn commit_diff_with_options<'r>(
+        repo: &'r Repository,
+        commit_sha: &str,
+        diff_opts: &mut DiffOptions,
+    ) -> Result<git2::Diff<'r>, GitServiceError> {
+        // Resolve commit and its baseline (the parent before the squash landed)
+        let commit_oid = git2::Oid::from_str(commit_sha).map_err(|_| {
+            GitServiceError::InvalidRepository(format!("Invalid commit SHA: {commit_sha}"))
+        })?;
+        let commit = repo.find_commit(commit_oid)?;
+        let parent = Self::baseline_parent(repo, &commit)?;
+
+        let parent_tree = parent.tree()?;
+        let commit_tree = commit.tree()?;
+
+        // Compute the diff parent -> commit
+        let mut diff =
+            repo.diff_tree_to_tree(Some(&parent_tree), Some(&commit_tree), Some(diff_opts))?;
+
+        // Enable rename detection
+        let mut find_opts = git2::DiffFindOptions::new();
+        diff.find_similar(Some(&mut find_opts))?;
+
+        Ok(diff)
+    }
+
+    /// Unified diff of a commit against its first parent, as `git apply`
+    /// accepts it, with binary changes included as `git diff --binary` does.
+    /// Files whose patch exceeds the inline size limit are left out whole and
+    /// listed at the top, before any file, on lines starting with
+    /// [`PATCH_TRUNCATED_MARKER`]; `git apply` skips such lines.
+    pub fn get_commit_patch(
+        &self,
+        repo_path: &Path,
+        commit_sha: &str,
+    ) -> Result<Vec<u8>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut diff_opts = DiffOptions::new();
+        diff_opts.include_typechange(true).show_binary(true);
+        let diff = Self::commit_diff_with_options(&repo, commit_sha, &mut diff_opts)?;
+        let max_file_bytes = self.file_content().max_inline_bytes;
+
+        let mut files = Vec::new();
+        let mut omitted = Vec::new();
+        // The patch of the file being printed, kept only if it fits
+        let mut current: Option<(String, Vec<u8>)> = None;
+        let mut finish = |current: Option<(String, Vec<u8>)>| {
+            if let Some((path, bytes)) = current {
+                if bytes.len() > max_file_bytes {
+                    omitted.push(path);
+                } else {
+                    files.extend_from_slice(&bytes);
+                }
+            }
+        };
+        diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+            if line.origin() == 'F' {
+                let path = delta
+                    .new_file()
+                    .path()
+                    .or_else(|| delta.old_file().path())
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                finish(current.replace((path, Vec::new())));
+            }
+            if let Some((_, bytes)) = current.as_mut() {
+                if matches!(line.origin(), '+' | '-' | ' ') {
+                    bytes.push(line.origin() as u8);
+                }
+                bytes.extend_from_slice(line.content());
+            }
+            true
+        })?;
+        finish(current);
+
+        let mut patch = Vec::new();
+        for path in omitted {
+            patch.extend_from_slice(
+                format!(
+                    "{PATCH_TRUNCATED_MARKER} {path}: changes exceed {max_file_bytes} bytes and are left out\n"
+                )
+                .as_bytes(),
+            );
+        }
+        patch.extend_from_slice(&files);
+        Ok(patch)
+    }
+
+    /// Same as [`Self::get_diffs`], but flags every file whose contents differ
+    /// from `since_commit`, i.e. what changed since a reviewer last looked.
+    /// Every file is flagged when `since_commit` can't be compared against,
+    /// e.g. it was garbage collected after a rebase.
+    pub fn get_diffs_since(
+        &self,
+        target: DiffTarget,
+        since_commit: &Commit,
+    ) -> Result<Vec<Diff>, GitServiceError> {
+        let changed = match self.paths_changed_since(&target, since_commit) {
+            Ok(changed) => Some(changed),
+            Err(e) => {
+                tracing::warn!("Can't diff against reviewed commit {since_commit}: {e}");
+                None
+            }
+        };
+        let mut diffs = self.get_diffs(target, None)?;
+        for diff in &mut diffs {
+            diff.changed_since_review = changed.as_ref().is_none_or(|changed| {
+                [&diff.new_path, &diff.old_path]
+                    .into_iter()
+                    .flatten()
+                    .any(|path| changed.contains(path))
+            });
+        }
+        Ok(diffs)
+    }
+
+    /// Paths touched between `since_commit` and the current state of `target`.
+    fn paths_changed_since(
+        &self,
+        target: &DiffTarget,
+        since_commit: &Commit,
+    ) -> Result<HashSet<String>, GitServiceError> {
+        let (repo, tree_id) = match target {
+            DiffTarget::Worktree { worktree_path, .. } => {
+                let entries = GitCli::new()
+                    .diff_status(worktree_path, since_commit, StatusDiffOptions::default())
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git diff failed: {e}"))
+                    })?;
+                return Ok(entries
+                    .into_iter()
+                    .flat_map(|e| [Some(e.path), e.old_path])
+                    .flatten()
+                    .collect());
+            }
+            DiffTarget::Branch {
+                repo_path,
+                branch_name,
+                ..
+            } => {
+                let repo = self.open_repo(repo_path)?;
+                let tree_id = Self::find_branch(&repo, branch_name)?
+                    .get()
+                    .peel_to_commit()?
+                    .tree_id();
+                (repo, tree_id)
             }
             DiffTarget::Commit {
                 repo_path,
                 commit_sha,
             } => {
                 let repo = self.open_repo(repo_path)?;
-
-                // Resolve commit and its baseline (the parent before the squash landed)
                 let commit_oid = git2::Oid::from_str(commit_sha).map_err(|_| {
                     GitServiceError::InvalidRepository(format!("Invalid commit SHA: {commit_sha}"))
                 })?;
-                let commit = repo.find_commit(commit_oid)?;
-                let parent = commit.parent(0).map_err(|_| {
-                    GitServiceError::InvalidRepository(
-                        "Commit has no parent; cannot diff a squash merge without a baseline"
-                            .into(),
-                    )
-                })?;
-
-                let parent_tree = parent.tree()?;
-                let commit_tree = commit.tree()?;
+                let tree_id = repo.find_commit(commit_oid)?.tree_id();
+                (repo, tree_id)
+            }
+            DiffTarget::StagedOnly { worktree_path } => {
+                let repo = self.open_repo(worktree_path)?;
+                let since_tree = repo.find_commit(since_commit.as_oid())?.tree()?;
+                let index = repo.index()?;
+                let diff = repo.diff_tree_to_index(Some(&since_tree), Some(&index), None)?;
+                return Ok(Self::diff_paths(&diff));
+            }
+            // What is unstaged is compared against the files on disk
+            DiffTarget::UnstagedOnly { worktree_path } => {
+                return self.paths_changed_since(
+                    &DiffTarget::Worktree {
+                        worktree_path,
+                        base_commit: since_commit,
+                    },
+                    since_commit,
+                );
+            }
+        };
 
-                // Diff options
-                let mut diff_opts = git2::DiffOptions::new();
-                diff_opts.include_typechange(true);
+        let tree = repo.find_tree(tree_id)?;
+        let since_tree = repo.find_commit(since_commit.as_oid())?.tree()?;
+        let diff = repo.diff_tree_to_tree(Some(&since_tree), Some(&tree), None)?;
+        Ok(Self::diff_paths(&diff))
+    }
 
-                // Optional path filtering
-                if let Some(paths) = path_filter {
-                    for path in paths {
-                        diff_opts.pathspec(*path);
-                    }
-                }
+    /// Old and new paths of every delta. No rename detection: a rename since
+    /// review flags both the old and new path.
+    fn diff_paths(diff: &git2::Diff) -> HashSet<String> {
+        diff.deltas()
+            .flat_map(|delta| [delta.old_file().path(), delta.new_file().path()])
+            .flatten()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect()
+    }
 
-                // Compute the diff parent -> commit
-                let mut diff = repo.diff_tree_to_tree(
-                    Some(&parent_tree),
-                    Some(&commit_tree),
-                    Some(&mut diff_opts),
-                )?;
+    /// Same as [`Self::get_diffs`] unless the changeset exceeds `limits`, in which
+    /// case only line counts are computed and a [`DiffSummary`] is returned.
+    pub fn get_diffs_or_summary(
+        &self,
+        target: DiffTarget,
+        path_filter: Option<&[&str]>,
+        limits: &DiffSizeLimits,
+    ) -> Result<DiffResult, GitServiceError> {
+        let mut files = self.file_numstats(&target, path_filter)?;
+        let additions: usize = files.iter().map(|f| f.additions).sum();
+        let deletions: usize = files.iter().map(|f| f.deletions).sum();
+
+        if files.len() <= limits.max_files && additions + deletions <= limits.max_changed_lines {
+            return Ok(DiffResult::Full {
+                diffs: self.get_diffs(target, path_filter)?,
+            });
+        }
 
-                // Enable rename detection
-                let mut find_opts = git2::DiffFindOptions::new();
-                diff.find_similar(Some(&mut find_opts))?;
+        let files_changed = files.len();
+        files.sort_by_key(|f| std::cmp::Reverse(f.additions + f.deletions));
+        files.truncate(limits.top_n);
+        Ok(DiffResult::Summary {
+            summary: DiffSummary {
+                files_changed,
+                additions,
+                deletions,
+                largest_changes: files
+                    .into_iter()
+                    .map(|f| FileChangeSize {
+                        path: f.path,
+                        additions: f.additions,
+                        deletions: f.deletions,
+                    })
+                    .collect(),
+            },
+        })
+    }
 
-                self.convert_diff_to_file_diffs(diff, &repo)
+    /// Per-file line counts for `target` without building file contents.
+    fn file_numstats(
+        &self,
+        target: &DiffTarget,
+        path_filter: Option<&[&str]>,
+    ) -> Result<Vec<FileNumstat>, GitServiceError> {
+        let git = GitCli::new();
+        let opts = || StatusDiffOptions {
+            path_filter: path_filter.map(|fs| fs.iter().map(|s| s.to_string()).collect()),
+        };
+        let cli_err =
+            |e: GitCliError| GitServiceError::InvalidRepository(format!("git diff failed: {e}"));
+        let repo;
+        let diff;
+        match target {
+            DiffTarget::Worktree {
+                worktree_path,
+                base_commit,
+            } => {
+                return git
+                    .diff_numstat_files(worktree_path, base_commit, opts())
+                    .map_err(cli_err);
+            }
+            DiffTarget::StagedOnly { worktree_path } => {
+                return git
+                    .diff_index_numstat_files(worktree_path, IndexDiff::Staged, opts())
+                    .map_err(cli_err);
+            }
+            DiffTarget::UnstagedOnly { worktree_path } => {
+                return git
+                    .diff_index_numstat_files(worktree_path, IndexDiff::Unstaged, opts())
+                    .map_err(cli_err);
+            }
+            DiffTarget::Branch {
+                repo_path,
+                branch_name,
+                base_branch,
+                merge_base_relative,
+            } => {
+                repo = self.open_repo(repo_path)?;
+                diff = self.branch_diff(
+                    &repo,
+                    branch_name,
+                    base_branch,
+                    *merge_base_relative,
+                    path_filter,
+                )?;
+            }
+            DiffTarget::Commit {
+                repo_path,
+                commit_sha,
+            } => {
+                repo = self.open_repo(repo_path)?;
+                diff = Self::commit_diff(&repo, commit_sha, path_filter)?;
             }
         }
+
+        let mut files = Vec::with_capacity(diff.deltas().len());
+        for (idx, delta) in diff.deltas().enumerate() {
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let (additions, deletions) = match git2::Patch::from_diff(&diff, idx)? {
+                Some(patch) if !delta.flags().is_binary() => {
+                    let (_, additions, deletions) = patch.line_stats()?;
+                    (additions, deletions)
+                }
+                _ => (0, 0),
+            };
+            files.push(FileNumstat {
+                path,
+                additions,
+                deletions,
+            });
+        }
+        Ok(files)
     }
 
     /// Convert git2::Diff to our Diff structs
@@ -449,9 +1667,26 @@ impl GitService {
         repo: &Repository,
     ) -> Result<Vec<Diff>, GitServiceError> {
         let mut file_diffs = Vec::new();
+        self.visit_file_diffs(diff, repo, &mut |diff| {
+            file_diffs.push(diff);
+            true
+        })?;
+        Ok(file_diffs)
+    }
+
+    /// Convert each delta of a git2::Diff to our Diff struct and pass it to
+    /// `emit`, stopping once `emit` returns false
+    fn visit_file_diffs(
+        &self,
+        diff: git2::Diff,
+        repo: &Repository,
+        emit: &mut dyn FnMut(Diff) -> bool,
+    ) -> Result<(), GitServiceError> {
+        let mut stopped = false;
+        let max_inline_bytes = self.file_content().max_inline_bytes;
 
         let mut delta_index: usize = 0;
-        diff.foreach(
+        let result = diff.foreach(
             &mut |delta, _| {
                 if delta.status() == Delta::Unreadable {
                     return true;
@@ -459,6 +1694,28 @@ impl GitService {
 
                 let status = delta.status();
 
+                let is_gitlink = |file: &git2::DiffFile| file.mode() == git2::FileMode::Commit;
+                if is_gitlink(&delta.old_file()) || is_gitlink(&delta.new_file()) {
+                    let side = |file: git2::DiffFile| {
+                        (is_gitlink(&file) && !file.id().is_zero()).then(|| file.id())
+                    };
+                    let path = delta
+                        .new_file()
+                        .path()
+                        .or(delta.old_file().path())
+                        .map(|p| p.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    delta_index += 1;
+                    stopped = !emit(Self::submodule_diff(
+                        repo,
+                        status,
+                        &path,
+                        side(delta.old_file()),
+                        side(delta.new_file()),
+                    ));
+                    return !stopped;
+                }
+
                 // Decide if we should omit content due to size
                 let mut content_omitted = false;
                 // Check old blob size when applicable
@@ -467,7 +1724,7 @@ impl GitService {
                     if !oid.is_zero()
                         && let Ok(blob) = repo.find_blob(oid)
                         && !blob.is_binary()
-                        && blob.size() > MAX_INLINE_DIFF_BYTES
+                        && blob.size() > max_inline_bytes
                     {
                         content_omitted = true;
                     }
@@ -478,7 +1735,7 @@ impl GitService {
                     if !oid.is_zero()
                         && let Ok(blob) = repo.find_blob(oid)
                         && !blob.is_binary()
-                        && blob.size() > MAX_INLINE_DIFF_BYTES
+                        && blob.size() > max_inline_bytes
                     {
                         content_omitted = true;
                     }
@@ -558,7 +1815,8 @@ impl GitService {
                     (None, None)
                 };
 
-                file_diffs.push(Diff {
+                delta_index += 1;
+                stopped = !emit(Diff {
                     change,
                     old_path,
                     new_path,
@@ -568,17 +1826,21 @@ impl GitService {
                     additions,
                     deletions,
                     repo_id: None,
+                    changed_since_review: false,
+                    submodule: None,
                 });
-
-                delta_index += 1;
-                true
+                !stopped
             },
             None,
             None,
             None,
-        )?;
+        );
 
-        Ok(file_diffs)
+        match result {
+            // Returning false from the callback aborts the walk with an error
+            Err(_) if stopped => Ok(()),
+            other => other.map_err(Into::into),
+        }
     }
 
     /// Extract file path from a Diff (for indexing and ConversationPatch)
@@ -590,18 +1852,17 @@ impl GitService {
     }
 
     /// Helper function to convert blob to string content
-    fn blob_to_string(blob: &git2::Blob) -> Option<String> {
-        if blob.is_binary() {
+    fn blob_to_string(&self, blob: &git2::Blob) -> Option<String> {
+        let options = self.file_content();
+        if options.binary_detection == BinaryDetection::NullByte && blob.is_binary() {
             None // Skip binary files
         } else {
-            std::str::from_utf8(blob.content())
-                .ok()
-                .map(|s| s.to_string())
+            options.decode(blob.content().to_vec())
         }
     }
 
     /// Helper function to read file content from filesystem with safety guards
-    fn read_file_to_string(repo: &Repository, rel_path: &Path) -> Option<String> {
+    fn read_file_to_string(&self, repo: &Repository, rel_path: &Path) -> Option<String> {
         let workdir = repo.workdir()?;
         let abs_path = workdir.join(rel_path);
 
@@ -614,8 +1875,10 @@ impl GitService {
             }
         };
 
+        let options = self.file_content();
+
         // Size guard - skip files larger than UI inline threshold
-        if bytes.len() > MAX_INLINE_DIFF_BYTES {
+        if bytes.len() > options.max_inline_bytes {
             tracing::debug!(
                 "Skipping large file ({}KB): {:?}",
                 bytes.len() / 1024,
@@ -624,20 +1887,18 @@ impl GitService {
             return None;
         }
 
-        // Binary guard - skip files containing null bytes
-        if bytes.contains(&0) {
+        // Binary guard - by default, skip files containing null bytes
+        if options.looks_binary(&bytes) {
             tracing::debug!("Skipping binary file: {:?}", abs_path);
             return None;
         }
 
-        // UTF-8 validation
-        match String::from_utf8(bytes) {
-            Ok(content) => Some(content),
-            Err(e) => {
-                tracing::debug!("File is not valid UTF-8: {:?}: {}", abs_path, e);
-                None
-            }
+        // UTF-8 validation, with the configured fallback encoding if any
+        let content = options.decode(bytes);
+        if content.is_none() {
+            tracing::debug!("File is not valid UTF-8: {:?}", abs_path);
         }
+        content
     }
 
     /// Create FileDiffDetails from path and blob with filesystem fallback
@@ -653,18 +1914,18 @@ impl GitService {
         let content = if !blob_id.is_zero() {
             repo.find_blob(*blob_id)
                 .ok()
-                .and_then(|blob| Self::blob_to_string(&blob))
+                .and_then(|blob| self.blob_to_string(&blob))
                 .or_else(|| {
                     // Fallback to filesystem for unstaged changes
                     tracing::debug!(
                         "Blob not found for non-zero OID, reading from filesystem: {}",
                         file_name
                     );
-                    Self::read_file_to_string(repo, path)
+                    self.read_file_to_string(repo, path)
                 })
         } else {
             // For zero OIDs, check filesystem directly (covers new/untracked files)
-            Self::read_file_to_string(repo, path)
+            self.read_file_to_string(repo, path)
         };
 
         FileDiffDetails {
@@ -675,7 +1936,44 @@ impl GitService {
 
     /// Create Diff entries from git_cli::StatusDiffEntry
     /// New Diff format is flattened with change kind, paths, and optional contents.
-    fn status_entry_to_diff(repo: &Repository, base_tree: &git2::Tree, e: StatusDiffEntry) -> Diff {
+    fn status_entry_to_diff(
+        &self,
+        repo: &Repository,
+        old: &DiffSide,
+        new: &DiffSide,
+        e: StatusDiffEntry,
+    ) -> Diff {
+        // Submodules: a tree or index holds a commit entry, the worktree a checked out repo
+        let old_side = match e.change {
+            ChangeType::Added => None,
+            _ => Some(e.old_path.as_deref().unwrap_or(&e.path)),
+        };
+        let old_gitlink = old_side
+            .and_then(|p| old.object(p))
+            .filter(|(_, is_gitlink)| *is_gitlink)
+            .map(|(id, _)| id);
+        let new_gitlink = match new {
+            _ if matches!(e.change, ChangeType::Deleted) => None,
+            DiffSide::Workdir => repo
+                .workdir()
+                .map(|workdir| workdir.join(&e.path))
+                .filter(|abs| abs.is_dir())
+                .and_then(|abs| Repository::open(abs).ok())
+                .and_then(|sub| sub.head().ok().and_then(|head| head.target())),
+            side => side
+                .object(&e.path)
+                .filter(|(_, is_gitlink)| *is_gitlink)
+                .map(|(id, _)| id),
+        };
+        if old_gitlink.is_some() || new_gitlink.is_some() {
+            let status = match e.change {
+                ChangeType::Added => Delta::Added,
+                ChangeType::Deleted => Delta::Deleted,
+                _ => Delta::Modified,
+            };
+            return Self::submodule_diff(repo, status, &e.path, old_gitlink, new_gitlink);
+        }
+
         // Map ChangeType to DiffChangeKind
         let mut change = match e.change {
             ChangeType::Added => DiffChangeKind::Added,
@@ -701,57 +1999,39 @@ impl GitService {
         };
 
         // Decide if we should omit content by size (either side)
-        let mut content_omitted = false;
-        // Old side (from base tree)
-        if let Some(ref oldp) = old_path_opt {
-            let rel = std::path::Path::new(oldp);
-            if let Ok(entry) = base_tree.get_path(rel)
-                && entry.kind() == Some(git2::ObjectType::Blob)
-                && let Ok(blob) = repo.find_blob(entry.id())
-                && !blob.is_binary()
-                && blob.size() > MAX_INLINE_DIFF_BYTES
-            {
-                content_omitted = true;
-            }
-        }
-        // New side (from filesystem)
-        if let Some(ref newp) = new_path_opt
-            && let Some(workdir) = repo.workdir()
-        {
-            let abs = workdir.join(newp);
-            if let Ok(md) = std::fs::metadata(&abs)
-                && (md.len() as usize) > MAX_INLINE_DIFF_BYTES
-            {
-                content_omitted = true;
-            }
-        }
+        let max_inline_bytes = self.file_content().max_inline_bytes;
+        let exceeds_inline = |side: &DiffSide, path: &str| match side {
+            DiffSide::Workdir => repo
+                .workdir()
+                .and_then(|workdir| std::fs::metadata(workdir.join(path)).ok())
+                .is_some_and(|md| (md.len() as usize) > max_inline_bytes),
+            side => side
+                .object(path)
+                .and_then(|(id, _)| repo.find_blob(id).ok())
+                .is_some_and(|blob| !blob.is_binary() && blob.size() > max_inline_bytes),
+        };
+        let content_omitted = old_path_opt
+            .as_deref()
+            .is_some_and(|p| exceeds_inline(old, p))
+            || new_path_opt
+                .as_deref()
+                .is_some_and(|p| exceeds_inline(new, p));
 
         // Load contents only if not omitted
+        let content = |side: &DiffSide, path: &str| match side {
+            DiffSide::Workdir => self.read_file_to_string(repo, Path::new(path)),
+            side => side
+                .object(path)
+                .and_then(|(id, _)| repo.find_blob(id).ok())
+                .and_then(|blob| self.blob_to_string(&blob)),
+        };
         let (old_content, new_content) = if content_omitted {
             (None, None)
         } else {
-            // Load old content from base tree if possible
-            let old_content = if let Some(ref oldp) = old_path_opt {
-                let rel = std::path::Path::new(oldp);
-                match base_tree.get_path(rel) {
-                    Ok(entry) if entry.kind() == Some(git2::ObjectType::Blob) => repo
-                        .find_blob(entry.id())
-                        .ok()
-                        .and_then(|b| Self::blob_to_string(&b)),
-                    _ => None,
-                }
-            } else {
-                None
-            };
-
-            // Load new content from filesystem (worktree) when available
-            let new_content = if let Some(ref newp) = new_path_opt {
-                let rel = std::path::Path::new(newp);
-                Self::read_file_to_string(repo, rel)
-            } else {
-                None
-            };
-            (old_content, new_content)
+            (
+                old_path_opt.as_deref().and_then(|p| content(old, p)),
+                new_path_opt.as_deref().and_then(|p| content(new, p)),
+            )
         };
 
         // If reported as Modified but content is identical, treat as a permission-only change
@@ -790,6 +2070,48 @@ impl GitService {
             additions,
             deletions,
             repo_id: None,
+            changed_since_review: false,
+            submodule: None,
+        }
+    }
+
+    /// Diff entry for a submodule (gitlink) path. Its "content" is just the commit
+    /// id, so report the pointer move instead of a one-line blob diff.
+    fn submodule_diff(
+        repo: &Repository,
+        status: Delta,
+        path: &str,
+        old_commit: Option<git2::Oid>,
+        new_commit: Option<git2::Oid>,
+    ) -> Diff {
+        let change = match status {
+            Delta::Added | Delta::Untracked => DiffChangeKind::Added,
+            Delta::Deleted => DiffChangeKind::Deleted,
+            _ if old_commit.is_none() => DiffChangeKind::Added,
+            _ if new_commit.is_none() => DiffChangeKind::Deleted,
+            _ => DiffChangeKind::SubmoduleUpdate,
+        };
+        let new_commit_subject = new_commit.and_then(|oid| {
+            let submodule_repo = Repository::open(repo.workdir()?.join(path)).ok()?;
+            let commit = submodule_repo.find_commit(oid).ok()?;
+            commit.summary().map(|s| s.to_string())
+        });
+        Diff {
+            change,
+            old_path: old_commit.map(|_| path.to_string()),
+            new_path: new_commit.map(|_| path.to_string()),
+            old_content: None,
+            new_content: None,
+            content_omitted: false,
+            additions: None,
+            deletions: None,
+            repo_id: None,
+            changed_since_review: false,
+            submodule: Some(SubmoduleUpdate {
+                old_commit: old_commit.map(|oid| oid.to_string()),
+                new_commit: new_commit.map(|oid| oid.to_string()),
+                new_commit_subject,
+            }),
         }
     }
 
@@ -920,19 +2242,155 @@ impl GitService {
         branch_ref: &Reference,
         base_branch_ref: &Reference,
     ) -> Result<(usize, usize), GitServiceError> {
-        let (a, b) = repo.graph_ahead_behind(
-            branch_ref.target().ok_or(GitServiceError::BranchNotFound(
+        let branch_oid = branch_ref.target().ok_or(GitServiceError::BranchNotFound(
+            "Branch not found".to_string(),
+        ))?;
+        let base_oid = base_branch_ref
+            .target()
+            .ok_or(GitServiceError::BranchNotFound(
                 "Branch not found".to_string(),
-            ))?,
-            base_branch_ref
-                .target()
-                .ok_or(GitServiceError::BranchNotFound(
-                    "Branch not found".to_string(),
-                ))?,
-        )?;
+            ))?;
+        self.ensure_shared_history(repo, branch_oid, base_oid)?;
+        let (a, b) = repo.graph_ahead_behind(branch_oid, base_oid)?;
         Ok((a, b))
     }
 
+    /// Merge base of two commits that also copes with shallow clones, where a merge
+    /// base beyond the shallow boundary makes the commits look unrelated. That case
+    /// is reported as [`GitServiceError::ShallowRepository`]; a merge base sitting
+    /// right on the boundary is returned but may be older than the real one.
+    fn merge_base_checked(
+        &self,
+        repo: &Repository,
+        a: git2::Oid,
+        b: git2::Oid,
+    ) -> Result<git2::Oid, GitServiceError> {
+        match repo.merge_base(a, b) {
+            Ok(oid) => {
+                if Self::is_shallow_boundary(repo, oid) {
+                    tracing::warn!(
+                        "Merge base {} of {} and {} is at the shallow clone boundary and may be inaccurate",
+                        oid,
+                        a,
+                        b
+                    );
+                }
+                Ok(oid)
+            }
+            Err(e) if e.code() == git2::ErrorCode::NotFound && Self::is_shallow(repo) => {
+                Err(GitServiceError::ShallowRepository(format!(
+                    "no common ancestor of {a} and {b} has been fetched"
+                )))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Commit counts between two commits are only meaningful when their common
+    /// history is present, which a shallow clone does not guarantee.
+    fn ensure_shared_history(
+        &self,
+        repo: &Repository,
+        a: git2::Oid,
+        b: git2::Oid,
+    ) -> Result<(), GitServiceError> {
+        if !Self::is_shallow(repo) {
+            return Ok(());
+        }
+        self.merge_base_checked(repo, a, b).map(|_| ())
+    }
+
+    /// Whether the merge bases a rebase of `task_branch` relies on are present
+    /// locally. Bases that cannot be resolved are left for the rebase to report.
+    fn has_rebase_history(
+        &self,
+        repo_path: &Path,
+        bases: [&str; 2],
+        task_branch: &str,
+    ) -> Result<bool, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let task = Self::find_branch(&repo, task_branch)?
+            .get()
+            .peel_to_commit()?
+            .id();
+        for base in bases {
+            let Ok(base_branch) = Self::find_branch(&repo, base) else {
+                continue;
+            };
+            let base = base_branch.get().peel_to_commit()?.id();
+            if repo.merge_base(task, base).is_err() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// In a shallow clone, a rebase whose merge base lies beyond the shallow
+    /// boundary replays the wrong commits and fails with confusing "could not
+    /// apply" errors. Fetch more history first, in growing steps, unless the user
+    /// turned that off.
+    fn ensure_rebase_history(
+        &self,
+        repo_path: &Path,
+        new_base_branch: &str,
+        old_base_branch: &str,
+        task_branch: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let bases = [new_base_branch, old_base_branch];
+        if !Self::is_shallow(&repo) || self.has_rebase_history(repo_path, bases, task_branch)? {
+            return Ok(());
+        }
+        if !self.deepen_shallow_clones.load(Ordering::Relaxed) {
+            return Err(GitServiceError::ShallowRepository(format!(
+                "rebasing '{task_branch}' onto '{new_base_branch}' needs commits beyond the shallow boundary, and fetching more history is disabled"
+            )));
+        }
+
+        // The base branch's history comes from the remote it tracks
+        let remote = self.resolve_remote_for_branch(repo_path, new_base_branch)?;
+        let git = GitCli::new();
+        for depth in SHALLOW_DEEPEN_STEPS {
+            tracing::info!(
+                "Deepening shallow clone by {} commits from {} to rebase {}",
+                depth,
+                remote.name,
+                task_branch
+            );
+            git.fetch_deepen(repo_path, &remote.name, Some(depth))?;
+            if self.has_rebase_history(repo_path, bases, task_branch)? {
+                return Ok(());
+            }
+        }
+        tracing::info!(
+            "Fetching full history from {} to rebase {}",
+            remote.name,
+            task_branch
+        );
+        git.fetch_deepen(repo_path, &remote.name, None)?;
+        Ok(())
+    }
+
+    /// `Repository::is_shallow` only looks at the worktree's own git dir, while the
+    /// shallow file of a linked worktree lives in the common dir.
+    fn is_shallow(repo: &Repository) -> bool {
+        repo.is_shallow() || repo.commondir().join("shallow").is_file()
+    }
+
+    /// Whether `oid` is a commit whose parents were cut off by a shallow fetch
+    fn is_shallow_boundary(repo: &Repository, oid: git2::Oid) -> bool {
+        if !Self::is_shallow(repo) {
+            return false;
+        }
+        std::fs::read_to_string(repo.commondir().join("shallow"))
+            .map(|shallow| {
+                shallow
+                    .lines()
+                    .any(|line| git2::Oid::from_str(line.trim()) == Ok(oid))
+            })
+            .unwrap_or(false)
+    }
+
     pub fn get_branch_status(
         &self,
         repo_path: &Path,
@@ -958,21 +2416,39 @@ impl GitService {
         let repo = Repository::open(repo_path)?;
         let branch = Self::find_branch(&repo, branch_name)?;
         let base_branch = Self::find_branch(&repo, base_branch_name)?;
+        let branch_oid = branch.get().peel_to_commit()?.id();
+        let base_branch_oid = base_branch.get().peel_to_commit()?.id();
+
+        let key = (repo.commondir().to_path_buf(), branch_oid, base_branch_oid);
+        let cached = self
+            .merge_base_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+            .copied();
+        if let Some(oid) = cached {
+            return Ok(Commit::new(oid));
+        }
+
         // Find the common ancestor (merge base)
-        let oid = repo
-            .merge_base(
-                branch.get().peel_to_commit()?.id(),
-                base_branch.get().peel_to_commit()?.id(),
-            )
-            .map_err(GitServiceError::from)?;
+        let oid = self.merge_base_checked(&repo, branch_oid, base_branch_oid)?;
+        self.merge_base_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .put(key, oid);
         Ok(Commit::new(oid))
     }
 
+    /// Ahead/behind counts against a remote branch, fetched first. Cancelling
+    /// `cancel` aborts the fetch with [`GitServiceError::Cancelled`]; the fetch's
+    /// transfer progress is reported to `progress`.
     pub fn get_remote_branch_status(
         &self,
         repo_path: &Path,
         branch_name: &str,
         base_branch_name: Option<&str>,
+        cancel: Option<&CancellationToken>,
+        progress: Option<FetchProgressSink<'_>>,
     ) -> Result<(usize, usize), GitServiceError> {
         let repo = Repository::open(repo_path)?;
         let branch_ref = Self::find_branch(&repo, branch_name)?.into_reference();
@@ -985,7 +2461,7 @@ impl GitService {
         }
         .into_reference();
         let remote = self.get_remote_from_branch_ref(&repo, &base_branch_ref)?;
-        self.fetch_all_from_remote(&repo, &remote)?;
+        self.fetch_all_from_remote(&repo, &remote, cancel, progress)?;
         self.get_branch_status_inner(&repo, &branch_ref, &base_branch_ref)
     }
 
@@ -1044,6 +2520,38 @@ impl GitService {
         Ok(())
     }
 
+    /// Whether a commit (or annotated tag) is signed and whether the signature
+    /// verifies with the locally configured gpg or SSH allowed signers. When it
+    /// can't be checked here, [`SignatureStatus::Unknown`] is returned.
+    pub fn verify_commit_signature(
+        &self,
+        repo_path: &Path,
+        sha: &str,
+    ) -> Result<SignatureStatus, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let object = repo.revparse_single(sha)?;
+        let (signed, is_tag) = match object.as_tag() {
+            Some(tag) => (
+                String::from_utf8_lossy(tag.message_bytes()).contains("-----BEGIN "),
+                true,
+            ),
+            None => {
+                let commit = object.peel_to_commit()?;
+                (repo.extract_signature(&commit.id(), None).is_ok(), false)
+            }
+        };
+        if !signed {
+            return Ok(SignatureStatus::Unsigned);
+        }
+
+        match GitCli::new().verify_signature(repo_path, &object.id().to_string(), is_tag) {
+            Ok(output) => Ok(parse_verify_output(&output)),
+            Err(e) => Ok(SignatureStatus::Unknown {
+                reason: e.to_string(),
+            }),
+        }
+    }
+
     /// Get current HEAD information including branch name and commit OID
     pub fn get_head_info(&self, repo_path: &Path) -> Result<HeadInfo, GitServiceError> {
         let repo = self.open_repo(repo_path)?;
@@ -1064,7 +2572,11 @@ impl GitService {
             ));
         };
 
-        Ok(HeadInfo { branch, oid })
+        Ok(HeadInfo {
+            branch,
+            oid,
+            is_detached: repo.head_detached()?,
+        })
     }
 
     pub fn get_current_branch(&self, repo_path: &Path) -> Result<String, git2::Error> {
@@ -1095,7 +2607,76 @@ impl GitService {
         task_branch: &str,
     ) -> Result<String, GitServiceError> {
         let git = GitCli::new();
-        Ok(git.merge_base(worktree_path, target_branch, task_branch)?)
+        match git.merge_base(worktree_path, target_branch, task_branch) {
+            Ok(sha) => {
+                if let Ok(repo) = self.open_repo(worktree_path)
+                    && let Ok(oid) = git2::Oid::from_str(&sha)
+                    && Self::is_shallow_boundary(&repo, oid)
+                {
+                    tracing::warn!(
+                        "Fork point {} of {} is at the shallow clone boundary and may be inaccurate",
+                        sha,
+                        task_branch
+                    );
+                }
+                Ok(sha)
+            }
+            Err(_)
+                if self
+                    .open_repo(worktree_path)
+                    .is_ok_and(|r| Self::is_shallow(&r)) =>
+            {
+                Err(GitServiceError::ShallowRepository(format!(
+                    "no common ancestor of {target_branch} and {task_branch} has been fetched"
+                )))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Commits of `branch` not reachable from `upstream`, oldest first, each marked
+    /// with whether an equivalent change is already in `upstream`. Empty when the
+    /// branch has nothing upstream lacks.
+    pub fn get_cherry_status(
+        &self,
+        repo_path: &Path,
+        upstream: &str,
+        branch: &str,
+    ) -> Result<Vec<CherryStatusEntry>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        // Resolve both sides first so arbitrary input never reaches the CLI as an option
+        let resolve = |rev: &str| -> Result<String, GitServiceError> {
+            let commit = repo
+                .revparse_single(rev)
+                .and_then(|obj| obj.peel_to_commit())
+                .map_err(|_| GitServiceError::BranchNotFound(rev.to_string()))?;
+            Ok(commit.id().to_string())
+        };
+        let upstream_sha = resolve(upstream)?;
+        let branch_sha = resolve(branch)?;
+
+        let entries = GitCli::new().cherry(repo_path, &upstream_sha, &branch_sha)?;
+        Ok(entries
+            .into_iter()
+            .map(|(in_upstream, sha, subject)| CherryStatusEntry {
+                sha,
+                subject,
+                in_upstream,
+            })
+            .collect())
+    }
+
+    /// Whether `commit_sha` names a commit in the repository
+    pub fn commit_exists(
+        &self,
+        repo_path: &Path,
+        commit_sha: &str,
+    ) -> Result<bool, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let Ok(oid) = git2::Oid::from_str(commit_sha) else {
+            return Ok(false);
+        };
+        Ok(repo.find_commit(oid).is_ok())
     }
 
     /// Get the subject/summary line for a given commit OID
@@ -1124,6 +2705,7 @@ impl GitService {
             .map_err(|_| GitServiceError::InvalidRepository("Invalid from OID".into()))?;
         let to = git2::Oid::from_str(to_oid)
             .map_err(|_| GitServiceError::InvalidRepository("Invalid to OID".into()))?;
+        self.ensure_shared_history(&repo, from, to)?;
         let (ahead, behind) = repo.graph_ahead_behind(from, to)?;
         Ok((ahead, behind))
     }
@@ -1147,6 +2729,35 @@ impl GitService {
         Ok((st.uncommitted_tracked, st.untracked))
     }
 
+    /// Capture HEAD, the uncommitted tracked content and the untracked files'
+    /// content of a worktree. Runs git commands; call off the async runtime.
+    pub fn worktree_snapshot(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<WorktreeSnapshot, GitServiceError> {
+        let cli = GitCli::new();
+        let status = self.get_worktree_status(worktree_path)?;
+        let tracked_tree = if status.uncommitted_tracked > 0 {
+            cli.stash_create_tree(worktree_path).map_err(|e| {
+                GitServiceError::InvalidRepository(format!("git stash create failed: {e}"))
+            })?
+        } else {
+            None
+        };
+        let untracked = if status.untracked > 0 {
+            cli.untracked_blobs(worktree_path).map_err(|e| {
+                GitServiceError::InvalidRepository(format!("hashing untracked files failed: {e}"))
+            })?
+        } else {
+            HashMap::new()
+        };
+        Ok(WorktreeSnapshot {
+            head: self.get_head_info(worktree_path).ok().map(|h| h.oid),
+            tracked_tree,
+            untracked,
+        })
+    }
+
     /// Evaluate whether any action is needed to reset to `target_commit_oid` and
     /// optionally perform the actions.
     pub fn reconcile_worktree_to_commit(
@@ -1211,7 +2822,78 @@ impl GitService {
         Ok(())
     }
 
+    /// Switch an existing worktree to another branch, creating it from the
+    /// current HEAD when `create` is set. If `force` is false and the worktree is
+    /// dirty, returns WorktreeDirty error; with `force` local changes are discarded.
+    /// Untracked files the switch would overwrite also give WorktreeDirty.
+    pub fn checkout_branch(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        create: bool,
+        force: bool,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        if !force {
+            self.check_worktree_clean(&repo)?;
+        }
+
+        // git refuses to check out a branch in two worktrees; report which one has it
+        let cli = GitCli::new();
+        let this_worktree = worktree_path.canonicalize()?;
+        let (stale, live): (Vec<_>, Vec<_>) = cli
+            .list_worktrees(worktree_path)?
+            .into_iter()
+            .filter(|wt| wt.branch.as_deref() == Some(branch_name))
+            .partition(|wt| wt.prunable);
+        if let Some(other) = live.into_iter().next() {
+            if Path::new(&other.path).canonicalize().ok().as_deref()
+                == Some(this_worktree.as_path())
+            {
+                return Ok(());
+            }
+            return Err(GitServiceError::BranchCheckedOutElsewhere {
+                branch: branch_name.to_string(),
+                worktree_path: other.path,
+            });
+        }
+        // A deleted worktree still claims its branch until its metadata is pruned
+        if !stale.is_empty() {
+            cli.worktree_prune(worktree_path)?;
+        }
+
+        match cli.switch_branch(worktree_path, branch_name, create, force) {
+            Ok(()) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) if msg.contains("would be overwritten") => {
+                let current = repo
+                    .head()
+                    .ok()
+                    .and_then(|h| h.shorthand().map(|s| s.to_string()))
+                    .unwrap_or_else(|| "unknown branch".to_string());
+                Err(GitServiceError::WorktreeDirty(current, msg))
+            }
+            Err(GitCliError::CommandFailed(msg))
+                if msg.contains("resolve your current index") || msg.contains("needs merge") =>
+            {
+                Err(GitServiceError::MergeConflicts {
+                    conflicted_files: cli.get_conflicted_files(worktree_path)?,
+                    message: msg,
+                })
+            }
+            Err(GitCliError::CommandFailed(msg)) if msg.contains("invalid reference") => {
+                Err(GitServiceError::BranchNotFound(branch_name.to_string()))
+            }
+            Err(e) => Err(GitServiceError::InvalidRepository(format!(
+                "git switch failed: {e}"
+            ))),
+        }
+    }
+
     /// Add a worktree for a branch, optionally creating the branch
+    ///
+    /// When [`Self::set_init_submodules`] is on, submodules are checked out in the new
+    /// worktree. Failing to do so (e.g. no network) only logs a warning since the
+    /// worktree itself is usable.
     pub fn add_worktree(
         &self,
         repo_path: &Path,
@@ -1222,6 +2904,29 @@ impl GitService {
         let git = GitCli::new();
         git.worktree_add(repo_path, worktree_path, branch, create_branch)
             .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))?;
+
+        if !self.init_submodules.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        // Submodules are a convenience; a worktree without them is still usable
+        let has_submodules = match self.list_submodules(worktree_path) {
+            Ok(submodules) => !submodules.is_empty(),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to read .gitmodules in worktree {}: {}",
+                    worktree_path.display(),
+                    e
+                );
+                false
+            }
+        };
+        if has_submodules && let Err(e) = git.submodule_update_init(worktree_path) {
+            tracing::warn!(
+                "Failed to initialize submodules in worktree {}: {}",
+                worktree_path.display(),
+                e
+            );
+        }
         Ok(())
     }
 
@@ -1258,6 +2963,184 @@ impl GitService {
         Ok(())
     }
 
+    /// Whether `worktree_path` is a usable worktree of `repo_path`: its `.git` link
+    /// resolves to this repository's admin entry and that entry points back to it.
+    /// A crash in the middle of `add_worktree` can leave a directory that fails this.
+    pub fn is_worktree_healthy(&self, repo_path: &Path, worktree_path: &Path) -> bool {
+        if !worktree_path.join(".git").is_file() {
+            return false;
+        }
+        let (Ok(repo), Ok(worktree_repo)) =
+            (Repository::open(repo_path), Repository::open(worktree_path))
+        else {
+            return false;
+        };
+        let Ok(worktree) = git2::Worktree::open_from_repository(&worktree_repo) else {
+            return false;
+        };
+        worktree.validate().is_ok()
+            && same_path(repo.commondir(), worktree_repo.commondir())
+            && same_path(worktree.path(), worktree_path)
+            && worktree_repo.head().is_ok()
+    }
+
+    /// Bring a broken worktree back to a usable state. `git worktree repair` fixes
+    /// stale links (e.g. after a move or an interrupted add); if that is not enough
+    /// the worktree is pruned and recreated on the branch it had checked out. Files
+    /// left in the old directory are moved aside rather than deleted, since git can
+    /// no longer tell whether they hold uncommitted work.
+    pub fn repair_worktree(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+    ) -> Result<(), GitServiceError> {
+        if self.is_worktree_healthy(repo_path, worktree_path) {
+            return Ok(());
+        }
+
+        let git = GitCli::new();
+        if worktree_path.exists() {
+            if let Err(e) = git.worktree_repair(repo_path, worktree_path) {
+                tracing::debug!("git worktree repair non-fatal error: {}", e);
+            }
+            if self.is_worktree_healthy(repo_path, worktree_path) {
+                tracing::info!("Repaired worktree at {}", worktree_path.display());
+                return Ok(());
+            }
+        }
+
+        let branch = self
+            .list_worktrees_detailed(repo_path)?
+            .into_iter()
+            .find(|w| same_path(Path::new(&w.path), worktree_path))
+            .and_then(|w| w.branch)
+            .ok_or_else(|| {
+                GitServiceError::InvalidRepository(format!(
+                    "Cannot recover worktree {}: no branch is registered for it",
+                    worktree_path.display()
+                ))
+            })?;
+
+        tracing::warn!(
+            "Worktree at {} is irrecoverable, recreating it on branch {}",
+            worktree_path.display(),
+            branch
+        );
+        if worktree_path.exists() {
+            if has_worktree_files(worktree_path)? {
+                let aside = set_aside_path(worktree_path);
+                std::fs::rename(worktree_path, &aside)?;
+                // Drop the broken link so nothing treats the copy as a worktree
+                let _ = std::fs::remove_file(aside.join(".git"));
+                tracing::warn!(
+                    "Moved the contents of broken worktree {} to {}",
+                    worktree_path.display(),
+                    aside.display()
+                );
+            } else {
+                std::fs::remove_dir_all(worktree_path)?;
+            }
+        }
+        if let Err(e) = git.worktree_remove(repo_path, worktree_path, true) {
+            tracing::debug!("git worktree remove non-fatal error: {}", e);
+        }
+        self.prune_worktrees(repo_path)?;
+        self.add_worktree(repo_path, worktree_path, &branch, false)
+    }
+
+    /// Ecosystems the repository uses, most certain first, judged from manifests
+    /// tracked at the root or one directory below (e.g. `Cargo.toml`,
+    /// `package.json`). Never walks the tree.
+    pub fn detect_ecosystem(&self, repo_path: &Path) -> Result<Vec<Ecosystem>, GitServiceError> {
+        let files = GitCli::new().ls_files_shallow(repo_path)?;
+        Ok(detect_ecosystems(files.iter().map(String::as_str)))
+    }
+
+    /// Write executable hook scripts for `worktree_path` only. They go in a
+    /// `vk-hooks` directory inside the worktree's git dir, which vibe-kanban
+    /// passes as `-c core.hooksPath=` on its own commits and pushes; the shared
+    /// repository config is never changed. Every other hook git would run is
+    /// chained from a small wrapper that execs the original in place, so hook
+    /// managers such as husky still find their helpers next to the script. A
+    /// hook that already exists with different contents is only replaced when
+    /// `force` is set; nothing is written if any hook would be refused.
+    pub fn install_hooks(
+        &self,
+        worktree_path: &Path,
+        hooks: &[(HookName, String)],
+        force: bool,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        let current_dir = git.hooks_dir(worktree_path)?;
+        let hooks_dir = git
+            .worktree_git_dir(worktree_path)?
+            .join(WORKTREE_HOOKS_DIR);
+
+        if !force {
+            for (name, script) in hooks {
+                let ours = hooks_dir.join(name.file_name());
+                let path = if ours.exists() {
+                    ours
+                } else {
+                    current_dir.join(name.file_name())
+                };
+                if let Ok(existing) = std::fs::read_to_string(&path)
+                    && existing != *script
+                {
+                    return Err(GitServiceError::HookExists(*name));
+                }
+            }
+        }
+
+        std::fs::create_dir_all(&hooks_dir)?;
+        if current_dir != hooks_dir
+            && let Ok(entries) = std::fs::read_dir(&current_dir)
+        {
+            for entry in entries.flatten() {
+                let file_name = entry.file_name();
+                let is_sample = file_name.to_string_lossy().ends_with(".sample");
+                let target = hooks_dir.join(&file_name);
+                // git skips hooks that aren't executable, so they aren't chained
+                if !is_sample && is_executable(&entry.path()) && !target.exists() {
+                    let original = std::path::absolute(entry.path())?;
+                    write_hook(&target, &chain_hook_script(&original))?;
+                }
+            }
+        }
+
+        for (name, script) in hooks {
+            let path = hooks_dir.join(name.file_name());
+            write_hook(&path, script)?;
+            tracing::debug!("Installed {} hook at {}", name, path.display());
+        }
+        Ok(())
+    }
+
+    /// The `vk-hooks` directory [`Self::install_hooks`] wrote for this
+    /// worktree, if any.
+    fn worktree_hooks_path(repo: &Repository) -> Option<PathBuf> {
+        let dir = repo.path().join(WORKTREE_HOOKS_DIR);
+        dir.is_dir().then_some(dir)
+    }
+
+    /// List every worktree registered in the repository, including the main one,
+    /// with lock/prunable state.
+    pub fn list_worktrees_detailed(
+        &self,
+        repo_path: &Path,
+    ) -> Result<Vec<WorktreeEntry>, GitServiceError> {
+        let git = GitCli::new();
+        git.list_worktrees(repo_path)
+            .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))
+    }
+
+    /// Whether a worktree has staged, unstaged or untracked changes.
+    pub fn worktree_has_changes(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
+        let git = GitCli::new();
+        git.has_changes(worktree_path)
+            .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))
+    }
+
     pub fn get_all_branches(&self, repo_path: &Path) -> Result<Vec<GitBranch>, git2::Error> {
         let repo = Repository::open(repo_path)?;
         let current_branch = self.get_current_branch(repo_path).unwrap_or_default();
@@ -1341,10 +3224,14 @@ impl GitService {
 
         // If there are conflicts, return an error
         if index.has_conflicts() {
+            let mut conflicted_files = Vec::new();
+            for conflict in index.conflicts()? {
+                conflicted_files.extend(Self::conflict_path(&conflict?));
+            }
             return Err(GitServiceError::MergeConflicts {
                 message: "Merge failed due to conflicts. Please resolve conflicts manually."
                     .to_string(),
-                conflicted_files: vec![],
+                conflicted_files,
             });
         }
 
@@ -1377,6 +3264,7 @@ impl GitService {
         new_base_branch: &str,
         old_base_branch: &str,
         task_branch: &str,
+        auto_stash: bool,
     ) -> Result<String, GitServiceError> {
         let worktree_repo = Repository::open(worktree_path)?;
         let main_repo = self.open_repo(repo_path)?;
@@ -1384,7 +3272,10 @@ impl GitService {
         // Safety guard: never operate on a dirty worktree. This preserves any
         // uncommitted changes to tracked files by failing fast instead of
         // resetting or cherry-picking over them. Untracked files are allowed.
-        self.check_worktree_clean(&worktree_repo)?;
+        // With `auto_stash`, changes are stashed right before the rebase instead.
+        if !auto_stash {
+            self.check_worktree_clean(&worktree_repo)?;
+        }
 
         // If a rebase is already in progress, refuse to proceed instead of
         // aborting (which might destroy user changes mid-rebase).
@@ -1399,9 +3290,144 @@ impl GitService {
         if nbr.is_remote() {
             self.fetch_branch_from_remote(&main_repo, &nbr)?;
         }
+        self.ensure_rebase_history(repo_path, new_base_branch, old_base_branch, task_branch)?;
+
+        // Ensure identity for any commits produced by rebase
+        self.ensure_cli_commit_identity(worktree_path)?;
+
+        let stash = if auto_stash {
+            self.stash_worktree(worktree_path)?
+        } else {
+            None
+        };
+        let rebased = self.run_rebase(
+            &git,
+            &worktree_repo,
+            worktree_path,
+            new_base_branch,
+            old_base_branch,
+            task_branch,
+        );
+        match (rebased, stash) {
+            (Ok(()), Some(stash)) => self.pop_stash(worktree_path, &stash)?,
+            (Ok(()), None) => {}
+            // Leave the stash alone while the conflicted rebase is in progress
+            (
+                Err(GitServiceError::MergeConflicts {
+                    message,
+                    conflicted_files,
+                }),
+                Some(stash),
+            ) => {
+                return Err(GitServiceError::MergeConflicts {
+                    message: format!(
+                        "{message} Uncommitted changes were saved in {stash}; pop it once the rebase is done."
+                    ),
+                    conflicted_files,
+                });
+            }
+            (Err(e), Some(stash)) => {
+                if let Err(pop_err) = self.pop_stash(worktree_path, &stash) {
+                    tracing::warn!(
+                        "Failed to restore {} after failed rebase: {}",
+                        stash,
+                        pop_err
+                    );
+                }
+                return Err(e);
+            }
+            (Err(e), None) => return Err(e),
+        }
+
+        // Return resulting HEAD commit
+        let final_commit = worktree_repo.head()?.peel_to_commit()?;
+        Ok(final_commit.id().to_string())
+    }
+
+    /// Cherry-pick `commit_shas` onto the branch checked out in the worktree, in
+    /// order, returning the new commit OIDs. On conflict the cherry-pick is left
+    /// in progress and [`GitServiceError::MergeConflicts`] is returned; commits
+    /// picked before it stay applied.
+    pub fn cherry_pick_commits(
+        &self,
+        worktree_path: &Path,
+        commit_shas: &[&str],
+    ) -> Result<Vec<String>, GitServiceError> {
+        let worktree_repo = Repository::open(worktree_path)?;
+        self.check_worktree_clean(&worktree_repo)?;
+
+        match self.detect_conflict_op(worktree_path)? {
+            None => {}
+            Some(ConflictOp::Rebase) => return Err(GitServiceError::RebaseInProgress),
+            Some(op) => {
+                return Err(GitServiceError::InvalidRepository(format!(
+                    "{op:?} in progress; resolve or abort it before cherry-picking"
+                )));
+            }
+        }
+
+        // Resolve everything up front so a typo doesn't leave a partial pick
+        let commits = commit_shas
+            .iter()
+            .map(|sha| {
+                worktree_repo
+                    .revparse_single(sha)
+                    .and_then(|obj| obj.peel_to_commit())
+                    .map(|commit| commit.id().to_string())
+                    .map_err(|_| {
+                        GitServiceError::InvalidRepository(format!("Commit not found: {sha}"))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Ensure identity for the commits cherry-pick creates
+        self.ensure_cli_commit_identity(worktree_path)?;
+
+        let git = GitCli::new();
+        let mut picked = Vec::with_capacity(commits.len());
+        for sha in &commits {
+            match git.cherry_pick(worktree_path, sha) {
+                Ok(()) => picked.push(worktree_repo.head()?.peel_to_commit()?.id().to_string()),
+                Err(GitCliError::CommandFailed(stderr)) => {
+                    let conflicted_files =
+                        git.get_conflicted_files(worktree_path).unwrap_or_default();
+                    if !conflicted_files.is_empty() || stderr.contains("CONFLICT") {
+                        let short = &sha[..sha.len().min(12)];
+                        return Err(GitServiceError::MergeConflicts {
+                            message: format!(
+                                "Cherry-picking {short} caused merge conflicts after {} of {} commits were applied. Resolve conflicts and then continue or abort.",
+                                picked.len(),
+                                commits.len()
+                            ),
+                            conflicted_files,
+                        });
+                    }
+                    // Nothing to resolve (e.g. the commit is already applied), so
+                    // don't leave the worktree mid cherry-pick
+                    if let Err(e) = git.abort_cherry_pick(worktree_path) {
+                        tracing::warn!("Failed to abort cherry-pick of {}: {}", sha, e);
+                    }
+                    return Err(GitServiceError::InvalidRepository(format!(
+                        "Cherry-pick of {sha} failed: {}",
+                        stderr.lines().next().unwrap_or("")
+                    )));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(picked)
+    }
 
-        // Ensure identity for any commits produced by rebase
-        self.ensure_cli_commit_identity(worktree_path)?;
+    /// Run the CLI rebase, turning conflicts into [`GitServiceError::MergeConflicts`]
+    fn run_rebase(
+        &self,
+        git: &GitCli,
+        worktree_repo: &Repository,
+        worktree_path: &Path,
+        new_base_branch: &str,
+        old_base_branch: &str,
+        task_branch: &str,
+    ) -> Result<(), GitServiceError> {
         // Use git CLI rebase to carry out the operation safely
         match git.rebase_onto(worktree_path, new_base_branch, old_base_branch, task_branch) {
             Ok(()) => {}
@@ -1460,10 +3486,7 @@ impl GitService {
                 )));
             }
         }
-
-        // Return resulting HEAD commit
-        let final_commit = worktree_repo.head()?.peel_to_commit()?;
-        Ok(final_commit.id().to_string())
+        Ok(())
     }
 
     pub fn find_branch_type(
@@ -1519,6 +3542,87 @@ impl GitService {
         Ok(())
     }
 
+    /// Delete a local branch. Like `git branch -d`, a branch whose commits are
+    /// not in its upstream (or HEAD, without one) is kept unless `force` is set.
+    /// A branch checked out in any worktree is never deleted.
+    pub fn delete_local_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        force: bool,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut branch = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?;
+
+        if let Some(worktree) = GitCli::new()
+            .list_worktrees(repo_path)?
+            .into_iter()
+            .find(|wt| wt.branch.as_deref() == Some(branch_name))
+        {
+            return Err(GitServiceError::BranchCheckedOutElsewhere {
+                branch: branch_name.to_string(),
+                worktree_path: worktree.path,
+            });
+        }
+
+        if !force {
+            let branch_oid = branch.get().peel_to_commit()?.id();
+            let merged_into = match branch.upstream() {
+                Ok(upstream) => upstream.get().peel_to_commit()?.id(),
+                Err(_) => repo.head()?.peel_to_commit()?.id(),
+            };
+            if merged_into != branch_oid && !repo.graph_descendant_of(merged_into, branch_oid)? {
+                return Err(GitServiceError::BranchNotMerged(branch_name.to_string()));
+            }
+        }
+
+        branch.delete()?;
+        Ok(())
+    }
+
+    /// Delete `branch_name` on `remote` and drop its remote-tracking branch.
+    /// The remote's default branch is never deleted.
+    pub fn delete_remote_branch(
+        &self,
+        repo_path: &Path,
+        remote: &str,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let remote_url = repo
+            .find_remote(remote)?
+            .url()
+            .map(str::to_string)
+            .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))?;
+
+        let default_branch = repo
+            .find_reference(&format!("refs/remotes/{remote}/HEAD"))
+            .ok()
+            .and_then(|head| head.symbolic_target().map(str::to_string));
+        if default_branch.as_deref() == Some(&format!("refs/remotes/{remote}/{branch_name}")) {
+            return Err(GitServiceError::InvalidRepository(format!(
+                "{branch_name} is the default branch of {remote}"
+            )));
+        }
+
+        let git = GitCli::new();
+        if !git.check_remote_branch_exists(repo_path, &remote_url, branch_name)? {
+            return Err(GitServiceError::BranchNotFound(format!(
+                "{remote}/{branch_name}"
+            )));
+        }
+        git.delete_remote_branch(repo_path, &remote_url, branch_name)?;
+
+        if let Ok(mut tracking) =
+            repo.find_branch(&format!("{remote}/{branch_name}"), BranchType::Remote)
+        {
+            tracking.delete()?;
+        }
+        Ok(())
+    }
+
     /// Return true if a rebase is currently in progress in this worktree.
     pub fn is_rebase_in_progress(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
         let git = GitCli::new();
@@ -1561,6 +3665,304 @@ impl GitService {
         })
     }
 
+    /// Path of a conflicted index entry, from whichever stage is present
+    fn conflict_path(conflict: &git2::IndexConflict) -> Option<String> {
+        [&conflict.our, &conflict.their, &conflict.ancestor]
+            .into_iter()
+            .flatten()
+            .next()
+            .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+    }
+
+    /// Conflicted files in the worktree with the base/ours/theirs contents
+    /// recorded in the index, for showing conflicts to the user.
+    pub fn get_conflict_details(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Vec<FileConflict>, GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        let index = repo.index()?;
+        let max_inline_bytes = self.file_content().max_inline_bytes;
+
+        let mut conflicts = Vec::new();
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let Some(path) = Self::conflict_path(&conflict) else {
+                continue;
+            };
+
+            let mut content_omitted = false;
+            let mut stage = |entry: Option<git2::IndexEntry>| -> Result<_, GitServiceError> {
+                // Submodule (gitlink) entries have no blob to show
+                let Some(entry) = entry.filter(|entry| entry.mode != 0o160000) else {
+                    return Ok(None);
+                };
+                let blob = repo.find_blob(entry.id)?;
+                if blob.size() > max_inline_bytes {
+                    content_omitted = true;
+                    return Ok(None);
+                }
+                let content = self.blob_to_string(&blob);
+                content_omitted |= content.is_none();
+                Ok(content)
+            };
+            let base = stage(conflict.ancestor)?;
+            let ours = stage(conflict.our)?;
+            let theirs = stage(conflict.their)?;
+
+            conflicts.push(FileConflict {
+                path,
+                base,
+                ours,
+                theirs,
+                content_omitted,
+            });
+        }
+        Ok(conflicts)
+    }
+
+    /// Who last changed each line of `rel_path` as of `at_commit` (`HEAD` when
+    /// `None`). Uncommitted edits are not included. Files that are untracked at
+    /// that commit, binary or larger than the inline size limit have no lines.
+    pub fn blame_file(
+        &self,
+        repo_path: &Path,
+        rel_path: &str,
+        at_commit: Option<&str>,
+    ) -> Result<Vec<BlameLine>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let commit = repo
+            .revparse_single(at_commit.unwrap_or("HEAD"))?
+            .peel_to_commit()?;
+        let path = Path::new(rel_path);
+
+        let entry = match commit.tree()?.get_path(path) {
+            Ok(entry) => entry,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        // Directories and submodules have no lines to blame
+        let Ok(blob) = entry.to_object(&repo)?.into_blob() else {
+            return Ok(Vec::new());
+        };
+        let options = self.file_content();
+        if blob.size() > options.max_inline_bytes || options.looks_binary(blob.content()) {
+            return Ok(Vec::new());
+        }
+
+        let mut blame_opts = git2::BlameOptions::new();
+        blame_opts.newest_commit(commit.id());
+        let blame = repo.blame_file(path, Some(&mut blame_opts))?;
+
+        let mut authors: HashMap<git2::Oid, (Option<String>, Option<String>, DateTime<Utc>)> =
+            HashMap::new();
+        let mut lines = Vec::new();
+        for hunk in blame.iter() {
+            let commit_id = hunk.final_commit_id();
+            let (name, email, authored_at) = match authors.get(&commit_id) {
+                Some(author) => author.clone(),
+                None => {
+                    let author = repo.find_commit(commit_id)?.author();
+                    let info = (
+                        author.name().map(str::to_string),
+                        author.email().map(str::to_string),
+                        DateTime::from_timestamp(author.when().seconds(), 0)
+                            .unwrap_or_else(Utc::now),
+                    );
+                    authors.insert(commit_id, info.clone());
+                    info
+                }
+            };
+            for offset in 0..hunk.lines_in_hunk() {
+                lines.push(BlameLine {
+                    line: hunk.final_start_line() + offset,
+                    commit: commit_id.to_string(),
+                    author_name: name.clone(),
+                    author_email: email.clone(),
+                    authored_at,
+                });
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Whether `rel_path` is ignored by `.gitignore`, `.git/info/exclude` or
+    /// `core.excludesFile`. The path does not need to exist.
+    pub fn is_path_ignored(
+        &self,
+        repo_path: &Path,
+        rel_path: &str,
+    ) -> Result<bool, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        Ok(repo.is_path_ignored(rel_path)?)
+    }
+
+    /// The paths of `paths` (relative to the repository root) that are not
+    /// ignored, in their original order. Opens the repository once for all of them.
+    pub fn filter_ignored(
+        &self,
+        repo_path: &Path,
+        paths: &[String],
+    ) -> Result<Vec<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut kept = Vec::with_capacity(paths.len());
+        for path in paths {
+            if !repo.is_path_ignored(path)? {
+                kept.push(path.clone());
+            }
+        }
+        Ok(kept)
+    }
+
+    /// Stashes of the repository, newest first. All worktrees of a repository
+    /// share one stash list.
+    pub fn list_stashes(&self, worktree_path: &Path) -> Result<Vec<StashEntry>, GitServiceError> {
+        let mut repo = self.open_repo(worktree_path)?;
+        let mut stashes = Vec::new();
+        repo.stash_foreach(|index, message, oid| {
+            stashes.push((index, message.to_string(), *oid));
+            true
+        })?;
+
+        stashes
+            .into_iter()
+            .map(|(index, message, oid)| {
+                let commit = repo.find_commit(oid)?;
+                Ok(StashEntry {
+                    index,
+                    sha: oid.to_string(),
+                    branch: stash_branch(&message),
+                    message,
+                    created_at: DateTime::from_timestamp(commit.time().seconds(), 0)
+                        .unwrap_or_else(Utc::now),
+                })
+            })
+            .collect()
+    }
+
+    fn find_stash(
+        &self,
+        worktree_path: &Path,
+        index: usize,
+    ) -> Result<StashEntry, GitServiceError> {
+        self.list_stashes(worktree_path)?
+            .into_iter()
+            .find(|stash| stash.index == index)
+            .ok_or(GitServiceError::StashNotFound(index))
+    }
+
+    /// Changes to tracked files saved in `stash@{index}`, relative to the
+    /// commit it was made on. Stashed untracked files are not included.
+    pub fn stash_diff(
+        &self,
+        worktree_path: &Path,
+        index: usize,
+    ) -> Result<Vec<Diff>, GitServiceError> {
+        let stash = self.find_stash(worktree_path, index)?;
+        let repo = self.open_repo(worktree_path)?;
+        let diff = Self::commit_diff(&repo, &stash.sha, None)?;
+        self.convert_diff_to_file_diffs(diff, &repo)
+    }
+
+    /// Apply `stash@{index}` to the worktree without dropping it
+    pub fn stash_apply(&self, worktree_path: &Path, index: usize) -> Result<(), GitServiceError> {
+        let stash_lock = self.stash_lock(worktree_path)?;
+        let _stashes = stash_lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.find_stash(worktree_path, index)?;
+        let git = GitCli::new();
+        match git.stash_apply(worktree_path, index) {
+            Ok(()) => Ok(()),
+            Err(GitCliError::CommandFailed(stderr)) if stderr.contains("CONFLICT") => {
+                let conflicted_files = git.get_conflicted_files(worktree_path).unwrap_or_default();
+                Err(GitServiceError::MergeConflicts {
+                    message: format!(
+                        "Applying stash@{{{index}}} caused merge conflicts. Resolve them before continuing."
+                    ),
+                    conflicted_files,
+                })
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn stash_drop(&self, worktree_path: &Path, index: usize) -> Result<(), GitServiceError> {
+        let stash_lock = self.stash_lock(worktree_path)?;
+        let _stashes = stash_lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.find_stash(worktree_path, index)?;
+        GitCli::new().stash_drop(worktree_path, index)?;
+        Ok(())
+    }
+
+    /// The lock guarding the stash list `worktree_path` shares with the other
+    /// worktrees of its repository
+    fn stash_lock(&self, worktree_path: &Path) -> Result<Arc<Mutex<()>>, GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        let common_dir = std::fs::canonicalize(repo.commondir())
+            .unwrap_or_else(|_| repo.commondir().to_path_buf());
+        let mut locks = self.stash_locks.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(locks.entry(common_dir).or_default().clone())
+    }
+
+    /// Stash uncommitted changes, untracked files included. Returns `None` when
+    /// there was nothing to stash.
+    pub fn stash_worktree(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Option<StashRef>, GitServiceError> {
+        // `git stash create` would hand back the commit directly but cannot
+        // include untracked files, so the stash is found by a message no other
+        // stash has instead of by its position
+        static AUTO_STASH_COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let tag = format!(
+            "{AUTO_STASH_MESSAGE} [{}-{}-{}]",
+            std::process::id(),
+            Utc::now().timestamp_micros(),
+            AUTO_STASH_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let stash_lock = self.stash_lock(worktree_path)?;
+        let _stashes = stash_lock.lock().unwrap_or_else(|e| e.into_inner());
+        GitCli::new().stash_push(worktree_path, &tag)?;
+
+        // git exits successfully without stashing when there are no changes
+        Ok(self
+            .list_stashes(worktree_path)?
+            .into_iter()
+            .find(|stash| stash.message.ends_with(&tag))
+            .map(|stash| StashRef {
+                sha: stash.sha,
+                message: stash.message,
+            }))
+    }
+
+    /// Restore and drop a stash made by [`Self::stash_worktree`]. On conflict the
+    /// stash is kept and [`GitServiceError::StashPopConflict`] is returned.
+    pub fn pop_stash(&self, worktree_path: &Path, stash: &StashRef) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        // Applied by commit, so it doesn't matter where the stash sits in the list
+        match git.stash_apply_commit(worktree_path, &stash.sha) {
+            Ok(()) => {}
+            Err(GitCliError::CommandFailed(stderr)) if stderr.contains("CONFLICT") => {
+                return Err(GitServiceError::StashPopConflict {
+                    stash: stash.clone(),
+                    conflicted_files: git.get_conflicted_files(worktree_path).unwrap_or_default(),
+                });
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        let stash_lock = self.stash_lock(worktree_path)?;
+        let _stashes = stash_lock.lock().unwrap_or_else(|e| e.into_inner());
+        match self
+            .list_stashes(worktree_path)?
+            .into_iter()
+            .find(|entry| entry.sha == stash.sha)
+        {
+            Some(entry) => git.stash_drop(worktree_path, entry.index)?,
+            None => tracing::debug!("{} was already dropped", stash),
+        }
+        Ok(())
+    }
+
     /// Abort an in-progress rebase in this worktree (no-op if none).
     pub fn abort_rebase(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
         let git = GitCli::new();
@@ -1669,6 +4071,54 @@ impl GitService {
             .collect())
     }
 
+    /// Parse `.gitmodules` at the repository root. Returns an empty list when the
+    /// repository has no submodules.
+    pub fn list_submodules(&self, repo_path: &Path) -> Result<Vec<SubmoduleInfo>, GitServiceError> {
+        let gitmodules = repo_path.join(".gitmodules");
+        if !gitmodules.is_file() {
+            return Ok(Vec::new());
+        }
+
+        let config = git2::Config::open(&gitmodules)?;
+        let mut submodules: Vec<SubmoduleInfo> = Vec::new();
+        let mut entries = config.entries(Some(r"^submodule\..*"))?;
+        while let Some(entry) = entries.next() {
+            let entry = entry?;
+            let (Some(key), Some(value)) = (entry.name(), entry.value()) else {
+                continue;
+            };
+            // Keys look like `submodule.<name>.<field>`; names may contain dots
+            let Some((name, field)) = key
+                .strip_prefix("submodule.")
+                .and_then(|rest| rest.rsplit_once('.'))
+            else {
+                continue;
+            };
+
+            let idx = match submodules.iter().position(|s| s.name == name) {
+                Some(idx) => idx,
+                None => {
+                    submodules.push(SubmoduleInfo {
+                        name: name.to_string(),
+                        path: name.to_string(),
+                        url: None,
+                        branch: None,
+                    });
+                    submodules.len() - 1
+                }
+            };
+            let submodule = &mut submodules[idx];
+            match field {
+                "path" => submodule.path = value.to_string(),
+                "url" => submodule.url = Some(value.to_string()),
+                "branch" => submodule.branch = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        Ok(submodules)
+    }
+
     pub fn check_remote_branch_exists(
         &self,
         repo_path: &Path,
@@ -1681,17 +4131,23 @@ impl GitService {
             .map_err(GitServiceError::from)
     }
 
+    /// Fetch a branch from `remote_url` into the local branch of the same name,
+    /// reporting transfer progress to `progress` while it runs
     pub fn fetch_branch(
         &self,
         repo_path: &Path,
         remote_url: &str,
         branch_name: &str,
+        cancel: Option<&CancellationToken>,
+        progress: Option<FetchProgressSink<'_>>,
     ) -> Result<(), GitServiceError> {
         let git_cli = GitCli::new();
         let refspec = format!("+refs/heads/{branch_name}:refs/heads/{branch_name}");
-        git_cli
-            .fetch_with_refspec(repo_path, remote_url, &refspec)
-            .map_err(GitServiceError::from)
+        match git_cli.fetch_with_refspec(repo_path, remote_url, &refspec, cancel, progress) {
+            Ok(()) => Ok(()),
+            Err(GitCliError::Cancelled) => Err(GitServiceError::Cancelled),
+            Err(e) => Err(e.into()),
+        }
     }
 
     pub fn resolve_remote_for_branch(
@@ -1728,20 +4184,118 @@ impl GitService {
         })
     }
 
+    /// The branch a local branch tracks, e.g. `origin/main`, or `None` when no
+    /// upstream is configured. The upstream does not need to have been fetched.
+    pub fn get_upstream(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<Option<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let branch = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?;
+        let refname = branch
+            .get()
+            .name()
+            .ok_or_else(|| GitServiceError::InvalidRepository("Invalid branch ref".into()))?;
+
+        let upstream_buf = match repo.branch_upstream_name(refname) {
+            Ok(buf) => buf,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let upstream = str::from_utf8(&upstream_buf).map_err(|e| {
+            GitServiceError::InvalidRepository(format!(
+                "Invalid upstream for branch {branch_name}: {e}"
+            ))
+        })?;
+        Ok(Some(
+            upstream
+                .strip_prefix("refs/remotes/")
+                .or_else(|| upstream.strip_prefix("refs/heads/"))
+                .unwrap_or(upstream)
+                .to_string(),
+        ))
+    }
+
+    /// Set the branch a local branch tracks (`origin/main`, or a local branch
+    /// name), or clear it with `None`. The upstream branch must exist locally.
+    pub fn set_upstream(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        upstream_ref: Option<&str>,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut branch = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?;
+        if let Some(upstream) = upstream_ref {
+            Self::find_branch(&repo, upstream)?;
+        }
+        branch.set_upstream(upstream_ref)?;
+        Ok(())
+    }
+
+    /// [`Self::push_branch`] with a plain or forced push
     pub fn push_to_remote(
         &self,
         worktree_path: &Path,
         branch_name: &str,
         force: bool,
+    ) -> Result<(), GitServiceError> {
+        let mode = if force {
+            PushMode::Force
+        } else {
+            PushMode::Normal
+        };
+        self.push_branch(worktree_path, branch_name, mode)
+    }
+
+    /// The commit `branch_name` points at on its push remote, asked of the remote
+    /// directly; `None` when the branch doesn't exist there
+    pub fn remote_branch_sha(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+    ) -> Result<Option<String>, GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
+        let remote = self.push_remote_for_branch(&repo, worktree_path, branch_name)?;
+        Ok(GitCli::new().remote_branch_sha(worktree_path, &remote.url, branch_name)?)
+    }
+
+    /// Push a branch to its push remote and track it there
+    pub fn push_branch(
+        &self,
+        worktree_path: &Path,
+        branch_name: &str,
+        mode: PushMode,
     ) -> Result<(), GitServiceError> {
         let repo = Repository::open(worktree_path)?;
         self.check_worktree_clean(&repo)?;
 
-        // Get the remote
-        let remote = self.default_remote(&repo, worktree_path)?;
+        let remote = self.push_remote_for_branch(&repo, worktree_path, branch_name)?;
 
         let git_cli = GitCli::new();
-        if let Err(e) = git_cli.push(worktree_path, &remote.url, branch_name, force) {
+        let hooks_path = Self::worktree_hooks_path(&repo);
+        let hooks_path = hooks_path.as_deref();
+        let result = match mode {
+            PushMode::Normal => {
+                git_cli.push(worktree_path, &remote.url, branch_name, false, hooks_path)
+            }
+            PushMode::Force => {
+                git_cli.push(worktree_path, &remote.url, branch_name, true, hooks_path)
+            }
+            PushMode::ForceWithLease { expected_sha } => git_cli.push_with_lease(
+                worktree_path,
+                &remote.url,
+                branch_name,
+                Some(&expected_sha),
+                hooks_path,
+            ),
+        };
+        if let Err(e) = result {
             tracing::error!("Push to remote failed: {}", e);
             return Err(e.into());
         }
@@ -1763,12 +4317,15 @@ impl GitService {
         Ok(())
     }
 
-    /// Fetch from remote repository using native git authentication
+    /// Fetch from remote repository using native git authentication. The fetch
+    /// is aborted with [`GitServiceError::Cancelled`] once `cancel` fires.
     fn fetch_from_remote(
         &self,
         repo: &Repository,
         remote: &Remote,
         refspec: &str,
+        cancel: Option<&CancellationToken>,
+        progress: Option<FetchProgressSink<'_>>,
     ) -> Result<(), GitServiceError> {
         // Get the remote
         let remote_url = remote
@@ -1776,11 +4333,17 @@ impl GitService {
             .ok_or_else(|| GitServiceError::InvalidRepository("Remote has no URL".to_string()))?;
 
         let git_cli = GitCli::new();
-        if let Err(e) = git_cli.fetch_with_refspec(repo.path(), remote_url, refspec) {
-            tracing::error!("Fetch from GitHub failed: {}", e);
-            return Err(e.into());
+        match git_cli.fetch_with_refspec(repo.path(), remote_url, refspec, cancel, progress) {
+            Ok(()) => Ok(()),
+            Err(GitCliError::Cancelled) => {
+                tracing::debug!("Fetch from {} cancelled", remote_url);
+                Err(GitServiceError::Cancelled)
+            }
+            Err(e) => {
+                tracing::error!("Fetch from GitHub failed: {}", e);
+                Err(e.into())
+            }
         }
-        Ok(())
     }
 
     /// Fetch from remote repository using native git authentication
@@ -1798,7 +4361,7 @@ impl GitService {
         let remote_prefix = format!("refs/remotes/{remote_name}/");
         let src_ref = dest_ref.replacen(&remote_prefix, "refs/heads/", 1);
         let refspec = format!("+{src_ref}:{dest_ref}");
-        self.fetch_from_remote(repo, &remote, &refspec)
+        self.fetch_from_remote(repo, &remote, &refspec, None, None)
     }
 
     /// Fetch from remote repository using native git authentication
@@ -1806,21 +4369,28 @@ impl GitService {
         &self,
         repo: &Repository,
         remote: &Remote,
+        cancel: Option<&CancellationToken>,
+        progress: Option<FetchProgressSink<'_>>,
     ) -> Result<(), GitServiceError> {
         let default_remote = self.default_remote(repo, repo.path())?;
         let remote_name = remote.name().unwrap_or(&default_remote.name);
         let refspec = format!("+refs/heads/*:refs/remotes/{remote_name}/*");
-        self.fetch_from_remote(repo, remote, &refspec)
+        self.fetch_from_remote(repo, remote, &refspec, cancel, progress)
     }
 
-    /// Clone a repository to the specified directory
+    /// Clone a repository to the specified directory. With `depth`, only that
+    /// many commits of history are fetched; operations needing older commits then
+    /// fail with [`GitServiceError::ShallowRepository`]. Transfer progress is
+    /// reported to `progress`.
     #[cfg(feature = "cloud")]
     pub fn clone_repository(
         clone_url: &str,
         target_path: &Path,
         token: Option<&str>,
+        depth: Option<u32>,
+        progress: Option<FetchProgressSink<'_>>,
     ) -> Result<Repository, GitServiceError> {
-        use git2::{Cred, FetchOptions, RemoteCallbacks};
+        use git2::{Cred, FetchOptions, ProxyOptions, RemoteCallbacks};
 
         if let Some(parent) = target_path.parent() {
             std::fs::create_dir_all(parent)?;
@@ -1849,11 +4419,26 @@ impl GitService {
                 Cred::ssh_key(username_from_url.unwrap_or("git"), None, &key_path, None)
             });
         }
+        if let Some(progress) = progress {
+            callbacks.transfer_progress(move |stats| {
+                progress(FetchProgress::from_git2(&stats));
+                true
+            });
+        }
 
         // Set up fetch options with our callbacks
         let mut fetch_opts = FetchOptions::new();
         fetch_opts.remote_callbacks(callbacks);
 
+        // Honor http.proxy and the HTTP(S)_PROXY environment, like the git CLI
+        let mut proxy_opts = ProxyOptions::new();
+        proxy_opts.auto();
+        fetch_opts.proxy_options(proxy_opts);
+
+        if let Some(depth) = depth {
+            fetch_opts.depth(depth.try_into().unwrap_or(i32::MAX));
+        }
+
         // Create a repository builder with fetch options
         let mut builder = git2::build::RepoBuilder::new();
         builder.fetch_options(fetch_opts);
@@ -1869,77 +4454,142 @@ impl GitService {
         Ok(repo)
     }
 
-    /// Collect file statistics from recent commits for ranking purposes
+    /// Collect file statistics from recent commits for ranking purposes. Commits
+    /// are diffed against their parents on up to [`FILE_STATS_MAX_THREADS`]
+    /// threads, each with its own handle on the repository.
     pub fn collect_recent_file_stats(
         &self,
         repo_path: &Path,
         commit_limit: usize,
     ) -> Result<HashMap<String, FileStat>, GitServiceError> {
         let repo = self.open_repo(repo_path)?;
-        let mut stats: HashMap<String, FileStat> = HashMap::new();
 
         // Set up revision walk from HEAD
         let mut revwalk = repo.revwalk()?;
         revwalk.push_head()?;
         revwalk.set_sorting(Sort::TIME)?;
+        let oids = revwalk.take(commit_limit).collect::<Result<Vec<_>, _>>()?;
+
+        let threads = std::thread::available_parallelism()
+            .map_or(1, NonZeroUsize::get)
+            .clamp(1, FILE_STATS_MAX_THREADS)
+            .min(oids.len());
+        let next = AtomicUsize::new(0);
+        let mut commits = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| -> Result<Vec<_>, GitServiceError> {
+                        let repo = self.open_repo(repo_path)?;
+                        let mut changes = Vec::new();
+                        loop {
+                            let commit_index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(&oid) = oids.get(commit_index) else {
+                                break;
+                            };
+                            changes.push((commit_index, Self::commit_changed_paths(&repo, oid)?));
+                        }
+                        Ok(changes)
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        // Merge in history order, as the sequential walk did
+        commits.sort_unstable_by_key(|(commit_index, _)| *commit_index);
+        let mut stats: HashMap<String, FileStat> = HashMap::new();
+        for (commit_index, (commit_time, paths)) in commits {
+            for path in paths {
+                // Update or insert file stats
+                let stat = stats.entry(path).or_insert(FileStat {
+                    last_index: commit_index,
+                    commit_count: 0,
+                    last_time: commit_time,
+                });
 
-        // Iterate through recent commits
-        for (commit_index, oid_result) in revwalk.take(commit_limit).enumerate() {
-            let oid = oid_result?;
-            let commit = repo.find_commit(oid)?;
-
-            // Get commit timestamp
-            let commit_time = {
-                let time = commit.time();
-                DateTime::from_timestamp(time.seconds(), 0).unwrap_or_else(Utc::now)
-            };
-
-            // Get the commit tree
-            let commit_tree = commit.tree()?;
-
-            // For the first commit (no parent), diff against empty tree
-            let parent_tree = if commit.parent_count() == 0 {
-                None
-            } else {
-                Some(commit.parent(0)?.tree()?)
-            };
-
-            // Create diff between parent and current commit
-            let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit_tree), None)?;
+                // Increment commit count
+                stat.commit_count += 1;
 
-            // Process each changed file in this commit
-            diff.foreach(
-                &mut |delta, _progress| {
-                    // Get the file path - prefer new file path, fall back to old
-                    if let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path())
-                    {
-                        let path_str = path.to_string_lossy().to_string();
+                // Keep the most recent change (smallest index)
+                if commit_index < stat.last_index {
+                    stat.last_index = commit_index;
+                    stat.last_time = commit_time;
+                }
+            }
+        }
 
-                        // Update or insert file stats
-                        let stat = stats.entry(path_str).or_insert(FileStat {
-                            last_index: commit_index,
-                            commit_count: 0,
-                            last_time: commit_time,
-                        });
+        Ok(stats)
+    }
 
-                        // Increment commit count
-                        stat.commit_count += 1;
+    /// Timestamp of a commit and the paths it changed relative to its first
+    /// parent (or to an empty tree for a root commit)
+    fn commit_changed_paths(
+        repo: &Repository,
+        oid: git2::Oid,
+    ) -> Result<(DateTime<Utc>, Vec<String>), GitServiceError> {
+        let commit = repo.find_commit(oid)?;
+        let commit_time =
+            DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_else(Utc::now);
 
-                        // Keep the most recent change (smallest index)
-                        if commit_index < stat.last_index {
-                            stat.last_index = commit_index;
-                            stat.last_time = commit_time;
-                        }
-                    }
+        let commit_tree = commit.tree()?;
+        let parent_tree = if commit.parent_count() == 0 {
+            None
+        } else {
+            Some(commit.parent(0)?.tree()?)
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit_tree), None)?;
+
+        // Prefer the new file path, fall back to the old one
+        let paths = diff
+            .deltas()
+            .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        Ok((commit_time, paths))
+    }
+}
 
-                    true // Continue iteration
-                },
-                None, // No binary callback
-                None, // No hunk callback
-                None, // No line callback
-            )?;
+/// Whether a worktree directory holds anything besides its `.git` link
+fn has_worktree_files(worktree_path: &Path) -> std::io::Result<bool> {
+    for entry in std::fs::read_dir(worktree_path)? {
+        if entry?.file_name() != ".git" {
+            return Ok(true);
         }
+    }
+    Ok(false)
+}
 
-        Ok(stats)
+/// A sibling path, not yet taken, to move a broken worktree's files to
+fn set_aside_path(worktree_path: &Path) -> PathBuf {
+    let name = worktree_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut suffix = 0;
+    loop {
+        let candidate = worktree_path.with_file_name(match suffix {
+            0 => format!("{name}.broken"),
+            n => format!("{name}.broken-{n}"),
+        });
+        if !candidate.exists() {
+            return candidate;
+        }
+        suffix += 1;
     }
 }
+
+/// Compare paths after resolving symlinks, falling back to the paths as given
+fn same_path(a: &Path, b: &Path) -> bool {
+    let canonical = |p: &Path| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+    canonical(a) == canonical(b)
+}
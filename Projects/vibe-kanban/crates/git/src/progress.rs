@@ -0,0 +1,136 @@
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Transfer state of a fetch or clone, reported while it runs
+#[derive(Debug, Clone, Copy, Default, Serialize, TS, PartialEq, Eq)]
+pub struct FetchProgress {
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    /// Objects to transfer; 0 until the remote has told us
+    pub total_objects: usize,
+    pub received_bytes: usize,
+    pub indexed_deltas: usize,
+    pub total_deltas: usize,
+}
+
+/// Receives [`FetchProgress`] updates. Called from the thread doing the fetch.
+pub type FetchProgressSink<'a> = &'a (dyn Fn(FetchProgress) + Send + Sync);
+
+impl FetchProgress {
+    /// Progress as reported by libgit2
+    pub(crate) fn from_git2(stats: &git2::Progress<'_>) -> Self {
+        Self {
+            received_objects: stats.received_objects(),
+            indexed_objects: stats.indexed_objects(),
+            total_objects: stats.total_objects(),
+            received_bytes: stats.received_bytes(),
+            indexed_deltas: stats.indexed_deltas(),
+            total_deltas: stats.total_deltas(),
+        }
+    }
+}
+
+/// Fold one line of `git fetch --progress` stderr into `progress`. Returns
+/// false for lines that carry no transfer state, e.g. `remote: Counting objects`.
+///
+/// ```text
+/// Receiving objects:  45% (123/273), 1.20 MiB | 2.00 MiB/s
+/// Resolving deltas: 100% (20/20), done.
+/// ```
+pub(crate) fn apply_progress_line(progress: &mut FetchProgress, line: &str) -> bool {
+    let line = line.trim();
+    if let Some(rest) = line.strip_prefix("Receiving objects:") {
+        let Some((done, total)) = counts(rest) else {
+            return false;
+        };
+        // index-pack indexes objects as they arrive
+        progress.received_objects = done;
+        progress.indexed_objects = done;
+        progress.total_objects = total;
+        if let Some(bytes) = rest
+            .split_once("), ")
+            .and_then(|(_, size)| parse_size(size.split(" |").next()?))
+        {
+            progress.received_bytes = bytes;
+        }
+        true
+    } else if let Some(rest) = line.strip_prefix("Resolving deltas:") {
+        let Some((done, total)) = counts(rest) else {
+            return false;
+        };
+        progress.indexed_deltas = done;
+        progress.total_deltas = total;
+        true
+    } else {
+        false
+    }
+}
+
+/// `(done/total)` from `  45% (123/273), ...`
+fn counts(rest: &str) -> Option<(usize, usize)> {
+    let (_, after) = rest.split_once('(')?;
+    let (inner, _) = after.split_once(')')?;
+    let (done, total) = inner.split_once('/')?;
+    Some((done.trim().parse().ok()?, total.trim().parse().ok()?))
+}
+
+/// Bytes in a size printed by git, e.g. `1.20 MiB` or `512 bytes`
+fn parse_size(size: &str) -> Option<usize> {
+    let (value, unit) = size.trim().split_once(' ')?;
+    let value: f64 = value.parse().ok()?;
+    let multiplier = match unit.trim_end_matches(',') {
+        "byte" | "bytes" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((value * multiplier) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_receiving_and_resolving_lines() {
+        let mut progress = FetchProgress::default();
+        assert!(!apply_progress_line(
+            &mut progress,
+            "remote: Counting objects: 100% (5/5), done."
+        ));
+        assert!(apply_progress_line(
+            &mut progress,
+            "Receiving objects:  45% (123/273), 1.50 MiB | 2.00 MiB/s"
+        ));
+        assert_eq!(progress.received_objects, 123);
+        assert_eq!(progress.indexed_objects, 123);
+        assert_eq!(progress.total_objects, 273);
+        assert_eq!(progress.received_bytes, 1_572_864);
+
+        assert!(apply_progress_line(
+            &mut progress,
+            "Resolving deltas: 100% (20/20), done."
+        ));
+        assert_eq!((progress.indexed_deltas, progress.total_deltas), (20, 20));
+        assert_eq!(progress.total_objects, 273);
+    }
+
+    #[test]
+    fn keeps_bytes_when_line_has_none() {
+        let mut progress = FetchProgress {
+            received_bytes: 42,
+            ..Default::default()
+        };
+        assert!(apply_progress_line(
+            &mut progress,
+            "Receiving objects:   1% (1/100)"
+        ));
+        assert_eq!(progress.received_bytes, 42);
+        assert!(apply_progress_line(
+            &mut progress,
+            "Receiving objects: 100% (100/100), 512 bytes | 512.00 KiB/s, done."
+        ));
+        assert_eq!(progress.received_bytes, 512);
+    }
+}
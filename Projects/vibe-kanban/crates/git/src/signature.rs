@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Result of checking a commit's or tag's signature with `git verify-commit`
+/// / `git verify-tag`
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+#[ts(tag = "status", rename_all = "snake_case")]
+pub enum SignatureStatus {
+    Unsigned,
+    /// Verified against a key trusted at least marginally (gpg) or listed in
+    /// the allowed signers file (SSH)
+    Good {
+        signer: String,
+        key: Option<String>,
+    },
+    /// The signature does not match, or its key is expired or revoked
+    Bad {
+        signer: Option<String>,
+    },
+    /// Signed with a key that is not known or not trusted here
+    UnknownKey {
+        key: Option<String>,
+    },
+    /// Signed, but the signature could not be checked, e.g. because gpg is
+    /// not installed or SSH allowed signers are not configured
+    Unknown {
+        reason: String,
+    },
+}
+
+/// Interpret the `--raw` output of `git verify-commit` / `git verify-tag` for
+/// a signed object. Handles gpg status lines and `ssh-keygen -Y` messages.
+pub(crate) fn parse_verify_output(output: &str) -> SignatureStatus {
+    let mut unknown_key = None;
+    // gpg reports GOODSIG before VALIDSIG and the key's TRUST_* level
+    let mut good_sig = None;
+    let mut valid = false;
+    let mut trusted = false;
+    for line in output.lines().map(str::trim) {
+        if let Some(status) = line.strip_prefix("[GNUPG:] ") {
+            let mut parts = status.splitn(3, ' ');
+            let keyword = parts.next().unwrap_or_default();
+            let key = parts.next().map(str::to_string);
+            let signer = parts.next().map(str::to_string);
+            match keyword {
+                "GOODSIG" => good_sig = Some((signer.unwrap_or_default(), key)),
+                "VALIDSIG" => valid = true,
+                "TRUST_MARGINAL" | "TRUST_FULLY" | "TRUST_ULTIMATE" => trusted = true,
+                "BADSIG" | "EXPKEYSIG" | "EXPSIG" | "REVKEYSIG" => {
+                    return SignatureStatus::Bad { signer };
+                }
+                "NO_PUBKEY" => unknown_key = Some(SignatureStatus::UnknownKey { key }),
+                _ => {}
+            }
+        } else if let Some(rest) = line.strip_prefix("Good \"git\" signature for ") {
+            // Good "git" signature for <principal> with <type> key <fingerprint>
+            let (signer, key) = match rest.split_once(" with ") {
+                Some((signer, key)) => (signer, key.split(' ').next_back()),
+                None => (rest, None),
+            };
+            return SignatureStatus::Good {
+                signer: signer.to_string(),
+                key: key.map(str::to_string),
+            };
+        } else if let Some(rest) = line.strip_prefix("Good \"git\" signature with ") {
+            // No principal in the allowed signers file matched the key
+            return SignatureStatus::UnknownKey {
+                key: rest.split(' ').next_back().map(str::to_string),
+            };
+        } else if line.starts_with("Signature verification failed")
+            || line.starts_with("Could not verify signature")
+        {
+            return SignatureStatus::Bad { signer: None };
+        }
+    }
+
+    if let Some((signer, key)) = good_sig {
+        // A good signature from a key with undefined or no trust
+        return if valid && trusted {
+            SignatureStatus::Good { signer, key }
+        } else {
+            SignatureStatus::UnknownKey { key }
+        };
+    }
+
+    unknown_key.unwrap_or_else(|| SignatureStatus::Unknown {
+        reason: output
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("signature could not be verified")
+            .to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_gpg_status_lines() {
+        let good = "[GNUPG:] NEWSIG\n[GNUPG:] GOODSIG 1234ABCD Alice <alice@example.com>\n[GNUPG:] VALIDSIG FPR\n[GNUPG:] TRUST_FULLY 0 pgp\n";
+        assert_eq!(
+            parse_verify_output(good),
+            SignatureStatus::Good {
+                signer: "Alice <alice@example.com>".to_string(),
+                key: Some("1234ABCD".to_string()),
+            }
+        );
+
+        let untrusted = "[GNUPG:] GOODSIG 1234ABCD Alice <alice@example.com>\n[GNUPG:] VALIDSIG FPR\n[GNUPG:] TRUST_UNDEFINED 0 pgp\n";
+        assert_eq!(
+            parse_verify_output(untrusted),
+            SignatureStatus::UnknownKey {
+                key: Some("1234ABCD".to_string())
+            }
+        );
+
+        let missing =
+            "[GNUPG:] ERRSIG 1234ABCD 1 8 00 1700000000 9 -\n[GNUPG:] NO_PUBKEY 1234ABCD\n";
+        assert_eq!(
+            parse_verify_output(missing),
+            SignatureStatus::UnknownKey {
+                key: Some("1234ABCD".to_string())
+            }
+        );
+
+        let bad = "[GNUPG:] BADSIG 1234ABCD Alice <alice@example.com>\n";
+        assert!(matches!(
+            parse_verify_output(bad),
+            SignatureStatus::Bad { .. }
+        ));
+    }
+
+    #[test]
+    fn parses_ssh_keygen_messages() {
+        assert_eq!(
+            parse_verify_output(
+                "Good \"git\" signature for alice@example.com with ED25519 key SHA256:abc\n"
+            ),
+            SignatureStatus::Good {
+                signer: "alice@example.com".to_string(),
+                key: Some("SHA256:abc".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_verify_output(
+                "No principal matched.\nGood \"git\" signature with ED25519 key SHA256:abc\n"
+            ),
+            SignatureStatus::UnknownKey {
+                key: Some("SHA256:abc".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn missing_tooling_is_unknown() {
+        assert_eq!(
+            parse_verify_output("error: cannot run gpg: No such file or directory\n"),
+            SignatureStatus::Unknown {
+                reason: "error: cannot run gpg: No such file or directory".to_string()
+            }
+        );
+    }
+}
@@ -10,10 +10,43 @@ pub fn is_valid_branch_prefix(prefix: &str) -> bool {
     git2::Branch::name_is_valid(&format!("{prefix}/x")).unwrap_or_default()
 }
 
+/// Whether `branch` lives under `prefix/`, e.g. `vk/1a2b-fix-login` under `vk`.
+/// An empty prefix accepts any branch.
+pub fn branch_has_prefix(branch: &str, prefix: &str) -> bool {
+    prefix.is_empty()
+        || branch
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .is_some_and(|rest| !rest.is_empty())
+}
+
+pub fn is_valid_remote_name(name: &str) -> bool {
+    git2::Remote::is_valid_name(name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_remote_names() {
+        assert!(is_valid_remote_name("origin"));
+        assert!(is_valid_remote_name("my-fork"));
+        assert!(!is_valid_remote_name(""));
+        assert!(!is_valid_remote_name("foo bar"));
+        assert!(!is_valid_remote_name("foo..bar"));
+    }
+
+    #[test]
+    fn test_branch_has_prefix() {
+        assert!(branch_has_prefix("vk/1a2b-fix-login", "vk"));
+        assert!(branch_has_prefix("anything", ""));
+        assert!(!branch_has_prefix("feature/x", "vk"));
+        assert!(!branch_has_prefix("vkx/fix", "vk"));
+        assert!(!branch_has_prefix("vk/", "vk"));
+        assert!(!branch_has_prefix("vk", "vk"));
+    }
+
     #[test]
     fn test_valid_prefixes() {
         assert!(is_valid_branch_prefix(""));
@@ -4,7 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use git::{GitCli, GitCliError, GitService};
+use git::{ConflictOp, FileConflict, GitCli, GitCliError, GitService, GitServiceError, PushMode};
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
 use tempfile::TempDir;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
@@ -279,7 +279,7 @@ fn push_reports_non_fast_forward() {
     let remote_url_string = remote.url().expect("origin url").to_string();
 
     let git_cli = GitCli::new();
-    let result = git_cli.push(&local_path, &remote_url_string, "main", false);
+    let result = git_cli.push(&local_path, &remote_url_string, "main", false, None);
     match result {
         Err(GitCliError::PushRejected(msg)) => {
             let lower = msg.to_ascii_lowercase();
@@ -293,6 +293,72 @@ fn push_reports_non_fast_forward() {
     }
 }
 
+#[test]
+fn force_with_lease_refuses_to_overwrite_unseen_commits() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote_path = temp_dir.path().join("remote.git");
+    Repository::init_bare(&remote_path).expect("init bare remote");
+    let remote_url = remote_path.to_str().expect("remote path str");
+
+    let seed_path = temp_dir.path().join("seed");
+    let service = GitService::new();
+    service
+        .initialize_repo_with_main_branch(&seed_path)
+        .expect("init seed repo");
+    let seed_repo = Repository::open(&seed_path).expect("open seed repo");
+    configure_user(&seed_repo);
+    seed_repo.remote("origin", remote_url).expect("add remote");
+    push_ref(&seed_repo, "refs/heads/main", "refs/heads/main");
+    Repository::open_bare(&remote_path)
+        .expect("open bare remote")
+        .set_head("refs/heads/main")
+        .expect("set remote HEAD");
+
+    let local_path = temp_dir.path().join("local");
+    let local_repo = Repository::clone(remote_url, &local_path).expect("clone local");
+    configure_user(&local_repo);
+    checkout_branch(&local_repo, "main");
+
+    // Someone else pushes after our clone
+    let updater_path = temp_dir.path().join("updater");
+    let updater_repo = Repository::clone(remote_url, &updater_path).expect("clone updater");
+    configure_user(&updater_repo);
+    checkout_branch(&updater_repo, "main");
+    write_file(&updater_path, "file.txt", "upstream change\n");
+    commit_all(&updater_repo, "upstream commit");
+    push_ref(&updater_repo, "refs/heads/main", "refs/heads/main");
+
+    write_file(&local_path, "file.txt", "local change\n");
+    commit_all(&local_repo, "local commit");
+    let local_head = local_repo.head().unwrap().target().unwrap();
+
+    // The commit we cloned is no longer what the remote has
+    let cloned_sha = local_repo
+        .refname_to_id("refs/remotes/origin/main")
+        .unwrap()
+        .to_string();
+    let lease = |expected_sha: String| PushMode::ForceWithLease { expected_sha };
+    match service.push_branch(&local_path, "main", lease(cloned_sha)) {
+        Err(GitServiceError::GitCLI(GitCliError::PushRejected(_))) => {}
+        other => panic!("expected push rejected, got {other:?}"),
+    }
+
+    // Once the upstream commit has been seen, the lease allows overwriting it,
+    // without needing a fetch
+    let seen_sha = service
+        .remote_branch_sha(&local_path, "main")
+        .unwrap()
+        .expect("remote branch exists");
+    service
+        .push_branch(&local_path, "main", lease(seen_sha))
+        .expect("push with lease");
+    let remote_main = Repository::open_bare(&remote_path)
+        .unwrap()
+        .refname_to_id("refs/heads/main")
+        .unwrap();
+    assert_eq!(remote_main, local_head);
+}
+
 #[test]
 fn fetch_with_missing_ref_returns_error() {
     let temp_dir = TempDir::new().unwrap();
@@ -319,7 +385,7 @@ fn fetch_with_missing_ref_returns_error() {
 
     let git_cli = GitCli::new();
     let refspec = "+refs/heads/missing:refs/remotes/origin/missing";
-    let result = git_cli.fetch_with_refspec(&local_path, remote_url, refspec);
+    let result = git_cli.fetch_with_refspec(&local_path, remote_url, refspec, None, None);
     match result {
         Err(GitCliError::CommandFailed(msg)) => {
             assert!(
@@ -392,6 +458,8 @@ fn push_and_fetch_roundtrip_updates_tracking_branch() {
             &consumer_path,
             &remote_url_string,
             "+refs/heads/main:refs/remotes/origin/main",
+            None,
+            None,
         )
         .expect("fetch succeeded");
 
@@ -420,6 +488,7 @@ fn rebase_preserves_untracked_files() {
         "new-base",
         "old-base",
         "feature",
+        false,
     );
     assert!(res.is_ok(), "rebase should succeed: {res:?}");
 
@@ -442,6 +511,7 @@ fn rebase_aborts_on_uncommitted_tracked_changes() {
         "new-base",
         "old-base",
         "feature",
+        false,
     );
     assert!(res.is_err(), "rebase should fail on dirty worktree");
 
@@ -449,6 +519,74 @@ fn rebase_aborts_on_uncommitted_tracked_changes() {
     assert_eq!(edited, "feat change (edited)\n");
 }
 
+#[test]
+fn rebase_with_auto_stash_restores_uncommitted_changes() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+
+    write_file(&worktree_path, "feat.txt", "feat change (edited)\n");
+    write_file(&worktree_path, "scratch/untracked.txt", "temporary note\n");
+
+    let service = GitService::new();
+    let res = service.rebase_branch(
+        &repo_path,
+        &worktree_path,
+        "new-base",
+        "old-base",
+        "feature",
+        true,
+    );
+    assert!(res.is_ok(), "rebase should succeed: {res:?}");
+
+    let edited = fs::read_to_string(worktree_path.join("feat.txt")).unwrap();
+    assert_eq!(edited, "feat change (edited)\n");
+    assert!(worktree_path.join("scratch/untracked.txt").exists());
+    assert!(service.list_stashes(&worktree_path).unwrap().is_empty());
+}
+
+#[test]
+fn pop_stash_finds_its_stash_after_another_is_pushed() {
+    let td = TempDir::new().unwrap();
+    let (_repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let service = GitService::new();
+
+    write_file(&worktree_path, "feat.txt", "first edit\n");
+    let first = service.stash_worktree(&worktree_path).unwrap().unwrap();
+    write_file(&worktree_path, "feat.txt", "second edit\n");
+    let second = service.stash_worktree(&worktree_path).unwrap().unwrap();
+    assert_ne!(first.sha, second.sha);
+
+    service.pop_stash(&worktree_path, &first).unwrap();
+
+    let edited = fs::read_to_string(worktree_path.join("feat.txt")).unwrap();
+    assert_eq!(edited, "first edit\n");
+    let stashes = service.list_stashes(&worktree_path).unwrap();
+    assert_eq!(stashes.len(), 1);
+    assert_eq!(stashes[0].sha, second.sha);
+}
+
+#[test]
+fn pop_stash_conflict_keeps_the_stash() {
+    let td = TempDir::new().unwrap();
+    let (_repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let service = GitService::new();
+
+    write_file(&worktree_path, "feat.txt", "stashed edit\n");
+    let stash = service.stash_worktree(&worktree_path).unwrap().unwrap();
+
+    write_file(&worktree_path, "feat.txt", "committed edit\n");
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    commit_all(&wt_repo, "conflicting change to feat.txt");
+
+    let res = service.pop_stash(&worktree_path, &stash);
+    assert!(
+        matches!(res, Err(GitServiceError::StashPopConflict { stash: ref s, .. }) if s.sha == stash.sha),
+        "expected StashPopConflict, got {res:?}"
+    );
+    let stashes = service.list_stashes(&worktree_path).unwrap();
+    assert!(stashes.iter().any(|entry| entry.sha == stash.sha));
+}
+
 #[test]
 fn rebase_aborts_if_untracked_would_be_overwritten_by_base() {
     let td = TempDir::new().unwrap();
@@ -463,6 +601,7 @@ fn rebase_aborts_if_untracked_would_be_overwritten_by_base() {
         "new-base",
         "old-base",
         "feature",
+        false,
     );
     assert!(
         res.is_err(),
@@ -697,6 +836,7 @@ fn rebase_refuses_to_abort_existing_rebase() {
             "new-base",
             "old-base",
             "feature",
+            false,
         )
         .expect_err("first rebase should error and leave in-progress state");
 
@@ -708,6 +848,7 @@ fn rebase_refuses_to_abort_existing_rebase() {
         "new-base",
         "old-base",
         "feature",
+        false,
     );
     assert!(res.is_err(), "should error because rebase is in progress");
     // Note: We do not auto-abort; user should resolve or abort explicitly
@@ -728,6 +869,7 @@ fn rebase_fast_forwards_when_no_unique_commits() {
             "new-base",
             "old-base",
             "feature",
+            false,
         )
         .expect("rebase should succeed");
     let after_oid = g.get_head_info(&worktree_path).unwrap().oid;
@@ -759,6 +901,7 @@ fn rebase_applies_multiple_commits_onto_ahead_base() {
             "new-base",
             "old-base",
             "feature",
+            false,
         )
         .expect("rebase should succeed");
 
@@ -769,6 +912,93 @@ fn rebase_applies_multiple_commits_onto_ahead_base() {
     assert_eq!(feat2, "second change\n");
 }
 
+#[test]
+fn cherry_pick_commits_applies_in_order() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+
+    let repo = Repository::open(&repo_path).unwrap();
+    checkout_branch(&repo, "main");
+    create_branch_from_head(&repo, "fixes");
+    checkout_branch(&repo, "fixes");
+    write_file(&repo_path, "fix1.txt", "one\n");
+    commit_all(&repo, "first fix");
+    let first = repo.head().unwrap().target().unwrap().to_string();
+    write_file(&repo_path, "fix2.txt", "two\n");
+    commit_all(&repo, "second fix");
+    let second = repo.head().unwrap().target().unwrap().to_string();
+
+    let service = GitService::new();
+    let picked = service
+        .cherry_pick_commits(&worktree_path, &[&first, &second])
+        .expect("cherry-pick succeeds");
+    assert_eq!(picked.len(), 2);
+
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    let head = wt_repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.id().to_string(), picked[1]);
+    assert_eq!(head.summary(), Some("second fix"));
+    assert_eq!(head.parent(0).unwrap().id().to_string(), picked[0]);
+    assert!(worktree_path.join("fix1.txt").exists());
+    assert!(worktree_path.join("feat.txt").exists());
+}
+
+#[test]
+fn cherry_pick_conflict_is_left_in_progress() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_direct_conflict_repo(&td);
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let main_change = repo.head().unwrap().target().unwrap().to_string();
+
+    let service = GitService::new();
+    let res = service.cherry_pick_commits(&worktree_path, &[&main_change]);
+    match res {
+        Err(GitServiceError::MergeConflicts {
+            conflicted_files, ..
+        }) => assert_eq!(conflicted_files, vec!["conflict.txt".to_string()]),
+        other => panic!("expected merge conflicts, got {other:?}"),
+    }
+    assert_eq!(
+        service.detect_conflict_op(&worktree_path).unwrap(),
+        Some(ConflictOp::CherryPick)
+    );
+}
+
+#[test]
+fn conflict_details_report_index_stages() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_direct_conflict_repo(&td);
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let main_change = repo.head().unwrap().target().unwrap().to_string();
+
+    let service = GitService::new();
+    assert!(
+        service
+            .get_conflict_details(&worktree_path)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        service
+            .cherry_pick_commits(&worktree_path, &[&main_change])
+            .is_err()
+    );
+
+    let details = service.get_conflict_details(&worktree_path).unwrap();
+    assert_eq!(
+        details,
+        vec![FileConflict {
+            path: "conflict.txt".to_string(),
+            base: Some("base\n".to_string()),
+            ours: Some("feature change\n".to_string()),
+            theirs: Some("main change\n".to_string()),
+            content_omitted: false,
+        }]
+    );
+}
+
 #[test]
 fn merge_when_base_ahead_and_feature_ahead_fails() {
     let td = TempDir::new().unwrap();
@@ -904,6 +1134,7 @@ fn rebase_preserves_rename_changes() {
             "new-base",
             "old-base",
             "feature",
+            false,
         )
         .expect("rebase should succeed");
     // after rebase, renamed file present; original absent
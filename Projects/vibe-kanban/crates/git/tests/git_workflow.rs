@@ -4,10 +4,15 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use git::{DiffTarget, GitCli, GitService};
+use futures::StreamExt;
+use git::{
+    CONVENTIONAL_COMMIT_PATTERN, CommitMessageRules, DiffTarget, FallbackEncoding,
+    FileContentOptions, GitCli, GitService, GitServiceError, HookName, SignatureStatus,
+};
 use git2::{Repository, build::CheckoutBuilder};
 use tempfile::TempDir;
-use utils::diff::DiffChangeKind;
+use tokio_util::sync::CancellationToken;
+use utils::diff::{DiffChangeKind, DiffResult};
 
 fn add_path(repo_path: &Path, path: &str) {
     let git = GitCli::new();
@@ -120,6 +125,61 @@ fn initialize_repo_without_user_creates_initial_commit() {
     }
 }
 
+#[test]
+fn initialize_repo_uses_configured_identity_without_user() {
+    let td = TempDir::new().unwrap();
+    let repo_path = td.path().join("repo_custom_identity");
+    let s = GitService::with_identity("Custom Bot", "bot@example.com");
+    s.initialize_repo_with_main_branch(&repo_path).unwrap();
+    let (name, email) = get_head_author(&repo_path);
+    if has_global_git_identity() {
+        assert!(name.is_some() && email.is_some());
+    } else {
+        assert_eq!(name.as_deref(), Some("Custom Bot"));
+        assert_eq!(email.as_deref(), Some("bot@example.com"));
+    }
+}
+
+#[test]
+fn signed_commit_fails_with_signing_error_when_signer_fails() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    s.set_commit_signing(true);
+    let repo = Repository::open(&repo_path).unwrap();
+    let mut cfg = repo.config().unwrap();
+    cfg.set_str("gpg.format", "openpgp").unwrap();
+    cfg.set_str("gpg.program", "false").unwrap();
+
+    write_file(&repo_path, "a.txt", "a\n");
+    assert!(matches!(
+        s.commit(&repo_path, "signed"),
+        Err(GitServiceError::CommitSigningFailed(_))
+    ));
+
+    // Clones share the setting, and turning it off commits unsigned
+    s.clone().set_commit_signing(false);
+    assert!(s.commit(&repo_path, "unsigned").unwrap());
+}
+
+#[test]
+fn ssh_signing_without_key_fails_before_committing() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    s.set_commit_signing(true);
+    let repo = Repository::open(&repo_path).unwrap();
+    let mut cfg = repo.config().unwrap();
+    cfg.set_str("gpg.format", "ssh").unwrap();
+    cfg.set_str("user.signingkey", "").unwrap();
+
+    write_file(&repo_path, "a.txt", "a\n");
+    assert!(matches!(
+        s.commit(&repo_path, "signed"),
+        Err(GitServiceError::CommitSigningFailed(_))
+    ));
+}
+
 #[test]
 fn commit_without_user_config_succeeds() {
     let td = TempDir::new().unwrap();
@@ -132,6 +192,35 @@ fn commit_without_user_config_succeeds() {
     assert!(res.is_ok());
 }
 
+#[test]
+fn commit_rejects_messages_that_break_enforced_rules() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "change.txt", "change\n");
+
+    let s = GitService::new();
+    s.set_commit_message_rules(CommitMessageRules {
+        max_subject_length: Some(50),
+        require_blank_line_before_body: true,
+        subject_pattern: Some(CONVENTIONAL_COMMIT_PATTERN.to_string()),
+    });
+    let message = "Commit changes from coding agent for workspace 1234";
+    assert!(matches!(
+        s.commit(&repo_path, message),
+        Err(GitServiceError::InvalidCommitMessage(issues)) if issues.len() == 2
+    ));
+    let repo = Repository::open(&repo_path).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_ne!(head.message().unwrap().trim(), message);
+
+    assert!(s.commit(&repo_path, "feat: add change").unwrap());
+
+    // The default rules enforce nothing
+    s.set_commit_message_rules(CommitMessageRules::default());
+    write_file(&repo_path, "change.txt", "again\n");
+    assert!(s.commit(&repo_path, message).unwrap());
+}
+
 #[test]
 fn commit_fails_when_index_locked() {
     use std::fs::File;
@@ -203,6 +292,7 @@ fn diff_added_binary_file_has_no_content() {
                 repo_path: Path::new(&repo_path),
                 branch_name: "feature",
                 base_branch: "main",
+                merge_base_relative: false,
             },
             None,
         )
@@ -228,6 +318,139 @@ fn initialize_and_default_branch_and_head_info() {
     assert!(!head.oid.is_empty());
 }
 
+#[test]
+fn blame_file_attributes_lines_to_their_last_commit() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "notes.txt", "one\ntwo\n");
+    s.commit(&repo_path, "add notes").unwrap();
+    let first = s.get_head_info(&repo_path).unwrap().oid;
+
+    configure_user(&repo_path, "Other User", "other@example.com");
+    write_file(&repo_path, "notes.txt", "one\nTWO\n");
+    s.commit(&repo_path, "edit notes").unwrap();
+    let second = s.get_head_info(&repo_path).unwrap().oid;
+
+    let blame = s.blame_file(&repo_path, "notes.txt", None).unwrap();
+    let summary: Vec<_> = blame
+        .iter()
+        .map(|l| (l.line, l.commit.as_str(), l.author_name.as_deref()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (1, first.as_str(), Some("Test User")),
+            (2, second.as_str(), Some("Other User")),
+        ]
+    );
+
+    // As of the first commit, both lines come from it
+    let blame = s.blame_file(&repo_path, "notes.txt", Some(&first)).unwrap();
+    assert!(blame.iter().all(|l| l.commit == first));
+    assert_eq!(blame.len(), 2);
+
+    write_file(&repo_path, "untracked.txt", "new\n");
+    assert!(
+        s.blame_file(&repo_path, "untracked.txt", None)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn recent_file_stats_count_commits_and_keep_latest_index() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    for i in 0..20 {
+        write_file(&repo_path, "hot.txt", &format!("{i}\n"));
+        if i % 5 == 0 {
+            write_file(&repo_path, &format!("cold{i}.txt"), "x\n");
+        }
+        s.commit(&repo_path, &format!("change {i}")).unwrap();
+    }
+
+    let stats = s.collect_recent_file_stats(&repo_path, 20).unwrap();
+    let hot = &stats["hot.txt"];
+    assert_eq!(hot.commit_count, 20);
+    assert_eq!(hot.last_index, 0);
+    // The initial commit is beyond the limit
+    assert_eq!(stats.len(), 5);
+    for i in (0..20).step_by(5) {
+        assert_eq!(stats[&format!("cold{i}.txt")].commit_count, 1);
+    }
+}
+
+#[test]
+fn ignored_paths_are_detected_and_filtered() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, ".gitignore", "target/\n*.log\n");
+
+    assert!(s.is_path_ignored(&repo_path, "target/debug/app").unwrap());
+    assert!(s.is_path_ignored(&repo_path, "nested/run.log").unwrap());
+    assert!(!s.is_path_ignored(&repo_path, "src/main.rs").unwrap());
+
+    let paths = ["src/main.rs", "build.log", "README.md", "target/x"].map(String::from);
+    assert_eq!(
+        s.filter_ignored(&repo_path, &paths).unwrap(),
+        vec!["src/main.rs".to_string(), "README.md".to_string()]
+    );
+}
+
+#[test]
+fn detect_default_branch_prefers_remote_head_then_local_names() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let repo = Repository::open(&repo_path).unwrap();
+    let head = repo.head().unwrap().target().unwrap();
+
+    // Only a local main
+    assert_eq!(s.detect_default_branch(&repo_path).unwrap(), "main");
+
+    // Only a local master
+    repo.find_branch("main", git2::BranchType::Local)
+        .unwrap()
+        .rename("master", false)
+        .unwrap();
+    assert_eq!(s.detect_default_branch(&repo_path).unwrap(), "master");
+
+    // The remote's HEAD wins over local branches
+    repo.remote("origin", "https://example.invalid/repo.git")
+        .unwrap();
+    repo.reference("refs/remotes/origin/trunk", head, true, "test")
+        .unwrap();
+    repo.reference_symbolic(
+        "refs/remotes/origin/HEAD",
+        "refs/remotes/origin/trunk",
+        true,
+        "test",
+    )
+    .unwrap();
+    assert_eq!(s.detect_default_branch(&repo_path).unwrap(), "trunk");
+}
+
+#[test]
+fn head_info_reports_detached_head() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let head = s.get_head_info(&repo_path).unwrap();
+    assert!(!head.is_detached);
+
+    let repo = Repository::open(&repo_path).unwrap();
+    repo.set_head_detached(git2::Oid::from_str(&head.oid).unwrap())
+        .unwrap();
+
+    let detached = s.get_head_info(&repo_path).unwrap();
+    assert!(detached.is_detached);
+    assert_eq!(detached.branch, "HEAD");
+    assert_eq!(detached.oid, head.oid);
+}
+
 #[test]
 fn commit_and_is_worktree_clean() {
     let td = TempDir::new().unwrap();
@@ -346,6 +569,7 @@ fn get_branch_diffs_between_branches() {
                 repo_path: Path::new(&repo_path),
                 branch_name: "feature",
                 base_branch: "main",
+                merge_base_relative: false,
             },
             None,
         )
@@ -395,6 +619,62 @@ fn worktree_diff_respects_path_filter() {
     );
 }
 
+#[tokio::test]
+async fn stream_diffs_yields_same_diffs_as_get_diffs() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "a.txt", "a\n");
+    let s = GitService::new();
+    let _ = s.commit(&repo_path, "baseline").unwrap();
+
+    create_branch(&repo_path, "feature");
+    write_file(&repo_path, "a.txt", "a2\n");
+    write_file(&repo_path, "src/b.txt", "b\n");
+    let _ = s.commit(&repo_path, "feature work").unwrap();
+    write_file(&repo_path, "src/c.txt", "c\n");
+
+    let base_commit = s.get_base_commit(&repo_path, "feature", "main").unwrap();
+    let worktree = DiffTarget::Worktree {
+        worktree_path: Path::new(&repo_path),
+        base_commit: &base_commit,
+    };
+    let branch = DiffTarget::Branch {
+        repo_path: Path::new(&repo_path),
+        branch_name: "feature",
+        base_branch: "main",
+        merge_base_relative: false,
+    };
+
+    for (target, filter) in [(worktree, None), (branch, Some(&["src"][..]))] {
+        let expected = s.get_diffs(target, filter).unwrap();
+        let streamed: Vec<_> = s
+            .stream_diffs(target, filter)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let paths = |diffs: &[utils::diff::Diff]| {
+            diffs.iter().map(GitService::diff_path).collect::<Vec<_>>()
+        };
+        assert!(!streamed.is_empty());
+        assert_eq!(paths(&streamed), paths(&expected));
+    }
+
+    // Errors arrive as a stream item rather than a panic or silent end
+    let mut missing = s.stream_diffs(
+        DiffTarget::Branch {
+            repo_path: Path::new(&repo_path),
+            branch_name: "no-such-branch",
+            base_branch: "main",
+            merge_base_relative: false,
+        },
+        None,
+    );
+    assert!(matches!(missing.next().await, Some(Err(_))));
+    assert!(missing.next().await.is_none());
+}
+
 #[test]
 fn get_branch_oid_nonexistent_errors() {
     let td = TempDir::new().unwrap();
@@ -520,3 +800,1312 @@ fn squash_merge_libgit2_sets_author_without_user() {
         assert_eq!(email.as_deref(), Some("noreply@vibekanban.com"));
     }
 }
+
+#[test]
+fn list_worktrees_detailed_reports_state() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    create_branch(&repo_path, "feature");
+    let wt_path = td.path().join("wt-feature");
+    let s = GitService::new();
+    s.add_worktree(&repo_path, &wt_path, "feature", false)
+        .unwrap();
+
+    let worktrees = s.list_worktrees_detailed(&repo_path).unwrap();
+    assert_eq!(worktrees.len(), 2);
+    let wt = worktrees
+        .iter()
+        .find(|w| w.branch.as_deref() == Some("feature"))
+        .unwrap();
+    assert!(!wt.head.is_empty());
+    assert!(!wt.locked && !wt.prunable);
+    assert!(!s.worktree_has_changes(&wt_path).unwrap());
+
+    write_file(&wt_path, "dirty.txt", "dirty\n");
+    assert!(s.worktree_has_changes(&wt_path).unwrap());
+
+    // Deleting the directory behind git's back leaves a prunable entry
+    fs::remove_dir_all(&wt_path).unwrap();
+    let worktrees = s.list_worktrees_detailed(&repo_path).unwrap();
+    assert!(
+        worktrees
+            .iter()
+            .any(|w| w.branch.as_deref() == Some("feature") && w.prunable)
+    );
+    s.prune_worktrees(&repo_path).unwrap();
+    assert_eq!(s.list_worktrees_detailed(&repo_path).unwrap().len(), 1);
+}
+
+#[test]
+fn repair_worktree_relinks_or_recreates_broken_worktrees() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    create_branch(&repo_path, "feature");
+    let wt_path = td.path().join("wt-feature");
+    let s = GitService::new();
+    s.add_worktree(&repo_path, &wt_path, "feature", false)
+        .unwrap();
+    assert!(s.is_worktree_healthy(&repo_path, &wt_path));
+
+    // Moving the directory behind git's back breaks the admin entry's back link
+    let moved_path = td.path().join("wt-moved");
+    fs::rename(&wt_path, &moved_path).unwrap();
+    assert!(!s.is_worktree_healthy(&repo_path, &moved_path));
+    s.repair_worktree(&repo_path, &moved_path).unwrap();
+    assert!(s.is_worktree_healthy(&repo_path, &moved_path));
+
+    // A missing .git link is restored from the admin entry, keeping the files
+    write_file(&moved_path, "leftover.txt", "partial\n");
+    fs::remove_file(moved_path.join(".git")).unwrap();
+    assert!(!s.is_worktree_healthy(&repo_path, &moved_path));
+    s.repair_worktree(&repo_path, &moved_path).unwrap();
+    assert!(s.is_worktree_healthy(&repo_path, &moved_path));
+    assert!(moved_path.join("leftover.txt").exists());
+
+    // A deleted directory cannot be relinked and is recreated on its branch
+    fs::remove_dir_all(&moved_path).unwrap();
+    s.repair_worktree(&repo_path, &moved_path).unwrap();
+    assert!(s.is_worktree_healthy(&repo_path, &moved_path));
+    assert_eq!(s.get_current_branch(&moved_path).unwrap(), "feature");
+
+    // A link git cannot repair forces a recreate, but the old files are kept aside
+    write_file(&moved_path, "notes.txt", "uncommitted\n");
+    fs::write(moved_path.join(".git"), "gitdir: /nonexistent/worktree\n").unwrap();
+    assert!(!s.is_worktree_healthy(&repo_path, &moved_path));
+    s.repair_worktree(&repo_path, &moved_path).unwrap();
+    assert!(s.is_worktree_healthy(&repo_path, &moved_path));
+    assert!(!moved_path.join("notes.txt").exists());
+    let aside = td.path().join("wt-moved.broken");
+    assert_eq!(
+        fs::read_to_string(aside.join("notes.txt")).unwrap(),
+        "uncommitted\n"
+    );
+    assert!(!aside.join(".git").exists());
+}
+
+#[test]
+fn default_remote_resolution_order() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let repo = Repository::open(&repo_path).unwrap();
+    // `git remote` lists alphabetically, so "fork" comes before "origin"
+    for name in ["fork", "origin", "zz-upstream"] {
+        repo.remote(name, &format!("https://example.com/{name}.git"))
+            .unwrap();
+    }
+    let s = GitService::new();
+    assert_eq!(s.get_default_remote(&repo_path).unwrap().name, "origin");
+
+    // Preferences naming missing remotes are skipped
+    s.set_preferred_remotes(vec!["missing".to_string(), "zz-upstream".to_string()]);
+    let preferred = s.get_default_remote(&repo_path).unwrap().name;
+    repo.config()
+        .unwrap()
+        .set_str("remote.pushDefault", "fork")
+        .unwrap();
+    let push_default = s.get_default_remote(&repo_path).unwrap().name;
+    assert_eq!(preferred, "zz-upstream");
+    assert_eq!(push_default, "fork");
+
+    repo.remote_delete("origin").unwrap();
+    repo.config().unwrap().remove("remote.pushDefault").unwrap();
+    assert_eq!(s.get_default_remote(&repo_path).unwrap().name, "fork");
+}
+
+#[test]
+fn set_and_get_upstream() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    create_branch(&repo_path, "feature");
+    let s = GitService::new();
+    assert_eq!(s.get_upstream(&repo_path, "feature").unwrap(), None);
+
+    s.set_upstream(&repo_path, "feature", Some("main")).unwrap();
+    assert_eq!(
+        s.get_upstream(&repo_path, "feature").unwrap().as_deref(),
+        Some("main")
+    );
+
+    // Remote-tracking upstreams are reported by their short name
+    let repo = Repository::open(&repo_path).unwrap();
+    repo.remote("origin", "https://example.com/repo.git")
+        .unwrap();
+    let head = repo.head().unwrap().target().unwrap();
+    repo.reference("refs/remotes/origin/main", head, false, "test")
+        .unwrap();
+    s.set_upstream(&repo_path, "feature", Some("origin/main"))
+        .unwrap();
+    assert_eq!(
+        s.get_upstream(&repo_path, "feature").unwrap().as_deref(),
+        Some("origin/main")
+    );
+
+    assert!(
+        s.set_upstream(&repo_path, "feature", Some("origin/missing"))
+            .is_err()
+    );
+    assert!(s.get_upstream(&repo_path, "missing").is_err());
+
+    s.set_upstream(&repo_path, "feature", None).unwrap();
+    assert_eq!(s.get_upstream(&repo_path, "feature").unwrap(), None);
+}
+
+/// Clone of a repository whose `main` and `feature` diverged, with depth 1 so
+/// that only the branch tips are present and their common ancestor is cut off.
+fn shallow_clone_of_diverged_branches(td: &TempDir) -> (PathBuf, PathBuf) {
+    let repo_path = init_repo_main(td);
+    let s = GitService::new();
+    write_file(&repo_path, "a.txt", "one\n");
+    s.commit(&repo_path, "one").unwrap();
+    create_branch(&repo_path, "feature");
+    write_file(&repo_path, "a.txt", "two\n");
+    s.commit(&repo_path, "two").unwrap();
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "b.txt", "feature\n");
+    s.commit(&repo_path, "feature").unwrap();
+    checkout_branch(&repo_path, "main");
+
+    let shallow_path = td.path().join("shallow");
+    let url = format!("file://{}", repo_path.display());
+    GitCli::new()
+        .git(
+            td.path(),
+            [
+                "clone",
+                "--depth",
+                "1",
+                "--no-single-branch",
+                url.as_str(),
+                shallow_path.to_str().unwrap(),
+            ],
+        )
+        .unwrap();
+    configure_user(&shallow_path, "Test User", "test@example.com");
+    (repo_path, shallow_path)
+}
+
+#[test]
+fn shallow_clone_reports_missing_merge_base() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, shallow_path) = shallow_clone_of_diverged_branches(&td);
+    let s = GitService::new();
+
+    assert!(matches!(
+        s.get_base_commit(&shallow_path, "origin/feature", "origin/main"),
+        Err(GitServiceError::ShallowRepository(_))
+    ));
+    assert!(matches!(
+        s.get_branch_status(&shallow_path, "origin/feature", "origin/main"),
+        Err(GitServiceError::ShallowRepository(_))
+    ));
+    assert!(matches!(
+        s.get_fork_point(&shallow_path, "origin/main", "origin/feature"),
+        Err(GitServiceError::ShallowRepository(_))
+    ));
+
+    // The tip's parent was cut off, so there is no baseline to diff against
+    let tip = s.get_branch_oid(&shallow_path, "origin/main").unwrap();
+    assert!(matches!(
+        s.get_diffs(
+            DiffTarget::Commit {
+                repo_path: &shallow_path,
+                commit_sha: &tip,
+            },
+            None,
+        ),
+        Err(GitServiceError::ShallowRepository(_))
+    ));
+
+    // The full repository is unaffected
+    assert!(s.get_base_commit(&repo_path, "feature", "main").is_ok());
+    assert_eq!(
+        s.get_branch_status(&repo_path, "feature", "main").unwrap(),
+        (1, 1)
+    );
+}
+
+/// Worktree of a `task` branch starting at `feature_ref` in `shallow_path`
+fn task_worktree_at(
+    s: &GitService,
+    td: &TempDir,
+    shallow_path: &Path,
+    feature_ref: &str,
+) -> PathBuf {
+    create_branch(shallow_path, "task");
+    let repo = Repository::open(shallow_path).unwrap();
+    let feature_tip = repo.find_reference(feature_ref).unwrap().target().unwrap();
+    repo.reference("refs/heads/task", feature_tip, true, "test")
+        .unwrap();
+    let wt_path = td.path().join("wt-task");
+    s.add_worktree(shallow_path, &wt_path, "task", false)
+        .unwrap();
+    wt_path
+}
+
+#[test]
+fn rebase_in_shallow_clone_deepens_history_when_allowed() {
+    let td = TempDir::new().unwrap();
+    let (_, shallow_path) = shallow_clone_of_diverged_branches(&td);
+    let s = GitService::new();
+    let wt_path = task_worktree_at(&s, &td, &shallow_path, "refs/remotes/origin/feature");
+
+    s.set_shallow_deepening(false);
+    let refused = s.rebase_branch(
+        &shallow_path,
+        &wt_path,
+        "origin/main",
+        "origin/main",
+        "task",
+        false,
+    );
+    s.set_shallow_deepening(true);
+    assert!(matches!(
+        refused,
+        Err(GitServiceError::ShallowRepository(_))
+    ));
+
+    s.rebase_branch(
+        &shallow_path,
+        &wt_path,
+        "origin/main",
+        "origin/main",
+        "task",
+        false,
+    )
+    .unwrap();
+    assert!(wt_path.join("b.txt").exists());
+    assert_eq!(
+        s.get_branch_status(&shallow_path, "task", "origin/main")
+            .unwrap(),
+        (1, 0)
+    );
+}
+
+#[test]
+fn rebase_in_shallow_clone_deepens_the_remote_of_the_base_branch() {
+    let td = TempDir::new().unwrap();
+    let (_, shallow_path) = shallow_clone_of_diverged_branches(&td);
+    // The base comes from `upstream`, while the default remote can't be fetched
+    let cli = GitCli::new();
+    cli.git(&shallow_path, ["remote", "rename", "origin", "upstream"])
+        .unwrap();
+    let missing = td.path().join("missing");
+    cli.git(
+        &shallow_path,
+        ["remote", "add", "origin", missing.to_str().unwrap()],
+    )
+    .unwrap();
+    let s = GitService::new();
+    let wt_path = task_worktree_at(&s, &td, &shallow_path, "refs/remotes/upstream/feature");
+
+    s.rebase_branch(
+        &shallow_path,
+        &wt_path,
+        "upstream/main",
+        "upstream/main",
+        "task",
+        false,
+    )
+    .unwrap();
+    assert_eq!(
+        s.get_branch_status(&shallow_path, "task", "upstream/main")
+            .unwrap(),
+        (1, 0)
+    );
+}
+
+#[test]
+fn list_submodules_parses_gitmodules() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    assert!(s.list_submodules(&repo_path).unwrap().is_empty());
+
+    write_file(
+        &repo_path,
+        ".gitmodules",
+        "[submodule \"libs/core\"]\n\tpath = vendor/core\n\turl = https://example.com/core.git\n\tbranch = stable\n[submodule \"docs.site\"]\n\tpath = docs\n\turl = ../docs.git\n",
+    );
+    let submodules = s.list_submodules(&repo_path).unwrap();
+    assert_eq!(submodules.len(), 2);
+    assert_eq!(submodules[0].name, "libs/core");
+    assert_eq!(submodules[0].path, "vendor/core");
+    assert_eq!(
+        submodules[0].url.as_deref(),
+        Some("https://example.com/core.git")
+    );
+    assert_eq!(submodules[0].branch.as_deref(), Some("stable"));
+    assert_eq!(submodules[1].name, "docs.site");
+    assert_eq!(submodules[1].path, "docs");
+    assert_eq!(submodules[1].branch, None);
+}
+
+#[test]
+fn add_worktree_submodule_init_failure_is_not_fatal() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(
+        &repo_path,
+        ".gitmodules",
+        "[submodule \"missing\"]\n\tpath = missing\n\turl = ../does-not-exist.git\n",
+    );
+    // A gitlink whose repository can't be cloned, so `submodule update` really
+    // fails. Committed with plain git since `add -A` would drop the missing path.
+    let git = GitCli::new();
+    let head = git.git(&repo_path, ["rev-parse", "HEAD"]).unwrap();
+    let gitlink = format!("160000,{},missing", head.trim());
+    git.git(
+        &repo_path,
+        ["update-index", "--add", "--cacheinfo", gitlink.as_str()],
+    )
+    .unwrap();
+    add_path(&repo_path, ".gitmodules");
+    git.git(&repo_path, ["commit", "-q", "-m", "add submodule"])
+        .unwrap();
+    create_branch(&repo_path, "feature");
+    assert!(
+        git.git(&repo_path, ["ls-tree", "feature", "missing"])
+            .unwrap()
+            .starts_with("160000 commit")
+    );
+
+    let s = GitService::new();
+    s.set_init_submodules(true);
+    let wt_path = td.path().join("wt-feature");
+    s.add_worktree(&repo_path, &wt_path, "feature", false)
+        .unwrap();
+    assert!(wt_path.join(".gitmodules").exists());
+    assert!(
+        !wt_path.join("missing/.git").exists(),
+        "submodule should not have been checked out"
+    );
+}
+
+#[test]
+fn add_worktree_with_unreadable_gitmodules_is_not_fatal() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(
+        &repo_path,
+        ".gitmodules",
+        "[submodule \"broken\"\n\tpath = x\n",
+    );
+    let s = GitService::new();
+    s.set_init_submodules(true);
+    s.commit(&repo_path, "add broken gitmodules").unwrap();
+    assert!(s.list_submodules(&repo_path).is_err());
+    create_branch(&repo_path, "feature");
+
+    let wt_path = td.path().join("wt-feature");
+    s.add_worktree(&repo_path, &wt_path, "feature", false)
+        .unwrap();
+    assert!(wt_path.join(".gitmodules").exists());
+}
+
+#[test]
+fn diff_stats_count_committed_staged_and_untracked() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "a.txt", "one\ntwo\nthree\n");
+    let s = GitService::new();
+    s.commit(&repo_path, "add a").unwrap();
+    create_branch(&repo_path, "feature");
+    checkout_branch(&repo_path, "feature");
+
+    write_file(&repo_path, "a.txt", "one\nTWO\nthree\nfour\n");
+    s.commit(&repo_path, "edit a").unwrap();
+    write_file(&repo_path, "new.txt", "x\ny\n");
+
+    let base_commit = s.get_base_commit(&repo_path, "feature", "main").unwrap();
+    let stats = s.get_diff_stats(&repo_path, &base_commit).unwrap();
+    assert_eq!(stats.files_changed, 2);
+    assert_eq!(stats.additions, 4);
+    assert_eq!(stats.deletions, 1);
+}
+
+#[test]
+fn diffs_since_flags_only_files_changed_after_review() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let base_oid = s.get_head_info(&repo_path).unwrap().oid;
+    let base = git::Commit::new(git2::Oid::from_str(&base_oid).unwrap());
+
+    write_file(&repo_path, "a.txt", "a\n");
+    write_file(&repo_path, "b.txt", "b\n");
+    assert!(s.commit(&repo_path, "reviewed").unwrap());
+    let reviewed_oid = s.get_head_info(&repo_path).unwrap().oid;
+    let reviewed = git::Commit::new(git2::Oid::from_str(&reviewed_oid).unwrap());
+
+    // b.txt edited and c.txt added after the review, left uncommitted
+    write_file(&repo_path, "b.txt", "b2\n");
+    write_file(&repo_path, "c.txt", "c\n");
+
+    let diffs = s
+        .get_diffs_since(
+            DiffTarget::Worktree {
+                worktree_path: &repo_path,
+                base_commit: &base,
+            },
+            &reviewed,
+        )
+        .unwrap();
+    let flagged = |name: &str| {
+        diffs
+            .iter()
+            .find(|d| d.new_path.as_deref() == Some(name))
+            .unwrap_or_else(|| panic!("{name} diff present"))
+            .changed_since_review
+    };
+    assert_eq!(diffs.len(), 3);
+    assert!(!flagged("a.txt"));
+    assert!(flagged("b.txt"));
+    assert!(flagged("c.txt"));
+
+    // A reviewed commit that no longer exists flags everything instead of failing
+    let missing_oid = "0123456789abcdef0123456789abcdef01234567";
+    assert!(s.commit_exists(&repo_path, &reviewed_oid).unwrap());
+    assert!(!s.commit_exists(&repo_path, missing_oid).unwrap());
+    let missing = git::Commit::new(git2::Oid::from_str(missing_oid).unwrap());
+    let diffs = s
+        .get_diffs_since(
+            DiffTarget::Worktree {
+                worktree_path: &repo_path,
+                base_commit: &base,
+            },
+            &missing,
+        )
+        .unwrap();
+    assert_eq!(diffs.len(), 3);
+    assert!(diffs.iter().all(|d| d.changed_since_review));
+}
+
+#[test]
+fn file_content_options_apply_per_service() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "keep.txt", "one\n");
+    assert!(s.commit(&repo_path, "base").unwrap());
+    let base_oid = s.get_head_info(&repo_path).unwrap().oid;
+    let base = git::Commit::new(git2::Oid::from_str(&base_oid).unwrap());
+    // "café" in Latin-1, which is not valid UTF-8
+    fs::write(repo_path.join("latin1.txt"), b"caf\xe9\n").unwrap();
+
+    let latin1 = GitService::new();
+    latin1.set_file_content(FileContentOptions {
+        fallback_encoding: Some(FallbackEncoding::Latin1),
+        ..Default::default()
+    });
+    let content = |service: &GitService| {
+        service
+            .get_diffs(
+                DiffTarget::Worktree {
+                    worktree_path: &repo_path,
+                    base_commit: &base,
+                },
+                None,
+            )
+            .unwrap()
+            .into_iter()
+            .find(|d| d.new_path.as_deref() == Some("latin1.txt"))
+            .unwrap()
+            .new_content
+    };
+    assert_eq!(content(&latin1).as_deref(), Some("café\n"));
+    assert_eq!(content(&s), None);
+}
+
+#[test]
+fn large_changesets_fall_back_to_summary() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "keep.txt", "one\ntwo\n");
+    assert!(s.commit(&repo_path, "base").unwrap());
+    let base_oid = s.get_head_info(&repo_path).unwrap().oid;
+    let base = git::Commit::new(git2::Oid::from_str(&base_oid).unwrap());
+
+    write_file(&repo_path, "big.txt", &"line\n".repeat(10));
+    write_file(&repo_path, "small.txt", "x\n");
+    fs::rename(repo_path.join("keep.txt"), repo_path.join("moved.txt")).unwrap();
+    let target = || DiffTarget::Worktree {
+        worktree_path: &repo_path,
+        base_commit: &base,
+    };
+
+    let result = s
+        .get_diffs_or_summary(target(), None, &git::DiffSizeLimits::default())
+        .unwrap();
+    assert!(matches!(result, DiffResult::Full { ref diffs } if diffs.len() == 3));
+
+    let limits = git::DiffSizeLimits {
+        max_files: 2,
+        top_n: 1,
+        ..Default::default()
+    };
+    let DiffResult::Summary { summary } = s.get_diffs_or_summary(target(), None, &limits).unwrap()
+    else {
+        panic!("expected a summary");
+    };
+    assert_eq!(summary.files_changed, 3);
+    assert_eq!(summary.additions, 11);
+    assert_eq!(summary.largest_changes.len(), 1);
+    assert_eq!(summary.largest_changes[0].path, "big.txt");
+}
+
+#[test]
+fn omitted_file_contents_still_count_toward_the_size_limit() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "keep.txt", "one\n");
+    assert!(s.commit(&repo_path, "base").unwrap());
+    let base_oid = s.get_head_info(&repo_path).unwrap().oid;
+    let base = git::Commit::new(git2::Oid::from_str(&base_oid).unwrap());
+
+    // Too large to inline, so the diff itself carries no line counts
+    s.set_file_content(FileContentOptions {
+        max_inline_bytes: 16,
+        ..Default::default()
+    });
+    write_file(&repo_path, "big.txt", &"line\n".repeat(50));
+    let limits = git::DiffSizeLimits {
+        max_changed_lines: 20,
+        ..Default::default()
+    };
+    let result = s
+        .get_diffs_or_summary(
+            DiffTarget::Worktree {
+                worktree_path: &repo_path,
+                base_commit: &base,
+            },
+            None,
+            &limits,
+        )
+        .unwrap();
+    let DiffResult::Summary { summary } = result else {
+        panic!("expected a summary");
+    };
+    assert_eq!(summary.additions, 50);
+}
+
+#[test]
+fn cherry_status_marks_commits_already_upstream() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "a.txt", "base\n");
+    s.commit(&repo_path, "base").unwrap();
+    create_branch(&repo_path, "feature");
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "b.txt", "picked\n");
+    s.commit(&repo_path, "add b").unwrap();
+    write_file(&repo_path, "c.txt", "only on feature\n");
+    s.commit(&repo_path, "add c").unwrap();
+
+    // Apply the same change as "add b" on main under a different commit
+    checkout_branch(&repo_path, "main");
+    write_file(&repo_path, "b.txt", "picked\n");
+    s.commit(&repo_path, "add b (picked)").unwrap();
+
+    let entries = s.get_cherry_status(&repo_path, "main", "feature").unwrap();
+    let summary: Vec<(&str, bool)> = entries
+        .iter()
+        .map(|e| (e.subject.as_str(), e.in_upstream))
+        .collect();
+    assert_eq!(summary, vec![("add b", true), ("add c", false)]);
+    assert!(entries.iter().all(|e| e.sha.len() == 40));
+
+    assert!(
+        s.get_cherry_status(&repo_path, "feature", "feature")
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        s.get_cherry_status(&repo_path, "main", "--missing")
+            .is_err()
+    );
+}
+
+#[test]
+fn submodule_pointer_changes_are_reported_as_updates() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let git = GitCli::new();
+
+    let sub_src = td.path().join("sub-src");
+    s.initialize_repo_with_main_branch(&sub_src).unwrap();
+    configure_user(&sub_src, "Test User", "test@example.com");
+    write_file(&sub_src, "lib.txt", "v1\n");
+    s.commit(&sub_src, "v1").unwrap();
+    let old_commit = s.get_head_info(&sub_src).unwrap().oid;
+
+    // Embed a clone of the submodule and record it as a gitlink
+    let sub_path = repo_path.join("sub");
+    git.git(
+        &repo_path,
+        ["clone", "-q", sub_src.to_str().unwrap(), "sub"],
+    )
+    .unwrap();
+    add_path(&repo_path, "sub");
+    s.commit(&repo_path, "add submodule").unwrap();
+    let base_commit = s.get_base_commit(&repo_path, "main", "main").unwrap();
+
+    configure_user(&sub_path, "Test User", "test@example.com");
+    write_file(&sub_path, "lib.txt", "v2\n");
+    s.commit(&sub_path, "bump lib to v2").unwrap();
+    let new_commit = s.get_head_info(&sub_path).unwrap().oid;
+
+    let assert_update = |diffs: Vec<utils::diff::Diff>| {
+        assert_eq!(diffs.len(), 1);
+        let diff = &diffs[0];
+        assert!(matches!(diff.change, DiffChangeKind::SubmoduleUpdate));
+        assert_eq!(diff.new_path.as_deref(), Some("sub"));
+        assert!(diff.old_content.is_none() && diff.new_content.is_none());
+        let submodule = diff.submodule.as_ref().unwrap();
+        assert_eq!(submodule.old_commit.as_deref(), Some(old_commit.as_str()));
+        assert_eq!(submodule.new_commit.as_deref(), Some(new_commit.as_str()));
+        assert_eq!(
+            submodule.new_commit_subject.as_deref(),
+            Some("bump lib to v2")
+        );
+    };
+
+    // Uncommitted pointer move in the worktree
+    assert_update(
+        s.get_diffs(
+            DiffTarget::Worktree {
+                worktree_path: &repo_path,
+                base_commit: &base_commit,
+            },
+            None,
+        )
+        .unwrap(),
+    );
+
+    // Committed pointer move on a branch
+    create_branch(&repo_path, "bump");
+    checkout_branch(&repo_path, "bump");
+    add_path(&repo_path, "sub");
+    s.commit(&repo_path, "bump submodule").unwrap();
+    assert_update(
+        s.get_diffs(
+            DiffTarget::Branch {
+                repo_path: &repo_path,
+                branch_name: "bump",
+                base_branch: "main",
+                merge_base_relative: false,
+            },
+            None,
+        )
+        .unwrap(),
+    );
+}
+
+#[test]
+fn stage_and_unstage_single_hunk() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let git = GitCli::new();
+    let original: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+    write_file(&repo_path, "a.txt", &original);
+    s.commit(&repo_path, "base").unwrap();
+
+    let edited = original
+        .replace("line 2\n", "line two\n")
+        .replace("line 19\n", "line nineteen\n");
+    write_file(&repo_path, "a.txt", &edited);
+
+    let header = "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n";
+    let first_hunk = format!(
+        "{header}@@ -1,5 +1,5 @@\n line 1\n-line 2\n+line two\n line 3\n line 4\n line 5\n"
+    );
+    s.stage_hunk(&repo_path, &first_hunk).unwrap();
+
+    let staged = git.git(&repo_path, ["diff", "--cached"]).unwrap();
+    assert!(staged.contains("+line two"));
+    assert!(!staged.contains("nineteen"));
+    let unstaged = git.git(&repo_path, ["diff"]).unwrap();
+    assert!(unstaged.contains("+line nineteen"));
+    assert!(!unstaged.contains("line two"));
+
+    // Staging the same hunk again no longer matches the index
+    match s.stage_hunk(&repo_path, &first_hunk) {
+        Err(GitServiceError::PatchRejected { hunk, .. }) => assert_eq!(hunk, first_hunk),
+        other => panic!("expected PatchRejected, got {other:?}"),
+    }
+
+    s.unstage_hunk(&repo_path, &first_hunk).unwrap();
+    assert!(
+        git.git(&repo_path, ["diff", "--cached"])
+            .unwrap()
+            .is_empty()
+    );
+    // The worktree keeps both edits
+    assert_eq!(fs::read_to_string(repo_path.join("a.txt")).unwrap(), edited);
+}
+
+#[cfg(unix)]
+#[test]
+fn install_hooks_only_affects_the_target_worktree() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let log = td.path().join("hooks.log");
+
+    // A husky-style setup: a relative hooks path whose scripts source a
+    // helper kept in a subdirectory next to them
+    write_file(
+        &repo_path,
+        ".husky/_/h",
+        &format!("echo husky >> '{}'\n", log.display()),
+    );
+    write_file(
+        &repo_path,
+        ".husky/pre-commit",
+        "#!/bin/sh\n. \"$(dirname \"$0\")/_/h\"\n",
+    );
+    fs::set_permissions(
+        repo_path.join(".husky/pre-commit"),
+        fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+    s.commit(&repo_path, "base").unwrap();
+    let git = GitCli::new();
+    git.git(&repo_path, ["config", "core.hooksPath", ".husky"])
+        .unwrap();
+    create_branch(&repo_path, "feature");
+    create_branch(&repo_path, "other");
+    let worktree_path = td.path().join("wt");
+    let other_path = td.path().join("other");
+    s.add_worktree(&repo_path, &worktree_path, "feature", false)
+        .unwrap();
+    s.add_worktree(&repo_path, &other_path, "other", false)
+        .unwrap();
+
+    let hook = format!("#!/bin/sh\necho vk >> '{}'\n", log.display());
+    s.install_hooks(
+        &worktree_path,
+        &[(HookName::CommitMsg, hook.clone())],
+        false,
+    )
+    .unwrap();
+    let installed = git
+        .worktree_git_dir(&worktree_path)
+        .unwrap()
+        .join("vk-hooks/commit-msg");
+    assert_eq!(fs::read_to_string(&installed).unwrap(), hook);
+    let mode = fs::metadata(&installed).unwrap().permissions().mode();
+    assert_eq!(mode & 0o111, 0o111);
+
+    // The shared config is left alone
+    assert!(
+        git.git(&repo_path, ["config", "--get", "extensions.worktreeConfig"])
+            .is_err()
+    );
+    assert_eq!(
+        git.git(&worktree_path, ["config", "--get", "core.hooksPath"])
+            .unwrap()
+            .trim(),
+        ".husky"
+    );
+
+    // Committing in the worktree runs the new hook and still the husky one
+    write_file(&worktree_path, "b.txt", "b\n");
+    assert!(s.commit(&worktree_path, "feature work").unwrap());
+    assert_eq!(fs::read_to_string(&log).unwrap(), "husky\nvk\n");
+
+    // Other worktrees only run the repository's own hooks
+    fs::remove_file(&log).unwrap();
+    write_file(&other_path, "c.txt", "c\n");
+    assert!(s.commit(&other_path, "other work").unwrap());
+    assert_eq!(fs::read_to_string(&log).unwrap(), "husky\n");
+    for path in [&repo_path, &other_path] {
+        let dir = git.hooks_dir(path).unwrap();
+        assert!(!dir.join("commit-msg").exists());
+    }
+
+    // Reinstalling the same script is a no-op, a different one needs force
+    s.install_hooks(
+        &worktree_path,
+        &[(HookName::CommitMsg, hook.clone())],
+        false,
+    )
+    .unwrap();
+    let replacement = "#!/bin/sh\nexit 1\n".to_string();
+    assert!(matches!(
+        s.install_hooks(
+            &worktree_path,
+            &[(HookName::CommitMsg, replacement.clone())],
+            false,
+        ),
+        Err(GitServiceError::HookExists(HookName::CommitMsg))
+    ));
+    assert_eq!(fs::read_to_string(&installed).unwrap(), hook);
+
+    s.install_hooks(
+        &worktree_path,
+        &[(HookName::CommitMsg, replacement.clone())],
+        true,
+    )
+    .unwrap();
+    assert_eq!(fs::read_to_string(&installed).unwrap(), replacement);
+}
+
+#[test]
+fn commit_patch_is_plain_unified_diff() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "a.txt", "one\ntwo\n");
+    s.commit(&repo_path, "add a").unwrap();
+    write_file(&repo_path, "a.txt", "one\nthree\n");
+    s.commit(&repo_path, "edit a").unwrap();
+
+    let head = s.get_head_info(&repo_path).unwrap().oid;
+    let patch = String::from_utf8(s.get_commit_patch(&repo_path, &head).unwrap()).unwrap();
+    assert!(patch.starts_with("diff --git a/a.txt b/a.txt\n"));
+    assert!(patch.contains("\n-two\n+three\n"));
+    assert!(!patch.contains(git::PATCH_TRUNCATED_MARKER));
+}
+
+#[test]
+fn truncated_commit_patch_still_applies() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "a.txt", "one\ntwo\n");
+    write_file(&repo_path, "big.txt", &"old\n".repeat(100));
+    s.commit(&repo_path, "base").unwrap();
+    write_file(&repo_path, "a.txt", "one\nthree\n");
+    write_file(&repo_path, "big.txt", &"new\n".repeat(100));
+    fs::write(repo_path.join("image.bin"), [0u8, 1, 2, 255, 0, 7]).unwrap();
+    s.commit(&repo_path, "change").unwrap();
+    let head = s.get_head_info(&repo_path).unwrap().oid;
+
+    s.set_file_content(FileContentOptions {
+        max_inline_bytes: 300,
+        ..Default::default()
+    });
+    let patch = s.get_commit_patch(&repo_path, &head).unwrap();
+    let text = String::from_utf8_lossy(&patch);
+    assert!(text.starts_with(&format!("{} big.txt", git::PATCH_TRUNCATED_MARKER)));
+    assert!(text.contains("GIT binary patch"));
+
+    let git = GitCli::new();
+    git.git(&repo_path, ["reset", "-q", "--hard", "HEAD~1"])
+        .unwrap();
+    let patch_file = td.path().join("change.patch");
+    fs::write(&patch_file, &patch).unwrap();
+    let patch_arg = patch_file.to_str().unwrap();
+    git.git(&repo_path, ["apply", "--check", patch_arg])
+        .unwrap();
+    git.git(&repo_path, ["apply", patch_arg]).unwrap();
+    assert_eq!(
+        fs::read_to_string(repo_path.join("a.txt")).unwrap(),
+        "one\nthree\n"
+    );
+    assert_eq!(
+        fs::read(repo_path.join("image.bin")).unwrap(),
+        [0u8, 1, 2, 255, 0, 7]
+    );
+    assert!(
+        fs::read_to_string(repo_path.join("big.txt"))
+            .unwrap()
+            .starts_with("old\n")
+    );
+}
+
+#[test]
+fn unsigned_commit_reports_unsigned() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "a.txt", "a\n");
+    s.commit(&repo_path, "add a").unwrap();
+
+    let head = s.get_head_info(&repo_path).unwrap().oid;
+    assert_eq!(
+        s.verify_commit_signature(&repo_path, &head).unwrap(),
+        SignatureStatus::Unsigned
+    );
+}
+
+#[test]
+fn list_inspect_and_drop_stashes() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "a.txt", "one\n");
+    s.commit(&repo_path, "add a").unwrap();
+
+    write_file(&repo_path, "a.txt", "two\n");
+    GitCli::new()
+        .git(&repo_path, ["stash", "push", "-m", "keep this"])
+        .unwrap();
+
+    let stashes = s.list_stashes(&repo_path).unwrap();
+    assert_eq!(stashes.len(), 1);
+    assert_eq!(stashes[0].index, 0);
+    assert_eq!(stashes[0].branch.as_deref(), Some("main"));
+    assert!(stashes[0].message.ends_with("keep this"));
+
+    let diffs = s.stash_diff(&repo_path, 0).unwrap();
+    assert_eq!(diffs.len(), 1);
+    assert_eq!(diffs[0].new_content.as_deref(), Some("two\n"));
+
+    s.stash_apply(&repo_path, 0).unwrap();
+    assert_eq!(
+        fs::read_to_string(repo_path.join("a.txt")).unwrap(),
+        "two\n"
+    );
+    s.stash_drop(&repo_path, 0).unwrap();
+    assert!(s.list_stashes(&repo_path).unwrap().is_empty());
+    assert!(matches!(
+        s.stash_drop(&repo_path, 0),
+        Err(GitServiceError::StashNotFound(_))
+    ));
+}
+
+#[test]
+fn staged_and_unstaged_diffs_are_separate() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "a.txt", "one\n");
+    let s = GitService::new();
+    let _ = s.commit(&repo_path, "add a").unwrap();
+
+    write_file(&repo_path, "a.txt", "one\ntwo\n");
+    write_file(&repo_path, "b.txt", "new\n");
+    add_path(&repo_path, "a.txt");
+    add_path(&repo_path, "b.txt");
+    write_file(&repo_path, "a.txt", "one\ntwo\nthree\n");
+    write_file(&repo_path, "untracked.txt", "u\n");
+
+    let mut staged = s
+        .get_diffs(
+            DiffTarget::StagedOnly {
+                worktree_path: &repo_path,
+            },
+            None,
+        )
+        .unwrap();
+    staged.sort_by(|a, b| a.new_path.cmp(&b.new_path));
+    assert_eq!(staged.len(), 2);
+    assert_eq!(staged[0].new_path.as_deref(), Some("a.txt"));
+    assert_eq!(staged[0].old_content.as_deref(), Some("one\n"));
+    assert_eq!(staged[0].new_content.as_deref(), Some("one\ntwo\n"));
+    assert_eq!(
+        (staged[0].additions, staged[0].deletions),
+        (Some(1), Some(0))
+    );
+    assert_eq!(staged[1].new_path.as_deref(), Some("b.txt"));
+    assert!(matches!(staged[1].change, DiffChangeKind::Added));
+
+    let unstaged = s
+        .get_diffs(
+            DiffTarget::UnstagedOnly {
+                worktree_path: &repo_path,
+            },
+            None,
+        )
+        .unwrap();
+    assert_eq!(unstaged.len(), 1);
+    assert_eq!(unstaged[0].new_path.as_deref(), Some("a.txt"));
+    assert_eq!(unstaged[0].old_content.as_deref(), Some("one\ntwo\n"));
+    assert_eq!(
+        unstaged[0].new_content.as_deref(),
+        Some("one\ntwo\nthree\n")
+    );
+    assert_eq!(
+        (unstaged[0].additions, unstaged[0].deletions),
+        (Some(1), Some(0))
+    );
+}
+
+#[test]
+fn delete_local_branch_requires_force_when_unmerged() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+
+    create_branch(&repo_path, "merged");
+    create_branch(&repo_path, "unmerged");
+    checkout_branch(&repo_path, "unmerged");
+    write_file(&repo_path, "wip.txt", "wip\n");
+    let _ = s.commit(&repo_path, "wip").unwrap();
+
+    // Checked out, so never deleted
+    assert!(matches!(
+        s.delete_local_branch(&repo_path, "unmerged", true),
+        Err(GitServiceError::BranchCheckedOutElsewhere { .. })
+    ));
+    checkout_branch(&repo_path, "main");
+
+    assert!(matches!(
+        s.delete_local_branch(&repo_path, "unmerged", false),
+        Err(GitServiceError::BranchNotMerged(_))
+    ));
+    s.delete_local_branch(&repo_path, "merged", false).unwrap();
+    s.delete_local_branch(&repo_path, "unmerged", true).unwrap();
+    assert!(!s.check_branch_exists(&repo_path, "merged").unwrap());
+    assert!(!s.check_branch_exists(&repo_path, "unmerged").unwrap());
+
+    assert!(matches!(
+        s.delete_local_branch(&repo_path, "merged", false),
+        Err(GitServiceError::BranchNotFound(_))
+    ));
+}
+
+#[test]
+fn delete_remote_branch_removes_branch_and_tracking_ref() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let remote_path = td.path().join("remote.git");
+    Repository::init_bare(&remote_path).unwrap();
+    let git = GitCli::new();
+    git.git(
+        &repo_path,
+        ["remote", "add", "origin", remote_path.to_str().unwrap()],
+    )
+    .unwrap();
+    create_branch(&repo_path, "feature");
+    git.git(&repo_path, ["push", "origin", "main", "feature"])
+        .unwrap();
+    git.git(&repo_path, ["fetch", "origin"]).unwrap();
+
+    let s = GitService::new();
+    s.delete_remote_branch(&repo_path, "origin", "feature")
+        .unwrap();
+    let remote = Repository::open_bare(&remote_path).unwrap();
+    assert!(remote.find_reference("refs/heads/feature").is_err());
+    assert!(remote.find_reference("refs/heads/main").is_ok());
+    let repo = Repository::open(&repo_path).unwrap();
+    assert!(repo.find_reference("refs/remotes/origin/feature").is_err());
+
+    assert!(matches!(
+        s.delete_remote_branch(&repo_path, "origin", "feature"),
+        Err(GitServiceError::BranchNotFound(_))
+    ));
+}
+
+#[test]
+fn base_commit_follows_moved_branch_tips() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    create_branch(&repo_path, "feature");
+    let initial = s.get_branch_oid(&repo_path, "main").unwrap();
+    assert_eq!(
+        s.get_base_commit(&repo_path, "feature", "main")
+            .unwrap()
+            .to_string(),
+        initial
+    );
+
+    // Advance main, then move feature onto it; the cached merge base must not
+    // be reused for the new tips
+    write_file(&repo_path, "main.txt", "main\n");
+    let _ = s.commit(&repo_path, "advance main").unwrap();
+    let advanced = s.get_branch_oid(&repo_path, "main").unwrap();
+    assert_eq!(
+        s.get_base_commit(&repo_path, "feature", "main")
+            .unwrap()
+            .to_string(),
+        initial
+    );
+    create_branch(&repo_path, "feature");
+    assert_eq!(
+        s.get_base_commit(&repo_path, "feature", "main")
+            .unwrap()
+            .to_string(),
+        advanced
+    );
+
+    s.clear_merge_base_cache();
+    assert_eq!(
+        s.get_base_commit(&repo_path, "feature", "main")
+            .unwrap()
+            .to_string(),
+        advanced
+    );
+}
+
+#[test]
+fn checkout_branch_in_existing_worktree() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "a.txt", "a\n");
+    s.commit(&repo_path, "add a").unwrap();
+    create_branch(&repo_path, "feature");
+    let wt_path = td.path().join("wt-feature");
+    s.add_worktree(&repo_path, &wt_path, "feature", false)
+        .unwrap();
+
+    assert!(matches!(
+        s.checkout_branch(&wt_path, "main", false, false),
+        Err(GitServiceError::BranchCheckedOutElsewhere { .. })
+    ));
+
+    s.checkout_branch(&wt_path, "next", true, false).unwrap();
+    assert_eq!(s.get_head_info(&wt_path).unwrap().branch, "next");
+
+    write_file(&wt_path, "a.txt", "changed\n");
+    assert!(matches!(
+        s.checkout_branch(&wt_path, "feature", false, false),
+        Err(GitServiceError::WorktreeDirty(_, _))
+    ));
+    s.checkout_branch(&wt_path, "feature", false, true).unwrap();
+    assert_eq!(s.get_head_info(&wt_path).unwrap().branch, "feature");
+    assert_eq!(fs::read_to_string(wt_path.join("a.txt")).unwrap(), "a\n");
+}
+
+#[test]
+fn checkout_branch_reports_blocked_switches_and_ignores_deleted_worktrees() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    create_branch(&repo_path, "feature");
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "b.txt", "tracked on feature\n");
+    s.commit(&repo_path, "add b").unwrap();
+    checkout_branch(&repo_path, "main");
+
+    // An untracked file in the way is not a broken repository
+    write_file(&repo_path, "b.txt", "untracked\n");
+    assert!(matches!(
+        s.checkout_branch(&repo_path, "feature", false, false),
+        Err(GitServiceError::WorktreeDirty(_, _))
+    ));
+    fs::remove_file(repo_path.join("b.txt")).unwrap();
+
+    assert!(matches!(
+        s.checkout_branch(&repo_path, "missing", false, false),
+        Err(GitServiceError::BranchNotFound(_))
+    ));
+
+    // A worktree whose directory was deleted no longer holds its branch
+    let wt_path = td.path().join("wt-feature");
+    s.add_worktree(&repo_path, &wt_path, "feature", false)
+        .unwrap();
+    fs::remove_dir_all(&wt_path).unwrap();
+    s.checkout_branch(&repo_path, "feature", false, false)
+        .unwrap();
+    assert_eq!(s.get_head_info(&repo_path).unwrap().branch, "feature");
+}
+
+#[test]
+fn branch_diff_relative_to_merge_base_ignores_base_changes() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "a.txt", "a\n");
+    s.commit(&repo_path, "add a").unwrap();
+
+    create_branch(&repo_path, "feature");
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "b.txt", "b\n");
+    s.commit(&repo_path, "add b").unwrap();
+
+    // main moves on after the branch diverged
+    checkout_branch(&repo_path, "main");
+    write_file(&repo_path, "c.txt", "c\n");
+    s.commit(&repo_path, "add c").unwrap();
+
+    let changed_paths = |merge_base_relative| {
+        let mut paths: Vec<String> = s
+            .get_diffs(
+                DiffTarget::Branch {
+                    repo_path: &repo_path,
+                    branch_name: "feature",
+                    base_branch: "main",
+                    merge_base_relative,
+                },
+                None,
+            )
+            .unwrap()
+            .into_iter()
+            .filter_map(|d| d.new_path.or(d.old_path))
+            .collect();
+        paths.sort();
+        paths
+    };
+    assert_eq!(changed_paths(false), vec!["b.txt", "c.txt"]);
+    assert_eq!(changed_paths(true), vec!["b.txt"]);
+}
+
+#[test]
+fn cancelled_fetch_reports_cancelled() {
+    let td = TempDir::new().unwrap();
+    let remote_path = init_repo_main(&td);
+    let clone_path = td.path().join("clone");
+    GitCli::new()
+        .git(
+            td.path(),
+            [
+                "clone",
+                remote_path.to_str().unwrap(),
+                clone_path.to_str().unwrap(),
+            ],
+        )
+        .unwrap();
+
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let result = GitService::new().fetch_branch(
+        &clone_path,
+        remote_path.to_str().unwrap(),
+        "feature",
+        Some(&cancel),
+        None,
+    );
+    assert!(matches!(result, Err(GitServiceError::Cancelled)));
+}
+
+#[test]
+fn worktree_snapshot_tracks_content_not_just_dirtiness() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "a.txt", "one\n");
+    assert!(s.commit(&repo_path, "base").unwrap());
+
+    let clean = s.worktree_snapshot(&repo_path).unwrap();
+    assert_eq!(clean.tracked_tree, None);
+    assert!(clean.untracked.is_empty());
+
+    write_file(&repo_path, "a.txt", "two\n");
+    write_file(&repo_path, "notes.txt", "scratch\n");
+    let dirty = s.worktree_snapshot(&repo_path).unwrap();
+    assert!(dirty.tracked_tree.is_some());
+    assert_eq!(dirty.head, clean.head);
+    assert!(dirty.untracked.contains_key(b"notes.txt".as_slice()));
+    // Unchanged worktree snapshots the same, and the stash list is untouched
+    assert_eq!(s.worktree_snapshot(&repo_path).unwrap(), dirty);
+    assert!(s.list_stashes(&repo_path).unwrap().is_empty());
+
+    // Editing an already-modified file is still seen
+    write_file(&repo_path, "a.txt", "three\n");
+    assert_ne!(
+        s.worktree_snapshot(&repo_path).unwrap().tracked_tree,
+        dirty.tracked_tree
+    );
+
+    // So is editing an untracked file, or adding one in an untracked directory
+    write_file(&repo_path, "notes.txt", "more scratch\n");
+    let edited = s.worktree_snapshot(&repo_path).unwrap();
+    assert_ne!(edited.untracked, dirty.untracked);
+    write_file(&repo_path, "scratch/new.txt", "x\n");
+    assert!(
+        s.worktree_snapshot(&repo_path)
+            .unwrap()
+            .untracked
+            .contains_key(b"scratch/new.txt".as_slice())
+    );
+}
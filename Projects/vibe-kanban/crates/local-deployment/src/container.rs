@@ -14,7 +14,8 @@ use db::{
     models::{
         coding_agent_turn::CodingAgentTurn,
         execution_process::{
-            ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+            ExecutionChangeOutcome, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
+            ExecutionProcessStatus,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
         repo::Repo,
@@ -31,14 +32,25 @@ use executors::{
         Executable, ExecutorAction, ExecutorActionType,
         coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
+        script::{ScriptContext, ScriptRequest},
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::{ExecutionEnv, RepoContext},
-    executors::{BaseCodingAgent, CancellationToken, ExecutorExitResult, ExecutorExitSignal},
-    logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    executors::{
+        BaseCodingAgent, CancellationToken, ExecutorError, ExecutorExitResult, ExecutorExitSignal,
+        SpawnRetryPolicy, SpawnedChild, spawn_with_retry,
+    },
+    logs::{
+        NormalizedEntry, NormalizedEntryType,
+        utils::{
+            EntryIndexProvider,
+            dev_server_ready::DevServerReadyDetector,
+            patch::{add_normalized_entry, extract_normalized_entry_from_patch},
+        },
+    },
 };
 use futures::{FutureExt, TryStreamExt, stream::select};
-use git::GitService;
+use git::{GitService, WorktreeSnapshot};
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
@@ -46,22 +58,31 @@ use services::services::{
     config::Config,
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
+    execution_slots::ExecutionSlots,
+    file_search::FileSearchCache,
     image::ImageService,
     notification::NotificationService,
     queued_message::QueuedMessageService,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
-use tokio::{sync::RwLock, task::JoinHandle};
+use tokio::{
+    sync::{RwLock, broadcast},
+    task::JoinHandle,
+};
 use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
+    redaction::Redactor,
     text::{git_branch_id, short_uuid, truncate_to_char_boundary},
 };
 use uuid::Uuid;
 
 use crate::{command, copy};
 
+/// How long an executor may take to start before the attempt counts as failed
+const SPAWN_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Clone)]
 pub struct LocalContainerService {
     db: DBService,
@@ -72,6 +93,10 @@ pub struct LocalContainerService {
     /// When stopping execution, we await these to ensure logs are fully persisted.
     db_stream_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
     exit_monitor_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
+    /// Dev server readiness detectors, aborted when their process ends
+    dev_server_ready_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
+    /// Worktree state of each repo (by repo id) when a coding agent run started
+    change_snapshots: Arc<RwLock<HashMap<Uuid, HashMap<Uuid, WorktreeSnapshot>>>>,
     config: Arc<RwLock<Config>>,
     git: GitService,
     image_service: ImageService,
@@ -79,6 +104,8 @@ pub struct LocalContainerService {
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
     notification_service: NotificationService,
+    file_search_cache: Arc<FileSearchCache>,
+    execution_slots: ExecutionSlots,
 }
 
 impl LocalContainerService {
@@ -92,12 +119,16 @@ impl LocalContainerService {
         analytics: Option<AnalyticsContext>,
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
+        file_search_cache: Arc<FileSearchCache>,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let cancellation_tokens = Arc::new(RwLock::new(HashMap::new()));
         let db_stream_handles = Arc::new(RwLock::new(HashMap::new()));
         let exit_monitor_handles = Arc::new(RwLock::new(HashMap::new()));
+        let dev_server_ready_handles = Arc::new(RwLock::new(HashMap::new()));
+        let change_snapshots = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
+        let execution_slots = ExecutionSlots::new(config.read().await.max_concurrent_executions);
 
         let container = LocalContainerService {
             db,
@@ -106,6 +137,8 @@ impl LocalContainerService {
             msg_stores,
             db_stream_handles,
             exit_monitor_handles,
+            dev_server_ready_handles,
+            change_snapshots,
             config,
             git,
             image_service,
@@ -113,6 +146,8 @@ impl LocalContainerService {
             approvals,
             queued_message_service,
             notification_service,
+            file_search_cache,
+            execution_slots,
         };
 
         container.spawn_workspace_cleanup();
@@ -165,6 +200,18 @@ impl LocalContainerService {
         map.remove(id)
     }
 
+    async fn add_dev_server_ready_handle(&self, id: Uuid, handle: JoinHandle<()>) {
+        let mut map = self.dev_server_ready_handles.write().await;
+        map.insert(id, handle);
+    }
+
+    async fn abort_dev_server_ready_detector(&self, id: &Uuid) {
+        let mut map = self.dev_server_ready_handles.write().await;
+        if let Some(handle) = map.remove(id) {
+            handle.abort();
+        }
+    }
+
     pub async fn cleanup_workspace(db: &DBService, workspace: &Workspace) {
         let Some(container_ref) = &workspace.container_ref else {
             return;
@@ -315,7 +362,7 @@ impl LocalContainerService {
         workspace_root: &Path,
         repos: &[Repo],
     ) -> Result<Vec<(Repo, PathBuf)>, ContainerError> {
-        let git = GitService::new();
+        let git = &self.git;
         let mut repos_with_changes = Vec::new();
 
         for repo in repos {
@@ -371,6 +418,111 @@ impl LocalContainerService {
         Ok(false)
     }
 
+    /// Snapshot each repo before a coding agent run so its outcome can be
+    /// judged against the state the agent started from. Best-effort: repos
+    /// whose state can't be read are left out.
+    async fn snapshot_worktrees(&self, workspace: &Workspace, execution_process_id: Uuid) {
+        let repos = match WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await
+        {
+            Ok(repos) => repos,
+            Err(e) => {
+                tracing::warn!("Failed to load repos for change snapshot: {}", e);
+                return;
+            }
+        };
+        let workspace_root = self.workspace_to_current_dir(workspace);
+        let git = self.git().clone();
+        let snapshots = match tokio::task::spawn_blocking(move || {
+            repos
+                .iter()
+                .filter_map(|repo| {
+                    git.worktree_snapshot(&workspace_root.join(&repo.name))
+                        .ok()
+                        .map(|snapshot| (repo.id, snapshot))
+                })
+                .collect::<HashMap<_, _>>()
+        })
+        .await
+        {
+            Ok(snapshots) => snapshots,
+            Err(e) => {
+                tracing::warn!("Change snapshot task failed: {}", e);
+                return;
+            }
+        };
+        self.change_snapshots
+            .write()
+            .await
+            .insert(execution_process_id, snapshots);
+    }
+
+    /// Classify what the run did to the worktrees, before any auto-commit.
+    /// Best-effort: repos whose state can't be read are skipped. Without a
+    /// snapshot from the start of the run (e.g. after a restart) the repo is
+    /// assumed to have been clean at its recorded before-commit.
+    async fn record_change_outcome(&self, ctx: &ExecutionContext) {
+        let workspace_root = self.workspace_to_current_dir(&ctx.workspace);
+        let mut snapshots = self
+            .change_snapshots
+            .write()
+            .await
+            .remove(&ctx.execution_process.id)
+            .unwrap_or_default();
+        let repo_states = match ExecutionProcessRepoState::find_by_execution_process_id(
+            &self.db.pool,
+            ctx.execution_process.id,
+        )
+        .await
+        {
+            Ok(states) => states,
+            Err(e) => {
+                tracing::warn!("Failed to load repo states for change outcome: {}", e);
+                return;
+            }
+        };
+
+        let git = self.git().clone();
+        let repos = ctx.repos.clone();
+        let outcome = tokio::task::spawn_blocking(move || {
+            let mut outcome = ExecutionChangeOutcome::NoChanges;
+            for repo in &repos {
+                let repo_path = workspace_root.join(&repo.name);
+                let Ok(after) = git.worktree_snapshot(&repo_path) else {
+                    continue;
+                };
+                let before = snapshots
+                    .remove(&repo.id)
+                    .unwrap_or_else(|| WorktreeSnapshot {
+                        head: repo_states
+                            .iter()
+                            .find(|s| s.repo_id == repo.id)
+                            .and_then(|s| s.before_head_commit.clone()),
+                        ..Default::default()
+                    });
+                outcome = outcome.max(change_outcome(&before, &after));
+            }
+            outcome
+        })
+        .await;
+        let outcome = match outcome {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                tracing::warn!("Change outcome task failed: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = ExecutionProcess::update_change_outcome(
+            &self.db.pool,
+            ctx.execution_process.id,
+            outcome,
+        )
+        .await
+        {
+            tracing::warn!("Failed to record change outcome: {}", e);
+        }
+    }
+
     /// Commit changes to each repo. Logs failures but continues with other repos.
     fn commit_repos(&self, repos_with_changes: Vec<(Repo, PathBuf)>, message: &str) -> bool {
         let mut any_committed = false;
@@ -385,6 +537,7 @@ impl LocalContainerService {
             match self.git().commit(&worktree_path, message) {
                 Ok(true) => {
                     any_committed = true;
+                    self.file_search_cache.mark_dirty(&worktree_path);
                     tracing::info!("Committed changes in repo '{}'", repo.name);
                 }
                 Ok(false) => {
@@ -399,12 +552,29 @@ impl LocalContainerService {
         any_committed
     }
 
+    /// Resolve once `exec_id` has written no stdout or stderr for `timeout`,
+    /// see [`wait_for_idle_output`]
+    async fn wait_for_idle(&self, exec_id: Uuid, timeout: Duration) {
+        let Some(store) = self.msg_stores.read().await.get(&exec_id).cloned() else {
+            return std::future::pending().await;
+        };
+        wait_for_idle_output(&store, timeout, || {
+            !self
+                .approvals
+                .get_pending_execution_process_ids(&[exec_id])
+                .is_empty()
+        })
+        .await
+    }
+
     /// Spawn a background task that polls the child process for completion and
-    /// cleans up the execution entry when it exits.
+    /// cleans up the execution entry when it exits. With an `idle_timeout`, a
+    /// process that stops writing output is cancelled and marked failed.
     pub fn spawn_exit_monitor(
         &self,
         exec_id: &Uuid,
         exit_signal: Option<ExecutorExitSignal>,
+        idle_timeout: Option<Duration>,
     ) -> JoinHandle<()> {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
@@ -420,6 +590,10 @@ impl LocalContainerService {
             let mut exit_signal_future = exit_signal
                 .map(|rx| rx.boxed()) // wait for result
                 .unwrap_or_else(|| std::future::pending().boxed()); // no signal, stall forever
+            let mut idle_future = match idle_timeout {
+                Some(timeout) => container.wait_for_idle(exec_id, timeout).boxed(),
+                None => std::future::pending().boxed(),
+            };
 
             let status_result: std::io::Result<std::process::ExitStatus>;
 
@@ -448,6 +622,25 @@ impl LocalContainerService {
                 exit_status_result = &mut process_exit_rx => {
                     status_result = exit_status_result.unwrap_or_else(|e| Err(std::io::Error::other(e)));
                 }
+                // No output for the idle timeout: ask the executor to stop, then kill the group
+                () = &mut idle_future => {
+                    let secs = idle_timeout.unwrap_or_default().as_secs();
+                    tracing::warn!("Execution process {} produced no output for {}s, stopping it", exec_id, secs);
+                    if let Some(store) = msg_stores.read().await.get(&exec_id) {
+                        store.push_stderr(format!("No output for {secs}s, stopping the process"));
+                    }
+                    if let Some(cancel) = container.take_cancellation_token(&exec_id).await {
+                        cancel.cancel();
+                        let _ = tokio::time::timeout(Duration::from_secs(5), &mut process_exit_rx).await;
+                    }
+                    if let Some(child_lock) = child_store.read().await.get(&exec_id).cloned() {
+                        let mut child = child_lock.write().await;
+                        if let Err(err) = command::kill_process_group(&mut child).await {
+                            tracing::error!("Failed to kill process group after idle timeout: {} {}", exec_id, err);
+                        }
+                    }
+                    status_result = Ok(failure_exit_status());
+                }
             }
 
             let (exit_code, status) = match status_result {
@@ -469,6 +662,8 @@ impl LocalContainerService {
             {
                 tracing::error!("Failed to update execution process completion: {}", e);
             }
+            container.execution_slots.release(exec_id);
+            container.abort_dev_server_ready_detector(&exec_id).await;
 
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
                 // Update executor session summary if available
@@ -476,6 +671,13 @@ impl LocalContainerService {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
 
+                if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::CodingAgent
+                ) {
+                    container.record_change_outcome(&ctx).await;
+                }
+
                 let success = matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
@@ -663,9 +865,9 @@ impl LocalContainerService {
         format!("{}-{}", short_uuid(workspace_id), task_title_id)
     }
 
-    async fn track_child_msgs_in_store(&self, id: Uuid, child: &mut AsyncGroupChild) {
-        let store = Arc::new(MsgStore::new());
-
+    /// Forward the child's output into `store`, which must already be
+    /// registered for `id` in `msg_stores`.
+    fn track_child_msgs_in_store(store: &Arc<MsgStore>, child: &mut AsyncGroupChild) {
         let out = child.inner().stdout.take().expect("no stdout");
         let err = child.inner().stderr.take().expect("no stderr");
 
@@ -682,9 +884,176 @@ impl LocalContainerService {
         // Merge and forward into the store
         let merged = select(out, err); // Stream<Item = Result<LogMsg, io::Error>>
         store.clone().spawn_forwarder(merged);
+    }
 
-        let mut map = self.msg_stores().write().await;
-        map.insert(id, store);
+    /// Spawn the executor, retrying transient failures with jittered exponential
+    /// backoff as configured, for agents that opt into retries. Each retry is
+    /// announced in `store` so the conversation shows why the agent is slow to
+    /// start.
+    async fn spawn_with_retry(
+        &self,
+        executor_action: &ExecutorAction,
+        current_dir: &Path,
+        approvals_service: Arc<dyn ExecutorApprovalService>,
+        env: &ExecutionEnv,
+        store: &Arc<MsgStore>,
+    ) -> Result<SpawnedChild, ContainerError> {
+        let (max_retries, backoff_ms) = {
+            let config = self.config.read().await;
+            (config.spawn_retry_attempts, config.spawn_retry_backoff_ms)
+        };
+        let max_retries = if executor_action.retries_spawn() {
+            max_retries
+        } else {
+            0
+        };
+        let policy = SpawnRetryPolicy {
+            max_retries,
+            base_delay: Duration::from_millis(u64::from(backoff_ms)),
+        };
+        let entry_index = EntryIndexProvider::start_from(store);
+        spawn_with_retry(
+            policy,
+            || async {
+                tokio::time::timeout(
+                    SPAWN_TIMEOUT,
+                    executor_action.spawn(current_dir, approvals_service.clone(), env),
+                )
+                .await
+                .unwrap_or(Err(ExecutorError::SpawnTimeout(SPAWN_TIMEOUT)))
+                .map_err(ContainerError::from)
+            },
+            |e| matches!(e, ContainerError::ExecutorError(e) if e.is_transient()),
+            |e, delay, retry| {
+                tracing::warn!(
+                    "Spawn failed with a transient error, retrying in {:?} ({}/{}): {}",
+                    delay,
+                    retry,
+                    max_retries,
+                    e
+                );
+                add_normalized_entry(
+                    store,
+                    &entry_index,
+                    NormalizedEntry {
+                        timestamp: None,
+                        entry_type: NormalizedEntryType::SystemMessage,
+                        content: format!(
+                            "Failed to start: {e}. Retrying in {:.1}s (attempt {} of {})",
+                            delay.as_secs_f32(),
+                            retry + 1,
+                            max_retries + 1
+                        ),
+                        metadata: None,
+                    },
+                );
+            },
+        )
+        .await
+    }
+
+    /// Redactor for a new execution's logs, from the current config. Patterns
+    /// are validated when the config is saved; should one still fail, only the
+    /// built-in patterns are used.
+    async fn log_redactor(&self) -> Option<Arc<Redactor>> {
+        let config = self.config.read().await;
+        if !config.log_redaction_enabled {
+            return None;
+        }
+        let redactor = Redactor::new(&config.log_redaction_patterns).or_else(|e| {
+            tracing::warn!(
+                "Invalid log redaction patterns, using built-in patterns only: {}",
+                e
+            );
+            Redactor::new(&[])
+        });
+        redactor.ok().map(Arc::new)
+    }
+
+    /// Environment executors run with in this workspace: repo context plus the
+    /// `VK_*` project, task and workspace variables.
+    pub async fn execution_env(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<ExecutionEnv, ContainerError> {
+        let container_ref = workspace
+            .container_ref
+            .as_ref()
+            .ok_or(ContainerError::Other(anyhow!(
+                "Container ref not found for workspace"
+            )))?;
+
+        let repos = WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
+        let repo_names: Vec<String> = repos.iter().map(|r| r.name.clone()).collect();
+        let repo_context =
+            RepoContext::new(PathBuf::from(container_ref), repo_names, self.git.clone());
+
+        let (commit_reminder, idle_timeout_secs) = {
+            let config = self.config.read().await;
+            (config.commit_reminder, config.executor_idle_timeout_secs)
+        };
+        let mut env = ExecutionEnv::new(repo_context, commit_reminder);
+        env.idle_timeout = idle_timeout_secs.map(|secs| Duration::from_secs(u64::from(secs)));
+
+        // Load task and project context for environment variables
+        let task = workspace
+            .parent_task(&self.db.pool)
+            .await?
+            .ok_or(ContainerError::Other(anyhow!(
+                "Task not found for workspace"
+            )))?;
+        let project = task
+            .parent_project(&self.db.pool)
+            .await?
+            .ok_or(ContainerError::Other(anyhow!("Project not found for task")))?;
+
+        env.insert("VK_PROJECT_NAME", &project.name);
+        env.insert("VK_PROJECT_ID", project.id.to_string());
+        env.insert("VK_TASK_ID", task.id.to_string());
+        env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
+        env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
+
+        Ok(env)
+    }
+
+    /// Announce the dev server's URL in its logs once it is listening, using the
+    /// project's readiness pattern if it has one.
+    async fn spawn_dev_server_ready_detector(
+        &self,
+        exec_id: Uuid,
+        workspace: &Workspace,
+        store: Arc<MsgStore>,
+    ) {
+        let pattern = match workspace.parent_task(&self.db.pool).await {
+            Ok(Some(task)) => match task.parent_project(&self.db.pool).await {
+                Ok(project) => project.and_then(|p| p.dev_server_ready_pattern),
+                Err(e) => {
+                    tracing::warn!("Failed to load project for task {}: {}", task.id, e);
+                    None
+                }
+            },
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Failed to load task for workspace {}: {}", workspace.id, e);
+                None
+            }
+        };
+
+        let detector = DevServerReadyDetector::new(pattern.as_deref()).or_else(|e| {
+            // Patterns are validated on save, so this only happens for rows
+            // written before validation existed
+            tracing::warn!(
+                "Invalid dev server ready pattern for workspace {}, using the default: {}",
+                workspace.id,
+                e
+            );
+            DevServerReadyDetector::new(None)
+        });
+        let Ok(detector) = detector else {
+            return;
+        };
+        self.add_dev_server_ready_handle(exec_id, detector.spawn(store))
+            .await;
     }
 
     /// Create a live diff log stream for ongoing attempts for WebSocket
@@ -900,6 +1269,7 @@ impl LocalContainerService {
                 session_id: agent_session_id,
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir: working_dir.clone(),
+                resume_from: None,
             })
         } else {
             ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
@@ -952,6 +1322,73 @@ impl ContainerService for LocalContainerService {
         &self.notification_service
     }
 
+    fn execution_slots(&self) -> &ExecutionSlots {
+        &self.execution_slots
+    }
+
+    fn queue_execution(
+        &self,
+        workspace: Workspace,
+        execution_process: ExecutionProcess,
+        executor_action: ExecutorAction,
+    ) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            if !container
+                .execution_slots
+                .acquire(execution_process.id)
+                .await
+            {
+                tracing::debug!(
+                    "Queued execution process {} was stopped before it started",
+                    execution_process.id
+                );
+                return;
+            }
+
+            let execution_process = match ExecutionProcess::mark_started(
+                &container.db.pool,
+                execution_process.id,
+            )
+            .await
+            {
+                Ok(true) => ExecutionProcess {
+                    status: ExecutionProcessStatus::Running,
+                    ..execution_process
+                },
+                Ok(false) => {
+                    // Stopped between getting the slot and starting
+                    container.execution_slots.release(execution_process.id);
+                    return;
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to mark queued execution process {} as started: {}",
+                        execution_process.id,
+                        e
+                    );
+                    container.execution_slots.release(execution_process.id);
+                    return;
+                }
+            };
+
+            tracing::info!(
+                "Execution slot freed, starting queued execution process {}",
+                execution_process.id
+            );
+            if let Err(e) = container
+                .launch_execution(&workspace, &execution_process, &executor_action)
+                .await
+            {
+                tracing::error!(
+                    "Failed to start queued execution process {}: {}",
+                    execution_process.id,
+                    e
+                );
+            }
+        });
+    }
+
     async fn store_db_stream_handle(&self, id: Uuid, handle: JoinHandle<()>) {
         self.add_db_stream_handle(id, handle).await;
     }
@@ -964,6 +1401,13 @@ impl ContainerService for LocalContainerService {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn context_warning_threshold(&self) -> Option<u8> {
+        let config = self.config.read().await;
+        config
+            .context_warning_enabled
+            .then_some(config.context_warning_threshold_percent)
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -1003,6 +1447,7 @@ impl ContainerService for LocalContainerService {
             .collect();
 
         let created_workspace = WorkspaceManager::create_workspace(
+            &self.git,
             &workspace_dir,
             &workspace_inputs,
             &workspace.branch,
@@ -1061,8 +1506,13 @@ impl ContainerService for LocalContainerService {
             WorkspaceManager::get_workspace_base_dir().join(&workspace_dir_name)
         };
 
-        WorkspaceManager::ensure_workspace_exists(&workspace_dir, &repositories, &workspace.branch)
-            .await?;
+        WorkspaceManager::ensure_workspace_exists(
+            &self.git,
+            &workspace_dir,
+            &repositories,
+            &workspace.branch,
+        )
+        .await?;
 
         if workspace.container_ref.is_none() {
             Workspace::update_container_ref(
@@ -1137,45 +1587,60 @@ impl ContainerService for LocalContainerService {
                 _ => Arc::new(NoopExecutorApprovalService {}),
             };
 
-        let repos = WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
-        let repo_names: Vec<String> = repos.iter().map(|r| r.name.clone()).collect();
-        let repo_context = RepoContext::new(current_dir.clone(), repo_names);
-
-        let commit_reminder = self.config.read().await.commit_reminder;
-        let mut env = ExecutionEnv::new(repo_context, commit_reminder);
+        let env = self.execution_env(workspace).await?;
 
-        // Load task and project context for environment variables
-        let task = workspace
-            .parent_task(&self.db.pool)
-            .await?
-            .ok_or(ContainerError::Other(anyhow!(
-                "Task not found for workspace"
-            )))?;
-        let project = task
-            .parent_project(&self.db.pool)
-            .await?
-            .ok_or(ContainerError::Other(anyhow!("Project not found for task")))?;
+        if execution_process.run_reason == ExecutionProcessRunReason::CodingAgent {
+            self.snapshot_worktrees(workspace, execution_process.id)
+                .await;
+        }
 
-        env.insert("VK_PROJECT_NAME", &project.name);
-        env.insert("VK_PROJECT_ID", project.id.to_string());
-        env.insert("VK_TASK_ID", task.id.to_string());
-        env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
-        env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
+        // Register the store up front so retry notices stream while spawning
+        let store = Arc::new(
+            MsgStore::with_redactor(self.log_redactor().await)
+                .with_context_warning(self.context_warning_threshold().await),
+        );
+        self.msg_stores()
+            .write()
+            .await
+            .insert(execution_process.id, store.clone());
 
         // Create the child and stream, add to execution tracker with timeout
-        let mut spawned = tokio::time::timeout(
-            Duration::from_secs(30),
-            executor_action.spawn(&current_dir, approvals_service, &env),
-        )
-        .await
-        .map_err(|_| {
-            ContainerError::Other(anyhow!(
-                "Timeout: process took more than 30 seconds to start"
-            ))
-        })??;
+        let mut spawned = match self
+            .spawn_with_retry(
+                executor_action,
+                &current_dir,
+                approvals_service,
+                &env,
+                &store,
+            )
+            .await
+        {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                self.msg_stores()
+                    .write()
+                    .await
+                    .remove(&execution_process.id);
+                self.change_snapshots
+                    .write()
+                    .await
+                    .remove(&execution_process.id);
+                return Err(e);
+            }
+        };
 
-        self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
-            .await;
+        Self::track_child_msgs_in_store(&store, &mut spawned.child);
+
+        if matches!(
+            executor_action.typ(),
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                context: ScriptContext::DevServer,
+                ..
+            })
+        ) {
+            self.spawn_dev_server_ready_detector(execution_process.id, workspace, store.clone())
+                .await;
+        }
 
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
@@ -1186,8 +1651,15 @@ impl ContainerService for LocalContainerService {
                 .await;
         }
 
+        // Only coding agents can stall; scripts and dev servers may legitimately go quiet
+        let idle_timeout = match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(_)
+            | ExecutorActionType::CodingAgentFollowUpRequest(_) => env.idle_timeout,
+            _ => None,
+        };
+
         // Spawn unified exit monitor: watches OS exit and optional executor signal
-        let hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
+        let hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal, idle_timeout);
         self.add_exit_monitor_handle(execution_process.id, hn).await;
 
         Ok(())
@@ -1198,6 +1670,20 @@ impl ContainerService for LocalContainerService {
         execution_process: &ExecutionProcess,
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError> {
+        // Still waiting for a slot: there is no process to stop yet
+        if self.execution_slots.cancel_queued(execution_process.id) {
+            ExecutionProcess::update_completion(&self.db.pool, execution_process.id, status, None)
+                .await?;
+            if let Ok(ctx) =
+                ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
+                && let Err(e) =
+                    Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await
+            {
+                tracing::error!("Failed to update task status to InReview: {e}");
+            }
+            return Ok(());
+        }
+
         let child = self
             .get_child_from_store(&execution_process.id)
             .await
@@ -1246,6 +1732,13 @@ impl ContainerService for LocalContainerService {
             }
         }
         self.remove_child_from_store(&execution_process.id).await;
+        self.change_snapshots
+            .write()
+            .await
+            .remove(&execution_process.id);
+        self.execution_slots.release(execution_process.id);
+        self.abort_dev_server_ready_detector(&execution_process.id)
+            .await;
 
         // Mark the process finished in the MsgStore and wait for DB persistence
         let db_stream_handle = self.take_db_stream_handle(&execution_process.id).await;
@@ -1447,3 +1940,169 @@ fn success_exit_status() -> std::process::ExitStatus {
         ExitStatusExt::from_raw(0)
     }
 }
+
+/// Resolve once `store` has seen no stdout or stderr for `timeout`. While
+/// `awaiting_approval` holds the clock restarts instead, and it never resolves
+/// after the output has ended.
+async fn wait_for_idle_output(
+    store: &MsgStore,
+    timeout: Duration,
+    awaiting_approval: impl Fn() -> bool,
+) {
+    let mut rx = store.get_receiver();
+    let mut deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        match tokio::time::timeout_at(deadline, rx.recv()).await {
+            Ok(Ok(LogMsg::Stdout(_) | LogMsg::Stderr(_)))
+            | Ok(Err(broadcast::error::RecvError::Lagged(_))) => {
+                deadline = tokio::time::Instant::now() + timeout;
+            }
+            Ok(Ok(LogMsg::Finished)) | Ok(Err(broadcast::error::RecvError::Closed)) => {
+                return std::future::pending().await;
+            }
+            Ok(Ok(_)) => {}
+            Err(_) => {
+                if !awaiting_approval() {
+                    return;
+                }
+                deadline = tokio::time::Instant::now() + timeout;
+            }
+        }
+    }
+}
+
+/// What a run did to one repo, judged from its state before and after.
+/// Changes that were already in the worktree when the run started don't count.
+fn change_outcome(before: &WorktreeSnapshot, after: &WorktreeSnapshot) -> ExecutionChangeOutcome {
+    if after.head != before.head || after.tracked_tree != before.tracked_tree {
+        ExecutionChangeOutcome::ChangesMade
+    } else if after.untracked != before.untracked {
+        ExecutionChangeOutcome::OnlyUntracked
+    } else {
+        ExecutionChangeOutcome::NoChanges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+
+    fn snapshot(
+        head: &str,
+        tracked_tree: Option<&str>,
+        untracked: &[(&str, &str)],
+    ) -> WorktreeSnapshot {
+        WorktreeSnapshot {
+            head: Some(head.to_string()),
+            tracked_tree: tracked_tree.map(str::to_string),
+            untracked: untracked
+                .iter()
+                .map(|(path, blob)| (path.as_bytes().to_vec(), blob.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn pre_existing_changes_are_not_the_runs() {
+        let dirty = snapshot("abc", Some("tree1"), &[("notes.txt", "blob1")]);
+        assert_eq!(
+            change_outcome(&dirty, &dirty.clone()),
+            ExecutionChangeOutcome::NoChanges
+        );
+    }
+
+    #[test]
+    fn change_outcome_compares_against_the_start_of_the_run() {
+        let before = snapshot("abc", Some("tree1"), &[("notes.txt", "blob1")]);
+        assert_eq!(
+            change_outcome(
+                &before,
+                &snapshot(
+                    "abc",
+                    Some("tree1"),
+                    &[("notes.txt", "blob1"), ("new.rs", "blob2")]
+                )
+            ),
+            ExecutionChangeOutcome::OnlyUntracked
+        );
+        // Editing a file that was already untracked counts too
+        assert_eq!(
+            change_outcome(
+                &before,
+                &snapshot("abc", Some("tree1"), &[("notes.txt", "blob3")])
+            ),
+            ExecutionChangeOutcome::OnlyUntracked
+        );
+        assert_eq!(
+            change_outcome(
+                &before,
+                &snapshot("abc", Some("tree2"), &[("notes.txt", "blob1")])
+            ),
+            ExecutionChangeOutcome::ChangesMade
+        );
+        // Reverting the pre-existing edits is a change too
+        assert_eq!(
+            change_outcome(&before, &snapshot("abc", None, &[("notes.txt", "blob1")])),
+            ExecutionChangeOutcome::ChangesMade
+        );
+        assert_eq!(
+            change_outcome(
+                &before,
+                &snapshot("def", Some("tree1"), &[("notes.txt", "blob1")])
+            ),
+            ExecutionChangeOutcome::ChangesMade
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_clock_restarts_on_output() {
+        let store = Arc::new(MsgStore::new());
+        let writer = store.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(6)).await;
+            writer.push_stdout("still working");
+        });
+
+        let start = tokio::time::Instant::now();
+        wait_for_idle_output(&store, Duration::from_secs(10), || false).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(16));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn pending_approval_pauses_idle_clock() {
+        let store = MsgStore::new();
+        let awaiting = Arc::new(AtomicBool::new(true));
+        let approver = awaiting.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(25)).await;
+            approver.store(false, Ordering::SeqCst);
+        });
+
+        // Checked at 10s and 20s while the approval is open, fires at the 30s check
+        let start = tokio::time::Instant::now();
+        wait_for_idle_output(&store, Duration::from_secs(10), || {
+            awaiting.load(Ordering::SeqCst)
+        })
+        .await;
+        assert_eq!(start.elapsed(), Duration::from_secs(30));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn finished_output_never_times_out() {
+        let store = Arc::new(MsgStore::new());
+        let writer = store.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            writer.push_finished();
+        });
+
+        let waited = tokio::time::timeout(
+            Duration::from_secs(60),
+            wait_for_idle_output(&store, Duration::from_secs(10), || false),
+        )
+        .await;
+        assert!(waited.is_err());
+    }
+}
@@ -1,24 +1,26 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
-use db::DBService;
-use deployment::{Deployment, DeploymentError, RemoteClientNotConfigured};
-use executors::profile::ExecutorConfigs;
+use db::{DBService, models::workspace::Workspace};
+use deployment::{Deployment, DeploymentError, RemoteClientNotConfigured, RemoteUnavailable};
+use executors::{env::ExecutionEnv, profile::ExecutorConfigs};
 use git::GitService;
 use services::services::{
     analytics::{AnalyticsConfig, AnalyticsContext, AnalyticsService, generate_user_id},
     approvals::Approvals,
     auth::AuthContext,
-    config::{Config, load_config_from_file, save_config_to_file},
-    container::ContainerService,
+    config::{Config, ConfigError, load_config_from_file, save_config_to_file},
+    container::{ContainerError, ContainerService},
     events::EventService,
     file_search::FileSearchCache,
     filesystem::FilesystemService,
     image::ImageService,
     oauth_credentials::OAuthCredentials,
+    pr_monitor::PrMonitorHandle,
     project::ProjectService,
     queued_message::QueuedMessageService,
     remote_client::{RemoteClient, RemoteClientError},
+    remote_connectivity::RemoteConnectivityState,
     repo::RepoService,
     worktree_manager::WorktreeManager,
 };
@@ -56,6 +58,7 @@ pub struct LocalDeployment {
     auth_context: AuthContext,
     oauth_handoffs: Arc<RwLock<HashMap<Uuid, PendingHandoff>>>,
     pty: PtyService,
+    pr_monitor: PrMonitorHandle,
 }
 
 #[derive(Debug, Clone)]
@@ -88,18 +91,46 @@ impl Deployment for LocalDeployment {
             }
         }
 
-        // Always save config (may have been migrated or version updated)
-        save_config_to_file(&raw_config, &config_path()).await?;
+        // Always save config (may have been migrated or version updated). A config that
+        // fails validation is still loaded so the user can fix it from settings.
+        match save_config_to_file(&raw_config, &config_path()).await {
+            Ok(()) => {}
+            Err(ConfigError::ValidationError(message)) => {
+                tracing::warn!("Stored config is invalid, not re-saving: {}", message);
+            }
+            Err(e @ ConfigError::NewerVersionNotBackedUp(_)) => {
+                tracing::error!("{}", e);
+            }
+            Err(e) => return Err(e.into()),
+        }
 
         if let Some(workspace_dir) = &raw_config.workspace_dir {
             let path = utils::path::expand_tilde(workspace_dir);
             WorktreeManager::set_workspace_dir_override(path);
         }
 
+        let pr_monitor = PrMonitorHandle::new(Duration::from_secs(
+            raw_config.pr_monitor_poll_interval_secs.into(),
+        ));
+
+        let pty = PtyService::new(
+            raw_config.terminal_output_buffer_kb as usize * 1024,
+            raw_config.max_terminal_sessions as usize,
+        );
+        let git = match &raw_config.commit_author {
+            Some(author) => GitService::with_identity(&author.name, &author.email),
+            None => GitService::new(),
+        };
+        git.set_commit_signing(raw_config.sign_commits);
+        git.set_file_content(raw_config.file_content.clone());
+        git.set_commit_message_rules(raw_config.commit_message_rules.clone());
+        git.set_preferred_remotes(raw_config.preferred_remotes.clone());
+        git.set_shallow_deepening(raw_config.deepen_shallow_clones);
+        git.set_init_submodules(raw_config.init_submodules);
+
         let config = Arc::new(RwLock::new(raw_config));
         let user_id = generate_user_id();
         let analytics = AnalyticsConfig::new().map(AnalyticsService::new);
-        let git = GitService::new();
         let project = ProjectService::new();
         let repo = RepoService::new();
         let msg_stores = Arc::new(RwLock::new(HashMap::new()));
@@ -149,6 +180,7 @@ impl Deployment for LocalDeployment {
             Some(url) => match RemoteClient::new(&url, auth_context.clone()) {
                 Ok(client) => {
                     tracing::info!("Remote client initialized with URL: {}", url);
+                    client.connectivity().spawn_checker(client.clone());
                     Ok(client)
                 }
                 Err(e) => {
@@ -170,6 +202,8 @@ impl Deployment for LocalDeployment {
             user_id: user_id.clone(),
             analytics_service: s.clone(),
         });
+        let file_search_cache = Arc::new(FileSearchCache::new(git.clone()));
+
         let container = LocalContainerService::new(
             db.clone(),
             msg_stores.clone(),
@@ -179,15 +213,12 @@ impl Deployment for LocalDeployment {
             analytics_ctx,
             approvals.clone(),
             queued_message_service.clone(),
+            file_search_cache.clone(),
         )
         .await;
 
         let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
 
-        let file_search_cache = Arc::new(FileSearchCache::new());
-
-        let pty = PtyService::new();
-
         let deployment = Self {
             config,
             user_id,
@@ -207,6 +238,7 @@ impl Deployment for LocalDeployment {
             auth_context,
             oauth_handoffs,
             pty,
+            pr_monitor,
         };
 
         Ok(deployment)
@@ -271,11 +303,29 @@ impl Deployment for LocalDeployment {
     fn auth_context(&self) -> &AuthContext {
         &self.auth_context
     }
+
+    fn pr_monitor(&self) -> &PrMonitorHandle {
+        &self.pr_monitor
+    }
 }
 
 impl LocalDeployment {
-    pub fn remote_client(&self) -> Result<RemoteClient, RemoteClientNotConfigured> {
-        self.remote_client.clone()
+    /// The remote client, unless it is unconfigured or the server is currently
+    /// unreachable. Callers surface [`RemoteUnavailable::Offline`] as a single
+    /// "offline" state instead of failing each request separately.
+    pub fn remote_client(&self) -> Result<RemoteClient, RemoteUnavailable> {
+        let client = self.remote_client.clone()?;
+        if !client.connectivity().is_online() {
+            return Err(RemoteUnavailable::Offline);
+        }
+        Ok(client)
+    }
+
+    pub fn remote_connectivity(&self) -> RemoteConnectivityState {
+        match &self.remote_client {
+            Ok(client) => client.connectivity().state(),
+            Err(RemoteClientNotConfigured) => RemoteConnectivityState::NotConfigured,
+        }
     }
 
     pub async fn get_login_status(&self) -> LoginStatus {
@@ -290,8 +340,10 @@ impl LocalDeployment {
             };
         }
 
-        let Ok(client) = self.remote_client() else {
-            return LoginStatus::LoggedOut;
+        let client = match self.remote_client() {
+            Ok(client) => client,
+            Err(RemoteUnavailable::Offline) => return LoginStatus::Offline,
+            Err(RemoteUnavailable::NotConfigured) => return LoginStatus::LoggedOut,
         };
 
         match client.profile().await {
@@ -299,12 +351,20 @@ impl LocalDeployment {
                 self.auth_context.set_profile(profile.clone()).await;
                 LoginStatus::LoggedIn { profile }
             }
+            // Only a rejected token means the user is signed out
             Err(RemoteClientError::Auth) => {
                 let _ = self.auth_context.clear_credentials().await;
                 self.auth_context.clear_profile().await;
                 LoginStatus::LoggedOut
             }
-            Err(_) => LoginStatus::LoggedOut,
+            Err(e) if e.should_retry() => {
+                tracing::warn!("Remote unreachable while checking login status: {}", e);
+                LoginStatus::Offline
+            }
+            Err(e) => {
+                tracing::warn!("Failed to fetch profile: {}", e);
+                LoginStatus::LoggedOut
+            }
         }
     }
 
@@ -334,4 +394,13 @@ impl LocalDeployment {
     pub fn pty(&self) -> &PtyService {
         &self.pty
     }
+
+    /// The environment a coding agent in `workspace` would be started with, for
+    /// opening shells that match it.
+    pub async fn workspace_execution_env(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<ExecutionEnv, ContainerError> {
+        self.container.execution_env(workspace).await
+    }
 }
@@ -1,17 +1,24 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     io::{Read, Write},
-    path::PathBuf,
-    sync::{Arc, Mutex},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
     thread,
 };
 
-use portable_pty::{CommandBuilder, NativePtySystem, PtySize, PtySystem};
+use executors::env::{ExecutionEnv, SERVER_ENV_VARS};
+use portable_pty::{ChildKiller, CommandBuilder, NativePtySystem, PtySize, PtySystem};
 use thiserror::Error;
-use tokio::sync::mpsc;
+use tokio::sync::Notify;
 use utils::shell::get_interactive_shell;
 use uuid::Uuid;
 
+/// Written in place of output dropped because the client fell too far behind.
+const DROPPED_OUTPUT_MARKER: &[u8] = b"\r\n\x1b[2m[... earlier output dropped ...]\x1b[0m\r\n";
+
 #[derive(Debug, Error)]
 pub enum PtyError {
     #[error("Failed to create PTY: {0}")]
@@ -24,35 +31,162 @@ pub enum PtyError {
     ResizeFailed(String),
     #[error("Session already closed")]
     SessionClosed,
+    #[error("Too many open terminals (limit {0}); close one and try again")]
+    TooManySessions(usize),
 }
 
 struct PtySession {
     writer: Box<dyn Write + Send>,
     master: Box<dyn portable_pty::MasterPty + Send>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
     _output_handle: thread::JoinHandle<()>,
     closed: bool,
 }
 
+/// Output read from the PTY that the client hasn't taken yet. Capped so a
+/// chatty process (e.g. a dev server) behind a slow or stalled client can't
+/// grow memory without bound; the oldest bytes go first.
+struct OutputBuffer {
+    data: VecDeque<u8>,
+    limit: usize,
+    dropped: bool,
+    finished: bool,
+}
+
+impl OutputBuffer {
+    fn new(limit: usize) -> Self {
+        Self {
+            data: VecDeque::new(),
+            limit: limit.max(1),
+            dropped: false,
+            finished: false,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.data.extend(bytes);
+        if self.data.len() > self.limit {
+            let excess = self.data.len() - self.limit;
+            self.data.drain(..excess);
+            self.dropped = true;
+        }
+    }
+
+    fn take(&mut self) -> Option<Vec<u8>> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let mut chunk = Vec::with_capacity(self.data.len() + DROPPED_OUTPUT_MARKER.len());
+        if std::mem::take(&mut self.dropped) {
+            chunk.extend_from_slice(DROPPED_OUTPUT_MARKER);
+        }
+        chunk.extend(self.data.drain(..));
+        Some(chunk)
+    }
+}
+
+struct SharedOutput {
+    buffer: Mutex<OutputBuffer>,
+    ready: Notify,
+}
+
+/// Receiving end of a session's output.
+pub struct PtyOutput {
+    shared: Arc<SharedOutput>,
+}
+
+impl PtyOutput {
+    /// Wait for the next output, or `None` once the shell has exited and
+    /// everything has been read.
+    pub async fn recv(&mut self) -> Option<Vec<u8>> {
+        loop {
+            {
+                let mut buffer = self.shared.buffer.lock().ok()?;
+                if let Some(chunk) = buffer.take() {
+                    return Some(chunk);
+                }
+                if buffer.finished {
+                    return None;
+                }
+            }
+            self.shared.ready.notified().await;
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct PtyService {
     sessions: Arc<Mutex<HashMap<Uuid, PtySession>>>,
+    output_buffer_limit: Arc<AtomicUsize>,
+    /// Upper bound on open sessions; each one holds a shell process and a
+    /// reader thread until its client disconnects.
+    max_sessions: Arc<AtomicUsize>,
 }
 
 impl PtyService {
-    pub fn new() -> Self {
+    pub fn new(output_buffer_limit: usize, max_sessions: usize) -> Self {
         Self {
             sessions: Arc::new(Mutex::new(HashMap::new())),
+            output_buffer_limit: Arc::new(AtomicUsize::new(output_buffer_limit)),
+            max_sessions: Arc::new(AtomicUsize::new(max_sessions)),
         }
     }
 
+    /// Applies to sessions opened after the call.
+    pub fn set_output_buffer_limit(&self, bytes: usize) {
+        self.output_buffer_limit.store(bytes, Ordering::Relaxed);
+    }
+
+    /// Sessions already open stay open when lowering the limit; new ones are
+    /// refused until enough have closed.
+    pub fn set_max_sessions(&self, max_sessions: usize) {
+        self.max_sessions.store(max_sessions, Ordering::Relaxed);
+    }
+
     pub async fn create_session(
         &self,
         working_dir: PathBuf,
         cols: u16,
         rows: u16,
-    ) -> Result<(Uuid, mpsc::UnboundedReceiver<Vec<u8>>), PtyError> {
+    ) -> Result<(Uuid, PtyOutput), PtyError> {
+        self.open_session(working_dir, HashMap::new(), cols, rows)
+            .await
+    }
+
+    /// Open a shell in a workspace worktree with the same environment variables
+    /// a coding agent there is started with, so the user sees what the agent sees.
+    /// Like the agent, the shell doesn't inherit [`SERVER_ENV_VARS`].
+    pub async fn spawn_shell_in(
+        &self,
+        worktree_path: &Path,
+        env: &ExecutionEnv,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(Uuid, PtyOutput), PtyError> {
+        self.open_session(worktree_path.to_path_buf(), env.vars.clone(), cols, rows)
+            .await
+    }
+
+    async fn open_session(
+        &self,
+        working_dir: PathBuf,
+        env_vars: HashMap<String, String>,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(Uuid, PtyOutput), PtyError> {
+        // Fail before spawning a shell when at the limit; re-checked on insert
+        self.ensure_capacity()?;
+
         let session_id = Uuid::new_v4();
-        let (output_tx, output_rx) = mpsc::unbounded_channel();
+        let shared = Arc::new(SharedOutput {
+            buffer: Mutex::new(OutputBuffer::new(
+                self.output_buffer_limit.load(Ordering::Relaxed),
+            )),
+            ready: Notify::new(),
+        });
+        let output = PtyOutput {
+            shared: shared.clone(),
+        };
         let shell = get_interactive_shell().await;
 
         let result = tokio::task::spawn_blocking(move || {
@@ -69,6 +203,9 @@ impl PtyService {
 
             let mut cmd = CommandBuilder::new(&shell);
             cmd.cwd(&working_dir);
+            for var in SERVER_ENV_VARS {
+                cmd.env_remove(var);
+            }
 
             // Configure shell-specific options
             let shell_name = shell.file_name().and_then(|n| n.to_str()).unwrap_or("");
@@ -91,6 +228,10 @@ impl PtyService {
                 }
             }
 
+            for (key, value) in &env_vars {
+                cmd.env(key, value);
+            }
+
             cmd.env("TERM", "xterm-256color");
             cmd.env("COLORTERM", "truecolor");
 
@@ -98,6 +239,7 @@ impl PtyService {
                 .slave
                 .spawn_command(cmd)
                 .map_err(|e| PtyError::CreateFailed(e.to_string()))?;
+            let killer = child.clone_killer();
 
             let mut writer = pty_pair
                 .master
@@ -122,36 +264,64 @@ impl PtyService {
                     match reader.read(&mut buf) {
                         Ok(0) => break,
                         Ok(n) => {
-                            if output_tx.send(buf[..n].to_vec()).is_err() {
+                            // Nobody left to read the output
+                            if Arc::strong_count(&shared) == 1 {
                                 break;
                             }
+                            if let Ok(mut buffer) = shared.buffer.lock() {
+                                buffer.push(&buf[..n]);
+                            }
+                            shared.ready.notify_one();
                         }
                         Err(_) => break,
                     }
                 }
+                if let Ok(mut buffer) = shared.buffer.lock() {
+                    buffer.finished = true;
+                }
+                shared.ready.notify_one();
                 drop(child);
             });
 
-            Ok::<_, PtyError>((pty_pair.master, writer, output_handle))
+            Ok::<_, PtyError>((pty_pair.master, writer, killer, output_handle))
         })
         .await
         .map_err(|e| PtyError::CreateFailed(e.to_string()))??;
 
-        let (master, writer, output_handle) = result;
+        let (master, writer, mut killer, output_handle) = result;
+
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|e| PtyError::CreateFailed(e.to_string()))?;
+        let max_sessions = self.max_sessions.load(Ordering::Relaxed);
+        if sessions.len() >= max_sessions {
+            let _ = killer.kill();
+            return Err(PtyError::TooManySessions(max_sessions));
+        }
 
         let session = PtySession {
             writer,
             master,
+            killer,
             _output_handle: output_handle,
             closed: false,
         };
+        sessions.insert(session_id, session);
 
-        self.sessions
-            .lock()
-            .map_err(|e| PtyError::CreateFailed(e.to_string()))?
-            .insert(session_id, session);
+        Ok((session_id, output))
+    }
 
-        Ok((session_id, output_rx))
+    fn ensure_capacity(&self) -> Result<(), PtyError> {
+        let sessions = self
+            .sessions
+            .lock()
+            .map_err(|e| PtyError::CreateFailed(e.to_string()))?;
+        let max_sessions = self.max_sessions.load(Ordering::Relaxed);
+        if sessions.len() >= max_sessions {
+            return Err(PtyError::TooManySessions(max_sessions));
+        }
+        Ok(())
     }
 
     pub async fn write(&self, session_id: Uuid, data: &[u8]) -> Result<(), PtyError> {
@@ -180,7 +350,15 @@ impl PtyService {
         Ok(())
     }
 
+    /// Set the PTY window size; the kernel sends the shell `SIGWINCH` so
+    /// full-screen programs redraw at the new size.
     pub async fn resize(&self, session_id: Uuid, cols: u16, rows: u16) -> Result<(), PtyError> {
+        if cols == 0 || rows == 0 {
+            return Err(PtyError::ResizeFailed(format!(
+                "invalid terminal size {cols}x{rows}"
+            )));
+        }
+
         let sessions = self
             .sessions
             .lock()
@@ -206,6 +384,8 @@ impl PtyService {
         Ok(())
     }
 
+    /// Remove the session and kill its shell. Closing the master alone leaves
+    /// shells that ignore SIGHUP (or their background jobs) running.
     pub async fn close_session(&self, session_id: Uuid) -> Result<(), PtyError> {
         if let Some(mut session) = self
             .sessions
@@ -214,6 +394,7 @@ impl PtyService {
             .remove(&session_id)
         {
             session.closed = true;
+            let _ = session.killer.kill();
         }
         Ok(())
     }
@@ -226,8 +407,94 @@ impl PtyService {
     }
 }
 
-impl Default for PtyService {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_buffer_drops_oldest_bytes_past_limit() {
+        let mut buffer = OutputBuffer::new(8);
+        buffer.push(b"hello ");
+        buffer.push(b"world");
+
+        let chunk = buffer.take().unwrap();
+        assert!(chunk.starts_with(DROPPED_OUTPUT_MARKER));
+        assert!(chunk.ends_with(b"lo world"));
+        assert!(buffer.take().is_none());
+
+        buffer.push(b"ok");
+        assert_eq!(buffer.take().unwrap(), b"ok");
+    }
+
+    #[tokio::test]
+    async fn output_recv_drains_then_ends_when_finished() {
+        let shared = Arc::new(SharedOutput {
+            buffer: Mutex::new(OutputBuffer::new(1024)),
+            ready: Notify::new(),
+        });
+        let mut output = PtyOutput {
+            shared: shared.clone(),
+        };
+
+        shared.buffer.lock().unwrap().push(b"$ ");
+        shared.buffer.lock().unwrap().finished = true;
+        shared.ready.notify_one();
+
+        assert_eq!(output.recv().await.unwrap(), b"$ ");
+        assert!(output.recv().await.is_none());
+    }
+
+    /// Reads session output until it contains `needle`
+    async fn wait_for_output(output: &mut PtyOutput, needle: &str) -> String {
+        let mut seen = String::new();
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            while let Some(chunk) = output.recv().await {
+                seen.push_str(&String::from_utf8_lossy(&chunk));
+                if seen.contains(needle) {
+                    return;
+                }
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("{needle:?} never appeared in {seen:?}"));
+        seen
+    }
+
+    #[tokio::test]
+    async fn sessions_past_the_limit_are_refused_until_one_closes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let pty = PtyService::new(1024, 1);
+
+        let (first, _output) = pty
+            .create_session(dir.path().to_path_buf(), 80, 24)
+            .await
+            .unwrap();
+        assert!(matches!(
+            pty.create_session(dir.path().to_path_buf(), 80, 24).await,
+            Err(PtyError::TooManySessions(1))
+        ));
+
+        pty.close_session(first).await.unwrap();
+        let (second, _output) = pty
+            .create_session(dir.path().to_path_buf(), 80, 24)
+            .await
+            .unwrap();
+        pty.close_session(second).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn worktree_shell_gets_the_agent_environment() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let pty = PtyService::new(64 * 1024, 4);
+        let mut env = ExecutionEnv::new(Default::default(), false);
+        env.insert("VK_PTY_TEST_VAR", "from-agent-env");
+
+        let (session, mut output) = pty.spawn_shell_in(dir.path(), &env, 80, 24).await.unwrap();
+        pty.write(session, b"echo \"seen:$VK_PTY_TEST_VAR\"\n")
+            .await
+            .unwrap();
+        wait_for_output(&mut output, "seen:from-agent-env").await;
+        pty.close_session(session).await.unwrap();
     }
 }
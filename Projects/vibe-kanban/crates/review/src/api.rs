@@ -27,7 +27,32 @@ struct InitRequest {
     pr_title: String,
 }
 
+/// A repository included in a review, mirroring the executors' `RepoReviewContext`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoReviewContext {
+    pub org: String,
+    pub repo_name: String,
+    pub base_commit: String,
+    /// Directory of the repository within the uploaded archive, empty for the
+    /// archive root
+    pub path: String,
+}
+
+/// What the uploaded archive contains
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveMode {
+    /// Full checkout of each repository at the PR head
+    Full,
+    /// Only the PR's changed files and its patch (`.pr.diff`) per repository
+    DiffOnly,
+}
+
 /// Request body for POST /review/start
+///
+/// `org`, `repo` and `base_commit` describe the primary repository; `repos`
+/// lists every repository in the archive, including the primary one.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StartRequest {
@@ -38,6 +63,8 @@ pub struct StartRequest {
     pub repo: String,
     pub codebase_url: String,
     pub base_commit: String,
+    pub repos: Vec<RepoReviewContext>,
+    pub archive_mode: ArchiveMode,
 }
 
 /// Response from GET /review/{id}/status
@@ -73,7 +100,9 @@ impl std::fmt::Display for ReviewStatus {
 }
 
 impl ReviewApiClient {
-    /// Create a new API client
+    /// Create a new API client. Requests, including the archive upload, go
+    /// through the proxies reqwest reads from `HTTP_PROXY`/`HTTPS_PROXY`, except
+    /// for hosts listed in `NO_PROXY`.
     pub fn new(base_url: String) -> Self {
         Self {
             client: Client::new(),
@@ -1,11 +1,15 @@
 use std::{fs::File, path::Path};
 
 use flate2::{Compression, write::GzEncoder};
+use git2::{Delta, DiffFindOptions, DiffFormat, FileMode, Oid, Repository};
 use tar::Builder;
 use tracing::debug;
 
 use crate::error::ReviewError;
 
+/// Name of the patch file written alongside the changed files in diff-only mode
+pub const DIFF_FILE_NAME: &str = ".pr.diff";
+
 /// Create a tar.gz archive from a directory
 pub fn create_tarball(source_dir: &Path) -> Result<Vec<u8>, ReviewError> {
     debug!("Creating tarball from {}", source_dir.display());
@@ -68,6 +72,82 @@ fn add_directory_to_archive<W: std::io::Write>(
     Ok(())
 }
 
+/// Write the PR's changes into `out_dir` for a diff-only upload: the patch from
+/// the merge base of `base_commit` and `head_commit` to `head_commit` as
+/// [`DIFF_FILE_NAME`], plus the head version of every added or modified file.
+///
+/// Returns the number of changed files written.
+pub fn write_diff_only_tree(
+    repo_dir: &Path,
+    base_commit: &str,
+    head_commit: &str,
+    out_dir: &Path,
+) -> Result<usize, ReviewError> {
+    let git_err = |e: git2::Error| ReviewError::ArchiveFailed(e.message().to_string());
+    let io_err = |e: std::io::Error| ReviewError::ArchiveFailed(e.to_string());
+
+    let repo = Repository::open(repo_dir).map_err(git_err)?;
+    let base = Oid::from_str(base_commit).map_err(git_err)?;
+    let head = repo
+        .find_commit(Oid::from_str(head_commit).map_err(git_err)?)
+        .map_err(git_err)?;
+
+    // Match GitHub's PR diff, which compares against the merge base
+    let merge_base = repo.merge_base(base, head.id()).map_err(git_err)?;
+    let base_tree = repo
+        .find_commit(merge_base)
+        .and_then(|c| c.tree())
+        .map_err(git_err)?;
+    let head_tree = head.tree().map_err(git_err)?;
+
+    let mut diff = repo
+        .diff_tree_to_tree(Some(&base_tree), Some(&head_tree), None)
+        .map_err(git_err)?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+        .map_err(git_err)?;
+
+    let mut patch = Vec::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin() as u8);
+        }
+        patch.extend_from_slice(line.content());
+        true
+    })
+    .map_err(git_err)?;
+
+    std::fs::create_dir_all(out_dir).map_err(io_err)?;
+    std::fs::write(out_dir.join(DIFF_FILE_NAME), patch).map_err(io_err)?;
+
+    let mut written = 0;
+    for delta in diff.deltas() {
+        let file = delta.new_file();
+        // Deleted files only appear in the patch; submodules have no content
+        if delta.status() == Delta::Deleted || file.mode() == FileMode::Commit {
+            continue;
+        }
+        let Some(path) = file.path() else {
+            continue;
+        };
+
+        let blob = repo.find_blob(file.id()).map_err(git_err)?;
+        let target = out_dir.join(path);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(io_err)?;
+        }
+        std::fs::write(&target, blob.content()).map_err(io_err)?;
+        written += 1;
+    }
+
+    debug!(
+        "Wrote {written} changed file(s) from {} to {}",
+        repo_dir.display(),
+        out_dir.display()
+    );
+
+    Ok(written)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -103,4 +183,78 @@ mod tests {
         assert!(entries.contains(&"file1.txt".to_string()));
         assert!(entries.contains(&"subdir/file2.txt".to_string()));
     }
+
+    fn commit_files(repo: &Repository, files: &[(&str, Option<&str>)]) -> Oid {
+        let workdir = repo.workdir().unwrap();
+        let mut index = repo.index().unwrap();
+        for (path, content) in files {
+            match content {
+                Some(content) => {
+                    let full = workdir.join(path);
+                    std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+                    std::fs::write(full, content).unwrap();
+                    index.add_path(Path::new(path)).unwrap();
+                }
+                None => {
+                    std::fs::remove_file(workdir.join(path)).unwrap();
+                    index.remove_path(Path::new(path)).unwrap();
+                }
+            }
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parents: Vec<_> = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parents: Vec<_> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, "commit", &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_write_diff_only_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_dir = temp_dir.path().join("repo");
+        let repo = Repository::init(&repo_dir).unwrap();
+
+        let base = commit_files(
+            &repo,
+            &[
+                ("unchanged.txt", Some("same")),
+                ("modified.txt", Some("before")),
+                ("deleted.txt", Some("gone soon")),
+            ],
+        );
+        let head = commit_files(
+            &repo,
+            &[
+                ("modified.txt", Some("after")),
+                ("deleted.txt", None),
+                ("src/added.rs", Some("fn main() {}")),
+            ],
+        );
+
+        let out_dir = temp_dir.path().join("out");
+        let written =
+            write_diff_only_tree(&repo_dir, &base.to_string(), &head.to_string(), &out_dir)
+                .unwrap();
+
+        assert_eq!(written, 2);
+        assert_eq!(
+            std::fs::read_to_string(out_dir.join("modified.txt")).unwrap(),
+            "after"
+        );
+        assert!(out_dir.join("src/added.rs").exists());
+        assert!(!out_dir.join("unchanged.txt").exists());
+        assert!(!out_dir.join("deleted.txt").exists());
+
+        let patch = std::fs::read_to_string(out_dir.join(DIFF_FILE_NAME)).unwrap();
+        assert!(patch.contains("-before"));
+        assert!(patch.contains("+after"));
+        assert!(patch.contains("deleted.txt"));
+    }
 }
@@ -2,6 +2,7 @@ use std::{
     fs::{self, File},
     io::{BufRead, BufReader},
     path::{Path, PathBuf},
+    process::Command,
     time::SystemTime,
 };
 
@@ -16,6 +17,8 @@ pub struct ClaudeProject {
     pub path: PathBuf,
     pub name: String,
     pub git_branch: Option<String>,
+    /// Working directory of the most recent session
+    pub cwd: Option<PathBuf>,
     pub first_prompt: Option<String>,
     pub session_count: usize,
     pub modified_at: SystemTime,
@@ -26,6 +29,7 @@ pub struct ClaudeProject {
 pub struct ClaudeSession {
     pub path: PathBuf,
     pub git_branch: Option<String>,
+    pub cwd: Option<PathBuf>,
     pub first_prompt: Option<String>,
     pub modified_at: SystemTime,
 }
@@ -35,6 +39,7 @@ pub struct ClaudeSession {
 #[serde(rename_all = "camelCase")]
 struct JsonlRecord {
     git_branch: Option<String>,
+    cwd: Option<String>,
     message: Option<JsonlMessage>,
 }
 
@@ -45,6 +50,115 @@ struct JsonlMessage {
     content: Option<serde_json::Value>,
 }
 
+/// The GitHub repository a PR belongs to, used to scope session discovery
+#[derive(Debug, Clone)]
+pub struct RepoScope {
+    pub owner: String,
+    pub repo: String,
+}
+
+impl RepoScope {
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.repo)
+    }
+
+    /// Whether a session run in `cwd` was working on this repository. `None`
+    /// when that can't be told, e.g. the directory has since been removed.
+    pub fn matches(&self, cwd: &Path) -> Option<bool> {
+        let remotes = github_remotes_of(cwd);
+        if !remotes.is_empty() {
+            return Some(self.matches_remotes(remotes.iter().map(|(_, r)| r.as_str())));
+        }
+
+        // Worktrees and multi-repo workspaces name directories after the repo
+        let names_repo = cwd.components().any(|c| {
+            c.as_os_str()
+                .to_str()
+                .is_some_and(|s| s.eq_ignore_ascii_case(&self.repo))
+        });
+        if names_repo || cwd.join(&self.repo).is_dir() {
+            return Some(true);
+        }
+
+        cwd.is_dir().then_some(false)
+    }
+
+    /// Whether any of a checkout's GitHub remotes (`owner/repo`) is this
+    /// repository. Fork checkouts usually only have the fork as `origin`, so a
+    /// remote with the same repository name under another owner also counts.
+    fn matches_remotes<'a>(&self, remotes: impl IntoIterator<Item = &'a str>) -> bool {
+        let full_name = self.full_name();
+        remotes.into_iter().any(|remote| {
+            remote.eq_ignore_ascii_case(&full_name)
+                || remote
+                    .split_once('/')
+                    .is_some_and(|(_, repo)| repo.eq_ignore_ascii_case(&self.repo))
+        })
+    }
+}
+
+impl ClaudeProject {
+    /// Whether this project belongs to `scope`; see [`RepoScope::matches`]
+    pub fn matches_repo(&self, scope: &RepoScope) -> Option<bool> {
+        self.cwd.as_deref().and_then(|cwd| scope.matches(cwd))
+    }
+
+    /// `owner/repo` of the project's working directory, falling back to the
+    /// directory name
+    pub fn repo_label(&self) -> Option<String> {
+        let cwd = self.cwd.as_deref()?;
+        let remotes = github_remotes_of(cwd);
+        remotes
+            .iter()
+            .find(|(name, _)| name == "origin")
+            .or(remotes.first())
+            .map(|(_, repo)| repo.clone())
+            .or_else(|| {
+                cwd.file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| n.to_string())
+            })
+    }
+}
+
+/// `(remote name, owner/repo)` of each of the directory's remotes hosted on
+/// GitHub
+fn github_remotes_of(dir: &Path) -> Vec<(String, String)> {
+    if !dir.is_dir() {
+        return Vec::new();
+    }
+    let Ok(output) = Command::new("git")
+        .args(["config", "--get-regexp", r"^remote\..*\.url$"])
+        .current_dir(dir)
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (key, url) = line.split_once(' ')?;
+            let name = key.strip_prefix("remote.")?.strip_suffix(".url")?;
+            Some((name.to_string(), parse_github_remote(url.trim())?))
+        })
+        .collect()
+}
+
+/// Parse `owner/repo` from an HTTPS or SSH GitHub remote URL
+fn parse_github_remote(url: &str) -> Option<String> {
+    let (_, path) = url.split_once("github.com")?;
+    let path = path
+        .trim_start_matches([':', '/'])
+        .trim_end_matches('/')
+        .trim_end_matches(".git");
+    let (owner, repo) = path.split_once('/')?;
+    (!owner.is_empty() && !repo.is_empty() && !repo.contains('/'))
+        .then(|| format!("{owner}/{repo}"))
+}
+
 /// Get the Claude projects directory path (~/.claude/projects)
 pub fn get_claude_projects_dir() -> Option<PathBuf> {
     dirs::home_dir().map(|home| home.join(".claude").join("projects"))
@@ -106,11 +220,13 @@ pub fn discover_projects() -> Result<Vec<ClaudeProject>, ReviewError> {
         let most_recent = &sessions[0]; // Already sorted by modification time
         let git_branch = most_recent.git_branch.clone();
         let first_prompt = most_recent.first_prompt.clone();
+        let cwd = most_recent.cwd.clone();
 
         projects.push(ClaudeProject {
             path,
             name,
             git_branch,
+            cwd,
             first_prompt,
             session_count,
             modified_at,
@@ -176,11 +292,12 @@ fn discover_sessions_in_dir(dir_path: &Path) -> Result<Vec<ClaudeSession>, Revie
         let modified_at = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
 
         // Extract metadata from the JSONL file
-        let (git_branch, first_prompt) = extract_session_metadata(&path);
+        let (git_branch, first_prompt, cwd) = extract_session_metadata(&path);
 
         sessions.push(ClaudeSession {
             path,
             git_branch,
+            cwd,
             first_prompt,
             modified_at,
         });
@@ -193,16 +310,17 @@ fn discover_sessions_in_dir(dir_path: &Path) -> Result<Vec<ClaudeSession>, Revie
 }
 
 /// Extract session metadata from a JSONL file
-/// Returns: (git_branch, first_prompt)
-fn extract_session_metadata(path: &Path) -> (Option<String>, Option<String>) {
+/// Returns: (git_branch, first_prompt, cwd)
+fn extract_session_metadata(path: &Path) -> (Option<String>, Option<String>, Option<PathBuf>) {
     let file = match File::open(path) {
         Ok(f) => f,
-        Err(_) => return (None, None),
+        Err(_) => return (None, None, None),
     };
     let reader = BufReader::new(file);
 
     let mut git_branch: Option<String> = None;
     let mut first_prompt: Option<String> = None;
+    let mut cwd: Option<PathBuf> = None;
 
     // Check first 50 lines for metadata
     for line in reader.lines().take(50) {
@@ -220,6 +338,10 @@ fn extract_session_metadata(path: &Path) -> (Option<String>, Option<String>) {
                 git_branch = record.git_branch;
             }
 
+            if cwd.is_none() {
+                cwd = record.cwd.map(PathBuf::from);
+            }
+
             // Extract first user prompt if not already found
             if first_prompt.is_none()
                 && let Some(ref message) = record.message
@@ -232,14 +354,14 @@ fn extract_session_metadata(path: &Path) -> (Option<String>, Option<String>) {
                 }
             }
 
-            // Stop early if we have both
-            if git_branch.is_some() && first_prompt.is_some() {
+            // Stop early once everything is found
+            if git_branch.is_some() && first_prompt.is_some() && cwd.is_some() {
                 break;
             }
         }
     }
 
-    (git_branch, first_prompt)
+    (git_branch, first_prompt, cwd)
 }
 
 /// Truncate a string to max length, adding "..." if truncated
@@ -253,11 +375,13 @@ fn truncate_string(s: &str, max_len: usize) -> String {
     }
 }
 
-/// Find projects matching a specific git branch using fuzzy matching
-/// Returns matching projects with all their sessions
+/// Find projects matching a specific git branch using fuzzy matching, skipping
+/// projects known to belong to a different repository than `scope`.
+/// Returns matching projects with all their sessions, confirmed repo matches first
 pub fn find_projects_by_branch(
     projects: &[ClaudeProject],
     target_branch: &str,
+    scope: &RepoScope,
 ) -> Result<Vec<(ClaudeProject, Vec<ClaudeSession>)>, ReviewError> {
     let mut matches = Vec::new();
 
@@ -266,15 +390,28 @@ pub fn find_projects_by_branch(
         if let Some(ref project_branch) = project.git_branch
             && branches_match(target_branch, project_branch)
         {
+            let repo_match = project.matches_repo(scope);
+            if repo_match == Some(false) {
+                debug!(
+                    "Skipping project {} on branch {}: not in {}",
+                    project.name,
+                    project_branch,
+                    scope.full_name()
+                );
+                continue;
+            }
             let sessions = discover_sessions(project)?;
-            matches.push((project.clone(), sessions));
+            matches.push((repo_match == Some(true), project.clone(), sessions));
         }
     }
 
-    // Sort by modification time, most recent first
-    matches.sort_by(|a, b| b.0.modified_at.cmp(&a.0.modified_at));
+    // Confirmed repo matches first, then most recently modified
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.modified_at.cmp(&a.1.modified_at)));
 
-    Ok(matches)
+    Ok(matches
+        .into_iter()
+        .map(|(_, project, sessions)| (project, sessions))
+        .collect())
 }
 
 /// Check if two branch names match using fuzzy matching
@@ -314,6 +451,15 @@ struct TimestampedMessage {
     message: serde_json::Value,
 }
 
+/// Messages concatenated from one or more session files
+pub struct ConcatenatedSessions {
+    pub json: String,
+    /// Number of messages included, not counting the truncation marker
+    pub message_count: usize,
+    /// Number of oldest messages dropped to stay within the size limit
+    pub truncated_count: usize,
+}
+
 /// Concatenate multiple JSONL files into a single JSON array of messages.
 ///
 /// Filters to include only:
@@ -321,7 +467,13 @@ struct TimestampedMessage {
 /// - Assistant messages with text content (role = "assistant" with content[].type = "text")
 ///
 /// For assistant messages, only text content blocks are kept (tool_use, etc. are filtered out).
-pub fn concatenate_sessions_to_json(session_paths: &[PathBuf]) -> Result<String, ReviewError> {
+///
+/// If the result would exceed `max_bytes`, the oldest messages are dropped and
+/// replaced with a single truncation marker. The most recent message is always kept.
+pub fn concatenate_sessions_to_json(
+    session_paths: &[PathBuf],
+    max_bytes: Option<usize>,
+) -> Result<ConcatenatedSessions, ReviewError> {
     let mut all_messages: Vec<TimestampedMessage> = Vec::new();
 
     for path in session_paths {
@@ -362,7 +514,68 @@ pub fn concatenate_sessions_to_json(session_paths: &[PathBuf]) -> Result<String,
     // Extract just the messages
     let messages: Vec<serde_json::Value> = all_messages.into_iter().map(|m| m.message).collect();
 
-    serde_json::to_string(&messages).map_err(|e| ReviewError::JsonlParseFailed(e.to_string()))
+    truncate_to_size(messages, max_bytes)
+}
+
+/// Serialize `messages` as a JSON array, dropping the oldest ones (and adding a
+/// marker in their place) until the array fits in `max_bytes`
+fn truncate_to_size(
+    messages: Vec<serde_json::Value>,
+    max_bytes: Option<usize>,
+) -> Result<ConcatenatedSessions, ReviewError> {
+    let encoded = messages
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ReviewError::JsonlParseFailed(e.to_string()))?;
+
+    // Size of `[a,b,...]` holding the given elements
+    let array_len = |lens: &[usize]| 2 + lens.iter().sum::<usize>() + lens.len().saturating_sub(1);
+    let lens: Vec<usize> = encoded.iter().map(String::len).collect();
+
+    let mut start = 0;
+    let mut marker = None;
+    if let Some(max_bytes) = max_bytes
+        && array_len(&lens) > max_bytes
+    {
+        // Keep as many recent messages as fit alongside the marker
+        start = encoded.len().saturating_sub(1);
+        while start > 0 {
+            let marker_len = truncation_marker(start - 1).to_string().len();
+            if array_len(&lens[start - 1..]) + marker_len + 1 > max_bytes {
+                break;
+            }
+            start -= 1;
+        }
+        if start > 0 {
+            marker = Some(truncation_marker(start).to_string());
+        }
+    }
+
+    let json = format!(
+        "[{}]",
+        marker
+            .iter()
+            .chain(&encoded[start..])
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+
+    Ok(ConcatenatedSessions {
+        json,
+        message_count: encoded.len() - start,
+        truncated_count: start,
+    })
+}
+
+fn truncation_marker(dropped: usize) -> serde_json::Value {
+    serde_json::json!({
+        "role": "user",
+        "content": format!(
+            "[{dropped} earlier message(s) truncated to fit the upload size limit]"
+        ),
+    })
 }
 
 /// Extract and filter a message from a JSONL record.
@@ -494,6 +707,141 @@ mod tests {
         assert!(!branches_match("feature-auth", "feature"));
     }
 
+    fn text_message(role: &str, text: &str) -> serde_json::Value {
+        serde_json::json!({ "role": role, "content": text })
+    }
+
+    #[test]
+    fn test_truncate_to_size_keeps_everything_within_limit() {
+        let messages = vec![
+            text_message("user", "hi"),
+            text_message("assistant", "hello"),
+        ];
+        let expected = serde_json::to_string(&messages).unwrap();
+
+        let result = truncate_to_size(messages.clone(), None).unwrap();
+        assert_eq!(result.json, expected);
+        assert_eq!(result.truncated_count, 0);
+
+        let result = truncate_to_size(messages, Some(expected.len())).unwrap();
+        assert_eq!(result.json, expected);
+        assert_eq!(result.message_count, 2);
+    }
+
+    #[test]
+    fn test_truncate_to_size_drops_oldest_messages() {
+        let messages: Vec<_> = (0..20)
+            .map(|i| text_message("user", &format!("message number {i:02}")))
+            .collect();
+        let full_len = serde_json::to_string(&messages).unwrap().len();
+
+        let result = truncate_to_size(messages, Some(full_len / 2)).unwrap();
+        assert!(result.json.len() <= full_len / 2);
+        assert!(result.truncated_count > 0);
+        assert_eq!(result.message_count + result.truncated_count, 20);
+
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&result.json).unwrap();
+        assert_eq!(parsed.len(), result.message_count + 1);
+        assert!(
+            parsed[0]["content"]
+                .as_str()
+                .unwrap()
+                .starts_with(&format!("[{} earlier", result.truncated_count))
+        );
+        assert_eq!(parsed.last().unwrap()["content"], "message number 19");
+    }
+
+    #[test]
+    fn test_truncate_to_size_always_keeps_latest_message() {
+        let messages = vec![text_message("user", "old"), text_message("user", "latest")];
+        let result = truncate_to_size(messages, Some(1)).unwrap();
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&result.json).unwrap();
+        assert_eq!(result.message_count, 1);
+        assert_eq!(result.truncated_count, 1);
+        assert_eq!(parsed.last().unwrap()["content"], "latest");
+    }
+
+    #[test]
+    fn test_parse_github_remote() {
+        assert_eq!(
+            parse_github_remote("https://github.com/owner/repo.git").as_deref(),
+            Some("owner/repo")
+        );
+        assert_eq!(
+            parse_github_remote("git@github.com:owner/repo.git").as_deref(),
+            Some("owner/repo")
+        );
+        assert_eq!(
+            parse_github_remote("https://github.com/owner/repo").as_deref(),
+            Some("owner/repo")
+        );
+        assert_eq!(
+            parse_github_remote("https://gitlab.com/owner/repo.git"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_repo_scope_matches_by_path() {
+        let scope = RepoScope {
+            owner: "owner".to_string(),
+            repo: "vibe-kanban".to_string(),
+        };
+        // Removed worktree whose path still names the repo
+        assert_eq!(
+            scope.matches(Path::new("/nonexistent/worktrees/a04a-fix/vibe-kanban")),
+            Some(true)
+        );
+        // Removed directory for some other repo: can't tell
+        assert_eq!(scope.matches(Path::new("/nonexistent/other-repo")), None);
+    }
+
+    #[test]
+    fn test_repo_scope_matches_fork_checkouts() {
+        let scope = RepoScope {
+            owner: "owner".to_string(),
+            repo: "vibe-kanban".to_string(),
+        };
+        assert!(scope.matches_remotes(["Owner/Vibe-Kanban"]));
+        // Fork PR: the author's clone only has their fork as origin
+        assert!(scope.matches_remotes(["contributor/vibe-kanban"]));
+        // Fork with the upstream added under another remote name
+        assert!(scope.matches_remotes(["contributor/vk-fork", "owner/vibe-kanban"]));
+        assert!(!scope.matches_remotes(["owner/other-repo"]));
+    }
+
+    #[test]
+    fn test_repo_scope_matches_checkout_remotes() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(args)
+                .current_dir(dir.path())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        git(&[
+            "remote",
+            "add",
+            "origin",
+            "git@github.com:contributor/vk-fork.git",
+        ]);
+        git(&[
+            "remote",
+            "add",
+            "upstream",
+            "https://github.com/owner/vibe-kanban.git",
+        ]);
+
+        let scope = RepoScope {
+            owner: "owner".to_string(),
+            repo: "vibe-kanban".to_string(),
+        };
+        assert_eq!(scope.matches(dir.path()), Some(true));
+    }
+
     #[test]
     fn test_normalize_branch() {
         assert_eq!(normalize_branch("refs/heads/main"), "main");
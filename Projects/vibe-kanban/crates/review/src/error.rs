@@ -40,4 +40,7 @@ pub enum ReviewError {
 
     #[error("Failed to parse JSONL file: {0}")]
     JsonlParseFailed(String),
+
+    #[error("Missing required input in non-interactive mode: {0}")]
+    MissingInput(String),
 }
@@ -1,10 +1,74 @@
-use std::{path::Path, process::Command};
+use std::{fmt, path::Path, process::Command};
 
 use serde::Deserialize;
 use tracing::debug;
 
 use crate::error::ReviewError;
 
+/// Env var the inline credential helper reads the token from, so it never
+/// appears in a command line.
+const TOKEN_ENV: &str = "REVIEW_GIT_TOKEN";
+
+/// Answers git's credential requests with the token. Clearing the helper list
+/// first keeps stored credentials from taking precedence.
+const CREDENTIAL_HELPER: &str = r#"!f() { test "$1" = get && echo username=x-access-token && echo "password=$REVIEW_GIT_TOKEN"; }; f"#;
+
+/// A GitHub token that stays out of `Debug` output and logs.
+#[derive(Clone)]
+pub struct GitHubToken(String);
+
+impl From<String> for GitHubToken {
+    fn from(token: String) -> Self {
+        Self(token)
+    }
+}
+
+impl fmt::Debug for GitHubToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("GitHubToken(***)")
+    }
+}
+
+/// Credentials and commit identity for every `gh`/`git` command the CLI runs.
+#[derive(Debug, Clone)]
+pub struct GitContext {
+    /// When unset, `gh`'s own login is used
+    pub token: Option<GitHubToken>,
+    pub user_name: String,
+    pub user_email: String,
+}
+
+impl GitContext {
+    fn gh(&self) -> Command {
+        let mut cmd = Command::new("gh");
+        if let Some(GitHubToken(token)) = &self.token {
+            cmd.env("GH_TOKEN", token);
+        }
+        cmd
+    }
+
+    /// `git` with the configured identity, so commits work without a global
+    /// git config, and with the token wired in as a credential helper.
+    fn git(&self, repo_dir: Option<&Path>) -> Command {
+        let mut cmd = Command::new("git");
+        if let Some(dir) = repo_dir {
+            cmd.current_dir(dir);
+        }
+        cmd.arg("-c")
+            .arg(format!("user.name={}", self.user_name))
+            .arg("-c")
+            .arg(format!("user.email={}", self.user_email))
+            .env("GIT_TERMINAL_PROMPT", "0");
+        if let Some(GitHubToken(token)) = &self.token {
+            cmd.args(["-c", "credential.helper="])
+                .arg("-c")
+                .arg(format!("credential.helper={CREDENTIAL_HELPER}"))
+                .env(TOKEN_ENV, token);
+        }
+        cmd
+    }
+}
+
 /// Information about a pull request
 #[derive(Debug)]
 pub struct PrInfo {
@@ -103,10 +167,16 @@ fn ensure_gh_available() -> Result<(), ReviewError> {
 /// Get PR information using `gh api` (REST API)
 /// This is used as a fallback for older gh CLI versions that don't support
 /// the baseRefOid/headRefOid fields in `gh pr view --json`
-fn get_pr_info_via_api(owner: &str, repo: &str, pr_number: i64) -> Result<PrInfo, ReviewError> {
+fn get_pr_info_via_api(
+    ctx: &GitContext,
+    owner: &str,
+    repo: &str,
+    pr_number: i64,
+) -> Result<PrInfo, ReviewError> {
     debug!("Fetching PR info via gh api for {owner}/{repo}#{pr_number}");
 
-    let output = Command::new("gh")
+    let output = ctx
+        .gh()
         .args(["api", &format!("repos/{owner}/{repo}/pulls/{pr_number}")])
         .output()
         .map_err(|e| ReviewError::PrInfoFailed(e.to_string()))?;
@@ -141,12 +211,18 @@ fn get_pr_info_via_api(owner: &str, repo: &str, pr_number: i64) -> Result<PrInfo
 }
 
 /// Get PR information using `gh pr view`
-pub fn get_pr_info(owner: &str, repo: &str, pr_number: i64) -> Result<PrInfo, ReviewError> {
+pub fn get_pr_info(
+    ctx: &GitContext,
+    owner: &str,
+    repo: &str,
+    pr_number: i64,
+) -> Result<PrInfo, ReviewError> {
     ensure_gh_available()?;
 
     debug!("Fetching PR info for {owner}/{repo}#{pr_number}");
 
-    let output = Command::new("gh")
+    let output = ctx
+        .gh()
         .args([
             "pr",
             "view",
@@ -166,7 +242,7 @@ pub fn get_pr_info(owner: &str, repo: &str, pr_number: i64) -> Result<PrInfo, Re
         // Check for old gh CLI version that doesn't support these JSON fields
         if lower.contains("unknown json field") {
             debug!("gh pr view --json failed with unknown field, falling back to gh api");
-            return get_pr_info_via_api(owner, repo, pr_number);
+            return get_pr_info_via_api(ctx, owner, repo, pr_number);
         }
 
         if lower.contains("authentication")
@@ -194,43 +270,121 @@ pub fn get_pr_info(owner: &str, repo: &str, pr_number: i64) -> Result<PrInfo, Re
     })
 }
 
-/// Clone a repository using `gh repo clone`
-pub fn clone_repo(owner: &str, repo: &str, target_dir: &Path) -> Result<(), ReviewError> {
-    ensure_gh_available()?;
-
+/// Clone a repository. With a token this clones over HTTPS with plain `git`;
+/// otherwise `gh repo clone` uses the CLI's own login. `depth` makes a shallow
+/// clone with only that many commits of history.
+pub fn clone_repo(
+    ctx: &GitContext,
+    owner: &str,
+    repo: &str,
+    target_dir: &Path,
+    depth: Option<u32>,
+    blobless: bool,
+) -> Result<(), ReviewError> {
     debug!("Cloning {owner}/{repo} to {}", target_dir.display());
 
-    let output = Command::new("gh")
-        .args([
-            "repo",
-            "clone",
-            &format!("{owner}/{repo}"),
-            target_dir
-                .to_str()
-                .ok_or_else(|| ReviewError::CloneFailed("Invalid target path".to_string()))?,
-        ])
-        .output()
-        .map_err(|e| ReviewError::CloneFailed(e.to_string()))?;
+    let target = target_dir
+        .to_str()
+        .ok_or_else(|| ReviewError::CloneFailed("Invalid target path".to_string()))?;
+    let mut clone_args = depth
+        .map(|depth| vec!["--depth".to_string(), depth.to_string()])
+        .unwrap_or_default();
+    if blobless {
+        // File contents are fetched on demand, see `fetch_diff_blobs`
+        clone_args.extend([
+            "--filter=blob:none".to_string(),
+            "--no-checkout".to_string(),
+        ]);
+    }
+
+    let output = if ctx.token.is_some() {
+        ctx.git(None)
+            .args([
+                "clone",
+                &format!("https://github.com/{owner}/{repo}.git"),
+                target,
+            ])
+            .args(&clone_args)
+            .output()
+    } else {
+        ensure_gh_available()?;
+        let mut cmd = ctx.gh();
+        cmd.args(["repo", "clone", &format!("{owner}/{repo}"), target]);
+        // Flags after `--` are passed through to `git clone`
+        if !clone_args.is_empty() {
+            cmd.arg("--").args(&clone_args);
+        }
+        cmd.output()
+    }
+    .map_err(|e| ReviewError::CloneFailed(e.to_string()))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
+        if is_git_auth_error(&stderr) {
+            return Err(ReviewError::CloneFailed(format!(
+                "{stderr}\nFor private repositories, pass --token or set GITHUB_TOKEN"
+            )));
+        }
         return Err(ReviewError::CloneFailed(stderr.to_string()));
     }
 
     Ok(())
 }
 
+fn is_git_auth_error(stderr: &str) -> bool {
+    let lower = stderr.to_ascii_lowercase();
+    lower.contains("authentication failed")
+        || lower.contains("could not read username")
+        || lower.contains("repository not found")
+}
+
 /// Checkout a specific commit by SHA
 ///
 /// This is more reliable than `gh pr checkout` because it works even when
 /// the PR's branch has been deleted (common for merged PRs).
-pub fn checkout_commit(commit_sha: &str, repo_dir: &Path) -> Result<(), ReviewError> {
+pub fn checkout_commit(
+    ctx: &GitContext,
+    commit_sha: &str,
+    repo_dir: &Path,
+) -> Result<(), ReviewError> {
+    // First, fetch the specific commit
+    fetch_commit(ctx, commit_sha, repo_dir)?;
+
+    debug!("Checking out commit {commit_sha}");
+
+    // Then checkout the commit
+    let output = ctx
+        .git(Some(repo_dir))
+        .args(["checkout", commit_sha])
+        .output()
+        .map_err(|e| ReviewError::CheckoutFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ReviewError::CheckoutFailed(format!(
+            "Failed to checkout commit: {stderr}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Fetch a specific commit from `origin`, e.g. a PR's base which may no longer
+/// be on any branch
+pub fn fetch_commit(
+    ctx: &GitContext,
+    commit_sha: &str,
+    repo_dir: &Path,
+) -> Result<(), ReviewError> {
     debug!("Fetching commit {commit_sha} in {}", repo_dir.display());
 
-    // First, fetch the specific commit
-    let output = Command::new("git")
-        .args(["fetch", "origin", commit_sha])
-        .current_dir(repo_dir)
+    let mut cmd = ctx.git(Some(repo_dir));
+    cmd.args(["fetch", "origin", commit_sha]);
+    // Keep a shallow clone shallow instead of pulling the commit's whole history
+    if repo_dir.join(".git").join("shallow").is_file() {
+        cmd.arg("--depth=1");
+    }
+    let output = cmd
         .output()
         .map_err(|e| ReviewError::CheckoutFailed(e.to_string()))?;
 
@@ -241,25 +395,106 @@ pub fn checkout_commit(commit_sha: &str, repo_dir: &Path) -> Result<(), ReviewEr
         )));
     }
 
-    debug!("Checking out commit {commit_sha}");
+    Ok(())
+}
 
-    // Then checkout the commit
-    let output = Command::new("git")
-        .args(["checkout", commit_sha])
-        .current_dir(repo_dir)
+/// How many more commits to fetch, in turn, while a shallow clone lacks the
+/// merge base of a PR's base and head. The whole history is fetched after that.
+const DEEPEN_STEPS: [u32; 3] = [100, 1_000, 10_000];
+
+/// Deepen a shallow clone until `base_commit` and `head_commit` share history,
+/// so the PR's changes can be diffed against their merge base.
+pub fn deepen_to_merge_base(
+    ctx: &GitContext,
+    base_commit: &str,
+    head_commit: &str,
+    repo_dir: &Path,
+) -> Result<(), ReviewError> {
+    for step in DEEPEN_STEPS {
+        if has_merge_base(ctx, base_commit, head_commit, repo_dir)? {
+            return Ok(());
+        }
+        debug!("Deepening {} by {step} commits", repo_dir.display());
+        run_fetch(
+            ctx,
+            repo_dir,
+            &[
+                "fetch",
+                &format!("--deepen={step}"),
+                "origin",
+                base_commit,
+                head_commit,
+            ],
+        )?;
+    }
+    if !has_merge_base(ctx, base_commit, head_commit, repo_dir)? {
+        debug!("Fetching the full history of {}", repo_dir.display());
+        run_fetch(ctx, repo_dir, &["fetch", "--unshallow", "origin"])?;
+    }
+    Ok(())
+}
+
+/// Fetch the file contents the PR's diff needs into a blobless clone. Git
+/// fetches the missing blobs in one batch while diffing; libgit2 can't fetch
+/// them at all, so this has to run before the diff is read with it.
+pub fn fetch_diff_blobs(
+    ctx: &GitContext,
+    base_commit: &str,
+    head_commit: &str,
+    repo_dir: &Path,
+) -> Result<(), ReviewError> {
+    debug!("Fetching changed file contents in {}", repo_dir.display());
+
+    let output = ctx
+        .git(Some(repo_dir))
+        .args([
+            "diff",
+            "--find-renames",
+            &format!("{base_commit}...{head_commit}"),
+        ])
+        .stdout(std::process::Stdio::null())
         .output()
         .map_err(|e| ReviewError::CheckoutFailed(e.to_string()))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(ReviewError::CheckoutFailed(format!(
-            "Failed to checkout commit: {stderr}"
+            "Failed to fetch changed files: {stderr}"
         )));
     }
 
     Ok(())
 }
 
+fn has_merge_base(
+    ctx: &GitContext,
+    base_commit: &str,
+    head_commit: &str,
+    repo_dir: &Path,
+) -> Result<bool, ReviewError> {
+    let output = ctx
+        .git(Some(repo_dir))
+        .args(["merge-base", base_commit, head_commit])
+        .output()
+        .map_err(|e| ReviewError::CheckoutFailed(e.to_string()))?;
+    Ok(output.status.success())
+}
+
+fn run_fetch(ctx: &GitContext, repo_dir: &Path, args: &[&str]) -> Result<(), ReviewError> {
+    let output = ctx
+        .git(Some(repo_dir))
+        .args(args)
+        .output()
+        .map_err(|e| ReviewError::CheckoutFailed(e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ReviewError::CheckoutFailed(format!(
+            "Failed to fetch history: {stderr}"
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,6 +517,146 @@ mod tests {
         assert_eq!(pr, 456);
     }
 
+    fn context(token: Option<&str>) -> GitContext {
+        GitContext {
+            token: token.map(|t| GitHubToken::from(t.to_string())),
+            user_name: "Review Bot".to_string(),
+            user_email: "review@example.com".to_string(),
+        }
+    }
+
+    fn args(cmd: &Command) -> Vec<String> {
+        cmd.get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_git_sets_identity() {
+        let cmd = context(None).git(None);
+        let args = args(&cmd);
+        assert!(args.contains(&"user.name=Review Bot".to_string()));
+        assert!(args.contains(&"user.email=review@example.com".to_string()));
+        assert!(!args.iter().any(|a| a.starts_with("credential.helper")));
+    }
+
+    #[test]
+    fn test_git_passes_token_through_env_only() {
+        let cmd = context(Some("ghp_secret")).git(None);
+        assert!(!args(&cmd).iter().any(|a| a.contains("ghp_secret")));
+        assert!(
+            cmd.get_envs()
+                .any(|(k, v)| k == TOKEN_ENV && v == Some("ghp_secret".as_ref()))
+        );
+    }
+
+    #[test]
+    fn test_token_is_not_debug_printed() {
+        let ctx = context(Some("ghp_secret"));
+        assert!(!format!("{ctx:?}").contains("ghp_secret"));
+    }
+
+    #[test]
+    fn test_deepen_to_merge_base_fetches_shared_history() {
+        let td = tempfile::TempDir::new().unwrap();
+        let ctx = context(None);
+        let run = |dir: &Path, args: &[&str]| -> String {
+            let out = ctx.git(Some(dir)).args(args).output().unwrap();
+            assert!(out.status.success(), "git {args:?} failed");
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        let commit = |dir: &Path, name: &str| {
+            std::fs::write(dir.join(name), name).unwrap();
+            run(dir, &["add", name]);
+            run(dir, &["commit", "-q", "-m", name]);
+            run(dir, &["rev-parse", "HEAD"])
+        };
+
+        let upstream = td.path().join("upstream");
+        std::fs::create_dir(&upstream).unwrap();
+        run(&upstream, &["init", "-q", "-b", "main"]);
+        let merge_base = commit(&upstream, "shared");
+        run(&upstream, &["checkout", "-q", "-b", "feature"]);
+        commit(&upstream, "feature-1");
+        let head = commit(&upstream, "feature-2");
+        run(&upstream, &["checkout", "-q", "main"]);
+        commit(&upstream, "main-1");
+        let base = commit(&upstream, "main-2");
+
+        let clone = td.path().join("clone");
+        let url = format!("file://{}", upstream.display());
+        run(
+            td.path(),
+            &["clone", "-q", "--depth", "1", &url, clone.to_str().unwrap()],
+        );
+        fetch_commit(&ctx, &head, &clone).unwrap();
+        fetch_commit(&ctx, &base, &clone).unwrap();
+        assert!(!has_merge_base(&ctx, &base, &head, &clone).unwrap());
+
+        deepen_to_merge_base(&ctx, &base, &head, &clone).unwrap();
+        assert_eq!(run(&clone, &["merge-base", &base, &head]), merge_base);
+    }
+
+    #[test]
+    fn test_fetch_diff_blobs_fills_in_a_blobless_clone() {
+        let td = tempfile::TempDir::new().unwrap();
+        let ctx = context(None);
+        let run = |dir: &Path, args: &[&str]| -> String {
+            let out = ctx.git(Some(dir)).args(args).output().unwrap();
+            assert!(out.status.success(), "git {args:?} failed");
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        let commit = |dir: &Path, name: &str, content: &str| {
+            std::fs::write(dir.join(name), content).unwrap();
+            run(dir, &["add", name]);
+            run(dir, &["commit", "-q", "-m", name]);
+            run(dir, &["rev-parse", "HEAD"])
+        };
+
+        let upstream = td.path().join("upstream");
+        std::fs::create_dir(&upstream).unwrap();
+        run(&upstream, &["init", "-q", "-b", "main"]);
+        run(&upstream, &["config", "uploadpack.allowFilter", "true"]);
+        commit(&upstream, "untouched.txt", "untouched");
+        commit(&upstream, "changed.txt", "before");
+        run(&upstream, &["checkout", "-q", "-b", "feature"]);
+        let head = commit(&upstream, "changed.txt", "after");
+        run(&upstream, &["checkout", "-q", "main"]);
+        let base = commit(&upstream, "other.txt", "other");
+
+        let clone = td.path().join("clone");
+        let url = format!("file://{}", upstream.display());
+        run(
+            td.path(),
+            &[
+                "clone",
+                "-q",
+                "--depth",
+                "1",
+                "--filter=blob:none",
+                "--no-checkout",
+                &url,
+                clone.to_str().unwrap(),
+            ],
+        );
+        assert!(!clone.join("untouched.txt").exists());
+
+        fetch_commit(&ctx, &head, &clone).unwrap();
+        fetch_commit(&ctx, &base, &clone).unwrap();
+        deepen_to_merge_base(&ctx, &base, &head, &clone).unwrap();
+        fetch_diff_blobs(&ctx, &base, &head, &clone).unwrap();
+
+        let out_dir = td.path().join("out");
+        let written = crate::archive::write_diff_only_tree(&clone, &base, &head, &out_dir).unwrap();
+        assert_eq!(written, 1);
+        assert_eq!(
+            std::fs::read_to_string(out_dir.join("changed.txt")).unwrap(),
+            "after"
+        );
+        let patch = std::fs::read_to_string(out_dir.join(crate::archive::DIFF_FILE_NAME)).unwrap();
+        assert!(patch.contains("-before"));
+    }
+
     #[test]
     fn test_parse_pr_url_invalid_format() {
         assert!(parse_pr_url("https://github.com/owner/repo").is_err());
@@ -6,13 +6,17 @@ mod error;
 mod github;
 mod session_selector;
 
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
 
 use anyhow::Result;
-use api::{ReviewApiClient, ReviewStatus, StartRequest};
+use api::{ArchiveMode, RepoReviewContext, ReviewApiClient, ReviewStatus, StartRequest};
 use clap::Parser;
+use claude_session::RepoScope;
 use error::ReviewError;
-use github::{checkout_commit, clone_repo, get_pr_info, parse_pr_url};
+use github::{
+    GitContext, GitHubToken, PrInfo, checkout_commit, clone_repo, deepen_to_merge_base,
+    fetch_commit, fetch_diff_blobs, get_pr_info, parse_pr_url,
+};
 use indicatif::{ProgressBar, ProgressStyle};
 use tempfile::TempDir;
 use tracing::debug;
@@ -21,6 +25,7 @@ use tracing_subscriber::EnvFilter;
 const DEFAULT_API_URL: &str = "https://api.vibekanban.com";
 const POLL_INTERVAL: Duration = Duration::from_secs(10);
 const TIMEOUT: Duration = Duration::from_secs(600); // 10 minutes
+const DEFAULT_MAX_SESSION_BYTES: usize = 5 * 1_048_576; // 5 MB
 
 const BANNER: &str = r#"
 ██████╗ ███████╗██╗   ██╗██╗███████╗██╗    ██╗   ███████╗ █████╗ ███████╗████████╗
@@ -42,6 +47,11 @@ struct Args {
     /// GitHub PR URL (e.g., https://github.com/owner/repo/pull/123)
     pr_url: String,
 
+    /// PR in another repository that is part of the same change; repeat for
+    /// each additional repository
+    #[arg(long = "with-pr", value_name = "PR_URL")]
+    additional_pr_urls: Vec<String>,
+
     /// Enable verbose output
     #[arg(short, long, default_value_t = false)]
     verbose: bool,
@@ -49,9 +59,53 @@ struct Args {
     /// API base URL
     #[arg(long, env = "REVIEW_API_URL", default_value = DEFAULT_API_URL)]
     api_url: String,
+
+    /// GitHub token for private repositories (defaults to the `gh` login)
+    #[arg(long, env = "GITHUB_TOKEN", hide_env_values = true)]
+    token: Option<GitHubToken>,
+
+    /// Name used for git operations in the temporary clone
+    #[arg(long, env = "REVIEW_GIT_USER_NAME", default_value = "Vibe Kanban")]
+    git_user_name: String,
+
+    /// Email used for git operations in the temporary clone
+    #[arg(
+        long,
+        env = "REVIEW_GIT_USER_EMAIL",
+        default_value = "noreply@vibekanban.com"
+    )]
+    git_user_email: String,
+
+    /// Maximum size in bytes of the attached session messages; the oldest
+    /// messages are truncated beyond this
+    #[arg(long, env = "REVIEW_MAX_SESSION_BYTES", default_value_t = DEFAULT_MAX_SESSION_BYTES)]
+    max_session_bytes: usize,
+
+    /// Run without prompts (e.g. in CI); requires --email and --accept-terms
+    /// and skips attaching Claude Code sessions
+    #[arg(long, default_value_t = false)]
+    non_interactive: bool,
+
+    /// Email address to send the review link to
+    #[arg(long, env = "REVIEW_EMAIL")]
+    email: Option<String>,
+
+    /// Upload only the PR's changed files and diff instead of the full
+    /// checkout; faster for small PRs in large repositories
+    #[arg(long, default_value_t = false)]
+    diff_only: bool,
+
+    /// Clone only the history back to where the PR branched off instead of the
+    /// full history; faster for repositories with a long history
+    #[arg(long, default_value_t = false, conflicts_with = "diff_only")]
+    shallow: bool,
+
+    /// Accept the terms and conditions without prompting
+    #[arg(long, default_value_t = false)]
+    accept_terms: bool,
 }
 
-fn show_disclaimer() {
+fn show_disclaimer(wait_for_enter: bool) {
     println!();
     println!(
         "DISCLAIMER: Your code will be processed on our secure remote servers, all artefacts (code, AI logs, etc...) will be deleted after 14 days."
@@ -59,12 +113,39 @@ fn show_disclaimer() {
     println!();
     println!("Full terms and conditions and privacy policy: https://review.fast/terms");
     println!();
+
+    if !wait_for_enter {
+        println!("Terms accepted via --accept-terms");
+        return;
+    }
+
     println!("Press Enter to accept and continue...");
 
     let mut input = String::new();
     std::io::stdin().read_line(&mut input).ok();
 }
 
+/// The email passed with --email or REVIEW_EMAIL, if it isn't blank
+fn email_from_args(args: &Args) -> Option<String> {
+    args.email
+        .as_deref()
+        .map(str::trim)
+        .filter(|email| !email.is_empty())
+        .map(str::to_string)
+}
+
+/// Resolve the disclaimer and email without touching stdin
+fn non_interactive_email(args: &Args) -> Result<String, ReviewError> {
+    if !args.accept_terms {
+        return Err(ReviewError::MissingInput(
+            "pass --accept-terms to accept the terms and conditions".to_string(),
+        ));
+    }
+
+    email_from_args(args)
+        .ok_or_else(|| ReviewError::MissingInput("pass --email or set REVIEW_EMAIL".to_string()))
+}
+
 fn prompt_email(config: &mut config::Config) -> String {
     use dialoguer::Input;
 
@@ -86,6 +167,30 @@ fn prompt_email(config: &mut config::Config) -> String {
     email
 }
 
+/// Directory name for each repository inside the archive, disambiguated by
+/// owner when two repositories share a name and by a numeric suffix when that
+/// still collides (e.g. two PRs from the same repository)
+fn repo_dir_names(prs: &[&PrInfo]) -> Vec<String> {
+    let mut taken = HashSet::new();
+    prs.iter()
+        .map(|pr| {
+            let shared = prs.iter().filter(|other| other.repo == pr.repo).count() > 1;
+            let base = if shared {
+                format!("{}-{}", pr.owner, pr.repo)
+            } else {
+                pr.repo.clone()
+            };
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while !taken.insert(name.clone()) {
+                name = format!("{base}-{suffix}");
+                suffix += 1;
+            }
+            name
+        })
+        .collect()
+}
+
 fn create_spinner(message: &str) -> ProgressBar {
     let spinner = ProgressBar::new_spinner();
     spinner.set_style(
@@ -117,8 +222,6 @@ async fn main() -> Result<()> {
 
     println!("{}", BANNER);
 
-    show_disclaimer();
-
     debug!("Args: {:?}", args);
 
     // Run the main flow and handle errors
@@ -131,25 +234,62 @@ async fn main() -> Result<()> {
 }
 
 async fn run(args: Args) -> Result<(), ReviewError> {
-    // 1. Load config and prompt for email
-    let mut config = config::Config::load();
-    let email = prompt_email(&mut config);
+    // 1. Accept the disclaimer, then load config and prompt for email
+    let email = if args.non_interactive {
+        let email = non_interactive_email(&args)?;
+        show_disclaimer(false);
+        email
+    } else {
+        show_disclaimer(!args.accept_terms);
+        // Only prompt for what wasn't passed on the command line
+        match email_from_args(&args) {
+            Some(email) => email,
+            None => prompt_email(&mut config::Config::load()),
+        }
+    };
+
+    let git_ctx = GitContext {
+        token: args.token.clone(),
+        user_name: args.git_user_name.clone(),
+        user_email: args.git_user_email.clone(),
+    };
 
     // 2. Parse PR URL
     let spinner = create_spinner("Parsing PR URL...");
     let (owner, repo, pr_number) = parse_pr_url(&args.pr_url)?;
     spinner.finish_with_message(format!("PR: {owner}/{repo}#{pr_number}"));
 
-    // 3. Get PR info
+    // 3. Get PR info, for the primary PR and any in other repositories
     let spinner = create_spinner("Fetching PR information...");
-    let pr_info = get_pr_info(&owner, &repo, pr_number)?;
+    let pr_info = get_pr_info(&git_ctx, &owner, &repo, pr_number)?;
+    let mut additional_prs = Vec::new();
+    for url in &args.additional_pr_urls {
+        let (owner, repo, number) = parse_pr_url(url)?;
+        additional_prs.push(get_pr_info(&git_ctx, &owner, &repo, number)?);
+    }
     spinner.finish_with_message(format!("PR: {}", pr_info.title));
 
     // 4. Select Claude Code session (optional)
-    let session_files = match session_selector::select_session(&pr_info.head_ref_name) {
-        Ok(session_selector::SessionSelection::Selected(files)) => {
-            println!("  Selected {} session file(s)", files.len());
-            Some(files)
+    let scope = RepoScope {
+        owner: owner.clone(),
+        repo: repo.clone(),
+    };
+    let selection = if args.non_interactive {
+        Ok(session_selector::SessionSelection::Skipped)
+    } else {
+        session_selector::select_session(&pr_info.head_ref_name, &scope)
+    };
+    let session_files = match selection {
+        Ok(session_selector::SessionSelection::Selected(sessions)) => {
+            match sessions.first().and_then(|s| s.repo.as_deref()) {
+                Some(repo) => println!(
+                    "  Selected {} session file(s) from {}",
+                    sessions.len(),
+                    repo
+                ),
+                None => println!("  Selected {} session file(s)", sessions.len()),
+            }
+            Some(sessions.into_iter().map(|s| s.path).collect::<Vec<_>>())
         }
         Ok(session_selector::SessionSelection::Skipped) => {
             println!("  Skipping project attachment");
@@ -162,31 +302,107 @@ async fn run(args: Args) -> Result<(), ReviewError> {
         }
     };
 
-    // 5. Clone repository to temp directory
+    // 5. Clone repositories to temp directory. A single repository is the
+    // archive root; multiple repositories each get a subdirectory.
     let temp_dir = TempDir::new().map_err(|e| ReviewError::CloneFailed(e.to_string()))?;
-    let repo_dir = temp_dir.path().join(&repo);
+    let all_prs: Vec<&PrInfo> = std::iter::once(&pr_info).chain(&additional_prs).collect();
+    let checkout_root = if all_prs.len() > 1 {
+        temp_dir.path().join("repos")
+    } else {
+        temp_dir.path().join(&repo)
+    };
+    // In diff-only mode the archive holds just the changes, staged separately
+    let archive_root = if args.diff_only {
+        temp_dir.path().join("diff")
+    } else {
+        checkout_root.clone()
+    };
+    let repo_paths: Vec<String> = if all_prs.len() > 1 {
+        repo_dir_names(&all_prs)
+    } else {
+        vec![String::new()]
+    };
 
-    let spinner = create_spinner("Cloning repository...");
-    clone_repo(&owner, &repo, &repo_dir)?;
-    spinner.finish_with_message("Repository cloned");
+    for (pr, path) in all_prs.iter().zip(&repo_paths) {
+        let repo_dir = checkout_root.join(path);
+
+        let spinner = create_spinner(&format!("Cloning {}/{}...", pr.owner, pr.repo));
+        // Diff-only uploads never need the working tree or unchanged files
+        let depth = (args.shallow || args.diff_only).then_some(1);
+        clone_repo(
+            &git_ctx,
+            &pr.owner,
+            &pr.repo,
+            &repo_dir,
+            depth,
+            args.diff_only,
+        )?;
+        spinner.finish_with_message(format!("Repository {}/{} cloned", pr.owner, pr.repo));
+
+        if args.diff_only {
+            let spinner = create_spinner("Collecting changed files...");
+            fetch_commit(&git_ctx, &pr.head_commit, &repo_dir)?;
+            fetch_commit(&git_ctx, &pr.base_commit, &repo_dir)?;
+            deepen_to_merge_base(&git_ctx, &pr.base_commit, &pr.head_commit, &repo_dir)?;
+            fetch_diff_blobs(&git_ctx, &pr.base_commit, &pr.head_commit, &repo_dir)?;
+            let changed = archive::write_diff_only_tree(
+                &repo_dir,
+                &pr.base_commit,
+                &pr.head_commit,
+                &archive_root.join(path),
+            )?;
+            spinner.finish_with_message(format!("{changed} changed file(s) collected"));
+            continue;
+        }
+
+        // 6. Checkout PR head commit
+        let spinner = create_spinner("Checking out PR...");
+        checkout_commit(&git_ctx, &pr.head_commit, &repo_dir)?;
+        if args.shallow {
+            // The PR's changes are reviewed against the merge base of its base and
+            // head, which a depth-1 clone of each doesn't have
+            fetch_commit(&git_ctx, &pr.base_commit, &repo_dir)?;
+            deepen_to_merge_base(&git_ctx, &pr.base_commit, &pr.head_commit, &repo_dir)?;
+        }
+        spinner.finish_with_message("PR checked out");
+    }
 
-    // 6. Checkout PR head commit
-    let spinner = create_spinner("Checking out PR...");
-    checkout_commit(&pr_info.head_commit, &repo_dir)?;
-    spinner.finish_with_message("PR checked out");
+    let repos: Vec<RepoReviewContext> = all_prs
+        .iter()
+        .zip(repo_paths)
+        .map(|(pr, path)| RepoReviewContext {
+            org: pr.owner.clone(),
+            repo_name: pr.repo.clone(),
+            base_commit: pr.base_commit.clone(),
+            path,
+        })
+        .collect();
 
     // 7. Create tarball (with optional session data)
     let spinner = create_spinner("Creating archive...");
 
-    // If sessions were selected, write .agent-messages.json to repo root
+    // If sessions were selected, write .agent-messages.json to archive root
     if let Some(ref files) = session_files {
-        let json_content = claude_session::concatenate_sessions_to_json(files)?;
-        let agent_messages_path = repo_dir.join(".agent-messages.json");
-        std::fs::write(&agent_messages_path, json_content)
+        let sessions =
+            claude_session::concatenate_sessions_to_json(files, Some(args.max_session_bytes))?;
+        let size_kb = sessions.json.len() as f64 / 1024.0;
+        if sessions.truncated_count > 0 {
+            spinner.println(format!(
+                "  Session messages: {} included ({size_kb:.1} KB), {} oldest truncated",
+                sessions.message_count, sessions.truncated_count
+            ));
+        } else {
+            spinner.println(format!(
+                "  Session messages: {} included ({size_kb:.1} KB)",
+                sessions.message_count
+            ));
+        }
+        let agent_messages_path = archive_root.join(".agent-messages.json");
+        std::fs::write(&agent_messages_path, sessions.json)
             .map_err(|e| ReviewError::ArchiveFailed(e.to_string()))?;
     }
 
-    let payload = archive::create_tarball(&repo_dir)?;
+    let payload = archive::create_tarball(&archive_root)?;
     let size_mb = payload.len() as f64 / 1_048_576.0;
     spinner.finish_with_message(format!("Archive created ({size_mb:.2} MB)"));
 
@@ -213,6 +429,12 @@ async fn run(args: Args) -> Result<(), ReviewError> {
             repo: pr_info.repo,
             codebase_url,
             base_commit: pr_info.base_commit,
+            repos,
+            archive_mode: if args.diff_only {
+                ArchiveMode::DiffOnly
+            } else {
+                ArchiveMode::Full
+            },
         })
         .await?;
     spinner.finish_with_message(format!("Review started, we'll send you an email at {} when the review is ready. This can take a few minutes, you may now close the terminal", email));
@@ -258,3 +480,92 @@ async fn run(args: Args) -> Result<(), ReviewError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pr(owner: &str, repo: &str) -> PrInfo {
+        PrInfo {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            title: String::new(),
+            description: String::new(),
+            base_commit: String::new(),
+            head_commit: String::new(),
+            head_ref_name: String::new(),
+        }
+    }
+
+    fn parse_args(extra: &[&str]) -> Args {
+        let argv = ["review", "https://github.com/acme/api/pull/1"];
+        Args::try_parse_from(argv.iter().chain(extra)).unwrap()
+    }
+
+    #[test]
+    fn test_email_from_args_ignores_blank_values() {
+        assert_eq!(
+            email_from_args(&parse_args(&["--email", " dev@example.com "])).as_deref(),
+            Some("dev@example.com")
+        );
+        assert_eq!(email_from_args(&parse_args(&["--email", "  "])), None);
+    }
+
+    #[test]
+    fn test_non_interactive_email_requires_terms_and_email() {
+        let args = parse_args(&["--non-interactive", "--email", "dev@example.com"]);
+        assert!(matches!(
+            non_interactive_email(&args),
+            Err(ReviewError::MissingInput(_))
+        ));
+
+        let args = parse_args(&["--non-interactive", "--accept-terms", "--email", " "]);
+        assert!(matches!(
+            non_interactive_email(&args),
+            Err(ReviewError::MissingInput(_))
+        ));
+
+        let args = parse_args(&[
+            "--non-interactive",
+            "--accept-terms",
+            "--email",
+            "dev@example.com",
+        ]);
+        assert_eq!(non_interactive_email(&args).unwrap(), "dev@example.com");
+    }
+
+    fn names(prs: &[PrInfo]) -> Vec<String> {
+        repo_dir_names(&prs.iter().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_repo_dir_names_use_repo_name_when_distinct() {
+        assert_eq!(
+            names(&[pr("acme", "api"), pr("acme", "web")]),
+            ["api", "web"]
+        );
+    }
+
+    #[test]
+    fn test_repo_dir_names_prefix_owner_for_shared_repo_names() {
+        assert_eq!(
+            names(&[pr("acme", "api"), pr("other", "api")]),
+            ["acme-api", "other-api"]
+        );
+    }
+
+    #[test]
+    fn test_repo_dir_names_are_unique() {
+        let cases = [
+            vec![pr("acme", "api"), pr("acme", "api")],
+            vec![pr("acme", "api"), pr("acme", "api"), pr("acme", "api-2")],
+            vec![pr("a-b", "c"), pr("a", "b-c"), pr("x", "c"), pr("y", "b-c")],
+            vec![pr("acme", "api"), pr("other", "api"), pr("x", "acme-api")],
+        ];
+        for prs in cases {
+            let names = names(&prs);
+            let unique: HashSet<_> = names.iter().collect();
+            assert_eq!(unique.len(), names.len(), "duplicate names in {names:?}");
+        }
+    }
+}
@@ -5,15 +5,23 @@ use tracing::debug;
 
 use crate::{
     claude_session::{
-        ClaudeProject, discover_projects, discover_sessions, find_projects_by_branch,
+        ClaudeProject, ClaudeSession, RepoScope, discover_projects, discover_sessions,
+        find_projects_by_branch,
     },
     error::ReviewError,
 };
 
+/// A session file chosen for upload
+pub struct SelectedSession {
+    pub path: PathBuf,
+    /// `owner/repo` (or directory name) the session ran in, when known
+    pub repo: Option<String>,
+}
+
 /// Result of session selection process
 pub enum SessionSelection {
     /// User selected session files to include (all sessions from a project)
-    Selected(Vec<PathBuf>),
+    Selected(Vec<SelectedSession>),
     /// User chose to skip session attachment
     Skipped,
 }
@@ -21,19 +29,21 @@ pub enum SessionSelection {
 /// Prompt user to select a Claude Code project
 ///
 /// Flow:
-/// 1. Try auto-match by branch name
+/// 1. Try auto-match by branch name, within the PR's repository
 /// 2. If match found, confirm with user
-/// 3. If no match or user declines, show scrollable project list
+/// 3. If no match or user declines, show scrollable project list, with projects
+///    from the PR's repository first
 /// 4. Allow user to skip entirely
 ///
 /// When a project is selected, ALL sessions from that project are included.
-pub fn select_session(pr_branch: &str) -> Result<SessionSelection, ReviewError> {
+pub fn select_session(pr_branch: &str, scope: &RepoScope) -> Result<SessionSelection, ReviewError> {
     debug!(
-        "Looking for Claude Code projects matching branch: {}",
-        pr_branch
+        "Looking for Claude Code projects matching branch {} in {}",
+        pr_branch,
+        scope.full_name()
     );
 
-    let projects = discover_projects()?;
+    let mut projects = discover_projects()?;
 
     if projects.is_empty() {
         debug!("No Claude Code projects found");
@@ -41,7 +51,7 @@ pub fn select_session(pr_branch: &str) -> Result<SessionSelection, ReviewError>
     }
 
     // Try auto-match by branch
-    let matches = find_projects_by_branch(&projects, pr_branch)?;
+    let matches = find_projects_by_branch(&projects, pr_branch, scope)?;
 
     if !matches.is_empty() {
         // Found a matching project, ask for confirmation
@@ -54,6 +64,9 @@ pub fn select_session(pr_branch: &str) -> Result<SessionSelection, ReviewError>
             pr_branch
         );
         println!("  Project: {}", project.name);
+        if let Some(repo) = project.repo_label() {
+            println!("  Repository: {}", repo);
+        }
         if let Some(ref prompt) = project.first_prompt {
             println!("  \"{}\"", prompt);
         }
@@ -79,8 +92,9 @@ pub fn select_session(pr_branch: &str) -> Result<SessionSelection, ReviewError>
         match selection {
             0 => {
                 // Yes, use all sessions from this project
-                let paths: Vec<PathBuf> = sessions.iter().map(|s| s.path.clone()).collect();
-                return Ok(SessionSelection::Selected(paths));
+                return Ok(SessionSelection::Selected(selected_sessions(
+                    project, sessions,
+                )));
             }
             2 => {
                 // Skip
@@ -92,10 +106,27 @@ pub fn select_session(pr_branch: &str) -> Result<SessionSelection, ReviewError>
         }
     }
 
-    // Manual selection: select a project
+    // Manual selection: projects from the PR's repository first (stable sort
+    // keeps the most-recent-first order within each group)
+    projects.sort_by_cached_key(|p| match p.matches_repo(scope) {
+        Some(true) => 0,
+        None => 1,
+        Some(false) => 2,
+    });
     select_project(&projects)
 }
 
+fn selected_sessions(project: &ClaudeProject, sessions: &[ClaudeSession]) -> Vec<SelectedSession> {
+    let repo = project.repo_label();
+    sessions
+        .iter()
+        .map(|s| SelectedSession {
+            path: s.path.clone(),
+            repo: repo.clone(),
+        })
+        .collect()
+}
+
 /// Manual project selection - returns all sessions from selected project
 fn select_project(projects: &[ClaudeProject]) -> Result<SessionSelection, ReviewError> {
     // Build project list with rich metadata
@@ -123,8 +154,9 @@ fn select_project(projects: &[ClaudeProject]) -> Result<SessionSelection, Review
     let sessions = discover_sessions(project)?;
 
     // Return all session paths from this project
-    let paths: Vec<PathBuf> = sessions.iter().map(|s| s.path.clone()).collect();
-    Ok(SessionSelection::Selected(paths))
+    Ok(SessionSelection::Selected(selected_sessions(
+        project, &sessions,
+    )))
 }
 
 /// Format a project item for display in the selection list
@@ -141,10 +173,16 @@ fn format_project_item(project: &ClaudeProject) -> String {
         .map(|b| format!("branch: {}", b))
         .unwrap_or_else(|| "no branch".to_string());
 
+    let repo = project
+        .repo_label()
+        .map(|r| format!("{} · ", r))
+        .unwrap_or_default();
+
     format!(
-        "{}{}\n  {} · {} session{} · {}\n",
+        "{}{}\n  {}{} · {} session{} · {}\n",
         project.name,
         prompt_line,
+        repo,
         branch,
         project.session_count,
         if project.session_count == 1 { "" } else { "s" },
@@ -9,6 +9,15 @@ fn main() {
     if let Ok(api_endpoint) = std::env::var("POSTHOG_API_ENDPOINT") {
         println!("cargo:rustc-env=POSTHOG_API_ENDPOINT={}", api_endpoint);
     }
+    for name in [
+        "VK_ANALYTICS_BACKEND",
+        "VK_ANALYTICS_ENDPOINT",
+        "VK_ANALYTICS_TOKEN",
+    ] {
+        if let Ok(value) = std::env::var(name) {
+            println!("cargo:rustc-env={}={}", name, value);
+        }
+    }
     if let Ok(vk_shared_api_base) = std::env::var("VK_SHARED_API_BASE") {
         println!("cargo:rustc-env=VK_SHARED_API_BASE={}", vk_shared_api_base);
     }
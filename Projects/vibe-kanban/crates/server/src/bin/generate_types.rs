@@ -47,9 +47,11 @@ fn generate_types_content() -> String {
         db::models::workspace::Workspace::decl(),
         db::models::workspace::WorkspaceWithStatus::decl(),
         db::models::session::Session::decl(),
+        db::models::session_review_marker::SessionReviewMarker::decl(),
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_process::ExecutionChangeOutcome::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
@@ -61,6 +63,10 @@ fn generate_types_content() -> String {
         utils::approvals::ApprovalResponse::decl(),
         utils::diff::Diff::decl(),
         utils::diff::DiffChangeKind::decl(),
+        utils::diff::SubmoduleUpdate::decl(),
+        utils::diff::FileChangeSize::decl(),
+        utils::diff::DiffSummary::decl(),
+        utils::diff::DiffResult::decl(),
         utils::response::ApiResponse::<()>::decl(),
         utils::api::oauth::LoginStatus::decl(),
         utils::api::oauth::ProfileResponse::decl(),
@@ -92,6 +98,7 @@ fn generate_types_content() -> String {
         utils::api::projects::RemoteProjectMembersResponse::decl(),
         server::routes::repo::RegisterRepoRequest::decl(),
         server::routes::repo::InitRepoRequest::decl(),
+        server::routes::repo::BranchUpstream::decl(),
         server::routes::tags::TagSearchParams::decl(),
         server::routes::oauth::TokenResponse::decl(),
         server::routes::config::UserSystemInfo::decl(),
@@ -102,6 +109,13 @@ fn generate_types_content() -> String {
         server::routes::config::CheckEditorAvailabilityQuery::decl(),
         server::routes::config::CheckEditorAvailabilityResponse::decl(),
         server::routes::config::CheckAgentAvailabilityQuery::decl(),
+        server::routes::health::SubsystemHealth::decl(),
+        server::routes::health::GitHostCliHealth::decl(),
+        server::routes::health::ExecutorHealth::decl(),
+        db::MigrationStatus::decl(),
+        db::CompactionReport::decl(),
+        db::WalCheckpoint::decl(),
+        server::routes::health::DetailedHealthResponse::decl(),
         server::routes::oauth::CurrentUserResponse::decl(),
         server::routes::sessions::CreateFollowUpAttempt::decl(),
         server::routes::task_attempts::ChangeTargetBranchRequest::decl(),
@@ -112,6 +126,7 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::RenameBranchResponse::decl(),
         server::routes::sessions::review::StartReviewRequest::decl(),
         server::routes::sessions::review::ReviewError::decl(),
+        server::routes::sessions::review_markers::MarkReviewedRequest::decl(),
         server::routes::task_attempts::OpenEditorRequest::decl(),
         server::routes::task_attempts::OpenEditorResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
@@ -127,17 +142,51 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::AbortConflictsRequest::decl(),
         server::routes::task_attempts::GitOperationError::decl(),
         server::routes::task_attempts::PushError::decl(),
+        git::CommitMessageRules::decl(),
+        git::CommitIdentity::decl(),
+        git::BinaryDetection::decl(),
+        git::FallbackEncoding::decl(),
+        git::FileContentOptions::decl(),
+        git::CommitMessageIssue::decl(),
+        server::routes::task_attempts::CommitWorkspaceError::decl(),
+        server::routes::task_attempts::CommitWorkspaceRequest::decl(),
+        server::routes::task_attempts::RepoCommitOutcome::decl(),
+        server::routes::task_attempts::RepoCommitResult::decl(),
+        server::routes::task_attempts::CommitWorkspaceResponse::decl(),
+        server::routes::task_attempts::HunkPatchRequest::decl(),
+        server::routes::task_attempts::HunkPatchError::decl(),
+        services::services::secret_scan::SecretKind::decl(),
+        services::services::secret_scan::SecretFinding::decl(),
+        server::routes::task_attempts::secret_scan::RepoSecretFindings::decl(),
         server::routes::task_attempts::pr::PrError::decl(),
         server::routes::task_attempts::RunScriptError::decl(),
         server::routes::task_attempts::pr::AttachPrResponse::decl(),
         server::routes::task_attempts::pr::AttachExistingPrRequest::decl(),
         server::routes::task_attempts::pr::PrCommentsResponse::decl(),
+        server::routes::task_attempts::pr::PrReviewThreadsResponse::decl(),
         server::routes::task_attempts::pr::GetPrCommentsError::decl(),
         server::routes::task_attempts::pr::GetPrCommentsQuery::decl(),
         services::services::git_host::UnifiedPrComment::decl(),
+        services::services::git_host::ReviewThread::decl(),
+        services::services::git_host::ReviewThreadComment::decl(),
         services::services::git_host::ProviderKind::decl(),
         services::services::git_host::OpenPrInfo::decl(),
+        services::services::git_host::HostPermission::decl(),
+        services::services::pr_monitor::PrMonitorStatus::decl(),
+        services::services::remote_connectivity::RemoteConnectivityState::decl(),
+        services::services::execution_slots::ExecutionSlotsStatus::decl(),
+        services::services::project::ProjectReportQuery::decl(),
+        services::services::project::ProjectReport::decl(),
+        services::services::project::ProjectReportTotals::decl(),
+        services::services::project::AttemptReport::decl(),
+        services::services::project::AttemptRepoReport::decl(),
         git::GitRemote::decl(),
+        git::SubmoduleInfo::decl(),
+        git::CherryStatusEntry::decl(),
+        git::StashEntry::decl(),
+        git::FileConflict::decl(),
+        git::BlameLine::decl(),
+        git::FetchProgress::decl(),
         server::routes::repo::ListPrsError::decl(),
         server::routes::task_attempts::pr::CreateWorkspaceFromPrBody::decl(),
         server::routes::task_attempts::pr::CreateWorkspaceFromPrResponse::decl(),
@@ -148,6 +197,9 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::workspace_summary::WorkspaceSummary::decl(),
         server::routes::task_attempts::workspace_summary::WorkspaceSummaryResponse::decl(),
         server::routes::task_attempts::workspace_summary::DiffStats::decl(),
+        server::routes::task_attempts::workspace_summary::RepoDiffStats::decl(),
+        server::routes::task_attempts::workspace_summary::WorkspaceDiffSummary::decl(),
+        server::routes::task_attempts::workspace_summary::RepoDiffResult::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
         services::services::filesystem::DirectoryListResponse::decl(),
         services::services::file_search::SearchMode::decl(),
@@ -155,6 +207,8 @@ fn generate_types_content() -> String {
         services::services::config::NotificationConfig::decl(),
         services::services::config::ThemeMode::decl(),
         services::services::config::EditorConfig::decl(),
+        services::services::config::editor::EditorProfile::decl(),
+        services::services::config::editor::RemoteEditorTarget::decl(),
         services::services::config::EditorType::decl(),
         services::services::config::EditorOpenError::decl(),
         services::services::config::GitHubConfig::decl(),
@@ -181,6 +235,7 @@ fn generate_types_content() -> String {
         executors::profile::ExecutorConfig::decl(),
         executors::profile::ExecutorConfigs::decl(),
         executors::executors::BaseAgentCapability::decl(),
+        executors::executors::TurnRef::decl(),
         executors::executors::claude::ClaudeCode::decl(),
         executors::executors::gemini::Gemini::decl(),
         executors::executors::amp::Amp::decl(),
@@ -9,7 +9,7 @@ use db::models::{
     project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
     workspace::WorkspaceError,
 };
-use deployment::{DeploymentError, RemoteClientNotConfigured};
+use deployment::{DeploymentError, RemoteClientNotConfigured, RemoteUnavailable};
 use executors::{command::CommandBuildError, executors::ExecutorError};
 use git::GitServiceError;
 use git2::Error as Git2Error;
@@ -68,6 +68,8 @@ pub enum ApiError {
     EditorOpen(#[from] EditorOpenError),
     #[error(transparent)]
     RemoteClient(#[from] RemoteClientError),
+    #[error("Remote server is unreachable")]
+    RemoteOffline,
     #[error("Unauthorized")]
     Unauthorized,
     #[error("Bad request: {0}")]
@@ -100,6 +102,15 @@ impl From<RemoteClientNotConfigured> for ApiError {
     }
 }
 
+impl From<RemoteUnavailable> for ApiError {
+    fn from(err: RemoteUnavailable) -> Self {
+        match err {
+            RemoteUnavailable::NotConfigured => RemoteClientNotConfigured.into(),
+            RemoteUnavailable::Offline => ApiError::RemoteOffline,
+        }
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status_code, error_type) = match &self {
@@ -120,11 +131,48 @@ impl IntoResponse for ApiError {
                     (StatusCode::CONFLICT, "GitServiceError")
                 }
                 git::GitServiceError::RebaseInProgress => (StatusCode::CONFLICT, "GitServiceError"),
+                git::GitServiceError::WorktreeDirty(_, _) => {
+                    (StatusCode::CONFLICT, "GitServiceError")
+                }
+                git::GitServiceError::ShallowRepository(_) => {
+                    (StatusCode::CONFLICT, "GitServiceError")
+                }
+                git::GitServiceError::InvalidCommitMessage(_) => {
+                    (StatusCode::BAD_REQUEST, "GitServiceError")
+                }
+                git::GitServiceError::PatchRejected { .. } => {
+                    (StatusCode::CONFLICT, "GitServiceError")
+                }
+                git::GitServiceError::HookExists(_) => (StatusCode::CONFLICT, "GitServiceError"),
+                git::GitServiceError::StashNotFound(_) => {
+                    (StatusCode::NOT_FOUND, "GitServiceError")
+                }
+                git::GitServiceError::BranchCheckedOutElsewhere { .. } => {
+                    (StatusCode::CONFLICT, "GitServiceError")
+                }
+                git::GitServiceError::BranchNotMerged(_) => {
+                    (StatusCode::CONFLICT, "GitServiceError")
+                }
+                git::GitServiceError::CommitSigningFailed(_) => {
+                    (StatusCode::BAD_REQUEST, "GitServiceError")
+                }
+                git::GitServiceError::StashPopConflict { .. } => {
+                    (StatusCode::CONFLICT, "GitServiceError")
+                }
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "GitServiceError"),
             },
             ApiError::GitHost(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHostError"),
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
+            ApiError::Container(ContainerError::NoDevServer(_)) => {
+                (StatusCode::NOT_FOUND, "ContainerError")
+            }
+            ApiError::Container(ContainerError::ExecutorError(ExecutorError::UnknownModel {
+                ..
+            })) => (StatusCode::BAD_REQUEST, "ContainerError"),
             ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
+            ApiError::Executor(
+                ExecutorError::FollowUpNotSupported(_) | ExecutorError::UnknownModel { .. },
+            ) => (StatusCode::BAD_REQUEST, "ExecutorError"),
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
             ApiError::CommandBuilder(_) => (StatusCode::INTERNAL_SERVER_ERROR, "CommandBuildError"),
             ApiError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError"),
@@ -176,6 +224,7 @@ impl IntoResponse for ApiError {
                     (StatusCode::BAD_REQUEST, "RemoteClientError")
                 }
             },
+            ApiError::RemoteOffline => (StatusCode::SERVICE_UNAVAILABLE, "RemoteOffline"),
             ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
             ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "BadRequest"),
             ApiError::Conflict(_) => (StatusCode::CONFLICT, "ConflictError"),
@@ -183,6 +232,7 @@ impl IntoResponse for ApiError {
             ApiError::Pty(err) => match err {
                 PtyError::SessionNotFound(_) => (StatusCode::NOT_FOUND, "PtyError"),
                 PtyError::SessionClosed => (StatusCode::GONE, "PtyError"),
+                PtyError::TooManySessions(_) => (StatusCode::TOO_MANY_REQUESTS, "PtyError"),
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "PtyError"),
             },
         };
@@ -207,6 +257,8 @@ impl IntoResponse for ApiError {
                 git::GitServiceError::RebaseInProgress => {
                     "A rebase is already in progress. Resolve conflicts or abort the rebase, then retry.".to_string()
                 }
+                git::GitServiceError::ShallowRepository(_)
+                | git::GitServiceError::InvalidCommitMessage(_) => git_err.to_string(),
                 _ => format!("{}: {}", error_type, self),
             },
             ApiError::Multipart(_) => "Failed to upload file. Please ensure the file is valid and try again.".to_string(),
@@ -259,6 +311,9 @@ impl IntoResponse for ApiError {
                 RemoteClientError::Serde(_) => "Unexpected response from remote service.".to_string(),
                 RemoteClientError::Url(_) => "Remote service URL is invalid.".to_string(),
             },
+            ApiError::RemoteOffline => {
+                "You're offline: the remote service can't be reached. Reconnecting automatically.".to_string()
+            }
             ApiError::Unauthorized => "Unauthorized. Please sign in again.".to_string(),
             ApiError::BadRequest(msg) => msg.clone(),
             ApiError::Conflict(msg) => msg.clone(),
@@ -285,6 +340,9 @@ impl From<ProjectServiceError> for ApiError {
             ProjectServiceError::NotGitRepository(path) => {
                 ApiError::BadRequest(format!("Path is not a git repository: {}", path.display()))
             }
+            ProjectServiceError::NoBranches(path) => {
+                ApiError::BadRequest(format!("Repository has no branches: {}", path.display()))
+            }
             ProjectServiceError::DuplicateGitRepoPath => ApiError::Conflict(
                 "A project with this git repository path already exists".to_string(),
             ),
@@ -300,6 +358,12 @@ impl From<ProjectServiceError> for ApiError {
             ProjectServiceError::RemoteClient(msg) => {
                 ApiError::BadRequest(format!("Remote client error: {}", msg))
             }
+            ProjectServiceError::InvalidBranchPrefix(prefix) => ApiError::BadRequest(format!(
+                "Invalid branch prefix '{prefix}': use a single path segment without spaces, e.g. 'vk'"
+            )),
+            ProjectServiceError::InvalidDevServerReadyPattern(e) => {
+                ApiError::BadRequest(format!("Invalid dev server ready pattern: {e}"))
+            }
         }
     }
 }
@@ -318,6 +382,9 @@ impl From<RepoServiceError> for ApiError {
             RepoServiceError::NotGitRepository(path) => {
                 ApiError::BadRequest(format!("Path is not a git repository: {}", path.display()))
             }
+            RepoServiceError::NoBranches(path) => {
+                ApiError::BadRequest(format!("Repository has no branches: {}", path.display()))
+            }
             RepoServiceError::NotFound => ApiError::BadRequest("Repository not found".to_string()),
             RepoServiceError::DirectoryAlreadyExists(path) => {
                 ApiError::BadRequest(format!("Directory already exists: {}", path.display()))
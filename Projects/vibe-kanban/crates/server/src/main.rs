@@ -9,7 +9,7 @@ use tracing_subscriber::{EnvFilter, prelude::*};
 use utils::{
     assets::asset_dir,
     browser::open_browser,
-    port_file::write_port_file,
+    port_file::{read_port_file, write_port_file},
     sentry::{self as sentry_utils, SentrySource, sentry_layer},
 };
 
@@ -57,6 +57,11 @@ async fn main() -> Result<(), VibeKanbanError> {
         .cleanup_orphan_executions()
         .await
         .map_err(DeploymentError::from)?;
+    deployment
+        .container()
+        .cleanup_orphan_worktrees(std::env::var("FORCE_WORKTREE_CLEANUP").is_ok())
+        .await
+        .map_err(DeploymentError::from)?;
     deployment
         .container()
         .backfill_before_head_commits()
@@ -100,17 +105,17 @@ async fn main() -> Result<(), VibeKanbanError> {
         }); // Use 0 to find free port if no specific port provided
 
     let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
-    let listener = tokio::net::TcpListener::bind(format!("{host}:{port}")).await?;
+    let listener = bind_listener(&host, port).await?;
     let actual_port = listener.local_addr()?.port(); // get → 53427 (example)
 
     // Write port file for discovery if prod, warn on fail
-    if let Err(e) = write_port_file(actual_port).await {
+    if let Err(e) = write_port_file(actual_port, &host).await {
         tracing::warn!("Failed to write port file: {}", e);
     }
 
     tracing::info!("Server running on http://{host}:{actual_port}");
 
-    if !cfg!(debug_assertions) {
+    if should_open_browser() {
         tracing::info!("Opening browser...");
         tokio::spawn(async move {
             if let Err(e) = open_browser(&format!("http://127.0.0.1:{actual_port}")).await {
@@ -175,3 +180,62 @@ pub async fn perform_cleanup_actions(deployment: &DeploymentImpl) {
         .await
         .expect("Failed to cleanly kill running execution processes");
 }
+
+/// Bind the backend listener. When an explicitly requested port is taken, explain
+/// what is likely holding it, and fall back to a random port if `VK_PORT_FALLBACK`
+/// is truthy.
+async fn bind_listener(host: &str, port: u16) -> Result<tokio::net::TcpListener, VibeKanbanError> {
+    let err = match tokio::net::TcpListener::bind(format!("{host}:{port}")).await {
+        Ok(listener) => return Ok(listener),
+        Err(e) if port != 0 && e.kind() == std::io::ErrorKind::AddrInUse => e,
+        Err(e) => return Err(e.into()),
+    };
+
+    let holder = match read_port_file("vibe-kanban").await {
+        Ok(p) if p == port => "a previous Vibe Kanban instance that did not shut down",
+        _ => "another process",
+    };
+    tracing::error!("Port {port} on {host} is already in use, probably by {holder}");
+
+    if std::env::var("VK_PORT_FALLBACK").is_ok_and(|v| is_truthy(&v)) {
+        tracing::warn!("VK_PORT_FALLBACK is set, binding to a random free port instead");
+        return Ok(tokio::net::TcpListener::bind(format!("{host}:0")).await?);
+    }
+
+    Err(anyhow::anyhow!(
+        "Port {port} is already in use ({err}). Stop {holder}, choose a different PORT/BACKEND_PORT, or set VK_PORT_FALLBACK=1 to use a random port"
+    )
+    .into())
+}
+
+/// `1`, `true`, `yes` or `on`, in any case
+fn is_truthy(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    )
+}
+
+/// `VK_NO_BROWSER` takes precedence over the build profile: a truthy value never
+/// opens the browser, `0`/`false` always does. Unset falls back to release-only.
+fn should_open_browser() -> bool {
+    match std::env::var("VK_NO_BROWSER") {
+        Ok(v) => matches!(v.trim().to_ascii_lowercase().as_str(), "0" | "false" | "no"),
+        Err(_) => !cfg!(debug_assertions),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_truthy_values_enable_a_flag() {
+        for value in ["1", "true", "TRUE", " yes ", "on"] {
+            assert!(is_truthy(value), "{value:?}");
+        }
+        for value in ["", "0", "false", "no", "off", "2"] {
+            assert!(!is_truthy(value), "{value:?}");
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use axum::{
     Json, Router,
@@ -25,9 +25,10 @@ use services::services::{
     config::{
         Config, ConfigError, SoundFile,
         editor::{EditorConfig, EditorType},
-        save_config_to_file,
+        save_config_to_file, validate_config,
     },
     container::ContainerService,
+    remote_connectivity::RemoteConnectivityState,
 };
 use tokio::fs;
 use ts_rs::TS;
@@ -85,11 +86,14 @@ pub struct UserSystemInfo {
     pub config: Config,
     pub analytics_user_id: String,
     pub login_status: LoginStatus,
+    pub remote_connectivity: RemoteConnectivityState,
     #[serde(flatten)]
     pub profiles: ExecutorConfigs,
     pub environment: Environment,
     /// Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
     pub capabilities: HashMap<String, Vec<BaseAgentCapability>>,
+    /// Known model names per executor, empty when the executor accepts any model
+    pub supported_models: HashMap<String, Vec<String>>,
 }
 
 // TODO: update frontend, BE schema has changed, this replaces GET /config and /config/constants
@@ -100,22 +104,25 @@ async fn get_user_system_info(
     let config = deployment.config().read().await;
     let login_status = deployment.get_login_status().await;
 
+    let mut capabilities: HashMap<String, Vec<BaseAgentCapability>> = HashMap::new();
+    let mut supported_models: HashMap<String, Vec<String>> = HashMap::new();
+    let profs = ExecutorConfigs::get_cached();
+    for key in profs.executors.keys() {
+        if let Some(agent) = profs.get_coding_agent(&ExecutorProfileId::new(*key)) {
+            capabilities.insert(key.to_string(), agent.capabilities());
+            supported_models.insert(key.to_string(), agent.supported_models());
+        }
+    }
+
     let user_system_info = UserSystemInfo {
         config: config.clone(),
         analytics_user_id: deployment.user_id().to_string(),
         login_status,
+        remote_connectivity: deployment.remote_connectivity(),
         profiles: ExecutorConfigs::get_cached(),
         environment: Environment::new(),
-        capabilities: {
-            let mut caps: HashMap<String, Vec<BaseAgentCapability>> = HashMap::new();
-            let profs = ExecutorConfigs::get_cached();
-            for key in profs.executors.keys() {
-                if let Some(agent) = profs.get_coding_agent(&ExecutorProfileId::new(*key)) {
-                    caps.insert(key.to_string(), agent.capabilities());
-                }
-            }
-            caps
-        },
+        capabilities,
+        supported_models,
     };
 
     ResponseJson(ApiResponse::success(user_system_info))
@@ -127,11 +134,12 @@ async fn update_config(
 ) -> ResponseJson<ApiResponse<Config>> {
     let config_path = config_path();
 
-    // Validate git branch prefix
-    if !git::is_valid_branch_prefix(&new_config.git_branch_prefix) {
-        return ResponseJson(ApiResponse::error(
-            "Invalid git branch prefix. Must be a valid git branch name component without slashes.",
-        ));
+    if let Err(e) = validate_config(&new_config) {
+        let message = match e {
+            ConfigError::ValidationError(message) => message,
+            other => other.to_string(),
+        };
+        return ResponseJson(ApiResponse::error(&message));
     }
 
     // Get old config state before updating
@@ -187,6 +195,63 @@ async fn track_config_events(deployment: &DeploymentImpl, old: &Config, new: &Co
 async fn handle_config_events(deployment: &DeploymentImpl, old: &Config, new: &Config) {
     track_config_events(deployment, old, new).await;
 
+    if old.init_submodules != new.init_submodules {
+        deployment.git().set_init_submodules(new.init_submodules);
+    }
+
+    if old.preferred_remotes != new.preferred_remotes {
+        deployment
+            .git()
+            .set_preferred_remotes(new.preferred_remotes.clone());
+    }
+
+    if old.deepen_shallow_clones != new.deepen_shallow_clones {
+        deployment
+            .git()
+            .set_shallow_deepening(new.deepen_shallow_clones);
+    }
+
+    if old.sign_commits != new.sign_commits {
+        deployment.git().set_commit_signing(new.sign_commits);
+    }
+
+    if old.commit_message_rules != new.commit_message_rules {
+        deployment
+            .git()
+            .set_commit_message_rules(new.commit_message_rules.clone());
+    }
+
+    if old.file_content != new.file_content {
+        deployment.git().set_file_content(new.file_content.clone());
+    }
+
+    if old.pr_monitor_poll_interval_secs != new.pr_monitor_poll_interval_secs {
+        deployment
+            .pr_monitor()
+            .set_poll_interval(Duration::from_secs(
+                new.pr_monitor_poll_interval_secs.into(),
+            ));
+    }
+
+    if old.max_concurrent_executions != new.max_concurrent_executions {
+        deployment
+            .container()
+            .execution_slots()
+            .set_max_concurrent(new.max_concurrent_executions);
+    }
+
+    if old.terminal_output_buffer_kb != new.terminal_output_buffer_kb {
+        deployment
+            .pty()
+            .set_output_buffer_limit(new.terminal_output_buffer_kb as usize * 1024);
+    }
+
+    if old.max_terminal_sessions != new.max_terminal_sessions {
+        deployment
+            .pty()
+            .set_max_sessions(new.max_terminal_sessions as usize);
+    }
+
     if !old.disclaimer_acknowledged && new.disclaimer_acknowledged {
         // Spawn auto project setup as background task to avoid blocking config response
         let deployment_clone = deployment.clone();
@@ -2,11 +2,15 @@ use axum::{
     Router,
     extract::{Query, State},
     response::Json as ResponseJson,
-    routing::get,
+    routing::{get, post},
+};
+use db::models::{
+    execution_process::ExecutionProcess,
+    workspace::{Workspace, WorkspaceContext},
 };
-use db::models::workspace::{Workspace, WorkspaceContext};
 use deployment::Deployment;
 use serde::{Deserialize, Serialize};
+use services::services::container::ContainerService;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
@@ -63,6 +67,24 @@ pub async fn get_context(
     }
 }
 
+/// Restart the workspace's dev servers, e.g. after one crashed, without touching
+/// its other processes.
+pub async fn restart_dev_server(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ContainerQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionProcess>>>, ApiError> {
+    let info = Workspace::resolve_container_ref(&deployment.db().pool, &query.container_ref)
+        .await
+        .map_err(ApiError::Database)?;
+
+    let execution_processes = deployment
+        .container()
+        .restart_dev_server(info.workspace_id)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(execution_processes)))
+}
+
 pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     Router::new()
         // NOTE: /containers/info is required by the VSCode extension (vibe-kanban-vscode)
@@ -70,4 +92,5 @@ pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         // Do not remove this endpoint without updating the extension.
         .route("/containers/info", get(get_container_info))
         .route("/containers/attempt-context", get(get_context))
+        .route("/containers/dev-server/restart", post(restart_dev_server))
 }
@@ -16,7 +16,7 @@ use db::models::{
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
-use services::services::container::ContainerService;
+use services::services::{container::ContainerService, execution_slots::ExecutionSlotsStatus};
 use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
@@ -243,6 +243,15 @@ pub async fn get_execution_process_repo_states(
     Ok(ResponseJson(ApiResponse::success(repo_states)))
 }
 
+/// Running and queued coding agents, for showing "waiting for a slot"
+pub async fn get_execution_slots(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ExecutionSlotsStatus>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        deployment.container().execution_slots().status(),
+    )))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let workspace_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
@@ -256,6 +265,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         ));
 
     let workspaces_router = Router::new()
+        .route("/slots", get(get_execution_slots))
         .route(
             "/stream/session/ws",
             get(stream_execution_processes_by_session_ws),
@@ -1,6 +1,200 @@
-use axum::response::Json;
-use utils::response::ApiResponse;
+use axum::{extract::State, response::Json};
+use db::{DBService, MigrationStatus};
+use deployment::Deployment;
+use executors::{
+    executors::{AvailabilityInfo, BaseCodingAgent, StandardCodingAgentExecutor},
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
+use serde::Serialize;
+use services::services::git_host::ProviderKind;
+use ts_rs::TS;
+use utils::{response::ApiResponse, shell::resolve_executable_path};
+
+use crate::DeploymentImpl;
 
 pub async fn health_check() -> Json<ApiResponse<String>> {
     Json(ApiResponse::success("OK".to_string()))
 }
+
+#[derive(Debug, Serialize, TS)]
+pub struct SubsystemHealth {
+    pub ok: bool,
+    pub detail: Option<String>,
+}
+
+impl SubsystemHealth {
+    fn ok(detail: Option<String>) -> Self {
+        Self { ok: true, detail }
+    }
+
+    fn failed(detail: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct GitHostCliHealth {
+    pub provider: ProviderKind,
+    pub cli: String,
+    pub health: SubsystemHealth,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ExecutorHealth {
+    pub executor: BaseCodingAgent,
+    pub availability: AvailabilityInfo,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DetailedHealthResponse {
+    /// True only when the database and git are both usable
+    pub ok: bool,
+    pub database: SubsystemHealth,
+    /// `None` if the migration table could not be read
+    pub migrations: Option<MigrationStatus>,
+    pub git: SubsystemHealth,
+    pub git_host_clis: Vec<GitHostCliHealth>,
+    pub executors: Vec<ExecutorHealth>,
+}
+
+/// Report the status of each subsystem independently. Always responds with 200
+/// so a single broken dependency doesn't hide the state of the others.
+pub async fn health_detailed(
+    State(deployment): State<DeploymentImpl>,
+) -> Json<ApiResponse<DetailedHealthResponse>> {
+    Json(ApiResponse::success(detailed_health(deployment.db()).await))
+}
+
+async fn detailed_health(db: &DBService) -> DetailedHealthResponse {
+    let database = match db.ping().await {
+        Ok(()) => SubsystemHealth::ok(None),
+        Err(e) => SubsystemHealth::failed(e.to_string()),
+    };
+    let migrations = match db.migration_status().await {
+        Ok(status) => Some(status),
+        Err(e) => {
+            tracing::warn!("Failed to read migration status: {}", e);
+            None
+        }
+    };
+    let git = check_git().await;
+
+    let mut git_host_clis = Vec::new();
+    for (provider, cli) in [
+        (ProviderKind::GitHub, "gh"),
+        (ProviderKind::AzureDevOps, "az"),
+        (ProviderKind::GitLab, "glab"),
+    ] {
+        let health = match resolve_executable_path(cli).await {
+            Some(path) => SubsystemHealth::ok(Some(path.to_string_lossy().into_owned())),
+            None => SubsystemHealth::failed(format!("`{cli}` not found in PATH")),
+        };
+        git_host_clis.push(GitHostCliHealth {
+            provider,
+            cli: cli.to_string(),
+            health,
+        });
+    }
+
+    let profiles = ExecutorConfigs::get_cached();
+    let mut executors: Vec<ExecutorHealth> = profiles
+        .executors
+        .keys()
+        .map(|&executor| ExecutorHealth {
+            executor,
+            availability: profiles
+                .get_coding_agent(&ExecutorProfileId::new(executor))
+                .map(|agent| agent.get_availability_info())
+                .unwrap_or(AvailabilityInfo::NotFound),
+        })
+        .collect();
+    executors.sort_by_key(|e| e.executor.to_string());
+
+    DetailedHealthResponse {
+        ok: database.ok && git.ok,
+        database,
+        migrations,
+        git,
+        git_host_clis,
+        executors,
+    }
+}
+
+async fn check_git() -> SubsystemHealth {
+    let Some(git) = resolve_executable_path("git").await else {
+        return SubsystemHealth::failed("`git` not found in PATH");
+    };
+    match tokio::process::Command::new(git)
+        .arg("--version")
+        .output()
+        .await
+    {
+        Ok(out) if out.status.success() => SubsystemHealth::ok(Some(
+            String::from_utf8_lossy(&out.stdout).trim().to_string(),
+        )),
+        Ok(out) => SubsystemHealth::failed(String::from_utf8_lossy(&out.stderr).trim().to_string()),
+        Err(e) => SubsystemHealth::failed(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    async fn test_db() -> DBService {
+        // One connection, so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("../db/migrations").run(&pool).await.unwrap();
+        DBService { pool }
+    }
+
+    #[tokio::test]
+    async fn detailed_health_reports_each_subsystem() {
+        let db = test_db().await;
+
+        let health = detailed_health(&db).await;
+        assert!(health.database.ok);
+        assert!(health.git.ok, "git check failed: {:?}", health.git.detail);
+        assert!(health.ok);
+        assert!(health.migrations.unwrap().is_up_to_date());
+        let clis: Vec<_> = health
+            .git_host_clis
+            .iter()
+            .map(|c| c.cli.as_str())
+            .collect();
+        assert_eq!(clis, ["gh", "az", "glab"]);
+
+        let json = serde_json::to_value(detailed_health(&db).await).unwrap();
+        for key in [
+            "ok",
+            "database",
+            "migrations",
+            "git",
+            "git_host_clis",
+            "executors",
+        ] {
+            assert!(json.get(key).is_some(), "missing `{key}` in {json}");
+        }
+    }
+
+    #[tokio::test]
+    async fn unreachable_database_fails_overall_health() {
+        let db = test_db().await;
+        db.pool.close().await;
+
+        let health = detailed_health(&db).await;
+        assert!(!health.database.ok);
+        assert!(health.database.detail.is_some());
+        assert!(health.migrations.is_none());
+        assert!(!health.ok);
+    }
+}
@@ -0,0 +1,165 @@
+use axum::{Router, extract::State, response::Json as ResponseJson, routing::post};
+use db::{CompactionReport, WalCheckpoint, models::execution_process::ExecutionProcess};
+use deployment::Deployment;
+use sqlx::SqlitePool;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Maintenance takes the database's write lock, which would stall the log and
+/// status writes of anything still executing
+async fn ensure_no_running_processes(pool: &SqlitePool) -> Result<(), ApiError> {
+    let running = ExecutionProcess::find_running(pool).await?;
+    if running.is_empty() {
+        return Ok(());
+    }
+    Err(ApiError::Conflict(format!(
+        "{} execution process(es) running or queued; stop them before database maintenance",
+        running.len()
+    )))
+}
+
+/// Reclaims free pages in the local database. Refused while anything is
+/// executing, since it blocks other writes while it runs.
+pub async fn compact_database(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<CompactionReport>>, ApiError> {
+    ensure_no_running_processes(&deployment.db().pool).await?;
+    let report = deployment.db().compact().await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub async fn checkpoint_database(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WalCheckpoint>>, ApiError> {
+    ensure_no_running_processes(&deployment.db().pool).await?;
+    let checkpoint = deployment.db().wal_checkpoint().await?;
+    Ok(ResponseJson(ApiResponse::success(checkpoint)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/maintenance/db/compact", post(compact_database))
+        .route("/maintenance/db/checkpoint", post(checkpoint_database))
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::{
+        execution_process::{
+            CreateExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+        },
+        project::{CreateProject, Project},
+        session::{CreateSession, Session},
+        task::{CreateTask, Task},
+        workspace::{CreateWorkspace, Workspace},
+    };
+    use executors::actions::{
+        ExecutorAction, ExecutorActionType,
+        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+    };
+    use sqlx::sqlite::SqlitePoolOptions;
+    use uuid::Uuid;
+
+    use super::*;
+
+    async fn test_pool() -> SqlitePool {
+        // One connection, so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("../db/migrations").run(&pool).await.unwrap();
+        pool
+    }
+
+    async fn start_process(pool: &SqlitePool) -> ExecutionProcess {
+        let project = Project::create(
+            pool,
+            &CreateProject {
+                name: "Maintenance".to_string(),
+                repositories: Vec::new(),
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let task = Task::create(
+            pool,
+            &CreateTask {
+                project_id: project.id,
+                title: "Busy".to_string(),
+                description: None,
+                status: None,
+                parent_workspace_id: None,
+                image_ids: None,
+            },
+            Uuid::new_v4(),
+        )
+        .await
+        .unwrap();
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch: "vk/busy".to_string(),
+                agent_working_dir: None,
+            },
+            Uuid::new_v4(),
+            task.id,
+        )
+        .await
+        .unwrap();
+        let session = Session::create(
+            pool,
+            &CreateSession { executor: None },
+            Uuid::new_v4(),
+            workspace.id,
+        )
+        .await
+        .unwrap();
+        ExecutionProcess::create(
+            pool,
+            &CreateExecutionProcess {
+                session_id: session.id,
+                executor_action: ExecutorAction::new(
+                    ExecutorActionType::ScriptRequest(ScriptRequest {
+                        script: "true".to_string(),
+                        language: ScriptRequestLanguage::Bash,
+                        context: ScriptContext::SetupScript,
+                        working_dir: None,
+                    }),
+                    None,
+                ),
+                run_reason: ExecutionProcessRunReason::SetupScript,
+            },
+            Uuid::new_v4(),
+            ExecutionProcessStatus::Running,
+            &[],
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn maintenance_is_refused_while_a_process_is_running() {
+        let pool = test_pool().await;
+        ensure_no_running_processes(&pool).await.unwrap();
+
+        let process = start_process(&pool).await;
+        assert!(matches!(
+            ensure_no_running_processes(&pool).await,
+            Err(ApiError::Conflict(_))
+        ));
+
+        ExecutionProcess::update_completion(
+            &pool,
+            process.id,
+            ExecutionProcessStatus::Completed,
+            Some(0),
+        )
+        .await
+        .unwrap();
+        ensure_no_running_processes(&pool).await.unwrap();
+    }
+}
@@ -232,22 +232,9 @@ async fn logout(State(deployment): State<DeploymentImpl>) -> Result<StatusCode,
 async fn status(
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<StatusResponse>>, ApiError> {
-    use utils::api::oauth::LoginStatus;
-
-    match deployment.get_login_status().await {
-        LoginStatus::LoggedOut => Ok(ResponseJson(ApiResponse::success(StatusResponse {
-            logged_in: false,
-            profile: None,
-            degraded: None,
-        }))),
-        LoginStatus::LoggedIn { profile } => {
-            Ok(ResponseJson(ApiResponse::success(StatusResponse {
-                logged_in: true,
-                profile: Some(profile),
-                degraded: None,
-            })))
-        }
-    }
+    Ok(ResponseJson(ApiResponse::success(
+        deployment.get_login_status().await.into(),
+    )))
 }
 
 /// Returns the current access token (auto-refreshes if needed)
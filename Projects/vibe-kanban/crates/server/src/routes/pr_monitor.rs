@@ -0,0 +1,39 @@
+use axum::{
+    Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use deployment::Deployment;
+use services::services::pr_monitor::PrMonitorStatus;
+use utils::response::ApiResponse;
+
+use crate::DeploymentImpl;
+
+pub async fn get_pr_monitor_status(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<PrMonitorStatus>> {
+    ResponseJson(ApiResponse::success(deployment.pr_monitor().status()))
+}
+
+pub async fn pause_pr_monitor(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<PrMonitorStatus>> {
+    deployment.pr_monitor().pause();
+    ResponseJson(ApiResponse::success(deployment.pr_monitor().status()))
+}
+
+/// Resumes polling and checks open PRs immediately.
+pub async fn resume_pr_monitor(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<PrMonitorStatus>> {
+    deployment.pr_monitor().resume();
+    ResponseJson(ApiResponse::success(deployment.pr_monitor().status()))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/pr-monitor", get(get_pr_monitor_status))
+        .route("/pr-monitor/pause", post(pause_pr_monitor))
+        .route("/pr-monitor/resume", post(resume_pr_monitor))
+}
@@ -19,7 +19,11 @@ use db::models::{
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
-use services::services::{file_search::SearchQuery, project::ProjectServiceError};
+use services::services::{
+    config::EditorType,
+    file_search::SearchQuery,
+    project::{ProjectReport, ProjectReportQuery, ProjectServiceError},
+};
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
@@ -89,7 +93,12 @@ pub async fn create_project(
 
     match deployment
         .project()
-        .create_project(&deployment.db().pool, deployment.repo(), payload)
+        .create_project(
+            &deployment.db().pool,
+            deployment.git(),
+            deployment.repo(),
+            payload,
+        )
         .await
     {
         Ok(project) => {
@@ -130,18 +139,12 @@ pub async fn update_project(
     Extension(existing_project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateProject>,
-) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
-    match deployment
+) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
+    let project = deployment
         .project()
         .update_project(&deployment.db().pool, &existing_project, payload)
-        .await
-    {
-        Ok(project) => Ok(ResponseJson(ApiResponse::success(project))),
-        Err(e) => {
-            tracing::error!("Failed to update project: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(project)))
 }
 
 pub async fn delete_project(
@@ -185,6 +188,8 @@ pub struct OpenEditorRequest {
 #[derive(Debug, serde::Serialize, ts_rs::TS)]
 pub struct OpenEditorResponse {
     pub url: Option<String>,
+    /// The editor that handled the request, which may be a fallback
+    pub editor_type: EditorType,
 }
 
 pub async fn open_project_in_editor(
@@ -215,12 +220,16 @@ pub async fn open_project_in_editor(
     };
 
     match editor_config.open_file(&path).await {
-        Ok(url) => {
+        Ok(opened) => {
             tracing::info!(
                 "Opened editor for project {} at path: {}{}",
                 project.id,
                 path.to_string_lossy(),
-                if url.is_some() { " (remote mode)" } else { "" }
+                if opened.url.is_some() {
+                    " (remote mode)"
+                } else {
+                    ""
+                }
             );
 
             deployment
@@ -229,13 +238,15 @@ pub async fn open_project_in_editor(
                     serde_json::json!({
                         "project_id": project.id.to_string(),
                         "editor_type": payload.as_ref().and_then(|req| req.editor_type.as_ref()),
-                        "remote_mode": url.is_some(),
+                        "opened_editor_type": opened.editor_type,
+                        "remote_mode": opened.url.is_some(),
                     }),
                 )
                 .await;
 
             Ok(ResponseJson(ApiResponse::success(OpenEditorResponse {
-                url,
+                url: opened.url,
+                editor_type: opened.editor_type,
             })))
         }
         Err(e) => {
@@ -312,6 +323,7 @@ pub async fn add_project_repository(
         .project()
         .add_repository(
             &deployment.db().pool,
+            deployment.git(),
             deployment.repo(),
             project.id,
             &payload,
@@ -433,6 +445,28 @@ pub async fn get_project_repository(
     }
 }
 
+/// Report of the project's task attempts (diff stats, merges, PR links),
+/// optionally scoped to attempts created in `[from, to)`.
+pub async fn get_project_report(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ProjectReportQuery>,
+) -> Result<ResponseJson<ApiResponse<ProjectReport>>, ApiError> {
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from > to
+    {
+        return Err(ApiError::BadRequest(
+            "'from' must not be after 'to'".to_string(),
+        ));
+    }
+
+    let report = deployment
+        .project()
+        .build_report(&deployment.db().pool, deployment.git(), &project, &query)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let project_id_router = Router::new()
         .route(
@@ -441,6 +475,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         )
         .route("/search", get(search_project_files))
         .route("/open-editor", post(open_project_in_editor))
+        .route("/report", get(get_project_report))
         .route(
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
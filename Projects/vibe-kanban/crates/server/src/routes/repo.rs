@@ -1,8 +1,8 @@
 use axum::{
     Router,
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json as ResponseJson,
+    http::{StatusCode, header},
+    response::{IntoResponse, Json as ResponseJson},
     routing::{get, post},
 };
 use db::models::{
@@ -10,14 +10,16 @@ use db::models::{
     repo::{Repo, UpdateRepo},
 };
 use deployment::Deployment;
-use git::{GitBranch, GitRemote};
+use git::{CherryStatusEntry, GitBranch, GitRemote, StashEntry, SubmoduleInfo};
 use serde::{Deserialize, Serialize};
 use services::services::{
     file_search::SearchQuery,
-    git_host::{GitHostError, GitHostProvider, GitHostService, OpenPrInfo, ProviderKind},
+    git_host::{
+        GitHostError, GitHostProvider, GitHostService, HostPermission, OpenPrInfo, ProviderKind,
+    },
 };
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{diff::Diff, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
@@ -54,6 +56,7 @@ pub async fn register_repo(
         .repo()
         .register(
             &deployment.db().pool,
+            deployment.git(),
             &payload.path,
             payload.display_name.as_deref(),
         )
@@ -92,6 +95,94 @@ pub async fn get_repo_branches(
     Ok(ResponseJson(ApiResponse::success(branches)))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct BranchUpstreamQuery {
+    pub branch: String,
+}
+
+/// Tracking relationship of a local branch; `upstream` is e.g. `origin/main`
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BranchUpstream {
+    pub branch: String,
+    pub upstream: Option<String>,
+}
+
+pub async fn get_branch_upstream(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    Query(query): Query<BranchUpstreamQuery>,
+) -> Result<ResponseJson<ApiResponse<BranchUpstream>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+
+    let upstream = deployment.git().get_upstream(&repo.path, &query.branch)?;
+    Ok(ResponseJson(ApiResponse::success(BranchUpstream {
+        branch: query.branch,
+        upstream,
+    })))
+}
+
+/// Set or clear (`upstream: null`) the upstream of a local branch
+pub async fn set_branch_upstream(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<BranchUpstream>,
+) -> Result<ResponseJson<ApiResponse<BranchUpstream>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+
+    let git = deployment.git();
+    git.set_upstream(&repo.path, &payload.branch, payload.upstream.as_deref())?;
+    let upstream = git.get_upstream(&repo.path, &payload.branch)?;
+    Ok(ResponseJson(ApiResponse::success(BranchUpstream {
+        branch: payload.branch,
+        upstream,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CherryStatusQuery {
+    pub upstream: String,
+    pub branch: String,
+}
+
+/// Commits of `branch` missing from `upstream`, marking those whose change is
+/// already upstream under another commit
+pub async fn get_cherry_status(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    Query(query): Query<CherryStatusQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<CherryStatusEntry>>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+
+    let entries = deployment
+        .git()
+        .get_cherry_status(&repo.path, &query.upstream, &query.branch)?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+/// Raw unified diff of a commit against its parent, for copying or `git apply`
+pub async fn get_commit_patch(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, sha)): Path<(Uuid, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+
+    let patch = deployment.git().get_commit_patch(&repo.path, &sha)?;
+    Ok(([(header::CONTENT_TYPE, "text/x-diff")], patch))
+}
+
 pub async fn get_repo_remotes(
     State(deployment): State<DeploymentImpl>,
     Path(repo_id): Path<Uuid>,
@@ -105,6 +196,45 @@ pub async fn get_repo_remotes(
     Ok(ResponseJson(ApiResponse::success(remotes)))
 }
 
+pub async fn get_repo_submodules(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<SubmoduleInfo>>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+
+    let submodules = deployment.git().list_submodules(&repo.path)?;
+    Ok(ResponseJson(ApiResponse::success(submodules)))
+}
+
+pub async fn get_repo_stashes(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<StashEntry>>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+
+    let stashes = deployment.git().list_stashes(&repo.path)?;
+    Ok(ResponseJson(ApiResponse::success(stashes)))
+}
+
+pub async fn get_repo_stash_diff(
+    State(deployment): State<DeploymentImpl>,
+    Path((repo_id, index)): Path<(Uuid, usize)>,
+) -> Result<ResponseJson<ApiResponse<Vec<Diff>>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+
+    let diffs = deployment.git().stash_diff(&repo.path, index)?;
+    Ok(ResponseJson(ApiResponse::success(diffs)))
+}
+
 pub async fn get_repos_batch(
     State(deployment): State<DeploymentImpl>,
     ResponseJson(payload): ResponseJson<BatchRepoRequest>,
@@ -157,12 +287,16 @@ pub async fn open_repo_in_editor(
     };
 
     match editor_config.open_file(&repo.path).await {
-        Ok(url) => {
+        Ok(opened) => {
             tracing::info!(
                 "Opened editor for repo {} at path: {}{}",
                 repo_id,
                 repo.path.to_string_lossy(),
-                if url.is_some() { " (remote mode)" } else { "" }
+                if opened.url.is_some() {
+                    " (remote mode)"
+                } else {
+                    ""
+                }
             );
 
             deployment
@@ -171,13 +305,15 @@ pub async fn open_repo_in_editor(
                     serde_json::json!({
                         "repo_id": repo_id.to_string(),
                         "editor_type": payload.as_ref().and_then(|req| req.editor_type.as_ref()),
-                        "remote_mode": url.is_some(),
+                        "opened_editor_type": opened.editor_type,
+                        "remote_mode": opened.url.is_some(),
                     }),
                 )
                 .await;
 
             Ok(ResponseJson(ApiResponse::success(OpenEditorResponse {
-                url,
+                url: opened.url,
+                editor_type: opened.editor_type,
             })))
         }
         Err(e) => {
@@ -255,7 +391,8 @@ pub async fn list_open_prs(
         None => deployment.git().get_default_remote(&repo.path)?,
     };
 
-    let git_host = match GitHostService::from_url(&remote.url) {
+    let gitlab_hosts = deployment.config().read().await.gitlab_hosts.clone();
+    let git_host = match GitHostService::from_url(&remote.url, &gitlab_hosts) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -286,6 +423,56 @@ pub async fn list_open_prs(
     }
 }
 
+/// Permissions the configured host credentials lack on the repo's remote, for
+/// warning at connect time rather than when a PR fails to open.
+pub async fn check_host_permissions(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    Query(query): Query<ListPrsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<HostPermission>, ListPrsError>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+
+    let remote = match query.remote {
+        Some(name) => GitRemote {
+            url: deployment.git().get_remote_url(&repo.path, &name)?,
+            name,
+        },
+        None => deployment.git().get_default_remote(&repo.path)?,
+    };
+
+    let gitlab_hosts = deployment.config().read().await.gitlab_hosts.clone();
+    let git_host = match GitHostService::from_url(&remote.url, &gitlab_hosts) {
+        Ok(host) => host,
+        Err(GitHostError::UnsupportedProvider) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                ListPrsError::UnsupportedProvider,
+            )));
+        }
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    };
+
+    match git_host.check_permissions(&repo.path, &remote.url).await {
+        Ok(missing) => Ok(ResponseJson(ApiResponse::success(missing))),
+        Err(GitHostError::CliNotInstalled { provider }) => Ok(ResponseJson(
+            ApiResponse::error_with_data(ListPrsError::CliNotInstalled { provider }),
+        )),
+        Err(GitHostError::AuthFailed(message)) => Ok(ResponseJson(ApiResponse::error_with_data(
+            ListPrsError::AuthFailed { message },
+        ))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to check host permissions for repo {}: {}",
+                repo_id,
+                e
+            );
+            Ok(ResponseJson(ApiResponse::error(&e.to_string())))
+        }
+    }
+}
+
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
         .route("/repos", get(get_repos).post(register_repo))
@@ -293,8 +480,27 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/repos/batch", post(get_repos_batch))
         .route("/repos/{repo_id}", get(get_repo).put(update_repo))
         .route("/repos/{repo_id}/branches", get(get_repo_branches))
+        .route(
+            "/repos/{repo_id}/branches/upstream",
+            get(get_branch_upstream).put(set_branch_upstream),
+        )
+        .route("/repos/{repo_id}/cherry", get(get_cherry_status))
+        .route(
+            "/repos/{repo_id}/commits/{sha}/patch",
+            get(get_commit_patch),
+        )
         .route("/repos/{repo_id}/remotes", get(get_repo_remotes))
+        .route("/repos/{repo_id}/submodules", get(get_repo_submodules))
+        .route("/repos/{repo_id}/stashes", get(get_repo_stashes))
+        .route(
+            "/repos/{repo_id}/stashes/{index}/diff",
+            get(get_repo_stash_diff),
+        )
         .route("/repos/{repo_id}/prs", get(list_open_prs))
+        .route(
+            "/repos/{repo_id}/host-permissions",
+            get(check_host_permissions),
+        )
         .route("/repos/{repo_id}/search", get(search_repo))
         .route("/repos/{repo_id}/open-editor", post(open_repo_in_editor))
 }
@@ -1,5 +1,6 @@
 pub mod queue;
 pub mod review;
+pub mod review_markers;
 
 use axum::{
     Extension, Json, Router,
@@ -20,7 +21,8 @@ use executors::{
     actions::{
         ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
     },
-    profile::ExecutorProfileId,
+    executors::{BaseAgentCapability, ExecutorError, TurnRef},
+    profile::{ExecutorConfigs, ExecutorProfileId},
 };
 use serde::Deserialize;
 use services::services::container::ContainerService;
@@ -92,6 +94,8 @@ pub struct CreateFollowUpAttempt {
     pub retry_process_id: Option<Uuid>,
     pub force_when_dirty: Option<bool>,
     pub perform_git_reset: Option<bool>,
+    /// Branch the conversation from this earlier turn instead of the latest
+    pub resume_from: Option<TurnRef>,
 }
 
 pub async fn follow_up(
@@ -188,12 +192,36 @@ pub async fn follow_up(
         .filter(|dir| !dir.is_empty())
         .cloned();
 
+    if payload.resume_from.is_some() {
+        if latest_agent_session_id.is_none() {
+            return Err(ApiError::BadRequest(
+                "Cannot resume from a turn: session has no previous agent turns".to_string(),
+            ));
+        }
+        let supports_turn_resume = ExecutorConfigs::get_cached()
+            .get_coding_agent(&executor_profile_id)
+            .is_some_and(|agent| {
+                agent
+                    .capabilities()
+                    .contains(&BaseAgentCapability::ResumeFromTurn)
+            });
+        if !supports_turn_resume {
+            return Err(ApiError::Executor(ExecutorError::FollowUpNotSupported(
+                format!(
+                    "{} cannot resume from a specific turn",
+                    executor_profile_id.executor
+                ),
+            )));
+        }
+    }
+
     let action_type = if let Some(agent_session_id) = latest_agent_session_id {
         ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
             prompt: prompt.clone(),
             session_id: agent_session_id,
             executor_profile_id: executor_profile_id.clone(),
             working_dir: working_dir.clone(),
+            resume_from: payload.resume_from,
         })
     } else {
         ExecutorActionType::CodingAgentInitialRequest(
@@ -236,6 +264,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_session))
         .route("/follow-up", post(follow_up))
         .route("/review", post(review::start_review))
+        .route(
+            "/review-markers",
+            get(review_markers::get_review_markers).put(review_markers::mark_reviewed),
+        )
+        .route(
+            "/diffs-since-review",
+            get(review_markers::get_diffs_since_review),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
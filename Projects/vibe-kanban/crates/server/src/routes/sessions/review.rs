@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
 use db::models::{
@@ -13,13 +13,14 @@ use executors::{
         ExecutorAction, ExecutorActionType,
         review::{RepoReviewContext as ExecutorRepoReviewContext, ReviewRequest as ReviewAction},
     },
-    executors::build_review_prompt,
+    executors::{ReviewPromptOptions, build_review_prompt_with_options},
     profile::ExecutorProfileId,
 };
 use serde::{Deserialize, Serialize};
 use services::services::container::ContainerService;
 use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
 
@@ -29,6 +30,14 @@ pub struct StartReviewRequest {
     pub additional_prompt: Option<String>,
     #[serde(default)]
     pub use_all_workspace_commits: bool,
+    /// Paths to review per repo id; repos without an entry are reviewed in full
+    #[serde(default)]
+    #[ts(optional)]
+    pub repo_paths: Option<HashMap<Uuid, Vec<String>>>,
+    /// Most files the agent should review across all repos
+    #[serde(default)]
+    #[ts(optional)]
+    pub max_files: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -97,7 +106,15 @@ pub async fn start_review(
         None
     };
 
-    let prompt = build_review_prompt(context.as_deref(), payload.additional_prompt.as_deref());
+    let options = ReviewPromptOptions {
+        repo_paths: payload.repo_paths.clone().unwrap_or_default(),
+        max_files: payload.max_files,
+    };
+    let prompt = build_review_prompt_with_options(
+        context.as_deref(),
+        payload.additional_prompt.as_deref(),
+        &options,
+    );
     let resumed_session = agent_session_id.is_some();
 
     let action = ExecutorAction::new(
@@ -0,0 +1,141 @@
+use std::path::PathBuf;
+
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    session::Session,
+    session_review_marker::SessionReviewMarker,
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use git::{Commit, DiffTarget};
+use serde::Deserialize;
+use ts_rs::TS;
+use utils::{diff::Diff, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct MarkReviewedRequest {
+    pub repo_id: Uuid,
+    /// Defaults to the current HEAD of the repo's worktree
+    pub commit_oid: Option<String>,
+}
+
+async fn load_workspace(
+    deployment: &DeploymentImpl,
+    session: &Session,
+) -> Result<(Workspace, PathBuf), ApiError> {
+    let workspace = Workspace::find_by_id(&deployment.db().pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+    let container_ref = workspace
+        .container_ref
+        .clone()
+        .ok_or_else(|| ApiError::BadRequest("No container ref".to_string()))?;
+    Ok((workspace, PathBuf::from(container_ref)))
+}
+
+pub async fn get_review_markers(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<SessionReviewMarker>>>, ApiError> {
+    let markers =
+        SessionReviewMarker::find_by_session_id(&deployment.db().pool, session.id).await?;
+    Ok(ResponseJson(ApiResponse::success(markers)))
+}
+
+pub async fn mark_reviewed(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<MarkReviewedRequest>,
+) -> Result<ResponseJson<ApiResponse<SessionReviewMarker>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let (workspace, workspace_path) = load_workspace(&deployment, &session).await?;
+
+    let repo = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id)
+        .await?
+        .into_iter()
+        .find(|r| r.id == payload.repo_id)
+        .ok_or_else(|| ApiError::BadRequest("Repo is not part of this workspace".to_string()))?;
+
+    let worktree_path = workspace_path.join(&repo.name);
+    let commit_oid = match payload.commit_oid {
+        Some(oid) => {
+            if !deployment.git().commit_exists(&worktree_path, &oid)? {
+                return Err(ApiError::BadRequest(format!(
+                    "Commit {oid} does not exist in {}",
+                    repo.name
+                )));
+            }
+            oid
+        }
+        None => deployment.git().get_head_info(&worktree_path)?.oid,
+    };
+
+    let marker = SessionReviewMarker::upsert(pool, session.id, repo.id, &commit_oid).await?;
+    Ok(ResponseJson(ApiResponse::success(marker)))
+}
+
+/// Diffs for every repo in the session's workspace, with files that changed
+/// since the stored review marker flagged. Repos that were never reviewed have
+/// every file flagged.
+pub async fn get_diffs_since_review(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Diff>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let (workspace, workspace_path) = load_workspace(&deployment, &session).await?;
+    let markers = SessionReviewMarker::find_by_session_id(pool, session.id).await?;
+    let repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+
+    let mut all_diffs = Vec::new();
+    for repo_with_branch in repos {
+        let repo = repo_with_branch.repo;
+        let worktree_path = workspace_path.join(&repo.name);
+        let since = markers
+            .iter()
+            .find(|m| m.repo_id == repo.id)
+            .map(|m| m.commit_oid.clone());
+
+        let diffs = tokio::task::spawn_blocking({
+            let git = deployment.git().clone();
+            let repo_path = repo.path.clone();
+            let branch = workspace.branch.clone();
+            let target_branch = repo_with_branch.target_branch.clone();
+            move || -> Result<Vec<Diff>, git::GitServiceError> {
+                let base_commit = git.get_base_commit(&repo_path, &branch, &target_branch)?;
+                let target = DiffTarget::Worktree {
+                    worktree_path: &worktree_path,
+                    base_commit: &base_commit,
+                };
+                // A marker that no longer parses flags every file, like a missing commit
+                let since = since.and_then(|oid| git2::Oid::from_str(&oid).ok());
+                match since {
+                    Some(oid) => git.get_diffs_since(target, &Commit::new(oid)),
+                    None => Ok(git
+                        .get_diffs(target, None)?
+                        .into_iter()
+                        .map(|mut d| {
+                            d.changed_since_review = true;
+                            d
+                        })
+                        .collect()),
+                }
+            }
+        })
+        .await
+        .map_err(std::io::Error::from)??;
+
+        all_diffs.extend(diffs.into_iter().map(|mut d| {
+            d.repo_id = Some(repo.id);
+            d
+        }));
+    }
+
+    Ok(ResponseJson(ApiResponse::success(all_diffs)))
+}
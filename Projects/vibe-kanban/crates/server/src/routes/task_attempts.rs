@@ -3,6 +3,7 @@ pub mod cursor_setup;
 pub mod gh_cli_setup;
 pub mod images;
 pub mod pr;
+pub mod secret_scan;
 pub mod util;
 pub mod workspace_summary;
 
@@ -26,7 +27,7 @@ use db::models::{
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
-    project::SearchResult,
+    project::{ProjectError, SearchResult},
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{Task, TaskRelationships, TaskStatus},
@@ -35,20 +36,24 @@ use db::models::{
 };
 use deployment::Deployment;
 use executors::{
-    actions::{
-        ExecutorAction, ExecutorActionType,
-        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
-    },
     executors::{CodingAgent, ExecutorError},
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
-use git::{ConflictOp, GitCliError, GitServiceError};
+use git::{
+    CommitMessageIssue, ConflictOp, FetchProgress, GitCliError, GitService, GitServiceError,
+    PushMode, validate_commit_message,
+};
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
-    container::ContainerService, file_search::SearchQuery, workspace_manager::WorkspaceManager,
+    config::EditorType,
+    container::{ContainerService, dev_server_actions},
+    events::fetch_progress_patch,
+    file_search::SearchQuery,
+    workspace_manager::WorkspaceManager,
 };
 use sqlx::Error as SqlxError;
+use tokio_util::sync::CancellationToken;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -63,6 +68,10 @@ pub struct RebaseTaskAttemptRequest {
     pub repo_id: Uuid,
     pub old_base_branch: Option<String>,
     pub new_base_branch: Option<String>,
+    /// Stash uncommitted changes before rebasing and restore them afterwards,
+    /// instead of refusing to rebase a dirty worktree
+    #[serde(default)]
+    pub auto_stash: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -201,10 +210,19 @@ pub async fn create_task_attempt(
         None
     };
 
+    let project = task
+        .parent_project(pool)
+        .await?
+        .ok_or(ProjectError::ProjectNotFound)?;
+    let branch_prefix = deployment
+        .container()
+        .branch_prefix_for_project(&project)
+        .await;
+
     let attempt_id = Uuid::new_v4();
     let git_branch_name = deployment
         .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
+        .git_branch_from_workspace(&attempt_id, &task.title, &branch_prefix)
         .await;
 
     let workspace = Workspace::create(
@@ -413,6 +431,12 @@ pub struct MergeTaskAttemptRequest {
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct PushTaskAttemptRequest {
     pub repo_id: Uuid,
+    /// For a force push, the commit the remote branch had when the user last saw
+    /// it (from the rejected push). The force push is refused if the remote moved
+    /// since; without it the remote branch is overwritten unconditionally.
+    #[serde(default)]
+    #[ts(optional)]
+    pub expected_remote_sha: Option<String>,
 }
 
 #[axum::debug_handler]
@@ -474,6 +498,9 @@ pub async fn merge_task_attempt(
         &workspace_repo.target_branch,
         &commit_message,
     )?;
+    // The target branch may be checked out in the main repo as well as the worktree
+    deployment.file_search_cache().mark_dirty(&worktree_path);
+    deployment.file_search_cache().mark_dirty(&repo.path);
 
     Merge::create_direct(
         pool,
@@ -549,16 +576,14 @@ pub async fn push_task_attempt_branch(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
-    match deployment
-        .git()
-        .push_to_remote(&worktree_path, &workspace.branch, false)
-    {
-        Ok(_) => Ok(ResponseJson(ApiResponse::success(()))),
-        Err(GitServiceError::GitCLI(GitCliError::PushRejected(_))) => Ok(ResponseJson(
-            ApiResponse::error_with_data(PushError::ForcePushRequired),
-        )),
-        Err(e) => Err(ApiError::GitService(e)),
-    }
+    push_response(
+        deployment
+            .git()
+            .push_to_remote(&worktree_path, &workspace.branch, false),
+        || PushError::ForcePushRequired {
+            remote_sha: remote_sha(&deployment, &worktree_path, &workspace.branch),
+        },
+    )
 }
 
 pub async fn force_push_task_attempt_branch(
@@ -584,28 +609,308 @@ pub async fn force_push_task_attempt_branch(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
+    // The lease protects commits pushed since the user saw the remote; when it
+    // is rejected they are shown the new remote commit and can retry with that
+    let mode = match request.expected_remote_sha {
+        Some(expected_sha) => PushMode::ForceWithLease { expected_sha },
+        None => PushMode::Force,
+    };
+    push_response(
+        deployment
+            .git()
+            .push_branch(&worktree_path, &workspace.branch, mode),
+        || PushError::RemoteChanged {
+            remote_sha: remote_sha(&deployment, &worktree_path, &workspace.branch),
+        },
+    )
+}
+
+/// The commit the remote branch points at now, for the UI to lease a force
+/// push against
+fn remote_sha(deployment: &DeploymentImpl, worktree_path: &Path, branch: &str) -> Option<String> {
     deployment
         .git()
-        .push_to_remote(&worktree_path, &workspace.branch, true)?;
-    Ok(ResponseJson(ApiResponse::success(())))
+        .remote_branch_sha(worktree_path, branch)
+        .inspect_err(|e| tracing::warn!("Failed to look up remote branch {branch}: {e}"))
+        .ok()
+        .flatten()
+}
+
+/// Report a rejected push as `rejected()` for the UI to act on; other failures
+/// are errors
+fn push_response(
+    result: Result<(), GitServiceError>,
+    rejected: impl FnOnce() -> PushError,
+) -> Result<ResponseJson<ApiResponse<(), PushError>>, ApiError> {
+    match result {
+        Ok(()) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(GitServiceError::GitCLI(GitCliError::PushRejected(_))) => {
+            Ok(ResponseJson(ApiResponse::error_with_data(rejected())))
+        }
+        Err(e) => Err(ApiError::GitService(e)),
+    }
 }
 
+/// Each variant carries the commit the remote branch had when the push was
+/// rejected, `None` if it couldn't be looked up
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
 pub enum PushError {
-    ForcePushRequired,
+    ForcePushRequired {
+        remote_sha: Option<String>,
+    },
+    /// The remote branch moved since the user last saw it, so the lease of a
+    /// force push was rejected
+    RemoteChanged {
+        remote_sha: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum CommitWorkspaceError {
+    InvalidMessage {
+        issues: Vec<CommitMessageIssue>,
+    },
+    /// `block_commits_with_secrets` is on and the changes appear to add secrets
+    SecretsDetected {
+        findings: Vec<secret_scan::RepoSecretFindings>,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CommitWorkspaceRequest {
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum RepoCommitOutcome {
+    Committed {
+        sha: String,
+    },
+    NoChanges,
+    Failed {
+        error: String,
+    },
+    /// An earlier repo failed, so this one was left untouched
+    NotAttempted,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct RepoCommitResult {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub outcome: RepoCommitOutcome,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct CommitWorkspaceResponse {
+    pub results: Vec<RepoCommitResult>,
+}
+
+/// Commit pending changes in every repo of the workspace with the same message.
+/// Stops at the first failure; the per-repo results say exactly which repos were
+/// committed so a partial commit can be recovered.
+pub async fn commit_workspace(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<CommitWorkspaceRequest>,
+) -> Result<ResponseJson<ApiResponse<CommitWorkspaceResponse, CommitWorkspaceError>>, ApiError> {
+    let message = request.message.trim();
+    if message.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Commit message cannot be empty".to_string(),
+        ));
+    }
+
+    let (rules, block_secrets) = {
+        let config = deployment.config().read().await;
+        (
+            config.commit_message_rules.clone(),
+            config.block_commits_with_secrets,
+        )
+    };
+    if let Err(issues) = validate_commit_message(message, &rules) {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            CommitWorkspaceError::InvalidMessage { issues },
+        )));
+    }
+    if block_secrets {
+        let findings = secret_scan::scan_workspace(&deployment, &workspace).await?;
+        if !findings.is_empty() {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                CommitWorkspaceError::SecretsDetected { findings },
+            )));
+        }
+    }
+
+    let repos =
+        WorkspaceRepo::find_repos_for_workspace(&deployment.db().pool, workspace.id).await?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let workspace_path = Path::new(&container_ref);
+
+    let results = commit_repos(deployment.git(), workspace_path, repos, message);
+    let mut committed = 0;
+    for result in &results {
+        if matches!(result.outcome, RepoCommitOutcome::Committed { .. }) {
+            deployment
+                .file_search_cache()
+                .mark_dirty(&workspace_path.join(&result.repo_name));
+            committed += 1;
+        }
+    }
+    let failed = results
+        .iter()
+        .any(|r| matches!(r.outcome, RepoCommitOutcome::Failed { .. }));
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_committed",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repos_committed": committed,
+                "failed": failed,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        CommitWorkspaceResponse { results },
+    )))
+}
+
+/// Commit each repo's worktree under `workspace_path` in order, leaving the
+/// repos after the first failure untouched.
+fn commit_repos(
+    git: &GitService,
+    workspace_path: &Path,
+    repos: Vec<Repo>,
+    message: &str,
+) -> Vec<RepoCommitResult> {
+    let mut results = Vec::with_capacity(repos.len());
+    let mut failed = false;
+    for repo in repos {
+        let outcome = if failed {
+            RepoCommitOutcome::NotAttempted
+        } else {
+            let worktree_path = workspace_path.join(&repo.name);
+            match git.commit(&worktree_path, message).and_then(|committed| {
+                committed
+                    .then(|| git.get_head_info(&worktree_path))
+                    .transpose()
+            }) {
+                Ok(Some(head)) => RepoCommitOutcome::Committed { sha: head.oid },
+                Ok(None) => RepoCommitOutcome::NoChanges,
+                Err(e) => {
+                    tracing::error!("Failed to commit repo '{}': {}", repo.name, e);
+                    failed = true;
+                    RepoCommitOutcome::Failed {
+                        error: e.to_string(),
+                    }
+                }
+            }
+        };
+        results.push(RepoCommitResult {
+            repo_id: repo.id,
+            repo_name: repo.name,
+            outcome,
+        });
+    }
+    results
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct HunkPatchRequest {
+    pub repo_id: Uuid,
+    /// Unified diff with the file header and the hunk(s) to (un)stage
+    pub patch: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum HunkPatchError {
+    /// The patch no longer matches the index, usually because the file changed
+    Rejected { hunk: String, message: String },
+}
+
+pub async fn stage_hunk(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<HunkPatchRequest>,
+) -> Result<ResponseJson<ApiResponse<(), HunkPatchError>>, ApiError> {
+    apply_hunk_patch(&deployment, &workspace, request, false).await
+}
+
+pub async fn unstage_hunk(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<HunkPatchRequest>,
+) -> Result<ResponseJson<ApiResponse<(), HunkPatchError>>, ApiError> {
+    apply_hunk_patch(&deployment, &workspace, request, true).await
+}
+
+async fn apply_hunk_patch(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    request: HunkPatchRequest,
+    unstage: bool,
+) -> Result<ResponseJson<ApiResponse<(), HunkPatchError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+
+    let result = if unstage {
+        deployment
+            .git()
+            .unstage_hunk(&worktree_path, &request.patch)
+    } else {
+        deployment.git().stage_hunk(&worktree_path, &request.patch)
+    };
+    match result {
+        Ok(()) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(GitServiceError::PatchRejected { hunk, message }) => Ok(ResponseJson(
+            ApiResponse::error_with_data(HunkPatchError::Rejected { hunk, message }),
+        )),
+        Err(e) => Err(ApiError::GitService(e)),
+    }
 }
 
 #[derive(serde::Deserialize, TS)]
 pub struct OpenEditorRequest {
     editor_type: Option<String>,
     file_path: Option<String>,
+    /// Open the worktree as a workspace: `true` for a fresh window, `false` to add it
+    /// to the focused one. Ignored when `file_path` is set.
+    #[serde(default)]
+    #[ts(optional)]
+    new_window: Option<bool>,
 }
 
 #[derive(Debug, Serialize, TS)]
 pub struct OpenEditorResponse {
     pub url: Option<String>,
+    /// The editor that handled the request, which may be a fallback
+    pub editor_type: EditorType,
 }
 
 pub async fn open_task_attempt_in_editor(
@@ -644,13 +949,24 @@ pub async fn open_task_attempt_in_editor(
         config.editor.with_override(editor_type_str)
     };
 
-    match editor_config.open_file(path.as_path()).await {
-        Ok(url) => {
+    let opened = match payload.new_window {
+        Some(new_window) if payload.file_path.is_none() => {
+            editor_config.open_workspace(&path, new_window).await
+        }
+        _ => editor_config.open_file(&path).await,
+    };
+
+    match opened {
+        Ok(opened) => {
             tracing::info!(
                 "Opened editor for task attempt {} at path: {}{}",
                 workspace.id,
                 path.display(),
-                if url.is_some() { " (remote mode)" } else { "" }
+                if opened.url.is_some() {
+                    " (remote mode)"
+                } else {
+                    ""
+                }
             );
 
             deployment
@@ -659,13 +975,15 @@ pub async fn open_task_attempt_in_editor(
                     serde_json::json!({
                         "workspace_id": workspace.id.to_string(),
                         "editor_type": payload.editor_type.as_ref(),
-                        "remote_mode": url.is_some(),
+                        "opened_editor_type": opened.editor_type,
+                        "remote_mode": opened.url.is_some(),
                     }),
                 )
                 .await;
 
             Ok(ResponseJson(ApiResponse::success(OpenEditorResponse {
-                url,
+                url: opened.url,
+                editor_type: opened.editor_type,
             })))
         }
         Err(e) => {
@@ -709,6 +1027,49 @@ pub struct RepoBranchStatus {
     pub status: BranchStatus,
 }
 
+/// Ahead/behind counts against a remote branch, fetching off the runtime. If the
+/// request is dropped, e.g. the client navigated away, the fetch is cancelled.
+/// Fetch progress is pushed to the event stream.
+async fn remote_branch_status(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+    repo: &Repo,
+    branch_name: &str,
+    base_branch_name: Option<&str>,
+) -> Result<(usize, usize), ApiError> {
+    let cancel = CancellationToken::new();
+    let _cancel_on_drop = cancel.clone().drop_guard();
+    let status = tokio::task::spawn_blocking({
+        let git = deployment.git().clone();
+        let msg_store = deployment.events().msg_store().clone();
+        let repo_id = repo.id;
+        let repo_path = repo.path.clone();
+        let branch_name = branch_name.to_string();
+        let base_branch_name = base_branch_name.map(str::to_string);
+        move || {
+            let report_progress = |progress: FetchProgress| {
+                msg_store.push_patch(fetch_progress_patch::replace(
+                    workspace_id,
+                    repo_id,
+                    &progress,
+                ));
+            };
+            let status = git.get_remote_branch_status(
+                &repo_path,
+                &branch_name,
+                base_branch_name.as_deref(),
+                Some(&cancel),
+                Some(&report_progress),
+            );
+            msg_store.push_patch(fetch_progress_patch::done(workspace_id, repo_id));
+            status
+        }
+    })
+    .await
+    .map_err(std::io::Error::from)??;
+    Ok(status)
+}
+
 pub async fn get_task_attempt_branch_status(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -801,11 +1162,14 @@ pub async fn get_task_attempt_branch_status(
                 (Some(a), Some(b))
             }
             BranchType::Remote => {
-                let (ahead, behind) = deployment.git().get_remote_branch_status(
-                    &repo.path,
+                let (ahead, behind) = remote_branch_status(
+                    &deployment,
+                    workspace.id,
+                    &repo,
                     &workspace.branch,
                     Some(&target_branch),
-                )?;
+                )
+                .await?;
                 (Some(ahead), Some(behind))
             }
         };
@@ -819,9 +1183,8 @@ pub async fn get_task_attempt_branch_status(
             ..
         })) = repo_merges.first()
         {
-            match deployment
-                .git()
-                .get_remote_branch_status(&repo.path, &workspace.branch, None)
+            match remote_branch_status(&deployment, workspace.id, &repo, &workspace.branch, None)
+                .await
             {
                 Ok((ahead, behind)) => (Some(ahead), Some(behind)),
                 Err(_) => (None, None),
@@ -884,10 +1247,21 @@ pub struct RenameBranchResponse {
 pub enum RenameBranchError {
     EmptyBranchName,
     InvalidBranchNameFormat,
+    /// The project requires task branches to live under `prefix/`
+    MissingBranchPrefix {
+        prefix: String,
+    },
     OpenPullRequest,
-    BranchAlreadyExists { repo_name: String },
-    RebaseInProgress { repo_name: String },
-    RenameFailed { repo_name: String, message: String },
+    BranchAlreadyExists {
+        repo_name: String,
+    },
+    RebaseInProgress {
+        repo_name: String,
+    },
+    RenameFailed {
+        repo_name: String,
+        message: String,
+    },
 }
 
 #[axum::debug_handler]
@@ -969,6 +1343,25 @@ pub async fn rename_branch(
 
     let pool = &deployment.db().pool;
 
+    let project = workspace
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?
+        .parent_project(pool)
+        .await?
+        .ok_or(ProjectError::ProjectNotFound)?;
+    if project.enforce_branch_prefix {
+        let prefix = deployment
+            .container()
+            .branch_prefix_for_project(&project)
+            .await;
+        if !git::branch_has_prefix(new_branch_name, &prefix) {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                RenameBranchError::MissingBranchPrefix { prefix },
+            )));
+        }
+    }
+
     // Fail if workspace has an open PR in any repo
     let merges = Merge::find_by_workspace_id(pool, workspace.id).await?;
     let has_open_pr = merges.into_iter().any(|merge| {
@@ -1144,6 +1537,7 @@ pub async fn rebase_task_attempt(
         &new_base_branch,
         &old_base_branch,
         &workspace.branch.clone(),
+        payload.auto_stash,
     );
     if let Err(e) = result {
         return match e {
@@ -1169,6 +1563,7 @@ pub async fn rebase_task_attempt(
             other => Err(ApiError::GitService(other)),
         };
     }
+    deployment.file_search_cache().mark_dirty(&worktree_path);
 
     deployment
         .track_if_analytics_allowed(
@@ -1259,12 +1654,9 @@ pub async fn start_dev_server(
     }
 
     let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
-    let repos_with_dev_script: Vec<_> = repos
-        .iter()
-        .filter(|r| r.dev_server_script.as_ref().is_some_and(|s| !s.is_empty()))
-        .collect();
+    let executor_actions = dev_server_actions(&repos);
 
-    if repos_with_dev_script.is_empty() {
+    if executor_actions.is_empty() {
         return Ok(ResponseJson(ApiResponse::error(
             "No dev server script configured for any repository in this workspace",
         )));
@@ -1286,17 +1678,7 @@ pub async fn start_dev_server(
     };
 
     let mut execution_processes = Vec::new();
-    for repo in repos_with_dev_script {
-        let executor_action = ExecutorAction::new(
-            ExecutorActionType::ScriptRequest(ScriptRequest {
-                script: repo.dev_server_script.clone().unwrap(),
-                language: ScriptRequestLanguage::Bash,
-                context: ScriptContext::DevServer,
-                working_dir: Some(repo.name.clone()),
-            }),
-            None,
-        );
-
+    for executor_action in executor_actions {
         let execution_process = deployment
             .container()
             .start_execution(
@@ -1606,6 +1988,18 @@ pub async fn search_workspace_files(
             }
         };
 
+    // Search the workspace's worktrees, which is what git operations mark dirty
+    let repos = match &workspace.container_ref {
+        Some(container_ref) => repos
+            .into_iter()
+            .map(|mut repo| {
+                repo.path = Path::new(container_ref).join(&repo.name);
+                repo
+            })
+            .collect(),
+        None => repos,
+    };
+
     match deployment
         .project()
         .search_files(
@@ -1767,12 +2161,20 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/diff/ws", get(stream_task_attempt_diff_ws))
         .route("/merge", post(merge_task_attempt))
         .route("/push", post(push_task_attempt_branch))
+        .route("/commit", post(commit_workspace))
+        .route("/stage-hunk", post(stage_hunk))
+        .route("/unstage-hunk", post(unstage_hunk))
+        .route(
+            "/secret-scan",
+            get(secret_scan::get_workspace_secret_findings),
+        )
         .route("/push/force", post(force_push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
         .route("/conflicts/abort", post(abort_conflicts_task_attempt))
         .route("/pr", post(pr::create_pr))
         .route("/pr/attach", post(pr::attach_existing_pr))
         .route("/pr/comments", get(pr::get_pr_comments))
+        .route("/pr/threads", get(pr::get_pr_review_threads))
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/children", get(get_task_attempt_children))
         .route("/stop", post(stop_task_attempt_execution))
@@ -1780,6 +2182,11 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/rename-branch", post(rename_branch))
         .route("/repos", get(get_task_attempt_repos))
         .route("/search", get(search_workspace_files))
+        .route(
+            "/diff-summary",
+            get(workspace_summary::get_workspace_diff_summary),
+        )
+        .route("/diffs", get(workspace_summary::get_workspace_diffs))
         .route("/first-message", get(get_first_user_message))
         .route("/mark-seen", put(mark_seen))
         .layer(from_fn_with_state(
@@ -1798,3 +2205,86 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     Router::new().nest("/task-attempts", task_attempts_router)
 }
+
+#[cfg(test)]
+mod tests {
+    use git::GitCli;
+
+    use super::*;
+
+    fn repo(name: &str) -> Repo {
+        Repo {
+            id: Uuid::new_v4(),
+            path: PathBuf::from(name),
+            name: name.to_string(),
+            display_name: name.to_string(),
+            setup_script: None,
+            cleanup_script: None,
+            copy_files: None,
+            parallel_setup_script: false,
+            dev_server_script: None,
+            default_target_branch: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn commit_repos_stops_at_the_first_failure() {
+        let workspace = tempfile::tempdir().unwrap();
+        let git = GitService::new();
+        for name in ["changed", "clean", "last"] {
+            let path = workspace.path().join(name);
+            git.initialize_repo_with_main_branch(&path).unwrap();
+            for (key, value) in [
+                ("user.name", "Test User"),
+                ("user.email", "test@example.com"),
+            ] {
+                GitCli::new().git(&path, ["config", key, value]).unwrap();
+            }
+        }
+        std::fs::write(workspace.path().join("changed/new.txt"), "content\n").unwrap();
+        std::fs::write(workspace.path().join("last/new.txt"), "content\n").unwrap();
+        // Not a git repository, so committing in it fails
+        std::fs::create_dir(workspace.path().join("broken")).unwrap();
+
+        let repos = ["changed", "clean", "broken", "last"].map(repo).into();
+        let results = commit_repos(&git, workspace.path(), repos, "Update files");
+        let outcomes: Vec<_> = results.iter().map(|r| &r.outcome).collect();
+
+        let head = git
+            .get_head_info(&workspace.path().join("changed"))
+            .unwrap();
+        assert!(matches!(outcomes[0], RepoCommitOutcome::Committed { sha } if *sha == head.oid));
+        assert!(matches!(outcomes[1], RepoCommitOutcome::NoChanges));
+        assert!(matches!(outcomes[2], RepoCommitOutcome::Failed { .. }));
+        assert!(matches!(outcomes[3], RepoCommitOutcome::NotAttempted));
+        assert!(workspace.path().join("last/new.txt").exists());
+        assert!(
+            GitCli::new()
+                .has_changes(&workspace.path().join("last"))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn force_push_lease_rejection_is_reported_as_remote_changed() {
+        let rejected = GitServiceError::GitCLI(GitCliError::PushRejected(
+            "! [rejected] main -> main (stale info)".to_string(),
+        ));
+        let remote_changed = || PushError::RemoteChanged {
+            remote_sha: Some("abc123".to_string()),
+        };
+        let ResponseJson(response) = push_response(Err(rejected), remote_changed).unwrap();
+        let body = serde_json::to_value(&response).unwrap();
+        assert_eq!(body["success"], false);
+        assert_eq!(body["error_data"]["type"], "remote_changed");
+        assert_eq!(body["error_data"]["remote_sha"], "abc123");
+
+        let failed = GitServiceError::GitCLI(GitCliError::AuthFailed("denied".to_string()));
+        assert!(push_response(Err(failed), remote_changed).is_err());
+
+        let ResponseJson(response) = push_response(Ok(()), remote_changed).unwrap();
+        assert!(response.is_success());
+    }
+}
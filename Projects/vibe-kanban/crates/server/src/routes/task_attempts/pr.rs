@@ -25,9 +25,10 @@ use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
     git_host::{
-        self, CreatePrRequest, GitHostError, GitHostProvider, ProviderKind, UnifiedPrComment,
-        github::GhCli,
+        self, CreatePrRequest, GitHostError, GitHostProvider, HostPermission, ProviderKind,
+        ReviewThread, UnifiedPrComment, github::GhCli,
     },
+    secret_scan::SecretFinding,
 };
 use ts_rs::TS;
 use utils::response::ApiResponse;
@@ -50,12 +51,26 @@ pub struct CreatePrApiRequest {
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
 pub enum PrError {
-    CliNotInstalled { provider: ProviderKind },
-    CliNotLoggedIn { provider: ProviderKind },
+    CliNotInstalled {
+        provider: ProviderKind,
+    },
+    CliNotLoggedIn {
+        provider: ProviderKind,
+    },
     GitCliNotLoggedIn,
     GitCliNotInstalled,
-    TargetBranchNotFound { branch: String },
+    TargetBranchNotFound {
+        branch: String,
+    },
     UnsupportedProvider,
+    /// `block_commits_with_secrets` is on and the changes appear to add secrets
+    SecretsDetected {
+        findings: Vec<SecretFinding>,
+    },
+    /// The host credentials can't open a PR on the target repository
+    MissingPermissions {
+        missing: Vec<HostPermission>,
+    },
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -76,6 +91,11 @@ pub struct PrCommentsResponse {
     pub comments: Vec<UnifiedPrComment>,
 }
 
+#[derive(Debug, Serialize, TS)]
+pub struct PrReviewThreadsResponse {
+    pub threads: Vec<ReviewThread>,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
@@ -101,7 +121,7 @@ Analyze the changes in this branch and write:
    - Any important implementation details
    - At the end, include a note: "This PR was written using [Vibe Kanban](https://vibekanban.com)"
 
-Use the appropriate CLI tool to update the PR (gh pr edit for GitHub, az repos pr update for Azure DevOps)."#;
+Use the appropriate CLI tool to update the PR (gh pr edit for GitHub, az repos pr update for Azure DevOps, glab mr update for GitLab)."#;
 
 async fn trigger_pr_description_follow_up(
     deployment: &DeploymentImpl,
@@ -170,6 +190,7 @@ async fn trigger_pr_description_follow_up(
             session_id: agent_session_id,
             executor_profile_id: executor_profile_id.clone(),
             working_dir: working_dir.clone(),
+            resume_from: None,
         })
     } else {
         ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
@@ -224,6 +245,22 @@ pub async fn create_pr(
     let workspace_path = PathBuf::from(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
+    if deployment.config().read().await.block_commits_with_secrets {
+        let findings = super::secret_scan::scan_repo(
+            &deployment,
+            &workspace,
+            &workspace_path,
+            &repo,
+            &target_branch,
+        )
+        .await?;
+        if !findings.is_empty() {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                PrError::SecretsDetected { findings },
+            )));
+        }
+    }
+
     let git = deployment.git();
     let push_remote = git.resolve_remote_for_branch(&repo_path, &workspace.branch)?;
 
@@ -262,6 +299,41 @@ pub async fn create_pr(
         Ok(true) => {}
     }
 
+    let gitlab_hosts = deployment.config().read().await.gitlab_hosts.clone();
+    let git_host = match git_host::GitHostService::from_url(&target_remote.url, &gitlab_hosts) {
+        Ok(host) => host,
+        Err(GitHostError::UnsupportedProvider) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                PrError::UnsupportedProvider,
+            )));
+        }
+        Err(GitHostError::CliNotInstalled { provider }) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                PrError::CliNotInstalled { provider },
+            )));
+        }
+        Err(e) => return Err(ApiError::GitHost(e)),
+    };
+
+    // Find out before pushing; a failed check shouldn't block the attempt
+    match git_host
+        .check_permissions(&repo_path, &target_remote.url)
+        .await
+    {
+        Ok(mut missing) => {
+            // A PR from a fork only needs to read the target repository
+            if push_remote.url != target_remote.url {
+                missing.retain(|p| *p != HostPermission::PullRequestWrite);
+            }
+            if !missing.is_empty() {
+                return Ok(ResponseJson(ApiResponse::error_with_data(
+                    PrError::MissingPermissions { missing },
+                )));
+            }
+        }
+        Err(e) => tracing::warn!("Could not check host permissions before creating PR: {}", e),
+    }
+
     if let Err(e) = git.push_to_remote(&worktree_path, &workspace.branch, false) {
         tracing::error!("Failed to push branch to remote: {}", e);
         match e {
@@ -279,21 +351,6 @@ pub async fn create_pr(
         }
     }
 
-    let git_host = match git_host::GitHostService::from_url(&target_remote.url) {
-        Ok(host) => host,
-        Err(GitHostError::UnsupportedProvider) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                PrError::UnsupportedProvider,
-            )));
-        }
-        Err(GitHostError::CliNotInstalled { provider }) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                PrError::CliNotInstalled { provider },
-            )));
-        }
-        Err(e) => return Err(ApiError::GitHost(e)),
-    };
-
     let provider = git_host.provider_kind();
 
     // Create the PR
@@ -416,7 +473,8 @@ pub async fn attach_existing_pr(
     let git = deployment.git();
     let remote = git.resolve_remote_for_branch(&repo.path, &workspace_repo.target_branch)?;
 
-    let git_host = match git_host::GitHostService::from_url(&remote.url) {
+    let gitlab_hosts = deployment.config().read().await.gitlab_hosts.clone();
+    let git_host = match git_host::GitHostService::from_url(&remote.url, &gitlab_hosts) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -500,53 +558,87 @@ pub async fn attach_existing_pr(
     }
 }
 
-pub async fn get_pr_comments(
-    Extension(workspace): Extension<Workspace>,
-    State(deployment): State<DeploymentImpl>,
-    Query(query): Query<GetPrCommentsQuery>,
-) -> Result<ResponseJson<ApiResponse<PrCommentsResponse, GetPrCommentsError>>, ApiError> {
+/// The attached PR for one repo of a workspace, plus the host it lives on.
+struct AttachedPr {
+    repo: Repo,
+    remote: GitRemote,
+    pr_number: i64,
+    git_host: git_host::GitHostService,
+}
+
+async fn find_attached_pr(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<Result<AttachedPr, GetPrCommentsError>, ApiError> {
     let pool = &deployment.db().pool;
 
     // Look up the specific repo using the multi-repo pattern
-    let workspace_repo =
-        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
+    let workspace_repo = WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
 
     let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
         .await?
         .ok_or(RepoError::NotFound)?;
 
     // Find the merge/PR for this specific repo
-    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, query.repo_id).await?;
+    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo_id).await?;
 
     // Ensure there's an attached PR for this repo
     let pr_info = match merges.into_iter().next() {
         Some(Merge::Pr(pr_merge)) => pr_merge.pr_info,
-        _ => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                GetPrCommentsError::NoPrAttached,
-            )));
-        }
+        _ => return Ok(Err(GetPrCommentsError::NoPrAttached)),
     };
 
     let git = deployment.git();
     let remote = git.resolve_remote_for_branch(&repo.path, &workspace_repo.target_branch)?;
 
-    let git_host = match git_host::GitHostService::from_url(&remote.url) {
+    let gitlab_hosts = deployment.config().read().await.gitlab_hosts.clone();
+    let git_host = match git_host::GitHostService::from_url(&remote.url, &gitlab_hosts) {
         Ok(host) => host,
         Err(GitHostError::CliNotInstalled { provider }) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                GetPrCommentsError::CliNotInstalled { provider },
-            )));
+            return Ok(Err(GetPrCommentsError::CliNotInstalled { provider }));
         }
         Err(e) => return Err(ApiError::GitHost(e)),
     };
 
-    let provider = git_host.provider_kind();
+    Ok(Ok(AttachedPr {
+        repo,
+        remote,
+        pr_number: pr_info.number,
+        git_host,
+    }))
+}
 
-    match git_host
-        .get_pr_comments(&repo.path, &remote.url, pr_info.number)
+/// Maps host errors the UI can act on to [`GetPrCommentsError`].
+fn pr_comments_error(
+    e: GitHostError,
+    provider: ProviderKind,
+) -> Result<GetPrCommentsError, ApiError> {
+    match e {
+        GitHostError::CliNotInstalled { provider } => {
+            Ok(GetPrCommentsError::CliNotInstalled { provider })
+        }
+        GitHostError::AuthFailed(_) => Ok(GetPrCommentsError::CliNotLoggedIn { provider }),
+        _ => Err(ApiError::GitHost(e)),
+    }
+}
+
+pub async fn get_pr_comments(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<GetPrCommentsQuery>,
+) -> Result<ResponseJson<ApiResponse<PrCommentsResponse, GetPrCommentsError>>, ApiError> {
+    let pr = match find_attached_pr(&deployment, &workspace, query.repo_id).await? {
+        Ok(pr) => pr,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error_with_data(e))),
+    };
+    let provider = pr.git_host.provider_kind();
+
+    match pr
+        .git_host
+        .get_pr_comments(&pr.repo.path, &pr.remote.url, pr.pr_number)
         .await
     {
         Ok(comments) => Ok(ResponseJson(ApiResponse::success(PrCommentsResponse {
@@ -556,20 +648,45 @@ pub async fn get_pr_comments(
             tracing::error!(
                 "Failed to fetch PR comments for attempt {}, PR #{}: {}",
                 workspace.id,
-                pr_info.number,
+                pr.pr_number,
                 e
             );
-            match &e {
-                GitHostError::CliNotInstalled { provider } => Ok(ResponseJson(
-                    ApiResponse::error_with_data(GetPrCommentsError::CliNotInstalled {
-                        provider: *provider,
-                    }),
-                )),
-                GitHostError::AuthFailed(_) => Ok(ResponseJson(ApiResponse::error_with_data(
-                    GetPrCommentsError::CliNotLoggedIn { provider },
-                ))),
-                _ => Err(ApiError::GitHost(e)),
-            }
+            Ok(ResponseJson(ApiResponse::error_with_data(
+                pr_comments_error(e, provider)?,
+            )))
+        }
+    }
+}
+
+pub async fn get_pr_review_threads(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<GetPrCommentsQuery>,
+) -> Result<ResponseJson<ApiResponse<PrReviewThreadsResponse, GetPrCommentsError>>, ApiError> {
+    let pr = match find_attached_pr(&deployment, &workspace, query.repo_id).await? {
+        Ok(pr) => pr,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error_with_data(e))),
+    };
+    let provider = pr.git_host.provider_kind();
+
+    match pr
+        .git_host
+        .list_review_threads(&pr.repo.path, &pr.remote.url, pr.pr_number)
+        .await
+    {
+        Ok(threads) => Ok(ResponseJson(ApiResponse::success(
+            PrReviewThreadsResponse { threads },
+        ))),
+        Err(e) => {
+            tracing::error!(
+                "Failed to fetch PR review threads for attempt {}, PR #{}: {}",
+                workspace.id,
+                pr.pr_number,
+                e
+            );
+            Ok(ResponseJson(ApiResponse::error_with_data(
+                pr_comments_error(e, provider)?,
+            )))
         }
     }
 }
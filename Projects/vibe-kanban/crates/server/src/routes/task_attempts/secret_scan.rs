@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use axum::{Extension, extract::State, response::Json as ResponseJson};
+use db::models::{repo::Repo, workspace::Workspace, workspace_repo::WorkspaceRepo};
+use deployment::Deployment;
+use git::{DiffTarget, GitServiceError};
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    secret_scan::{SecretFinding, scan_diffs},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct RepoSecretFindings {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub findings: Vec<SecretFinding>,
+}
+
+/// Likely secrets added by the workspace's pending changes, committed or not,
+/// relative to each repo's target branch. Advisory unless
+/// `block_commits_with_secrets` is set in the config.
+pub async fn get_workspace_secret_findings(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoSecretFindings>>>, ApiError> {
+    let findings = scan_workspace(&deployment, &workspace).await?;
+    Ok(ResponseJson(ApiResponse::success(findings)))
+}
+
+/// Scan every repo of the workspace, returning only repos with findings.
+pub async fn scan_workspace(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<Vec<RepoSecretFindings>, ApiError> {
+    let pool = &deployment.db().pool;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+
+    let mut results = Vec::new();
+    for repo_with_branch in
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?
+    {
+        let repo = repo_with_branch.repo;
+        let findings = scan_repo(
+            deployment,
+            workspace,
+            Path::new(&container_ref),
+            &repo,
+            &repo_with_branch.target_branch,
+        )
+        .await?;
+        if !findings.is_empty() {
+            results.push(RepoSecretFindings {
+                repo_id: repo.id,
+                repo_name: repo.name,
+                findings,
+            });
+        }
+    }
+    Ok(results)
+}
+
+pub async fn scan_repo(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    workspace_path: &Path,
+    repo: &Repo,
+    target_branch: &str,
+) -> Result<Vec<SecretFinding>, ApiError> {
+    let git = deployment.git().clone();
+    let repo_path = repo.path.clone();
+    let worktree_path = workspace_path.join(&repo.name);
+    let branch = workspace.branch.clone();
+    let target_branch = target_branch.to_string();
+
+    let findings =
+        tokio::task::spawn_blocking(move || -> Result<Vec<SecretFinding>, GitServiceError> {
+            let base_commit = git.get_base_commit(&repo_path, &branch, &target_branch)?;
+            let diffs = git.get_diffs(
+                DiffTarget::Worktree {
+                    worktree_path: &worktree_path,
+                    base_commit: &base_commit,
+                },
+                None,
+            )?;
+            Ok(scan_diffs(&diffs))
+        })
+        .await
+        .map_err(std::io::Error::from)??;
+    Ok(findings)
+}
@@ -67,7 +67,7 @@ pub async fn restore_worktrees_to_process(
 
         // Reset this repo's worktree
         if let Some(oid) = target_oid {
-            deployment.git().reconcile_worktree_to_commit(
+            let outcome = deployment.git().reconcile_worktree_to_commit(
                 &worktree_path,
                 &oid,
                 WorktreeResetOptions::new(
@@ -77,6 +77,9 @@ pub async fn restore_worktrees_to_process(
                     perform_git_reset,
                 ),
             );
+            if outcome.applied {
+                deployment.file_search_cache().mark_dirty(&worktree_path);
+            }
         }
     }
 
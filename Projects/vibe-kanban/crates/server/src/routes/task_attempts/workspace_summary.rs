@@ -1,6 +1,10 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use axum::{Json, extract::State, response::Json as ResponseJson};
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
 use db::models::{
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessStatus},
@@ -9,10 +13,10 @@ use db::models::{
     workspace_repo::WorkspaceRepo,
 };
 use deployment::Deployment;
-use git::DiffTarget;
+use git::{DiffSizeLimits, DiffTarget, GitServiceError};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{diff::DiffResult, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
@@ -63,6 +67,46 @@ pub struct DiffStats {
     pub lines_removed: usize,
 }
 
+impl DiffStats {
+    fn add(&mut self, other: &DiffStats) {
+        self.files_changed += other.files_changed;
+        self.lines_added += other.lines_added;
+        self.lines_removed += other.lines_removed;
+    }
+}
+
+/// Diff stats for one repository of a workspace
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RepoDiffStats {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub stats: DiffStats,
+}
+
+/// Diff stats for a whole workspace: the total plus a per-repo breakdown
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct WorkspaceDiffSummary {
+    pub total: DiffStats,
+    /// Number of repos with at least one changed file
+    pub repos_changed: usize,
+    pub repos: Vec<RepoDiffStats>,
+}
+
+impl WorkspaceDiffSummary {
+    pub fn from_repos(repos: Vec<RepoDiffStats>) -> Self {
+        let mut total = DiffStats::default();
+        for repo in &repos {
+            total.add(&repo.stats);
+        }
+        let repos_changed = repos.iter().filter(|r| r.stats.files_changed > 0).count();
+        Self {
+            total,
+            repos_changed,
+            repos,
+        }
+    }
+}
+
 /// Fetch summary information for workspaces filtered by archived status.
 /// This endpoint returns data that cannot be efficiently included in the streaming endpoint.
 #[axum::debug_handler]
@@ -117,10 +161,10 @@ pub async fn get_workspace_summaries(
             let deployment = deployment.clone();
             async move {
                 if workspace.container_ref.is_some() {
-                    compute_workspace_diff_stats(&deployment, &workspace)
+                    compute_workspace_diff_summary(&deployment, &workspace)
                         .await
                         .ok()
-                        .map(|stats| (workspace.id, stats))
+                        .map(|summary| (workspace.id, summary.total))
                 } else {
                     None
                 }
@@ -164,11 +208,20 @@ pub async fn get_workspace_summaries(
     )))
 }
 
-/// Compute diff stats for a workspace.
-async fn compute_workspace_diff_stats(
+/// Diff stats for a workspace, totalled and broken down per repo.
+pub async fn get_workspace_diff_summary(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceDiffSummary>>, ApiError> {
+    let summary = compute_workspace_diff_summary(&deployment, &workspace).await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+/// Compute diff stats for each repo of a workspace.
+async fn compute_workspace_diff_summary(
     deployment: &DeploymentImpl,
     workspace: &Workspace,
-) -> Result<DiffStats, ApiError> {
+) -> Result<WorkspaceDiffSummary, ApiError> {
     let pool = &deployment.db().pool;
 
     let container_ref = workspace
@@ -179,7 +232,7 @@ async fn compute_workspace_diff_stats(
     let workspace_repos =
         WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
 
-    let mut stats = DiffStats::default();
+    let mut repos = Vec::with_capacity(workspace_repos.len());
 
     for repo_with_branch in workspace_repos {
         let worktree_path = PathBuf::from(container_ref).join(&repo_with_branch.repo.name);
@@ -200,30 +253,122 @@ async fn compute_workspace_diff_stats(
             _ => continue,
         };
 
-        // Get diffs
-        let diffs_result = tokio::task::spawn_blocking({
+        // Count changes without loading file contents
+        let stats_result = tokio::task::spawn_blocking({
             let git = deployment.git().clone();
             let worktree = worktree_path.clone();
-            move || {
-                git.get_diffs(
-                    DiffTarget::Worktree {
-                        worktree_path: &worktree,
-                        base_commit: &base_commit,
-                    },
-                    None,
-                )
-            }
+            move || git.get_diff_stats(&worktree, &base_commit)
         })
         .await;
 
-        if let Ok(Ok(diffs)) = diffs_result {
-            for diff in diffs {
-                stats.files_changed += 1;
-                stats.lines_added += diff.additions.unwrap_or(0);
-                stats.lines_removed += diff.deletions.unwrap_or(0);
+        let stats = match stats_result {
+            Ok(Ok(numstat)) => DiffStats {
+                files_changed: numstat.files_changed,
+                lines_added: numstat.additions,
+                lines_removed: numstat.deletions,
+            },
+            _ => DiffStats::default(),
+        };
+
+        repos.push(RepoDiffStats {
+            repo_id: repo_with_branch.repo.id,
+            repo_name: repo_with_branch.repo.name.clone(),
+            stats,
+        });
+    }
+
+    Ok(WorkspaceDiffSummary::from_repos(repos))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceDiffsQuery {
+    /// Skip the size guard and always return per-file diffs
+    #[serde(default)]
+    pub full: bool,
+    pub max_files: Option<usize>,
+    pub max_changed_lines: Option<usize>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RepoDiffResult {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub result: DiffResult,
+}
+
+/// Per-repo diffs, replaced by a summary for repos whose changeset is too large
+/// to list unless `full` is set.
+pub async fn get_workspace_diffs(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<WorkspaceDiffsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoDiffResult>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let container_ref = workspace
+        .container_ref
+        .as_ref()
+        .ok_or_else(|| ApiError::BadRequest("No container ref".to_string()))?;
+
+    let defaults = DiffSizeLimits::default();
+    let limits = DiffSizeLimits {
+        max_files: query.max_files.unwrap_or(defaults.max_files),
+        max_changed_lines: query
+            .max_changed_lines
+            .unwrap_or(defaults.max_changed_lines),
+        ..defaults
+    };
+
+    let workspace_repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+    let mut results = Vec::with_capacity(workspace_repos.len());
+
+    for repo_with_branch in workspace_repos {
+        let repo = repo_with_branch.repo;
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+        let full = query.full;
+
+        let result = tokio::task::spawn_blocking({
+            let git = deployment.git().clone();
+            let repo_path = repo.path.clone();
+            let branch = workspace.branch.clone();
+            let target_branch = repo_with_branch.target_branch;
+            move || -> Result<DiffResult, GitServiceError> {
+                let base_commit = git.get_base_commit(&repo_path, &branch, &target_branch)?;
+                let target = DiffTarget::Worktree {
+                    worktree_path: &worktree_path,
+                    base_commit: &base_commit,
+                };
+                if full {
+                    Ok(DiffResult::Full {
+                        diffs: git.get_diffs(target, None)?,
+                    })
+                } else {
+                    git.get_diffs_or_summary(target, None, &limits)
+                }
             }
-        }
+        })
+        .await
+        .map_err(std::io::Error::from)??;
+
+        let result = match result {
+            DiffResult::Full { diffs } => DiffResult::Full {
+                diffs: diffs
+                    .into_iter()
+                    .map(|mut d| {
+                        d.repo_id = Some(repo.id);
+                        d
+                    })
+                    .collect(),
+            },
+            summary => summary,
+        };
+
+        results.push(RepoDiffResult {
+            repo_id: repo.id,
+            repo_name: repo.name,
+            result,
+        });
     }
 
-    Ok(stats)
+    Ok(ResponseJson(ApiResponse::success(results)))
 }
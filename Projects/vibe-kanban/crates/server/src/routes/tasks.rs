@@ -14,6 +14,7 @@ use axum::{
 };
 use db::models::{
     image::TaskImage,
+    project::ProjectError,
     repo::{Repo, RepoError},
     task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
     workspace::{CreateWorkspace, Workspace},
@@ -175,10 +176,19 @@ pub async fn create_task_and_start(
         )
         .await;
 
+    let project = task
+        .parent_project(pool)
+        .await?
+        .ok_or(ProjectError::ProjectNotFound)?;
+    let branch_prefix = deployment
+        .container()
+        .branch_prefix_for_project(&project)
+        .await;
+
     let attempt_id = Uuid::new_v4();
     let git_branch_name = deployment
         .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
+        .git_branch_from_workspace(&attempt_id, &task.title, &branch_prefix)
         .await;
 
     // Compute agent_working_dir based on repo count:
@@ -12,6 +12,7 @@ use axum::{
 use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
 use db::models::{workspace::Workspace, workspace_repo::WorkspaceRepo};
 use deployment::Deployment;
+use executors::env::ExecutionEnv;
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -60,6 +61,7 @@ pub async fn terminal_ws(
 
     let container_ref = attempt
         .container_ref
+        .clone()
         .ok_or_else(|| ApiError::BadRequest("Attempt has no workspace directory".to_string()))?;
 
     let base_dir = PathBuf::from(&container_ref);
@@ -87,8 +89,10 @@ pub async fn terminal_ws(
         }
     }
 
+    let env = deployment.workspace_execution_env(&attempt).await?;
+
     Ok(ws.on_upgrade(move |socket| {
-        handle_terminal_ws(socket, deployment, working_dir, query.cols, query.rows)
+        handle_terminal_ws(socket, deployment, working_dir, env, query.cols, query.rows)
     }))
 }
 
@@ -96,12 +100,13 @@ async fn handle_terminal_ws(
     socket: WebSocket,
     deployment: DeploymentImpl,
     working_dir: PathBuf,
+    env: ExecutionEnv,
     cols: u16,
     rows: u16,
 ) {
     let (session_id, mut output_rx) = match deployment
         .pty()
-        .create_session(working_dir, cols, rows)
+        .spawn_shell_in(&working_dir, &env, cols, rows)
         .await
     {
         Ok(result) => result,
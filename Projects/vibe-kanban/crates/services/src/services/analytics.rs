@@ -1,9 +1,11 @@
 use std::{
     collections::hash_map::DefaultHasher,
     hash::{Hash, Hasher},
+    sync::Arc,
     time::Duration,
 };
 
+use async_trait::async_trait;
 use os_info;
 use serde_json::{Value, json};
 
@@ -13,32 +15,212 @@ pub struct AnalyticsContext {
     pub analytics_service: AnalyticsService,
 }
 
+/// Where analytics events are delivered.
+#[derive(Debug, Clone)]
+pub enum AnalyticsBackend {
+    /// The hosted PostHog instance (the default when its key is configured)
+    PostHog {
+        api_key: String,
+        api_endpoint: String,
+    },
+    /// A self-hosted collector that receives each event as a JSON POST
+    SelfHosted {
+        endpoint: String,
+        token: Option<String>,
+    },
+    /// Events are accepted and dropped
+    Disabled,
+}
+
 #[derive(Debug, Clone)]
 pub struct AnalyticsConfig {
-    pub posthog_api_key: String,
-    pub posthog_api_endpoint: String,
+    pub backend: AnalyticsBackend,
+}
+
+/// Reads a build-time value, falling back to the runtime environment.
+fn env_value(build_time: Option<&'static str>, name: &str) -> Option<String> {
+    build_time
+        .map(|s| s.to_string())
+        .or_else(|| std::env::var(name).ok())
+        .filter(|s| !s.trim().is_empty())
 }
 
 impl AnalyticsConfig {
+    /// Selects the backend from `VK_ANALYTICS_BACKEND` (`posthog`, `self_hosted`
+    /// or `disabled`), baked in at build time or read at runtime. Without it,
+    /// PostHog is used if its key and endpoint are set.
     pub fn new() -> Option<Self> {
-        let api_key = option_env!("POSTHOG_API_KEY")
-            .map(|s| s.to_string())
-            .or_else(|| std::env::var("POSTHOG_API_KEY").ok())?;
-        let api_endpoint = option_env!("POSTHOG_API_ENDPOINT")
-            .map(|s| s.to_string())
-            .or_else(|| std::env::var("POSTHOG_API_ENDPOINT").ok())?;
-
-        Some(Self {
-            posthog_api_key: api_key,
-            posthog_api_endpoint: api_endpoint,
+        let backend = env_value(option_env!("VK_ANALYTICS_BACKEND"), "VK_ANALYTICS_BACKEND");
+
+        let backend = match backend.as_deref().map(str::trim) {
+            None | Some("posthog") => Self::posthog_backend()?,
+            Some("self_hosted") => {
+                let Some(endpoint) = env_value(
+                    option_env!("VK_ANALYTICS_ENDPOINT"),
+                    "VK_ANALYTICS_ENDPOINT",
+                ) else {
+                    tracing::warn!(
+                        "VK_ANALYTICS_BACKEND=self_hosted but VK_ANALYTICS_ENDPOINT is not set; analytics disabled"
+                    );
+                    return None;
+                };
+                AnalyticsBackend::SelfHosted {
+                    endpoint,
+                    token: env_value(option_env!("VK_ANALYTICS_TOKEN"), "VK_ANALYTICS_TOKEN"),
+                }
+            }
+            Some("disabled") => AnalyticsBackend::Disabled,
+            Some(other) => {
+                tracing::warn!(
+                    "Unknown VK_ANALYTICS_BACKEND '{}'; analytics disabled",
+                    other
+                );
+                return None;
+            }
+        };
+
+        Some(Self { backend })
+    }
+
+    fn posthog_backend() -> Option<AnalyticsBackend> {
+        let api_key = env_value(option_env!("POSTHOG_API_KEY"), "POSTHOG_API_KEY")?;
+        let api_endpoint = env_value(option_env!("POSTHOG_API_ENDPOINT"), "POSTHOG_API_ENDPOINT")?;
+        Some(AnalyticsBackend::PostHog {
+            api_key,
+            api_endpoint,
         })
     }
 }
 
+/// A single event, already enriched with the backend-independent properties.
+#[derive(Debug, Clone)]
+pub struct AnalyticsEvent {
+    pub name: String,
+    pub distinct_id: String,
+    /// Event properties, or person properties for `$identify`
+    pub properties: Value,
+}
+
+impl AnalyticsEvent {
+    pub fn is_identify(&self) -> bool {
+        self.name == "$identify"
+    }
+}
+
+/// Delivers events to an analytics backend.
+#[async_trait]
+pub trait AnalyticsTransport: Send + Sync + std::fmt::Debug {
+    async fn send(&self, event: &AnalyticsEvent) -> Result<(), String>;
+}
+
+#[derive(Debug)]
+pub struct PostHogTransport {
+    api_key: String,
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+impl PostHogTransport {
+    pub fn new(api_key: String, api_endpoint: &str, client: reqwest::Client) -> Self {
+        Self {
+            api_key,
+            endpoint: format!("{}/capture/", api_endpoint.trim_end_matches('/')),
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl AnalyticsTransport for PostHogTransport {
+    async fn send(&self, event: &AnalyticsEvent) -> Result<(), String> {
+        let mut payload = json!({
+            "api_key": self.api_key,
+            "event": event.name,
+            "distinct_id": event.distinct_id,
+        });
+        if event.is_identify() {
+            // For $identify, set person properties in $set
+            payload["$set"] = event.properties.clone();
+        } else {
+            payload["properties"] = event.properties.clone();
+        }
+        post_json(&self.client, &self.endpoint, None, &payload).await
+    }
+}
+
+/// Posts each event as `{ event, distinct_id, properties }` to a collector
+/// run by the deployment itself.
+#[derive(Debug)]
+pub struct SelfHostedTransport {
+    endpoint: String,
+    token: Option<String>,
+    client: reqwest::Client,
+}
+
+impl SelfHostedTransport {
+    pub fn new(endpoint: String, token: Option<String>, client: reqwest::Client) -> Self {
+        Self {
+            endpoint,
+            token,
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl AnalyticsTransport for SelfHostedTransport {
+    async fn send(&self, event: &AnalyticsEvent) -> Result<(), String> {
+        let payload = json!({
+            "event": event.name,
+            "distinct_id": event.distinct_id,
+            "properties": event.properties,
+        });
+        post_json(
+            &self.client,
+            &self.endpoint,
+            self.token.as_deref(),
+            &payload,
+        )
+        .await
+    }
+}
+
+#[derive(Debug)]
+pub struct NoopTransport;
+
+#[async_trait]
+impl AnalyticsTransport for NoopTransport {
+    async fn send(&self, _event: &AnalyticsEvent) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+async fn post_json(
+    client: &reqwest::Client,
+    endpoint: &str,
+    bearer_token: Option<&str>,
+    payload: &Value,
+) -> Result<(), String> {
+    let mut request = client
+        .post(endpoint)
+        .header("Content-Type", "application/json")
+        .json(payload);
+    if let Some(token) = bearer_token {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    if response.status().is_success() {
+        return Ok(());
+    }
+    let status = response.status();
+    let response_text = response.text().await.unwrap_or_default();
+    Err(format!("Status: {}. Response: {}", status, response_text))
+}
+
 #[derive(Clone, Debug)]
 pub struct AnalyticsService {
-    config: AnalyticsConfig,
-    client: reqwest::Client,
+    transport: Arc<dyn AnalyticsTransport>,
 }
 
 impl AnalyticsService {
@@ -48,27 +230,28 @@ impl AnalyticsService {
             .build()
             .unwrap();
 
-        Self { config, client }
+        let transport: Arc<dyn AnalyticsTransport> = match config.backend {
+            AnalyticsBackend::PostHog {
+                api_key,
+                api_endpoint,
+            } => Arc::new(PostHogTransport::new(api_key, &api_endpoint, client)),
+            AnalyticsBackend::SelfHosted { endpoint, token } => {
+                Arc::new(SelfHostedTransport::new(endpoint, token, client))
+            }
+            AnalyticsBackend::Disabled => Arc::new(NoopTransport),
+        };
+
+        Self::with_transport(transport)
     }
 
-    pub fn track_event(&self, user_id: &str, event_name: &str, properties: Option<Value>) {
-        let endpoint = format!(
-            "{}/capture/",
-            self.config.posthog_api_endpoint.trim_end_matches('/')
-        );
+    pub fn with_transport(transport: Arc<dyn AnalyticsTransport>) -> Self {
+        Self { transport }
+    }
 
-        let mut payload = json!({
-            "api_key": self.config.posthog_api_key,
-            "event": event_name,
-            "distinct_id": user_id,
-        });
-        if event_name == "$identify" {
-            // For $identify, set person properties in $set
-            if let Some(props) = properties {
-                payload["$set"] = props;
-            }
+    pub fn track_event(&self, user_id: &str, event_name: &str, properties: Option<Value>) {
+        let properties = if event_name == "$identify" {
+            properties.unwrap_or_else(|| json!({}))
         } else {
-            // For other events, use properties as before
             let mut event_properties = properties.unwrap_or_else(|| json!({}));
             if let Some(props) = event_properties.as_object_mut() {
                 props.insert(
@@ -79,36 +262,20 @@ impl AnalyticsService {
                 props.insert("device".to_string(), get_device_info());
                 props.insert("source".to_string(), json!("backend"));
             }
-            payload["properties"] = event_properties;
-        }
+            event_properties
+        };
 
-        let client = self.client.clone();
-        let event_name = event_name.to_string();
+        let event = AnalyticsEvent {
+            name: event_name.to_string(),
+            distinct_id: user_id.to_string(),
+            properties,
+        };
+        let transport = self.transport.clone();
 
         tokio::spawn(async move {
-            match client
-                .post(&endpoint)
-                .header("Content-Type", "application/json")
-                .json(&payload)
-                .send()
-                .await
-            {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        tracing::debug!("Event '{}' sent successfully", event_name);
-                    } else {
-                        let status = response.status();
-                        let response_text = response.text().await.unwrap_or_default();
-                        tracing::error!(
-                            "Failed to send event. Status: {}. Response: {}",
-                            status,
-                            response_text
-                        );
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Error sending event '{}': {}", event_name, e);
-                }
+            match transport.send(&event).await {
+                Ok(()) => tracing::debug!("Event '{}' sent successfully", event.name),
+                Err(e) => tracing::error!("Failed to send event '{}': {}", event.name, e),
             }
         });
     }
@@ -198,4 +365,36 @@ mod tests {
         let id2 = generate_user_id();
         assert_eq!(id1, id2, "ID should be consistent across calls");
     }
+
+    /// Hands every sent event to the test, which waits for them on the receiver
+    #[derive(Debug)]
+    struct RecordingTransport {
+        events: tokio::sync::mpsc::UnboundedSender<AnalyticsEvent>,
+    }
+
+    #[async_trait]
+    impl AnalyticsTransport for RecordingTransport {
+        async fn send(&self, event: &AnalyticsEvent) -> Result<(), String> {
+            self.events.send(event.clone()).map_err(|e| e.to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_track_event_enriches_properties_before_transport() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let service = AnalyticsService::with_transport(Arc::new(RecordingTransport { events: tx }));
+
+        service.track_event("user", "task_created", Some(json!({ "task_id": "t1" })));
+        service.track_event("user", "$identify", Some(json!({ "email": "a@b.c" })));
+        // Each event is sent from its own task, so they may arrive in either order
+        let events = [rx.recv().await.unwrap(), rx.recv().await.unwrap()];
+
+        let created = events.iter().find(|e| e.name == "task_created").unwrap();
+        assert_eq!(created.distinct_id, "user");
+        assert_eq!(created.properties["task_id"], "t1");
+        assert_eq!(created.properties["source"], "backend");
+
+        let identify = events.iter().find(|e| e.is_identify()).unwrap();
+        assert_eq!(identify.properties, json!({ "email": "a@b.c" }));
+    }
 }
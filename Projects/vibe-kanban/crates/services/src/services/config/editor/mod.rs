@@ -6,6 +6,8 @@ use strum_macros::{EnumIter, EnumString};
 use thiserror::Error;
 use ts_rs::TS;
 
+use super::ConfigError;
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS, Error)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
@@ -37,6 +39,124 @@ pub struct EditorConfig {
     remote_ssh_host: Option<String>,
     #[serde(default)]
     remote_ssh_user: Option<String>,
+    /// Non-SSH remote context (tunnel, Codespace, container) the worktrees live in
+    #[serde(default)]
+    remote_target: Option<RemoteEditorTarget>,
+    /// Per-path editors, checked in order before falling back to `editor_type`
+    #[serde(default)]
+    profiles: Vec<EditorProfile>,
+    /// Editors to try, in order, when the selected editor isn't installed
+    #[serde(default)]
+    fallback_editors: Vec<EditorType>,
+}
+
+/// The editor that ended up handling an `open_file` call.
+#[derive(Debug, Clone)]
+pub struct OpenedEditor {
+    pub editor_type: EditorType,
+    /// Set when the path should be opened via a remote URL instead of a local spawn
+    pub url: Option<String>,
+}
+
+/// A VS Code remote context other than SSH, identified by name.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum RemoteEditorTarget {
+    /// A machine exposed through `code tunnel`
+    Tunnel { name: String },
+    /// A GitHub Codespace
+    Codespace { name: String },
+    /// A running Docker container (ID or name), e.g. a dev container
+    Container { name: String },
+}
+
+impl RemoteEditorTarget {
+    fn name(&self) -> &str {
+        match self {
+            Self::Tunnel { name } | Self::Codespace { name } | Self::Container { name } => name,
+        }
+    }
+
+    /// The `vscode-remote` authority for this target.
+    fn authority(&self) -> String {
+        match self {
+            Self::Tunnel { name } => format!("tunnel+{name}"),
+            Self::Codespace { name } => format!("codespaces+{name}"),
+            Self::Container { name } => {
+                let hex: String = name.bytes().map(|b| format!("{b:02x}")).collect();
+                format!("attached-container+{hex}")
+            }
+        }
+    }
+}
+
+/// An editor that takes over for paths matching any of its `matchers`.
+///
+/// A matcher is either an extension (`.swift`) or a glob (`*.xcodeproj`, `*/ios/*`).
+/// Globs without a `/` are matched against the file name, others against the full path.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct EditorProfile {
+    pub editor_type: EditorType,
+    #[serde(default)]
+    pub custom_command: Option<String>,
+    #[serde(default)]
+    pub matchers: Vec<String>,
+}
+
+impl EditorProfile {
+    fn matches(&self, path: &Path) -> bool {
+        self.matchers
+            .iter()
+            .any(|matcher| path_matches(matcher.trim(), path))
+    }
+}
+
+fn path_matches(matcher: &str, path: &Path) -> bool {
+    if matcher.is_empty() {
+        return false;
+    }
+
+    if let Some(ext) = matcher.strip_prefix('.')
+        && !ext.contains(['*', '?', '/'])
+    {
+        return path
+            .extension()
+            .is_some_and(|e| e.to_string_lossy().eq_ignore_ascii_case(ext));
+    }
+
+    if matcher.contains('/') {
+        glob_match(matcher, &path.to_string_lossy())
+    } else {
+        path.file_name()
+            .is_some_and(|name| glob_match(matcher, &name.to_string_lossy()))
+    }
+}
+
+/// Minimal glob: `*` matches any run of characters (including `/`), `?` matches one.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS, EnumString, EnumIter)]
@@ -62,6 +182,9 @@ impl Default for EditorConfig {
             custom_command: None,
             remote_ssh_host: None,
             remote_ssh_user: None,
+            remote_target: None,
+            profiles: Vec::new(),
+            fallback_editors: Vec::new(),
         }
     }
 }
@@ -79,6 +202,92 @@ impl EditorConfig {
             custom_command,
             remote_ssh_host,
             remote_ssh_user,
+            remote_target: None,
+            profiles: Vec::new(),
+            fallback_editors: Vec::new(),
+        }
+    }
+
+    /// Validate field combinations that would otherwise only fail when opening a file.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let uses_custom = std::iter::once(&self.editor_type)
+            .chain(&self.fallback_editors)
+            .any(|editor_type| matches!(editor_type, EditorType::Custom));
+        if uses_custom
+            && self
+                .custom_command
+                .as_deref()
+                .is_none_or(|cmd| cmd.trim().is_empty())
+        {
+            return Err(ConfigError::ValidationError(
+                "Custom editor requires a command".to_string(),
+            ));
+        }
+
+        if let Some(host) = &self.remote_ssh_host
+            && (host.is_empty()
+                || host.contains("://")
+                || host.contains('@')
+                || host.chars().any(char::is_whitespace))
+        {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid remote SSH host '{host}'. Use a bare hostname, e.g. 'devbox.local'"
+            )));
+        }
+
+        if let Some(user) = &self.remote_ssh_user
+            && (user.is_empty() || user.contains('@') || user.chars().any(char::is_whitespace))
+        {
+            return Err(ConfigError::ValidationError(format!(
+                "Invalid remote SSH user '{user}'"
+            )));
+        }
+
+        if let Some(target) = &self.remote_target {
+            let name = target.name();
+            if name.is_empty() || name.contains('/') || name.chars().any(char::is_whitespace) {
+                return Err(ConfigError::ValidationError(format!(
+                    "Invalid remote name '{name}'"
+                )));
+            }
+        }
+
+        for profile in &self.profiles {
+            if matches!(profile.editor_type, EditorType::Custom)
+                && profile
+                    .custom_command
+                    .as_deref()
+                    .is_none_or(|cmd| cmd.trim().is_empty())
+            {
+                return Err(ConfigError::ValidationError(
+                    "Custom editor profile requires a command".to_string(),
+                ));
+            }
+            if profile.matchers.iter().all(|m| m.trim().is_empty()) {
+                return Err(ConfigError::ValidationError(format!(
+                    "Editor profile for {:?} needs at least one path or extension matcher",
+                    profile.editor_type
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the editor for `path`: the first profile with a matching pattern,
+    /// otherwise this config's default editor.
+    pub fn for_path(&self, path: &Path) -> EditorConfig {
+        match self.profiles.iter().find(|profile| profile.matches(path)) {
+            Some(profile) => EditorConfig {
+                editor_type: profile.editor_type.clone(),
+                custom_command: profile.custom_command.clone(),
+                remote_ssh_host: self.remote_ssh_host.clone(),
+                remote_ssh_user: self.remote_ssh_user.clone(),
+                remote_target: self.remote_target.clone(),
+                profiles: Vec::new(),
+                fallback_editors: self.fallback_editors.clone(),
+            },
+            None => self.clone(),
         }
     }
 
@@ -132,47 +341,136 @@ impl EditorConfig {
         self.resolve_command().await.is_ok()
     }
 
-    pub async fn open_file(&self, path: &Path) -> Result<Option<String>, EditorOpenError> {
-        if let Some(url) = self.remote_url(path) {
-            return Ok(Some(url));
+    /// Open `path` in the matching editor, moving down `fallback_editors` while the
+    /// candidate's executable isn't installed.
+    pub async fn open_file(&self, path: &Path) -> Result<OpenedEditor, EditorOpenError> {
+        self.open(path, None).await
+    }
+
+    /// Open `dir` as a workspace, either in a fresh window or added to the focused one.
+    pub async fn open_workspace(
+        &self,
+        dir: &Path,
+        new_window: bool,
+    ) -> Result<OpenedEditor, EditorOpenError> {
+        self.open(dir, Some(new_window)).await
+    }
+
+    async fn open(
+        &self,
+        path: &Path,
+        new_window: Option<bool>,
+    ) -> Result<OpenedEditor, EditorOpenError> {
+        let editor = self.for_path(path);
+        let is_file = new_window.is_none() && path.is_file();
+        if let Some(url) = editor.remote_url(path, is_file) {
+            return Ok(OpenedEditor {
+                editor_type: editor.editor_type,
+                url: Some(url),
+            });
+        }
+
+        let candidates = std::iter::once(editor.editor_type.clone())
+            .chain(editor.fallback_editors.iter().cloned());
+        let mut first_error = None;
+        for editor_type in candidates {
+            let candidate = EditorConfig {
+                editor_type: editor_type.clone(),
+                ..editor.clone()
+            };
+            let extra_args = candidate.workspace_args(new_window);
+            match candidate.spawn_local_with_args(path, extra_args).await {
+                Ok(()) => {
+                    return Ok(OpenedEditor {
+                        editor_type,
+                        url: None,
+                    });
+                }
+                Err(e @ EditorOpenError::ExecutableNotFound { .. }) => {
+                    tracing::debug!("{e}, trying next fallback editor");
+                    first_error.get_or_insert(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(first_error.expect("at least one editor candidate is always tried"))
+    }
+
+    /// Flags that make the editor open a folder in a new window or add it to the current one.
+    fn workspace_args(&self, new_window: Option<bool>) -> &'static [&'static str] {
+        let Some(new_window) = new_window else {
+            return &[];
+        };
+        match (&self.editor_type, new_window) {
+            (
+                EditorType::VsCode
+                | EditorType::VsCodeInsiders
+                | EditorType::Cursor
+                | EditorType::Windsurf
+                | EditorType::GoogleAntigravity,
+                true,
+            ) => &["--new-window"],
+            (EditorType::Zed, true) => &["--new"],
+            (
+                EditorType::VsCode
+                | EditorType::VsCodeInsiders
+                | EditorType::Cursor
+                | EditorType::Windsurf
+                | EditorType::GoogleAntigravity
+                | EditorType::Zed,
+                false,
+            ) => &["--add"],
+            _ => &[],
         }
-        self.spawn_local(path).await?;
-        Ok(None)
     }
 
-    fn remote_url(&self, path: &Path) -> Option<String> {
-        let remote_host = self.remote_ssh_host.as_ref()?;
-        let user_part = self
-            .remote_ssh_user
-            .as_ref()
-            .map(|u| format!("{u}@"))
-            .unwrap_or_default();
+    fn remote_url(&self, path: &Path, is_file: bool) -> Option<String> {
         let path_str = path.to_string_lossy();
 
+        let authority = if let Some(remote_host) = self.remote_ssh_host.as_ref() {
+            let user_part = self
+                .remote_ssh_user
+                .as_ref()
+                .map(|u| format!("{u}@"))
+                .unwrap_or_default();
+            if matches!(self.editor_type, EditorType::Zed) {
+                return Some(format!("zed://ssh/{user_part}{remote_host}{path_str}"));
+            }
+            format!("ssh-remote+{user_part}{remote_host}")
+        } else {
+            self.remote_target.as_ref()?.authority()
+        };
+
         let scheme = match self.editor_type {
             EditorType::VsCode => "vscode",
             EditorType::VsCodeInsiders => "vscode-insiders",
             EditorType::Cursor => "cursor",
             EditorType::Windsurf => "windsurf",
             EditorType::GoogleAntigravity => "antigravity",
-            EditorType::Zed => {
-                return Some(format!("zed://ssh/{user_part}{remote_host}{path_str}"));
-            }
             _ => return None,
         };
 
         // files must contain a line and column number
-        let line_col = if path.is_file() { ":1:1" } else { "" };
+        let line_col = if is_file { ":1:1" } else { "" };
         Some(format!(
-            "{scheme}://vscode-remote/ssh-remote+{user_part}{remote_host}{path_str}{line_col}"
+            "{scheme}://vscode-remote/{authority}{path_str}{line_col}"
         ))
     }
 
     pub async fn spawn_local(&self, path: &Path) -> Result<(), EditorOpenError> {
+        self.spawn_local_with_args(path, &[]).await
+    }
+
+    async fn spawn_local_with_args(
+        &self,
+        path: &Path,
+        extra_args: &[&str],
+    ) -> Result<(), EditorOpenError> {
         let (executable, args) = self.resolve_command().await?;
 
         let mut cmd = std::process::Command::new(&executable);
-        cmd.args(&args).arg(path);
+        cmd.args(&args).args(extra_args).arg(path);
         cmd.spawn().map_err(|e| EditorOpenError::LaunchFailed {
             executable: executable.to_string_lossy().into_owned(),
             details: e.to_string(),
@@ -181,6 +479,7 @@ impl EditorConfig {
         Ok(())
     }
 
+    /// Force a specific editor, bypassing path-matched profiles and fallbacks.
     pub fn with_override(&self, editor_type_str: Option<&str>) -> Self {
         if let Some(editor_type_str) = editor_type_str {
             let editor_type =
@@ -190,9 +489,163 @@ impl EditorConfig {
                 custom_command: self.custom_command.clone(),
                 remote_ssh_host: self.remote_ssh_host.clone(),
                 remote_ssh_user: self.remote_ssh_user.clone(),
+                remote_target: self.remote_target.clone(),
+                profiles: Vec::new(),
+                fallback_editors: Vec::new(),
             }
         } else {
             self.clone()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(editor_type: EditorType, matchers: &[&str]) -> EditorProfile {
+        EditorProfile {
+            editor_type,
+            custom_command: None,
+            matchers: matchers.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.swift", "App.swift"));
+        assert!(glob_match("*/ios/*", "/work/repo/ios/App.swift"));
+        assert!(glob_match("Package.?wift", "Package.swift"));
+        assert!(!glob_match("*.swift", "App.swift.orig"));
+        assert!(!glob_match("ios/*", "/work/repo/ios/App.swift"));
+    }
+
+    #[test]
+    fn test_for_path_picks_first_matching_profile() {
+        let config = EditorConfig {
+            profiles: vec![
+                profile(EditorType::Xcode, &[".swift", "*.xcodeproj"]),
+                profile(EditorType::Zed, &["*/docs/*"]),
+            ],
+            ..EditorConfig::default()
+        };
+
+        let swift = config.for_path(Path::new("/repo/docs/App.SWIFT"));
+        assert!(matches!(swift.editor_type, EditorType::Xcode));
+
+        let docs = config.for_path(Path::new("/repo/docs/README.md"));
+        assert!(matches!(docs.editor_type, EditorType::Zed));
+
+        let other = config.for_path(Path::new("/repo/src/main.rs"));
+        assert!(matches!(other.editor_type, EditorType::VsCode));
+    }
+
+    #[test]
+    fn test_remote_target_urls() {
+        let with_target = |editor_type, target| EditorConfig {
+            editor_type,
+            remote_target: Some(target),
+            ..EditorConfig::default()
+        };
+        let path = Path::new("/workspaces/repo");
+
+        let tunnel = with_target(
+            EditorType::VsCode,
+            RemoteEditorTarget::Tunnel {
+                name: "devbox".into(),
+            },
+        );
+        assert_eq!(
+            tunnel.remote_url(path, false).as_deref(),
+            Some("vscode://vscode-remote/tunnel+devbox/workspaces/repo")
+        );
+
+        let container = with_target(
+            EditorType::Cursor,
+            RemoteEditorTarget::Container { name: "app".into() },
+        );
+        assert_eq!(
+            container.remote_url(path, false).as_deref(),
+            Some("cursor://vscode-remote/attached-container+617070/workspaces/repo")
+        );
+
+        let zed = with_target(
+            EditorType::Zed,
+            RemoteEditorTarget::Codespace {
+                name: "cs-1".into(),
+            },
+        );
+        assert_eq!(zed.remote_url(path, false), None);
+        assert_eq!(EditorConfig::default().remote_url(path, false), None);
+    }
+
+    #[test]
+    fn test_workspace_args() {
+        let editor = |editor_type| EditorConfig {
+            editor_type,
+            ..EditorConfig::default()
+        };
+        assert_eq!(
+            editor(EditorType::VsCode).workspace_args(None),
+            &[] as &[&str]
+        );
+        assert_eq!(
+            editor(EditorType::Cursor).workspace_args(Some(true)),
+            &["--new-window"]
+        );
+        assert_eq!(
+            editor(EditorType::Zed).workspace_args(Some(true)),
+            &["--new"]
+        );
+        assert_eq!(
+            editor(EditorType::Zed).workspace_args(Some(false)),
+            &["--add"]
+        );
+        assert_eq!(
+            editor(EditorType::Xcode).workspace_args(Some(true)),
+            &[] as &[&str]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_open_tries_fallback_editors_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = EditorConfig {
+            editor_type: EditorType::GoogleAntigravity,
+            custom_command: Some("true".to_string()),
+            fallback_editors: vec![EditorType::Custom, EditorType::VsCode],
+            ..EditorConfig::default()
+        };
+
+        let opened = config.open_file(dir.path()).await.unwrap();
+        assert!(matches!(opened.editor_type, EditorType::Custom));
+        assert_eq!(opened.url, None);
+
+        // When nothing is installed, the selected editor's error is reported
+        let missing = EditorConfig {
+            custom_command: Some("vibe-kanban-missing-editor".to_string()),
+            fallback_editors: vec![EditorType::Custom],
+            ..config
+        };
+        assert!(matches!(
+            missing.open_file(dir.path()).await,
+            Err(EditorOpenError::ExecutableNotFound {
+                editor_type: EditorType::GoogleAntigravity,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_override_ignores_profiles() {
+        let config = EditorConfig {
+            profiles: vec![profile(EditorType::Xcode, &[".swift"])],
+            ..EditorConfig::default()
+        };
+
+        let forced = config.with_override(Some("ZED"));
+        let resolved = forced.for_path(Path::new("/repo/App.swift"));
+        assert!(matches!(resolved.editor_type, EditorType::Zed));
+    }
+}
@@ -1,12 +1,19 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use thiserror::Error;
+use utils::redaction::validate_redaction_patterns;
+
+use crate::services::pr_monitor::MIN_PR_POLL_INTERVAL_SECS;
 
 pub mod editor;
 mod versions;
 
 pub use editor::EditorOpenError;
 
+/// Upper bound for `spawn_retry_attempts`; with exponential backoff more
+/// attempts would leave an execution hanging for minutes.
+const MAX_SPAWN_RETRY_ATTEMPTS: u32 = 5;
+
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error(transparent)]
@@ -15,6 +22,11 @@ pub enum ConfigError {
     Json(#[from] serde_json::Error),
     #[error("Validation error: {0}")]
     ValidationError(String),
+    #[error(
+        "Config at {} is from a newer app version and has no backup; not overwriting it",
+        .0.display()
+    )]
+    NewerVersionNotBackedUp(PathBuf),
 }
 
 pub type Config = versions::v8::Config;
@@ -31,7 +43,24 @@ pub type SendMessageShortcut = versions::v8::SendMessageShortcut;
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
     match std::fs::read_to_string(config_path) {
-        Ok(raw_config) => Config::from(raw_config),
+        Ok(raw_config) => {
+            if let Some(version) = future_config_version(&raw_config) {
+                let backup = backup_config_file(config_path);
+                let message = format!(
+                    "Config at {} has version v{version}, newer than the latest supported v{}; \
+                     it was written by a newer app version",
+                    config_path.display(),
+                    versions::LATEST_CONFIG_VERSION,
+                );
+                // Fail loudly in development so schema bumps can't slip through unnoticed
+                if cfg!(debug_assertions) {
+                    panic!("{message}");
+                }
+                tracing::error!("{message}. Backed up to {backup:?}, using default config");
+                return Config::default();
+            }
+            Config::from(raw_config)
+        }
         Err(_) => {
             tracing::info!("No config file found, creating one");
             Config::default()
@@ -39,12 +68,340 @@ pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
     }
 }
 
+/// Returns the version number if the raw config declares a `config_version` newer
+/// than this build understands.
+fn future_config_version(raw_config: &str) -> Option<u32> {
+    let value: serde_json::Value = serde_json::from_str(raw_config).ok()?;
+    let version = value
+        .get("config_version")?
+        .as_str()?
+        .strip_prefix('v')?
+        .parse::<u32>()
+        .ok()?;
+    (version > versions::LATEST_CONFIG_VERSION).then_some(version)
+}
+
+fn config_backup_path(config_path: &Path) -> PathBuf {
+    config_path.with_extension("json.bak")
+}
+
+/// Copies the config file next to itself with a `.bak` extension so it isn't lost
+/// when we fall back to defaults.
+fn backup_config_file(config_path: &Path) -> Option<PathBuf> {
+    let backup = config_backup_path(config_path);
+    match std::fs::copy(config_path, &backup) {
+        Ok(_) => Some(backup),
+        Err(e) => {
+            tracing::error!("Failed to back up config to {}: {}", backup.display(), e);
+            None
+        }
+    }
+}
+
+/// Checks cross-field invariants that serde alone can't express, so an invalid
+/// combination is rejected up front instead of failing later at use time.
+pub fn validate_config(config: &Config) -> Result<(), ConfigError> {
+    if !git::is_valid_branch_prefix(&config.git_branch_prefix) {
+        return Err(ConfigError::ValidationError(
+            "Invalid git branch prefix. Must be a valid git branch name component without slashes."
+                .to_string(),
+        ));
+    }
+
+    if config
+        .pr_auto_description_prompt
+        .as_deref()
+        .is_some_and(|prompt| prompt.trim().is_empty())
+    {
+        return Err(ConfigError::ValidationError(
+            "PR description prompt cannot be empty; unset it to use the default prompt".to_string(),
+        ));
+    }
+
+    // Onboarding follows the disclaimer, and the analytics funnel events
+    // (`onboarding_disclaimer_accepted`, then `onboarding_completed`) rely on that order
+    if config.onboarding_acknowledged && !config.disclaimer_acknowledged {
+        return Err(ConfigError::ValidationError(
+            "Onboarding can't be completed before the disclaimer is acknowledged".to_string(),
+        ));
+    }
+
+    if !(1..=100).contains(&config.context_warning_threshold_percent) {
+        return Err(ConfigError::ValidationError(
+            "Context warning threshold must be between 1 and 100 percent".to_string(),
+        ));
+    }
+
+    if config.pr_monitor_poll_interval_secs < MIN_PR_POLL_INTERVAL_SECS {
+        return Err(ConfigError::ValidationError(format!(
+            "PR monitor poll interval must be at least {MIN_PR_POLL_INTERVAL_SECS} seconds"
+        )));
+    }
+
+    if config.max_concurrent_executions == Some(0) {
+        return Err(ConfigError::ValidationError(
+            "Maximum concurrent executions must be at least 1; unset it for no limit".to_string(),
+        ));
+    }
+
+    if config.executor_idle_timeout_secs == Some(0) {
+        return Err(ConfigError::ValidationError(
+            "Executor idle timeout must be at least 1 second; unset it for no timeout".to_string(),
+        ));
+    }
+
+    if let Some(name) = config
+        .preferred_remotes
+        .iter()
+        .find(|name| !git::is_valid_remote_name(name))
+    {
+        return Err(ConfigError::ValidationError(format!(
+            "Invalid preferred remote name: '{name}'"
+        )));
+    }
+
+    if config.spawn_retry_attempts > MAX_SPAWN_RETRY_ATTEMPTS {
+        return Err(ConfigError::ValidationError(format!(
+            "Spawn retry attempts must be at most {MAX_SPAWN_RETRY_ATTEMPTS}"
+        )));
+    }
+
+    if config.terminal_output_buffer_kb == 0 {
+        return Err(ConfigError::ValidationError(
+            "Terminal output buffer must be at least 1 KiB".to_string(),
+        ));
+    }
+
+    if config.max_terminal_sessions == 0 {
+        return Err(ConfigError::ValidationError(
+            "At least one terminal session must be allowed".to_string(),
+        ));
+    }
+
+    if config.file_content.max_inline_bytes == 0 {
+        return Err(ConfigError::ValidationError(
+            "Inline file content limit must be at least 1 byte".to_string(),
+        ));
+    }
+
+    if let Some(author) = &config.commit_author
+        && (author.name.trim().is_empty() || !author.email.contains('@'))
+    {
+        return Err(ConfigError::ValidationError(
+            "Commit author needs a name and an email address".to_string(),
+        ));
+    }
+
+    if config.commit_message_rules.max_subject_length == Some(0) {
+        return Err(ConfigError::ValidationError(
+            "Maximum commit subject length must be at least 1; unset it for no limit".to_string(),
+        ));
+    }
+
+    if let Some(pattern) = &config.commit_message_rules.subject_pattern
+        && let Err(e) = regex::Regex::new(pattern)
+    {
+        return Err(ConfigError::ValidationError(format!(
+            "Invalid commit subject pattern: {e}"
+        )));
+    }
+
+    if let Err(e) = validate_redaction_patterns(&config.log_redaction_patterns) {
+        return Err(ConfigError::ValidationError(format!(
+            "Invalid log redaction pattern: {e}"
+        )));
+    }
+
+    config.editor.validate()
+}
+
+/// Refuses to replace a config written by a newer app version unless
+/// [`backup_config_file`] managed to copy it, so its settings can't be lost.
+fn ensure_newer_config_backed_up(config_path: &Path) -> Result<(), ConfigError> {
+    let Ok(raw_config) = std::fs::read_to_string(config_path) else {
+        return Ok(());
+    };
+    if future_config_version(&raw_config).is_none()
+        || std::fs::read_to_string(config_backup_path(config_path)).is_ok_and(|b| b == raw_config)
+    {
+        return Ok(());
+    }
+    Err(ConfigError::NewerVersionNotBackedUp(
+        config_path.to_path_buf(),
+    ))
+}
+
 /// Saves the config to the given path
-pub async fn save_config_to_file(
-    config: &Config,
-    config_path: &PathBuf,
-) -> Result<(), ConfigError> {
+pub async fn save_config_to_file(config: &Config, config_path: &Path) -> Result<(), ConfigError> {
+    validate_config(config)?;
+    ensure_newer_config_backed_up(config_path)?;
     let raw_config = serde_json::to_string_pretty(config)?;
-    std::fs::write(config_path, raw_config)?;
+    utils::fs::write_atomic(config_path, raw_config)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_editor(editor: EditorConfig) -> Config {
+        Config {
+            editor,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_future_config_version_detected() {
+        assert_eq!(
+            future_config_version(r#"{"config_version": "v99"}"#),
+            Some(99)
+        );
+        let current = serde_json::to_string(&Config::default()).unwrap();
+        assert_eq!(future_config_version(&current), None);
+        assert_eq!(future_config_version(r#"{"config_version": "v2"}"#), None);
+        assert_eq!(future_config_version("not json"), None);
+    }
+
+    #[tokio::test]
+    async fn test_newer_config_is_only_replaced_once_backed_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.json");
+        let newer = r#"{"config_version": "v99"}"#;
+        std::fs::write(&config_path, newer).unwrap();
+
+        let result = save_config_to_file(&Config::default(), &config_path).await;
+        assert!(matches!(
+            result,
+            Err(ConfigError::NewerVersionNotBackedUp(_))
+        ));
+        assert_eq!(std::fs::read_to_string(&config_path).unwrap(), newer);
+
+        backup_config_file(&config_path).unwrap();
+        save_config_to_file(&Config::default(), &config_path)
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(config_backup_path(&config_path)).unwrap(),
+            newer
+        );
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(validate_config(&Config::default()).is_ok());
+    }
+
+    #[test]
+    fn test_custom_editor_requires_command() {
+        let config = config_with_editor(EditorConfig::new(EditorType::Custom, None, None, None));
+        assert!(matches!(
+            validate_config(&config),
+            Err(ConfigError::ValidationError(_))
+        ));
+
+        let config = config_with_editor(EditorConfig::new(
+            EditorType::Custom,
+            Some("nvim".into()),
+            None,
+            None,
+        ));
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_remote_ssh_host_must_be_bare_hostname() {
+        for host in ["ssh://devbox", "me@devbox", "dev box", ""] {
+            let config = config_with_editor(EditorConfig::new(
+                EditorType::VsCode,
+                None,
+                Some(host.to_string()),
+                None,
+            ));
+            assert!(
+                validate_config(&config).is_err(),
+                "{host} should be rejected"
+            );
+        }
+
+        let config = config_with_editor(EditorConfig::new(
+            EditorType::VsCode,
+            None,
+            Some("devbox.local".into()),
+            Some("me".into()),
+        ));
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_branch_prefix_rejected() {
+        let config = Config {
+            git_branch_prefix: "feat/x".to_string(),
+            ..Config::default()
+        };
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_invalid_preferred_remote_rejected() {
+        let config = Config {
+            preferred_remotes: vec!["upstream".to_string(), "my fork".to_string()],
+            ..Config::default()
+        };
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_commit_author_requires_name_and_email() {
+        let with_author = |name: &str, email: &str| Config {
+            commit_author: Some(git::CommitIdentity {
+                name: name.to_string(),
+                email: email.to_string(),
+            }),
+            ..Config::default()
+        };
+        assert!(validate_config(&with_author("", "me@example.com")).is_err());
+        assert!(validate_config(&with_author("Me", "not-an-email")).is_err());
+        assert!(validate_config(&with_author("Me", "me@example.com")).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_commit_subject_pattern_rejected() {
+        let config = Config {
+            commit_message_rules: git::CommitMessageRules {
+                subject_pattern: Some("^(feat|fix".to_string()),
+                ..Default::default()
+            },
+            ..Config::default()
+        };
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_onboarding_requires_acknowledged_disclaimer() {
+        let config = Config {
+            onboarding_acknowledged: true,
+            ..Config::default()
+        };
+        assert!(matches!(
+            validate_config(&config),
+            Err(ConfigError::ValidationError(_))
+        ));
+
+        let config = Config {
+            disclaimer_acknowledged: true,
+            onboarding_acknowledged: true,
+            ..Config::default()
+        };
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn test_zero_idle_timeout_rejected() {
+        let config = Config {
+            executor_idle_timeout_secs: Some(0),
+            ..Config::default()
+        };
+        assert!(validate_config(&config).is_err());
+    }
+}
@@ -6,3 +6,6 @@ pub(super) mod v5;
 pub(super) mod v6;
 pub(super) mod v7;
 pub(super) mod v8;
+
+/// Numeric suffix of the newest `config_version` ("v8") this build knows how to read.
+pub(super) const LATEST_CONFIG_VERSION: u32 = 8;
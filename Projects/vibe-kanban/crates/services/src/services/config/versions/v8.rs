@@ -1,5 +1,10 @@
 use anyhow::Error;
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use executors::{
+    executors::BaseCodingAgent,
+    logs::utils::context_budget::DEFAULT_CONTEXT_WARNING_THRESHOLD_PERCENT,
+    profile::ExecutorProfileId,
+};
+use git::{CommitIdentity, CommitMessageRules, FileContentOptions};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 pub use v7::{
@@ -7,7 +12,7 @@ pub use v7::{
     ThemeMode, UiLanguage,
 };
 
-use crate::services::config::versions::v7;
+use crate::services::{config::versions::v7, pr_monitor::DEFAULT_PR_POLL_INTERVAL_SECS};
 
 fn default_git_branch_prefix() -> String {
     "vk".to_string()
@@ -17,6 +22,42 @@ fn default_pr_auto_description_enabled() -> bool {
     true
 }
 
+fn default_context_warning_enabled() -> bool {
+    true
+}
+
+fn default_context_warning_threshold_percent() -> u8 {
+    DEFAULT_CONTEXT_WARNING_THRESHOLD_PERCENT
+}
+
+fn default_log_redaction_enabled() -> bool {
+    true
+}
+
+fn default_deepen_shallow_clones() -> bool {
+    true
+}
+
+fn default_spawn_retry_attempts() -> u32 {
+    2
+}
+
+fn default_spawn_retry_backoff_ms() -> u32 {
+    1000
+}
+
+fn default_terminal_output_buffer_kb() -> u32 {
+    1024
+}
+
+fn default_max_terminal_sessions() -> u32 {
+    16
+}
+
+fn default_pr_monitor_poll_interval_secs() -> u32 {
+    DEFAULT_PR_POLL_INTERVAL_SECS
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
 pub enum SendMessageShortcut {
     #[default]
@@ -56,6 +97,83 @@ pub struct Config {
     pub commit_reminder: bool,
     #[serde(default)]
     pub send_message_shortcut: SendMessageShortcut,
+    /// Run `git submodule update --init --recursive` in new worktrees
+    #[serde(default)]
+    pub init_submodules: bool,
+    /// Add a warning to the conversation when an agent's context window is nearly full
+    #[serde(default = "default_context_warning_enabled")]
+    pub context_warning_enabled: bool,
+    /// Percentage of the context window that triggers the warning
+    #[serde(default = "default_context_warning_threshold_percent")]
+    pub context_warning_threshold_percent: u8,
+    /// Mask secrets (API keys, tokens, JWTs) in agent logs before they are stored or streamed
+    #[serde(default = "default_log_redaction_enabled")]
+    pub log_redaction_enabled: bool,
+    /// Extra regex patterns to redact, on top of the built-in secret patterns
+    #[serde(default)]
+    pub log_redaction_patterns: Vec<String>,
+    /// How often open PRs are checked for merges, in seconds
+    #[serde(default = "default_pr_monitor_poll_interval_secs")]
+    pub pr_monitor_poll_interval_secs: u32,
+    /// Maximum number of coding agents running at once; further executions
+    /// wait for a slot. `None` for no limit. Dev servers are not counted.
+    #[serde(default)]
+    pub max_concurrent_executions: Option<u32>,
+    /// Remote names to push to and open PRs from, in order of preference, when a
+    /// repository sets no `remote.pushDefault`. `origin`, then the first remote,
+    /// are used when none of them exist.
+    #[serde(default)]
+    pub preferred_remotes: Vec<String>,
+    /// Hosts of self-hosted GitLab instances, so their remotes get merge
+    /// requests through `glab`. gitlab.com is always recognized.
+    #[serde(default)]
+    pub gitlab_hosts: Vec<String>,
+    /// Fetch more history when a rebase in a shallow clone needs commits that are
+    /// missing. When off, such rebases fail with an error instead.
+    #[serde(default = "default_deepen_shallow_clones")]
+    pub deepen_shallow_clones: bool,
+    /// Rules commit messages must follow. Commits that break them are
+    /// rejected; nothing is enforced by default.
+    #[serde(default)]
+    pub commit_message_rules: CommitMessageRules,
+    /// Sign commits with `git commit -S`, using the key and `gpg.format` from the
+    /// git config. A missing key makes commits fail instead of going unsigned.
+    #[serde(default)]
+    pub sign_commits: bool,
+    /// Refuse to commit or open a PR when the pending changes appear to add
+    /// secrets. Off by default: findings are only reported.
+    #[serde(default)]
+    pub block_commits_with_secrets: bool,
+    /// How many more times to try starting a coding agent whose spawn failed
+    /// with a transient error. 0 disables retries.
+    #[serde(default = "default_spawn_retry_attempts")]
+    pub spawn_retry_attempts: u32,
+    /// Delay before the first spawn retry; doubled for each further attempt.
+    /// Each delay is randomly shortened by up to half.
+    #[serde(default = "default_spawn_retry_backoff_ms")]
+    pub spawn_retry_backoff_ms: u32,
+    /// Stop a coding agent that has written no output for this many seconds and
+    /// mark it failed. Time spent waiting on an approval doesn't count. Unset
+    /// disables the timeout.
+    #[serde(default)]
+    pub executor_idle_timeout_secs: Option<u32>,
+    /// Terminal output kept for a client that hasn't read it yet, in KiB. When
+    /// the client falls further behind, the oldest output is dropped.
+    #[serde(default = "default_terminal_output_buffer_kb")]
+    pub terminal_output_buffer_kb: u32,
+    /// Terminals that can be open at once; each holds a shell process until
+    /// its client disconnects
+    #[serde(default = "default_max_terminal_sessions")]
+    pub max_terminal_sessions: u32,
+    /// Size limit, binary detection and fallback encoding used when showing
+    /// file contents in diffs
+    #[serde(default)]
+    pub file_content: FileContentOptions,
+    /// Author of commits Vibe Kanban makes in repositories with no
+    /// user.name/user.email. Defaults to `Vibe Kanban <noreply@vibekanban.com>`.
+    /// Takes effect on restart.
+    #[serde(default)]
+    pub commit_author: Option<CommitIdentity>,
 }
 
 impl Config {
@@ -85,6 +203,26 @@ impl Config {
             beta_workspaces_invitation_sent: false,
             commit_reminder: false,
             send_message_shortcut: SendMessageShortcut::default(),
+            init_submodules: false,
+            context_warning_enabled: default_context_warning_enabled(),
+            context_warning_threshold_percent: default_context_warning_threshold_percent(),
+            log_redaction_enabled: default_log_redaction_enabled(),
+            log_redaction_patterns: Vec::new(),
+            pr_monitor_poll_interval_secs: default_pr_monitor_poll_interval_secs(),
+            max_concurrent_executions: None,
+            preferred_remotes: Vec::new(),
+            gitlab_hosts: Vec::new(),
+            deepen_shallow_clones: default_deepen_shallow_clones(),
+            commit_message_rules: CommitMessageRules::default(),
+            sign_commits: false,
+            block_commits_with_secrets: false,
+            spawn_retry_attempts: default_spawn_retry_attempts(),
+            spawn_retry_backoff_ms: default_spawn_retry_backoff_ms(),
+            executor_idle_timeout_secs: None,
+            terminal_output_buffer_kb: default_terminal_output_buffer_kb(),
+            max_terminal_sessions: default_max_terminal_sessions(),
+            file_content: FileContentOptions::default(),
+            commit_author: None,
         }
     }
 
@@ -139,6 +277,26 @@ impl Default for Config {
             beta_workspaces_invitation_sent: false,
             commit_reminder: false,
             send_message_shortcut: SendMessageShortcut::default(),
+            init_submodules: false,
+            context_warning_enabled: default_context_warning_enabled(),
+            context_warning_threshold_percent: default_context_warning_threshold_percent(),
+            log_redaction_enabled: default_log_redaction_enabled(),
+            log_redaction_patterns: Vec::new(),
+            pr_monitor_poll_interval_secs: default_pr_monitor_poll_interval_secs(),
+            max_concurrent_executions: None,
+            preferred_remotes: Vec::new(),
+            gitlab_hosts: Vec::new(),
+            deepen_shallow_clones: default_deepen_shallow_clones(),
+            commit_message_rules: CommitMessageRules::default(),
+            sign_commits: false,
+            block_commits_with_secrets: false,
+            spawn_retry_attempts: default_spawn_retry_attempts(),
+            spawn_retry_backoff_ms: default_spawn_retry_backoff_ms(),
+            executor_idle_timeout_secs: None,
+            terminal_output_buffer_kb: default_terminal_output_buffer_kb(),
+            max_terminal_sessions: default_max_terminal_sessions(),
+            file_content: FileContentOptions::default(),
+            commit_author: None,
         }
     }
 }
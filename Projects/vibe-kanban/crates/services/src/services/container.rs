@@ -18,6 +18,7 @@ use db::{
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        project::Project,
         repo::Repo,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
@@ -53,8 +54,10 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
-    notification::NotificationService, workspace_manager::WorkspaceError as WorkspaceManagerError,
-    worktree_manager::WorktreeError,
+    execution_slots::ExecutionSlots,
+    notification::NotificationService,
+    workspace_manager::{WorkspaceError as WorkspaceManagerError, WorkspaceManager},
+    worktree_manager::{WorktreeError, WorktreeManager},
 };
 pub type ContainerRef = String;
 
@@ -80,6 +83,8 @@ pub enum ContainerError {
     Io(#[from] std::io::Error),
     #[error("Failed to kill process: {0}")]
     KillFailed(std::io::Error),
+    #[error("No dev server script is configured for workspace {0}")]
+    NoDevServer(Uuid),
     #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
@@ -94,6 +99,18 @@ pub trait ContainerService {
 
     fn notification_service(&self) -> &NotificationService;
 
+    /// Bounds how many coding agent processes run at once
+    fn execution_slots(&self) -> &ExecutionSlots;
+
+    /// Launch `execution_process` in the background once a coding agent slot
+    /// frees up, see [`ContainerService::launch_execution`]
+    fn queue_execution(
+        &self,
+        workspace: Workspace,
+        execution_process: ExecutionProcess,
+        executor_action: ExecutorAction,
+    );
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf;
 
     async fn available_agent_slash_commands(
@@ -170,7 +187,10 @@ pub trait ContainerService {
                     ExecutionProcess::find_by_session_id(&self.db().pool, session.id, false).await
                 {
                     for process in processes {
-                        if process.status == ExecutionProcessStatus::Running {
+                        if matches!(
+                            process.status,
+                            ExecutionProcessStatus::Running | ExecutionProcessStatus::Queued
+                        ) {
                             return Ok(true);
                         }
                     }
@@ -328,6 +348,107 @@ pub trait ContainerService {
         Ok(())
     }
 
+    /// Remove git worktrees left behind by workspaces that no longer exist, call at startup.
+    /// Only worktrees under the workspace base directories are considered. Worktrees with
+    /// uncommitted changes are kept unless `force` is set.
+    async fn cleanup_orphan_worktrees(&self, force: bool) -> Result<(), ContainerError> {
+        if std::env::var("DISABLE_WORKTREE_CLEANUP").is_ok() {
+            tracing::info!(
+                "Orphan worktree cleanup is disabled via DISABLE_WORKTREE_CLEANUP environment variable"
+            );
+            return Ok(());
+        }
+
+        let pool = &self.db().pool;
+        let base_dirs = [
+            WorktreeManager::get_default_worktree_base_dir(),
+            WorkspaceManager::get_workspace_base_dir(),
+        ];
+
+        for repo in Repo::list_all(pool).await? {
+            if !repo.path.exists() {
+                continue;
+            }
+            let worktrees = match self.git().list_worktrees_detailed(&repo.path) {
+                Ok(worktrees) => worktrees,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to list worktrees for repo {}: {}",
+                        repo.path.display(),
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            let mut needs_prune = false;
+            for worktree in worktrees {
+                let path = PathBuf::from(&worktree.path);
+                if worktree.locked
+                    || path == repo.path
+                    || !base_dirs.iter().any(|dir| path.starts_with(dir))
+                {
+                    continue;
+                }
+
+                // Worktrees live at <workspace>/<repo name>; legacy ones were the workspace itself
+                let mut owned = Workspace::container_ref_exists(pool, &worktree.path).await?;
+                if !owned && let Some(parent) = path.parent() {
+                    owned =
+                        Workspace::container_ref_exists(pool, &parent.to_string_lossy()).await?;
+                }
+                if owned {
+                    continue;
+                }
+
+                if worktree.prunable {
+                    needs_prune = true;
+                    continue;
+                }
+
+                if !force {
+                    match self.git().worktree_has_changes(&path) {
+                        Ok(false) => {}
+                        Ok(true) => {
+                            tracing::warn!(
+                                "Keeping orphaned worktree {} because it has uncommitted changes",
+                                path.display()
+                            );
+                            continue;
+                        }
+                        Err(e) => {
+                            tracing::warn!(
+                                "Keeping orphaned worktree {}, failed to check its status: {}",
+                                path.display(),
+                                e
+                            );
+                            continue;
+                        }
+                    }
+                }
+
+                tracing::info!("Removing orphaned worktree {}", path.display());
+                if let Err(e) = self.git().remove_worktree(&repo.path, &path, force) {
+                    tracing::warn!(
+                        "Failed to remove orphaned worktree {}: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            }
+
+            if needs_prune && let Err(e) = self.git().prune_worktrees(&repo.path) {
+                tracing::warn!(
+                    "Failed to prune worktrees for repo {}: {}",
+                    repo.path.display(),
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     /// Backfill before_head_commit for legacy execution processes.
     /// Rules:
     /// - If a process has after_head_commit and missing before_head_commit,
@@ -530,7 +651,10 @@ pub trait ContainerService {
                     {
                         continue;
                     }
-                    if process.status == ExecutionProcessStatus::Running {
+                    if matches!(
+                        process.status,
+                        ExecutionProcessStatus::Running | ExecutionProcessStatus::Queued
+                    ) {
                         self.stop_execution(&process, ExecutionProcessStatus::Killed)
                             .await
                             .unwrap_or_else(|e| {
@@ -547,6 +671,60 @@ pub trait ContainerService {
         }
     }
 
+    /// Stop every running dev server in the workspace and start one per repo
+    /// again from the repos' current dev server scripts. Other processes in the
+    /// workspace, coding agents included, are left alone.
+    async fn restart_dev_server(
+        &self,
+        workspace_id: Uuid,
+    ) -> Result<Vec<ExecutionProcess>, ContainerError> {
+        let pool = &self.db().pool;
+        let workspace = Workspace::find_by_id(pool, workspace_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace_id).await?;
+        let executor_actions = dev_server_actions(&repos);
+        if executor_actions.is_empty() {
+            return Err(ContainerError::NoDevServer(workspace_id));
+        }
+
+        for dev_server in
+            ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace_id).await?
+        {
+            self.stop_execution(&dev_server, ExecutionProcessStatus::Killed)
+                .await?;
+        }
+
+        let session = match Session::find_latest_by_workspace_id(pool, workspace_id).await? {
+            Some(session) => session,
+            None => {
+                Session::create(
+                    pool,
+                    &CreateSession {
+                        executor: Some("dev-server".to_string()),
+                    },
+                    Uuid::new_v4(),
+                    workspace_id,
+                )
+                .await?
+            }
+        };
+
+        let mut execution_processes = Vec::new();
+        for executor_action in executor_actions {
+            execution_processes.push(
+                self.start_execution(
+                    &workspace,
+                    &session,
+                    &executor_action,
+                    &ExecutionProcessRunReason::DevServer,
+                )
+                .await?,
+            );
+        }
+        Ok(execution_processes)
+    }
+
     async fn ensure_container_exists(
         &self,
         workspace: &Workspace,
@@ -591,9 +769,25 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
-    async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
+    /// How full an agent's context window gets before its logs warn about it,
+    /// `None` when the warning is turned off
+    async fn context_warning_threshold(&self) -> Option<u8>;
+
+    /// Prefix for task branches in `project`: its own if set, else the global one
+    async fn branch_prefix_for_project(&self, project: &Project) -> String {
+        match &project.git_branch_prefix {
+            Some(prefix) => prefix.clone(),
+            None => self.git_branch_prefix().await,
+        }
+    }
+
+    async fn git_branch_from_workspace(
+        &self,
+        workspace_id: &Uuid,
+        task_title: &str,
+        prefix: &str,
+    ) -> String {
         let task_title_id = git_branch_id(task_title);
-        let prefix = self.git_branch_prefix().await;
 
         if prefix.is_empty() {
             format!("{}-{}", short_uuid(workspace_id), task_title_id)
@@ -690,7 +884,9 @@ pub trait ContainerService {
 
             // Create temporary store and populate
             // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
-            let temp_store = Arc::new(MsgStore::new());
+            let temp_store = Arc::new(
+                MsgStore::new().with_context_warning(self.context_warning_threshold().await),
+            );
             for msg in raw_messages {
                 if matches!(
                     msg,
@@ -1041,15 +1237,28 @@ pub trait ContainerService {
             run_reason: run_reason.clone(),
         };
 
+        // Coding agents wait for a free slot; dev servers and scripts don't count
+        let process_id = Uuid::new_v4();
+        let queued = run_reason == &ExecutionProcessRunReason::CodingAgent
+            && !self.execution_slots().try_acquire(process_id);
+        let status = if queued {
+            ExecutionProcessStatus::Queued
+        } else {
+            ExecutionProcessStatus::Running
+        };
         let execution_process = ExecutionProcess::create(
             &self.db().pool,
             &create_execution_process,
-            Uuid::new_v4(),
+            process_id,
+            status,
             &repo_states,
         )
-        .await?;
+        .await
+        .inspect_err(|_| self.execution_slots().release(process_id))?;
 
-        Workspace::set_archived(&self.db().pool, workspace.id, false).await?;
+        Workspace::set_archived(&self.db().pool, workspace.id, false)
+            .await
+            .inspect_err(|_| self.execution_slots().release(process_id))?;
 
         if let Some(prompt) = match executor_action.typ() {
             ExecutorActionType::CodingAgentInitialRequest(coding_agent_request) => {
@@ -1075,13 +1284,42 @@ pub trait ContainerService {
                 &create_coding_agent_turn,
                 coding_agent_turn_id,
             )
-            .await?;
+            .await
+            .inspect_err(|_| self.execution_slots().release(process_id))?;
         }
 
+        if queued {
+            tracing::info!(
+                "Concurrent execution limit reached, queueing execution process {}",
+                execution_process.id
+            );
+            self.queue_execution(
+                workspace.clone(),
+                execution_process.clone(),
+                executor_action.clone(),
+            );
+            return Ok(execution_process);
+        }
+
+        self.launch_execution(workspace, &execution_process, executor_action)
+            .await?;
+        Ok(execution_process)
+    }
+
+    /// Spawn the process for an already-recorded execution and start streaming
+    /// its logs. On failure the process is marked failed and its slot released.
+    async fn launch_execution(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) -> Result<(), ContainerError> {
         if let Err(start_error) = self
-            .start_execution_inner(workspace, &execution_process, executor_action)
+            .start_execution_inner(workspace, execution_process, executor_action)
             .await
         {
+            self.execution_slots().release(execution_process.id);
+
             // Mark process as failed
             if let Err(update_error) = ExecutionProcess::update_completion(
                 &self.db().pool,
@@ -1097,7 +1335,7 @@ pub trait ContainerService {
                     update_error
                 );
             }
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await?;
+            Task::update_status(&self.db().pool, workspace.task_id, TaskStatus::InReview).await?;
 
             // Emit stderr error message
             let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
@@ -1179,7 +1417,7 @@ pub trait ContainerService {
         let db_stream_handle = self.spawn_stream_raw_logs_to_db(&execution_process.id);
         self.store_db_stream_handle(execution_process.id, db_stream_handle)
             .await;
-        Ok(execution_process)
+        Ok(())
     }
 
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
@@ -1217,3 +1455,75 @@ pub trait ContainerService {
         Ok(())
     }
 }
+
+/// One dev server script per repo with a non-empty `dev_server_script`, run
+/// from that repo's directory in the workspace
+pub fn dev_server_actions(repos: &[Repo]) -> Vec<ExecutorAction> {
+    repos
+        .iter()
+        .filter_map(|repo| {
+            let script = repo.dev_server_script.as_ref().filter(|s| !s.is_empty())?;
+            Some(ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: script.clone(),
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::DevServer,
+                    working_dir: Some(repo.name.clone()),
+                }),
+                None,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn repo(name: &str, dev_server_script: Option<&str>) -> Repo {
+        Repo {
+            id: Uuid::new_v4(),
+            path: PathBuf::from("/tmp").join(name),
+            name: name.to_string(),
+            display_name: name.to_string(),
+            setup_script: None,
+            cleanup_script: None,
+            copy_files: None,
+            parallel_setup_script: false,
+            dev_server_script: dev_server_script.map(str::to_string),
+            default_target_branch: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn dev_server_actions_start_one_server_per_configured_repo() {
+        let repos = [
+            repo("web", Some("npm run dev")),
+            repo("docs", None),
+            repo("api", Some("cargo run")),
+            repo("infra", Some("")),
+        ];
+
+        let started: Vec<_> = dev_server_actions(&repos)
+            .into_iter()
+            .map(|action| match action.typ() {
+                ExecutorActionType::ScriptRequest(request) => {
+                    assert!(matches!(request.context, ScriptContext::DevServer));
+                    (request.working_dir.clone().unwrap(), request.script.clone())
+                }
+                other => panic!("unexpected action {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            started,
+            [
+                ("web".to_string(), "npm run dev".to_string()),
+                ("api".to_string(), "cargo run".to_string()),
+            ]
+        );
+    }
+}
@@ -222,40 +222,25 @@ impl DiffStreamManager {
         self.cumulative.store(0, Ordering::Relaxed);
         self.full_sent.write().unwrap().clear();
 
-        let diffs = self.fetch_diffs().await?;
-        self.send_diffs(diffs).await?;
+        self.send_diffs().await?;
 
         Ok(())
     }
 
-    async fn fetch_diffs(&self) -> Result<Vec<Diff>, DiffStreamError> {
-        let git = self.args.git_service.clone();
-        let worktree = self.args.worktree_path.clone();
-        let base = self.current_base_commit.clone();
-        let stats_only = self.args.stats_only;
-        let cumulative = self.cumulative.clone();
-
-        tokio::task::spawn_blocking(move || {
-            let diffs = git.get_diffs(
-                DiffTarget::Worktree {
-                    worktree_path: &worktree,
-                    base_commit: &base,
-                },
-                None,
-            )?;
+    /// Send the full diff against the base, one file at a time as each is computed
+    async fn send_diffs(&self) -> Result<(), DiffStreamError> {
+        let mut diffs = self.args.git_service.stream_diffs(
+            DiffTarget::Worktree {
+                worktree_path: &self.args.worktree_path,
+                base_commit: &self.current_base_commit,
+            },
+            None,
+        );
 
-            let mut processed_diffs = Vec::with_capacity(diffs.len());
-            for mut diff in diffs {
-                apply_stream_omit_policy(&mut diff, &cumulative, stats_only);
-                processed_diffs.push(diff);
-            }
-            Ok(processed_diffs)
-        })
-        .await?
-    }
+        while let Some(diff) = diffs.next().await {
+            let mut diff = diff?;
+            apply_stream_omit_policy(&mut diff, &self.cumulative, self.args.stats_only);
 
-    async fn send_diffs(&self, diffs: Vec<Diff>) -> Result<(), DiffStreamError> {
-        for mut diff in diffs {
             let raw_path = GitService::diff_path(&diff);
 
             {
@@ -214,3 +214,45 @@ pub mod scratch_patch {
         })])
     }
 }
+
+/// Helper functions for creating fetch progress patches.
+/// All patches use path "/fetch_progress" - clients tell fetches apart by the
+/// workspace and repo ids in the value.
+pub mod fetch_progress_patch {
+    use git::FetchProgress;
+
+    use super::*;
+
+    const FETCH_PROGRESS_PATH: &str = "/fetch_progress";
+
+    /// Create patch reporting how far a workspace repo's fetch has got
+    pub fn replace(workspace_id: Uuid, repo_id: Uuid, progress: &FetchProgress) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: FETCH_PROGRESS_PATH
+                .try_into()
+                .expect("Fetch progress path should be valid"),
+            value: serde_json::json!({
+                "workspace_id": workspace_id,
+                "repo_id": repo_id,
+                "progress": progress,
+                "done": false
+            }),
+        })])
+    }
+
+    /// Create patch marking a workspace repo's fetch finished, whether or not
+    /// it succeeded
+    pub fn done(workspace_id: Uuid, repo_id: Uuid) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: FETCH_PROGRESS_PATH
+                .try_into()
+                .expect("Fetch progress path should be valid"),
+            value: serde_json::json!({
+                "workspace_id": workspace_id,
+                "repo_id": repo_id,
+                "progress": null,
+                "done": true
+            }),
+        })])
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use serde::Serialize;
+use tokio::sync::Notify;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ExecutionSlotsStatus {
+    /// `None` when the number of concurrent coding agents is unbounded
+    pub max_concurrent: Option<u32>,
+    pub running: u32,
+    /// Execution processes waiting for a slot, in start order
+    pub queued_execution_process_ids: Vec<Uuid>,
+}
+
+/// Counting semaphore bounding how many coding agent processes run at once.
+/// Slots are keyed by execution process so they can be released from wherever
+/// the process ends (exit monitor, stop, failed start). The limit can change at
+/// runtime; lowering it never stops running processes, it only delays new ones.
+#[derive(Debug, Clone)]
+pub struct ExecutionSlots {
+    inner: Arc<SlotsInner>,
+}
+
+#[derive(Debug)]
+struct SlotsInner {
+    state: Mutex<SlotsState>,
+    released: Notify,
+}
+
+#[derive(Debug, Default)]
+struct SlotsState {
+    max_concurrent: Option<u32>,
+    running: HashSet<Uuid>,
+    queued: VecDeque<Uuid>,
+}
+
+impl SlotsState {
+    fn has_free_slot(&self) -> bool {
+        self.max_concurrent
+            .is_none_or(|max| self.running.len() < max as usize)
+    }
+}
+
+impl ExecutionSlots {
+    pub fn new(max_concurrent: Option<u32>) -> Self {
+        Self {
+            inner: Arc::new(SlotsInner {
+                state: Mutex::new(SlotsState {
+                    max_concurrent,
+                    ..Default::default()
+                }),
+                released: Notify::new(),
+            }),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, SlotsState> {
+        self.inner.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set_max_concurrent(&self, max_concurrent: Option<u32>) {
+        self.state().max_concurrent = max_concurrent;
+        self.inner.released.notify_waiters();
+    }
+
+    /// Takes a slot for `execution_id` if one is free and nothing is queued
+    /// ahead of it.
+    pub fn try_acquire(&self, execution_id: Uuid) -> bool {
+        let mut state = self.state();
+        if state.queued.is_empty() && state.has_free_slot() {
+            state.running.insert(execution_id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Waits for a slot, first come first served. Returns `false` if the wait
+    /// was cancelled with [`Self::cancel_queued`].
+    pub async fn acquire(&self, execution_id: Uuid) -> bool {
+        if self.try_acquire(execution_id) {
+            return true;
+        }
+        self.state().queued.push_back(execution_id);
+
+        loop {
+            let released = self.inner.released.notified();
+            tokio::pin!(released);
+            released.as_mut().enable();
+
+            {
+                let mut state = self.state();
+                match state.queued.front() {
+                    None => return false,
+                    Some(front) if *front == execution_id => {
+                        if state.has_free_slot() {
+                            state.queued.pop_front();
+                            state.running.insert(execution_id);
+                            // The next in line may fit as well
+                            self.inner.released.notify_waiters();
+                            return true;
+                        }
+                    }
+                    Some(_) if !state.queued.contains(&execution_id) => return false,
+                    Some(_) => {}
+                }
+            }
+
+            released.await;
+        }
+    }
+
+    /// Removes a waiting execution from the queue. Returns whether it was queued.
+    pub fn cancel_queued(&self, execution_id: Uuid) -> bool {
+        let mut state = self.state();
+        let Some(pos) = state.queued.iter().position(|id| *id == execution_id) else {
+            return false;
+        };
+        state.queued.remove(pos);
+        drop(state);
+        self.inner.released.notify_waiters();
+        true
+    }
+
+    /// Frees the slot held by `execution_id`, if any. Safe to call repeatedly.
+    pub fn release(&self, execution_id: Uuid) {
+        if self.state().running.remove(&execution_id) {
+            self.inner.released.notify_waiters();
+        }
+    }
+
+    pub fn status(&self) -> ExecutionSlotsStatus {
+        let state = self.state();
+        ExecutionSlotsStatus {
+            max_concurrent: state.max_concurrent,
+            running: state.running.len() as u32,
+            queued_execution_process_ids: state.queued.iter().copied().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::timeout;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn queues_beyond_the_limit_until_a_slot_is_released() {
+        let slots = ExecutionSlots::new(Some(1));
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+
+        assert!(slots.try_acquire(first));
+        assert!(!slots.try_acquire(second));
+
+        let waiter = tokio::spawn({
+            let slots = slots.clone();
+            async move { slots.acquire(second).await }
+        });
+        tokio::task::yield_now().await;
+        assert_eq!(slots.status().queued_execution_process_ids, vec![second]);
+
+        slots.release(first);
+        assert!(
+            timeout(Duration::from_secs(1), waiter)
+                .await
+                .unwrap()
+                .unwrap()
+        );
+        let status = slots.status();
+        assert_eq!(status.running, 1);
+        assert!(status.queued_execution_process_ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelled_waiters_give_up() {
+        let slots = ExecutionSlots::new(Some(0));
+        let id = Uuid::new_v4();
+
+        let waiter = tokio::spawn({
+            let slots = slots.clone();
+            async move { slots.acquire(id).await }
+        });
+        tokio::task::yield_now().await;
+
+        assert!(slots.cancel_queued(id));
+        assert!(
+            !timeout(Duration::from_secs(1), waiter)
+                .await
+                .unwrap()
+                .unwrap()
+        );
+        assert!(!slots.cancel_queued(id));
+    }
+
+    #[tokio::test]
+    async fn raising_the_limit_admits_waiters() {
+        let slots = ExecutionSlots::new(Some(0));
+        let id = Uuid::new_v4();
+
+        let waiter = tokio::spawn({
+            let slots = slots.clone();
+            async move { slots.acquire(id).await }
+        });
+        tokio::task::yield_now().await;
+
+        slots.set_max_concurrent(None);
+        assert!(
+            timeout(Duration::from_secs(1), waiter)
+                .await
+                .unwrap()
+                .unwrap()
+        );
+    }
+}
@@ -37,17 +37,9 @@ pub struct FileRanker {
     git_service: GitService,
 }
 
-impl Default for FileRanker {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl FileRanker {
-    pub fn new() -> Self {
-        Self {
-            git_service: GitService::new(),
-        }
+    pub fn new(git_service: GitService) -> Self {
+        Self { git_service }
     }
 
     /// Get file statistics for a repository, using cache when possible
@@ -4,7 +4,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use db::models::{
     project::{Project, SearchMatchType, SearchResult},
     project_repo::ProjectRepo,
@@ -96,10 +96,12 @@ pub struct FileSearchCache {
     file_ranker: FileRanker,
     build_queue: mpsc::UnboundedSender<PathBuf>,
     watchers: DashMap<PathBuf, RecommendedWatcher>,
+    /// Repos whose working tree changed since their index was built
+    dirty: DashSet<PathBuf>,
 }
 
 impl FileSearchCache {
-    pub fn new() -> Self {
+    pub fn new(git_service: GitService) -> Self {
         let (build_sender, build_receiver) = mpsc::unbounded_channel();
 
         // Create cache with 100MB limit and 1 hour TTL
@@ -109,8 +111,7 @@ impl FileSearchCache {
             .build();
 
         let cache_for_worker = cache.clone();
-        let git_service = GitService::new();
-        let file_ranker = FileRanker::new();
+        let file_ranker = FileRanker::new(git_service.clone());
 
         // Spawn background worker
         let worker_git_service = git_service.clone();
@@ -131,6 +132,7 @@ impl FileSearchCache {
             file_ranker,
             build_queue: build_sender,
             watchers: DashMap::new(),
+            dirty: DashSet::new(),
         }
     }
 
@@ -143,6 +145,11 @@ impl FileSearchCache {
     ) -> Result<Vec<SearchResult>, CacheError> {
         let repo_path_buf = repo_path.to_path_buf();
 
+        // A dirty entry is dropped so the rebuild below replaces it
+        if self.dirty.remove(&repo_path_buf).is_some() {
+            self.cache.invalidate(&repo_path_buf).await;
+        }
+
         // Check if we have a valid cache entry
         if let Some(cached) = self.cache.get(&repo_path_buf).await
             && let Ok(head_info) = self.git_service.get_head_info(&repo_path_buf)
@@ -160,6 +167,13 @@ impl FileSearchCache {
         Err(CacheError::Miss)
     }
 
+    /// Mark the index for a searched path (a workspace worktree or a repo checkout) as
+    /// stale after a git operation changed its files (commit, reset, merge, rebase).
+    /// Only that path is rebuilt, on its next search.
+    pub fn mark_dirty(&self, repo_path: &Path) {
+        self.dirty.insert(repo_path.to_path_buf());
+    }
+
     /// Pre-warm cache for given repositories
     pub async fn warm_repos(&self, repo_paths: Vec<PathBuf>) -> Result<(), String> {
         for repo_path in repo_paths {
@@ -590,6 +604,7 @@ impl FileSearchCache {
                 file_ranker: file_ranker.clone(),
                 build_queue: mpsc::unbounded_channel().0, // Dummy sender
                 watchers: DashMap::new(),
+                dirty: DashSet::new(),
             };
 
             match cache_builder.build_repo_cache(&repo_path).await {
@@ -661,9 +676,3 @@ impl FileSearchCache {
         Ok(())
     }
 }
-
-impl Default for FileSearchCache {
-    fn default() -> Self {
-        Self::new()
-    }
-}
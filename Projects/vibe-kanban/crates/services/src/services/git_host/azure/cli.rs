@@ -15,7 +15,13 @@ use serde::Deserialize;
 use thiserror::Error;
 use utils::shell::resolve_executable_path_blocking;
 
-use crate::services::git_host::types::{CreatePrRequest, UnifiedPrComment};
+use crate::services::git_host::{
+    cli_command::{AuthCheck, cli_timeout, has_token_env, output_with_timeout},
+    types::{CreatePrRequest, ReviewThread, ReviewThreadComment, UnifiedPrComment},
+};
+
+/// Personal access token read by the `azure-devops` extension
+const TOKEN_ENV_VARS: &[&str] = &["AZURE_DEVOPS_EXT_PAT"];
 
 #[derive(Debug, Clone)]
 pub struct AzureRepoInfo {
@@ -56,6 +62,8 @@ struct AzThreadsResponse {
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct AzThread {
+    id: Option<i64>,
+    status: Option<String>,
     comments: Option<Vec<AzThreadComment>>,
     thread_context: Option<AzThreadContext>,
 }
@@ -65,6 +73,21 @@ struct AzThread {
 struct AzThreadContext {
     file_path: Option<String>,
     right_file_start: Option<AzFilePosition>,
+    left_file_start: Option<AzFilePosition>,
+}
+
+impl AzThreadContext {
+    /// Line a thread is anchored to and its diff side, named as on GitHub.
+    /// Threads on the new file (`rightFileStart`) take precedence.
+    fn position(&self) -> (Option<i64>, Option<&'static str>) {
+        let right = self.right_file_start.as_ref().and_then(|p| p.line);
+        let left = self.left_file_start.as_ref().and_then(|p| p.line);
+        match (right, left) {
+            (Some(line), _) => (Some(line), Some("RIGHT")),
+            (None, Some(line)) => (Some(line), Some("LEFT")),
+            (None, None) => (None, None),
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -118,11 +141,13 @@ pub enum AzCliError {
 }
 
 #[derive(Debug, Clone, Default)]
-pub struct AzCli;
+pub struct AzCli {
+    auth_check: AuthCheck,
+}
 
 impl AzCli {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
     }
 
     /// Ensure the Azure CLI binary is discoverable.
@@ -149,9 +174,22 @@ impl AzCli {
         }
         tracing::debug!("Running Azure CLI command: {:?} {:?}", az, cmd.get_args());
 
-        let output = cmd
-            .output()
-            .map_err(|err| AzCliError::CommandFailed(err.to_string()))?;
+        // With stdin closed, az errors out instead of prompting. A PAT in
+        // AZURE_DEVOPS_EXT_PAT is picked up by the devops extension on its own.
+        if !has_token_env(TOKEN_ENV_VARS)
+            && let Err(status) = self.auth_check.ensure(|| {
+                let mut check = Command::new(&az);
+                check.args(["account", "show"]);
+                check
+            })
+        {
+            return Err(AzCliError::AuthFailed(format!(
+                "{status}; run `az login` or set AZURE_DEVOPS_EXT_PAT"
+            )));
+        }
+
+        let output = output_with_timeout(&mut cmd, cli_timeout())
+            .map_err(|err| AzCliError::CommandFailed(format!("az {err}")))?;
 
         if output.status.success() {
             return Ok(String::from_utf8_lossy(&output.stdout).to_string());
@@ -371,6 +409,28 @@ impl AzCli {
         repo_id: &str,
         pr_id: i64,
     ) -> Result<Vec<UnifiedPrComment>, AzCliError> {
+        let raw = self.invoke_pr_threads(organization_url, project_id, repo_id, pr_id)?;
+        Self::parse_pr_threads(&raw)
+    }
+
+    pub fn get_pr_review_threads(
+        &self,
+        organization_url: &str,
+        project_id: &str,
+        repo_id: &str,
+        pr_id: i64,
+    ) -> Result<Vec<ReviewThread>, AzCliError> {
+        let raw = self.invoke_pr_threads(organization_url, project_id, repo_id, pr_id)?;
+        Self::parse_review_threads(&raw)
+    }
+
+    fn invoke_pr_threads(
+        &self,
+        organization_url: &str,
+        project_id: &str,
+        repo_id: &str,
+        pr_id: i64,
+    ) -> Result<String, AzCliError> {
         let mut args: Vec<OsString> = Vec::with_capacity(16);
         args.push(OsString::from("devops"));
         args.push(OsString::from("invoke"));
@@ -389,8 +449,7 @@ impl AzCli {
         args.push(OsString::from("--output"));
         args.push(OsString::from("json"));
 
-        let raw = self.run(args, None)?;
-        Self::parse_pr_threads(&raw)
+        self.run(args, None)
     }
 
     /// Parse PR URL to extract organization and PR ID.
@@ -474,12 +533,16 @@ impl AzCli {
         }
     }
 
-    fn parse_pr_threads(raw: &str) -> Result<Vec<UnifiedPrComment>, AzCliError> {
+    fn parse_threads_response(raw: &str) -> Result<Vec<AzThread>, AzCliError> {
         // REST API returns { "value": [...threads...] } wrapper
         let response: AzThreadsResponse = serde_json::from_str(raw.trim()).map_err(|e| {
             AzCliError::UnexpectedOutput(format!("Failed to parse threads: {e}; raw: {raw}"))
         })?;
-        let threads = response.value;
+        Ok(response.value)
+    }
+
+    fn parse_pr_threads(raw: &str) -> Result<Vec<UnifiedPrComment>, AzCliError> {
+        let threads = Self::parse_threads_response(raw)?;
 
         let mut comments = Vec::new();
 
@@ -488,11 +551,11 @@ impl AzCli {
                 .thread_context
                 .as_ref()
                 .and_then(|c| c.file_path.clone());
-            let line = thread
+            let (line, side) = thread
                 .thread_context
                 .as_ref()
-                .and_then(|c| c.right_file_start.as_ref())
-                .and_then(|p| p.line);
+                .map(AzThreadContext::position)
+                .unwrap_or_default();
 
             if let Some(thread_comments) = thread.comments {
                 for c in thread_comments {
@@ -523,7 +586,7 @@ impl AzCli {
                             url: None,
                             path: path.clone(),
                             line,
-                            side: None,
+                            side: side.map(str::to_string),
                             diff_hunk: None,
                         });
                     } else {
@@ -544,6 +607,60 @@ impl AzCli {
         Ok(comments)
     }
 
+    fn parse_review_threads(raw: &str) -> Result<Vec<ReviewThread>, AzCliError> {
+        let mut threads = Vec::new();
+
+        for thread in Self::parse_threads_response(raw)? {
+            let comments: Vec<ReviewThreadComment> = thread
+                .comments
+                .unwrap_or_default()
+                .into_iter()
+                // Skip system-generated comments (votes, pushes, status changes)
+                .filter(|c| c.comment_type.as_deref() != Some("system"))
+                .map(|c| ReviewThreadComment {
+                    id: c.id.unwrap_or(0).to_string(),
+                    author: c
+                        .author
+                        .and_then(|a| a.display_name)
+                        .unwrap_or_else(|| "unknown".to_string()),
+                    body: c.content.unwrap_or_default(),
+                    created_at: c
+                        .published_date
+                        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(Utc::now),
+                    url: None,
+                })
+                .collect();
+            if comments.is_empty() {
+                continue;
+            }
+
+            threads.push(ReviewThread {
+                id: thread.id.unwrap_or(0).to_string(),
+                path: thread
+                    .thread_context
+                    .as_ref()
+                    .and_then(|c| c.file_path.clone()),
+                line: thread.thread_context.as_ref().and_then(|c| c.position().0),
+                is_resolved: thread.status.as_deref().and_then(Self::map_thread_status),
+                comments,
+            });
+        }
+
+        Ok(threads)
+    }
+
+    /// Map Azure DevOps thread status to a resolved flag. `unknown` and
+    /// missing statuses carry no resolution state.
+    fn map_thread_status(status: &str) -> Option<bool> {
+        match status.to_lowercase().as_str() {
+            "active" | "pending" => Some(false),
+            "fixed" | "closed" | "wontfix" | "bydesign" => Some(true),
+            _ => None,
+        }
+    }
+
     /// Map Azure DevOps PR status to MergeStatus
     fn map_azure_status(status: &str) -> MergeStatus {
         match status.to_lowercase().as_str() {
@@ -670,6 +787,65 @@ mod tests {
         assert_eq!(org_url, "https://myorg.visualstudio.com");
     }
 
+    #[test]
+    fn test_parse_review_threads() {
+        let raw = r#"{"value": [
+            {"id": 1, "status": "active",
+             "threadContext": {"filePath": "/src/main.rs", "rightFileStart": {"line": 12}},
+             "comments": [{"id": 1, "author": {"displayName": "Ada"}, "content": "Nit",
+                           "publishedDate": "2024-01-01T00:00:00Z", "commentType": "text"}]},
+            {"id": 2, "status": "fixed",
+             "comments": [{"id": 1, "content": "Done", "commentType": "text"}]},
+            {"id": 3,
+             "comments": [{"id": 1, "content": "Policy updated", "commentType": "system"}]}
+        ]}"#;
+
+        let threads = AzCli::parse_review_threads(raw).unwrap();
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].path.as_deref(), Some("/src/main.rs"));
+        assert_eq!(threads[0].line, Some(12));
+        assert_eq!(threads[0].is_resolved, Some(false));
+        assert_eq!(threads[0].comments[0].author, "Ada");
+        assert_eq!(threads[1].is_resolved, Some(true));
+        assert_eq!(threads[1].comments[0].author, "unknown");
+    }
+
+    #[test]
+    fn test_parse_pr_threads_keeps_file_context() {
+        let raw = r#"{"value": [
+            {"id": 1, "threadContext": {"filePath": "/src/main.rs", "rightFileStart": {"line": 12}},
+             "comments": [{"id": 10, "author": {"displayName": "Ada"}, "content": "new side",
+                           "publishedDate": "2024-01-01T00:00:00Z", "commentType": "text"}]},
+            {"id": 2, "threadContext": {"filePath": "/src/lib.rs", "leftFileStart": {"line": 3}},
+             "comments": [{"id": 20, "content": "old side",
+                           "publishedDate": "2024-01-02T00:00:00Z", "commentType": "text"}]},
+            {"id": 3, "comments": [
+                {"id": 30, "content": "general", "publishedDate": "2024-01-03T00:00:00Z",
+                 "commentType": "text"},
+                {"id": 31, "content": "Policy updated", "commentType": "system"}]}
+        ]}"#;
+        let comments = AzCli::parse_pr_threads(raw).unwrap();
+        assert_eq!(comments.len(), 3);
+        match &comments[0] {
+            UnifiedPrComment::Review {
+                path, line, side, ..
+            } => {
+                assert_eq!(path, "/src/main.rs");
+                assert_eq!(*line, Some(12));
+                assert_eq!(side.as_deref(), Some("RIGHT"));
+            }
+            other => panic!("expected review comment, got {other:?}"),
+        }
+        match &comments[1] {
+            UnifiedPrComment::Review { line, side, .. } => {
+                assert_eq!(*line, Some(3));
+                assert_eq!(side.as_deref(), Some("LEFT"));
+            }
+            other => panic!("expected review comment, got {other:?}"),
+        }
+        assert!(matches!(comments[2], UnifiedPrComment::General { .. }));
+    }
+
     #[test]
     fn test_extract_organization_url_invalid() {
         assert!(AzCli::extract_organization_url("https://github.com/owner/repo").is_none());
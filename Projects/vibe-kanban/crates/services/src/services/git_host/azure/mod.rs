@@ -14,7 +14,9 @@ use tracing::info;
 
 use super::{
     GitHostProvider,
-    types::{CreatePrRequest, GitHostError, OpenPrInfo, ProviderKind, UnifiedPrComment},
+    types::{
+        CreatePrRequest, GitHostError, OpenPrInfo, ProviderKind, ReviewThread, UnifiedPrComment,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -53,7 +55,9 @@ impl From<AzCliError> for GitHostError {
             },
             AzCliError::CommandFailed(msg) => {
                 let lower = msg.to_ascii_lowercase();
-                if lower.contains("403") || lower.contains("forbidden") {
+                if GitHostError::is_rate_limit_message(&lower) {
+                    GitHostError::rate_limited_for_default_wait()
+                } else if lower.contains("403") || lower.contains("forbidden") {
                     GitHostError::InsufficientPermissions(msg.clone())
                 } else if lower.contains("404") || lower.contains("not found") {
                     GitHostError::RepoNotFoundOrNoAccess(msg.clone())
@@ -117,6 +121,7 @@ impl GitHostProvider for AzureDevOpsProvider {
                 .with_jitter(),
         )
         .when(|e: &GitHostError| e.should_retry())
+        .adjust(GitHostError::retry_delay)
         .notify(|err: &GitHostError, dur: Duration| {
             tracing::warn!(
                 "Azure DevOps API call failed, retrying after {:.2}s: {}",
@@ -149,6 +154,7 @@ impl GitHostProvider for AzureDevOpsProvider {
                 .with_jitter(),
         )
         .when(|err: &GitHostError| err.should_retry())
+        .adjust(GitHostError::retry_delay)
         .notify(|err: &GitHostError, dur: Duration| {
             tracing::warn!(
                 "Azure DevOps API call failed, retrying after {:.2}s: {}",
@@ -193,6 +199,7 @@ impl GitHostProvider for AzureDevOpsProvider {
                 .with_jitter(),
         )
         .when(|e: &GitHostError| e.should_retry())
+        .adjust(GitHostError::retry_delay)
         .notify(|err: &GitHostError, dur: Duration| {
             tracing::warn!(
                 "Azure DevOps API call failed, retrying after {:.2}s: {}",
@@ -236,6 +243,51 @@ impl GitHostProvider for AzureDevOpsProvider {
                 .with_jitter(),
         )
         .when(|e: &GitHostError| e.should_retry())
+        .adjust(GitHostError::retry_delay)
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "Azure DevOps API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn list_review_threads(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<ReviewThread>, GitHostError> {
+        let repo_info = self.get_repo_info(repo_path, remote_url).await?;
+
+        (|| async {
+            let cli = self.az_cli.clone();
+            let organization_url = repo_info.organization_url.clone();
+            let project_id = repo_info.project_id.clone();
+            let repo_id = repo_info.repo_id.clone();
+
+            let threads = task::spawn_blocking(move || {
+                cli.get_pr_review_threads(&organization_url, &project_id, &repo_id, pr_number)
+            })
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute Azure CLI for fetching review threads: {err}"
+                ))
+            })?;
+            threads.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .adjust(GitHostError::retry_delay)
         .notify(|err: &GitHostError, dur: Duration| {
             tracing::warn!(
                 "Azure DevOps API call failed, retrying after {:.2}s: {}",
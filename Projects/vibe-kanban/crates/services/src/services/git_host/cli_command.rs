@@ -0,0 +1,168 @@
+//! Process handling shared by the `gh` and `az` wrappers. Headless servers have
+//! nobody to answer a login prompt, so the CLIs run with stdin closed and are
+//! killed if they don't finish in time.
+
+use std::{
+    io::Read,
+    process::{Child, Command, Output, Stdio},
+    sync::{Arc, OnceLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+
+/// Overrides how long a single `gh`/`az` command may run, in seconds. The
+/// login check that precedes a wrapper's first command has its own, shorter cap.
+pub const CLI_TIMEOUT_ENV: &str = "VK_GIT_HOST_CLI_TIMEOUT_SECS";
+const DEFAULT_CLI_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// `gh auth status` validates the token against the API, so it needs some network
+/// headroom, but a missing login should still surface well before a command's cap.
+const AUTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum CliRunError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("timed out after {}s", .0.as_secs())]
+    TimedOut(Duration),
+}
+
+pub fn cli_timeout() -> Duration {
+    std::env::var(CLI_TIMEOUT_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_CLI_TIMEOUT)
+}
+
+/// Whether any of `vars` is set to a non-empty value.
+pub fn has_token_env(vars: &[&str]) -> bool {
+    vars.iter()
+        .any(|var| std::env::var(var).is_ok_and(|v| !v.trim().is_empty()))
+}
+
+/// Checks a CLI's stored login before its first command, so a missing login fails
+/// fast instead of after a command's full timeout. Clones share the result.
+#[derive(Debug, Clone, Default)]
+pub struct AuthCheck(Arc<OnceLock<()>>);
+
+impl AuthCheck {
+    /// Run `check` (e.g. `gh auth status`) unless it already passed. Returns the
+    /// check's error output when the login is missing. A check that doesn't finish
+    /// in time is not treated as a failure and is retried before the next command.
+    pub fn ensure(&self, check: impl FnOnce() -> Command) -> Result<(), String> {
+        if self.0.get().is_some() {
+            return Ok(());
+        }
+        let Ok(output) = output_with_timeout(&mut check(), AUTH_CHECK_TIMEOUT) else {
+            return Ok(());
+        };
+        if output.status.success() {
+            let _ = self.0.set(());
+            return Ok(());
+        }
+        // gh and glab print their status report on stderr, az on stdout
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Err(if stderr.is_empty() { stdout } else { stderr })
+    }
+}
+
+/// Like [`Command::output`], but with stdin closed and a deadline after which
+/// the process is killed.
+pub fn output_with_timeout(cmd: &mut Command, timeout: Duration) -> Result<Output, CliRunError> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain pipes on separate threads so a chatty process can't block on a full pipe
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let status = wait_with_deadline(&mut child, timeout)?;
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn wait_with_deadline(
+    child: &mut Child,
+    timeout: Duration,
+) -> Result<std::process::ExitStatus, CliRunError> {
+    let start = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if start.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(CliRunError::TimedOut(timeout));
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn drain(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kills_commands_that_outlive_the_timeout() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("5");
+        let start = Instant::now();
+        let result = output_with_timeout(&mut cmd, Duration::from_millis(200));
+        assert!(matches!(result, Err(CliRunError::TimedOut(_))));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn collects_output_of_commands_that_finish() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo out; echo err >&2"]);
+        let output = output_with_timeout(&mut cmd, Duration::from_secs(5)).unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "out\n");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "err\n");
+    }
+
+    #[test]
+    fn auth_check_reports_a_failed_login_and_remembers_a_good_one() {
+        let check = AuthCheck::default();
+        let failing = || {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", "echo 'not logged in' >&2; exit 1"]);
+            cmd
+        };
+        assert_eq!(check.ensure(failing), Err("not logged in".to_string()));
+
+        check
+            .ensure(|| {
+                let mut cmd = Command::new("sh");
+                cmd.args(["-c", "echo 'Logged in'"]);
+                cmd
+            })
+            .unwrap();
+        // Once passed, the check isn't run again
+        assert_eq!(check.clone().ensure(failing), Ok(()));
+    }
+}
@@ -2,19 +2,50 @@
 
 use super::types::ProviderKind;
 
+/// Host of an HTTPS, `ssh://` or scp-style (`git@host:path`) remote URL,
+/// lowercased and without user or port
+fn url_host(url_lower: &str) -> Option<&str> {
+    let authority = match url_lower.split_once("://") {
+        Some((_, rest)) => rest.split('/').next()?,
+        None => url_lower.split(':').next()?,
+    };
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+fn is_gitlab_host(url_lower: &str, gitlab_hosts: &[String]) -> bool {
+    url_host(url_lower).is_some_and(|host| {
+        gitlab_hosts
+            .iter()
+            .any(|h| h.trim().eq_ignore_ascii_case(host))
+    })
+}
+
 /// Detect the git hosting provider from a remote URL.
 ///
 /// Supports:
 /// - GitHub.com: `https://github.com/owner/repo` or `git@github.com:owner/repo.git`
 /// - GitHub Enterprise: URLs containing `github.` (e.g., `https://github.company.com/owner/repo`)
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo` or legacy `https://org.visualstudio.com/...`
-pub fn detect_provider_from_url(url: &str) -> ProviderKind {
+/// - GitLab.com: `https://gitlab.com/group/project` or `git@gitlab.com:group/project.git`
+/// - Self-hosted GitLab: any of `gitlab_hosts` from user config, e.g.
+///   `gitlab.example.com`
+pub fn detect_provider_from_url(url: &str, gitlab_hosts: &[String]) -> ProviderKind {
     let url_lower = url.to_lowercase();
 
+    // Explicitly configured hosts win over the name-based guesses below
+    if is_gitlab_host(&url_lower, gitlab_hosts) {
+        return ProviderKind::GitLab;
+    }
+
     if url_lower.contains("github.com") {
         return ProviderKind::GitHub;
     }
 
+    if url_lower.contains("gitlab.com") {
+        return ProviderKind::GitLab;
+    }
+
     // Check Azure patterns before GHE to avoid false positives
     if url_lower.contains("dev.azure.com")
         || url_lower.contains(".visualstudio.com")
@@ -42,10 +73,16 @@ pub fn detect_provider_from_url(url: &str) -> ProviderKind {
 /// - GitHub: `https://github.com/owner/repo/pull/123`
 /// - GitHub Enterprise: `https://github.company.com/owner/repo/pull/123`
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo/pullrequest/123`
+/// - GitLab: `https://gitlab.com/group/project/-/merge_requests/123`
 #[cfg(test)]
 fn detect_provider_from_pr_url(pr_url: &str) -> ProviderKind {
     let url_lower = pr_url.to_lowercase();
 
+    // /-/merge_requests/ is unique to GitLab, including self-hosted instances
+    if url_lower.contains("/-/merge_requests/") {
+        return ProviderKind::GitLab;
+    }
+
     // GitHub pattern: contains /pull/ in the path
     if url_lower.contains("/pull/") {
         // Could be github.com or GHE
@@ -60,7 +97,7 @@ fn detect_provider_from_pr_url(pr_url: &str) -> ProviderKind {
     }
 
     // Fall back to general URL detection
-    detect_provider_from_url(pr_url)
+    detect_provider_from_url(pr_url, &[])
 }
 
 #[cfg(test)]
@@ -70,11 +107,11 @@ mod tests {
     #[test]
     fn test_github_com_https() {
         assert_eq!(
-            detect_provider_from_url("https://github.com/owner/repo"),
+            detect_provider_from_url("https://github.com/owner/repo", &[]),
             ProviderKind::GitHub
         );
         assert_eq!(
-            detect_provider_from_url("https://github.com/owner/repo.git"),
+            detect_provider_from_url("https://github.com/owner/repo.git", &[]),
             ProviderKind::GitHub
         );
     }
@@ -82,7 +119,7 @@ mod tests {
     #[test]
     fn test_github_com_ssh() {
         assert_eq!(
-            detect_provider_from_url("git@github.com:owner/repo.git"),
+            detect_provider_from_url("git@github.com:owner/repo.git", &[]),
             ProviderKind::GitHub
         );
     }
@@ -90,15 +127,15 @@ mod tests {
     #[test]
     fn test_github_enterprise() {
         assert_eq!(
-            detect_provider_from_url("https://github.company.com/owner/repo"),
+            detect_provider_from_url("https://github.company.com/owner/repo", &[]),
             ProviderKind::GitHub
         );
         assert_eq!(
-            detect_provider_from_url("https://github.acme.corp/team/project"),
+            detect_provider_from_url("https://github.acme.corp/team/project", &[]),
             ProviderKind::GitHub
         );
         assert_eq!(
-            detect_provider_from_url("git@github.internal.io:org/repo.git"),
+            detect_provider_from_url("git@github.internal.io:org/repo.git", &[]),
             ProviderKind::GitHub
         );
     }
@@ -106,7 +143,7 @@ mod tests {
     #[test]
     fn test_azure_devops_https() {
         assert_eq!(
-            detect_provider_from_url("https://dev.azure.com/org/project/_git/repo"),
+            detect_provider_from_url("https://dev.azure.com/org/project/_git/repo", &[]),
             ProviderKind::AzureDevOps
         );
     }
@@ -114,7 +151,7 @@ mod tests {
     #[test]
     fn test_azure_devops_ssh() {
         assert_eq!(
-            detect_provider_from_url("git@ssh.dev.azure.com:v3/org/project/repo"),
+            detect_provider_from_url("git@ssh.dev.azure.com:v3/org/project/repo", &[]),
             ProviderKind::AzureDevOps
         );
     }
@@ -122,7 +159,7 @@ mod tests {
     #[test]
     fn test_azure_devops_legacy_visualstudio() {
         assert_eq!(
-            detect_provider_from_url("https://org.visualstudio.com/project/_git/repo"),
+            detect_provider_from_url("https://org.visualstudio.com/project/_git/repo", &[]),
             ProviderKind::AzureDevOps
         );
     }
@@ -131,19 +168,50 @@ mod tests {
     fn test_azure_devops_git_path() {
         // Any URL with /_git/ is Azure DevOps
         assert_eq!(
-            detect_provider_from_url("https://custom.domain.com/org/project/_git/repo"),
+            detect_provider_from_url("https://custom.domain.com/org/project/_git/repo", &[]),
             ProviderKind::AzureDevOps
         );
     }
 
     #[test]
-    fn test_unknown_provider() {
+    fn test_gitlab_com() {
+        assert_eq!(
+            detect_provider_from_url("https://gitlab.com/group/subgroup/repo", &[]),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_url("git@gitlab.com:group/repo.git", &[]),
+            ProviderKind::GitLab
+        );
+    }
+
+    #[test]
+    fn test_self_hosted_gitlab() {
+        let url = "https://code.gitlab-test.internal/team/repo";
+        assert_eq!(detect_provider_from_url(url, &[]), ProviderKind::Unknown);
+
+        let hosts = [" Code.GitLab-Test.Internal ".to_string()];
+        let detect = |url| detect_provider_from_url(url, &hosts);
+        assert_eq!(detect(url), ProviderKind::GitLab);
+        assert_eq!(
+            detect("ssh://git@code.gitlab-test.internal:2222/team/repo.git"),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect("git@code.gitlab-test.internal:team/repo.git"),
+            ProviderKind::GitLab
+        );
+        // Only the host counts, not a matching path
         assert_eq!(
-            detect_provider_from_url("https://gitlab.com/owner/repo"),
+            detect("https://bitbucket.org/code.gitlab-test.internal/repo"),
             ProviderKind::Unknown
         );
+    }
+
+    #[test]
+    fn test_unknown_provider() {
         assert_eq!(
-            detect_provider_from_url("https://bitbucket.org/owner/repo"),
+            detect_provider_from_url("https://bitbucket.org/owner/repo", &[]),
             ProviderKind::Unknown
         );
     }
@@ -160,6 +228,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_pr_url_gitlab() {
+        assert_eq!(
+            detect_provider_from_pr_url("https://git.example.com/group/repo/-/merge_requests/12"),
+            ProviderKind::GitLab
+        );
+    }
+
     #[test]
     fn test_pr_url_azure() {
         assert_eq!(
@@ -4,6 +4,7 @@
 //! the REST client does not cover well.
 
 use std::{
+    collections::HashMap,
     ffi::{OsStr, OsString},
     io::Write,
     path::Path,
@@ -17,16 +18,74 @@ use tempfile::NamedTempFile;
 use thiserror::Error;
 use utils::shell::resolve_executable_path_blocking;
 
-use crate::services::git_host::types::{
-    CreatePrRequest, OpenPrInfo, PrComment, PrCommentAuthor, PrReviewComment, ReviewCommentUser,
+use crate::services::git_host::{
+    cli_command::{AuthCheck, cli_timeout, has_token_env, output_with_timeout},
+    types::{
+        CreatePrRequest, DEFAULT_RATE_LIMIT_WAIT, GitHostError, HostPermission, OpenPrInfo,
+        PrComment, PrCommentAuthor, PrReviewComment, ReviewCommentUser, ReviewThread,
+        ReviewThreadComment,
+    },
 };
 
+/// Tokens `gh` picks up on its own, in its order of precedence
+const TOKEN_ENV_VARS: &[&str] = &["GH_TOKEN", "GITHUB_TOKEN"];
+
 #[derive(Debug, Clone)]
 pub struct GitHubRepoInfo {
     pub owner: String,
     pub repo_name: String,
 }
 
+/// What the token `gh` uses may do on one repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHubTokenAccess {
+    /// From `X-OAuth-Scopes`; `None` for fine-grained and GitHub App tokens,
+    /// which don't have scopes
+    pub scopes: Option<Vec<String>>,
+    pub private: bool,
+    pub can_read: bool,
+    /// Whether the repository's `permissions` grant push, which opening a PR
+    /// from a branch in the repository also needs
+    pub can_push: bool,
+}
+
+impl GitHubTokenAccess {
+    pub fn missing_permissions(&self) -> Vec<HostPermission> {
+        if !self.can_read {
+            return vec![HostPermission::RepoRead, HostPermission::PullRequestWrite];
+        }
+        // Classic tokens also need `repo`, or `public_repo` for public
+        // repositories. Fine-grained and App tokens have no scopes, so their
+        // repository permissions are all there is to go on.
+        let has_scope = self.scopes.as_ref().is_none_or(|scopes| {
+            scopes
+                .iter()
+                .any(|s| s == "repo" || (!self.private && s == "public_repo"))
+        });
+        let can_write_prs = self.can_push && has_scope;
+        if can_write_prs {
+            Vec::new()
+        } else {
+            vec![HostPermission::PullRequestWrite]
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GhRepoAccessResponse {
+    #[serde(default)]
+    private: bool,
+    permissions: Option<GhRepoPermissions>,
+}
+
+#[derive(Deserialize)]
+struct GhRepoPermissions {
+    #[serde(default)]
+    pull: bool,
+    #[serde(default)]
+    push: bool,
+}
+
 #[derive(Deserialize)]
 struct GhRepoViewResponse {
     owner: GhRepoOwner,
@@ -79,6 +138,151 @@ struct GhReviewCommentResponse {
     diff_hunk: String,
     #[serde(default)]
     author_association: String,
+    in_reply_to_id: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct GhNodes<T> {
+    #[serde(default = "Vec::new")]
+    nodes: Vec<T>,
+}
+
+/// One page of `gh api graphql --paginate` output for [`REVIEW_THREADS_QUERY`]
+#[derive(Deserialize)]
+struct GhReviewThreadsPage {
+    data: GhReviewThreadsData,
+}
+
+#[derive(Deserialize)]
+struct GhReviewThreadsData {
+    repository: Option<GhReviewThreadsRepository>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhReviewThreadsRepository {
+    pull_request: Option<GhReviewThreadsPullRequest>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhReviewThreadsPullRequest {
+    review_threads: GhNodes<GhReviewThreadNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhReviewThreadNode {
+    id: String,
+    is_resolved: bool,
+    path: Option<String>,
+    line: Option<i64>,
+    original_line: Option<i64>,
+    comments: GhThreadComments,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhThreadComments {
+    #[serde(default = "Vec::new")]
+    nodes: Vec<GhReviewThreadCommentNode>,
+    page_info: Option<GhPageInfo>,
+}
+
+impl GhThreadComments {
+    /// Cursor to continue from when the thread has more comments than one page
+    fn next_cursor(&self) -> Option<String> {
+        self.page_info
+            .as_ref()
+            .filter(|p| p.has_next_page)
+            .and_then(|p| p.end_cursor.clone())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhPageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+/// One page of `gh api graphql --paginate` output for [`THREAD_COMMENTS_QUERY`]
+#[derive(Deserialize)]
+struct GhThreadCommentsPage {
+    data: GhThreadCommentsData,
+}
+
+#[derive(Deserialize)]
+struct GhThreadCommentsData {
+    node: Option<GhThreadCommentsNode>,
+}
+
+#[derive(Deserialize)]
+struct GhThreadCommentsNode {
+    comments: GhThreadComments,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhReviewThreadCommentNode {
+    id: String,
+    author: Option<GhUserLogin>,
+    #[serde(default)]
+    body: String,
+    created_at: Option<DateTime<Utc>>,
+    url: Option<String>,
+}
+
+/// `$endCursor` and `pageInfo` let `gh api graphql --paginate` walk every page.
+const REVIEW_THREADS_QUERY: &str = r#"
+query($owner: String!, $name: String!, $number: Int!, $endCursor: String) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100, after: $endCursor) {
+        pageInfo { hasNextPage endCursor }
+        nodes {
+          id
+          isResolved
+          path
+          line
+          originalLine
+          comments(first: 100) {
+            pageInfo { hasNextPage endCursor }
+            nodes { id author { login } body createdAt url }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+/// The rest of a thread's comments once they overflow [`REVIEW_THREADS_QUERY`]'s
+/// first page, starting from the `$endCursor` passed in.
+const THREAD_COMMENTS_QUERY: &str = r#"
+query($id: ID!, $endCursor: String) {
+  node(id: $id) {
+    ... on PullRequestReviewThread {
+      comments(first: 100, after: $endCursor) {
+        pageInfo { hasNextPage endCursor }
+        nodes { id author { login } body createdAt url }
+      }
+    }
+  }
+}
+"#;
+
+/// Response of `gh api rate_limit`, keyed by resource (`core`, `graphql`, ...)
+#[derive(Deserialize)]
+struct GhRateLimitResponse {
+    resources: HashMap<String, GhRateLimitResource>,
+}
+
+#[derive(Deserialize)]
+struct GhRateLimitResource {
+    remaining: i64,
+    /// Unix timestamp
+    reset: i64,
 }
 
 #[derive(Deserialize)]
@@ -116,16 +320,20 @@ pub enum GhCliError {
     CommandFailed(String),
     #[error("GitHub CLI authentication failed: {0}")]
     AuthFailed(String),
+    #[error("GitHub API rate limit exceeded; resets at {reset_at}")]
+    RateLimited { reset_at: DateTime<Utc> },
     #[error("GitHub CLI returned unexpected output: {0}")]
     UnexpectedOutput(String),
 }
 
 #[derive(Debug, Clone, Default)]
-pub struct GhCli;
+pub struct GhCli {
+    auth_check: AuthCheck,
+}
 
 impl GhCli {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
     }
 
     /// Ensure the GitHub CLI binary is discoverable.
@@ -135,6 +343,36 @@ impl GhCli {
     }
 
     fn run<I, S>(&self, args: I, dir: Option<&Path>) -> Result<String, GhCliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        match self.run_command(args, dir) {
+            Err(GhCliError::RateLimited { reset_at }) => Err(GhCliError::RateLimited {
+                reset_at: self.rate_limit_reset().unwrap_or(reset_at),
+            }),
+            result => result,
+        }
+    }
+
+    /// When the exhausted rate limit resets, per `gh api rate_limit` (which
+    /// doesn't count against the limit). `None` for secondary rate limits,
+    /// which don't show up there.
+    fn rate_limit_reset(&self) -> Option<DateTime<Utc>> {
+        let raw = self.run_command(["api", "rate_limit"], None).ok()?;
+        Self::parse_rate_limit_reset(&raw)
+    }
+
+    fn parse_rate_limit_reset(raw: &str) -> Option<DateTime<Utc>> {
+        let resp: GhRateLimitResponse = serde_json::from_str(raw).ok()?;
+        resp.resources
+            .into_values()
+            .filter(|r| r.remaining == 0)
+            .filter_map(|r| DateTime::from_timestamp(r.reset, 0))
+            .max()
+    }
+
+    fn run_command<I, S>(&self, args: I, dir: Option<&Path>) -> Result<String, GhCliError>
     where
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
@@ -148,9 +386,25 @@ impl GhCli {
         for arg in args {
             cmd.arg(arg);
         }
-        let output = cmd
-            .output()
-            .map_err(|err| GhCliError::CommandFailed(err.to_string()))?;
+        // Never wait on an interactive prompt; a token or stored login must suffice
+        cmd.env("GH_PROMPT_DISABLED", "1");
+
+        if !has_token_env(TOKEN_ENV_VARS)
+            && let Err(status) = self.auth_check.ensure(|| {
+                let mut check = Command::new(&gh);
+                check
+                    .args(["auth", "status"])
+                    .env("GH_PROMPT_DISABLED", "1");
+                check
+            })
+        {
+            return Err(GhCliError::AuthFailed(format!(
+                "{status}; run `gh auth login` or set GH_TOKEN"
+            )));
+        }
+
+        let output = output_with_timeout(&mut cmd, cli_timeout())
+            .map_err(|err| GhCliError::CommandFailed(format!("gh {err}")))?;
 
         if output.status.success() {
             return Ok(String::from_utf8_lossy(&output.stdout).to_string());
@@ -174,6 +428,13 @@ impl GhCli {
             return Err(GhCliError::AuthFailed(stderr));
         }
 
+        // Primary and secondary rate limits. The reset time is filled in by `run`.
+        if GitHostError::is_rate_limit_message(&lower) {
+            return Err(GhCliError::RateLimited {
+                reset_at: Utc::now() + DEFAULT_RATE_LIMIT_WAIT,
+            });
+        }
+
         Err(GhCliError::CommandFailed(stderr))
     }
 
@@ -189,6 +450,47 @@ impl GhCli {
         Self::parse_repo_info_response(&raw)
     }
 
+    /// Inspect the token's access to `owner/repo` via the response headers and
+    /// `permissions` of `GET /repos/{owner}/{repo}`.
+    pub fn get_token_access(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<GitHubTokenAccess, GhCliError> {
+        let raw = self.run(["api", "--include", &format!("repos/{owner}/{repo}")], None)?;
+        Self::parse_token_access(&raw)
+    }
+
+    fn parse_token_access(raw: &str) -> Result<GitHubTokenAccess, GhCliError> {
+        let (headers, body) = raw
+            .split_once("\r\n\r\n")
+            .or_else(|| raw.split_once("\n\n"))
+            .ok_or_else(|| {
+                GhCliError::UnexpectedOutput("gh api response has no headers".to_string())
+            })?;
+        let scopes = headers.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim().eq_ignore_ascii_case("x-oauth-scopes").then(|| {
+                value
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+        });
+        let resp: GhRepoAccessResponse = serde_json::from_str(body.trim()).map_err(|e| {
+            GhCliError::UnexpectedOutput(format!("Failed to parse gh api repo response: {e}"))
+        })?;
+        Ok(GitHubTokenAccess {
+            scopes,
+            private: resp.private,
+            // Anonymous responses omit `permissions`; getting a response at
+            // all means the repository is readable
+            can_read: resp.permissions.as_ref().is_none_or(|p| p.pull),
+            can_push: resp.permissions.is_some_and(|p| p.push),
+        })
+    }
+
     fn parse_repo_info_response(raw: &str) -> Result<GitHubRepoInfo, GhCliError> {
         let resp: GhRepoViewResponse = serde_json::from_str(raw).map_err(|e| {
             GhCliError::UnexpectedOutput(format!("Failed to parse gh repo view response: {e}"))
@@ -338,6 +640,65 @@ impl GhCli {
         Self::parse_pr_review_comments(&raw)
     }
 
+    /// Review threads with resolution state, via the GraphQL API (REST has no
+    /// notion of threads).
+    pub fn get_pr_review_threads(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<Vec<ReviewThread>, GhCliError> {
+        let raw = self.run(
+            [
+                "api".to_string(),
+                "graphql".to_string(),
+                "--paginate".to_string(),
+                "-f".to_string(),
+                format!("query={REVIEW_THREADS_QUERY}"),
+                "-f".to_string(),
+                format!("owner={owner}"),
+                "-f".to_string(),
+                format!("name={repo}"),
+                "-F".to_string(),
+                format!("number={pr_number}"),
+            ],
+            None,
+        )?;
+        let mut threads = Vec::new();
+        for (mut thread, cursor) in Self::parse_review_threads(&raw)? {
+            if let Some(cursor) = cursor {
+                thread
+                    .comments
+                    .extend(self.get_thread_comments_after(&thread.id, &cursor)?);
+            }
+            threads.push(thread);
+        }
+        Ok(threads)
+    }
+
+    /// Comments of a review thread that come after `cursor`
+    fn get_thread_comments_after(
+        &self,
+        thread_id: &str,
+        cursor: &str,
+    ) -> Result<Vec<ReviewThreadComment>, GhCliError> {
+        let raw = self.run(
+            [
+                "api".to_string(),
+                "graphql".to_string(),
+                "--paginate".to_string(),
+                "-f".to_string(),
+                format!("query={THREAD_COMMENTS_QUERY}"),
+                "-f".to_string(),
+                format!("id={thread_id}"),
+                "-f".to_string(),
+                format!("endCursor={cursor}"),
+            ],
+            None,
+        )?;
+        Self::parse_thread_comments(&raw)
+    }
+
     pub fn pr_checkout(
         &self,
         repo_path: &Path,
@@ -510,7 +871,206 @@ impl GhCli {
                 side: c.side,
                 diff_hunk: c.diff_hunk,
                 author_association: c.author_association,
+                in_reply_to_id: c.in_reply_to_id,
             })
             .collect())
     }
+
+    /// Threads from every page, each with the cursor to fetch the rest of its
+    /// comments from when they didn't fit in the first page
+    fn parse_review_threads(raw: &str) -> Result<Vec<(ReviewThread, Option<String>)>, GhCliError> {
+        let mut threads = Vec::new();
+        // --paginate prints one JSON document per page
+        for page in serde_json::Deserializer::from_str(raw).into_iter::<GhReviewThreadsPage>() {
+            let page = page.map_err(|err| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Failed to parse review threads response: {err}; raw: {raw}"
+                ))
+            })?;
+            let Some(pr) = page.data.repository.and_then(|r| r.pull_request) else {
+                continue;
+            };
+            threads.extend(pr.review_threads.nodes.into_iter().map(|t| {
+                let cursor = t.comments.next_cursor();
+                let thread = ReviewThread {
+                    id: t.id,
+                    path: t.path,
+                    // `line` is null once the thread is outdated
+                    line: t.line.or(t.original_line),
+                    is_resolved: Some(t.is_resolved),
+                    comments: t
+                        .comments
+                        .nodes
+                        .into_iter()
+                        .map(Self::review_thread_comment)
+                        .collect(),
+                };
+                (thread, cursor)
+            }));
+        }
+        Ok(threads)
+    }
+
+    fn parse_thread_comments(raw: &str) -> Result<Vec<ReviewThreadComment>, GhCliError> {
+        let mut comments = Vec::new();
+        for page in serde_json::Deserializer::from_str(raw).into_iter::<GhThreadCommentsPage>() {
+            let page = page.map_err(|err| {
+                GhCliError::UnexpectedOutput(format!(
+                    "Failed to parse review thread comments response: {err}; raw: {raw}"
+                ))
+            })?;
+            if let Some(node) = page.data.node {
+                comments.extend(
+                    node.comments
+                        .nodes
+                        .into_iter()
+                        .map(Self::review_thread_comment),
+                );
+            }
+        }
+        Ok(comments)
+    }
+
+    fn review_thread_comment(c: GhReviewThreadCommentNode) -> ReviewThreadComment {
+        ReviewThreadComment {
+            id: c.id,
+            author: c
+                .author
+                .and_then(|a| a.login)
+                .unwrap_or_else(|| "unknown".to_string()),
+            body: c.body,
+            created_at: c.created_at.unwrap_or_else(Utc::now),
+            url: c.url,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_access_reports_missing_pr_scope() {
+        let raw = "HTTP/2.0 200 OK\r\nContent-Type: application/json\r\nX-Oauth-Scopes: read:org, gist\r\n\r\n{\"private\":true,\"permissions\":{\"pull\":true,\"push\":true}}";
+        let access = GhCli::parse_token_access(raw).unwrap();
+        assert_eq!(
+            access.scopes,
+            Some(vec!["read:org".to_string(), "gist".to_string()])
+        );
+        assert_eq!(
+            access.missing_permissions(),
+            vec![HostPermission::PullRequestWrite]
+        );
+
+        let public_repo_scope = GitHubTokenAccess {
+            scopes: Some(vec!["public_repo".to_string()]),
+            private: false,
+            can_read: true,
+            can_push: true,
+        };
+        assert!(public_repo_scope.missing_permissions().is_empty());
+        let private = GitHubTokenAccess {
+            private: true,
+            ..public_repo_scope
+        };
+        assert_eq!(
+            private.missing_permissions(),
+            vec![HostPermission::PullRequestWrite]
+        );
+    }
+
+    #[test]
+    fn fine_grained_tokens_are_checked_against_repo_permissions() {
+        let raw = "HTTP/2.0 200 OK\nContent-Type: application/json\n\n{\"private\":true,\"permissions\":{\"pull\":false}}";
+        let access = GhCli::parse_token_access(raw).unwrap();
+        assert_eq!(access.scopes, None);
+        assert_eq!(
+            access.missing_permissions(),
+            vec![HostPermission::RepoRead, HostPermission::PullRequestWrite]
+        );
+
+        let read_only =
+            "HTTP/2.0 200 OK\n\n{\"private\":true,\"permissions\":{\"pull\":true,\"push\":false}}";
+        assert_eq!(
+            GhCli::parse_token_access(read_only)
+                .unwrap()
+                .missing_permissions(),
+            vec![HostPermission::PullRequestWrite]
+        );
+
+        let writable =
+            "HTTP/2.0 200 OK\n\n{\"private\":true,\"permissions\":{\"pull\":true,\"push\":true}}";
+        assert!(
+            GhCli::parse_token_access(writable)
+                .unwrap()
+                .missing_permissions()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn parse_rate_limit_reset_picks_exhausted_resource() {
+        let raw = r#"{"resources": {
+            "core": {"limit": 5000, "remaining": 0, "reset": 1700000600, "used": 5000},
+            "graphql": {"limit": 5000, "remaining": 12, "reset": 1700000900, "used": 4988},
+            "search": {"limit": 30, "remaining": 0, "reset": 1700000060, "used": 30}
+        }}"#;
+        assert_eq!(
+            GhCli::parse_rate_limit_reset(raw),
+            DateTime::from_timestamp(1700000600, 0)
+        );
+
+        let not_exhausted = r#"{"resources": {"core": {"remaining": 10, "reset": 1700000600}}}"#;
+        assert_eq!(GhCli::parse_rate_limit_reset(not_exhausted), None);
+    }
+
+    #[test]
+    fn parse_review_threads_across_pages() {
+        let page = |id: &str, resolved: bool| {
+            format!(
+                r#"{{"data":{{"repository":{{"pullRequest":{{"reviewThreads":{{
+                    "pageInfo":{{"hasNextPage":false,"endCursor":null}},
+                    "nodes":[{{"id":"{id}","isResolved":{resolved},"path":"src/lib.rs",
+                        "line":null,"originalLine":7,
+                        "comments":{{"nodes":[{{"id":"c-{id}","author":null,"body":"Fix",
+                            "createdAt":"2024-01-01T00:00:00Z","url":null}}]}}}}]}}}}}}}}}}"#
+            )
+        };
+        let raw = format!("{}\n{}", page("t1", true), page("t2", false));
+
+        let threads = GhCli::parse_review_threads(&raw).unwrap();
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].0.is_resolved, Some(true));
+        assert_eq!(threads[0].0.line, Some(7));
+        assert_eq!(threads[0].0.comments[0].author, "unknown");
+        assert_eq!(threads[0].1, None);
+        assert_eq!(threads[1].0.id, "t2");
+        assert_eq!(threads[1].0.is_resolved, Some(false));
+    }
+
+    #[test]
+    fn parse_review_threads_reports_where_long_threads_continue() {
+        let raw = r#"{"data":{"repository":{"pullRequest":{"reviewThreads":{
+            "pageInfo":{"hasNextPage":false,"endCursor":null},
+            "nodes":[{"id":"t1","isResolved":false,"path":null,"line":3,"originalLine":3,
+                "comments":{"pageInfo":{"hasNextPage":true,"endCursor":"c100"},
+                    "nodes":[{"id":"c1","author":{"login":"octocat"},"body":"First",
+                        "createdAt":"2024-01-01T00:00:00Z","url":null}]}}]}}}}}"#;
+        let threads = GhCli::parse_review_threads(raw).unwrap();
+        assert_eq!(threads[0].1.as_deref(), Some("c100"));
+
+        let rest = r#"{"data":{"node":{"comments":{
+            "pageInfo":{"hasNextPage":true,"endCursor":"c200"},
+            "nodes":[{"id":"c101","author":{"login":"octocat"},"body":"More",
+                "createdAt":"2024-01-02T00:00:00Z","url":null}]}}}}
+            {"data":{"node":{"comments":{
+            "pageInfo":{"hasNextPage":false,"endCursor":"c201"},
+            "nodes":[{"id":"c201","author":null,"body":"Last",
+                "createdAt":"2024-01-03T00:00:00Z","url":null}]}}}}"#;
+        let comments = GhCli::parse_thread_comments(rest).unwrap();
+        assert_eq!(
+            comments.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(),
+            ["c101", "c201"]
+        );
+    }
 }
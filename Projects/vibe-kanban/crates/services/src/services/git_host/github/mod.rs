@@ -14,7 +14,10 @@ use tracing::info;
 
 use super::{
     GitHostProvider,
-    types::{CreatePrRequest, GitHostError, OpenPrInfo, ProviderKind, UnifiedPrComment},
+    types::{
+        CreatePrRequest, GitHostError, HostPermission, OpenPrInfo, PrReviewComment, ProviderKind,
+        ReviewThread, ReviewThreadComment, UnifiedPrComment,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -79,6 +82,7 @@ impl GitHubProvider {
                 .with_jitter(),
         )
         .when(|e: &GitHostError| e.should_retry())
+        .adjust(GitHostError::retry_delay)
         .notify(|err: &GitHostError, dur: Duration| {
             tracing::warn!(
                 "GitHub API call failed, retrying after {:.2}s: {}",
@@ -123,6 +127,51 @@ impl GitHubProvider {
                 .with_jitter(),
         )
         .when(|e: &GitHostError| e.should_retry())
+        .adjust(GitHostError::retry_delay)
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn fetch_review_threads(
+        &self,
+        owner: &str,
+        repo: &str,
+        pr_number: i64,
+    ) -> Result<Vec<ReviewThread>, GitHostError> {
+        let cli = self.gh_cli.clone();
+        let owner = owner.to_string();
+        let repo = repo.to_string();
+
+        (|| async {
+            let cli = cli.clone();
+            let owner = owner.clone();
+            let repo = repo.clone();
+
+            let threads =
+                task::spawn_blocking(move || cli.get_pr_review_threads(&owner, &repo, pr_number))
+                    .await
+                    .map_err(|err| {
+                        GitHostError::PullRequest(format!(
+                            "Failed to execute GitHub CLI for fetching review threads: {err}"
+                        ))
+                    })?;
+            threads.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry() && !graphql_unavailable(e))
+        .adjust(GitHostError::retry_delay)
         .notify(|err: &GitHostError, dur: Duration| {
             tracing::warn!(
                 "GitHub API call failed, retrying after {:.2}s: {}",
@@ -134,10 +183,63 @@ impl GitHubProvider {
     }
 }
 
+/// GraphQL can be off-limits where REST still works: app or fine-grained tokens
+/// without GraphQL access, or GitHub Enterprise versions that lack review threads.
+/// Retrying won't help, but the REST fallback will.
+fn graphql_unavailable(err: &GitHostError) -> bool {
+    match err {
+        GitHostError::InsufficientPermissions(_) => true,
+        GitHostError::PullRequest(msg) | GitHostError::RepoNotFoundOrNoAccess(msg) => {
+            let lower = msg.to_ascii_lowercase();
+            lower.contains("graphql") || lower.contains("resource not accessible")
+        }
+        _ => false,
+    }
+}
+
+/// Rebuilds threads from REST review comments by following `in_reply_to_id`
+/// back to the root comment. REST doesn't expose resolution state.
+fn group_review_comments(comments: Vec<PrReviewComment>) -> Vec<ReviewThread> {
+    let mut threads: Vec<(i64, ReviewThread)> = Vec::new();
+    for c in comments {
+        let root_id = c.in_reply_to_id.unwrap_or(c.id);
+        let comment = ReviewThreadComment {
+            id: c.id.to_string(),
+            author: c.user.login,
+            body: c.body,
+            created_at: c.created_at,
+            url: Some(c.html_url),
+        };
+        match threads.iter_mut().find(|(id, _)| *id == root_id) {
+            Some((_, thread)) => thread.comments.push(comment),
+            None => threads.push((
+                root_id,
+                ReviewThread {
+                    id: root_id.to_string(),
+                    path: Some(c.path),
+                    line: c.line,
+                    is_resolved: None,
+                    comments: vec![comment],
+                },
+            )),
+        }
+    }
+    threads
+        .into_iter()
+        .map(|(_, mut thread)| {
+            thread.comments.sort_by_key(|c| c.created_at);
+            thread
+        })
+        .collect()
+}
+
 impl From<GhCliError> for GitHostError {
     fn from(error: GhCliError) -> Self {
         match &error {
             GhCliError::AuthFailed(msg) => GitHostError::AuthFailed(msg.clone()),
+            GhCliError::RateLimited { reset_at } => GitHostError::RateLimited {
+                reset_at: *reset_at,
+            },
             GhCliError::NotAvailable => GitHostError::CliNotInstalled {
                 provider: ProviderKind::GitHub,
             },
@@ -215,6 +317,7 @@ impl GitHostProvider for GitHubProvider {
                 .with_jitter(),
         )
         .when(|e: &GitHostError| e.should_retry())
+        .adjust(GitHostError::retry_delay)
         .notify(|err: &GitHostError, dur: Duration| {
             tracing::warn!(
                 "GitHub API call failed, retrying after {:.2}s: {}",
@@ -249,6 +352,7 @@ impl GitHostProvider for GitHubProvider {
                 .with_jitter(),
         )
         .when(|err: &GitHostError| err.should_retry())
+        .adjust(GitHostError::retry_delay)
         .notify(|err: &GitHostError, dur: Duration| {
             tracing::warn!(
                 "GitHub API call failed, retrying after {:.2}s: {}",
@@ -294,6 +398,7 @@ impl GitHostProvider for GitHubProvider {
                 .with_jitter(),
         )
         .when(|e: &GitHostError| e.should_retry())
+        .adjust(GitHostError::retry_delay)
         .notify(|err: &GitHostError, dur: Duration| {
             tracing::warn!(
                 "GitHub API call failed, retrying after {:.2}s: {}",
@@ -359,6 +464,35 @@ impl GitHostProvider for GitHubProvider {
         Ok(unified)
     }
 
+    async fn check_permissions(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+    ) -> Result<Vec<HostPermission>, GitHostError> {
+        let all_missing = vec![HostPermission::RepoRead, HostPermission::PullRequestWrite];
+        let repo_info = match self.get_repo_info(remote_url, repo_path).await {
+            Ok(info) => info,
+            Err(GitHostError::RepoNotFoundOrNoAccess(_)) => return Ok(all_missing),
+            Err(e) => return Err(e),
+        };
+
+        let cli = self.gh_cli.clone();
+        let access = task::spawn_blocking(move || {
+            cli.get_token_access(&repo_info.owner, &repo_info.repo_name)
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::Repository(format!(
+                "Failed to execute GitHub CLI for checking permissions: {err}"
+            ))
+        })?;
+        match access.map_err(GitHostError::from) {
+            Ok(access) => Ok(access.missing_permissions()),
+            Err(GitHostError::RepoNotFoundOrNoAccess(_)) => Ok(all_missing),
+            Err(e) => Err(e),
+        }
+    }
+
     async fn list_open_prs(
         &self,
         repo_path: &Path,
@@ -390,6 +524,7 @@ impl GitHostProvider for GitHubProvider {
                 .with_jitter(),
         )
         .when(|e: &GitHostError| e.should_retry())
+        .adjust(GitHostError::retry_delay)
         .notify(|err: &GitHostError, dur: Duration| {
             tracing::warn!(
                 "GitHub API call failed, retrying after {:.2}s: {}",
@@ -400,7 +535,60 @@ impl GitHostProvider for GitHubProvider {
         .await
     }
 
+    async fn list_review_threads(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<ReviewThread>, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+
+        match self
+            .fetch_review_threads(&repo_info.owner, &repo_info.repo_name, pr_number)
+            .await
+        {
+            Ok(threads) => Ok(threads),
+            Err(err) if graphql_unavailable(&err) => {
+                // REST still gives us the threads, just without resolution state
+                tracing::warn!(
+                    "Failed to fetch GitHub review threads via GraphQL, falling back to REST: {err}"
+                );
+                let review_comments = self
+                    .fetch_review_comments(
+                        &self.gh_cli,
+                        &repo_info.owner,
+                        &repo_info.repo_name,
+                        pr_number,
+                    )
+                    .await?;
+                Ok(group_review_comments(review_comments))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::GitHub
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_graphql_access_errors_fall_back_to_rest() {
+        assert!(graphql_unavailable(&GitHostError::InsufficientPermissions(
+            "HTTP 403".to_string()
+        )));
+        assert!(graphql_unavailable(&GitHostError::PullRequest(
+            "GraphQL: Resource not accessible by integration (repository.pullRequest)".to_string()
+        )));
+        assert!(!graphql_unavailable(&GitHostError::PullRequest(
+            "connection reset by peer".to_string()
+        )));
+        assert!(!graphql_unavailable(&GitHostError::AuthFailed(
+            "not logged in".to_string()
+        )));
+    }
+}
@@ -0,0 +1,652 @@
+//! Minimal helpers around the GitLab CLI (`glab`).
+//!
+//! This module provides low-level access to the GitLab CLI for merge request
+//! operations on gitlab.com and self-hosted GitLab instances.
+
+use std::{
+    ffi::{OsStr, OsString},
+    path::Path,
+    process::Command,
+};
+
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use serde::Deserialize;
+use thiserror::Error;
+use utils::shell::resolve_executable_path_blocking;
+
+use crate::services::git_host::{
+    cli_command::{AuthCheck, cli_timeout, has_token_env, output_with_timeout},
+    types::{CreatePrRequest, OpenPrInfo, ReviewThread, ReviewThreadComment, UnifiedPrComment},
+};
+
+/// Tokens `glab` picks up on its own, in its order of precedence
+const TOKEN_ENV_VARS: &[&str] = &["GITLAB_TOKEN", "GITLAB_ACCESS_TOKEN", "OAUTH_TOKEN"];
+
+#[derive(Debug, Clone)]
+pub struct GitLabRepoInfo {
+    /// Host of the GitLab instance, e.g. `gitlab.com`
+    pub host: String,
+    /// Full project path, including any subgroups (`group/subgroup/project`)
+    pub project_path: String,
+    /// Project URL; `glab --repo` accepts it and it carries the host
+    pub web_url: String,
+}
+
+impl GitLabRepoInfo {
+    /// Project path in the form the REST API expects in `projects/:id`
+    fn api_id(&self) -> String {
+        self.project_path.replace('/', "%2F")
+    }
+
+    pub fn mr_url(&self, mr_iid: i64) -> String {
+        format!("{}/-/merge_requests/{mr_iid}", self.web_url)
+    }
+}
+
+#[derive(Deserialize)]
+struct GlProjectResponse {
+    path_with_namespace: String,
+    web_url: String,
+}
+
+#[derive(Deserialize)]
+struct GlMrResponse {
+    iid: i64,
+    web_url: String,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    source_branch: String,
+    #[serde(default)]
+    target_branch: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    squash_commit_sha: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GlDiscussion {
+    id: String,
+    #[serde(default)]
+    individual_note: bool,
+    #[serde(default)]
+    notes: Vec<GlNote>,
+}
+
+#[derive(Deserialize)]
+struct GlNote {
+    id: i64,
+    #[serde(default)]
+    body: String,
+    author: Option<GlAuthor>,
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    system: bool,
+    #[serde(default)]
+    resolvable: bool,
+    #[serde(default)]
+    resolved: bool,
+    position: Option<GlPosition>,
+}
+
+#[derive(Deserialize)]
+struct GlAuthor {
+    username: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GlPosition {
+    new_path: Option<String>,
+    old_path: Option<String>,
+    new_line: Option<i64>,
+    old_line: Option<i64>,
+}
+
+impl GlNote {
+    fn author(&self) -> String {
+        self.author
+            .as_ref()
+            .and_then(|a| a.username.clone())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    fn path(&self) -> Option<String> {
+        let position = self.position.as_ref()?;
+        position.new_path.clone().or(position.old_path.clone())
+    }
+
+    fn line(&self) -> Option<i64> {
+        let position = self.position.as_ref()?;
+        position.new_line.or(position.old_line)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GlabCliError {
+    #[error("GitLab CLI (`glab`) executable not found or not runnable")]
+    NotAvailable,
+    #[error("GitLab CLI command failed: {0}")]
+    CommandFailed(String),
+    #[error("GitLab CLI authentication failed: {0}")]
+    AuthFailed(String),
+    #[error("GitLab CLI returned unexpected output: {0}")]
+    UnexpectedOutput(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GlabCli {
+    auth_check: AuthCheck,
+}
+
+impl GlabCli {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ensure the GitLab CLI binary is discoverable.
+    fn ensure_available(&self) -> Result<(), GlabCliError> {
+        resolve_executable_path_blocking("glab").ok_or(GlabCliError::NotAvailable)?;
+        Ok(())
+    }
+
+    fn run<I, S>(&self, args: I, dir: Option<&Path>) -> Result<String, GlabCliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.ensure_available()?;
+        let glab = resolve_executable_path_blocking("glab").ok_or(GlabCliError::NotAvailable)?;
+        let mut cmd = Command::new(&glab);
+        if let Some(d) = dir {
+            cmd.current_dir(d);
+        }
+        for arg in args {
+            cmd.arg(arg);
+        }
+        // Never wait on an interactive prompt; a token or stored login must suffice
+        cmd.env("NO_PROMPT", "1");
+
+        if !has_token_env(TOKEN_ENV_VARS)
+            && let Err(status) = self.auth_check.ensure(|| {
+                let mut check = Command::new(&glab);
+                check.args(["auth", "status"]).env("NO_PROMPT", "1");
+                check
+            })
+        {
+            return Err(GlabCliError::AuthFailed(format!(
+                "{status}; run `glab auth login` or set GITLAB_TOKEN"
+            )));
+        }
+
+        let output = output_with_timeout(&mut cmd, cli_timeout())
+            .map_err(|err| GlabCliError::CommandFailed(format!("glab {err}")))?;
+
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+
+        let lower = stderr.to_ascii_lowercase();
+        if lower.contains("401")
+            || lower.contains("unauthorized")
+            || lower.contains("glab auth login")
+            || lower.contains("no token")
+            || lower.contains("not authenticated")
+        {
+            return Err(GlabCliError::AuthFailed(stderr));
+        }
+
+        Err(GlabCliError::CommandFailed(stderr))
+    }
+
+    pub fn get_repo_info(
+        &self,
+        remote_url: &str,
+        repo_path: &Path,
+    ) -> Result<GitLabRepoInfo, GlabCliError> {
+        let raw = self.run(
+            ["repo", "view", remote_url, "--output", "json"],
+            Some(repo_path),
+        )?;
+        Self::parse_repo_info_response(&raw)
+    }
+
+    fn parse_repo_info_response(raw: &str) -> Result<GitLabRepoInfo, GlabCliError> {
+        let resp: GlProjectResponse = serde_json::from_str(raw.trim()).map_err(|e| {
+            GlabCliError::UnexpectedOutput(format!("Failed to parse glab repo view response: {e}"))
+        })?;
+        let host = Self::url_host(&resp.web_url).ok_or_else(|| {
+            GlabCliError::UnexpectedOutput(format!(
+                "Could not extract host from project URL: {}",
+                resp.web_url
+            ))
+        })?;
+
+        Ok(GitLabRepoInfo {
+            host,
+            project_path: resp.path_with_namespace,
+            web_url: resp.web_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    fn url_host(url: &str) -> Option<String> {
+        let rest = url.split_once("://")?.1;
+        let host = rest.split('/').next()?;
+        (!host.is_empty()).then(|| host.to_string())
+    }
+
+    /// Run `glab mr create` and parse the merge request URL it prints.
+    ///
+    /// `head_project` names the fork holding the source branch, for merge
+    /// requests across forks.
+    pub fn create_mr(
+        &self,
+        request: &CreatePrRequest,
+        repo: &GitLabRepoInfo,
+        head_project: Option<&str>,
+        repo_path: &Path,
+    ) -> Result<PullRequestInfo, GlabCliError> {
+        let mut args: Vec<OsString> = Vec::with_capacity(18);
+        args.push(OsString::from("mr"));
+        args.push(OsString::from("create"));
+        args.push(OsString::from("--repo"));
+        args.push(OsString::from(&repo.web_url));
+        args.push(OsString::from("--source-branch"));
+        args.push(OsString::from(&request.head_branch));
+        args.push(OsString::from("--target-branch"));
+        args.push(OsString::from(&request.base_branch));
+        args.push(OsString::from("--title"));
+        args.push(OsString::from(&request.title));
+        args.push(OsString::from("--description"));
+        args.push(OsString::from(request.body.as_deref().unwrap_or("")));
+        // Skip the confirmation prompt
+        args.push(OsString::from("--yes"));
+
+        if let Some(head_project) = head_project {
+            args.push(OsString::from("--head"));
+            args.push(OsString::from(head_project));
+        }
+        if request.draft.unwrap_or(false) {
+            args.push(OsString::from("--draft"));
+        }
+
+        let raw = self.run(args, Some(repo_path))?;
+        Self::parse_mr_create_text(&raw)
+    }
+
+    /// Retrieve details for a merge request by URL.
+    pub fn view_mr(&self, mr_url: &str) -> Result<PullRequestInfo, GlabCliError> {
+        let (project_url, iid) = Self::parse_mr_url(mr_url).ok_or_else(|| {
+            GlabCliError::UnexpectedOutput(format!("Could not parse GitLab MR URL: {mr_url}"))
+        })?;
+        let raw = self.run(
+            [
+                "mr",
+                "view",
+                &iid.to_string(),
+                "--repo",
+                &project_url,
+                "--output",
+                "json",
+            ],
+            None,
+        )?;
+        let mr: GlMrResponse = serde_json::from_str(raw.trim()).map_err(|err| {
+            GlabCliError::UnexpectedOutput(format!(
+                "Failed to parse glab mr view response: {err}; raw: {raw}"
+            ))
+        })?;
+        Ok(Self::mr_response_to_info(mr))
+    }
+
+    /// List merge requests from a source branch (includes closed/merged).
+    pub fn list_mrs_for_branch(
+        &self,
+        repo: &GitLabRepoInfo,
+        branch: &str,
+    ) -> Result<Vec<PullRequestInfo>, GlabCliError> {
+        let raw = self.run(
+            [
+                "mr",
+                "list",
+                "--repo",
+                &repo.web_url,
+                "--all",
+                "--source-branch",
+                branch,
+                "--output",
+                "json",
+            ],
+            None,
+        )?;
+        Ok(Self::parse_mr_list(&raw)?
+            .into_iter()
+            .map(Self::mr_response_to_info)
+            .collect())
+    }
+
+    pub fn list_open_mrs(&self, repo: &GitLabRepoInfo) -> Result<Vec<OpenPrInfo>, GlabCliError> {
+        let raw = self.run(
+            ["mr", "list", "--repo", &repo.web_url, "--output", "json"],
+            None,
+        )?;
+        Ok(Self::parse_mr_list(&raw)?
+            .into_iter()
+            .map(|mr| OpenPrInfo {
+                number: mr.iid,
+                url: mr.web_url,
+                title: mr.title,
+                head_branch: mr.source_branch,
+                base_branch: mr.target_branch,
+            })
+            .collect())
+    }
+
+    /// Comments on a merge request, general and inline, oldest first.
+    pub fn get_mr_comments(
+        &self,
+        repo: &GitLabRepoInfo,
+        mr_iid: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GlabCliError> {
+        let raw = self.get_mr_discussions(repo, mr_iid)?;
+        Self::parse_mr_comments(&raw, &repo.mr_url(mr_iid))
+    }
+
+    /// Discussions on the merge request's diff, plus any other resolvable ones.
+    pub fn get_mr_review_threads(
+        &self,
+        repo: &GitLabRepoInfo,
+        mr_iid: i64,
+    ) -> Result<Vec<ReviewThread>, GlabCliError> {
+        let raw = self.get_mr_discussions(repo, mr_iid)?;
+        Self::parse_review_threads(&raw, &repo.mr_url(mr_iid))
+    }
+
+    fn get_mr_discussions(
+        &self,
+        repo: &GitLabRepoInfo,
+        mr_iid: i64,
+    ) -> Result<String, GlabCliError> {
+        self.run(
+            [
+                "api",
+                "--hostname",
+                &repo.host,
+                "--paginate",
+                &format!(
+                    "projects/{}/merge_requests/{mr_iid}/discussions",
+                    repo.api_id()
+                ),
+            ],
+            None,
+        )
+    }
+}
+
+impl GlabCli {
+    /// Split a merge request URL into its project URL and IID, e.g.
+    /// `https://gitlab.com/group/project/-/merge_requests/12`.
+    pub fn parse_mr_url(url: &str) -> Option<(String, i64)> {
+        let (project_url, rest) = url.split_once("/-/merge_requests/")?;
+        let iid = rest.split(['/', '#', '?']).next()?.parse::<i64>().ok()?;
+        Some((project_url.to_string(), iid))
+    }
+
+    fn parse_mr_create_text(raw: &str) -> Result<PullRequestInfo, GlabCliError> {
+        let mr_url = raw
+            .lines()
+            .rev()
+            .flat_map(|line| line.split_whitespace())
+            .find(|token| token.starts_with("http") && token.contains("/-/merge_requests/"))
+            .ok_or_else(|| {
+                GlabCliError::UnexpectedOutput(format!(
+                    "glab mr create did not return a merge request URL; raw output: {raw}"
+                ))
+            })?
+            .trim_end_matches(['.', ',', ';'])
+            .to_string();
+
+        let (_, number) = Self::parse_mr_url(&mr_url).ok_or_else(|| {
+            GlabCliError::UnexpectedOutput(format!(
+                "Failed to extract MR number from URL '{mr_url}'"
+            ))
+        })?;
+
+        Ok(PullRequestInfo {
+            number,
+            url: mr_url,
+            status: MergeStatus::Open,
+            merged_at: None,
+            merge_commit_sha: None,
+        })
+    }
+
+    fn parse_mr_list(raw: &str) -> Result<Vec<GlMrResponse>, GlabCliError> {
+        // An empty list may be printed as nothing at all
+        if raw.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(raw.trim()).map_err(|err| {
+            GlabCliError::UnexpectedOutput(format!(
+                "Failed to parse glab mr list response: {err}; raw: {raw}"
+            ))
+        })
+    }
+
+    fn mr_response_to_info(mr: GlMrResponse) -> PullRequestInfo {
+        PullRequestInfo {
+            number: mr.iid,
+            url: mr.web_url,
+            status: Self::map_mr_state(&mr.state),
+            merged_at: mr.merged_at,
+            // Squash merges leave `merge_commit_sha` unset when fast-forwarded
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+        }
+    }
+
+    /// Map a GitLab merge request state to MergeStatus. `locked` is the
+    /// transient state while a merge is in progress.
+    fn map_mr_state(state: &str) -> MergeStatus {
+        match state.to_ascii_lowercase().as_str() {
+            "" | "opened" | "locked" => MergeStatus::Open,
+            "merged" => MergeStatus::Merged,
+            "closed" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        }
+    }
+
+    fn parse_discussions(raw: &str) -> Result<Vec<GlDiscussion>, GlabCliError> {
+        let mut discussions = Vec::new();
+        // --paginate prints one JSON array per page
+        for page in serde_json::Deserializer::from_str(raw).into_iter::<Vec<GlDiscussion>>() {
+            discussions.extend(page.map_err(|err| {
+                GlabCliError::UnexpectedOutput(format!(
+                    "Failed to parse merge request discussions: {err}; raw: {raw}"
+                ))
+            })?);
+        }
+        Ok(discussions)
+    }
+
+    fn parse_mr_comments(raw: &str, mr_url: &str) -> Result<Vec<UnifiedPrComment>, GlabCliError> {
+        let mut comments = Vec::new();
+        for note in Self::parse_discussions(raw)?
+            .into_iter()
+            .flat_map(|d| d.notes)
+            // Skip system notes (pushes, label and status changes)
+            .filter(|n| !n.system)
+        {
+            let author = note.author();
+            let url = Some(format!("{mr_url}#note_{}", note.id));
+            let created_at = note.created_at.unwrap_or_else(Utc::now);
+            let line = note.line();
+            let side = note.position.as_ref().map(|p| {
+                let side = if p.new_line.is_some() {
+                    "RIGHT"
+                } else {
+                    "LEFT"
+                };
+                side.to_string()
+            });
+            match note.path() {
+                Some(path) => comments.push(UnifiedPrComment::Review {
+                    id: note.id,
+                    author,
+                    author_association: None,
+                    body: note.body,
+                    created_at,
+                    url,
+                    path,
+                    line,
+                    side,
+                    diff_hunk: None,
+                }),
+                None => comments.push(UnifiedPrComment::General {
+                    id: note.id.to_string(),
+                    author,
+                    author_association: None,
+                    body: note.body,
+                    created_at,
+                    url,
+                }),
+            }
+        }
+
+        comments.sort_by_key(|c| c.created_at());
+        Ok(comments)
+    }
+
+    fn parse_review_threads(raw: &str, mr_url: &str) -> Result<Vec<ReviewThread>, GlabCliError> {
+        let mut threads = Vec::new();
+        for discussion in Self::parse_discussions(raw)? {
+            let notes: Vec<GlNote> = discussion.notes.into_iter().filter(|n| !n.system).collect();
+            let Some(first) = notes.first() else {
+                continue;
+            };
+            // Standalone comments that can't be resolved are not threads
+            if discussion.individual_note && !first.resolvable && first.position.is_none() {
+                continue;
+            }
+
+            let path = first.path();
+            let line = first.line();
+            let is_resolved = first
+                .resolvable
+                .then(|| notes.iter().filter(|n| n.resolvable).all(|n| n.resolved));
+            threads.push(ReviewThread {
+                id: discussion.id,
+                path,
+                line,
+                is_resolved,
+                comments: notes
+                    .into_iter()
+                    .map(|n| ReviewThreadComment {
+                        id: n.id.to_string(),
+                        author: n.author(),
+                        url: Some(format!("{mr_url}#note_{}", n.id)),
+                        created_at: n.created_at.unwrap_or_else(Utc::now),
+                        body: n.body,
+                    })
+                    .collect(),
+            });
+        }
+        Ok(threads)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_merge_request_urls() {
+        assert_eq!(
+            GlabCli::parse_mr_url("https://gitlab.com/group/sub/project/-/merge_requests/12"),
+            Some(("https://gitlab.com/group/sub/project".to_string(), 12))
+        );
+        assert_eq!(
+            GlabCli::parse_mr_url("https://git.example.com/team/app/-/merge_requests/7#note_3"),
+            Some(("https://git.example.com/team/app".to_string(), 7))
+        );
+        assert_eq!(
+            GlabCli::parse_mr_url("https://github.com/owner/repo/pull/1"),
+            None
+        );
+
+        let created = GlabCli::parse_mr_create_text(
+            "\nCreating merge request for feature into main in group/project\n\n!12 Add thing (feature)\n https://gitlab.com/group/project/-/merge_requests/12\n",
+        )
+        .unwrap();
+        assert_eq!(created.number, 12);
+        assert_eq!(
+            created.url,
+            "https://gitlab.com/group/project/-/merge_requests/12"
+        );
+    }
+
+    #[test]
+    fn maps_merge_request_states() {
+        let raw = r#"[
+            {"iid": 1, "web_url": "u1", "state": "opened"},
+            {"iid": 2, "web_url": "u2", "state": "merged", "merged_at": "2024-01-01T00:00:00Z",
+                "merge_commit_sha": null, "squash_commit_sha": "abc123"},
+            {"iid": 3, "web_url": "u3", "state": "closed"}
+        ]"#;
+        let infos: Vec<_> = GlabCli::parse_mr_list(raw)
+            .unwrap()
+            .into_iter()
+            .map(GlabCli::mr_response_to_info)
+            .collect();
+        assert!(matches!(infos[0].status, MergeStatus::Open));
+        assert!(matches!(infos[1].status, MergeStatus::Merged));
+        assert_eq!(infos[1].merge_commit_sha.as_deref(), Some("abc123"));
+        assert!(matches!(infos[2].status, MergeStatus::Closed));
+        assert!(GlabCli::parse_mr_list("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parses_discussions_into_comments_and_threads() {
+        let page = |id: &str, resolved: bool| {
+            format!(
+                r#"[{{"id":"{id}","individual_note":false,"notes":[
+                    {{"id":1,"body":"Fix this","author":{{"username":"alice"}},
+                        "created_at":"2024-01-01T00:00:00Z","system":false,
+                        "resolvable":true,"resolved":{resolved},
+                        "position":{{"new_path":"src/lib.rs","old_path":"src/lib.rs","new_line":7,"old_line":null}}}},
+                    {{"id":2,"body":"Done","author":{{"username":"bob"}},
+                        "created_at":"2024-01-02T00:00:00Z","system":false,
+                        "resolvable":true,"resolved":{resolved}}}]}},
+                  {{"id":"n-{id}","individual_note":true,"notes":[
+                    {{"id":3,"body":"added 1 commit","created_at":"2024-01-03T00:00:00Z","system":true}},
+                    {{"id":4,"body":"Looks good","author":null,
+                        "created_at":"2024-01-04T00:00:00Z","system":false,"resolvable":false}}]}}]"#
+            )
+        };
+        let raw = format!("{}\n{}", page("d1", true), page("d2", false));
+        let mr_url = "https://gitlab.com/group/project/-/merge_requests/12";
+
+        let threads = GlabCli::parse_review_threads(&raw, mr_url).unwrap();
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(threads[0].line, Some(7));
+        assert_eq!(threads[0].is_resolved, Some(true));
+        assert_eq!(threads[0].comments.len(), 2);
+        assert_eq!(threads[1].is_resolved, Some(false));
+
+        let comments = GlabCli::parse_mr_comments(&page("d1", true), mr_url).unwrap();
+        assert_eq!(comments.len(), 3);
+        assert!(matches!(
+            &comments[0],
+            UnifiedPrComment::Review { path, line: Some(7), .. } if path == "src/lib.rs"
+        ));
+        assert!(matches!(
+            &comments[2],
+            UnifiedPrComment::General { author, url: Some(url), .. }
+                if author == "unknown" && url.ends_with("#note_4")
+        ));
+    }
+}
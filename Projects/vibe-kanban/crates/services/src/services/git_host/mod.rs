@@ -1,8 +1,10 @@
+mod cli_command;
 mod detection;
 mod types;
 
 pub mod azure;
 pub mod github;
+pub mod gitlab;
 
 use std::path::Path;
 
@@ -11,11 +13,12 @@ use db::models::merge::PullRequestInfo;
 use detection::detect_provider_from_url;
 use enum_dispatch::enum_dispatch;
 pub use types::{
-    CreatePrRequest, GitHostError, OpenPrInfo, PrComment, PrCommentAuthor, PrReviewComment,
-    ProviderKind, ReviewCommentUser, UnifiedPrComment,
+    CreatePrRequest, GitHostError, HostPermission, OpenPrInfo, PrComment, PrCommentAuthor,
+    PrReviewComment, ProviderKind, ReviewCommentUser, ReviewThread, ReviewThreadComment,
+    UnifiedPrComment,
 };
 
-use self::{azure::AzureDevOpsProvider, github::GitHubProvider};
+use self::{azure::AzureDevOpsProvider, github::GitHubProvider, gitlab::GitLabProvider};
 
 #[async_trait]
 #[enum_dispatch(GitHostService)]
@@ -49,6 +52,26 @@ pub trait GitHostProvider: Send + Sync {
         remote_url: &str,
     ) -> Result<Vec<OpenPrInfo>, GitHostError>;
 
+    /// Review comments grouped into threads. `is_resolved` is `None` when the
+    /// provider couldn't report resolution state.
+    async fn list_review_threads(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<ReviewThread>, GitHostError>;
+
+    /// Permissions the current credentials lack on the repository, so missing
+    /// access can be reported up front instead of failing deep inside e.g.
+    /// [`Self::create_pr`]. Providers that can't tell report nothing missing.
+    async fn check_permissions(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+    ) -> Result<Vec<HostPermission>, GitHostError> {
+        Ok(Vec::new())
+    }
+
     fn provider_kind(&self) -> ProviderKind;
 }
 
@@ -56,13 +79,17 @@ pub trait GitHostProvider: Send + Sync {
 pub enum GitHostService {
     GitHub(GitHubProvider),
     AzureDevOps(AzureDevOpsProvider),
+    GitLab(GitLabProvider),
 }
 
 impl GitHostService {
-    pub fn from_url(url: &str) -> Result<Self, GitHostError> {
-        match detect_provider_from_url(url) {
+    /// Provider for `url`. `gitlab_hosts` are the self-hosted GitLab hosts
+    /// from user config; gitlab.com is always recognized.
+    pub fn from_url(url: &str, gitlab_hosts: &[String]) -> Result<Self, GitHostError> {
+        match detect_provider_from_url(url, gitlab_hosts) {
             ProviderKind::GitHub => Ok(Self::GitHub(GitHubProvider::new()?)),
             ProviderKind::AzureDevOps => Ok(Self::AzureDevOps(AzureDevOpsProvider::new()?)),
+            ProviderKind::GitLab => Ok(Self::GitLab(GitLabProvider::new()?)),
             ProviderKind::Unknown => Err(GitHostError::UnsupportedProvider),
         }
     }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -8,6 +10,7 @@ use ts_rs::TS;
 pub enum ProviderKind {
     GitHub,
     AzureDevOps,
+    GitLab,
     Unknown,
 }
 
@@ -16,11 +19,22 @@ impl std::fmt::Display for ProviderKind {
         match self {
             ProviderKind::GitHub => write!(f, "GitHub"),
             ProviderKind::AzureDevOps => write!(f, "Azure DevOps"),
+            ProviderKind::GitLab => write!(f, "GitLab"),
             ProviderKind::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
+/// Access to a hosted repository that Vibe Kanban's operations rely on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum HostPermission {
+    /// View the repository, its pull requests and their comments
+    RepoRead,
+    /// Open pull requests
+    PullRequestWrite,
+}
+
 #[derive(Debug, Clone)]
 pub struct CreatePrRequest {
     pub title: String,
@@ -42,6 +56,8 @@ pub enum GitHostError {
     AuthFailed(String),
     #[error("Insufficient permissions: {0}")]
     InsufficientPermissions(String),
+    #[error("Rate limited until {reset_at}")]
+    RateLimited { reset_at: DateTime<Utc> },
     #[error("Repository not found or no access: {0}")]
     RepoNotFoundOrNoAccess(String),
     #[error("{provider} CLI is not installed or not available in PATH")]
@@ -53,7 +69,28 @@ pub enum GitHostError {
 }
 
 impl GitHostError {
+    /// Providers report rate limiting as a 403/429 with a recognisable message,
+    /// so this must be checked before the generic permission mapping. Only the
+    /// providers' own phrasing counts, not e.g. a PR title mentioning rate limits.
+    pub(super) fn is_rate_limit_message(lower: &str) -> bool {
+        const MARKERS: &[&str] = &[
+            // GitHub primary and secondary limits, GitLab
+            "rate limit exceeded",
+            "exceeded a secondary rate limit",
+            "http 429",
+            "429 too many requests",
+            // Azure DevOps throttling
+            "tf400733",
+        ];
+        MARKERS.iter().any(|marker| lower.contains(marker))
+    }
+
+    /// Rate limits that lift within [`MAX_RATE_LIMIT_RETRY_WAIT`] are retried
+    /// after the reset; longer ones are surfaced to the caller.
     pub fn should_retry(&self) -> bool {
+        if let GitHostError::RateLimited { reset_at } = self {
+            return time_until(*reset_at) <= MAX_RATE_LIMIT_RETRY_WAIT;
+        }
         !matches!(
             self,
             GitHostError::AuthFailed(_)
@@ -63,6 +100,31 @@ impl GitHostError {
                 | GitHostError::UnsupportedProvider
         )
     }
+
+    /// Delay before the next retry: the exponential `backoff` for most errors,
+    /// or until the reset when rate limited. `None` (attempts exhausted) is kept.
+    pub fn retry_delay(&self, backoff: Option<Duration>) -> Option<Duration> {
+        match self {
+            GitHostError::RateLimited { reset_at } => backoff.map(|_| time_until(*reset_at)),
+            _ => backoff,
+        }
+    }
+
+    /// For providers that don't report when a rate limit resets.
+    pub(super) fn rate_limited_for_default_wait() -> Self {
+        GitHostError::RateLimited {
+            reset_at: Utc::now() + DEFAULT_RATE_LIMIT_WAIT,
+        }
+    }
+}
+
+/// Assumed wait when a provider doesn't say when its rate limit resets.
+pub(super) const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+/// Longest a single call will sleep waiting for a rate limit to reset.
+const MAX_RATE_LIMIT_RETRY_WAIT: Duration = Duration::from_secs(60);
+
+pub(super) fn time_until(at: DateTime<Utc>) -> Duration {
+    (at - Utc::now()).to_std().unwrap_or(Duration::ZERO)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -98,6 +160,8 @@ pub struct PrReviewComment {
     pub side: Option<String>,
     pub diff_hunk: String,
     pub author_association: String,
+    #[serde(default)]
+    pub in_reply_to_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, TS)]
@@ -135,6 +199,28 @@ impl UnifiedPrComment {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReviewThreadComment {
+    pub id: String,
+    pub author: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub url: Option<String>,
+}
+
+/// A review conversation on a PR, grouped the way the provider shows it
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReviewThread {
+    pub id: String,
+    /// File the thread is anchored to; None for PR-level threads
+    pub path: Option<String>,
+    pub line: Option<i64>,
+    /// None when the provider can't report resolution state
+    pub is_resolved: Option<bool>,
+    /// Comments in the order they were posted
+    pub comments: Vec<ReviewThreadComment>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct OpenPrInfo {
     pub number: i64,
@@ -143,3 +229,32 @@ pub struct OpenPrInfo {
     pub head_branch: String,
     pub base_branch: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognises_only_provider_rate_limit_messages() {
+        for message in [
+            "gh: API rate limit exceeded for user ID 1234. (HTTP 403)",
+            "You have exceeded a secondary rate limit. Please wait a few minutes.",
+            "glab: 429 Too Many Requests",
+            "TF400733: The request has been canceled: Request was blocked due to exceeding usage of resource",
+        ] {
+            assert!(
+                GitHostError::is_rate_limit_message(&message.to_ascii_lowercase()),
+                "{message}"
+            );
+        }
+        for message in [
+            "a pull request for branch \"vk/rate-limit\" already exists: add rate limit handling",
+            "HTTP 403: Resource not accessible by integration",
+        ] {
+            assert!(
+                !GitHostError::is_rate_limit_message(&message.to_ascii_lowercase()),
+                "{message}"
+            );
+        }
+    }
+}
@@ -5,6 +5,7 @@ pub mod config;
 pub mod container;
 pub mod diff_stream;
 pub mod events;
+pub mod execution_slots;
 pub mod file_ranker;
 pub mod file_search;
 pub mod filesystem;
@@ -19,6 +20,8 @@ pub mod project;
 pub mod qa_repos;
 pub mod queued_message;
 pub mod remote_client;
+pub mod remote_connectivity;
 pub mod repo;
+pub mod secret_scan;
 pub mod workspace_manager;
 pub mod worktree_manager;
@@ -168,27 +168,8 @@ impl FileBackend {
     }
 
     async fn save(&self, creds: &StoredCredentials) -> std::io::Result<()> {
-        let tmp = self.path.with_extension("tmp");
-
-        let file = {
-            let mut opts = std::fs::OpenOptions::new();
-            opts.create(true).truncate(true).write(true);
-
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::OpenOptionsExt;
-                opts.mode(0o600);
-            }
-
-            opts.open(&tmp)?
-        };
-
-        serde_json::to_writer_pretty(&file, creds)?;
-        file.sync_all()?;
-        drop(file);
-
-        std::fs::rename(&tmp, &self.path)?;
-        Ok(())
+        let bytes = serde_json::to_vec_pretty(creds)?;
+        utils::fs::write_atomic_private(&self.path, bytes)
     }
 
     async fn clear(&self) -> std::io::Result<()> {
@@ -1,5 +1,12 @@
-use std::time::Duration;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::Duration,
+};
 
+use chrono::{DateTime, Utc};
 use db::{
     DBService,
     models::{
@@ -8,14 +15,20 @@ use db::{
         workspace::{Workspace, WorkspaceError},
     },
 };
+use serde::Serialize;
 use serde_json::json;
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
-use tokio::time::interval;
-use tracing::{debug, error, info};
+use tokio::{
+    sync::{Notify, RwLock},
+    time::sleep,
+};
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
 
 use crate::services::{
     analytics::AnalyticsContext,
+    config::Config,
     git_host::{self, GitHostError, GitHostProvider},
 };
 
@@ -29,21 +42,160 @@ enum PrMonitorError {
     Sqlx(#[from] SqlxError),
 }
 
+pub const DEFAULT_PR_POLL_INTERVAL_SECS: u32 = 60;
+pub const MIN_PR_POLL_INTERVAL_SECS: u32 = 10;
+/// Upper bound for the delay after repeated rate-limit responses
+const MAX_RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct PrMonitorStatus {
+    pub paused: bool,
+    pub poll_interval_secs: u64,
+    /// Delay currently in effect because a provider reported rate limiting
+    pub rate_limit_backoff_secs: Option<u64>,
+}
+
+/// Runtime controls for the PR monitor, shared between the deployment and the
+/// polling task.
+#[derive(Debug, Clone)]
+pub struct PrMonitorHandle {
+    inner: Arc<PrMonitorControl>,
+}
+
+#[derive(Debug)]
+struct PrMonitorControl {
+    paused: AtomicBool,
+    poll_interval_secs: AtomicU64,
+    /// 0 when not backing off
+    backoff_secs: AtomicU64,
+    poll_now: AtomicBool,
+    wake: Notify,
+}
+
+impl PrMonitorHandle {
+    pub fn new(poll_interval: Duration) -> Self {
+        Self {
+            inner: Arc::new(PrMonitorControl {
+                paused: AtomicBool::new(false),
+                poll_interval_secs: AtomicU64::new(poll_interval.as_secs().max(1)),
+                backoff_secs: AtomicU64::new(0),
+                poll_now: AtomicBool::new(false),
+                wake: Notify::new(),
+            }),
+        }
+    }
+
+    pub fn pause(&self) {
+        self.inner.paused.store(true, Ordering::Relaxed);
+        info!("PR monitoring paused");
+    }
+
+    /// Resumes polling and checks open PRs right away.
+    pub fn resume(&self) {
+        self.inner.paused.store(false, Ordering::Relaxed);
+        self.inner.poll_now.store(true, Ordering::Relaxed);
+        self.inner.wake.notify_one();
+        info!("PR monitoring resumed");
+    }
+
+    /// Takes effect immediately; the next poll is scheduled from now.
+    pub fn set_poll_interval(&self, poll_interval: Duration) {
+        self.inner
+            .poll_interval_secs
+            .store(poll_interval.as_secs().max(1), Ordering::Relaxed);
+        self.inner.wake.notify_one();
+    }
+
+    pub fn status(&self) -> PrMonitorStatus {
+        let backoff = self.inner.backoff_secs.load(Ordering::Relaxed);
+        PrMonitorStatus {
+            paused: self.is_paused(),
+            poll_interval_secs: self.poll_interval().as_secs(),
+            rate_limit_backoff_secs: (backoff > 0).then_some(backoff),
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        self.inner.paused.load(Ordering::Relaxed)
+    }
+
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.inner.poll_interval_secs.load(Ordering::Relaxed))
+    }
+
+    fn next_delay(&self) -> Duration {
+        let backoff = Duration::from_secs(self.inner.backoff_secs.load(Ordering::Relaxed));
+        self.poll_interval().max(backoff)
+    }
+
+    /// Doubles the delay between polls, up to [`MAX_RATE_LIMIT_BACKOFF`], and
+    /// never polls before the provider's reported reset.
+    fn back_off(&self, reset_at: DateTime<Utc>) -> Duration {
+        let until_reset = (reset_at - Utc::now()).to_std().unwrap_or(Duration::ZERO);
+        let next = (self.next_delay() * 2)
+            .max(until_reset)
+            .min(MAX_RATE_LIMIT_BACKOFF);
+        self.inner
+            .backoff_secs
+            .store(next.as_secs(), Ordering::Relaxed);
+        next
+    }
+
+    fn reset_backoff(&self) {
+        self.inner.backoff_secs.store(0, Ordering::Relaxed);
+    }
+
+    /// Waits until the next poll is due: the delay elapsed, or `resume` asked
+    /// for an immediate poll. Other wake-ups just restart the wait so interval
+    /// changes apply right away.
+    async fn wait_for_next_poll(&self) {
+        loop {
+            let elapsed = if self.is_paused() {
+                self.inner.wake.notified().await;
+                false
+            } else {
+                tokio::select! {
+                    _ = sleep(self.next_delay()) => true,
+                    _ = self.inner.wake.notified() => false,
+                }
+            };
+            if self.inner.poll_now.swap(false, Ordering::Relaxed) || elapsed {
+                return;
+            }
+        }
+    }
+}
+
+impl Default for PrMonitorHandle {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(DEFAULT_PR_POLL_INTERVAL_SECS.into()))
+    }
+}
+
+enum PollOutcome {
+    Completed,
+    RateLimited { reset_at: DateTime<Utc> },
+}
+
 /// Service to monitor PRs and update task status when they are merged
 pub struct PrMonitorService {
     db: DBService,
-    poll_interval: Duration,
+    config: Arc<RwLock<Config>>,
+    handle: PrMonitorHandle,
     analytics: Option<AnalyticsContext>,
 }
 
 impl PrMonitorService {
     pub async fn spawn(
         db: DBService,
+        config: Arc<RwLock<Config>>,
         analytics: Option<AnalyticsContext>,
+        handle: PrMonitorHandle,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
-            poll_interval: Duration::from_secs(60), // Check every minute
+            config,
+            handle,
             analytics,
         };
         tokio::spawn(async move {
@@ -54,44 +206,59 @@ impl PrMonitorService {
     async fn start(&self) {
         info!(
             "Starting PR monitoring service with interval {:?}",
-            self.poll_interval
+            self.handle.poll_interval()
         );
 
-        let mut interval = interval(self.poll_interval);
-
         loop {
-            interval.tick().await;
-            if let Err(e) = self.check_all_open_prs().await {
-                error!("Error checking open PRs: {}", e);
+            if !self.handle.is_paused() {
+                match self.check_all_open_prs().await {
+                    Ok(PollOutcome::Completed) => self.handle.reset_backoff(),
+                    Ok(PollOutcome::RateLimited { reset_at }) => {
+                        let delay = self.handle.back_off(reset_at);
+                        warn!("Git host rate limit hit, next PR check in {:?}", delay);
+                    }
+                    Err(e) => error!("Error checking open PRs: {}", e),
+                }
             }
+            self.handle.wait_for_next_poll().await;
         }
     }
 
-    /// Check all open PRs for updates with the provided GitHub token
-    async fn check_all_open_prs(&self) -> Result<(), PrMonitorError> {
+    /// Check all open PRs for updates. Stops early once a provider reports rate
+    /// limiting, since the remaining checks would only extend the limit.
+    async fn check_all_open_prs(&self) -> Result<PollOutcome, PrMonitorError> {
         let open_prs = Merge::get_open_prs(&self.db.pool).await?;
 
         if open_prs.is_empty() {
             debug!("No open PRs to check");
-            return Ok(());
+            return Ok(PollOutcome::Completed);
         }
 
         info!("Checking {} open PRs", open_prs.len());
 
         for pr_merge in open_prs {
-            if let Err(e) = self.check_pr_status(&pr_merge).await {
-                error!(
+            match self.check_pr_status(&pr_merge).await {
+                Ok(()) => {}
+                Err(PrMonitorError::GitHostError(GitHostError::RateLimited { reset_at })) => {
+                    debug!(
+                        "Rate limited checking PR #{} until {}",
+                        pr_merge.pr_info.number, reset_at
+                    );
+                    return Ok(PollOutcome::RateLimited { reset_at });
+                }
+                Err(e) => error!(
                     "Error checking PR #{} for workspace {}: {}",
                     pr_merge.pr_info.number, pr_merge.workspace_id, e
-                );
+                ),
             }
         }
-        Ok(())
+        Ok(PollOutcome::Completed)
     }
 
     /// Check the status of a specific PR
     async fn check_pr_status(&self, pr_merge: &PrMerge) -> Result<(), PrMonitorError> {
-        let git_host = git_host::GitHostService::from_url(&pr_merge.pr_info.url)?;
+        let gitlab_hosts = self.config.read().await.gitlab_hosts.clone();
+        let git_host = git_host::GitHostService::from_url(&pr_merge.pr_info.url, &gitlab_hosts)?;
         let pr_status = git_host.get_pr_status(&pr_merge.pr_info.url).await?;
 
         debug!(
@@ -146,3 +313,52 @@ impl PrMonitorService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_until_capped_and_resets() {
+        let handle = PrMonitorHandle::new(Duration::from_secs(60));
+        let now = Utc::now();
+        assert_eq!(handle.back_off(now), Duration::from_secs(120));
+        assert_eq!(handle.back_off(now), Duration::from_secs(240));
+        for _ in 0..10 {
+            handle.back_off(now);
+        }
+        assert_eq!(handle.next_delay(), MAX_RATE_LIMIT_BACKOFF);
+
+        handle.reset_backoff();
+        assert_eq!(handle.next_delay(), Duration::from_secs(60));
+        assert_eq!(handle.status().rate_limit_backoff_secs, None);
+    }
+
+    #[test]
+    fn backoff_waits_for_reported_reset() {
+        let handle = PrMonitorHandle::new(Duration::from_secs(60));
+        let delay = handle.back_off(Utc::now() + Duration::from_secs(900));
+        assert!(delay > Duration::from_secs(890) && delay <= Duration::from_secs(900));
+    }
+
+    #[tokio::test]
+    async fn resume_triggers_an_immediate_poll() {
+        let handle = PrMonitorHandle::new(Duration::from_secs(3600));
+        handle.pause();
+
+        let waiter = tokio::spawn({
+            let handle = handle.clone();
+            async move { handle.wait_for_next_poll().await }
+        });
+        handle.set_poll_interval(Duration::from_secs(1800));
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+
+        handle.resume();
+        tokio::time::timeout(Duration::from_secs(5), waiter)
+            .await
+            .expect("resume should wake the monitor")
+            .unwrap();
+        assert!(!handle.status().paused);
+    }
+}
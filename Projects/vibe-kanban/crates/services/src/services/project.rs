@@ -1,15 +1,28 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
+use chrono::{DateTime, Utc};
 use db::models::{
+    execution_process::{
+        CodingAgentRunSummary, ExecutionChangeOutcome, ExecutionProcess, ExecutionProcessStatus,
+    },
+    execution_process_repo_state::ExecutionProcessRepoState,
+    merge::{Merge, MergeStatus},
     project::{CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject},
     project_repo::{CreateProjectRepo, ProjectRepo},
     repo::Repo,
+    session::Session,
+    task::{Task, TaskStatus},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
 };
+use git::{DiffNumstat, GitService};
+use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use thiserror::Error;
+use ts_rs::TS;
 use uuid::Uuid;
 
 use super::{
@@ -31,6 +44,8 @@ pub enum ProjectServiceError {
     PathNotDirectory(PathBuf),
     #[error("Path is not a git repository: {0}")]
     NotGitRepository(PathBuf),
+    #[error("Repository has no branches: {0}")]
+    NoBranches(PathBuf),
     #[error("Duplicate git repository path")]
     DuplicateGitRepoPath,
     #[error("Duplicate repository name in project")]
@@ -41,6 +56,10 @@ pub enum ProjectServiceError {
     GitError(String),
     #[error("Remote client error: {0}")]
     RemoteClient(String),
+    #[error("Invalid branch prefix: {0}")]
+    InvalidBranchPrefix(String),
+    #[error("Invalid dev server ready pattern: {0}")]
+    InvalidDevServerReadyPattern(String),
 }
 
 pub type Result<T> = std::result::Result<T, ProjectServiceError>;
@@ -51,6 +70,7 @@ impl From<RepoError> for ProjectServiceError {
             RepoError::PathNotFound(p) => Self::PathNotFound(p),
             RepoError::PathNotDirectory(p) => Self::PathNotDirectory(p),
             RepoError::NotGitRepository(p) => Self::NotGitRepository(p),
+            RepoError::NoBranches(p) => Self::NoBranches(p),
             RepoError::Io(e) => Self::Io(e),
             RepoError::Database(e) => Self::Database(e),
             _ => Self::RepositoryNotFound,
@@ -58,6 +78,71 @@ impl From<RepoError> for ProjectServiceError {
     }
 }
 
+/// Date range for [`ProjectService::build_report`]. Both bounds are optional;
+/// `from` is inclusive and `to` exclusive.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct ProjectReportQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// "What did we ship" summary of a project's task attempts
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectReport {
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub generated_at: DateTime<Utc>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub totals: ProjectReportTotals,
+    pub attempts: Vec<AttemptReport>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct ProjectReportTotals {
+    pub attempts: usize,
+    /// Attempts with at least one direct merge or merged PR
+    pub merged_attempts: usize,
+    pub files_changed: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+/// One workspace (task attempt) in a [`ProjectReport`]
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptReport {
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub task_status: TaskStatus,
+    pub branch: String,
+    pub archived: bool,
+    pub created_at: DateTime<Utc>,
+    pub session_count: usize,
+    pub coding_agent_runs: usize,
+    /// Status of the most recent coding agent run
+    pub latest_run_status: Option<ExecutionProcessStatus>,
+    /// Most significant change outcome across all coding agent runs
+    pub change_outcome: Option<ExecutionChangeOutcome>,
+    pub merged: bool,
+    pub repos: Vec<AttemptRepoReport>,
+}
+
+/// Final state of one repository of an attempt
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AttemptRepoReport {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub target_branch: String,
+    /// Committed changes on the attempt branch; `None` if the branch is gone
+    pub files_changed: Option<usize>,
+    pub lines_added: Option<usize>,
+    pub lines_removed: Option<usize>,
+    pub merge_commit: Option<String>,
+    pub pr_url: Option<String>,
+    pub pr_status: Option<MergeStatus>,
+}
+
 #[derive(Clone, Default)]
 pub struct ProjectService;
 
@@ -69,6 +154,7 @@ impl ProjectService {
     pub async fn create_project(
         &self,
         pool: &SqlitePool,
+        git: &GitService,
         repo_service: &RepoService,
         payload: CreateProject,
     ) -> Result<Project> {
@@ -79,7 +165,7 @@ impl ProjectService {
 
         for repo in &payload.repositories {
             let path = repo_service.normalize_path(&repo.git_repo_path)?;
-            repo_service.validate_git_repo_path(&path)?;
+            repo_service.validate_git_repo_path(git, &path)?;
 
             let normalized_path = path.to_string_lossy().to_string();
 
@@ -107,6 +193,9 @@ impl ProjectService {
             let repo_entity =
                 Repo::find_or_create(pool, Path::new(&repo.git_repo_path), &repo.display_name)
                     .await?;
+            let repo_entity = repo_service
+                .fill_default_target_branch(pool, git, repo_entity)
+                .await?;
             ProjectRepo::create(pool, project.id, repo_entity.id).await?;
         }
 
@@ -119,6 +208,20 @@ impl ProjectService {
         existing: &Project,
         payload: UpdateProject,
     ) -> Result<Project> {
+        if let Some(Some(prefix)) = &payload.git_branch_prefix
+            && !git::is_valid_branch_prefix(prefix)
+        {
+            return Err(ProjectServiceError::InvalidBranchPrefix(prefix.clone()));
+        }
+
+        if let Some(Some(pattern)) = &payload.dev_server_ready_pattern
+            && let Err(e) = regex::Regex::new(pattern)
+        {
+            return Err(ProjectServiceError::InvalidDevServerReadyPattern(
+                e.to_string(),
+            ));
+        }
+
         let project = Project::update(pool, existing.id, &payload).await?;
 
         Ok(project)
@@ -127,6 +230,7 @@ impl ProjectService {
     pub async fn add_repository(
         &self,
         pool: &SqlitePool,
+        git: &GitService,
         repo_service: &RepoService,
         project_id: Uuid,
         payload: &CreateProjectRepo,
@@ -139,7 +243,7 @@ impl ProjectService {
         );
 
         let path = repo_service.normalize_path(&payload.git_repo_path)?;
-        repo_service.validate_git_repo_path(&path)?;
+        repo_service.validate_git_repo_path(git, &path)?;
 
         let repository = ProjectRepo::add_repo_to_project(
             pool,
@@ -157,6 +261,9 @@ impl ProjectService {
             }
             _ => ProjectServiceError::RepositoryNotFound,
         })?;
+        let repository = repo_service
+            .fill_default_target_branch(pool, git, repository)
+            .await?;
 
         tracing::info!(
             "Added repository {} to project {} (path: {})",
@@ -276,4 +383,169 @@ impl ProjectService {
         all_results.truncate(10);
         Ok(all_results)
     }
+
+    /// Aggregate every attempt of a project created in the given range into a
+    /// report. Diff stats come from the attempt branches, so archived
+    /// workspaces whose worktree is gone are still counted.
+    pub async fn build_report(
+        &self,
+        pool: &SqlitePool,
+        git: &GitService,
+        project: &Project,
+        query: &ProjectReportQuery,
+    ) -> Result<ProjectReport> {
+        let workspaces =
+            Workspace::find_by_project_created_between(pool, project.id, query.from, query.to)
+                .await?;
+        let tasks: HashMap<Uuid, Task> = Task::find_by_project_id(pool, project.id)
+            .await?
+            .into_iter()
+            .map(|task| (task.id, task))
+            .collect();
+        let session_counts = Session::count_by_workspace_for_project(pool, project.id).await?;
+        let mut runs: HashMap<Uuid, Vec<CodingAgentRunSummary>> = HashMap::new();
+        for run in ExecutionProcess::find_coding_agent_runs_for_project(pool, project.id).await? {
+            runs.entry(run.workspace_id).or_default().push(run);
+        }
+        let mut merges: HashMap<Uuid, Vec<Merge>> = HashMap::new();
+        for merge in Merge::find_by_project_id(pool, project.id).await? {
+            merges.entry(merge.workspace_id()).or_default().push(merge);
+        }
+        let mut targets = WorkspaceRepo::find_targets_for_project(pool, project.id).await?;
+        let start_commits =
+            ExecutionProcessRepoState::find_start_commits_for_project(pool, project.id).await?;
+
+        let stat_requests: Vec<_> = workspaces
+            .iter()
+            .flat_map(|workspace| {
+                targets
+                    .get(&workspace.id)
+                    .into_iter()
+                    .flatten()
+                    .map(|target| BranchStatsRequest {
+                        key: (workspace.id, target.repo_id),
+                        repo_path: target.repo_path.clone(),
+                        branch: workspace.branch.clone(),
+                        target_branch: target.target_branch.clone(),
+                        start_commit: start_commits.get(&(workspace.id, target.repo_id)).cloned(),
+                    })
+            })
+            .collect();
+        let stats = tokio::task::spawn_blocking({
+            let git = git.clone();
+            move || branch_stats(&git, stat_requests)
+        })
+        .await
+        .map_err(std::io::Error::from)?;
+
+        let mut totals = ProjectReportTotals::default();
+        let mut attempts = Vec::with_capacity(workspaces.len());
+        for workspace in workspaces {
+            let Some(task) = tasks.get(&workspace.task_id) else {
+                continue;
+            };
+            let runs = runs.remove(&workspace.id).unwrap_or_default();
+            let merges = merges.remove(&workspace.id).unwrap_or_default();
+
+            let repos: Vec<_> = targets
+                .remove(&workspace.id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|target| {
+                    let stats = stats.get(&(workspace.id, target.repo_id));
+                    // Merges are newest first, so this is the latest one for the repo
+                    let merge = merges.iter().find(|m| m.repo_id() == target.repo_id);
+                    let (pr_url, pr_status) = match merge {
+                        Some(Merge::Pr(pr)) => (
+                            Some(pr.pr_info.url.clone()),
+                            Some(pr.pr_info.status.clone()),
+                        ),
+                        _ => (None, None),
+                    };
+                    AttemptRepoReport {
+                        repo_id: target.repo_id,
+                        repo_name: target.repo_name,
+                        target_branch: target.target_branch,
+                        files_changed: stats.map(|s| s.files_changed),
+                        lines_added: stats.map(|s| s.additions),
+                        lines_removed: stats.map(|s| s.deletions),
+                        merge_commit: merge.and_then(Merge::merge_commit),
+                        pr_url,
+                        pr_status,
+                    }
+                })
+                .collect();
+
+            let merged = merges.iter().any(|m| match m {
+                Merge::Direct(_) => true,
+                Merge::Pr(pr) => matches!(pr.pr_info.status, MergeStatus::Merged),
+            });
+
+            totals.attempts += 1;
+            if merged {
+                totals.merged_attempts += 1;
+            }
+            for repo in &repos {
+                totals.files_changed += repo.files_changed.unwrap_or(0);
+                totals.lines_added += repo.lines_added.unwrap_or(0);
+                totals.lines_removed += repo.lines_removed.unwrap_or(0);
+            }
+
+            attempts.push(AttemptReport {
+                workspace_id: workspace.id,
+                task_id: task.id,
+                task_title: task.title.clone(),
+                task_status: task.status.clone(),
+                branch: workspace.branch,
+                archived: workspace.archived,
+                created_at: workspace.created_at,
+                session_count: session_counts.get(&workspace.id).copied().unwrap_or(0),
+                coding_agent_runs: runs.len(),
+                // Runs are oldest first
+                latest_run_status: runs.last().map(|run| run.status.clone()),
+                change_outcome: runs.iter().filter_map(|run| run.change_outcome).max(),
+                merged,
+                repos,
+            });
+        }
+
+        Ok(ProjectReport {
+            project_id: project.id,
+            project_name: project.name.clone(),
+            generated_at: Utc::now(),
+            from: query.from,
+            to: query.to,
+            totals,
+            attempts,
+        })
+    }
+}
+
+struct BranchStatsRequest {
+    key: (Uuid, Uuid),
+    repo_path: PathBuf,
+    branch: String,
+    target_branch: String,
+    start_commit: Option<String>,
+}
+
+/// Diff stats of each attempt branch, keyed by `(workspace_id, repo_id)`.
+/// Branches that are gone are left out.
+fn branch_stats(
+    git: &GitService,
+    requests: Vec<BranchStatsRequest>,
+) -> HashMap<(Uuid, Uuid), DiffNumstat> {
+    requests
+        .into_iter()
+        .filter_map(|request| {
+            git.get_branch_diff_stats(
+                &request.repo_path,
+                &request.branch,
+                &request.target_branch,
+                request.start_commit.as_deref(),
+            )
+            .ok()
+            .map(|stats| (request.key, stats))
+        })
+        .collect()
 }
@@ -27,7 +27,9 @@ use utils::{
 };
 use uuid::Uuid;
 
-use super::{auth::AuthContext, oauth_credentials::Credentials};
+use super::{
+    auth::AuthContext, oauth_credentials::Credentials, remote_connectivity::RemoteConnectivity,
+};
 
 #[derive(Debug, Clone, Error)]
 pub enum RemoteClientError {
@@ -56,10 +58,16 @@ impl RemoteClientError {
     pub fn should_retry(&self) -> bool {
         match self {
             Self::Transport(_) | Self::Timeout => true,
-            Self::Http { status, .. } => (500..=599).contains(status),
+            Self::Http { status, .. } => *status == 429 || (500..=599).contains(status),
             _ => false,
         }
     }
+
+    /// Returns true if the server could not be reached at all, as opposed to
+    /// answering with an error.
+    pub fn is_unreachable(&self) -> bool {
+        matches!(self, Self::Transport(_) | Self::Timeout)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -99,6 +107,7 @@ pub struct RemoteClient {
     base: Url,
     http: Client,
     auth_context: AuthContext,
+    connectivity: RemoteConnectivity,
 }
 
 impl std::fmt::Debug for RemoteClient {
@@ -107,6 +116,7 @@ impl std::fmt::Debug for RemoteClient {
             .field("base", &self.base)
             .field("http", &self.http)
             .field("auth_context", &"<present>")
+            .field("connectivity", &self.connectivity.state())
             .finish()
     }
 }
@@ -117,6 +127,7 @@ impl Clone for RemoteClient {
             base: self.base.clone(),
             http: self.http.clone(),
             auth_context: self.auth_context.clone(),
+            connectivity: self.connectivity.clone(),
         }
     }
 }
@@ -124,6 +135,7 @@ impl Clone for RemoteClient {
 impl RemoteClient {
     const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
     const TOKEN_REFRESH_LEEWAY_SECS: i64 = 20;
+    const PING_TIMEOUT: Duration = Duration::from_secs(5);
 
     pub fn new(base_url: &str, auth_context: AuthContext) -> Result<Self, RemoteClientError> {
         let base = Url::parse(base_url).map_err(|e| RemoteClientError::Url(e.to_string()))?;
@@ -136,9 +148,39 @@ impl RemoteClient {
             base,
             http,
             auth_context,
+            connectivity: RemoteConnectivity::new(),
         })
     }
 
+    /// Reachability of the server, updated by every request and by
+    /// [`RemoteConnectivity::spawn_checker`].
+    pub fn connectivity(&self) -> &RemoteConnectivity {
+        &self.connectivity
+    }
+
+    /// Single unauthenticated request to the health endpoint, without retries.
+    pub async fn ping(&self) -> Result<(), RemoteClientError> {
+        let url = self
+            .base
+            .join("/health")
+            .map_err(|e| RemoteClientError::Url(e.to_string()))?;
+        let res = self
+            .http
+            .get(url)
+            .timeout(Self::PING_TIMEOUT)
+            .send()
+            .await
+            .map_err(map_reqwest_error)?;
+        if res.status().is_success() {
+            Ok(())
+        } else {
+            Err(RemoteClientError::Http {
+                status: res.status().as_u16(),
+                body: String::new(),
+            })
+        }
+    }
+
     /// Returns a valid access token, refreshing when it's about to expire.
     fn require_token(
         &self,
@@ -214,9 +256,17 @@ impl RemoteClient {
         let request = TokenRefreshRequest {
             refresh_token: refresh_token.to_string(),
         };
+        // Keep transient failures as-is so callers report them as offline
+        // rather than as a rejected refresh token
         self.post_public("/v1/tokens/refresh", Some(&request))
             .await
-            .map_err(|e| self.map_api_error(e))
+            .map_err(|e| {
+                if e.should_retry() {
+                    e
+                } else {
+                    self.map_api_error(e)
+                }
+            })
     }
 
     /// Returns the base URL for the client.
@@ -273,7 +323,7 @@ impl RemoteClient {
             .join(path)
             .map_err(|e| RemoteClientError::Url(e.to_string()))?;
 
-        (|| async {
+        let result = (|| async {
             let mut req = self.http.request(method.clone(), url.clone());
 
             if requires_auth {
@@ -312,7 +362,14 @@ impl RemoteClient {
                 e
             )
         })
-        .await
+        .await;
+
+        match &result {
+            Ok(_) => self.connectivity.mark_online(),
+            Err(e) if e.is_unreachable() => self.connectivity.mark_offline(),
+            Err(_) => {}
+        }
+        result
     }
 
     // Public endpoint helpers (no auth required)
@@ -527,3 +584,24 @@ fn map_reqwest_error(e: reqwest::Error) -> RemoteClientError {
         RemoteClientError::Transport(e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_transient_failures_are_retried() {
+        let http = |status| RemoteClientError::Http {
+            status,
+            body: String::new(),
+        };
+        assert!(RemoteClientError::Timeout.should_retry());
+        assert!(RemoteClientError::Transport("connection refused".to_string()).should_retry());
+        assert!(http(429).should_retry());
+        assert!(http(503).should_retry());
+
+        assert!(!RemoteClientError::Auth.should_retry());
+        assert!(!http(401).should_retry());
+        assert!(!http(404).should_retry());
+    }
+}
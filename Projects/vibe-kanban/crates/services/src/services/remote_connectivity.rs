@@ -0,0 +1,193 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use serde::Serialize;
+use tokio::{sync::Notify, time::timeout};
+use tracing::{info, warn};
+use ts_rs::TS;
+
+use super::remote_client::{RemoteClient, RemoteClientError};
+
+/// How often reachability is re-checked while online
+pub const ONLINE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Re-checks while offline start here and double up to [`ONLINE_CHECK_INTERVAL`]
+const OFFLINE_MIN_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteConnectivityState {
+    /// `VK_SHARED_API_BASE` is not set; remote features are unavailable
+    NotConfigured,
+    Online,
+    /// Configured but unreachable; checked again periodically
+    Offline,
+}
+
+/// Live reachability of the remote server, shared between the remote client,
+/// the deployment and the background checker.
+#[derive(Debug, Clone)]
+pub struct RemoteConnectivity {
+    inner: Arc<ConnectivityControl>,
+}
+
+#[derive(Debug)]
+struct ConnectivityControl {
+    online: AtomicBool,
+    wake: Notify,
+}
+
+impl Default for RemoteConnectivity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RemoteConnectivity {
+    /// Starts online so nothing is blocked before the first check completes.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(ConnectivityControl {
+                online: AtomicBool::new(true),
+                wake: Notify::new(),
+            }),
+        }
+    }
+
+    pub fn is_online(&self) -> bool {
+        self.inner.online.load(Ordering::Relaxed)
+    }
+
+    pub fn state(&self) -> RemoteConnectivityState {
+        if self.is_online() {
+            RemoteConnectivityState::Online
+        } else {
+            RemoteConnectivityState::Offline
+        }
+    }
+
+    pub fn mark_online(&self) {
+        if !self.inner.online.swap(true, Ordering::Relaxed) {
+            info!("Remote server reachable again");
+        }
+    }
+
+    /// Records a failed request and wakes the checker so recovery is noticed
+    /// without waiting for the next scheduled check.
+    pub fn mark_offline(&self) {
+        if self.set_offline() {
+            self.inner.wake.notify_one();
+        }
+    }
+
+    /// Returns whether this changed the state.
+    fn set_offline(&self) -> bool {
+        let was_online = self.inner.online.swap(false, Ordering::Relaxed);
+        if was_online {
+            warn!("Remote server unreachable; remote features are offline");
+        }
+        was_online
+    }
+
+    /// Applies a ping the way `RemoteClient::send` applies responses: only
+    /// failing to reach the server counts as offline. Doesn't wake the
+    /// checker, which is the one pinging.
+    fn record_ping(&self, result: &Result<(), RemoteClientError>) {
+        match result {
+            Ok(()) => self.mark_online(),
+            Err(e) if e.is_unreachable() => {
+                self.set_offline();
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Pings the remote server periodically, more often while offline.
+    pub fn spawn_checker(&self, client: RemoteClient) -> tokio::task::JoinHandle<()> {
+        let connectivity = self.clone();
+        tokio::spawn(async move {
+            let mut offline_delay = OFFLINE_MIN_CHECK_INTERVAL;
+            loop {
+                connectivity.record_ping(&client.ping().await);
+
+                let delay = if connectivity.is_online() {
+                    offline_delay = OFFLINE_MIN_CHECK_INTERVAL;
+                    ONLINE_CHECK_INTERVAL
+                } else {
+                    let delay = offline_delay;
+                    offline_delay = (offline_delay * 2).min(ONLINE_CHECK_INTERVAL);
+                    delay
+                };
+                let _ = timeout(delay, connectivity.inner.wake.notified()).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_transitions() {
+        let connectivity = RemoteConnectivity::new();
+        assert_eq!(connectivity.state(), RemoteConnectivityState::Online);
+
+        connectivity.mark_offline();
+        assert_eq!(connectivity.state(), RemoteConnectivityState::Offline);
+        assert!(!connectivity.clone().is_online());
+
+        connectivity.mark_online();
+        assert_eq!(connectivity.state(), RemoteConnectivityState::Online);
+    }
+
+    #[test]
+    fn test_only_unreachable_pings_go_offline() {
+        let connectivity = RemoteConnectivity::new();
+
+        connectivity.record_ping(&Err(RemoteClientError::Http {
+            status: 503,
+            body: String::new(),
+        }));
+        assert_eq!(connectivity.state(), RemoteConnectivityState::Online);
+
+        connectivity.record_ping(&Err(RemoteClientError::Timeout));
+        assert_eq!(connectivity.state(), RemoteConnectivityState::Offline);
+
+        // An answer with an error doesn't prove the server is back
+        connectivity.record_ping(&Err(RemoteClientError::Http {
+            status: 503,
+            body: String::new(),
+        }));
+        assert_eq!(connectivity.state(), RemoteConnectivityState::Offline);
+
+        connectivity.record_ping(&Ok(()));
+        assert_eq!(connectivity.state(), RemoteConnectivityState::Online);
+    }
+
+    #[tokio::test]
+    async fn test_only_requests_outside_the_checker_wake_it() {
+        let connectivity = RemoteConnectivity::new();
+        connectivity.record_ping(&Err(RemoteClientError::Transport("refused".to_string())));
+        assert_eq!(connectivity.state(), RemoteConnectivityState::Offline);
+        let woken = timeout(
+            Duration::from_millis(50),
+            connectivity.inner.wake.notified(),
+        )
+        .await;
+        assert!(woken.is_err());
+
+        connectivity.mark_online();
+        connectivity.mark_offline();
+        let woken = timeout(
+            Duration::from_millis(50),
+            connectivity.inner.wake.notified(),
+        )
+        .await;
+        assert!(woken.is_ok());
+    }
+}
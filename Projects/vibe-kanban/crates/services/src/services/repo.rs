@@ -1,6 +1,6 @@
 use std::path::{Path, PathBuf};
 
-use db::models::repo::Repo as RepoModel;
+use db::models::repo::{Repo as RepoModel, RepoError as RepoModelError, UpdateRepo};
 use git::{GitService, GitServiceError};
 use sqlx::SqlitePool;
 use thiserror::Error;
@@ -19,6 +19,8 @@ pub enum RepoError {
     PathNotDirectory(PathBuf),
     #[error("Path is not a git repository: {0}")]
     NotGitRepository(PathBuf),
+    #[error("Repository has no branches: {0}")]
+    NoBranches(PathBuf),
     #[error("Repository not found")]
     NotFound,
     #[error("Directory already exists: {0}")]
@@ -39,7 +41,7 @@ impl RepoService {
         Self
     }
 
-    pub fn validate_git_repo_path(&self, path: &Path) -> Result<()> {
+    pub fn validate_git_repo_path(&self, git: &GitService, path: &Path) -> Result<()> {
         if !path.exists() {
             return Err(RepoError::PathNotFound(path.to_path_buf()));
         }
@@ -52,6 +54,19 @@ impl RepoService {
             return Err(RepoError::NotGitRepository(path.to_path_buf()));
         }
 
+        let repo = git
+            .open_repo(path)
+            .map_err(|_| RepoError::NotGitRepository(path.to_path_buf()))?;
+
+        // An empty repository has nothing to branch worktrees from
+        let has_branch = repo
+            .branches(Some(git2::BranchType::Local))
+            .map(|mut branches| branches.next().is_some())
+            .unwrap_or(false);
+        if !has_branch {
+            return Err(RepoError::NoBranches(path.to_path_buf()));
+        }
+
         Ok(())
     }
 
@@ -62,11 +77,12 @@ impl RepoService {
     pub async fn register(
         &self,
         pool: &SqlitePool,
+        git: &GitService,
         path: &str,
         display_name: Option<&str>,
     ) -> Result<RepoModel> {
         let normalized_path = self.normalize_path(path)?;
-        self.validate_git_repo_path(&normalized_path)?;
+        self.validate_git_repo_path(git, &normalized_path)?;
 
         let name = normalized_path
             .file_name()
@@ -79,6 +95,52 @@ impl RepoService {
         Ok(repo)
     }
 
+    /// Set the repo's default target branch to its detected default branch
+    /// ([`GitService::detect_default_branch`]) when none is configured yet.
+    /// If nothing is detected, the repo is returned unchanged.
+    pub async fn fill_default_target_branch(
+        &self,
+        pool: &SqlitePool,
+        git: &GitService,
+        repo: RepoModel,
+    ) -> Result<RepoModel> {
+        if repo.default_target_branch.is_some() {
+            return Ok(repo);
+        }
+        // May ask the remote over the network, so keep it off the async runtime
+        let repo_path = repo.path.clone();
+        let git = git.clone();
+        let detected = tokio::task::spawn_blocking(move || git.detect_default_branch(&repo_path))
+            .await
+            .map_err(std::io::Error::from)?;
+        let branch = match detected {
+            Ok(branch) => branch,
+            Err(e) => {
+                tracing::debug!(
+                    "No default branch detected for {}: {e}",
+                    repo.path.display()
+                );
+                return Ok(repo);
+            }
+        };
+
+        let payload = UpdateRepo {
+            display_name: None,
+            setup_script: None,
+            cleanup_script: None,
+            copy_files: None,
+            parallel_setup_script: None,
+            dev_server_script: None,
+            default_target_branch: Some(Some(branch)),
+        };
+        RepoModel::update(pool, repo.id, &payload)
+            .await
+            .map_err(|e| match e {
+                RepoModelError::Database(e) => RepoError::Database(e),
+                RepoModelError::NotFound => RepoError::NotFound,
+            })
+    }
+
     pub async fn find_by_id(&self, pool: &SqlitePool, repo_id: Uuid) -> Result<Option<RepoModel>> {
         let repo = RepoModel::find_by_id(pool, repo_id).await?;
         Ok(repo)
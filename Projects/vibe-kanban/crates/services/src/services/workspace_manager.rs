@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use db::models::{repo::Repo, workspace::Workspace as DbWorkspace};
+use git::GitService;
 use sqlx::{Pool, Sqlite};
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
@@ -57,6 +58,7 @@ impl WorkspaceManager {
     /// Create a workspace with worktrees for all repositories.
     /// On failure, rolls back any already-created worktrees.
     pub async fn create_workspace(
+        git: &GitService,
         workspace_dir: &Path,
         repos: &[RepoWorkspaceInput],
         branch_name: &str,
@@ -85,6 +87,7 @@ impl WorkspaceManager {
             );
 
             match WorktreeManager::create_worktree(
+                git,
                 &input.repo.path,
                 branch_name,
                 &worktree_path,
@@ -139,6 +142,7 @@ impl WorkspaceManager {
 
     /// Ensure all worktrees in a workspace exist (for cold restart scenarios)
     pub async fn ensure_workspace_exists(
+        git: &GitService,
         workspace_dir: &Path,
         repos: &[Repo],
         branch_name: &str,
@@ -149,7 +153,7 @@ impl WorkspaceManager {
 
         // Try legacy migration first (single repo projects only)
         // Old layout had worktree directly at workspace_dir; new layout has it at workspace_dir/{repo_name}
-        if repos.len() == 1 && Self::migrate_legacy_worktree(workspace_dir, &repos[0]).await? {
+        if repos.len() == 1 && Self::migrate_legacy_worktree(git, workspace_dir, &repos[0]).await? {
             return Ok(());
         }
 
@@ -166,7 +170,7 @@ impl WorkspaceManager {
                 worktree_path.display()
             );
 
-            WorktreeManager::ensure_worktree_exists(&repo.path, branch_name, &worktree_path)
+            WorktreeManager::ensure_worktree_exists(git, &repo.path, branch_name, &worktree_path)
                 .await?;
         }
 
@@ -215,6 +219,7 @@ impl WorkspaceManager {
     ///
     /// Returns Ok(true) if migration was performed, Ok(false) if no migration needed.
     pub async fn migrate_legacy_worktree(
+        git: &GitService,
         workspace_dir: &Path,
         repo: &Repo,
     ) -> Result<bool, WorkspaceError> {
@@ -247,13 +252,14 @@ impl WorkspaceManager {
         );
         let temp_path = workspace_dir.with_file_name(temp_name);
 
-        WorktreeManager::move_worktree(&repo.path, workspace_dir, &temp_path).await?;
+        WorktreeManager::move_worktree(git, &repo.path, workspace_dir, &temp_path).await?;
 
         // Create new workspace directory
         tokio::fs::create_dir_all(workspace_dir).await?;
 
         // Move worktree to final location using git worktree move
-        WorktreeManager::move_worktree(&repo.path, &temp_path, &expected_worktree_path).await?;
+        WorktreeManager::move_worktree(git, &repo.path, &temp_path, &expected_worktree_path)
+            .await?;
 
         if temp_path.exists() {
             let _ = tokio::fs::remove_dir_all(&temp_path).await;
@@ -61,6 +61,7 @@ impl WorktreeManager {
 
     /// Create a worktree with a new branch
     pub async fn create_worktree(
+        git: &GitService,
         repo_path: &Path,
         branch_name: &str,
         worktree_path: &Path,
@@ -87,12 +88,13 @@ impl WorktreeManager {
             .map_err(|e| WorktreeError::TaskJoin(format!("Task join error: {e}")))??;
         }
 
-        Self::ensure_worktree_exists(repo_path, branch_name, worktree_path).await
+        Self::ensure_worktree_exists(git, repo_path, branch_name, worktree_path).await
     }
 
     /// Ensure worktree exists, recreating if necessary with proper synchronization
     /// This is the main entry point for ensuring a worktree exists and prevents race conditions
     pub async fn ensure_worktree_exists(
+        git: &GitService,
         repo_path: &Path,
         branch_name: &str,
         worktree_path: &Path,
@@ -112,18 +114,39 @@ impl WorktreeManager {
         let _guard = lock.lock().await;
 
         // Check if worktree already exists and is properly set up
-        if Self::is_worktree_properly_set_up(repo_path, worktree_path).await? {
+        if Self::is_worktree_properly_set_up(git, repo_path, worktree_path).await? {
             trace!("Worktree already properly set up at path: {}", path_str);
             return Ok(());
         }
 
+        // An unclean shutdown can leave a directory whose git link is broken; try to
+        // repair it in place before throwing it away
+        if worktree_path.exists() {
+            let git = git.clone();
+            let repo_path_owned = repo_path.to_path_buf();
+            let worktree_path_owned = worktree_path.to_path_buf();
+            let repaired = tokio::task::spawn_blocking(move || {
+                git.repair_worktree(&repo_path_owned, &worktree_path_owned)
+            })
+            .await
+            .map_err(|e| WorktreeError::TaskJoin(format!("Task join error: {e}")))?;
+            match repaired {
+                Ok(()) => {
+                    info!("Repaired worktree at path: {}", path_str);
+                    return Ok(());
+                }
+                Err(e) => debug!("Worktree repair failed at {}: {}", path_str, e),
+            }
+        }
+
         // If worktree doesn't exist or isn't properly set up, recreate it
         info!("Worktree needs recreation at path: {}", path_str);
-        Self::recreate_worktree_internal(repo_path, branch_name, worktree_path).await
+        Self::recreate_worktree_internal(git, repo_path, branch_name, worktree_path).await
     }
 
     /// Internal worktree recreation function (always recreates)
     async fn recreate_worktree_internal(
+        git: &GitService,
         repo_path: &Path,
         branch_name: &str,
         worktree_path: &Path,
@@ -138,7 +161,7 @@ impl WorktreeManager {
         );
 
         // Step 1: Comprehensive cleanup of existing worktree and metadata (non-blocking)
-        Self::comprehensive_worktree_cleanup_async(repo_path, &worktree_path_owned).await?;
+        Self::comprehensive_worktree_cleanup_async(git, repo_path, &worktree_path_owned).await?;
 
         // Step 2: Ensure parent directory exists (non-blocking)
         if let Some(parent) = worktree_path_owned.parent() {
@@ -151,6 +174,7 @@ impl WorktreeManager {
 
         // Step 3: Create the worktree with retry logic for metadata conflicts (non-blocking)
         Self::create_worktree_with_retry(
+            git,
             repo_path,
             &branch_name_owned,
             &worktree_path_owned,
@@ -161,9 +185,11 @@ impl WorktreeManager {
 
     /// Check if a worktree is properly set up (filesystem + git metadata)
     async fn is_worktree_properly_set_up(
+        git: &GitService,
         repo_path: &Path,
         worktree_path: &Path,
     ) -> Result<bool, WorktreeError> {
+        let git = git.clone();
         let repo_path = repo_path.to_path_buf();
         let worktree_path = worktree_path.to_path_buf();
 
@@ -182,9 +208,9 @@ impl WorktreeManager {
                 return Ok(false);
             };
 
-            // Try to find the worktree - if it exists and is valid, we're good
+            // Try to find the worktree - if it exists and its links are intact, we're good
             match repo.find_worktree(&worktree_name) {
-                Ok(_) => Ok(true),
+                Ok(_) => Ok(git.is_worktree_healthy(&repo_path, &worktree_path)),
                 Err(_) => Ok(false),
             }
         })
@@ -238,6 +264,7 @@ impl WorktreeManager {
 
     /// Comprehensive cleanup of worktree path and metadata to prevent "path exists" errors (blocking)
     fn comprehensive_worktree_cleanup(
+        git_service: &GitService,
         repo: &Repository,
         worktree_path: &Path,
     ) -> Result<(), WorktreeError> {
@@ -248,7 +275,6 @@ impl WorktreeManager {
 
         // Step 1: Use GitService to remove the worktree registration (force) if present
         // The Git CLI is more robust than libgit2 for mutable worktree operations
-        let git_service = GitService::new();
         if let Err(e) = git_service.remove_worktree(&git_repo_path, worktree_path, true) {
             debug!("git worktree remove non-fatal error: {}", e);
         }
@@ -278,6 +304,7 @@ impl WorktreeManager {
 
     /// Async version of comprehensive cleanup to avoid blocking the main runtime
     async fn comprehensive_worktree_cleanup_async(
+        git: &GitService,
         git_repo_path: &Path,
         worktree_path: &Path,
     ) -> Result<(), WorktreeError> {
@@ -294,8 +321,9 @@ impl WorktreeManager {
         match repo_result {
             Ok(Ok(repo)) => {
                 // Repository exists, perform comprehensive cleanup
+                let git = git.clone();
                 tokio::task::spawn_blocking(move || {
-                    Self::comprehensive_worktree_cleanup(&repo, &worktree_path_owned)
+                    Self::comprehensive_worktree_cleanup(&git, &repo, &worktree_path_owned)
                 })
                 .await
                 .map_err(|e| WorktreeError::TaskJoin(format!("Task join error: {e}")))?
@@ -317,6 +345,7 @@ impl WorktreeManager {
 
     /// Create worktree with retry logic in non-blocking manner
     async fn create_worktree_with_retry(
+        git: &GitService,
         git_repo_path: &Path,
         branch_name: &str,
         worktree_path: &Path,
@@ -326,10 +355,10 @@ impl WorktreeManager {
         let branch_name = branch_name.to_string();
         let worktree_path = worktree_path.to_path_buf();
         let path_str = path_str.to_string();
+        let git_service = git.clone();
 
         tokio::task::spawn_blocking(move || -> Result<(), WorktreeError> {
             // Prefer git CLI for worktree add to inherit sparse-checkout semantics
-            let git_service = GitService::new();
             match git_service.add_worktree(&git_repo_path, &worktree_path, &branch_name, false) {
                 Ok(()) => {
                     if !worktree_path.exists() {
@@ -516,16 +545,17 @@ impl WorktreeManager {
 
     /// Move a worktree to a new location
     pub async fn move_worktree(
+        git: &GitService,
         repo_path: &Path,
         old_path: &Path,
         new_path: &Path,
     ) -> Result<(), WorktreeError> {
+        let git_service = git.clone();
         let repo_path = repo_path.to_path_buf();
         let old_path = old_path.to_path_buf();
         let new_path = new_path.to_path_buf();
 
         tokio::task::spawn_blocking(move || {
-            let git_service = GitService::new();
             git_service
                 .move_worktree(&repo_path, &old_path, &new_path)
                 .map_err(WorktreeError::GitService)
@@ -575,6 +605,7 @@ async fn create_worktree_when_repo_path_is_a_worktree() {
 
     let base_worktree_path = td.path().join("wt-base");
     WorktreeManager::create_worktree(
+        &git_service,
         &repo_path,
         "wt-base-branch",
         &base_worktree_path,
@@ -587,6 +618,7 @@ async fn create_worktree_when_repo_path_is_a_worktree() {
 
     let child_worktree_path = td.path().join("wt-child");
     WorktreeManager::create_worktree(
+        &git_service,
         &base_worktree_path,
         "wt-child-branch",
         &child_worktree_path,
@@ -599,6 +631,7 @@ async fn create_worktree_when_repo_path_is_a_worktree() {
 
     // Regression: repo_path itself is a worktree (so `.git` is a file), but metadata lookup still works.
     WorktreeManager::ensure_worktree_exists(
+        &git_service,
         &base_worktree_path,
         "wt-child-branch",
         &child_worktree_path,
@@ -0,0 +1,158 @@
+use db::models::{
+    execution_process::{
+        CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+    },
+    project::{CreateProject, Project},
+    session::{CreateSession, Session},
+    task::{CreateTask, Task},
+    workspace::{CreateWorkspace, Workspace},
+};
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use uuid::Uuid;
+
+async fn test_pool() -> SqlitePool {
+    // One connection, so every query sees the same in-memory database
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::migrate!("../db/migrations").run(&pool).await.unwrap();
+    pool
+}
+
+async fn create_session(pool: &SqlitePool) -> (Workspace, Session) {
+    let project = Project::create(
+        pool,
+        &CreateProject {
+            name: "Queue".to_string(),
+            repositories: Vec::new(),
+        },
+        Uuid::new_v4(),
+    )
+    .await
+    .unwrap();
+    let task = Task::create(
+        pool,
+        &CreateTask {
+            project_id: project.id,
+            title: "Wait for a slot".to_string(),
+            description: None,
+            status: None,
+            parent_workspace_id: None,
+            image_ids: None,
+        },
+        Uuid::new_v4(),
+    )
+    .await
+    .unwrap();
+    let workspace = Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch: "vk/queued".to_string(),
+            agent_working_dir: None,
+        },
+        Uuid::new_v4(),
+        task.id,
+    )
+    .await
+    .unwrap();
+    let session = Session::create(
+        pool,
+        &CreateSession { executor: None },
+        Uuid::new_v4(),
+        workspace.id,
+    )
+    .await
+    .unwrap();
+    (workspace, session)
+}
+
+async fn create_queued(pool: &SqlitePool, session: &Session) -> ExecutionProcess {
+    ExecutionProcess::create(
+        pool,
+        &CreateExecutionProcess {
+            session_id: session.id,
+            executor_action: ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: "true".to_string(),
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::SetupScript,
+                    working_dir: None,
+                }),
+                None,
+            ),
+            run_reason: ExecutionProcessRunReason::CodingAgent,
+        },
+        Uuid::new_v4(),
+        ExecutionProcessStatus::Queued,
+        &[],
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn queued_processes_count_as_active_until_they_finish() {
+    let pool = test_pool().await;
+    let (workspace, session) = create_session(&pool).await;
+
+    let queued = create_queued(&pool, &session).await;
+    assert_eq!(queued.status, ExecutionProcessStatus::Queued);
+    assert!(
+        ExecutionProcess::has_running_non_dev_server_processes_for_workspace(&pool, workspace.id)
+            .await
+            .unwrap()
+    );
+    let active = ExecutionProcess::find_running(&pool).await.unwrap();
+    assert_eq!(active.len(), 1);
+
+    assert!(
+        ExecutionProcess::mark_started(&pool, queued.id)
+            .await
+            .unwrap()
+    );
+    let started = ExecutionProcess::find_by_id(&pool, queued.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(started.status, ExecutionProcessStatus::Running);
+    // Only a queued process can be started
+    assert!(
+        !ExecutionProcess::mark_started(&pool, queued.id)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn a_stopped_queued_process_is_not_started() {
+    let pool = test_pool().await;
+    let (_, session) = create_session(&pool).await;
+
+    let queued = create_queued(&pool, &session).await;
+    ExecutionProcess::update_completion(&pool, queued.id, ExecutionProcessStatus::Killed, None)
+        .await
+        .unwrap();
+
+    assert!(
+        !ExecutionProcess::mark_started(&pool, queued.id)
+            .await
+            .unwrap()
+    );
+    let stopped = ExecutionProcess::find_by_id(&pool, queued.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stopped.status, ExecutionProcessStatus::Killed);
+    assert!(
+        ExecutionProcess::find_running(&pool)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
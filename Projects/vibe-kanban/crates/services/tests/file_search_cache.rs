@@ -0,0 +1,73 @@
+use std::{fs, path::Path, time::Duration};
+
+use db::models::project::SearchResult;
+use git::{GitCli, GitService};
+use services::services::file_search::{FileSearchCache, SearchMode};
+use tempfile::TempDir;
+
+fn git(repo_path: &Path, args: &[&str]) -> String {
+    GitCli::new().git(repo_path, args.iter().copied()).unwrap()
+}
+
+fn write_and_commit(repo_path: &Path, file: &str, message: &str) {
+    fs::write(repo_path.join(file), "content\n").unwrap();
+    git(repo_path, &["add", file]);
+    git(repo_path, &["commit", "-q", "-m", message]);
+}
+
+/// Searches until the cache serves a hit, waiting out background builds
+async fn cached_search(cache: &FileSearchCache, path: &Path, query: &str) -> Vec<SearchResult> {
+    for _ in 0..100 {
+        if let Ok(results) = cache.search(path, query, SearchMode::TaskForm).await {
+            return results;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("file search cache for {path:?} was never built");
+}
+
+#[tokio::test]
+async fn committing_in_a_worktree_refreshes_its_index_once_marked_dirty() {
+    let root = TempDir::new().unwrap();
+    let repo_path = root.path().join("repo");
+    GitService::new()
+        .initialize_repo_with_main_branch(&repo_path)
+        .unwrap();
+    git(&repo_path, &["config", "user.name", "Test User"]);
+    git(&repo_path, &["config", "user.email", "test@example.com"]);
+    write_and_commit(&repo_path, "existing.txt", "initial");
+
+    let worktree_path = root.path().join("worktree");
+    git(
+        &repo_path,
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "feature",
+            worktree_path.to_str().unwrap(),
+        ],
+    );
+
+    let cache = FileSearchCache::new(GitService::new());
+    assert!(
+        cached_search(&cache, &worktree_path, "fresh")
+            .await
+            .is_empty()
+    );
+
+    // Until the worktree is marked dirty its index is stale
+    fs::write(worktree_path.join("fresh.txt"), "content\n").unwrap();
+    assert!(
+        cached_search(&cache, &worktree_path, "fresh")
+            .await
+            .is_empty()
+    );
+
+    write_and_commit(&worktree_path, "fresh.txt", "add fresh file");
+    cache.mark_dirty(&worktree_path);
+
+    let results = cached_search(&cache, &worktree_path, "fresh").await;
+    assert!(results.iter().any(|r| r.path == "fresh.txt"));
+}
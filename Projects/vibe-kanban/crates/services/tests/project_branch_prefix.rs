@@ -0,0 +1,45 @@
+use db::models::project::{CreateProject, Project};
+use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use uuid::Uuid;
+
+async fn test_pool() -> SqlitePool {
+    // One connection, so every query sees the same in-memory database
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::migrate!("../db/migrations").run(&pool).await.unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn only_new_projects_enforce_the_branch_prefix() {
+    let pool = test_pool().await;
+
+    // A row inserted without the column, as projects from before the migration were
+    let existing_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO projects (id, name) VALUES ($1, $2)")
+        .bind(existing_id)
+        .bind("existing")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let existing = Project::find_by_id(&pool, existing_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!existing.enforce_branch_prefix);
+
+    let created = Project::create(
+        &pool,
+        &CreateProject {
+            name: "new".to_string(),
+            repositories: Vec::new(),
+        },
+        Uuid::new_v4(),
+    )
+    .await
+    .unwrap();
+    assert!(created.enforce_branch_prefix);
+}
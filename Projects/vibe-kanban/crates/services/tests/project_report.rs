@@ -0,0 +1,220 @@
+use std::{fs, path::Path};
+
+use db::models::{
+    execution_process::{
+        CreateExecutionProcess, ExecutionChangeOutcome, ExecutionProcess,
+        ExecutionProcessRunReason, ExecutionProcessStatus,
+    },
+    execution_process_repo_state::CreateExecutionProcessRepoState,
+    merge::Merge,
+    project::{CreateProject, Project},
+    repo::Repo,
+    session::{CreateSession, Session},
+    task::{CreateTask, Task},
+    workspace::{CreateWorkspace, Workspace},
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+use executors::actions::{
+    ExecutorAction, ExecutorActionType,
+    script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+};
+use git::{GitCli, GitService};
+use services::services::project::{ProjectReportQuery, ProjectService};
+use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use tempfile::TempDir;
+use uuid::Uuid;
+
+async fn test_pool() -> SqlitePool {
+    // One connection, so every query sees the same in-memory database
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::migrate!("../db/migrations").run(&pool).await.unwrap();
+    pool
+}
+
+fn git(repo_path: &Path, args: &[&str]) -> String {
+    GitCli::new().git(repo_path, args.iter().copied()).unwrap()
+}
+
+fn write_and_commit(repo_path: &Path, file: &str, content: &str, message: &str) {
+    fs::write(repo_path.join(file), content).unwrap();
+    git(repo_path, &["add", file]);
+    git(repo_path, &["commit", "-q", "-m", message]);
+}
+
+fn init_repo(root: &TempDir) -> std::path::PathBuf {
+    let path = root.path().join("repo");
+    GitService::new()
+        .initialize_repo_with_main_branch(&path)
+        .unwrap();
+    git(&path, &["config", "user.name", "Test User"]);
+    git(&path, &["config", "user.email", "test@example.com"]);
+    path
+}
+
+async fn create_workspace(
+    pool: &SqlitePool,
+    task_id: Uuid,
+    repo_id: Uuid,
+    branch: &str,
+) -> Workspace {
+    let workspace = Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch: branch.to_string(),
+            agent_working_dir: None,
+        },
+        Uuid::new_v4(),
+        task_id,
+    )
+    .await
+    .unwrap();
+    WorkspaceRepo::create_many(
+        pool,
+        workspace.id,
+        &[CreateWorkspaceRepo {
+            repo_id,
+            target_branch: "main".to_string(),
+        }],
+    )
+    .await
+    .unwrap();
+    workspace
+}
+
+#[tokio::test]
+async fn report_counts_merged_branches_from_where_they_started() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo(&td);
+    let start = git(&repo_path, &["rev-parse", "HEAD"]).trim().to_string();
+    git(&repo_path, &["checkout", "-q", "-b", "vk/feature"]);
+    write_and_commit(&repo_path, "a.txt", "one\ntwo\n", "add a");
+    write_and_commit(&repo_path, "b.txt", "three\n", "add b");
+    git(&repo_path, &["checkout", "-q", "main"]);
+    git(&repo_path, &["merge", "-q", "--ff-only", "vk/feature"]);
+    let merge_commit = git(&repo_path, &["rev-parse", "HEAD"]).trim().to_string();
+
+    let pool = test_pool().await;
+    let project = Project::create(
+        &pool,
+        &CreateProject {
+            name: "Report".to_string(),
+            repositories: Vec::new(),
+        },
+        Uuid::new_v4(),
+    )
+    .await
+    .unwrap();
+    let repo = Repo::find_or_create(&pool, &repo_path, "repo")
+        .await
+        .unwrap();
+    let task = Task::create(
+        &pool,
+        &CreateTask {
+            project_id: project.id,
+            title: "Ship it".to_string(),
+            description: None,
+            status: None,
+            parent_workspace_id: None,
+            image_ids: None,
+        },
+        Uuid::new_v4(),
+    )
+    .await
+    .unwrap();
+
+    let merged = create_workspace(&pool, task.id, repo.id, "vk/feature").await;
+    let session = Session::create(
+        &pool,
+        &CreateSession { executor: None },
+        Uuid::new_v4(),
+        merged.id,
+    )
+    .await
+    .unwrap();
+    let run = ExecutionProcess::create(
+        &pool,
+        &CreateExecutionProcess {
+            session_id: session.id,
+            executor_action: ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: "true".to_string(),
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::SetupScript,
+                    working_dir: None,
+                }),
+                None,
+            ),
+            run_reason: ExecutionProcessRunReason::CodingAgent,
+        },
+        Uuid::new_v4(),
+        ExecutionProcessStatus::Running,
+        &[CreateExecutionProcessRepoState {
+            repo_id: repo.id,
+            before_head_commit: Some(start),
+            after_head_commit: None,
+            merge_commit: None,
+        }],
+    )
+    .await
+    .unwrap();
+    ExecutionProcess::update_change_outcome(&pool, run.id, ExecutionChangeOutcome::ChangesMade)
+        .await
+        .unwrap();
+    Merge::create_direct(&pool, merged.id, repo.id, "main", &merge_commit)
+        .await
+        .unwrap();
+
+    // Never started, and its branch was never created
+    let abandoned = create_workspace(&pool, task.id, repo.id, "vk/abandoned").await;
+
+    let report = ProjectService
+        .build_report(
+            &pool,
+            &GitService::new(),
+            &project,
+            &ProjectReportQuery::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(report.totals.attempts, 2);
+    assert_eq!(report.totals.merged_attempts, 1);
+    assert_eq!(report.totals.files_changed, 2);
+    assert_eq!(report.totals.lines_added, 3);
+
+    let merged_report = report
+        .attempts
+        .iter()
+        .find(|a| a.workspace_id == merged.id)
+        .unwrap();
+    assert!(merged_report.merged);
+    assert_eq!(merged_report.task_title, "Ship it");
+    assert_eq!(merged_report.session_count, 1);
+    assert_eq!(merged_report.coding_agent_runs, 1);
+    assert_eq!(
+        merged_report.latest_run_status,
+        Some(ExecutionProcessStatus::Running)
+    );
+    assert_eq!(
+        merged_report.change_outcome,
+        Some(ExecutionChangeOutcome::ChangesMade)
+    );
+    assert_eq!(
+        merged_report.repos[0].merge_commit.as_deref(),
+        Some(merge_commit.as_str())
+    );
+
+    let abandoned_report = report
+        .attempts
+        .iter()
+        .find(|a| a.workspace_id == abandoned.id)
+        .unwrap();
+    assert!(!abandoned_report.merged);
+    assert_eq!(abandoned_report.session_count, 0);
+    assert_eq!(abandoned_report.latest_run_status, None);
+    assert_eq!(abandoned_report.repos[0].files_changed, None);
+}
@@ -0,0 +1,51 @@
+use std::fs;
+
+use git::GitService;
+use services::services::repo::{RepoError, RepoService};
+use tempfile::TempDir;
+
+#[test]
+fn validate_git_repo_path_rejects_unusable_paths() {
+    let root = TempDir::new().unwrap();
+    let git = GitService::new();
+    let service = RepoService::new();
+
+    let missing = root.path().join("missing");
+    assert!(matches!(
+        service.validate_git_repo_path(&git, &missing),
+        Err(RepoError::PathNotFound(_))
+    ));
+
+    let file = root.path().join("file.txt");
+    fs::write(&file, "content\n").unwrap();
+    assert!(matches!(
+        service.validate_git_repo_path(&git, &file),
+        Err(RepoError::PathNotDirectory(_))
+    ));
+
+    let plain_dir = root.path().join("plain");
+    fs::create_dir(&plain_dir).unwrap();
+    assert!(matches!(
+        service.validate_git_repo_path(&git, &plain_dir),
+        Err(RepoError::NotGitRepository(_))
+    ));
+
+    // A `.git` entry that git can't open
+    let broken = root.path().join("broken");
+    fs::create_dir_all(broken.join(".git")).unwrap();
+    assert!(matches!(
+        service.validate_git_repo_path(&git, &broken),
+        Err(RepoError::NotGitRepository(_))
+    ));
+
+    let empty = root.path().join("empty");
+    git2::Repository::init(&empty).unwrap();
+    assert!(matches!(
+        service.validate_git_repo_path(&git, &empty),
+        Err(RepoError::NoBranches(_))
+    ));
+
+    let repo = root.path().join("repo");
+    git.initialize_repo_with_main_branch(&repo).unwrap();
+    assert!(service.validate_git_repo_path(&git, &repo).is_ok());
+}
@@ -66,7 +66,13 @@ pub struct ProfileResponse {
 #[serde(tag = "status", rename_all = "lowercase")]
 pub enum LoginStatus {
     LoggedOut,
-    LoggedIn { profile: ProfileResponse },
+    LoggedIn {
+        profile: ProfileResponse,
+    },
+    /// Credentials are stored but the remote server could not be reached after
+    /// retrying; the user stays signed in and the status is checked again on
+    /// the next request.
+    Offline,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, TS)]
@@ -77,3 +83,55 @@ pub struct StatusResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub degraded: Option<bool>,
 }
+
+impl From<LoginStatus> for StatusResponse {
+    fn from(status: LoginStatus) -> Self {
+        match status {
+            LoginStatus::LoggedOut => Self {
+                logged_in: false,
+                profile: None,
+                degraded: None,
+            },
+            LoginStatus::LoggedIn { profile } => Self {
+                logged_in: true,
+                profile: Some(profile),
+                degraded: None,
+            },
+            // Still signed in, just without a fresh profile
+            LoginStatus::Offline => Self {
+                logged_in: true,
+                profile: None,
+                degraded: Some(true),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_login_is_reported_as_degraded() {
+        let offline = serde_json::to_value(StatusResponse::from(LoginStatus::Offline)).unwrap();
+        assert_eq!(
+            offline,
+            serde_json::json!({ "logged_in": true, "degraded": true })
+        );
+
+        let logged_out =
+            serde_json::to_value(StatusResponse::from(LoginStatus::LoggedOut)).unwrap();
+        assert_eq!(logged_out, serde_json::json!({ "logged_in": false }));
+
+        let profile = ProfileResponse {
+            user_id: Uuid::new_v4(),
+            username: None,
+            email: "user@example.com".to_string(),
+            providers: Vec::new(),
+        };
+        let logged_in = StatusResponse::from(LoginStatus::LoggedIn { profile });
+        assert!(logged_in.logged_in);
+        assert!(logged_in.profile.is_some());
+        assert_eq!(logged_in.degraded, None);
+    }
+}
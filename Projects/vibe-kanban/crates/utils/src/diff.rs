@@ -30,6 +30,23 @@ pub struct Diff {
     pub additions: Option<usize>,
     pub deletions: Option<usize>,
     pub repo_id: Option<Uuid>,
+    /// True when the file changed after the last reviewed commit
+    #[serde(default)]
+    pub changed_since_review: bool,
+    /// Set when the path is a submodule; its contents are never loaded
+    #[serde(default)]
+    pub submodule: Option<SubmoduleUpdate>,
+}
+
+/// Commits a submodule pointer moved between. A side is `None` when the
+/// submodule was added or removed.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmoduleUpdate {
+    pub old_commit: Option<String>,
+    pub new_commit: Option<String>,
+    /// Subject of the new commit, when the submodule is checked out locally
+    pub new_commit_subject: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -42,6 +59,38 @@ pub enum DiffChangeKind {
     Renamed,
     Copied,
     PermissionChange,
+    SubmoduleUpdate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChangeSize {
+    pub path: String,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Compact stand-in for a changeset too large to list file by file
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSummary {
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Files with the most changed lines, largest first
+    pub largest_changes: Vec<FileChangeSize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DiffResult {
+    Full {
+        diffs: Vec<Diff>,
+    },
+    /// The changeset exceeded the size limits; request the full diff explicitly
+    Summary {
+        summary: DiffSummary,
+    },
 }
 
 // ==============================
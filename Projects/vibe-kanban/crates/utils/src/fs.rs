@@ -0,0 +1,61 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Atomically replace `path` with `contents`.
+///
+/// The data is written and fsynced to a temporary file in the same directory,
+/// then renamed over the target, so a crash mid-write leaves either the old or
+/// the new file on disk but never a truncated one.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    write_atomic_inner(path, contents.as_ref(), false)
+}
+
+/// Same as [`write_atomic`], but the file is only readable by the owner (0600 on Unix).
+pub fn write_atomic_private(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    write_atomic_inner(path, contents.as_ref(), true)
+}
+
+fn write_atomic_inner(path: &Path, contents: &[u8], private: bool) -> io::Result<()> {
+    let tmp = tmp_path(path)?;
+
+    let result = (|| {
+        let mut opts = OpenOptions::new();
+        opts.create(true).truncate(true).write(true);
+
+        #[cfg(unix)]
+        if private {
+            use std::os::unix::fs::OpenOptionsExt;
+            opts.mode(0o600);
+        }
+        #[cfg(not(unix))]
+        let _ = private;
+
+        let mut file = opts.open(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&tmp, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+fn tmp_path(path: &Path) -> io::Result<PathBuf> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} has no file name", path.display()),
+        )
+    })?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(".tmp");
+    Ok(path.with_file_name(tmp_name))
+}
@@ -7,11 +7,13 @@ pub mod approvals;
 pub mod assets;
 pub mod browser;
 pub mod diff;
+pub mod fs;
 pub mod jwt;
 pub mod log_msg;
 pub mod msg_store;
 pub mod path;
 pub mod port_file;
+pub mod redaction;
 pub mod response;
 pub mod sentry;
 pub mod shell;
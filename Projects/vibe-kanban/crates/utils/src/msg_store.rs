@@ -8,7 +8,7 @@ use futures::{StreamExt, TryStreamExt, future};
 use tokio::{sync::broadcast, task::JoinHandle};
 use tokio_stream::wrappers::BroadcastStream;
 
-use crate::{log_msg::LogMsg, stream_lines::LinesStreamExt};
+use crate::{log_msg::LogMsg, redaction::Redactor, stream_lines::LinesStreamExt};
 
 // 100 MB Limit
 const HISTORY_BYTES: usize = 100000 * 1024;
@@ -27,6 +27,8 @@ struct Inner {
 pub struct MsgStore {
     inner: RwLock<Inner>,
     sender: broadcast::Sender<LogMsg>,
+    redactor: Option<Arc<Redactor>>,
+    context_warning_threshold: Option<u8>,
 }
 
 impl Default for MsgStore {
@@ -37,6 +39,12 @@ impl Default for MsgStore {
 
 impl MsgStore {
     pub fn new() -> Self {
+        Self::with_redactor(None)
+    }
+
+    /// A store that masks secrets in every message before it is streamed or
+    /// kept, and so before anything reads it back to normalize or persist it.
+    pub fn with_redactor(redactor: Option<Arc<Redactor>>) -> Self {
         let (sender, _) = broadcast::channel(10000);
         Self {
             inner: RwLock::new(Inner {
@@ -44,10 +52,27 @@ impl MsgStore {
                 total_bytes: 0,
             }),
             sender,
+            redactor,
+            context_warning_threshold: None,
         }
     }
 
+    /// Has log normalizers for this store warn once the agent's context window
+    /// is `threshold_percent` full. `None` (the default) never warns.
+    pub fn with_context_warning(mut self, threshold_percent: Option<u8>) -> Self {
+        self.context_warning_threshold = threshold_percent;
+        self
+    }
+
+    pub fn context_warning_threshold(&self) -> Option<u8> {
+        self.context_warning_threshold
+    }
+
     pub fn push(&self, msg: LogMsg) {
+        let msg = match &self.redactor {
+            Some(redactor) => redactor.redact_msg(msg),
+            None => msg,
+        };
         let _ = self.sender.send(msg.clone()); // live listeners
         let bytes = msg.approx_bytes();
 
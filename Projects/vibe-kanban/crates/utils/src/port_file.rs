@@ -1,26 +1,94 @@
 use std::{env, path::PathBuf};
 
+use serde::{Deserialize, Serialize};
 use tokio::fs;
 
-pub async fn write_port_file(port: u16) -> std::io::Result<PathBuf> {
-    let dir = env::temp_dir().join("vibe-kanban");
-    let path = dir.join("vibe-kanban.port");
+/// Overrides the port file location, e.g. to run several instances side by side.
+pub const PORT_FILE_ENV: &str = "VK_PORT_FILE";
+/// Set to `json` to write [`PortFileInfo`] instead of the bare port number.
+pub const PORT_FILE_FORMAT_ENV: &str = "VK_PORT_FILE_FORMAT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PortFileFormat {
+    #[default]
+    Plain,
+    Json,
+}
+
+impl PortFileFormat {
+    pub fn from_env() -> Self {
+        match env::var(PORT_FILE_FORMAT_ENV) {
+            Ok(v) if v.trim().eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Plain,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortFileInfo {
+    pub port: u16,
+    pub host: String,
+    pub pid: u32,
+}
+
+fn port_file_path(app_name: &str) -> PathBuf {
+    match env::var_os(PORT_FILE_ENV) {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => env::temp_dir()
+            .join(app_name)
+            .join(format!("{app_name}.port")),
+    }
+}
+
+pub async fn write_port_file(port: u16, host: &str) -> std::io::Result<PathBuf> {
+    let path = port_file_path("vibe-kanban");
+    let contents = match PortFileFormat::from_env() {
+        PortFileFormat::Plain => port.to_string(),
+        PortFileFormat::Json => serde_json::to_string(&PortFileInfo {
+            port,
+            host: host.to_string(),
+            pid: std::process::id(),
+        })?,
+    };
     tracing::debug!("Writing port {} to {:?}", port, path);
-    fs::create_dir_all(&dir).await?;
-    fs::write(&path, port.to_string()).await?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+    fs::write(&path, contents).await?;
     Ok(path)
 }
 
 pub async fn read_port_file(app_name: &str) -> std::io::Result<u16> {
-    let dir = env::temp_dir().join(app_name);
-    let path = dir.join(format!("{app_name}.port"));
+    let path = port_file_path(app_name);
     tracing::debug!("Reading port from {:?}", path);
 
     let content = fs::read_to_string(&path).await?;
-    let port: u16 = content
-        .trim()
+    parse_port(&content)
+}
+
+/// Accepts both the plain and JSON port file formats.
+fn parse_port(content: &str) -> std::io::Result<u16> {
+    let content = content.trim();
+    if content.starts_with('{') {
+        let info: PortFileInfo = serde_json::from_str(content)?;
+        return Ok(info.port);
+    }
+    content
         .parse()
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(port)
+    #[test]
+    fn parses_plain_and_json_port_files() {
+        assert_eq!(parse_port("53427\n").unwrap(), 53427);
+        assert_eq!(
+            parse_port(r#"{"port":8080,"host":"127.0.0.1","pid":42}"#).unwrap(),
+            8080
+        );
+        assert!(parse_port("not a port").is_err());
+    }
 }
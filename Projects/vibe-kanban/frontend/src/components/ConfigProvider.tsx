@@ -23,6 +23,7 @@ interface UserSystemState {
   environment: Environment | null;
   profiles: Record<string, ExecutorConfig> | null;
   capabilities: Record<string, BaseAgentCapability[]> | null;
+  supportedModels: Record<string, string[]> | null;
   analyticsUserId: string | null;
   loginStatus: LoginStatus | null;
 }
@@ -41,6 +42,7 @@ interface UserSystemContextType {
   environment: Environment | null;
   profiles: Record<string, ExecutorConfig> | null;
   capabilities: Record<string, BaseAgentCapability[]> | null;
+  supportedModels: Record<string, string[]> | null;
   analyticsUserId: string | null;
   loginStatus: LoginStatus | null;
   setEnvironment: (env: Environment | null) => void;
@@ -83,6 +85,9 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
       string,
       BaseAgentCapability[]
     > | null) || null;
+  const supportedModels =
+    (userSystemInfo?.supported_models as Record<string, string[]> | null) ||
+    null;
 
   // Sync language with i18n when config changes
   useEffect(() => {
@@ -186,6 +191,7 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
         environment,
         profiles,
         capabilities,
+        supportedModels,
         analyticsUserId,
         loginStatus,
       },
@@ -193,6 +199,7 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
       environment,
       profiles,
       capabilities,
+      supportedModels,
       analyticsUserId,
       loginStatus,
       updateConfig,
@@ -209,6 +216,7 @@ export function UserSystemProvider({ children }: UserSystemProviderProps) {
       environment,
       profiles,
       capabilities,
+      supportedModels,
       analyticsUserId,
       loginStatus,
       updateConfig,
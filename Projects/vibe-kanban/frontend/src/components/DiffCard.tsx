@@ -17,6 +17,7 @@ import {
   PencilLine,
   Copy,
   Key,
+  GitCommit,
   ExternalLink,
   MessageSquare,
 } from 'lucide-react';
@@ -53,6 +54,13 @@ function labelAndIcon(diff: Diff) {
   if (c === 'copied') return { label: 'Copied', Icon: Copy };
   if (c === 'permissionChange')
     return { label: 'Permission Changed', Icon: Key };
+  if (c === 'submoduleUpdate') {
+    const short = (sha?: string | null) => sha?.slice(0, 7) ?? '?';
+    const range = `${short(diff.submodule?.oldCommit)} → ${short(diff.submodule?.newCommit)}`;
+    const subject = diff.submodule?.newCommitSubject;
+    const label = `Submodule ${range}${subject ? ` (${subject})` : ''}`;
+    return { label, Icon: GitCommit };
+  }
   return { label: undefined as string | undefined, Icon: PencilLine };
 }
 
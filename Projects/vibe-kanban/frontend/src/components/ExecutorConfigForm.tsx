@@ -9,6 +9,7 @@ import { Card, CardContent } from '@/components/ui/card';
 import { Button } from '@/components/ui/button';
 import { Loader2 } from 'lucide-react';
 import { shadcnTheme } from './rjsf';
+import { useUserSystem } from '@/components/ConfigProvider';
 import { BaseCodingAgent } from 'shared/types';
 // Using custom shadcn/ui widgets instead of @rjsf/shadcn theme
 
@@ -40,9 +41,24 @@ export function ExecutorConfigForm({
     RJSFValidationError[]
   >([]);
 
+  const { supportedModels } = useUserSystem();
+
   const schema = useMemo(() => {
-    return schemas[executor];
-  }, [executor]);
+    const base = schemas[executor];
+    const model = base?.properties?.model;
+    const models = supportedModels?.[executor];
+    if (typeof model !== 'object' || !models?.length) {
+      return base;
+    }
+    // Suggest the executor's known models in the free-text model field
+    return {
+      ...base,
+      properties: {
+        ...base.properties,
+        model: { ...model, examples: models },
+      },
+    };
+  }, [executor, supportedModels]);
 
   // Custom handler for env field updates
   const handleEnvChange = useCallback(
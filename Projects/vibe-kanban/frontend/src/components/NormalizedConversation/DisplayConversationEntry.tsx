@@ -99,7 +99,10 @@ const getEntryIcon = (entryType: NormalizedEntryType) => {
       return <Terminal className={iconSize} />;
     } else if (action_type.action === 'search') {
       return <Search className={iconSize} />;
-    } else if (action_type.action === 'web_fetch') {
+    } else if (
+      action_type.action === 'web_fetch' ||
+      action_type.action === 'web_search'
+    ) {
       return <Globe className={iconSize} />;
     } else if (action_type.action === 'task_create') {
       return <Plus className={iconSize} />;
@@ -19,12 +19,18 @@ export interface ForcePushDialogProps {
   attemptId: string;
   repoId: string;
   branchName?: string;
+  // Remote commit reported by the rejected push; the force push only
+  // overwrites the remote while it is still there
+  remoteSha?: string | null;
 }
 
 const ForcePushDialogImpl = NiceModal.create<ForcePushDialogProps>((props) => {
   const modal = useModal();
-  const { attemptId, repoId, branchName } = props;
+  const { attemptId, repoId, branchName, remoteSha } = props;
   const [error, setError] = useState<string | null>(null);
+  const [expectedSha, setExpectedSha] = useState(remoteSha ?? null);
+  // The remote moved again; the next attempt overwrites what it now has
+  const [remoteChanged, setRemoteChanged] = useState(false);
   const { t } = useTranslation(['tasks', 'common']);
   const branchLabel = branchName ? ` "${branchName}"` : '';
 
@@ -35,7 +41,13 @@ const ForcePushDialogImpl = NiceModal.create<ForcePushDialogProps>((props) => {
       modal.resolve('success');
       modal.hide();
     },
-    (err: unknown) => {
+    (err: unknown, errorData) => {
+      if (errorData?.type === 'remote_changed') {
+        setExpectedSha(errorData.remote_sha);
+        setRemoteChanged(true);
+        setError(t('tasks:git.forcePushDialog.remoteChanged'));
+        return;
+      }
       // Error - show in dialog and keep open
       const message =
         err && typeof err === 'object' && 'message' in err
@@ -48,7 +60,10 @@ const ForcePushDialogImpl = NiceModal.create<ForcePushDialogProps>((props) => {
   const handleConfirm = async () => {
     setError(null);
     try {
-      await forcePush.mutateAsync({ repo_id: repoId });
+      await forcePush.mutateAsync({
+        repo_id: repoId,
+        expected_remote_sha: expectedSha ?? undefined,
+      });
     } catch {
       // Error already handled by onError callback
     }
@@ -100,7 +115,9 @@ const ForcePushDialogImpl = NiceModal.create<ForcePushDialogProps>((props) => {
             {isProcessing && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
             {isProcessing
               ? t('tasks:git.states.forcePushing')
-              : t('tasks:git.states.forcePush')}
+              : remoteChanged
+                ? t('tasks:git.forcePushDialog.overwrite')
+                : t('tasks:git.states.forcePush')}
           </Button>
         </DialogFooter>
       </DialogContent>
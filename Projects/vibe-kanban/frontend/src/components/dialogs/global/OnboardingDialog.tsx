@@ -66,6 +66,9 @@ const OnboardingDialogImpl = NiceModal.create<NoProps>(() => {
           editorType === EditorType.CUSTOM ? customCommand || null : null,
         remote_ssh_host: null,
         remote_ssh_user: null,
+        remote_target: null,
+        profiles: [],
+        fallback_editors: [],
       },
     } as OnboardingResult);
   };
@@ -189,7 +189,9 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
                 ? 'GitHub'
                 : result.error.provider === 'azure_dev_ops'
                   ? 'Azure DevOps'
-                  : 'Git host';
+                  : result.error.provider === 'git_lab'
+                    ? 'GitLab'
+                    : 'Git host';
             const action =
               result.error.type === 'cli_not_installed'
                 ? 'not installed'
@@ -218,6 +220,10 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
           );
           setGhCliHelp(null);
           return;
+        } else if (result.error.type === 'missing_permissions') {
+          setError(t('createPrDialog.errors.missingPermissions'));
+          setGhCliHelp(null);
+          return;
         }
       }
 
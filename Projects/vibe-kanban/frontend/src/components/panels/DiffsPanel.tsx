@@ -34,6 +34,7 @@ const DEFAULT_DIFF_COLLAPSE_DEFAULTS: DiffCollapseDefaults = {
   renamed: true,
   copied: true,
   permissionChange: true,
+  submoduleUpdate: true,
 };
 
 const DEFAULT_COLLAPSE_MAX_LINES = 200;
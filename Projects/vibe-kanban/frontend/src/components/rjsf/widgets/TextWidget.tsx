@@ -12,7 +12,12 @@ export const TextWidget = (props: WidgetProps) => {
     onFocus,
     placeholder,
     options,
+    schema,
   } = props;
+  const examples = Array.isArray(schema.examples)
+    ? schema.examples.map(String)
+    : [];
+  const listId = examples.length > 0 ? `${id}-examples` : undefined;
 
   const handleChange = (event: React.ChangeEvent<HTMLInputElement>) => {
     const newValue = event.target.value;
@@ -32,14 +37,24 @@ export const TextWidget = (props: WidgetProps) => {
   };
 
   return (
-    <Input
-      id={id}
-      value={value ?? ''}
-      placeholder={placeholder || ''}
-      disabled={disabled || readonly}
-      onChange={handleChange}
-      onBlur={handleBlur}
-      onFocus={handleFocus}
-    />
+    <>
+      <Input
+        id={id}
+        list={listId}
+        value={value ?? ''}
+        placeholder={placeholder || ''}
+        disabled={disabled || readonly}
+        onChange={handleChange}
+        onBlur={handleBlur}
+        onFocus={handleFocus}
+      />
+      {listId && (
+        <datalist id={listId}>
+          {examples.map((example) => (
+            <option key={example} value={example} />
+          ))}
+        </datalist>
+      )}
+    </>
   );
 };
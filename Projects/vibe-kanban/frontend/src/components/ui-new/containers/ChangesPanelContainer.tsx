@@ -15,6 +15,7 @@ const COLLAPSE_BY_CHANGE_TYPE: Record<DiffChangeKind, boolean> = {
   renamed: true, // Collapse renamed files
   copied: true, // Collapse copied files
   permissionChange: true, // Collapse permission changes
+  submoduleUpdate: true, // Collapse submodule pointer moves (no content)
 };
 
 // Collapse large diffs (over 200 lines)
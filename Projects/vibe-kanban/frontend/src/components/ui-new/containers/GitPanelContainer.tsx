@@ -120,6 +120,7 @@ export function GitPanelContainer({
         await ForcePushDialog.show({
           attemptId: selectedWorkspace.id,
           repoId,
+          remoteSha: errorData.remote_sha,
         });
         return;
       }
@@ -63,6 +63,10 @@ function getToolSummary(
       });
     case 'web_fetch':
       return t('conversation.toolSummary.fetched', { url: action_type.url });
+    case 'web_search':
+      return t('conversation.toolSummary.searchedWeb', {
+        query: action_type.query,
+      });
     case 'command_run':
       return action_type.command || t('conversation.toolSummary.ranCommand');
     case 'task_create':
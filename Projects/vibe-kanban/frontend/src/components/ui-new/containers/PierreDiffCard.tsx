@@ -250,6 +250,8 @@ export function PierreDiffCard({
         return 'Copied';
       case 'permissionChange':
         return 'Perm';
+      case 'submoduleUpdate':
+        return 'Submodule';
       default:
         return null;
     }
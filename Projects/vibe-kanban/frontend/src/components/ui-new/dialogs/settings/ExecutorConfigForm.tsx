@@ -5,6 +5,7 @@ import { RJSFValidationError } from '@rjsf/utils';
 import validator from '@rjsf/validator-ajv8';
 import { useTranslation } from 'react-i18next';
 import { BaseCodingAgent } from 'shared/types';
+import { useUserSystem } from '@/components/ConfigProvider';
 import { settingsRjsfTheme } from './rjsf/theme';
 import { SettingsSaveBar } from './SettingsComponents';
 
@@ -37,9 +38,24 @@ export function ExecutorConfigForm({
     RJSFValidationError[]
   >([]);
 
+  const { supportedModels } = useUserSystem();
+
   const schema = useMemo(() => {
-    return schemas[executor];
-  }, [executor]);
+    const base = schemas[executor];
+    const model = base?.properties?.model;
+    const models = supportedModels?.[executor];
+    if (typeof model !== 'object' || !models?.length) {
+      return base;
+    }
+    // Suggest the executor's known models in the free-text model field
+    return {
+      ...base,
+      properties: {
+        ...base.properties,
+        model: { ...model, examples: models },
+      },
+    };
+  }, [executor, supportedModels]);
 
   // Custom handler for env field updates
   const handleEnvChange = useCallback(
@@ -192,7 +192,11 @@ export function GeneralSettingsSection() {
 
   const resetDisclaimer = async () => {
     if (!config) return;
-    updateAndSaveConfig({ disclaimer_acknowledged: false });
+    // Onboarding comes after the disclaimer, so it is shown again too
+    updateAndSaveConfig({
+      disclaimer_acknowledged: false,
+      onboarding_acknowledged: false,
+    });
   };
 
   const resetOnboarding = async () => {
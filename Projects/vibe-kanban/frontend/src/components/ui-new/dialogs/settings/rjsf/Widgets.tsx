@@ -22,7 +22,12 @@ export const TextWidget = (props: WidgetProps) => {
     onFocus,
     placeholder,
     options,
+    schema,
   } = props;
+  const examples = Array.isArray(schema.examples)
+    ? schema.examples.map(String)
+    : [];
+  const listId = examples.length > 0 ? `${id}-examples` : undefined;
 
   const handleChange = (event: React.ChangeEvent<HTMLInputElement>) => {
     const newValue = event.target.value;
@@ -42,21 +47,31 @@ export const TextWidget = (props: WidgetProps) => {
   };
 
   return (
-    <input
-      id={id}
-      type="text"
-      value={value ?? ''}
-      placeholder={placeholder || ''}
-      disabled={disabled || readonly}
-      onChange={handleChange}
-      onBlur={handleBlur}
-      onFocus={handleFocus}
-      className={cn(
-        'w-full bg-secondary border border-border rounded-sm px-base py-half text-base text-high',
-        'placeholder:text-low placeholder:opacity-80 focus:outline-none focus:ring-1 focus:ring-brand',
-        (disabled || readonly) && 'opacity-50 cursor-not-allowed'
+    <>
+      <input
+        id={id}
+        list={listId}
+        type="text"
+        value={value ?? ''}
+        placeholder={placeholder || ''}
+        disabled={disabled || readonly}
+        onChange={handleChange}
+        onBlur={handleBlur}
+        onFocus={handleFocus}
+        className={cn(
+          'w-full bg-secondary border border-border rounded-sm px-base py-half text-base text-high',
+          'placeholder:text-low placeholder:opacity-80 focus:outline-none focus:ring-1 focus:ring-brand',
+          (disabled || readonly) && 'opacity-50 cursor-not-allowed'
+        )}
+      />
+      {listId && (
+        <datalist id={listId}>
+          {examples.map((example) => (
+            <option key={example} value={example} />
+          ))}
+        </datalist>
       )}
-    />
+    </>
   );
 };
 
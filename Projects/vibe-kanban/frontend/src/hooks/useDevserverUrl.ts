@@ -6,6 +6,10 @@ const urlPatterns = [
   /(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[[0-9a-f:]+\]|(?:\d{1,3}\.){3}\d{1,3}):(\d{2,5})/i,
 ];
 
+// The server announces the URL with this line once the project's readiness
+// pattern matches; it wins over URLs guessed from earlier output.
+const READY_LINE_PREFIX = 'Dev server is ready at ';
+
 export type DevserverUrlInfo = {
   url: string;
   port?: number;
@@ -64,26 +68,44 @@ export const useDevserverUrlFromLogs = (
 ): DevserverUrlInfo | undefined => {
   const [urlInfo, setUrlInfo] = useState<DevserverUrlInfo | undefined>();
   const lastIndexRef = useRef(0);
+  const announcedRef = useRef(false);
 
   useEffect(() => {
     if (!logs) {
       setUrlInfo(undefined);
       lastIndexRef.current = 0;
+      announcedRef.current = false;
       return;
     }
 
     if (logs.length < lastIndexRef.current) {
       lastIndexRef.current = 0;
+      announcedRef.current = false;
       setUrlInfo(undefined);
     }
 
+    const newEntries = logs.slice(lastIndexRef.current);
+    lastIndexRef.current = logs.length;
+
+    if (announcedRef.current) {
+      return;
+    }
+
+    const announced = newEntries
+      .filter((entry) => entry.content.startsWith(READY_LINE_PREFIX))
+      .map((entry) => detectDevserverUrl(entry.content))
+      .find((detected) => detected !== null);
+    if (announced) {
+      announcedRef.current = true;
+      setUrlInfo(announced);
+      return;
+    }
+
     if (urlInfo) {
-      lastIndexRef.current = logs.length;
       return;
     }
 
     let detectedUrl: DevserverUrlInfo | undefined;
-    const newEntries = logs.slice(lastIndexRef.current);
     newEntries.some((entry) => {
       const detected = detectDevserverUrl(entry.content);
       if (detected) {
@@ -96,8 +118,6 @@ export const useDevserverUrlFromLogs = (
     if (detectedUrl) {
       setUrlInfo((prev) => prev ?? detectedUrl);
     }
-
-    lastIndexRef.current = logs.length;
   }, [logs, urlInfo]);
 
   return urlInfo;
@@ -58,7 +58,7 @@ export const useExecutionProcesses = (
       (process.run_reason === 'codingagent' ||
         process.run_reason === 'setupscript' ||
         process.run_reason === 'cleanupscript') &&
-      process.status === 'running'
+      (process.status === 'running' || process.status === 'queued')
   );
   const isLoading = !!sessionId && !isInitialized && !error; // until first snapshot
 
@@ -63,7 +63,11 @@ export function useGitOperations(
       if (errorData?.type === 'force_push_required') {
         // Show confirmation dialog - dialog handles the force push internally
         if (attemptId && params?.repo_id) {
-          await ForcePushDialog.show({ attemptId, repoId: params.repo_id });
+          await ForcePushDialog.show({
+            attemptId,
+            repoId: params.repo_id,
+            remoteSha: errorData.remote_sha,
+          });
         }
         return;
       }
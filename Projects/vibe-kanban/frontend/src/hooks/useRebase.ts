@@ -28,6 +28,7 @@ export function useRebase(
           repo_id: repoId,
           old_base_branch: oldBaseBranch ?? null,
           new_base_branch: newBaseBranch ?? null,
+          auto_stash: false,
         };
 
         return attemptsApi.rebase(attemptId, data).then((res) => {
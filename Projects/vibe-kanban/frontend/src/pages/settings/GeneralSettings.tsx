@@ -183,7 +183,11 @@ export function GeneralSettings() {
 
   const resetDisclaimer = async () => {
     if (!config) return;
-    updateAndSaveConfig({ disclaimer_acknowledged: false });
+    // Onboarding comes after the disclaimer, so it is shown again too
+    updateAndSaveConfig({
+      disclaimer_acknowledged: false,
+      onboarding_acknowledged: false,
+    });
   };
 
   const resetOnboarding = async () => {
@@ -30,6 +30,7 @@ export type CommentAnnotation =
  * - 'renamed' → 'rename-pure' or 'rename-changed' (based on content diff)
  * - 'copied' → 'change'
  * - 'permissionChange' → 'change'
+ * - 'submoduleUpdate' → 'change'
  */
 function mapChangeKindToChangeType(
   kind: DiffChangeKind,
@@ -50,6 +51,8 @@ function mapChangeKindToChangeType(
       return 'change';
     case 'permissionChange':
       return 'change';
+    case 'submoduleUpdate':
+      return 'change';
     default:
       return 'change';
   }
@@ -4,11 +4,25 @@
 
 // If you are an AI, and you absolutely have to edit this file, please confirm with the user first.
 
-export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, default_agent_working_dir: string | null, 
+/**
+ * Prefix for this project's task branches; `None` uses the global setting
+ */
+git_branch_prefix: string | null, 
+/**
+ * Reject task branch names that don't carry the prefix
+ */
+enforce_branch_prefix: boolean, 
+/**
+ * Regex matched against dev server output to tell when it is ready. The
+ * first capture group, or else the whole match, is the URL or port to open.
+ * `None` looks for a localhost URL.
+ */
+dev_server_ready_pattern: string | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
 
 export type CreateProject = { name: string, repositories: Array<CreateProjectRepo>, };
 
-export type UpdateProject = { name: string | null, };
+export type UpdateProject = { name: string | null, git_branch_prefix?: string | null, enforce_branch_prefix?: boolean, dev_server_ready_pattern?: string | null, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, 
 /**
@@ -80,18 +94,26 @@ export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, id
 
 export type Session = { id: string, workspace_id: string, executor: string | null, created_at: string, updated_at: string, };
 
+export type SessionReviewMarker = { session_id: string, repo_id: string, commit_oid: string, created_at: string, updated_at: string, };
+
 export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
 /**
  * dropped: true if this process is excluded from the current
  * history view (due to restore/trimming). Hidden from logs/timeline;
  * still listed in the Processes tab.
  */
-dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
+dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, 
+/**
+ * Set once a coding agent run finishes; `None` for other run reasons
+ */
+change_outcome: ExecutionChangeOutcome | null, };
 
-export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+export enum ExecutionProcessStatus { queued = "queued", running = "running", completed = "completed", failed = "failed", killed = "killed" }
 
 export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
 
+export type ExecutionChangeOutcome = "no_changes" | "only_untracked" | "changes_made";
+
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
@@ -118,13 +140,37 @@ contentOmitted: boolean,
 /**
  * Optional precomputed stats for omitted content
  */
-additions: number | null, deletions: number | null, repoId: string | null, };
+additions: number | null, deletions: number | null, repoId: string | null, 
+/**
+ * True when the file changed after the last reviewed commit
+ */
+changedSinceReview: boolean, 
+/**
+ * Set when the path is a submodule; its contents are never loaded
+ */
+submodule: SubmoduleUpdate | null, };
 
-export type DiffChangeKind = "added" | "deleted" | "modified" | "renamed" | "copied" | "permissionChange";
+export type DiffChangeKind = "added" | "deleted" | "modified" | "renamed" | "copied" | "permissionChange" | "submoduleUpdate";
+
+export type SubmoduleUpdate = { oldCommit: string | null, newCommit: string | null, 
+/**
+ * Subject of the new commit, when the submodule is checked out locally
+ */
+newCommitSubject: string | null, };
+
+export type FileChangeSize = { path: string, additions: number, deletions: number, };
+
+export type DiffSummary = { filesChanged: number, additions: number, deletions: number, 
+/**
+ * Files with the most changed lines, largest first
+ */
+largestChanges: Array<FileChangeSize>, };
+
+export type DiffResult = { "type": "full", diffs: Array<Diff>, } | { "type": "summary", summary: DiffSummary, };
 
 export type ApiResponse<T, E = T> = { success: boolean, data: T | null, error_data: E | null, message: string | null, };
 
-export type LoginStatus = { "status": "loggedout" } | { "status": "loggedin", profile: ProfileResponse, };
+export type LoginStatus = { "status": "loggedout" } | { "status": "loggedin", profile: ProfileResponse, } | { "status": "offline" };
 
 export type ProfileResponse = { user_id: string, username: string | null, email: string, providers: Array<ProviderProfile>, };
 
@@ -184,15 +230,21 @@ export type RegisterRepoRequest = { path: string, display_name: string | null, }
 
 export type InitRepoRequest = { parent_path: string, folder_name: string, };
 
+export type BranchUpstream = { branch: string, upstream: string | null, };
+
 export type TagSearchParams = { search: string | null, };
 
 export type TokenResponse = { access_token: string, expires_at: string | null, };
 
-export type UserSystemInfo = { config: Config, analytics_user_id: string, login_status: LoginStatus, environment: Environment, 
+export type UserSystemInfo = { config: Config, analytics_user_id: string, login_status: LoginStatus, remote_connectivity: RemoteConnectivityState, environment: Environment, 
 /**
  * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
  */
-capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+capabilities: { [key in string]?: Array<BaseAgentCapability> }, 
+/**
+ * Known model names per executor, empty when the executor accepts any model
+ */
+supported_models: { [key in string]?: Array<string> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
 
 export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
 
@@ -208,9 +260,64 @@ export type CheckEditorAvailabilityResponse = { available: boolean, };
 
 export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
 
+export type SubsystemHealth = { ok: boolean, detail: string | null, };
+
+export type GitHostCliHealth = { provider: ProviderKind, cli: string, health: SubsystemHealth, };
+
+export type ExecutorHealth = { executor: BaseCodingAgent, availability: AvailabilityInfo, };
+
+export type MigrationStatus = { 
+/**
+ * Highest successfully applied version
+ */
+current_version: bigint | null, 
+/**
+ * Highest version embedded in this build
+ */
+latest_version: bigint | null, applied_versions: Array<bigint>, 
+/**
+ * Embedded migrations not yet applied
+ */
+pending_versions: Array<bigint>, 
+/**
+ * Migrations recorded as started but not successfully finished
+ */
+dirty_versions: Array<bigint>, 
+/**
+ * Applied migrations whose stored checksum differs from the embedded file
+ */
+checksum_mismatches: Array<bigint>, 
+/**
+ * Applied migrations this build doesn't know about, i.e. the database was
+ * migrated by a newer version
+ */
+unknown_versions: Array<bigint>, };
+
+export type CompactionReport = { size_before_bytes: bigint, size_after_bytes: bigint, };
+
+export type WalCheckpoint = { 
+/**
+ * A reader or writer blocked the checkpoint from completing
+ */
+busy: boolean, wal_frames: bigint, checkpointed_frames: bigint, };
+
+export type DetailedHealthResponse = { 
+/**
+ * True only when the database and git are both usable
+ */
+ok: boolean, database: SubsystemHealth, 
+/**
+ * `None` if the migration table could not be read
+ */
+migrations: MigrationStatus | null, git: SubsystemHealth, git_host_clis: Array<GitHostCliHealth>, executors: Array<ExecutorHealth>, };
+
 export type CurrentUserResponse = { user_id: string, };
 
-export type CreateFollowUpAttempt = { prompt: string, executor_profile_id: ExecutorProfileId, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
+export type CreateFollowUpAttempt = { prompt: string, executor_profile_id: ExecutorProfileId, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, 
+/**
+ * Branch the conversation from this earlier turn instead of the latest
+ */
+resume_from: TurnRef | null, };
 
 export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
 
@@ -218,19 +325,48 @@ export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: s
 
 export type MergeTaskAttemptRequest = { repo_id: string, };
 
-export type PushTaskAttemptRequest = { repo_id: string, };
+export type PushTaskAttemptRequest = { repo_id: string, 
+/**
+ * For a force push, the commit the remote branch had when the user last saw
+ * it (from the rejected push). The force push is refused if the remote moved
+ * since; without it the remote branch is overwritten unconditionally.
+ */
+expected_remote_sha?: string, };
 
 export type RenameBranchRequest = { new_branch_name: string, };
 
 export type RenameBranchResponse = { branch: string, };
 
-export type StartReviewRequest = { executor_profile_id: ExecutorProfileId, additional_prompt: string | null, use_all_workspace_commits: boolean, };
+export type StartReviewRequest = { executor_profile_id: ExecutorProfileId, additional_prompt: string | null, use_all_workspace_commits: boolean, 
+/**
+ * Paths to review per repo id; repos without an entry are reviewed in full
+ */
+repo_paths?: { [key in string]?: Array<string> }, 
+/**
+ * Most files the agent should review across all repos
+ */
+max_files?: number, };
 
 export type ReviewError = { "type": "process_already_running" };
 
-export type OpenEditorRequest = { editor_type: string | null, file_path: string | null, };
+export type MarkReviewedRequest = { repo_id: string, 
+/**
+ * Defaults to the current HEAD of the repo's worktree
+ */
+commit_oid: string | null, };
 
-export type OpenEditorResponse = { url: string | null, };
+export type OpenEditorRequest = { editor_type: string | null, file_path: string | null, 
+/**
+ * Open the worktree as a workspace: `true` for a fresh window, `false` to add it
+ * to the focused one. Ignored when `file_path` is set.
+ */
+new_window?: boolean, };
+
+export type OpenEditorResponse = { url: string | null, 
+/**
+ * The editor that handled the request, which may be a fallback
+ */
+editor_type: EditorType, };
 
 export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
 
@@ -250,15 +386,95 @@ export type RunAgentSetupResponse = Record<string, never>;
 
 export type GhCliSetupError = "BREW_MISSING" | "SETUP_HELPER_NOT_SUPPORTED" | { "OTHER": { message: string, } };
 
-export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, };
+export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, 
+/**
+ * Stash uncommitted changes before rebasing and restore them afterwards,
+ * instead of refusing to rebase a dirty worktree
+ */
+auto_stash: boolean, };
 
 export type AbortConflictsRequest = { repo_id: string, };
 
 export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress" };
 
-export type PushError = { "type": "force_push_required" };
+/**
+ * Each variant carries the commit the remote branch had when the push was
+ * rejected, `None` if it couldn't be looked up
+ */
+export type PushError = { "type": "force_push_required", remote_sha: string | null, } | { "type": "remote_changed", remote_sha: string | null, };
+
+export type CommitMessageRules = { 
+/**
+ * Maximum length of the subject line, in characters
+ */
+max_subject_length: number | null, 
+/**
+ * Require a blank line between the subject and the body
+ */
+require_blank_line_before_body: boolean, 
+/**
+ * Regex the subject line must match, e.g. [`CONVENTIONAL_COMMIT_PATTERN`]
+ */
+subject_pattern: string | null, };
+
+/**
+ * Name and email recorded on commits
+ */
+export type CommitIdentity = { name: string, email: string, };
+
+export type BinaryDetection = "null_byte" | "undecodable";
+
+export type FallbackEncoding = "latin1";
+
+/**
+ * How file contents are turned into text for diffs
+ */
+export type FileContentOptions = { 
+/**
+ * Files larger than this, in bytes, have their contents omitted
+ */
+max_inline_bytes: number, binary_detection: BinaryDetection, 
+/**
+ * Encoding to decode with when contents are not valid UTF-8. Such files
+ * are treated as binary when unset.
+ */
+fallback_encoding: FallbackEncoding | null, };
+
+export type CommitMessageIssue = { "type": "empty_subject" } | { "type": "subject_too_long", length: number, max: number, } | { "type": "missing_blank_line_before_body" } | { "type": "subject_pattern_mismatch", pattern: string, } | { "type": "invalid_subject_pattern", pattern: string, error: string, };
+
+export type CommitWorkspaceError = { "type": "invalid_message", issues: Array<CommitMessageIssue>, } | { "type": "secrets_detected", findings: Array<RepoSecretFindings>, };
+
+export type CommitWorkspaceRequest = { message: string, };
+
+export type RepoCommitOutcome = { "type": "committed", sha: string, } | { "type": "no_changes" } | { "type": "failed", error: string, } | { "type": "not_attempted" };
+
+export type RepoCommitResult = { repo_id: string, repo_name: string, outcome: RepoCommitOutcome, };
+
+export type CommitWorkspaceResponse = { results: Array<RepoCommitResult>, };
+
+export type HunkPatchRequest = { repo_id: string, 
+/**
+ * Unified diff with the file header and the hunk(s) to (un)stage
+ */
+patch: string, };
+
+export type HunkPatchError = { "type": "rejected", hunk: string, message: string, };
+
+export type SecretKind = "known_pattern" | "high_entropy";
 
-export type PrError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "unsupported_provider" };
+export type SecretFinding = { file_path: string, 
+/**
+ * 1-based line number in the new version of the file
+ */
+line_number: number, kind: SecretKind, 
+/**
+ * Start of the matched text, with the rest masked
+ */
+preview: string, };
+
+export type RepoSecretFindings = { repo_id: string, repo_name: string, findings: Array<SecretFinding>, };
+
+export type PrError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "unsupported_provider" } | { "type": "secrets_detected", findings: Array<SecretFinding>, } | { "type": "missing_permissions", missing: Array<HostPermission>, };
 
 export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
 
@@ -268,18 +484,134 @@ export type AttachExistingPrRequest = { repo_id: string, };
 
 export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
 
+export type PrReviewThreadsResponse = { threads: Array<ReviewThread>, };
+
 export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, };
 
 export type GetPrCommentsQuery = { repo_id: string, };
 
 export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
 
-export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+export type ReviewThread = { id: string, 
+/**
+ * File the thread is anchored to; None for PR-level threads
+ */
+path: string | null, line: bigint | null, 
+/**
+ * None when the provider can't report resolution state
+ */
+is_resolved: boolean | null, 
+/**
+ * Comments in the order they were posted
+ */
+comments: Array<ReviewThreadComment>, };
+
+export type ReviewThreadComment = { id: string, author: string, body: string, created_at: string, url: string | null, };
+
+export type ProviderKind = "git_hub" | "azure_dev_ops" | "git_lab" | "unknown";
 
 export type OpenPrInfo = { number: bigint, url: string, title: string, head_branch: string, base_branch: string, };
 
+export type HostPermission = "repo_read" | "pull_request_write";
+
+export type PrMonitorStatus = { paused: boolean, poll_interval_secs: bigint, 
+/**
+ * Delay currently in effect because a provider reported rate limiting
+ */
+rate_limit_backoff_secs: bigint | null, };
+
+export type RemoteConnectivityState = "not_configured" | "online" | "offline";
+
+export type ExecutionSlotsStatus = { 
+/**
+ * `None` when the number of concurrent coding agents is unbounded
+ */
+max_concurrent: number | null, running: number, 
+/**
+ * Execution processes waiting for a slot, in start order
+ */
+queued_execution_process_ids: Array<string>, };
+
+export type ProjectReportQuery = { from: string | null, to: string | null, };
+
+export type ProjectReport = { project_id: string, project_name: string, generated_at: string, from: string | null, to: string | null, totals: ProjectReportTotals, attempts: Array<AttemptReport>, };
+
+export type ProjectReportTotals = { attempts: number, 
+/**
+ * Attempts with at least one direct merge or merged PR
+ */
+merged_attempts: number, files_changed: number, lines_added: number, lines_removed: number, };
+
+export type AttemptReport = { workspace_id: string, task_id: string, task_title: string, task_status: TaskStatus, branch: string, archived: boolean, created_at: string, session_count: number, coding_agent_runs: number, 
+/**
+ * Status of the most recent coding agent run
+ */
+latest_run_status: ExecutionProcessStatus | null, 
+/**
+ * Most significant change outcome across all coding agent runs
+ */
+change_outcome: ExecutionChangeOutcome | null, merged: boolean, repos: Array<AttemptRepoReport>, };
+
+export type AttemptRepoReport = { repo_id: string, repo_name: string, target_branch: string, 
+/**
+ * Committed changes on the attempt branch; `None` if the branch is gone
+ */
+files_changed: number | null, lines_added: number | null, lines_removed: number | null, merge_commit: string | null, pr_url: string | null, pr_status: MergeStatus | null, };
+
 export type GitRemote = { name: string, url: string, };
 
+export type SubmoduleInfo = { name: string, 
+/**
+ * Path relative to the repository root
+ */
+path: string, url: string | null, branch: string | null, };
+
+export type CherryStatusEntry = { sha: string, subject: string, 
+/**
+ * An equivalent change (same patch id) is already upstream, e.g. because it
+ * was cherry-picked; rebasing would drop it
+ */
+in_upstream: boolean, };
+
+export type StashEntry = { 
+/**
+ * Position in the stash list, as in `stash@{index}`; 0 is the newest
+ */
+index: number, sha: string, message: string, 
+/**
+ * Branch checked out when the stash was made; `None` for a detached HEAD
+ */
+branch: string | null, created_at: string, };
+
+/**
+ * An unmerged file with the contents of its index stages. `base` is stage 1,
+ * `ours` stage 2 and `theirs` stage 3; a side is `None` when the file does not
+ * exist there (e.g. deleted on one side) or its content is omitted.
+ */
+export type FileConflict = { path: string, base: string | null, ours: string | null, theirs: string | null, 
+/**
+ * True when a side is binary or larger than the inline size limit
+ */
+content_omitted: boolean, };
+
+/**
+ * Last change to a line of a file, as found by [`GitService::blame_file`]
+ */
+export type BlameLine = { 
+/**
+ * 1-based line number in the blamed version of the file
+ */
+line: number, commit: string, author_name: string | null, author_email: string | null, authored_at: string, };
+
+/**
+ * Transfer state of a fetch or clone, reported while it runs
+ */
+export type FetchProgress = { received_objects: number, indexed_objects: number, 
+/**
+ * Objects to transfer; 0 until the remote has told us
+ */
+total_objects: number, received_bytes: number, indexed_deltas: number, total_deltas: number, };
+
 export type ListPrsError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "auth_failed", message: string, } | { "type": "unsupported_provider" };
 
 export type CreateWorkspaceFromPrBody = { repo_id: string, pr_number: bigint, pr_title: string, pr_url: string, head_branch: string, base_branch: string, run_setup: boolean, remote_name: string | null, };
@@ -356,19 +688,142 @@ export type WorkspaceSummaryResponse = { summaries: Array<WorkspaceSummary>, };
 
 export type DiffStats = { files_changed: number, lines_added: number, lines_removed: number, };
 
+export type RepoDiffStats = { repo_id: string, repo_name: string, stats: DiffStats, };
+
+export type WorkspaceDiffSummary = { total: DiffStats, 
+/**
+ * Number of repos with at least one changed file
+ */
+repos_changed: number, repos: Array<RepoDiffStats>, };
+
+export type RepoDiffResult = { repo_id: string, repo_name: string, result: DiffResult, };
+
 export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, last_modified: bigint | null, };
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, send_message_shortcut: SendMessageShortcut, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, send_message_shortcut: SendMessageShortcut, 
+/**
+ * Run `git submodule update --init --recursive` in new worktrees
+ */
+init_submodules: boolean, 
+/**
+ * Add a warning to the conversation when an agent's context window is nearly full
+ */
+context_warning_enabled: boolean, 
+/**
+ * Percentage of the context window that triggers the warning
+ */
+context_warning_threshold_percent: number, 
+/**
+ * Mask secrets (API keys, tokens, JWTs) in agent logs before they are stored or streamed
+ */
+log_redaction_enabled: boolean, 
+/**
+ * Extra regex patterns to redact, on top of the built-in secret patterns
+ */
+log_redaction_patterns: Array<string>, 
+/**
+ * How often open PRs are checked for merges, in seconds
+ */
+pr_monitor_poll_interval_secs: number, 
+/**
+ * Maximum number of coding agents running at once; further executions
+ * wait for a slot. `None` for no limit. Dev servers are not counted.
+ */
+max_concurrent_executions: number | null, 
+/**
+ * Remote names to push to and open PRs from, in order of preference, when a
+ * repository sets no `remote.pushDefault`. `origin`, then the first remote,
+ * are used when none of them exist.
+ */
+preferred_remotes: Array<string>, 
+/**
+ * Hosts of self-hosted GitLab instances, so their remotes get merge
+ * requests through `glab`. gitlab.com is always recognized.
+ */
+gitlab_hosts: Array<string>, 
+/**
+ * Fetch more history when a rebase in a shallow clone needs commits that are
+ * missing. When off, such rebases fail with an error instead.
+ */
+deepen_shallow_clones: boolean, 
+/**
+ * Rules commit messages must follow. Commits that break them are
+ * rejected; nothing is enforced by default.
+ */
+commit_message_rules: CommitMessageRules, 
+/**
+ * Sign commits with `git commit -S`, using the key and `gpg.format` from the
+ * git config. A missing key makes commits fail instead of going unsigned.
+ */
+sign_commits: boolean, 
+/**
+ * Refuse to commit or open a PR when the pending changes appear to add
+ * secrets. Off by default: findings are only reported.
+ */
+block_commits_with_secrets: boolean, 
+/**
+ * How many more times to try starting a coding agent whose spawn failed
+ * with a transient error. 0 disables retries.
+ */
+spawn_retry_attempts: number, 
+/**
+ * Delay before the first spawn retry; doubled for each further attempt.
+ * Each delay is randomly shortened by up to half.
+ */
+spawn_retry_backoff_ms: number, 
+/**
+ * Stop a coding agent that has written no output for this many seconds and
+ * mark it failed. Time spent waiting on an approval doesn't count. Unset
+ * disables the timeout.
+ */
+executor_idle_timeout_secs: number | null, 
+/**
+ * Terminal output kept for a client that hasn't read it yet, in KiB. When
+ * the client falls further behind, the oldest output is dropped.
+ */
+terminal_output_buffer_kb: number, 
+/**
+ * Terminals that can be open at once; each holds a shell process until
+ * its client disconnects
+ */
+max_terminal_sessions: number, 
+/**
+ * Size limit, binary detection and fallback encoding used when showing
+ * file contents in diffs
+ */
+file_content: FileContentOptions, 
+/**
+ * Author of commits Vibe Kanban makes in repositories with no
+ * user.name/user.email. Defaults to `Vibe Kanban <noreply@vibekanban.com>`.
+ * Takes effect on restart.
+ */
+commit_author: CommitIdentity | null, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
 export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM" }
 
-export type EditorConfig = { editor_type: EditorType, custom_command: string | null, remote_ssh_host: string | null, remote_ssh_user: string | null, };
+export type EditorConfig = { editor_type: EditorType, custom_command: string | null, remote_ssh_host: string | null, remote_ssh_user: string | null, 
+/**
+ * Non-SSH remote context (tunnel, Codespace, container) the worktrees live in
+ */
+remote_target: RemoteEditorTarget | null, 
+/**
+ * Per-path editors, checked in order before falling back to `editor_type`
+ */
+profiles: Array<EditorProfile>, 
+/**
+ * Editors to try, in order, when the selected editor isn't installed
+ */
+fallback_editors: Array<EditorType>, };
+
+export type EditorProfile = { editor_type: EditorType, custom_command: string | null, matchers: Array<string>, };
+
+export type RemoteEditorTarget = { "type": "tunnel", name: string, } | { "type": "codespace", name: string, } | { "type": "container", name: string, };
 
 export enum EditorType { VS_CODE = "VS_CODE", VS_CODE_INSIDERS = "VS_CODE_INSIDERS", CURSOR = "CURSOR", WINDSURF = "WINDSURF", INTELLI_J = "INTELLI_J", ZED = "ZED", XCODE = "XCODE", GOOGLE_ANTIGRAVITY = "GOOGLE_ANTIGRAVITY", CUSTOM = "CUSTOM" }
 
@@ -457,15 +912,25 @@ export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } |
 
 export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
 
-export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER", CONTEXT_USAGE = "CONTEXT_USAGE" }
+export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", RESUME_FROM_TURN = "RESUME_FROM_TURN", SETUP_HELPER = "SETUP_HELPER", CONTEXT_USAGE = "CONTEXT_USAGE" }
+
+export type TurnRef = { 
+/**
+ * Agent-native id of the last message to keep, e.g. a Claude assistant message uuid
+ */
+message_id: string, };
 
 export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
-export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, 
+/**
+ * Context window of the model in tokens, for the context usage bar
+ */
+context_window?: number | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
 export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -491,7 +956,11 @@ auto_approve: boolean,
  */
 auto_compact: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, 
+/**
+ * Context window of the model in tokens, for the context usage bar
+ */
+context_window?: number | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
 export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
 
@@ -521,7 +990,11 @@ executor_profile_id: ExecutorProfileId,
  * Optional relative path to execute the agent in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, 
+/**
+ * Resume from this earlier turn instead of the latest one
+ */
+resume_from: TurnRef | null, };
 
 export type ReviewRequest = { executor_profile_id: ExecutorProfileId, context: Array<RepoReviewContext> | null, prompt: string, 
 /**
@@ -555,7 +1028,7 @@ unified_diff: string,
  */
 has_line_numbers: boolean, };
 
-export type ActionType = { "action": "file_read", path: string, } | { "action": "file_edit", path: string, changes: Array<FileChange>, } | { "action": "command_run", command: string, result: CommandRunResult | null, } | { "action": "search", query: string, } | { "action": "web_fetch", url: string, } | { "action": "tool", tool_name: string, arguments: JsonValue | null, result: ToolResult | null, } | { "action": "task_create", description: string, } | { "action": "plan_presentation", plan: string, } | { "action": "todo_management", todos: Array<TodoItem>, operation: string, } | { "action": "other", description: string, };
+export type ActionType = { "action": "file_read", path: string, } | { "action": "file_edit", path: string, changes: Array<FileChange>, } | { "action": "command_run", command: string, result: CommandRunResult | null, } | { "action": "search", query: string, } | { "action": "web_fetch", url: string, } | { "action": "web_search", query: string, } | { "action": "tool", tool_name: string, arguments: JsonValue | null, result: ToolResult | null, } | { "action": "task_create", description: string, } | { "action": "plan_presentation", plan: string, } | { "action": "todo_management", todos: Array<TodoItem>, operation: string, } | { "action": "other", description: string, };
 
 export type TodoItem = { content: string, status: string, priority: string | null, };
 
@@ -586,4 +1059,4 @@ Analyze the changes in this branch and write:
    - Any important implementation details
    - At the end, include a note: "This PR was written using [Vibe Kanban](https://vibekanban.com)"
 
-Use the appropriate CLI tool to update the PR (gh pr edit for GitHub, az repos pr update for Azure DevOps).`;
\ No newline at end of file
+Use the appropriate CLI tool to update the PR (gh pr edit for GitHub, az repos pr update for Azure DevOps, glab mr update for GitLab).`;
\ No newline at end of file